@@ -1,5 +1,10 @@
 use std::path::{Path, PathBuf};
 
+use ambient_core::name;
+use ambient_ecs::{query, EntityId, World};
+use ambient_model_import::gltf::{
+    export_glb, world_roots, GltfExportDirAssets, GltfExportOptions, GltfExportUrlAssets,
+};
 use ambient_native_std::asset_cache::AssetCache;
 use anyhow::Context;
 use clap::{Args, Subcommand};
@@ -14,6 +19,12 @@ pub enum Assets {
     /// Import new assets with interactive prompts
     #[command(name = "import")]
     Import(ImportOptions),
+    /// Export a built prefab or model, or a subtree of it, to glTF (.glb)
+    ///
+    /// The entities of a running game can be exported with `--world` from the `server_world.json` written by the
+    /// debugger's "Dump Server World", which also writes the ids of the entities to `server_hierarchy.yml`
+    #[command(name = "export")]
+    Export(ExportOptions),
 }
 
 #[derive(Args, Clone, Debug)]
@@ -36,12 +47,35 @@ pub struct ImportOptions {
     pub collider_from_model: bool,
 }
 
+#[derive(Args, Clone, Debug)]
+pub struct ExportOptions {
+    #[arg()]
+    /// The built prefab or model to export, e.g. `build/assets/tree/prefabs/main.json`, or a world dump with `--world`
+    pub path: PathBuf,
+    #[arg(long)]
+    /// The path is a world dumped from a running game, whose assets are downloaded from their urls
+    pub world: bool,
+    #[arg(short, long)]
+    /// Where to write the .glb file
+    pub output: PathBuf,
+    #[arg(long)]
+    /// Only export the entity with this name and its children
+    pub node: Option<String>,
+    #[arg(long, conflicts_with = "node")]
+    /// Only export the entity with this id and its children
+    pub entity: Option<EntityId>,
+    #[arg(long)]
+    /// Keep the Z-up axes of Ambient instead of converting to the Y-up axes of glTF
+    pub keep_zup: bool,
+}
+
 pub async fn handle(command: &Assets, assets: &AssetCache) -> anyhow::Result<()> {
     match command {
         Assets::MigratePipelinesToml(opt) => {
             migrate_pipelines_toml(opt).await?;
         }
         Assets::Import(opt) => import(opt, assets).await?,
+        Assets::Export(opt) => export(opt, assets).await?,
     }
 
     Ok(())
@@ -78,3 +112,44 @@ async fn import(opt: &ImportOptions, assets: &AssetCache) -> anyhow::Result<()>
 
     Ok(())
 }
+
+async fn export(opt: &ExportOptions, asset_cache: &AssetCache) -> anyhow::Result<()> {
+    let data = tokio::fs::read(&opt.path)
+        .await
+        .with_context(|| format!("Failed to read {:?}", opt.path))?;
+    let world = World::from_slice(&data).context("Failed to parse the world")?;
+    let roots = match (&opt.node, opt.entity) {
+        (Some(node), _) => vec![query(name())
+            .iter(&world, None)
+            .find(|(_, name)| name == &node)
+            .map(|(id, _)| id)
+            .with_context(|| format!("No entity is named {node}"))?],
+        (None, Some(entity)) => {
+            anyhow::ensure!(world.exists(entity), "No entity has the id {entity}");
+            vec![entity]
+        }
+        (None, None) => world_roots(&world),
+    };
+
+    let options = GltfExportOptions {
+        zup_to_yup: !opt.keep_zup,
+    };
+    let glb = if opt.world {
+        let assets = GltfExportUrlAssets::fetch(asset_cache, &world, &roots).await;
+        export_glb(&world, &roots, &[], &assets, &options)?
+    } else {
+        // Prefabs and models are one directory down from the root of their model crate
+        let crate_dir = opt
+            .path
+            .parent()
+            .and_then(Path::parent)
+            .context("The prefab or model isn't in a model crate")?;
+        let assets = GltfExportDirAssets(crate_dir.to_path_buf());
+        export_glb(&world, &roots, &assets.animations(), &assets, &options)?
+    };
+    tokio::fs::write(&opt.output, glb)
+        .await
+        .with_context(|| format!("Failed to write {:?}", opt.output))?;
+    tracing::info!("Exported {:?} to {:?}", opt.path, opt.output);
+    Ok(())
+}
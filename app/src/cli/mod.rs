@@ -6,6 +6,7 @@ pub mod assets;
 pub mod join;
 pub mod login;
 pub mod package;
+pub mod terrain;
 
 mod package_path;
 pub use package_path::*;
@@ -16,6 +17,7 @@ use self::{
     package::{
         build::Build, deploy::Deploy, new::New, run::Run, serve::Serve, Package, PackageArgs,
     },
+    terrain::Terrain,
 };
 
 #[derive(Parser, Clone)]
@@ -43,6 +45,11 @@ pub enum Commands {
         #[command(subcommand)]
         assets: Assets,
     },
+    /// Terrain import and export
+    Terrain {
+        #[command(subcommand)]
+        terrain: Terrain,
+    },
     /// Log into Ambient and save your API token to settings
     Login,
 }
@@ -126,6 +133,7 @@ impl Cli {
             Commands::Serve(Serve { package, .. }) => Some(package),
             Commands::Join(Join { .. }) => None,
             Commands::Assets { .. } => None,
+            Commands::Terrain { .. } => None,
             Commands::Login => None,
         }
     }
@@ -139,7 +147,12 @@ impl Cli {
             C::Run(Run { package, .. }) | C::Build(Build { package, .. }) => {
                 package.is_release().unwrap_or(false)
             }
-            C::New(_) | C::Join(_) | C::Assets { .. } | C::Package { .. } | C::Login => false,
+            C::New(_)
+            | C::Join(_)
+            | C::Assets { .. }
+            | C::Terrain { .. }
+            | C::Package { .. }
+            | C::Login => false,
         }
     }
 }
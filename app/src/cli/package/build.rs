@@ -1,7 +1,10 @@
 use std::{collections::HashSet, future::Future, path::PathBuf};
 
 use ambient_build::BuildResult;
-use ambient_native_std::{asset_cache::AssetCache, asset_url::AbsAssetUrl};
+use ambient_native_std::{
+    asset_cache::{AssetCache, SyncAssetKeyExt},
+    asset_url::{AbsAssetUrl, DeploymentsBaseUrlKey},
+};
 use ambient_package::BuildSettings;
 use ambient_package_semantic::RetrievableFile;
 
@@ -116,6 +119,7 @@ pub async fn build<
     // the correct order.
     let mut queue: Vec<_> = {
         let mut semantic = ambient_package_semantic::Semantic::new(false).await?;
+        semantic.deployments_base_url = DeploymentsBaseUrlKey.get(assets);
         let primary_package_scope_id = semantic
             .add_package(RetrievableFile::Url(main_manifest_url.0.clone()), None)
             .await?;
@@ -0,0 +1,57 @@
+use ambient_native_std::{
+    asset_cache::{AssetCache, SyncAssetKeyExt},
+    asset_url::DeploymentsBaseUrlKey,
+};
+use ambient_package_semantic::{compare_packages, ItemId, Package, RetrievableFile, Semantic};
+use anyhow::Context;
+
+use super::PackageArgs;
+use crate::cli::PackagePath;
+
+/// Compares the schema of the package against an older version of it, and fails if the
+/// package's version does not reflect the changes.
+pub async fn handle(package: &PackageArgs, old: &str, assets: &AssetCache) -> anyhow::Result<()> {
+    let new = load(package.package_path()?, assets)
+        .await
+        .context("Failed to load the package")?;
+    let old = load(Some(old.to_string()).try_into()?, assets)
+        .await
+        .context("Failed to load the old version of the package")?;
+
+    let report = compare_packages((&old.0, old.1), (&new.0, new.1));
+    let old_version = &old.0.items.get(old.1).manifest.package.version;
+    let new_version = &new.0.items.get(new.1).manifest.package.version;
+
+    let Some(required) = report.required_bump() else {
+        tracing::info!("No schema changes between {old_version} and {new_version}");
+        return Ok(());
+    };
+
+    for change in &report.changes {
+        tracing::info!("{change}");
+    }
+    tracing::info!(
+        "{} changes ({} breaking); a {required} version bump is required",
+        report.changes.len(),
+        report.breaking_changes().count()
+    );
+
+    report.check_version(old_version, new_version)?;
+    Ok(())
+}
+
+async fn load(
+    package_path: PackagePath,
+    assets: &AssetCache,
+) -> anyhow::Result<(Semantic, ItemId<Package>)> {
+    let manifest_url = package_path.url.push("ambient.toml")?;
+
+    let mut semantic = Semantic::new(false).await?;
+    semantic.deployments_base_url = DeploymentsBaseUrlKey.get(assets);
+    let package_id = semantic
+        .add_package(RetrievableFile::Url(manifest_url.0), None)
+        .await?;
+    semantic.resolve_all()?;
+
+    Ok((semantic, package_id))
+}
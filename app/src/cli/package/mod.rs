@@ -4,17 +4,19 @@ use std::{
 };
 
 use ambient_native_std::asset_cache::AssetCache;
-use ambient_package::PackageId;
+use ambient_package::{resolve_dependencies, LocalRegistryIndex, Lockfile, Manifest, PackageId};
 use anyhow::Context;
 use clap::{Args, Subcommand};
 
 use super::PackagePath;
 
 pub mod build;
+pub mod check_compat;
 pub mod deploy;
 pub mod new;
 pub mod run;
 pub mod serve;
+pub mod vendor;
 
 #[derive(Subcommand, Clone, Debug)]
 /// Package-related commands.
@@ -24,11 +26,46 @@ pub enum Package {
         #[command(flatten)]
         package: PackageArgs,
     },
+    /// Resolve the versioned dependencies of a package against a registry index, and write the result to `ambient.lock`.
+    Lock {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The path to the registry index directory to resolve against
+        #[arg(long)]
+        index: PathBuf,
+        /// Ignore the existing lockfile and resolve to the newest compatible versions
+        #[arg(long)]
+        update: bool,
+    },
+    /// Download all deployed dependencies of a package, including their built WASM and assets, to a local directory.
+    ///
+    /// The directory can then be used as a deployment mirror with `--deployment-mirror` to build without network access.
+    Vendor {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The directory to download the dependencies to
+        #[arg(long, default_value = "vendor")]
+        output: PathBuf,
+    },
+    /// Compare the schema of a package with an older version of it, and report the changes and the version bump they require.
+    ///
+    /// Fails if the package's version does not reflect the changes, so that it can be used in CI.
+    #[command(alias = "diff")]
+    CheckCompat {
+        #[command(flatten)]
+        package: PackageArgs,
+        /// The path or URL of the older version of the package to compare against
+        #[arg(long)]
+        old: String,
+    },
 }
 impl Package {
     pub fn args(&self) -> &PackageArgs {
         match self {
             Package::RegenerateId { package } => package,
+            Package::Lock { package, .. } => package,
+            Package::Vendor { package, .. } => package,
+            Package::CheckCompat { package, .. } => package,
         }
     }
 }
@@ -61,6 +98,10 @@ pub struct PackageArgs {
     #[arg(long)]
     /// Only build the WASM modules
     pub build_wasm_only: bool,
+
+    /// Retrieve deployed dependencies from this URL or directory (e.g. one created by `ambient package vendor`) instead of the asset server
+    #[arg(long)]
+    pub deployment_mirror: Option<String>,
 }
 impl PackageArgs {
     pub fn is_release(&self) -> Option<bool> {
@@ -127,16 +168,76 @@ pub struct HostCli {
     /// Shutdown the server after the specified number of seconds of inactivity
     #[arg(long)]
     pub shutdown_after_inactivity_seconds: Option<u64>,
+
+    /// Stream terrain cells in and out around the players, keeping the unloaded cells in this directory
+    ///
+    /// The loaded cells are saved to it when the server shuts down
+    #[arg(long)]
+    pub terrain_dir: Option<PathBuf>,
 }
 
 pub fn handle(
     args: &Package,
-    _rt: &tokio::runtime::Runtime,
-    _assets: AssetCache,
+    rt: &tokio::runtime::Runtime,
+    assets: AssetCache,
 ) -> anyhow::Result<()> {
     match args {
         Package::RegenerateId { package } => regenerate_id(package),
+        Package::Lock {
+            package,
+            index,
+            update,
+        } => lock(package, index, *update),
+        Package::Vendor { package, output } => {
+            rt.block_on(vendor::handle(package, output, &assets))
+        }
+        Package::CheckCompat { package, old } => {
+            rt.block_on(check_compat::handle(package, old, &assets))
+        }
+    }
+}
+
+fn lock(package: &PackageArgs, index: &Path, update: bool) -> anyhow::Result<()> {
+    let package_path = package.package_path()?;
+    let Some(package_path) = &package_path.fs_path else {
+        anyhow::bail!("Cannot lock the dependencies of a remote package.");
+    };
+
+    let manifest_path = package_path.join("ambient.toml");
+    let manifest = Manifest::parse(&std::fs::read_to_string(&manifest_path)?)
+        .with_context(|| format!("Failed to parse manifest {manifest_path:?}"))?;
+
+    let lockfile_path = package_path.join(Lockfile::FILENAME);
+    let previous = if update || !lockfile_path.is_file() {
+        None
+    } else {
+        Some(Lockfile::parse(&std::fs::read_to_string(&lockfile_path)?)?)
+    };
+
+    let lockfile = resolve_dependencies(
+        &manifest,
+        &LocalRegistryIndex::new(index),
+        previous.as_ref(),
+    )?;
+
+    for package in &lockfile.packages {
+        let previous_version = previous
+            .as_ref()
+            .and_then(|p| p.get(&package.id))
+            .map(|p| &p.version);
+        if previous_version != Some(&package.version) {
+            tracing::info!(
+                "Locked {} ({}) to {} ({})",
+                package.name,
+                package.id,
+                package.version,
+                package.deployment
+            );
+        }
     }
+
+    std::fs::write(&lockfile_path, lockfile.to_toml_string())?;
+    Ok(())
 }
 
 fn regenerate_id(package: &PackageArgs) -> anyhow::Result<()> {
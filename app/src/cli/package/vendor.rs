@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path},
+    str::FromStr,
+};
+
+use ambient_native_std::{
+    asset_cache::{AssetCache, SyncAssetKeyExt},
+    asset_url::{AbsAssetUrl, DeploymentsBaseUrlKey},
+};
+use ambient_package::BuildMetadata;
+use ambient_package_semantic::{RetrievableFile, Semantic};
+use ambient_shared_types::urls::{deployment_url_from, DEPLOYMENT_MIRROR_ENV_VAR};
+use ambient_std::path::path_to_unix_string_lossy;
+use anyhow::Context;
+
+use super::PackageArgs;
+
+/// Downloads every deployment in the dependency tree of the package to `output`, laid out so that
+/// `output` can be used as a deployment mirror.
+pub async fn handle(
+    package: &PackageArgs,
+    output: &Path,
+    assets: &AssetCache,
+) -> anyhow::Result<()> {
+    let package_path = package.package_path()?;
+    let Some(package_path) = &package_path.fs_path else {
+        anyhow::bail!("Cannot vendor the dependencies of a remote package.");
+    };
+
+    let mut semantic = Semantic::new(false).await?;
+    semantic.deployments_base_url = DeploymentsBaseUrlKey.get(assets);
+    semantic
+        .add_package(
+            RetrievableFile::Path(package_path.join("ambient.toml")),
+            None,
+        )
+        .await?;
+
+    let deployments = semantic
+        .packages
+        .keys()
+        .filter_map(|locator| match &locator.source {
+            RetrievableFile::Deployment(deployment) => Some(deployment.id.clone()),
+            _ => None,
+        })
+        .collect::<BTreeSet<_>>();
+
+    if deployments.is_empty() {
+        tracing::info!("Package has no deployed dependencies to vendor");
+        return Ok(());
+    }
+
+    for deployment in &deployments {
+        vendor_deployment(assets, deployment, &output.join(deployment))
+            .await
+            .with_context(|| format!("Failed to vendor deployment {deployment}"))?;
+    }
+
+    tracing::info!(
+        "Vendored {} deployments to {output:?}. Use `--deployment-mirror {}` or set `{DEPLOYMENT_MIRROR_ENV_VAR}` to build from them.",
+        deployments.len(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+async fn vendor_deployment(
+    assets: &AssetCache,
+    deployment: &str,
+    output: &Path,
+) -> anyhow::Result<()> {
+    let base_url = deployment_url_from(&DeploymentsBaseUrlKey.get(assets), deployment);
+    let download = |path: String| {
+        let url = format!("{base_url}/{path}");
+        async move {
+            AbsAssetUrl::from_str(&url)?
+                .download_bytes(assets)
+                .await
+                .with_context(|| format!("Failed to download {url}"))
+        }
+    };
+
+    let metadata_bytes = download(BuildMetadata::FILENAME.to_string()).await?;
+    let metadata = BuildMetadata::parse(std::str::from_utf8(&metadata_bytes)?)?;
+
+    let mut files = metadata.files.iter().cloned().collect::<BTreeSet<_>>();
+    if files.is_empty() {
+        // Builds from before the file list was recorded; fetch what the metadata references
+        files.extend(metadata.client_component_paths.iter().cloned());
+        files.extend(metadata.server_component_paths.iter().cloned());
+        files.extend(
+            metadata
+                .asset
+                .iter()
+                .map(|asset| path_to_unix_string_lossy(&asset.output)),
+        );
+    }
+    files.insert("ambient.toml".to_string());
+
+    std::fs::create_dir_all(output)?;
+    std::fs::write(output.join(BuildMetadata::FILENAME), &metadata_bytes)?;
+
+    let mut downloaded = 0;
+    for file in files {
+        let relative_path = Path::new(&file);
+        if !relative_path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            tracing::warn!("Skipping file with invalid path {file:?} in deployment {deployment}");
+            continue;
+        }
+
+        let path = output.join(relative_path);
+        if path.exists() {
+            continue;
+        }
+
+        let bytes = download(file).await?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, bytes)?;
+        downloaded += 1;
+    }
+
+    tracing::info!("Vendored deployment {deployment} ({downloaded} files downloaded)");
+    Ok(())
+}
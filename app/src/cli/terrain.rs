@@ -0,0 +1,170 @@
+use std::path::{Path, PathBuf};
+
+use ambient_terrain::{
+    heightmap_io::{
+        decode_heightmap, encode_heightmap, export_heightmap_from_storage,
+        import_heightmap_to_storage, HeightmapFormat, HeightmapScale,
+    },
+    streaming::TerrainCellStorage,
+    TerrainSize,
+};
+use anyhow::Context;
+use clap::{Args, Subcommand};
+use glam::{ivec2, uvec2, vec2};
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Terrain {
+    /// Import a heightmap into the terrain cells of a terrain directory, as used by `--terrain-dir`
+    ///
+    /// The heights replace the rock layer of the cells they cover, and cells that don't exist yet are created
+    #[command(name = "import")]
+    Import(TerrainImportOptions),
+    /// Export the heights of terrain cells in a terrain directory, as used by `--terrain-dir`, to a heightmap
+    #[command(name = "export")]
+    Export(TerrainExportOptions),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct TerrainImportOptions {
+    #[arg()]
+    /// The heightmap to import: a 16 bit grayscale `.png`, a `.tif` GeoTIFF, or headerless `.r16` or `.r32` texels
+    pub path: PathBuf,
+    #[arg(long)]
+    /// The terrain directory to write the cells to
+    pub terrain_dir: PathBuf,
+    #[arg(long, default_value_t = 0.)]
+    /// The world X position of the first texel of the heightmap, in meters
+    pub x: f32,
+    #[arg(long, default_value_t = 0.)]
+    /// The world Y position of the first texel of the heightmap, in meters
+    pub y: f32,
+    #[command(flatten)]
+    pub file: HeightmapFileOptions,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct TerrainExportOptions {
+    #[arg(long)]
+    /// The terrain directory to read the cells from
+    pub terrain_dir: PathBuf,
+    #[arg(short, long)]
+    /// Where to write the heightmap: a 16 bit grayscale `.png`, a `.tif` GeoTIFF, or headerless `.r16` or `.r32`
+    /// texels
+    pub output: PathBuf,
+    #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+    /// The X coordinate of the first cell to export
+    pub cell_x: i32,
+    #[arg(long, default_value_t = 0, allow_hyphen_values = true)]
+    /// The Y coordinate of the first cell to export
+    pub cell_y: i32,
+    #[arg(long, default_value_t = 1)]
+    /// How many cells to export along X
+    pub cells_x: u32,
+    #[arg(long, default_value_t = 1)]
+    /// How many cells to export along Y
+    pub cells_y: u32,
+    #[arg(long, default_value_t = 0.)]
+    /// The height of the lowest value of `.png` and `.r16` heightmaps, in meters
+    pub min_height: f32,
+    #[arg(long, default_value_t = 256.)]
+    /// The height of the highest value of `.png` and `.r16` heightmaps, in meters
+    pub max_height: f32,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct HeightmapFileOptions {
+    #[arg(long)]
+    /// The width of `.r16` and `.r32` heightmaps, in texels
+    pub width: Option<u32>,
+    #[arg(long)]
+    /// The height of `.r16` and `.r32` heightmaps, in texels
+    pub height: Option<u32>,
+    #[arg(long, default_value_t = 1.)]
+    /// The distance between two texels of the heightmap, in meters
+    pub texel_size: f32,
+    #[arg(long, default_value_t = 0.)]
+    /// The height of the lowest value of `.png` and `.r16` heightmaps, in meters
+    pub min_height: f32,
+    #[arg(long, default_value_t = 256.)]
+    /// The height of the highest value of `.png` and `.r16` heightmaps, in meters
+    pub max_height: f32,
+}
+
+pub fn handle(command: &Terrain) -> anyhow::Result<()> {
+    match command {
+        Terrain::Import(opt) => import(opt),
+        Terrain::Export(opt) => export(opt),
+    }
+}
+
+fn import(opt: &TerrainImportOptions) -> anyhow::Result<()> {
+    let format = heightmap_format(&opt.path, opt.file.width.zip(opt.file.height))?;
+    let scale = HeightmapScale {
+        texel_size: opt.file.texel_size,
+        min_height: opt.file.min_height,
+        max_height: opt.file.max_height,
+    };
+    let data =
+        std::fs::read(&opt.path).with_context(|| format!("Failed to read {:?}", opt.path))?;
+    let heights = decode_heightmap(&data, format, &scale)?;
+    let storage = TerrainCellStorage::new(&opt.terrain_dir);
+    let cells =
+        import_heightmap_to_storage(&storage, &heights, vec2(opt.x, opt.y), scale.texel_size)
+            .context("Failed to write the terrain cells")?;
+    tracing::info!(
+        "Imported {:?} into {} cells in {:?}",
+        opt.path,
+        cells.len(),
+        opt.terrain_dir
+    );
+    Ok(())
+}
+
+fn export(opt: &TerrainExportOptions) -> anyhow::Result<()> {
+    let storage = TerrainCellStorage::new(&opt.terrain_dir);
+    let heights = export_heightmap_from_storage(
+        &storage,
+        ivec2(opt.cell_x, opt.cell_y),
+        uvec2(opt.cells_x, opt.cells_y),
+    )
+    .context("Failed to read the terrain cells")?;
+    let (height, width) = heights.dim();
+    let format = heightmap_format(&opt.output, Some((width as u32, height as u32)))?;
+    let scale = HeightmapScale {
+        texel_size: TerrainSize::new().texel_size_in_meters(),
+        min_height: opt.min_height,
+        max_height: opt.max_height,
+    };
+    let data = encode_heightmap(&heights, format, &scale)?;
+    std::fs::write(&opt.output, data)
+        .with_context(|| format!("Failed to write {:?}", opt.output))?;
+    tracing::info!(
+        "Exported {width}x{height} texels from {:?} to {:?}",
+        opt.terrain_dir,
+        opt.output
+    );
+    Ok(())
+}
+
+/// The format of the heightmap at `path`, from its extension. Raw formats need their `size`.
+fn heightmap_format(path: &Path, size: Option<(u32, u32)>) -> anyhow::Result<HeightmapFormat> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let raw_size = || size.context("`--width` and `--height` are required for raw heightmaps");
+    Ok(match extension.as_str() {
+        "png" => HeightmapFormat::Png16,
+        "tif" | "tiff" => HeightmapFormat::GeoTiff,
+        "r16" | "raw" => {
+            let (width, height) = raw_size()?;
+            HeightmapFormat::RawR16 { width, height }
+        }
+        "r32" => {
+            let (width, height) = raw_size()?;
+            HeightmapFormat::RawR32 { width, height }
+        }
+        _ => anyhow::bail!("Unsupported heightmap type {path:?}"),
+    })
+}
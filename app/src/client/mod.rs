@@ -408,6 +408,9 @@ fn systems() -> SystemGroup {
             Box::new(ambient_sky::systems()),
             Box::new(ambient_water::systems()),
             Box::new(ambient_gizmos::client_systems()),
+            Box::new(ambient_input::actions::InputActionSystem::new(
+                ambient_input::actions::default_devices(),
+            )),
             Box::new(wasm::systems()),
             Box::new(ambient_client_shared::player::systems_final()),
         ],
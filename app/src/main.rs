@@ -1,6 +1,7 @@
 use ambient_native_std::{
     asset_cache::{AssetCache, SyncAssetKeyExt},
-    asset_url::{ContentBaseUrlKey, UsingLocalDebugAssetsKey},
+    asset_url::{ContentBaseUrlKey, DeploymentsBaseUrlKey, UsingLocalDebugAssetsKey},
+    disk_cache::DiskCacheBudget,
     download_asset::AssetsCacheOnDisk,
 };
 use ambient_settings::SettingsKey;
@@ -46,7 +47,11 @@ fn main() -> anyhow::Result<()> {
     }
 
     PhysicsKey.get(&assets); // Load physics
-    AssetsCacheOnDisk.insert(&assets, false); // Disable disk caching for now; see https://github.com/AmbientRun/Ambient/issues/81
+    AssetsCacheOnDisk.insert(&assets, _settings.general.disk_cache.enabled);
+    DiskCacheBudget.insert(
+        &assets,
+        _settings.general.disk_cache.budget_mb * 1024 * 1024,
+    );
 
     let cli = if let Some(launch_json) = LaunchJson::load()? {
         Cli::parse_from(launch_json.args())
@@ -59,6 +64,13 @@ fn main() -> anyhow::Result<()> {
             tracing::warn!("`-p`/`--project` has no semantic meaning.");
             tracing::warn!("You do not need to use `-p`/`--project` - `ambient run project` is the same as `ambient run -p project`.");
         }
+
+        if let Some(mirror) = &package.deployment_mirror {
+            DeploymentsBaseUrlKey.insert(
+                &assets,
+                ambient_shared_types::urls::deployment_mirror_url(mirror),
+            );
+        }
     }
 
     // Update some ~~global variables~~ asset keys with package-path derived state
@@ -102,6 +114,7 @@ fn main() -> anyhow::Result<()> {
 
         // non-package commands
         Commands::Assets { assets: command } => rt.block_on(cli::assets::handle(command, &assets)),
+        Commands::Terrain { terrain: command } => cli::terrain::handle(command),
         Commands::Login => rt.block_on(cli::login::handle(&assets)),
         Commands::Join(join) => cli::join::handle(join, &rt, assets),
     }
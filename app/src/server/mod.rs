@@ -27,6 +27,7 @@ use ambient_network::{
     server::{ForkingEvent, ProxySettings, SharedServerState, ShutdownEvent},
 };
 use ambient_sys::task::RuntimeHandle;
+use ambient_terrain::streaming::{terrain_streaming, TerrainCellStorage, TerrainStreaming};
 use anyhow::Context;
 use axum::{
     extract::{Host, State},
@@ -162,6 +163,7 @@ pub async fn start(
         );
     }
 
+    let terrain_dir = host_cli.terrain_dir.clone();
     let join_handle = tokio::task::spawn(async move {
         let mut server_world = World::new_with_config("server", WorldContext::Server, true);
         server_world.init_shape_change_tracking();
@@ -173,6 +175,16 @@ pub async fn start(
             )
             .unwrap();
 
+        if let Some(terrain_dir) = terrain_dir {
+            server_world.add_resource(
+                terrain_streaming(),
+                TerrainStreaming {
+                    storage: Some(TerrainCellStorage::new(terrain_dir)),
+                    ..Default::default()
+                },
+            );
+        }
+
         // Keep track of the package name
         let name = manifest.package.name.clone();
         server_world
@@ -244,6 +256,8 @@ fn systems(_world: &mut World) -> SystemGroup {
             Box::new(WorldEventsSystem),
             Box::new(ambient_core::camera::camera_systems()),
             Box::new(ambient_physics::server_systems()),
+            Box::new(ambient_terrain::server_systems()),
+            Box::new(ambient_naturals::server_systems()),
             Box::new(ambient_package_semantic_native::server_systems()),
             Box::new(wasm::systems()),
         ],
@@ -262,6 +276,7 @@ fn on_shutdown_systems() -> SystemGroup<ShutdownEvent> {
     SystemGroup::new(
         "on_shutdown_systems",
         vec![
+            Box::new(ambient_terrain::streaming::on_shutdown_systems()),
             Box::new(ambient_physics::on_shutdown_systems()),
             Box::new(wasm::on_shutdown_systems()),
         ],
@@ -8,6 +8,8 @@ pub(crate) fn init() -> anyhow::Result<()> {
     ambient_primitives::init_components();
     ambient_sky::init_components();
     ambient_water::init_components();
+    ambient_terrain::init_all_components();
+    ambient_naturals::init_all_components();
     ambient_package_semantic_native::init_components();
 
     Ok(())
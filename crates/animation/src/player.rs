@@ -21,6 +21,7 @@ use ambient_model::{animation_binder, ModelFromUrl};
 use ambient_native_std::{
     asset_cache::{AssetCache, AsyncAssetKeyExt},
     asset_url::{AnimationAssetType, TypedAssetUrl},
+    disk_cache::DiskCachedJson,
     download_asset::AssetResult,
 };
 use anyhow::Context;
@@ -230,6 +231,9 @@ pub fn animation_player_systems() -> SystemGroup {
                     } else {
                         AnimationRetargeting::None
                     };
+                    // Scaled clips are retargeted from two models on every load, so the result is kept on disk
+                    let cache_on_disk =
+                        matches!(retargeting, AnimationRetargeting::AnimationScaled { .. });
                     let clip_ref = AnimationClipRetargetedFromModel {
                         clip: url,
                         translation_retargeting: retargeting,
@@ -268,7 +272,11 @@ pub fn animation_player_systems() -> SystemGroup {
                         apply_clip(world, clip);
                     } else {
                         runtime.spawn(async move {
-                            let clip = clip_ref.get(&assets).await;
+                            let clip = if cache_on_disk {
+                                DiskCachedJson(clip_ref).get(&assets).await
+                            } else {
+                                clip_ref.get(&assets).await
+                            };
                             async_run.run(move |world| {
                                 apply_clip(world, clip);
                             });
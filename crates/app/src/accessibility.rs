@@ -0,0 +1,453 @@
+//! Builds an accessibility tree from the UI elements that have an [accessibility_role], and
+//! exports it to assistive technologies, like screen readers, through AccessKit.
+//!
+//! Each world that runs [systems] adds its accessible elements to the [AccessibilityTree] that is
+//! shared by all the worlds of the app, so that the UI of the game client shows up in the same
+//! tree as the UI of the app.
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    num::NonZeroU128,
+    sync::Arc,
+};
+
+use accesskit::{
+    Action, ActionHandler, ActionRequest, Affine, Node, NodeBuilder, NodeClassSet, NodeId, Rect,
+    Role, Tree, TreeUpdate,
+};
+use ambient_core::{
+    hierarchy::{children, parent},
+    transform::local_to_world,
+};
+use ambient_ecs::{
+    components,
+    generated::{
+        layout::components::{height, width},
+        text::components::text,
+        ui::{
+            components::{
+                accessibility_disabled, accessibility_expanded, accessibility_label,
+                accessibility_role, accessibility_selected, accessibility_value, focus, focusable,
+            },
+            messages::Activate,
+            types::AccessibilityRole,
+        },
+    },
+    query, world_events, EntityId, FnSystem, SystemGroup, World,
+};
+use parking_lot::Mutex;
+
+components!("app-accessibility", {
+    accessibility_tree: Arc<Mutex<AccessibilityTree>>,
+});
+
+const ROOT_ID: NodeId = NodeId(NonZeroU128::MIN);
+
+/// The accessible elements of all the worlds of an app.
+#[derive(Debug, Default)]
+pub struct AccessibilityTree {
+    /// Set when an assistive technology asks for the tree; until then, it isn't built.
+    active: bool,
+    worlds: BTreeMap<&'static str, WorldTree>,
+    requests: Vec<ActionRequest>,
+    last_update: Option<TreeUpdate>,
+}
+
+#[derive(Debug, Default)]
+struct WorldTree {
+    /// Set when the world adds its elements; worlds that stop doing so are removed.
+    updated: bool,
+    roots: Vec<NodeId>,
+    nodes: Vec<(NodeId, Node)>,
+    focus: Option<NodeId>,
+    focus_ids: HashMap<NodeId, String>,
+}
+
+impl AccessibilityTree {
+    pub fn new_shared() -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    /// Combines the trees of the worlds under a window node.
+    fn build(&self, title: &str, scale_factor: f64, focused: bool) -> TreeUpdate {
+        let mut root = NodeBuilder::new(Role::Window);
+        root.set_name(title);
+        // The UI is laid out in logical pixels, and AccessKit expects physical ones
+        root.set_transform(Affine::scale(scale_factor));
+        root.set_children(
+            self.worlds
+                .values()
+                .flat_map(|world| world.roots.iter().copied())
+                .collect::<Vec<_>>(),
+        );
+        let mut nodes = vec![(ROOT_ID, root.build(&mut NodeClassSet::lock_global()))];
+        nodes.extend(
+            self.worlds
+                .values()
+                .flat_map(|world| world.nodes.iter().cloned()),
+        );
+        // The focus must be set if and only if the window has the keyboard focus
+        let focus = focused.then(|| {
+            self.worlds
+                .values()
+                .find_map(|world| world.focus)
+                .unwrap_or(ROOT_ID)
+        });
+        TreeUpdate {
+            nodes,
+            tree: Some(Tree::new(ROOT_ID)),
+            focus,
+        }
+    }
+}
+
+/// Queues the actions requested by assistive technologies, which are applied by [systems] in the
+/// world that the target belongs to.
+struct ActionQueue(Arc<Mutex<AccessibilityTree>>);
+impl ActionHandler for ActionQueue {
+    fn do_action(&self, request: ActionRequest) {
+        self.0.lock().requests.push(request);
+    }
+}
+
+/// Applies the requested actions to the accessible elements of this world, and adds them to the
+/// [accessibility_tree].
+pub fn systems() -> SystemGroup {
+    SystemGroup::new(
+        "accessibility",
+        vec![Box::new(FnSystem::new(|world, _| {
+            let Some(tree) = world.resource_opt(accessibility_tree()).cloned() else {
+                return;
+            };
+            let mut tree = tree.lock();
+            if !tree.active {
+                return;
+            }
+            if let Some(current) = tree.worlds.get(world.name()) {
+                let focus_ids = current.focus_ids.clone();
+                tree.requests.retain(|request| {
+                    let Some(focus_id) = focus_ids.get(&request.target) else {
+                        return true;
+                    };
+                    match request.action {
+                        Action::Focus => ambient_focus::set_focus(world, focus_id.clone()),
+                        Action::Default => {
+                            world.resource_mut(world_events()).add_message(Activate {
+                                focus: focus_id.clone(),
+                            });
+                        }
+                        _ => {}
+                    }
+                    false
+                });
+            }
+            let world_tree = build_world_tree(world);
+            tree.worlds.insert(world.name(), world_tree);
+        }))],
+    )
+}
+
+fn build_world_tree(world: &World) -> WorldTree {
+    let accessible = query((accessibility_role(),))
+        .iter(world, None)
+        .map(|(id, _)| id)
+        .collect::<HashSet<_>>();
+
+    // Elements without a role are skipped, so each element is attached to its nearest
+    // accessible ancestor
+    let mut tree_children = HashMap::<Option<EntityId>, Vec<EntityId>>::new();
+    for &id in &accessible {
+        let mut ancestor = world.get(id, parent()).ok();
+        while let Some(entity) = ancestor {
+            if accessible.contains(&entity) {
+                break;
+            }
+            ancestor = world.get(entity, parent()).ok();
+        }
+        tree_children.entry(ancestor).or_default().push(id);
+    }
+    for siblings in tree_children.values_mut() {
+        ambient_focus::sort_in_focus_order(world, siblings);
+    }
+
+    let current_focus = world.resource_opt(focus()).cloned().unwrap_or_default();
+    let mut result = WorldTree {
+        updated: true,
+        roots: tree_children
+            .get(&None)
+            .map(|roots| roots.iter().map(|&id| node_id(id)).collect())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let mut classes = NodeClassSet::lock_global();
+    for &id in &accessible {
+        let node_children = tree_children
+            .get(&Some(id))
+            .map_or(&[][..], |c| c.as_slice());
+        result.nodes.push((
+            node_id(id),
+            build_node(world, id, node_children, &mut classes),
+        ));
+        if let Ok(focus_id) = world.get_cloned(id, focusable()) {
+            if !focus_id.is_empty() && focus_id == current_focus {
+                result.focus = Some(node_id(id));
+            }
+            result.focus_ids.insert(node_id(id), focus_id);
+        }
+    }
+    result
+}
+
+fn build_node(
+    world: &World,
+    id: EntityId,
+    node_children: &[EntityId],
+    classes: &mut NodeClassSet,
+) -> Node {
+    let role = world.get(id, accessibility_role()).unwrap();
+    let mut node = NodeBuilder::new(to_accesskit_role(role));
+
+    let label = world
+        .get_cloned(id, accessibility_label())
+        .ok()
+        .or_else(|| {
+            let mut label = String::new();
+            if named_from_content(role) {
+                content_text(world, id, &mut label);
+            }
+            Some(label.trim().to_string()).filter(|label| !label.is_empty())
+        });
+    if let Some(label) = label {
+        node.set_name(label);
+    }
+    if let Ok(value) = world.get_cloned(id, accessibility_value()) {
+        node.set_value(value);
+    }
+    if world.get(id, accessibility_disabled()).unwrap_or_default() {
+        node.set_disabled();
+    }
+    if let Ok(expanded) = world.get(id, accessibility_expanded()) {
+        node.set_expanded(expanded);
+    }
+    if let Ok(selected) = world.get(id, accessibility_selected()) {
+        node.set_selected(selected);
+    }
+    if world.has_component(id, focusable()) {
+        node.add_action(Action::Focus);
+        // Buttons are pressed through [Activate]
+        if matches!(role, AccessibilityRole::Button) {
+            node.add_action(Action::Default);
+        }
+    }
+    if let Ok(transform) = world.get(id, local_to_world()) {
+        let position = transform.w_axis.truncate().as_dvec3();
+        let size = glam::vec2(
+            world.get(id, width()).unwrap_or_default(),
+            world.get(id, height()).unwrap_or_default(),
+        )
+        .as_dvec2();
+        node.set_bounds(Rect::new(
+            position.x,
+            position.y,
+            position.x + size.x,
+            position.y + size.y,
+        ));
+    }
+    node.set_children(
+        node_children
+            .iter()
+            .map(|&child| node_id(child))
+            .collect::<Vec<_>>(),
+    );
+    node.build(classes)
+}
+
+/// Whether an element without an [accessibility_label] is named after the text it contains.
+fn named_from_content(role: AccessibilityRole) -> bool {
+    matches!(
+        role,
+        AccessibilityRole::Text
+            | AccessibilityRole::Button
+            | AccessibilityRole::ComboBox
+            | AccessibilityRole::ListItem
+            | AccessibilityRole::Tab
+            | AccessibilityRole::Tooltip
+    )
+}
+
+/// Appends the text of `id` and its descendants, leaving out accessible descendants, which are
+/// nodes of their own.
+fn content_text(world: &World, id: EntityId, out: &mut String) {
+    if let Ok(value) = world.get_ref(id, text()) {
+        // Icons are drawn with glyphs from the private use area, which have no meaning when read
+        let value = value
+            .chars()
+            .filter(|c| !('\u{e000}'..='\u{f8ff}').contains(c))
+            .collect::<String>();
+        if !value.trim().is_empty() {
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            out.push_str(value.trim());
+        }
+    }
+    for child in world.get_cloned(id, children()).unwrap_or_default() {
+        if !world.has_component(child, accessibility_role()) {
+            content_text(world, child, out);
+        }
+    }
+}
+
+fn to_accesskit_role(role: AccessibilityRole) -> Role {
+    match role {
+        AccessibilityRole::Group => Role::Group,
+        AccessibilityRole::Text => Role::StaticText,
+        AccessibilityRole::Button => Role::Button,
+        AccessibilityRole::TextInput => Role::TextField,
+        AccessibilityRole::ComboBox => Role::PopupButton,
+        AccessibilityRole::List => Role::List,
+        AccessibilityRole::ListItem => Role::ListItem,
+        AccessibilityRole::TabList => Role::TabList,
+        AccessibilityRole::Tab => Role::Tab,
+        AccessibilityRole::TabPanel => Role::TabPanel,
+        AccessibilityRole::Tooltip => Role::Tooltip,
+        AccessibilityRole::Dialog => Role::Dialog,
+    }
+}
+
+fn node_id(id: EntityId) -> NodeId {
+    NodeId(NonZeroU128::new(id.0).expect("The null entity can't be accessible"))
+}
+
+/// Exports the [AccessibilityTree] of an app to the accessibility APIs of the platform.
+#[cfg(not(target_os = "unknown"))]
+pub struct AccessibilityAdapter {
+    adapter: accesskit_winit::Adapter,
+    tree: Arc<Mutex<AccessibilityTree>>,
+}
+#[cfg(not(target_os = "unknown"))]
+impl AccessibilityAdapter {
+    /// Creates the adapter for `window`, which must not have been shown yet.
+    pub fn new(window: &winit::window::Window, tree: Arc<Mutex<AccessibilityTree>>) -> Self {
+        let adapter = accesskit_winit::Adapter::with_action_handler(
+            window,
+            {
+                let tree = tree.clone();
+                let scale_factor = window.scale_factor();
+                move || {
+                    // Called the first time an assistive technology asks for the tree; the
+                    // worlds add their elements to it from the next frame on
+                    let mut tree = tree.lock();
+                    tree.active = true;
+                    tree.build("", scale_factor, false)
+                }
+            },
+            Box::new(ActionQueue(tree.clone())),
+        );
+        Self { adapter, tree }
+    }
+
+    pub fn on_event(&self, window: &winit::window::Window, event: &winit::event::WindowEvent) {
+        let _ = self.adapter.on_event(window, event);
+    }
+
+    /// Sends the changes to the tree to the platform. Must be called after the systems of all the
+    /// worlds have run.
+    pub fn update(&self, title: &str, scale_factor: f64, focused: bool) {
+        let mut tree = self.tree.lock();
+        // Requests for elements that are gone are dropped
+        tree.requests.clear();
+        if !tree.active {
+            return;
+        }
+        tree.worlds
+            .retain(|_, world| std::mem::take(&mut world.updated));
+        let update = tree.build(title, scale_factor, focused);
+        // Nodes that haven't changed don't need to be sent, but the whole tree is cheap enough to
+        // compare
+        if tree.last_update.as_ref() != Some(&update) {
+            self.adapter.update(update.clone());
+            tree.last_update = Some(update);
+        }
+    }
+}
+#[cfg(not(target_os = "unknown"))]
+impl std::fmt::Debug for AccessibilityAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessibilityAdapter")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ambient_element::ElementComponentExt;
+    use ambient_shared_types::VirtualKeyCode;
+    use ambient_ui_native::ui::{button::Button, layout::FlowColumn, text::Text, Rectangle};
+    use ambient_ui_testing::UiTestHarness;
+
+    use super::*;
+
+    #[test]
+    fn world_tree() {
+        let button = |label: &str| {
+            Button::new(
+                FlowColumn::el([
+                    Rectangle.el().with(width(), 40.).with(height(), 20.),
+                    Text::el(label),
+                ]),
+                |_| {},
+            )
+            .el()
+        };
+        let mut harness = UiTestHarness::new(
+            FlowColumn::el([
+                FlowColumn::el([button("Save"), button("Cancel")]),
+                Text::el("Unsaved changes").with(accessibility_role(), AccessibilityRole::Text),
+            ])
+            .with(accessibility_role(), AccessibilityRole::Group)
+            .with(accessibility_label(), "Settings".to_string()),
+        );
+
+        let tree = build_world_tree(&harness.world);
+        let node = |id: NodeId| {
+            &tree
+                .nodes
+                .iter()
+                .find(|(node_id, _)| *node_id == id)
+                .unwrap()
+                .1
+        };
+        assert_eq!(tree.roots.len(), 1);
+        let group = node(tree.roots[0]);
+        assert_eq!(group.role(), Role::Group);
+        assert_eq!(group.name(), Some("Settings"));
+
+        // The elements without a role are skipped, and the rest are in focus order
+        let children = group.children();
+        let names = children
+            .iter()
+            .map(|&id| node(id).name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [Some("Save"), Some("Cancel"), Some("Unsaved changes")]
+        );
+        let save = node(children[0]);
+        assert_eq!(save.role(), Role::Button);
+        assert!(save.supports_action(Action::Focus));
+        assert!(save.supports_action(Action::Default));
+        let text = node(children[2]);
+        assert_eq!(text.role(), Role::StaticText);
+        assert!(!text.supports_action(Action::Focus));
+
+        assert_eq!(tree.focus, None);
+        assert_eq!(tree.focus_ids.len(), 2);
+        harness.press_key(VirtualKeyCode::Tab);
+        let tree = build_world_tree(&harness.world);
+        assert_eq!(tree.focus, Some(children[0]));
+        assert_eq!(
+            tree.focus_ids[&children[0]],
+            *harness.world.resource(focus())
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Lets input methods compose text while a text input, like a `TextEditor`, has the focus. The
+//! composed text is sent as `WindowImeComposition` and `WindowKeyboardCharacter` messages.
+//!
+//! Each world that runs [systems] reports its focused text input to the [ImeState] that is shared
+//! by all the worlds of the app, and the window accepts input methods while one of them has one.
+use std::{collections::BTreeMap, sync::Arc};
+
+use ambient_core::transform::local_to_world;
+use ambient_ecs::{
+    components,
+    generated::{
+        layout::components::height,
+        ui::{
+            components::{accessibility_role, focus, focusable},
+            types::AccessibilityRole,
+        },
+    },
+    query, FnSystem, SystemGroup, World,
+};
+use glam::Vec2;
+use parking_lot::Mutex;
+use winit::{dpi::LogicalPosition, window::Window};
+
+components!("app-ime", {
+    ime_state: Arc<Mutex<ImeState>>,
+});
+
+/// The focused text inputs of all the worlds of an app.
+#[derive(Debug, Default)]
+pub struct ImeState {
+    worlds: BTreeMap<&'static str, WorldIme>,
+    allowed: bool,
+    position: Option<Vec2>,
+}
+
+#[derive(Debug, Default)]
+struct WorldIme {
+    /// Set when the world reports its text input; worlds that stop doing so are removed.
+    updated: bool,
+    /// Where the candidate window of the input method goes, below the focused text input.
+    position: Option<Vec2>,
+}
+
+impl ImeState {
+    pub fn new_shared() -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    /// Allows input methods on `window` while a text input has the focus, and moves their
+    /// candidate window below it.
+    pub(crate) fn apply(&mut self, window: &Window) {
+        self.worlds
+            .retain(|_, world| std::mem::take(&mut world.updated));
+        let position = self.worlds.values().find_map(|world| world.position);
+        if position.is_some() != self.allowed {
+            self.allowed = position.is_some();
+            window.set_ime_allowed(self.allowed);
+        }
+        if let Some(position) = position.filter(|&position| self.position != Some(position)) {
+            // The UI is laid out in logical pixels
+            window.set_ime_position(LogicalPosition::new(position.x, position.y));
+        }
+        self.position = position;
+    }
+}
+
+/// Reports the focused text input of this world to the [ime_state].
+pub fn systems() -> SystemGroup {
+    SystemGroup::new(
+        "ime",
+        vec![Box::new(FnSystem::new(|world, _| {
+            let Some(state) = world.resource_opt(ime_state()).cloned() else {
+                return;
+            };
+            let position = focused_text_input_position(world);
+            state.lock().worlds.insert(
+                world.name(),
+                WorldIme {
+                    updated: true,
+                    position,
+                },
+            );
+        }))],
+    )
+}
+
+fn focused_text_input_position(world: &World) -> Option<Vec2> {
+    let current_focus = world
+        .resource_opt(focus())
+        .filter(|focus| !focus.is_empty())?;
+    let (id, _) = query((focusable(), accessibility_role()))
+        .iter(world, None)
+        .find(|(_, (focus_id, role))| {
+            *focus_id == current_focus && matches!(role, AccessibilityRole::TextInput)
+        })?;
+    let transform = world.get(id, local_to_world()).ok()?;
+    let height = world.get(id, height()).unwrap_or_default();
+    Some(transform.w_axis.truncate().truncate() + Vec2::Y * height)
+}
@@ -1,5 +1,6 @@
 use std::{future::Future, sync::Arc, time::Duration};
 
+use accessibility::{accessibility_tree, AccessibilityTree};
 use ambient_cameras::assets_camera_systems;
 pub use ambient_core::gpu;
 use ambient_core::{
@@ -18,8 +19,10 @@ use ambient_core::{
     ClientTimeResourcesSystem, PerformanceSample, RuntimeKey,
 };
 use ambient_ecs::{
-    components, generated::ui::components::focus, world_events, Debuggable, DynSystem, Entity,
-    FrameEvent, MakeDefault, MaybeResource, System, SystemGroup, World, WorldEventsSystem,
+    components,
+    generated::ui::components::{focus, locale},
+    world_events, Debuggable, DynSystem, Entity, FrameEvent, MakeDefault, MaybeResource, System,
+    SystemGroup, World, WorldEventsSystem,
 };
 use ambient_element::ambient_system;
 use ambient_gizmos::{gizmos, Gizmos};
@@ -36,6 +39,7 @@ use ambient_procedurals::{procedural_storage, ProceduralStorage};
 use ambient_renderer::lod::lod_system;
 use ambient_settings::SettingsKey;
 use ambient_sys::{task::RuntimeHandle, time::Instant};
+use ime::{ime_state, ImeState};
 
 use glam::{uvec2, vec2, IVec2, UVec2, Vec2};
 use parking_lot::Mutex;
@@ -47,6 +51,9 @@ use winit::{
     window::{CursorGrabMode, Fullscreen, Window, WindowBuilder},
 };
 
+pub mod accessibility;
+pub mod ime;
+pub mod locale;
 mod renderers;
 
 fn default_title() -> String {
@@ -74,6 +81,8 @@ pub fn init_all_components() {
     ambient_model::init_components();
     ambient_cameras::init_all_components();
     renderers::init_components();
+    accessibility::init_components();
+    ime::init_components();
     ambient_procedurals::init_components();
 }
 
@@ -103,6 +112,7 @@ pub fn world_instance_systems(full: bool) -> SystemGroup {
             Box::new(ambient_core::hierarchy::systems()),
             Box::new(WorldEventsSystem),
             Box::new(ambient_focus::systems()),
+            Box::new(locale::systems()),
             if full {
                 Box::new(ambient_input::picking::frame_systems())
             } else {
@@ -123,6 +133,16 @@ pub fn world_instance_systems(full: bool) -> SystemGroup {
             Box::new(bounding_systems()),
             Box::new(camera_systems()),
             Box::new(ambient_procedurals::client_systems()),
+            if full {
+                Box::new(accessibility::systems())
+            } else {
+                Box::new(DummySystem)
+            },
+            if full {
+                Box::new(ime::systems())
+            } else {
+                Box::new(DummySystem)
+            },
         ],
     )
 }
@@ -132,6 +152,8 @@ pub struct AppResources {
     pub gpu: Arc<Gpu>,
     pub runtime: RuntimeHandle,
     pub ctl_tx: flume::Sender<WindowCtl>,
+    pub accessibility: Arc<Mutex<AccessibilityTree>>,
+    pub ime: Arc<Mutex<ImeState>>,
     window_physical_size: UVec2,
     window_logical_size: UVec2,
     window_scale_factor: f64,
@@ -144,6 +166,8 @@ impl AppResources {
             gpu: world.resource(self::gpu()).clone(),
             runtime: world.resource(self::runtime()).clone(),
             ctl_tx: world.resource(ambient_core::window::window_ctl()).clone(),
+            accessibility: world.resource(accessibility_tree()).clone(),
+            ime: world.resource(ime_state()).clone(),
             window_physical_size: *world.resource(ambient_core::window::window_physical_size()),
             window_logical_size: *world.resource(ambient_core::window::window_logical_size()),
             window_scale_factor: *world.resource(ambient_core::window::window_scale_factor()),
@@ -187,6 +211,9 @@ pub fn world_instance_resources(resources: AppResources) -> Entity {
         .with(ambient_core::window::window_ctl(), resources.ctl_tx)
         .with(procedural_storage(), ProceduralStorage::new())
         .with(focus(), Default::default())
+        .with(locale(), ambient_i18n::locale())
+        .with(accessibility_tree(), resources.accessibility)
+        .with(ime_state(), resources.ime)
 }
 
 pub struct AppBuilder {
@@ -321,6 +348,7 @@ impl AppBuilder {
             .unwrap_or_else(|| AssetCache::new(runtime.clone()));
 
         let settings = SettingsKey.get(&assets);
+        locale::init_locale(&assets);
 
         let (window, event_loop) = if self.headless.is_some() {
             (None, None)
@@ -346,10 +374,22 @@ impl AppBuilder {
             } else {
                 window
             };
-            let window = Arc::new(window.build(&event_loop).unwrap());
+            // AccessKit has to be set up before the window is shown
+            #[cfg(not(target_os = "unknown"))]
+            let window = window.with_visible(false);
+            let window = window.build(&event_loop).unwrap();
+            let window = Arc::new(window);
             (Some(window), Some(event_loop))
         };
 
+        let accessibility = AccessibilityTree::new_shared();
+        #[cfg(not(target_os = "unknown"))]
+        let accessibility_adapter = window.as_ref().map(|window| {
+            let adapter = accessibility::AccessibilityAdapter::new(window, accessibility.clone());
+            window.set_visible(true);
+            adapter
+        });
+
         let (cursor_lock_tx, cursor_lock_rx) = flume::unbounded::<bool>();
 
         // This isn't necessary on native
@@ -511,6 +551,8 @@ impl AppBuilder {
             runtime: runtime.clone(),
             assets,
             ctl_tx,
+            accessibility,
+            ime: ImeState::new_shared(),
             window_physical_size,
             window_logical_size,
             window_scale_factor,
@@ -555,6 +597,8 @@ impl AppBuilder {
         Ok(App {
             window_focused: true,
             window,
+            #[cfg(not(target_os = "unknown"))]
+            accessibility: accessibility_adapter,
             runtime,
             systems: SystemGroup::new(
                 "app",
@@ -620,6 +664,8 @@ pub struct App {
     pub window_event_systems: SystemGroup<Event<'static, ()>>,
     pub runtime: RuntimeHandle,
     pub window: Option<Arc<Window>>,
+    #[cfg(not(target_os = "unknown"))]
+    accessibility: Option<accessibility::AccessibilityAdapter>,
     event_loop: Option<EventLoop<()>>,
     fps: FpsCounter,
     #[cfg(feature = "profile")]
@@ -773,6 +819,13 @@ impl App {
 
         self.window_event_systems.run(world, event);
 
+        #[cfg(not(target_os = "unknown"))]
+        if let (Event::WindowEvent { event, .. }, Some(window), Some(accessibility)) =
+            (event, &self.window, &self.accessibility)
+        {
+            accessibility.on_event(window, event);
+        }
+
         match event {
             Event::MainEventsCleared => {
                 let frame_start = Instant::now();
@@ -850,6 +903,19 @@ impl App {
                     gpu_world_sync_systems.run(world, &GpuWorldSyncEvent);
                 }
 
+                if let Some(window) = &self.window {
+                    world.resource(ime_state()).lock().apply(window);
+                }
+
+                #[cfg(not(target_os = "unknown"))]
+                if let Some(accessibility) = &self.accessibility {
+                    accessibility.update(
+                        world.resource(window_title()),
+                        *world.resource(window_scale_factor()),
+                        self.window_focused,
+                    );
+                }
+
                 if let Some(fps) = self.fps.frame_next() {
                     world
                         .set(world.resource_entity(), self::fps_stats(), fps.clone())
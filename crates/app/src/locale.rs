@@ -0,0 +1,50 @@
+use ambient_core::asset_cache;
+use ambient_ecs::{
+    generated::ui::{components::locale, messages::SetLocale},
+    read_messages, world_events, FnSystem, SystemGroup, World, WorldEventReader,
+};
+use ambient_native_std::asset_cache::{AssetCache, SyncAssetKey};
+use ambient_settings::SettingsKey;
+
+/// Sets the current locale of `ambient_i18n` to the one stored in the settings.
+pub fn init_locale(assets: &AssetCache) {
+    if let Some(locale) = SettingsKey.get(assets).general.locale {
+        ambient_i18n::set_locale(&locale);
+    }
+}
+
+/// Applies the locale changes requested through [SetLocale], and keeps the [locale] resource in
+/// sync with the current locale of `ambient_i18n`, which is shared by all the worlds of the app.
+pub fn systems() -> SystemGroup {
+    let mut reader = WorldEventReader::new();
+    SystemGroup::new(
+        "locale",
+        vec![Box::new(FnSystem::new(move |world, _| {
+            for message in read_messages::<SetLocale>(&mut reader, world.resource(world_events())) {
+                set_locale(world, message.locale);
+            }
+            let current = ambient_i18n::locale();
+            if *world.resource(locale()) != current {
+                *world.resource_mut(locale()) = current;
+            }
+        }))],
+    )
+}
+
+/// Changes the locale of the app, and stores it in the player's settings.
+pub fn set_locale(world: &mut World, value: String) {
+    ambient_i18n::set_locale(&value);
+    *world.resource_mut(locale()) = value.clone();
+
+    let Some(assets) = world.resource_opt(asset_cache()) else {
+        return;
+    };
+    let mut settings = SettingsKey.get(assets);
+    settings.general.locale = Some(value);
+
+    #[cfg(not(target_os = "unknown"))]
+    if let Err(err) = settings.write_to_file(None) {
+        tracing::warn!("Failed to persist the locale: {err:?}");
+    }
+    SettingsKey.insert(assets, settings);
+}
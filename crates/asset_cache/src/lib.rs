@@ -729,9 +729,21 @@ impl AssetTimeline {
     }
 }
 
+/// An entry in the on-disk asset cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskCacheTimeline {
+    pub size: u64,
+    pub hits: u64,
+    pub inserted: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_access: chrono::DateTime<chrono::Utc>,
+    pub evicted: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AssetsTimeline {
     pub assets: HashMap<AssetKey, AssetTimeline>,
+    /// Entries of the on-disk cache that were used or evicted during this session
+    pub disk_cache: HashMap<String, DiskCacheTimeline>,
     pub start_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -739,6 +751,7 @@ impl AssetsTimeline {
     pub fn new() -> Self {
         Self {
             assets: Default::default(),
+            disk_cache: Default::default(),
             start_time: chrono::Utc::now(),
         }
     }
@@ -746,6 +759,58 @@ impl AssetsTimeline {
         self.assets.values().filter(|x| x.is_loading()).count()
     }
 
+    /// Returns the number of entries and the total size of the on-disk cache entries that have not been evicted
+    pub fn disk_cache_usage(&self) -> (usize, u64) {
+        self.disk_cache
+            .values()
+            .filter(|x| x.evicted.is_none())
+            .fold((0, 0), |(count, size), x| (count + 1, size + x.size))
+    }
+
+    pub fn disk_cache_insert(&mut self, key: &str, size: u64) {
+        let now = chrono::Utc::now();
+        self.disk_cache.insert(
+            key.to_string(),
+            DiskCacheTimeline {
+                size,
+                hits: 0,
+                inserted: Some(now),
+                last_access: now,
+                evicted: None,
+            },
+        );
+    }
+
+    pub fn disk_cache_hit(&mut self, key: &str, size: u64) {
+        let now = chrono::Utc::now();
+        let entry = self
+            .disk_cache
+            .entry(key.to_string())
+            .or_insert_with(|| DiskCacheTimeline {
+                size,
+                hits: 0,
+                inserted: None,
+                last_access: now,
+                evicted: None,
+            });
+        entry.hits += 1;
+        entry.last_access = now;
+    }
+
+    pub fn disk_cache_evict(&mut self, key: &str, size: u64) {
+        let now = chrono::Utc::now();
+        self.disk_cache
+            .entry(key.to_string())
+            .or_insert_with(|| DiskCacheTimeline {
+                size,
+                hits: 0,
+                inserted: None,
+                last_access: now,
+                evicted: None,
+            })
+            .evicted = Some(now);
+    }
+
     fn start_load(
         &mut self,
         key: AssetKey,
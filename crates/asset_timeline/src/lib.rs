@@ -55,6 +55,7 @@ impl ElementComponent for AssetTimelineVisualizer {
             }
         }
         let total_roots_gpu_size = roots.values().map(|x| x.1).sum::<u64>();
+        let (disk_cache_count, disk_cache_size) = self.timeline.disk_cache_usage();
         let timeline = Arc::new(self.timeline);
         let mut children = roots
             .into_iter()
@@ -163,6 +164,10 @@ impl ElementComponent for AssetTimelineVisualizer {
                     Text::el("Limit:"),
                     Option::<usize>::editor(limit, set_limit, Default::default()),
                 ]),
+                Text::el(format!(
+                    "Disk cache: {disk_cache_count} entries ({})",
+                    to_byte_unit(disk_cache_size)
+                )),
             ])
             .keyboard(),
         );
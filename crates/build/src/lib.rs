@@ -8,9 +8,12 @@ use std::{
 };
 
 use ambient_asset_cache::{AssetCache, SyncAssetKeyExt};
-use ambient_native_std::{asset_url::AbsAssetUrl, AmbientVersion};
+use ambient_native_std::{
+    asset_url::{AbsAssetUrl, DeploymentsBaseUrlKey},
+    AmbientVersion,
+};
 use ambient_package::{
-    BuildMetadata, BuildMetadataError, BuildSettings, Manifest as PackageManifest,
+    BuildMetadata, BuildMetadataError, BuildSettings, Lockfile, Manifest as PackageManifest,
 };
 use ambient_package_semantic::{package_dependency_to_retrievable_file, RetrievableFile, Semantic};
 use ambient_package_semantic_native::add_to_semantic_and_register_components;
@@ -50,6 +53,7 @@ pub async fn build_package(
 ) -> anyhow::Result<BuildResult> {
     let _span = tracing::info_span!("register_semantic", ?package_path).entered();
     let mut semantic = Semantic::new(settings.deploy).await?;
+    semantic.deployments_base_url = DeploymentsBaseUrlKey.get(assets);
 
     let package_item_id = add_to_semantic_and_register_components(
         &mut semantic,
@@ -124,10 +128,18 @@ pub async fn build_package(
             omr: &RetrievableFile,
             deploy: bool,
             dependency: &ambient_package::Dependency,
+            lockfile: Option<&Lockfile>,
+            deployments_base_url: &str,
         ) -> anyhow::Result<Option<chrono::DateTime<chrono::Utc>>> {
-            let metadata_path = package_dependency_to_retrievable_file(omr, deploy, dependency)?
-                .map(|p| p.parent_join(Path::new(BuildMetadata::FILENAME)))
-                .transpose()?;
+            let metadata_path = package_dependency_to_retrievable_file(
+                omr,
+                deploy,
+                dependency,
+                lockfile,
+                deployments_base_url,
+            )?
+            .map(|p| p.parent_join(Path::new(BuildMetadata::FILENAME)))
+            .transpose()?;
 
             Ok(match metadata_path {
                 Some(metadata_path) => get_build_metadata(metadata_path)
@@ -140,7 +152,13 @@ pub async fn build_package(
 
         let output_manifest_retrievable = RetrievableFile::Path(output_manifest_path.clone());
         futures::future::try_join_all(manifest.dependencies.values().map(|dep| {
-            dependency_to_build_time(&output_manifest_retrievable, settings.deploy, dep)
+            dependency_to_build_time(
+                &output_manifest_retrievable,
+                settings.deploy,
+                dep,
+                semantic.lockfile.as_ref(),
+                &semantic.deployments_base_url,
+            )
         }))
         .await?
         .into_iter()
@@ -208,6 +226,14 @@ pub async fn build_package(
     tracing::info!("Source built");
 
     tokio::fs::write(&output_manifest_path, toml::to_string(&manifest)?).await?;
+    if let Some(lockfile) = &semantic.lockfile {
+        // Ship the lockfile with the build so that it resolves to the same dependencies
+        tokio::fs::write(
+            build_path.join(Lockfile::FILENAME),
+            lockfile.to_toml_string(),
+        )
+        .await?;
+    }
 
     store_metadata(&package_path, &build_path, settings, &assets).await?;
 
@@ -308,6 +334,22 @@ pub async fn build_assets(
         .await
         .with_context(|| format!("Failed to process pipelines for {assets_path:?}"))?;
 
+    // String tables are loaded as they are by `LoadLocale`, so they're copied without a pipeline
+    if !for_import_only {
+        for path in get_files_in_path(&assets_path.join("locales"))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "ftl"))
+        {
+            let contents = tokio::fs::read(&path)
+                .await
+                .with_context(|| format!("Failed to read string table {path:?}"))?;
+            let relative_path = path
+                .strip_prefix(assets_path)?
+                .to_string_lossy()
+                .replace('\\', "/");
+            (ctx.write_file)(relative_path, contents).await;
+        }
+    }
+
     if !anim_files.lock().is_empty() {
         println!("🐆 Available animation files: {:?}", anim_files.lock());
         println!("🧂 You can use the animation files like this:");
@@ -407,6 +449,11 @@ async fn store_metadata(
                 })
             })
             .collect(),
+        files: get_files_in_path(build_path)
+            .filter_map(|p| Some(path_to_unix_string_lossy(p.strip_prefix(build_path).ok()?)))
+            .filter(|p| p != BuildMetadata::FILENAME)
+            .sorted()
+            .collect(),
     };
     let metadata_path = build_path.join(BuildMetadata::FILENAME);
     tokio::fs::write(&metadata_path, toml::to_string(&metadata)?).await?;
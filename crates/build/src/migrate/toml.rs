@@ -287,6 +287,7 @@ impl From<json_pipeline::ModelsPipeline> for ModelsPipeline {
             prefab_components: None,
             material_overrides: value.material_overrides,
             transforms: value.transforms,
+            generate_lods: vec![],
         }
     }
 }
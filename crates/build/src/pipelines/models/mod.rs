@@ -25,6 +25,7 @@ pub async fn pipeline(ctx: &PipelineCtx, config: ModelsPipeline) -> Vec<OutAsset
         ModelImporter::UnityModels { use_prefabs } => {
             unity::pipeline(ctx, *use_prefabs, config.clone()).await
         }
+        ModelImporter::UnityScenes => unity::scene_pipeline(ctx, config.clone()).await,
         ModelImporter::Quixel => quixel::pipeline(ctx, config.clone()).await,
     };
     if config.collection_of_variants && assets.len() > 1 {
@@ -70,6 +71,7 @@ async fn apply(
     if let Some(max_size) = pipeline.cap_texture_sizes {
         model_crate.cap_texture_sizes(max_size.size());
     }
+    model_crate.generate_lods(&pipeline.generate_lods);
     model_crate.finalize_model();
     match pipeline.collider {
         Collider::None => {}
@@ -84,6 +86,16 @@ async fn apply(
         Collider::Character { radius, height } => {
             model_crate.create_character_collider(radius, height)
         }
+        Collider::ConvexDecomposition {
+            max_hulls,
+            resolution,
+        } => {
+            model_crate.create_convex_decomposition_collider(
+                &ctx.process_ctx.assets,
+                max_hulls as usize,
+                resolution,
+            );
+        }
     }
     model_crate.add_component_to_prefab(
         collider_type(),
@@ -1,8 +1,13 @@
-use std::{collections::HashMap, io::Cursor, sync::Arc};
+use std::{
+    collections::HashMap,
+    f32::consts::{FRAC_PI_2, PI},
+    io::Cursor,
+    sync::Arc,
+};
 
 use ambient_core::{
     hierarchy::{children, parent},
-    name,
+    main_scene, name,
     transform::{get_world_transform, mesh_to_local, rotation, scale, translation},
 };
 use ambient_ecs::{Entity, EntityId, World};
@@ -17,20 +22,31 @@ use ambient_native_std::{
     asset_cache::AssetCache,
     asset_url::{AbsAssetUrl, AssetType, ModelCrateAssetType, TypedAssetUrl},
 };
+use ambient_physics::{
+    collider::{collider, ColliderFromUrls},
+    mesh::PhysxGeometryFromUrl,
+};
 use ambient_renderer::{
+    light_diffuse,
     lod::{gpu_lod, lod_cutoffs, LodCutoffs},
     materials::pbr_material::PbrMaterialDesc,
+    sun,
 };
 use ambient_unity_parser as unity_parser;
 use anyhow::Context;
 use async_recursion::async_recursion;
 use futures::{future::join_all, FutureExt};
-use glam::{Mat4, Vec3, Vec4};
+use glam::{vec3, Mat4, Quat, Vec3, Vec4};
 use image::ImageOutputFormat;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
-use unity_parser::{parse_unity_yaml, prefab::PrefabObject, UnityRef};
+use unity_parser::{
+    parse_unity_yaml,
+    prefab::PrefabObject,
+    scene::{ColliderShape, LightType, SceneNode, UnityScene},
+    UnityRef,
+};
 use yaml_rust::Yaml;
 
 use super::{super::context::PipelineCtx, create_texture_resolver, ModelsPipeline};
@@ -51,46 +67,11 @@ pub async fn pipeline(
     use_prefabs: bool,
     config: ModelsPipeline,
 ) -> Vec<OutAsset> {
-    let guid_lookup = join_all(
-        ctx.files
-            .0
-            .iter()
-            .cloned()
-            .filter_map(|file| {
-                if let Some(base_path) = file.decoded_path().as_str().strip_suffix(".meta") {
-                    let base_path = base_path.to_string();
-                    let mut base_file = file.clone();
-                    base_file.set_path(base_path);
-                    if let Ok(base_file) = ctx.get_downloadable_url(&base_file).cloned() {
-                        Some(async move {
-                            let docs = download_unity_yaml(ctx.assets(), &file).await?;
-                            Ok((
-                                docs[0]["guid"]
-                                    .as_str()
-                                    .unwrap_or_else(|| panic!("Failed to find GUID in {file}"))
-                                    .to_string(),
-                                base_file,
-                            ))
-                        })
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect_vec(),
-    )
-    .await
-    .into_iter()
-    .collect::<anyhow::Result<HashMap<_, _>>>()
-    .unwrap();
-
+    let guid_lookup = Arc::new(create_guid_lookup(ctx).await);
     let materials = Arc::new(Mutex::new(UnityMaterials {
         materials: Default::default(),
         ctx: ctx.clone(),
     }));
-    let guid_lookup = Arc::new(guid_lookup);
     let mesh_models = Arc::new(Mutex::new(MeshModels {
         models: Default::default(),
         force_assimp: config.force_assimp,
@@ -105,17 +86,17 @@ pub async fn pipeline(
                 let mesh_models = mesh_models.clone();
                 let guid_lookup = guid_lookup.clone();
                 async move {
-                    let mut res = Vec::new();
                     let prefab = unity_parser::prefab::PrefabFile::from_yaml(
                         download_unity_yaml(ctx.assets(), &file).await.unwrap(),
                     )
                     .unwrap();
+                    let roots = prefab
+                        .get_root_game_objects()
+                        .into_iter()
+                        .map(SceneNode::GameObject)
+                        .collect_vec();
 
-                    let out_model_path = ctx.in_root().relative_path(file.decoded_path());
-                    let out_model_url =
-                        ctx.out_root().push(&out_model_path).unwrap().as_directory();
-
-                    let mut asset_crate = model_from_prefab(
+                    let asset = unity_file_to_asset(
                         UnityCtx {
                             ctx: &ctx,
                             config: &config,
@@ -123,29 +104,12 @@ pub async fn pipeline(
                             mesh_models: &mesh_models,
                             guid_lookup: &guid_lookup,
                         },
+                        &file,
                         &prefab,
-                        &out_model_url.into(),
+                        roots,
                     )
                     .await?;
-
-                    models::apply(&config, &ctx, &mut asset_crate, &out_model_path).await?;
-
-                    let model_crate_url =
-                        ctx.write_model_crate(&asset_crate, &out_model_path).await;
-                    res.push(OutAsset {
-                        id: asset_id_from_url(&file),
-                        type_: AssetType::Prefab,
-                        hidden: false,
-                        name: file.decoded_path().file_name().unwrap().to_string(),
-                        tags: Default::default(),
-                        categories: Default::default(),
-                        preview: OutAssetPreview::FromModel {
-                            url: model_crate_url.model().abs().unwrap(),
-                        },
-                        content: OutAssetContent::Content(model_crate_url.prefab().abs().unwrap()),
-                        source: Some(file.clone()),
-                    });
-                    Ok(res)
+                    Ok(vec![asset])
                 }
             },
         )
@@ -216,6 +180,121 @@ pub async fn pipeline(
     }
 }
 
+pub async fn scene_pipeline(ctx: &PipelineCtx, config: ModelsPipeline) -> Vec<OutAsset> {
+    let guid_lookup = Arc::new(create_guid_lookup(ctx).await);
+    let materials = Arc::new(Mutex::new(UnityMaterials {
+        materials: Default::default(),
+        ctx: ctx.clone(),
+    }));
+    let mesh_models = Arc::new(Mutex::new(MeshModels {
+        models: Default::default(),
+        force_assimp: config.force_assimp,
+    }));
+
+    ctx.process_files(
+        |file| file.extension() == Some("unity".to_string()),
+        move |ctx, file| {
+            let config = config.clone();
+            let materials = materials.clone();
+            let mesh_models = mesh_models.clone();
+            let guid_lookup = guid_lookup.clone();
+            async move {
+                let scene = UnityScene::from_yaml(download_unity_yaml(ctx.assets(), &file).await?)
+                    .with_context(|| format!("Failed to parse scene {file}"))?;
+
+                let asset = unity_file_to_asset(
+                    UnityCtx {
+                        ctx: &ctx,
+                        config: &config,
+                        materials_lookup: &materials,
+                        mesh_models: &mesh_models,
+                        guid_lookup: &guid_lookup,
+                    },
+                    &file,
+                    &scene.0,
+                    scene.get_root_nodes(),
+                )
+                .await?;
+                Ok(vec![asset])
+            }
+        },
+    )
+    .await
+}
+
+/// Maps the guids in the .meta files to the files they describe
+async fn create_guid_lookup(ctx: &PipelineCtx) -> HashMap<String, AbsAssetUrl> {
+    join_all(
+        ctx.files
+            .0
+            .iter()
+            .cloned()
+            .filter_map(|file| {
+                if let Some(base_path) = file.decoded_path().as_str().strip_suffix(".meta") {
+                    let base_path = base_path.to_string();
+                    let mut base_file = file.clone();
+                    base_file.set_path(base_path);
+                    if let Ok(base_file) = ctx.get_downloadable_url(&base_file).cloned() {
+                        Some(async move {
+                            let docs = download_unity_yaml(ctx.assets(), &file).await?;
+                            Ok((
+                                docs[0]["guid"]
+                                    .as_str()
+                                    .unwrap_or_else(|| panic!("Failed to find GUID in {file}"))
+                                    .to_string(),
+                                base_file,
+                            ))
+                        })
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                }
+            })
+            .collect_vec(),
+    )
+    .await
+    .into_iter()
+    .collect::<anyhow::Result<HashMap<_, _>>>()
+    .unwrap()
+}
+
+/// Converts a Unity prefab or scene (which share the same object model) to a model crate and writes it out
+async fn unity_file_to_asset(
+    ctx: UnityCtx<'_>,
+    file: &AbsAssetUrl,
+    prefab: &unity_parser::prefab::PrefabFile,
+    roots: Vec<SceneNode<'_>>,
+) -> anyhow::Result<OutAsset> {
+    let out_model_path = ctx.ctx.in_root().relative_path(file.decoded_path());
+    let out_model_url = ctx.ctx.out_root().push(&out_model_path)?.as_directory();
+
+    let (mut asset_crate, colliders) =
+        model_from_unity_nodes(ctx, prefab, roots, &out_model_url.into()).await?;
+
+    models::apply(ctx.config, ctx.ctx, &mut asset_crate, &out_model_path).await?;
+    create_unity_colliders(ctx.ctx, &mut asset_crate, colliders);
+
+    let model_crate_url = ctx
+        .ctx
+        .write_model_crate(&asset_crate, &out_model_path)
+        .await;
+    Ok(OutAsset {
+        id: asset_id_from_url(file),
+        type_: AssetType::Prefab,
+        hidden: false,
+        name: file.decoded_path().file_name().unwrap().to_string(),
+        tags: Default::default(),
+        categories: Default::default(),
+        preview: OutAssetPreview::FromModel {
+            url: model_crate_url.model().abs().unwrap(),
+        },
+        content: OutAssetContent::Content(model_crate_url.prefab().abs().unwrap()),
+        source: Some(file.clone()),
+    })
+}
+
 async fn download_unity_yaml(assets: &AssetCache, url: &AbsAssetUrl) -> anyhow::Result<Vec<Yaml>> {
     let data = url.download_string(assets).await?;
     parse_unity_yaml(&data)
@@ -410,32 +489,49 @@ impl UnityMaterials {
     }
 }
 
-async fn model_from_prefab(
+/// The model crate that a Unity prefab or scene is converted to
+struct UnityModel {
+    model_crate: parking_lot::Mutex<ModelCrate>,
+    /// Colliders with their Unity world transforms. They are cooked once the model is finalized
+    colliders: parking_lot::Mutex<Vec<(Mat4, UnityCollider)>>,
+    out_model_url: TypedAssetUrl<ModelCrateAssetType>,
+}
+
+enum UnityCollider {
+    /// A box, sphere or capsule, as the points to build a convex hull from
+    Hull(Vec<Vec3>),
+    /// Meshes in the model crate
+    Meshes { mesh_ids: Vec<String>, convex: bool },
+}
+
+async fn model_from_unity_nodes(
     ctx: UnityCtx<'_>,
     prefab_file: &unity_parser::prefab::PrefabFile,
+    roots: Vec<SceneNode<'_>>,
     out_model_url: &TypedAssetUrl<ModelCrateAssetType>,
-) -> anyhow::Result<ModelCrate> {
+) -> anyhow::Result<(ModelCrate, Vec<(Mat4, UnityCollider)>)> {
     // std::fs::write("tmp/unity.yml", prefab_file.dump());
-    let root_game_objects = prefab_file.get_root_game_objects();
-    let model_crate = parking_lot::Mutex::new(ModelCrate::new());
-    model_crate.lock().models.insert(
+    let out = UnityModel {
+        model_crate: parking_lot::Mutex::new(ModelCrate::new()),
+        colliders: Default::default(),
+        out_model_url: out_model_url.clone(),
+    };
+    out.model_crate.lock().models.insert(
         ModelCrate::MAIN,
         Model(World::new("model", ambient_ecs::WorldContext::Prefab)),
     );
-    let roots = join_all(root_game_objects.into_iter().map(|root_game_object| {
-        recursively_create_game_objects(
-            ctx,
-            prefab_file,
-            root_game_object,
-            None,
-            &model_crate,
-            out_model_url,
-        )
-    }))
+    let roots = join_all(
+        roots
+            .into_iter()
+            .map(|root| create_unity_node(ctx, prefab_file, root, None, Mat4::IDENTITY, &out)),
+    )
     .await
     .into_iter()
-    .collect::<anyhow::Result<Vec<_>>>()?;
-    let mut model_crate = model_crate.into_inner();
+    .collect::<anyhow::Result<Vec<_>>>()?
+    .into_iter()
+    .flatten()
+    .collect_vec();
+    let mut model_crate = out.model_crate.into_inner();
     model_crate
         .model_world_mut()
         .add_resource(children(), roots);
@@ -444,7 +540,117 @@ async fn model_from_prefab(
         .transform(Mat4::from_cols(Vec4::Y, Vec4::Z, Vec4::X, Vec4::W));
 
     model_crate.create_prefab_from_model();
-    Ok(model_crate)
+    Ok((model_crate, out.colliders.into_inner()))
+}
+
+/// Creates a game object, or the objects of a prefab instance with its overrides applied.
+/// Returns None for inactive objects
+#[async_recursion]
+async fn create_unity_node<'a: 'async_recursion>(
+    ctx: UnityCtx<'a>,
+    prefab: &unity_parser::prefab::PrefabFile,
+    node: SceneNode<'async_recursion>,
+    parent_id: Option<EntityId>,
+    parent_transform: Mat4,
+    out: &UnityModel,
+) -> anyhow::Result<Option<EntityId>> {
+    match node {
+        SceneNode::GameObject(object) => {
+            if !object.is_active {
+                return Ok(None);
+            }
+            Ok(Some(
+                recursively_create_game_objects(
+                    ctx,
+                    prefab,
+                    object,
+                    parent_id,
+                    parent_transform,
+                    out,
+                )
+                .await?,
+            ))
+        }
+        SceneNode::PrefabInstance { id, instance } => {
+            let guid = instance
+                .source_prefab
+                .guid
+                .as_ref()
+                .context("Prefab instance without a source prefab")?;
+            let source_url = ctx
+                .guid_lookup
+                .get(guid)
+                .with_context(|| format!("Failed to find prefab with guid: {guid}"))?;
+            let mut source = unity_parser::prefab::PrefabFile::from_yaml(
+                download_unity_yaml(ctx.ctx.assets(), source_url).await?,
+            )
+            .with_context(|| format!("Failed to parse prefab {source_url}"))?;
+            source.apply_instance(instance);
+
+            // The root of a prefab variant is itself a prefab instance
+            let Some(root) = source.get_root_nodes().into_iter().next() else {
+                return Ok(None);
+            };
+            let Some(root_id) =
+                create_unity_node(ctx, &source, root, parent_id, parent_transform, out).await?
+            else {
+                return Ok(None);
+            };
+
+            // Objects can be added to an instance without changing the source prefab. We only
+            // support adding them to the root of the instance for now
+            let Some((root_source_id, root_transform)) =
+                source.objects.iter().find_map(|(obj_id, obj)| match obj {
+                    PrefabObject::Transform(trans) if trans.father.file_id == 0 => {
+                        Some((*obj_id, trans))
+                    }
+                    _ => None,
+                })
+            else {
+                return Ok(Some(root_id));
+            };
+            let root_world = parent_transform * local_transform(root_transform);
+            let added = join_all(
+                prefab
+                    .get_added_game_objects(id, root_source_id)
+                    .into_iter()
+                    .map(|obj| {
+                        create_unity_node(
+                            ctx,
+                            prefab,
+                            SceneNode::GameObject(obj),
+                            Some(root_id),
+                            root_world,
+                            out,
+                        )
+                    }),
+            )
+            .await
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect_vec();
+            if !added.is_empty() {
+                let mut model_crate = out.model_crate.lock();
+                let world = model_crate.model_world_mut();
+                if let Ok(childs) = world.get_mut(root_id, children()) {
+                    childs.extend(added);
+                } else {
+                    world.add_component(root_id, children(), added).unwrap();
+                }
+            }
+            Ok(Some(root_id))
+        }
+    }
+}
+
+fn local_transform(transform: &unity_parser::prefab::Transform) -> Mat4 {
+    Mat4::from_scale_rotation_translation(
+        transform.local_scale,
+        transform.local_rotation,
+        transform.local_position,
+    )
 }
 
 #[async_recursion]
@@ -453,66 +659,60 @@ async fn recursively_create_game_objects<'a: 'async_recursion>(
     prefab: &unity_parser::prefab::PrefabFile,
     object: &unity_parser::prefab::GameObject,
     parent_id: Option<EntityId>,
-    model_crate: &parking_lot::Mutex<ModelCrate>,
-    out_model_url: &TypedAssetUrl<ModelCrateAssetType>,
+    parent_transform: Mat4,
+    out: &UnityModel,
 ) -> anyhow::Result<EntityId> {
-    let go_transform = object
-        .get_component::<unity_parser::prefab::Transform>(prefab)
+    let transform = object.get_component::<unity_parser::prefab::Transform>(prefab);
+    let go_transform = transform
         .map(|t| t.absolute_transform(prefab))
         .unwrap_or_default();
+    let world_transform = parent_transform * transform.map(local_transform).unwrap_or_default();
     let mut has_lod_group = false;
-    let mut node =
-        if let Some(lod_group) = object.get_component::<unity_parser::prefab::LODGroup>(prefab) {
-            has_lod_group = true;
-            let mut model_lods = Vec::<PbrRenderPrimitiveFromUrl>::new();
-            let mut cutoffs = Vec::new();
-            for (lod_i, lod) in lod_group.lods.iter().enumerate() {
-                let mesh_renderer = lod.get_renderer(prefab).unwrap();
-
-                model_lods.extend(
-                    primitives_from_unity_mesh_renderer(
-                        ctx,
-                        prefab,
-                        mesh_renderer,
-                        model_crate,
-                        go_transform,
-                        lod_i,
-                        out_model_url,
-                    )
-                    .await?
-                    .into_iter(),
-                );
-                cutoffs.push(lod.screen_relative_height);
-            }
+    let mut primitives = Vec::new();
+    let mut node = if let Some(lod_group) =
+        object.get_component::<unity_parser::prefab::LODGroup>(prefab)
+    {
+        has_lod_group = true;
+        let mut cutoffs = Vec::new();
+        for (lod_i, lod) in lod_group.lods.iter().enumerate() {
+            let mesh_renderer = lod.get_renderer(prefab).unwrap();
 
-            Entity::new()
-                .with(lod_cutoffs(), LodCutoffs::new(&cutoffs))
-                .with(gpu_lod(), ())
-                .with(pbr_renderer_primitives_from_url(), model_lods)
-        } else if let Some(mesh_renderer) =
-            object.get_component::<unity_parser::prefab::MeshRenderer>(prefab)
-        {
-            let primitives = primitives_from_unity_mesh_renderer(
-                ctx,
-                prefab,
-                mesh_renderer,
-                model_crate,
-                go_transform,
-                0,
-                out_model_url,
-            )
-            .await?;
-            Entity::new()
-                .with(lod_cutoffs(), Default::default())
-                .with(gpu_lod(), ())
-                .with(pbr_renderer_primitives_from_url(), primitives)
-        } else {
-            Entity::new()
-        };
+            primitives.extend(
+                primitives_from_unity_mesh_renderer(
+                    ctx,
+                    prefab,
+                    mesh_renderer,
+                    out,
+                    go_transform,
+                    lod_i,
+                )
+                .await?
+                .into_iter(),
+            );
+            cutoffs.push(lod.screen_relative_height);
+        }
+
+        Entity::new()
+            .with(lod_cutoffs(), LodCutoffs::new(&cutoffs))
+            .with(gpu_lod(), ())
+            .with(pbr_renderer_primitives_from_url(), primitives.clone())
+    } else if let Some(mesh_renderer) =
+        object.get_component::<unity_parser::prefab::MeshRenderer>(prefab)
+    {
+        primitives =
+            primitives_from_unity_mesh_renderer(ctx, prefab, mesh_renderer, out, go_transform, 0)
+                .await?;
+        Entity::new()
+            .with(lod_cutoffs(), Default::default())
+            .with(gpu_lod(), ())
+            .with(pbr_renderer_primitives_from_url(), primitives.clone())
+    } else {
+        Entity::new()
+    };
 
     node.set(name(), object.name.clone());
 
-    if let Some(transform) = object.get_component::<unity_parser::prefab::Transform>(prefab) {
+    if let Some(transform) = transform {
         node.set(scale(), transform.local_scale);
         node.set(rotation(), transform.local_rotation);
         node.set(translation(), transform.local_position);
@@ -520,45 +720,211 @@ async fn recursively_create_game_objects<'a: 'async_recursion>(
     if let Some(parent_id) = parent_id {
         node.set(parent(), parent_id);
     }
-    let id = node.spawn(model_crate.lock().model_world_mut());
+    let id = node.spawn(out.model_crate.lock().model_world_mut());
+
+    for collider in object.get_components::<unity_parser::scene::Collider>(prefab) {
+        if !collider.enabled || collider.is_trigger {
+            continue;
+        }
+        let collider = match &collider.shape {
+            ColliderShape::Box { size, center } => UnityCollider::Hull(box_points(*size, *center)),
+            ColliderShape::Sphere { radius, center } => {
+                UnityCollider::Hull(capsule_points(*radius, 0., 1, *center))
+            }
+            ColliderShape::Capsule {
+                radius,
+                height,
+                direction,
+                center,
+            } => UnityCollider::Hull(capsule_points(*radius, *height, *direction, *center)),
+            ColliderShape::Mesh { mesh, convex } => {
+                let same_mesh = object
+                    .get_component::<unity_parser::prefab::MeshFilter>(prefab)
+                    .map_or(true, |filter| {
+                        filter.mesh.guid == mesh.guid && filter.mesh.file_id == mesh.file_id
+                    });
+                if !same_mesh {
+                    tracing::warn!(
+                        "Skipping MeshCollider on {}; only colliders using the rendered mesh are supported",
+                        object.name
+                    );
+                    continue;
+                }
+                let model_crate = out.model_crate.lock();
+                UnityCollider::Meshes {
+                    mesh_ids: primitives
+                        .iter()
+                        .filter(|prim| prim.lod == 0)
+                        .filter_map(|prim| model_crate.meshes.loc.id_from_path(prim.mesh.path()))
+                        .collect(),
+                    convex: *convex,
+                }
+            }
+        };
+        out.colliders.lock().push((world_transform, collider));
+    }
+
+    let mut childs = Vec::new();
+    for light in object.get_components::<unity_parser::scene::Light>(prefab) {
+        if !light.enabled {
+            continue;
+        }
+        if light.type_ != LightType::Directional {
+            tracing::warn!(
+                "Skipping {:?} light on {}; only directional lights are supported",
+                light.type_,
+                object.name
+            );
+            continue;
+        }
+        // The sun shines from its X axis, while Unity lights shine along their Z axis
+        childs.push(
+            Entity::new()
+                .with(name(), format!("{} (sun)", object.name))
+                .with(parent(), id)
+                .with(translation(), Vec3::ZERO)
+                .with(rotation(), Quat::from_rotation_y(FRAC_PI_2))
+                .with(scale(), Vec3::ONE)
+                .with(main_scene(), ())
+                .with(sun(), 0.)
+                .with(light_diffuse(), light.color * light.intensity)
+                .spawn(out.model_crate.lock().model_world_mut()),
+        );
+    }
+
     if !has_lod_group {
-        if let Some(transform) = object.get_component::<unity_parser::prefab::Transform>(prefab) {
-            let childs = join_all(transform.children.iter().map(|c| async move {
-                if let Some(PrefabObject::Transform(trans)) = prefab.objects.get(&c.file_id) {
-                    if let Some(PrefabObject::GameObject(obj)) =
-                        prefab.objects.get(&trans.game_object.file_id)
-                    {
-                        return Ok(Some(
-                            recursively_create_game_objects(
-                                ctx,
-                                prefab,
-                                obj,
-                                Some(id),
-                                model_crate,
-                                out_model_url,
-                            )
-                            .await?,
-                        ));
+        if let Some(transform) = transform {
+            childs.extend(
+                join_all(
+                    prefab
+                        .get_child_nodes(transform)
+                        .into_iter()
+                        .map(|c| create_unity_node(ctx, prefab, c, Some(id), world_transform, out)),
+                )
+                .await
+                .into_iter()
+                .collect::<anyhow::Result<Vec<_>>>()?
+                .into_iter()
+                .flatten(),
+            );
+        }
+    }
+    if !childs.is_empty() {
+        out.model_crate
+            .lock()
+            .model_world_mut()
+            .add_component(id, children(), childs)
+            .unwrap();
+    }
+    Ok(id)
+}
+
+/// Cooks the colliders of a Unity prefab or scene, unless the pipeline already created one
+fn create_unity_colliders(
+    ctx: &PipelineCtx,
+    model_crate: &mut ModelCrate,
+    colliders: Vec<(Mat4, UnityCollider)>,
+) {
+    let prefab = model_crate.prefab_world();
+    if colliders.is_empty() || prefab.has_component(prefab.resource(children())[0], collider()) {
+        return;
+    }
+    let root_transform = model_crate.model().get_transform().unwrap_or_default();
+    let mut convex = Vec::new();
+    let mut concave = Vec::new();
+    for (i, (transform, collider)) in colliders.into_iter().enumerate() {
+        let transform = root_transform * transform;
+        match collider {
+            UnityCollider::Hull(points) => {
+                if let Some(path) =
+                    model_crate.cook_convex_mesh(ctx.assets(), format!("unity_{i}"), points, None)
+                {
+                    let geometry = PhysxGeometryFromUrl(dotdot_path(path).into());
+                    convex.push((transform, geometry.clone()));
+                    concave.push((transform, geometry));
+                }
+            }
+            UnityCollider::Meshes {
+                mesh_ids,
+                convex: is_convex,
+            } => {
+                let (scale, rot, pos) = transform.to_scale_rotation_translation();
+                for mesh_id in mesh_ids {
+                    // Physx doesn't support negative scaling on convex meshes, so the mirroring is
+                    // baked into the hull
+                    let points = model_crate.meshes.content[&mesh_id]
+                        .positions()
+                        .iter()
+                        .map(|&p| p * scale.signum())
+                        .collect_vec();
+                    let Some(hull) = model_crate.cook_convex_mesh(
+                        ctx.assets(),
+                        format!("unity_{i}_{mesh_id}"),
+                        points,
+                        None,
+                    ) else {
+                        continue;
+                    };
+                    let hull = (
+                        Mat4::from_scale_rotation_translation(scale.abs(), rot, pos),
+                        PhysxGeometryFromUrl(dotdot_path(hull).into()),
+                    );
+                    if is_convex {
+                        concave.push(hull.clone());
+                    } else if model_crate.cook_triangle_mesh(ctx.assets(), &mesh_id, false, true) {
+                        let path = dotdot_path(model_crate.px_triangle_meshes.loc.path(&mesh_id));
+                        concave.push((transform, PhysxGeometryFromUrl(path.into())));
                     }
+                    convex.push(hull);
                 }
-                Ok(None)
-            }))
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<Vec<_>>>()?
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
-            if !childs.is_empty() {
-                model_crate
-                    .lock()
-                    .model_world_mut()
-                    .add_component(id, children(), childs)
-                    .unwrap();
             }
         }
     }
-    Ok(id)
+    model_crate.set_collider(ColliderFromUrls { convex, concave });
+}
+
+fn box_points(size: Vec3, center: Vec3) -> Vec<Vec3> {
+    (0..8)
+        .map(|i| {
+            let corner = vec3(
+                if i & 1 == 0 { -0.5 } else { 0.5 },
+                if i & 2 == 0 { -0.5 } else { 0.5 },
+                if i & 4 == 0 { -0.5 } else { 0.5 },
+            );
+            center + corner * size
+        })
+        .collect()
+}
+
+/// Points on a capsule along the `direction` axis (0 = X, 1 = Y, 2 = Z). A height of zero gives a sphere
+fn capsule_points(radius: f32, height: f32, direction: usize, center: Vec3) -> Vec<Vec3> {
+    const RINGS: usize = 8;
+    const SEGMENTS: usize = 12;
+    let axis = Vec3::AXES[direction.min(2)];
+    let rotation = Quat::from_rotation_arc(Vec3::Z, axis);
+    let half = (height / 2. - radius).max(0.);
+    let mut points = Vec::new();
+    for ring in 0..=RINGS {
+        let theta = PI * ring as f32 / RINGS as f32;
+        let z = theta.cos();
+        let ring_radius = theta.sin();
+        let segments = if ring == 0 || ring == RINGS {
+            1
+        } else {
+            SEGMENTS
+        };
+        for segment in 0..segments {
+            let phi = 2. * PI * segment as f32 / segments as f32;
+            let p = vec3(ring_radius * phi.cos(), ring_radius * phi.sin(), z) * radius;
+            if z >= 0. {
+                points.push(center + rotation * (p + Vec3::Z * half));
+            }
+            if z <= 0. {
+                points.push(center + rotation * (p - Vec3::Z * half));
+            }
+        }
+    }
+    points
 }
 
 struct MeshModels {
@@ -595,11 +961,11 @@ async fn primitives_from_unity_mesh_renderer(
     ctx: UnityCtx<'_>,
     prefab: &unity_parser::prefab::PrefabFile,
     mesh_renderer: &unity_parser::prefab::MeshRenderer,
-    model_crate: &parking_lot::Mutex<ModelCrate>,
+    out: &UnityModel,
     go_transform: Mat4,
     lod_i: usize,
-    out_model_url: &TypedAssetUrl<ModelCrateAssetType>,
 ) -> anyhow::Result<Vec<PbrRenderPrimitiveFromUrl>> {
+    let out_model_url = &out.out_model_url;
     let game_object = mesh_renderer.get_game_object(prefab).unwrap();
     let mesh_filter = game_object
         .get_component::<unity_parser::prefab::MeshFilter>(prefab)
@@ -632,7 +998,7 @@ async fn primitives_from_unity_mesh_renderer(
             .unwrap();
         mat = mat.relative_path_from(&out_model_url.abs().unwrap().push("materials").unwrap());
         mesh = mesh.transformed(Mat4::from_cols(-Vec4::X, Vec4::Z, -Vec4::Y, Vec4::W));
-        let mut model_crate = model_crate.lock();
+        let mut model_crate = out.model_crate.lock();
         Ok(vec![PbrRenderPrimitiveFromUrl {
             mesh: dotdot_path(model_crate.meshes.insert(mesh_guid.clone(), mesh).path).into(),
             material: Some(
@@ -688,7 +1054,7 @@ async fn primitives_from_unity_mesh_renderer(
             // below
             mesh = mesh.transformed(go_transform.inverse() * file_scale * transform);
             mesh = mesh.winding_flipped();
-            let mut model_crate = model_crate.lock();
+            let mut model_crate = out.model_crate.lock();
             res.push(PbrRenderPrimitiveFromUrl {
                 mesh: dotdot_path(
                     model_crate
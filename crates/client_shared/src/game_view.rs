@@ -14,8 +14,7 @@ use ambient_layout::Docking;
 use ambient_network::client::{ClientState, GameClientRenderTarget, GameClientWorld};
 use ambient_shared_types::CursorIcon;
 use ambient_ui_native::{
-    cb, docking, padding, width, Borders, Button, Dock, MeasureSize, ScrollArea, ScrollAreaSizing,
-    UIExt, STREET,
+    cb, docking, padding, width, Borders, Button, Dock, MeasureSize, UIExt, STREET,
 };
 use glam::{uvec2, vec4, Vec2};
 
@@ -28,6 +27,7 @@ pub fn GameView(hooks: &mut Hooks, show_debug: bool) -> Element {
 
     let (show_ecs, set_show_ecs) = use_state(hooks, true);
     let (ecs_size, set_ecs_size) = use_state(hooks, Vec2::ZERO);
+    let (ecs_toggle_size, set_ecs_toggle_size) = use_state(hooks, Vec2::ZERO);
     let (debugger_size, set_debugger_size) = use_state(hooks, Vec2::ZERO);
 
     let (w, set_w) = use_state(hooks, 300.0);
@@ -102,32 +102,35 @@ pub fn GameView(hooks: &mut Hooks, show_debug: bool) -> Element {
         if ECS_DEBUGGER && show_debug {
             MeasureSize::el(
                 Dock::el([
-                    Button::new(if show_ecs { "\u{f137}" } else { "\u{f138}" }, move |_| {
-                        set_show_ecs(!show_ecs)
-                    })
-                    .style(ambient_ui_native::ButtonStyle::Flat)
-                    .toggled(show_ecs)
-                    .el(),
+                    MeasureSize::el(
+                        Button::new(if show_ecs { "\u{f137}" } else { "\u{f138}" }, move |_| {
+                            set_show_ecs(!show_ecs)
+                        })
+                        .style(ambient_ui_native::ButtonStyle::Flat)
+                        .toggled(show_ecs)
+                        .el(),
+                        set_ecs_toggle_size,
+                    ),
                     if show_ecs {
+                        // The panel is padded, and the toggle is above the editor
+                        let ecs_height = (ecs_size.y - ecs_toggle_size.y - 2. * STREET).max(0.);
                         if w_memory != 0.0 {
                             set_w(w_memory)
                         } else {
                             set_w(300.0)
                         };
-                        ScrollArea::el(
-                            ScrollAreaSizing::FitParentWidth,
-                            ECSEditor {
-                                world: Arc::new(InspectableAsyncWorld(cb({
-                                    let client_state = client_state.clone();
-                                    move |res| {
-                                        let client_state = client_state.game_state.lock();
-                                        res(&client_state.world)
-                                    }
-                                }))),
-                            }
-                            .el()
-                            .memoize_subtree(client_state.uid),
-                        )
+                        ECSEditor {
+                            world: Arc::new(InspectableAsyncWorld(cb({
+                                let client_state = client_state.clone();
+                                move |res| {
+                                    let client_state = client_state.game_state.lock();
+                                    res(&client_state.world)
+                                }
+                            }))),
+                            height: ecs_height,
+                        }
+                        .el()
+                        .memoize_subtree(format!("{}-{ecs_height}", client_state.uid))
                     } else {
                         set_w(0.0);
                         Element::new()
@@ -39,8 +39,19 @@ pub async fn rpc_dump_world_hierarchy(args: ServerRpcArgs, _: ()) -> Option<Stri
     Some(String::from_utf8(res).unwrap())
 }
 
+/// The server world as JSON, which `ambient assets export --world` can export to glTF
+#[allow(clippy::let_unit_value)]
+pub async fn rpc_dump_world(args: ServerRpcArgs, _: ()) -> Option<String> {
+    let state = args.state.lock();
+    let world = state.get_player_world(&args.user_id)?;
+    serde_json::to_string(world)
+        .map_err(|err| tracing::error!("Failed to serialize the server world: {err:?}"))
+        .ok()
+}
+
 pub fn register_server_rpcs(reg: &mut RpcRegistry<ServerRpcArgs>) {
     reg.register(rpc_dump_world_hierarchy);
+    reg.register(rpc_dump_world);
 }
 
 fn dump_to_user(_assets: &AssetCache, _label: &'static str, s: String) {
@@ -187,6 +198,9 @@ pub fn Debugger(hooks: &mut Hooks, get_state: GetDebuggerState) -> Element {
                                 {
                                     dump_to_user(&assets, "server_hierarchy.yml", res);
                                 }
+                                if let Ok(Some(res)) = client_state.rpc(rpc_dump_world, ()).await {
+                                    dump_to_user(&assets, "server_world.json", res);
+                                }
                             });
                         }
                     })
@@ -77,6 +77,7 @@ fn decal(out: ptr<function, Decal>, in: VertexOutput) -> bool {
     var decal: Decal;
     decal.material.position = in.position;
     decal.material.texcoord = texcoord;
+    decal.material.color = vec4<f32>(1.0);
     decal.material.world_position = in.world_position.xyz;
     decal.material.normal = normal_matrix * vec3<f32>(0.0, 0.0, 1.0);
     decal.material.normal_matrix = normal_matrix;
@@ -126,7 +126,7 @@ mod raw {
                 };
                 use glam::{Mat4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
                 use std::time::Duration;
-                components ! ("input" , { # [doc = "**Mouse over entity**: The entity the mouse is currently over.\n\n*Attributes*: Debuggable, Resource"] @ [Debuggable , Resource , Name ["Mouse over entity"] , Description ["The entity the mouse is currently over."]] mouse_over_entity : EntityId , # [doc = "**Mouse over distance**: This distance to the entity that the mouse is currently over.\n\n*Attributes*: Debuggable, Resource"] @ [Debuggable , Resource , Name ["Mouse over distance"] , Description ["This distance to the entity that the mouse is currently over."]] mouse_over_distance : f32 , # [doc = "**Mouse over**: The number of mouse cursors that are currently over this entity.\n\n*Attributes*: Debuggable"] @ [Debuggable , Name ["Mouse over"] , Description ["The number of mouse cursors that are currently over this entity."]] is_mouse_over : u32 , # [doc = "**Mouse pickable max**: This entity can be clicked by the mouse, and this component defines the max AABB bound of the click area.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Mouse pickable max"] , Description ["This entity can be clicked by the mouse, and this component defines the max AABB bound of the click area."]] mouse_pickable_max : Vec3 , # [doc = "**Mouse pickable min**: This entity can be clicked by the mouse, and this component defines the min AABB bound of the click area.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Mouse pickable min"] , Description ["This entity can be clicked by the mouse, and this component defines the min AABB bound of the click area."]] mouse_pickable_min : Vec3 , });
+                components ! ("input" , { # [doc = "**Mouse over entity**: The entity the mouse is currently over.\n\n*Attributes*: Debuggable, Resource"] @ [Debuggable , Resource , Name ["Mouse over entity"] , Description ["The entity the mouse is currently over."]] mouse_over_entity : EntityId , # [doc = "**Mouse over distance**: This distance to the entity that the mouse is currently over.\n\n*Attributes*: Debuggable, Resource"] @ [Debuggable , Resource , Name ["Mouse over distance"] , Description ["This distance to the entity that the mouse is currently over."]] mouse_over_distance : f32 , # [doc = "**Mouse over**: The number of mouse cursors that are currently over this entity.\n\n*Attributes*: Debuggable"] @ [Debuggable , Name ["Mouse over"] , Description ["The number of mouse cursors that are currently over this entity."]] is_mouse_over : u32 , # [doc = "**Mouse pickable max**: This entity can be clicked by the mouse, and this component defines the max AABB bound of the click area.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Mouse pickable max"] , Description ["This entity can be clicked by the mouse, and this component defines the max AABB bound of the click area."]] mouse_pickable_max : Vec3 , # [doc = "**Mouse pickable min**: This entity can be clicked by the mouse, and this component defines the min AABB bound of the click area.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Mouse pickable min"] , Description ["This entity can be clicked by the mouse, and this component defines the min AABB bound of the click area."]] mouse_pickable_min : Vec3 , # [doc = "**Input action**: The name of the input action this entity represents. Actions are declared in the `[actions]` section of a package manifest.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Input action"] , Description ["The name of the input action this entity represents. Actions are declared in the `[actions]` section of a package manifest."]] input_action : String , # [doc = "**Input axis**: The name of the input axis this entity represents. Axes are declared in the `[axes]` section of a package manifest.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Input axis"] , Description ["The name of the input axis this entity represents. Axes are declared in the `[axes]` section of a package manifest."]] input_axis : String , # [doc = "**Input bindings**: The inputs that trigger this action, or that move this axis in the positive direction (e.g. `Space`, `Mouse.Left`, `Gamepad.South`).\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Input bindings"] , Description ["The inputs that trigger this action, or that move this axis in the positive direction (e.g. `Space`, `Mouse.Left`, `Gamepad.South`)."]] input_bindings : Vec :: < String > , # [doc = "**Input negative bindings**: The inputs that move this axis in the negative direction.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Input negative bindings"] , Description ["The inputs that move this axis in the negative direction."]] input_negative_bindings : Vec :: < String > , # [doc = "**Input value**: The current value of this action or axis. Actions are 1.0 when triggered and 0.0 otherwise, and axes are in the range -1.0 to 1.0. Only updated on the client.\n\n*Attributes*: Debuggable"] @ [Debuggable , Name ["Input value"] , Description ["The current value of this action or axis. Actions are 1.0 when triggered and 0.0 otherwise, and axes are in the range -1.0 to 1.0. Only updated on the client."]] input_value : f32 , # [doc = "**Input previous value**: The value of this action or axis prior to the most recent update. Only updated on the client.\n\n*Attributes*: Debuggable"] @ [Debuggable , Name ["Input previous value"] , Description ["The value of this action or axis prior to the most recent update. Only updated on the client."]] input_previous_value : f32 , });
             }
             #[doc = r" Auto-generated message definitions. Messages are used to communicate with the runtime, the other side of the network,"]
             #[doc = r" and with other modules."]
@@ -206,6 +206,47 @@ mod raw {
                     }
                 }
                 impl RuntimeMessage for ClipboardGet {}
+                #[derive(Clone, Debug)]
+                #[doc = "**InputRebind**: Changes the bindings of an input action or axis belonging to the sending package. The new bindings are persisted to the player's settings. Empty bindings restore the defaults from the manifest."]
+                pub struct InputRebind {
+                    pub name: String,
+                    pub bindings: Vec<String>,
+                    pub negative_bindings: Vec<String>,
+                }
+                impl InputRebind {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(
+                        name: impl Into<String>,
+                        bindings: impl Into<Vec<String>>,
+                        negative_bindings: impl Into<Vec<String>>,
+                    ) -> Self {
+                        Self {
+                            name: name.into(),
+                            bindings: bindings.into(),
+                            negative_bindings: negative_bindings.into(),
+                        }
+                    }
+                }
+                impl Message for InputRebind {
+                    fn id() -> &'static str {
+                        "ambient_core::input::InputRebind"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.name.serialize_message_part(&mut output)?;
+                        self.bindings.serialize_message_part(&mut output)?;
+                        self.negative_bindings.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            name: String::deserialize_message_part(&mut input)?,
+                            bindings: Vec::<String>::deserialize_message_part(&mut input)?,
+                            negative_bindings: Vec::<String>::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for InputRebind {}
             }
         }
         pub mod layout {
@@ -221,7 +262,7 @@ mod raw {
                 };
                 use glam::{Mat4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
                 use std::time::Duration;
-                components ! ("layout" , { # [doc = "**Align horizontal**: Layout alignment: horizontal.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Align horizontal"] , Description ["Layout alignment: horizontal."]] align_horizontal : crate :: generated :: raw :: ambient_core :: layout :: types :: Align , # [doc = "**Align vertical**: Layout alignment: vertical.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Align vertical"] , Description ["Layout alignment: vertical."]] align_vertical : crate :: generated :: raw :: ambient_core :: layout :: types :: Align , # [doc = "**Docking**: Layout docking.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Docking"] , Description ["Layout docking."]] docking : crate :: generated :: raw :: ambient_core :: layout :: types :: Docking , # [doc = "**Fit horizontal**: Layout fit: horizontal.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Fit horizontal"] , Description ["Layout fit: horizontal."]] fit_horizontal : crate :: generated :: raw :: ambient_core :: layout :: types :: Fit , # [doc = "**Fit vertical**: Layout fit: vertical.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Fit vertical"] , Description ["Layout fit: vertical."]] fit_vertical : crate :: generated :: raw :: ambient_core :: layout :: types :: Fit , # [doc = "**Layout**: Layout.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Layout"] , Description ["Layout."]] layout : crate :: generated :: raw :: ambient_core :: layout :: types :: Layout , # [doc = "**Orientation**: Layout orientation.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Orientation"] , Description ["Layout orientation."]] orientation : crate :: generated :: raw :: ambient_core :: layout :: types :: Orientation , # [doc = "**Is book file**: This is a file in a `layout_bookcase`.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Is book file"] , Description ["This is a file in a `layout_bookcase`."]] is_book_file : () , # [doc = "**Margin**: Layout margin: [top, right, bottom, left].\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Margin"] , Description ["Layout margin: [top, right, bottom, left]."]] margin : Vec4 , # [doc = "**Padding**: Layout padding: [top, right, bottom, left].\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Padding"] , Description ["Layout padding: [top, right, bottom, left]."]] padding : Vec4 , # [doc = "**Mesh to local from size**: Update the `mesh_to_local` based on the width and height of this entity.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Mesh to local from size"] , Description ["Update the `mesh_to_local` based on the width and height of this entity."]] mesh_to_local_from_size : () , # [doc = "**Minimum height**: The minimum height of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Minimum height"] , Description ["The minimum height of a UI element."]] min_height : f32 , # [doc = "**Minimum width**: The minimum width of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Minimum width"] , Description ["The minimum width of a UI element."]] min_width : f32 , # [doc = "**Maximum height**: The maximum height of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Maximum height"] , Description ["The maximum height of a UI element."]] max_height : f32 , # [doc = "**Maximum width**: The maximum width of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Maximum width"] , Description ["The maximum width of a UI element."]] max_width : f32 , # [doc = "**Is screen**: This entity will be treated as a screen. Used by the Screen ui component.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Is screen"] , Description ["This entity will be treated as a screen. Used by the Screen ui component."]] is_screen : () , # [doc = "**Space between items**: Space between items in a layout.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Space between items"] , Description ["Space between items in a layout."]] space_between_items : f32 , # [doc = "**Width**: The width of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Width"] , Description ["The width of a UI element."]] width : f32 , # [doc = "**Height**: The height of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Height"] , Description ["The height of a UI element."]] height : f32 , # [doc = "**GPU UI size**: Upload the width and height of this UI element to the GPU.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["GPU UI size"] , Description ["Upload the width and height of this UI element to the GPU."]] gpu_ui_size : Vec4 , });
+                components ! ("layout" , { # [doc = "**Align horizontal**: Layout alignment: horizontal.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Align horizontal"] , Description ["Layout alignment: horizontal."]] align_horizontal : crate :: generated :: raw :: ambient_core :: layout :: types :: Align , # [doc = "**Align vertical**: Layout alignment: vertical.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Align vertical"] , Description ["Layout alignment: vertical."]] align_vertical : crate :: generated :: raw :: ambient_core :: layout :: types :: Align , # [doc = "**Docking**: Layout docking.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Docking"] , Description ["Layout docking."]] docking : crate :: generated :: raw :: ambient_core :: layout :: types :: Docking , # [doc = "**Fit horizontal**: Layout fit: horizontal.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Fit horizontal"] , Description ["Layout fit: horizontal."]] fit_horizontal : crate :: generated :: raw :: ambient_core :: layout :: types :: Fit , # [doc = "**Fit vertical**: Layout fit: vertical.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Fit vertical"] , Description ["Layout fit: vertical."]] fit_vertical : crate :: generated :: raw :: ambient_core :: layout :: types :: Fit , # [doc = "**Layout**: Layout.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Layout"] , Description ["Layout."]] layout : crate :: generated :: raw :: ambient_core :: layout :: types :: Layout , # [doc = "**Orientation**: Layout orientation.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Orientation"] , Description ["Layout orientation."]] orientation : crate :: generated :: raw :: ambient_core :: layout :: types :: Orientation , # [doc = "**Is book file**: This is a file in a `layout_bookcase`.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Is book file"] , Description ["This is a file in a `layout_bookcase`."]] is_book_file : () , # [doc = "**Margin**: Layout margin: [top, right, bottom, left].\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Margin"] , Description ["Layout margin: [top, right, bottom, left]."]] margin : Vec4 , # [doc = "**Padding**: Layout padding: [top, right, bottom, left].\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Padding"] , Description ["Layout padding: [top, right, bottom, left]."]] padding : Vec4 , # [doc = "**Mesh to local from size**: Update the `mesh_to_local` based on the width and height of this entity.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Mesh to local from size"] , Description ["Update the `mesh_to_local` based on the width and height of this entity."]] mesh_to_local_from_size : () , # [doc = "**Minimum height**: The minimum height of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Minimum height"] , Description ["The minimum height of a UI element."]] min_height : f32 , # [doc = "**Minimum width**: The minimum width of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Minimum width"] , Description ["The minimum width of a UI element."]] min_width : f32 , # [doc = "**Maximum height**: The maximum height of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Maximum height"] , Description ["The maximum height of a UI element."]] max_height : f32 , # [doc = "**Maximum width**: The maximum width of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Maximum width"] , Description ["The maximum width of a UI element."]] max_width : f32 , # [doc = "**Is screen**: This entity will be treated as a screen. Used by the Screen ui component.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Is screen"] , Description ["This entity will be treated as a screen. Used by the Screen ui component."]] is_screen : () , # [doc = "**Space between items**: Space between items in a layout.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Space between items"] , Description ["Space between items in a layout."]] space_between_items : f32 , # [doc = "**Width**: The width of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Width"] , Description ["The width of a UI element."]] width : f32 , # [doc = "**Height**: The height of a UI element.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Height"] , Description ["The height of a UI element."]] height : f32 , # [doc = "**GPU UI size**: Upload the width and height of this UI element to the GPU.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["GPU UI size"] , Description ["Upload the width and height of this UI element to the GPU."]] gpu_ui_size : Vec4 , # [doc = "**Gap**: Space between columns (x) and rows (y) in grid and flex layouts. Defaults to `space_between_items` on both axes.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Gap"] , Description ["Space between columns (x) and rows (y) in grid and flex layouts. Defaults to `space_between_items` on both axes."]] gap : Vec2 , # [doc = "**Grid columns**: The column tracks of a grid layout, separated by spaces. A track is a size in pixels (`100`), a fraction of the remaining space (`1fr`) or `auto`; `repeat(3, 1fr)` repeats tracks.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Grid columns"] , Description ["The column tracks of a grid layout, separated by spaces. A track is a size in pixels (`100`), a fraction of the remaining space (`1fr`) or `auto`; `repeat(3, 1fr)` repeats tracks."]] grid_columns : String , # [doc = "**Grid rows**: The row tracks of a grid layout, in the same format as `grid_columns`. Rows beyond the template are sized `auto`.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Grid rows"] , Description ["The row tracks of a grid layout, in the same format as `grid_columns`. Rows beyond the template are sized `auto`."]] grid_rows : String , # [doc = "**Grid column**: The column a grid item starts at. Items without a column or row are placed in the next free cell.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Grid column"] , Description ["The column a grid item starts at. Items without a column or row are placed in the next free cell."]] grid_column : u32 , # [doc = "**Grid row**: The row a grid item starts at. Items without a column or row are placed in the next free cell.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Grid row"] , Description ["The row a grid item starts at. Items without a column or row are placed in the next free cell."]] grid_row : u32 , # [doc = "**Grid column span**: The number of columns a grid item covers. Defaults to 1.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Grid column span"] , Description ["The number of columns a grid item covers. Defaults to 1."]] grid_column_span : u32 , # [doc = "**Grid row span**: The number of rows a grid item covers. Defaults to 1.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Grid row span"] , Description ["The number of rows a grid item covers. Defaults to 1."]] grid_row_span : u32 , # [doc = "**Flex wrap**: Whether the items of a flex layout wrap onto new lines when they do not fit.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Flex wrap"] , Description ["Whether the items of a flex layout wrap onto new lines when they do not fit."]] flex_wrap : bool , # [doc = "**Flex grow**: How much of the free space on its line a flex item takes, relative to its siblings. Defaults to 0.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Flex grow"] , Description ["How much of the free space on its line a flex item takes, relative to its siblings. Defaults to 0."]] flex_grow : f32 , # [doc = "**Flex shrink**: How much a flex item shrinks when its line overflows, relative to its siblings. Defaults to 1.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Flex shrink"] , Description ["How much a flex item shrinks when its line overflows, relative to its siblings. Defaults to 1."]] flex_shrink : f32 , # [doc = "**Flex basis**: The size of a flex item along the main axis before growing or shrinking. Defaults to the size it is given outside of the layout, e.g. by its author or the text system.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Flex basis"] , Description ["The size of a flex item along the main axis before growing or shrinking. Defaults to the size it is given outside of the layout, e.g. by its author or the text system."]] flex_basis : f32 , # [doc = "**Justify content**: How the free space along the main axis of a flex layout is distributed between the items of a line.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Justify content"] , Description ["How the free space along the main axis of a flex layout is distributed between the items of a line."]] justify_content : crate :: generated :: raw :: ambient_core :: layout :: types :: Justify , # [doc = "**Align content**: How the free space along the cross axis of a flex layout is distributed between its lines.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Align content"] , Description ["How the free space along the cross axis of a flex layout is distributed between its lines."]] align_content : crate :: generated :: raw :: ambient_core :: layout :: types :: Justify , });
             }
             #[doc = r" Auto-generated type definitions."]
             pub mod types {
@@ -468,6 +509,86 @@ mod raw {
                     Default,
                 )]
                 #[serde(crate = "self::serde")]
+                #[doc = "**Justify**: How free space is distributed along an axis."]
+                pub enum Justify {
+                    #[default]
+                    #[doc = "Pack at the beginning"]
+                    Begin,
+                    #[doc = "Pack in the center"]
+                    Center,
+                    #[doc = "Pack at the end"]
+                    End,
+                    #[doc = "Evenly between items, with no space at the ends"]
+                    SpaceBetween,
+                    #[doc = "Evenly around items, with half the space at the ends"]
+                    SpaceAround,
+                    #[doc = "Evenly between items and at the ends"]
+                    SpaceEvenly,
+                    #[doc = "Stretch lines to fill the space; only used by `align_content`"]
+                    Stretch,
+                }
+                impl crate::EnumComponent for Justify {
+                    fn to_u32(&self) -> u32 {
+                        match self {
+                            Self::Begin => Justify::Begin as u32,
+                            Self::Center => Justify::Center as u32,
+                            Self::End => Justify::End as u32,
+                            Self::SpaceBetween => Justify::SpaceBetween as u32,
+                            Self::SpaceAround => Justify::SpaceAround as u32,
+                            Self::SpaceEvenly => Justify::SpaceEvenly as u32,
+                            Self::Stretch => Justify::Stretch as u32,
+                        }
+                    }
+                    fn from_u32(value: u32) -> Option<Self> {
+                        if value == Justify::Begin as u32 {
+                            return Some(Self::Begin);
+                        }
+                        if value == Justify::Center as u32 {
+                            return Some(Self::Center);
+                        }
+                        if value == Justify::End as u32 {
+                            return Some(Self::End);
+                        }
+                        if value == Justify::SpaceBetween as u32 {
+                            return Some(Self::SpaceBetween);
+                        }
+                        if value == Justify::SpaceAround as u32 {
+                            return Some(Self::SpaceAround);
+                        }
+                        if value == Justify::SpaceEvenly as u32 {
+                            return Some(Self::SpaceEvenly);
+                        }
+                        if value == Justify::Stretch as u32 {
+                            return Some(Self::Stretch);
+                        }
+                        None
+                    }
+                }
+                impl MessageSerde for Justify {
+                    fn serialize_message_part(
+                        &self,
+                        output: &mut Vec<u8>,
+                    ) -> Result<(), MessageSerdeError> {
+                        crate::EnumComponent::to_u32(self).serialize_message_part(output)
+                    }
+                    fn deserialize_message_part(
+                        input: &mut dyn std::io::Read,
+                    ) -> Result<Self, MessageSerdeError> {
+                        crate::EnumComponent::from_u32(u32::deserialize_message_part(input)?)
+                            .ok_or(MessageSerdeError::InvalidValue)
+                    }
+                }
+                #[derive(
+                    Copy,
+                    Clone,
+                    Debug,
+                    PartialEq,
+                    Eq,
+                    serde :: Serialize,
+                    serde :: Deserialize,
+                    Default,
+                )]
+                #[serde(crate = "self::serde")]
                 #[doc = "**Layout**: The type of the layout to use."]
                 pub enum Layout {
                     #[default]
@@ -479,6 +600,10 @@ mod raw {
                     Bookcase,
                     #[doc = "Width to children."]
                     WidthToChildren,
+                    #[doc = "Grid layout with column and row tracks."]
+                    Grid,
+                    #[doc = "Flex layout with wrapping, growing and shrinking items."]
+                    Flex,
                 }
                 impl crate::EnumComponent for Layout {
                     fn to_u32(&self) -> u32 {
@@ -487,6 +612,8 @@ mod raw {
                             Self::Dock => Layout::Dock as u32,
                             Self::Bookcase => Layout::Bookcase as u32,
                             Self::WidthToChildren => Layout::WidthToChildren as u32,
+                            Self::Grid => Layout::Grid as u32,
+                            Self::Flex => Layout::Flex as u32,
                         }
                     }
                     fn from_u32(value: u32) -> Option<Self> {
@@ -502,6 +629,12 @@ mod raw {
                         if value == Layout::WidthToChildren as u32 {
                             return Some(Self::WidthToChildren);
                         }
+                        if value == Layout::Grid as u32 {
+                            return Some(Self::Grid);
+                        }
+                        if value == Layout::Flex as u32 {
+                            return Some(Self::Flex);
+                        }
                         None
                     }
                 }
@@ -836,7 +969,7 @@ mod raw {
                 };
                 use glam::{Mat4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
                 use std::time::Duration;
-                components ! ("text" , { # [doc = "**Font family**: Font family to be used. Can either be 'Default', 'FontAwesome', 'FontAwesomeSolid', 'Code' or a url to a font.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Font family"] , Description ["Font family to be used. Can either be 'Default', 'FontAwesome', 'FontAwesomeSolid', 'Code' or a url to a font."]] font_family : String , # [doc = "**Font size**: Size of the font.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Font size"] , Description ["Size of the font."]] font_size : f32 , # [doc = "**Font style**: Style of the font.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Font style"] , Description ["Style of the font."]] font_style : crate :: generated :: raw :: ambient_core :: text :: types :: FontStyle , # [doc = "**Text**: Create a text mesh on this entity.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Text"] , Description ["Create a text mesh on this entity."]] text : String , });
+                components ! ("text" , { # [doc = "**Font family**: Font family to be used. Can either be 'Default', 'FontAwesome', 'FontAwesomeSolid', 'Code' or a url to a font.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Font family"] , Description ["Font family to be used. Can either be 'Default', 'FontAwesome', 'FontAwesomeSolid', 'Code' or a url to a font."]] font_family : String , # [doc = "**Font size**: Size of the font.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Font size"] , Description ["Size of the font."]] font_size : f32 , # [doc = "**Font style**: Style of the font.\n\n*Attributes*: Debuggable, Networked, Store, Enum"] @ [Debuggable , Networked , Store , Enum , Name ["Font style"] , Description ["Style of the font."]] font_style : crate :: generated :: raw :: ambient_core :: text :: types :: FontStyle , # [doc = "**Text**: Create a text mesh on this entity.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Text"] , Description ["Create a text mesh on this entity."]] text : String , # [doc = "**Rich text**: Parse markup in the `text` of this entity: `<b>`, `<i>`, `<u>`, `<color=#f00>` and `<size=20>` style the enclosed text, `<icon=f118>` inserts a FontAwesome icon, and `<lt>` inserts a literal `<`.\n\n*Attributes*: Debuggable, Networked, Store"] @ [Debuggable , Networked , Store , Name ["Rich text"] , Description ["Parse markup in the `text` of this entity: `<b>`, `<i>`, `<u>`, `<color=#f00>` and `<size=20>` style the enclosed text, `<icon=f118>` inserts a FontAwesome icon, and `<lt>` inserts a literal `<`."]] rich_text : () , });
             }
             #[doc = r" Auto-generated type definitions."]
             pub mod types {
@@ -959,7 +1092,7 @@ mod raw {
                 };
                 use glam::{Mat4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
                 use std::time::Duration;
-                components ! ("ui" , { # [doc = "**Focus**: Currently focused object.\n\n*Attributes*: Debuggable, Networked, Resource"] @ [Debuggable , Networked , Resource , Name ["Focus"] , Description ["Currently focused object."]] focus : String , # [doc = "**Focus**: This entity can be focused. The value is the focus id.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Focus"] , Description ["This entity can be focused. The value is the focus id."]] focusable : String , });
+                components ! ("ui" , { # [doc = "**Focus**: Currently focused object.\n\n*Attributes*: Debuggable, Networked, Resource"] @ [Debuggable , Networked , Resource , Name ["Focus"] , Description ["Currently focused object."]] focus : String , # [doc = "**Focus**: This entity can be focused. The value is the focus id.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Focus"] , Description ["This entity can be focused. The value is the focus id."]] focusable : String , # [doc = "**Locale**: The locale of the UI, like `en` or `pt-BR`, which selects the string tables used by `t!`. Defaults to the locale stored in the settings, or `en`.\n\n*Attributes*: Debuggable, Resource"] @ [Debuggable , Resource , Name ["Locale"] , Description ["The locale of the UI, like `en` or `pt-BR`, which selects the string tables used by `t!`. Defaults to the locale stored in the settings, or `en`."]] locale : String , # [doc = "**Accessibility role**: Exposes this UI element to assistive technologies, like screen readers, with this role. Elements without a role are left out of the accessibility tree, and their accessible descendants are attached to the nearest accessible ancestor.\n\n*Attributes*: Debuggable, Networked, Enum"] @ [Debuggable , Networked , Enum , Name ["Accessibility role"] , Description ["Exposes this UI element to assistive technologies, like screen readers, with this role. Elements without a role are left out of the accessibility tree, and their accessible descendants are attached to the nearest accessible ancestor."]] accessibility_role : crate :: generated :: raw :: ambient_core :: ui :: types :: AccessibilityRole , # [doc = "**Accessibility label**: The name of this UI element for assistive technologies. Defaults to the text of its descendants.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Accessibility label"] , Description ["The name of this UI element for assistive technologies. Defaults to the text of its descendants."]] accessibility_label : String , # [doc = "**Accessibility value**: The current value of this UI element for assistive technologies, like the text of a text field.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Accessibility value"] , Description ["The current value of this UI element for assistive technologies, like the text of a text field."]] accessibility_value : String , # [doc = "**Accessibility disabled**: Whether this UI element is disabled, for assistive technologies.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Accessibility disabled"] , Description ["Whether this UI element is disabled, for assistive technologies."]] accessibility_disabled : bool , # [doc = "**Accessibility expanded**: Whether this UI element is expanded or collapsed, for assistive technologies. Leave it out for elements that can't be expanded.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Accessibility expanded"] , Description ["Whether this UI element is expanded or collapsed, for assistive technologies. Leave it out for elements that can't be expanded."]] accessibility_expanded : bool , # [doc = "**Accessibility selected**: Whether this UI element is selected, for assistive technologies. Leave it out for elements that can't be selected.\n\n*Attributes*: Debuggable, Networked"] @ [Debuggable , Networked , Name ["Accessibility selected"] , Description ["Whether this UI element is selected, for assistive technologies. Leave it out for elements that can't be selected."]] accessibility_selected : bool , });
             }
             #[doc = r" Auto-generated message definitions. Messages are used to communicate with the runtime, the other side of the network,"]
             #[doc = r" and with other modules."]
@@ -1002,6 +1135,258 @@ mod raw {
                     }
                 }
                 impl ModuleMessage for FocusChanged {}
+                #[derive(Clone, Debug)]
+                #[doc = "**SetLocale**: Changes the locale of the UI and stores it in the player's settings."]
+                pub struct SetLocale {
+                    pub locale: String,
+                }
+                impl SetLocale {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(locale: impl Into<String>) -> Self {
+                        Self {
+                            locale: locale.into(),
+                        }
+                    }
+                }
+                impl Message for SetLocale {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::SetLocale"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.locale.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            locale: String::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for SetLocale {}
+                #[derive(Clone, Debug)]
+                #[doc = "**LoadLocale**: Asks the runtime for the string table of `locale` in the `assets/locales/<locale>.ftl` asset of `package`. The runtime replies to the sending module with `LocaleLoaded`."]
+                pub struct LoadLocale {
+                    pub package: EntityId,
+                    pub locale: String,
+                }
+                impl LoadLocale {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(package: impl Into<EntityId>, locale: impl Into<String>) -> Self {
+                        Self {
+                            package: package.into(),
+                            locale: locale.into(),
+                        }
+                    }
+                }
+                impl Message for LoadLocale {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::LoadLocale"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.package.serialize_message_part(&mut output)?;
+                        self.locale.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            package: EntityId::deserialize_message_part(&mut input)?,
+                            locale: String::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for LoadLocale {}
+                #[derive(Clone, Debug)]
+                #[doc = "**LocaleLoaded**: Sent in reply to `LoadLocale` with the `source` of the string table, or the `error` that prevented it from being loaded."]
+                pub struct LocaleLoaded {
+                    pub package: EntityId,
+                    pub locale: String,
+                    pub source: String,
+                    pub error: Option<String>,
+                }
+                impl LocaleLoaded {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(
+                        package: impl Into<EntityId>,
+                        locale: impl Into<String>,
+                        source: impl Into<String>,
+                        error: impl Into<Option<String>>,
+                    ) -> Self {
+                        Self {
+                            package: package.into(),
+                            locale: locale.into(),
+                            source: source.into(),
+                            error: error.into(),
+                        }
+                    }
+                }
+                impl Message for LocaleLoaded {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::LocaleLoaded"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.package.serialize_message_part(&mut output)?;
+                        self.locale.serialize_message_part(&mut output)?;
+                        self.source.serialize_message_part(&mut output)?;
+                        self.error.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            package: EntityId::deserialize_message_part(&mut input)?,
+                            locale: String::deserialize_message_part(&mut input)?,
+                            source: String::deserialize_message_part(&mut input)?,
+                            error: Option::<String>::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl RuntimeMessage for LocaleLoaded {}
+                #[derive(Clone, Debug)]
+                #[doc = "**Activate**: Activates the focusable UI element `focus` on behalf of an assistive technology, like a screen reader pressing a button."]
+                pub struct Activate {
+                    pub focus: String,
+                }
+                impl Activate {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(focus: impl Into<String>) -> Self {
+                        Self {
+                            focus: focus.into(),
+                        }
+                    }
+                }
+                impl Message for Activate {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::Activate"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.focus.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            focus: String::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for Activate {}
+            }
+            #[doc = r" Auto-generated type definitions."]
+            pub mod types {
+                use ambient_package_rt::message_serde::*;
+                use serde;
+                #[derive(
+                    Copy,
+                    Clone,
+                    Debug,
+                    PartialEq,
+                    Eq,
+                    serde :: Serialize,
+                    serde :: Deserialize,
+                    Default,
+                )]
+                #[serde(crate = "self::serde")]
+                #[doc = "**AccessibilityRole**: The role of a UI element for assistive technologies."]
+                pub enum AccessibilityRole {
+                    #[default]
+                    #[doc = "A group of related elements"]
+                    Group,
+                    #[doc = "Static text"]
+                    Text,
+                    #[doc = "A button"]
+                    Button,
+                    #[doc = "A field that text can be typed into"]
+                    TextInput,
+                    #[doc = "A button that opens a list of options"]
+                    ComboBox,
+                    #[doc = "A list of items"]
+                    List,
+                    #[doc = "An item of a list"]
+                    ListItem,
+                    #[doc = "A list of tabs"]
+                    TabList,
+                    #[doc = "A tab of a tab list"]
+                    Tab,
+                    #[doc = "The content of the selected tab"]
+                    TabPanel,
+                    #[doc = "A tooltip"]
+                    Tooltip,
+                    #[doc = "A dialog or window"]
+                    Dialog,
+                }
+                impl crate::EnumComponent for AccessibilityRole {
+                    fn to_u32(&self) -> u32 {
+                        match self {
+                            Self::Group => AccessibilityRole::Group as u32,
+                            Self::Text => AccessibilityRole::Text as u32,
+                            Self::Button => AccessibilityRole::Button as u32,
+                            Self::TextInput => AccessibilityRole::TextInput as u32,
+                            Self::ComboBox => AccessibilityRole::ComboBox as u32,
+                            Self::List => AccessibilityRole::List as u32,
+                            Self::ListItem => AccessibilityRole::ListItem as u32,
+                            Self::TabList => AccessibilityRole::TabList as u32,
+                            Self::Tab => AccessibilityRole::Tab as u32,
+                            Self::TabPanel => AccessibilityRole::TabPanel as u32,
+                            Self::Tooltip => AccessibilityRole::Tooltip as u32,
+                            Self::Dialog => AccessibilityRole::Dialog as u32,
+                        }
+                    }
+                    fn from_u32(value: u32) -> Option<Self> {
+                        if value == AccessibilityRole::Group as u32 {
+                            return Some(Self::Group);
+                        }
+                        if value == AccessibilityRole::Text as u32 {
+                            return Some(Self::Text);
+                        }
+                        if value == AccessibilityRole::Button as u32 {
+                            return Some(Self::Button);
+                        }
+                        if value == AccessibilityRole::TextInput as u32 {
+                            return Some(Self::TextInput);
+                        }
+                        if value == AccessibilityRole::ComboBox as u32 {
+                            return Some(Self::ComboBox);
+                        }
+                        if value == AccessibilityRole::List as u32 {
+                            return Some(Self::List);
+                        }
+                        if value == AccessibilityRole::ListItem as u32 {
+                            return Some(Self::ListItem);
+                        }
+                        if value == AccessibilityRole::TabList as u32 {
+                            return Some(Self::TabList);
+                        }
+                        if value == AccessibilityRole::Tab as u32 {
+                            return Some(Self::Tab);
+                        }
+                        if value == AccessibilityRole::TabPanel as u32 {
+                            return Some(Self::TabPanel);
+                        }
+                        if value == AccessibilityRole::Tooltip as u32 {
+                            return Some(Self::Tooltip);
+                        }
+                        if value == AccessibilityRole::Dialog as u32 {
+                            return Some(Self::Dialog);
+                        }
+                        None
+                    }
+                }
+                impl MessageSerde for AccessibilityRole {
+                    fn serialize_message_part(
+                        &self,
+                        output: &mut Vec<u8>,
+                    ) -> Result<(), MessageSerdeError> {
+                        crate::EnumComponent::to_u32(self).serialize_message_part(output)
+                    }
+                    fn deserialize_message_part(
+                        input: &mut dyn std::io::Read,
+                    ) -> Result<Self, MessageSerdeError> {
+                        crate::EnumComponent::from_u32(u32::deserialize_message_part(input)?)
+                            .ok_or(MessageSerdeError::InvalidValue)
+                    }
+                }
             }
         }
         pub mod wasm {
@@ -1259,6 +1644,33 @@ mod raw {
             }
             impl RuntimeMessage for WindowKeyboardCharacter {}
             #[derive(Clone, Debug)]
+            #[doc = "**WindowImeComposition**: Sent when the text being composed by the input method changes. `text` is empty when the composition ends; the final text is sent as a `WindowKeyboardCharacter`."]
+            pub struct WindowImeComposition {
+                pub text: String,
+            }
+            impl WindowImeComposition {
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(text: impl Into<String>) -> Self {
+                    Self { text: text.into() }
+                }
+            }
+            impl Message for WindowImeComposition {
+                fn id() -> &'static str {
+                    "ambient_core::WindowImeComposition"
+                }
+                fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                    let mut output = vec![];
+                    self.text.serialize_message_part(&mut output)?;
+                    Ok(output)
+                }
+                fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                    Ok(Self {
+                        text: String::deserialize_message_part(&mut input)?,
+                    })
+                }
+            }
+            impl RuntimeMessage for WindowImeComposition {}
+            #[derive(Clone, Debug)]
             #[doc = "**WindowKeyboardModifiersChange**: Sent when the window's keyboard modifiers change."]
             pub struct WindowKeyboardModifiersChange {
                 pub modifiers: u32,
@@ -5,16 +5,18 @@ use ambient_ecs::World;
 use ambient_ecs_editor::{ECSEditor, InspectableAsyncWorld};
 use ambient_element::{element_component, Element, ElementComponentExt, Group, Hooks};
 use ambient_native_std::cb;
-use ambient_ui_native::{ScrollArea, ScrollAreaSizing, WindowSized};
+use ambient_ui_native::{use_window_logical_resolution, WindowSized};
 use std::sync::Arc;
 
 #[element_component]
 fn ECSEditorUIWorld(hooks: &mut Hooks) -> Element {
     let async_run = hooks.world.resource(async_run()).clone();
+    let window = use_window_logical_resolution(hooks);
     ECSEditor {
         world: Arc::new(InspectableAsyncWorld(cb(move |cb| {
             async_run.run(move |world| cb(world))
         }))),
+        height: window.y as f32,
     }
     .el()
 }
@@ -22,11 +24,7 @@ fn ECSEditorUIWorld(hooks: &mut Hooks) -> Element {
 fn init(world: &mut World) {
     Group(vec![
         UICamera.el(),
-        WindowSized(vec![ScrollArea::el(
-            ScrollAreaSizing::FitChildrenWidth,
-            ECSEditorUIWorld.el().memoize_subtree(""),
-        )])
-        .el(),
+        WindowSized(vec![ECSEditorUIWorld.el()]).el(),
     ])
     .el()
     .spawn_interactive(world);
@@ -9,10 +9,10 @@ use ambient_layout::{fit_horizontal, max_width, width, Fit};
 use ambient_native_std::{cb, Cb};
 use ambient_renderer::color;
 use ambient_ui_native::{
-    margin, Borders, Button, ButtonStyle, FlowColumn, FlowRow, Text, TextEditor, UIExt,
-    CHEVRON_DOWN, CHEVRON_RIGHT, STREET,
+    margin, virtual_list::VirtualList, Borders, Button, ButtonStyle, FlowColumn, FlowRow,
+    MeasureSize, Text, TextEditor, UIExt, CHEVRON_DOWN, CHEVRON_RIGHT, STREET,
 };
-use glam::vec4;
+use glam::{vec4, Vec2};
 use itertools::Itertools;
 pub trait InspectableWorld: Sync + Send + std::fmt::Debug {
     fn get_entities(
@@ -121,41 +121,88 @@ impl InspectableWorld for InspectableAsyncWorld {
     }
 }
 
+/// Shows the entities of `world` and their components. The root entities are in a [VirtualList],
+/// so that large worlds stay fast, which fills the `height` left by the filters.
 #[element_component]
-pub fn ECSEditor(hooks: &mut Hooks, world: Arc<dyn InspectableWorld>) -> Element {
+pub fn ECSEditor(hooks: &mut Hooks, world: Arc<dyn InspectableWorld>, height: f32) -> Element {
     let (comp_filter, set_comp_filter) = use_state(hooks, "".to_string());
     let (entity_filter, set_entity_filter) = use_state(hooks, "".to_string());
+    let (filters_size, set_filters_size) = use_state(hooks, Vec2::ZERO);
 
     FlowColumn::el([
-        {
-            let entity_filter = entity_filter.clone();
-            TextEditor::new(entity_filter, set_entity_filter)
-                .placeholder(Some("\u{f422} entity filter".to_string()))
-                .el()
-                .with(margin(), Borders::even(STREET).into())
-        },
-        {
-            let comp_filter = comp_filter.clone();
-            TextEditor::new(comp_filter, set_comp_filter)
-                .placeholder(Some("\u{f422} component filter".to_string()))
-                .el()
-                .with(margin(), Borders::even(STREET).into())
-        },
-        EntityList {
+        MeasureSize::el(
+            FlowColumn::el([
+                {
+                    let entity_filter = entity_filter.clone();
+                    TextEditor::new(entity_filter, set_entity_filter)
+                        .placeholder(Some("\u{f422} entity filter".to_string()))
+                        .el()
+                        .with(margin(), Borders::even(STREET).into())
+                },
+                {
+                    let comp_filter = comp_filter.clone();
+                    TextEditor::new(comp_filter, set_comp_filter)
+                        .placeholder(Some("\u{f422} component filter".to_string()))
+                        .el()
+                        .with(margin(), Borders::even(STREET).into())
+                },
+            ]),
+            set_filters_size,
+        ),
+        RootEntityList {
             world,
-            parent: None,
             filter_components: comp_filter,
             filter_entities: entity_filter,
         }
-        .el(),
+        .el()
+        .with(ambient_layout::height(), (height - filters_size.y).max(0.)),
     ])
 }
 
+#[element_component]
+fn RootEntityList(
+    hooks: &mut Hooks,
+    world: Arc<dyn InspectableWorld>,
+    filter_entities: String,
+    filter_components: String,
+) -> Element {
+    let (entities, set_entities) = use_state(hooks, Vec::new());
+    use_interval_deps(
+        hooks,
+        Duration::from_secs_f32(0.5),
+        true,
+        filter_entities.clone(),
+        {
+            let world = world.clone();
+            let filter_entities = filter_entities.clone();
+            move |_| {
+                world.get_entities(None, set_entities.clone(), filter_entities.clone());
+            }
+        },
+    );
+    let entities: Arc<[InspectedEntity]> = entities.into();
+    VirtualList::new(entities.len(), move |index| {
+        let entity = entities[index].clone();
+        EntityBlock {
+            world: world.clone(),
+            entity: entity.clone(),
+            filter_entities: filter_entities.clone(),
+            filter_components: filter_components.clone(),
+        }
+        .el()
+        .memoize_subtree(format!(
+            "{}-{}-{}",
+            entity.id, filter_entities, filter_components
+        ))
+    })
+    .el()
+}
+
 #[element_component]
 fn EntityList(
     hooks: &mut Hooks,
     world: Arc<dyn InspectableWorld>,
-    parent: Option<EntityId>,
+    parent: EntityId,
     filter_entities: String,
     filter_components: String,
 ) -> Element {
@@ -171,7 +218,7 @@ fn EntityList(
             let world = world.clone();
             let filter_entities = filter_entities.clone();
             move |_| {
-                world.get_entities(parent, set_entities.clone(), filter_entities.clone());
+                world.get_entities(Some(parent), set_entities.clone(), filter_entities.clone());
             }
         },
     );
@@ -256,7 +303,7 @@ fn EntityBlock(
         if expanded {
             EntityList {
                 world,
-                parent: Some(entity.id),
+                parent: entity.id,
                 filter_entities,
                 filter_components,
             }
@@ -10,19 +10,29 @@ use ambient_network::{
     is_remote_entity,
 };
 use ambient_ui_native::{
-    fit_horizontal, space_between_items, Button, ButtonStyle, DialogScreen, Fit, FlowColumn,
-    FlowRow, ScrollArea, ScrollAreaSizing, STREET,
+    drag_drop::DragSource, fit_horizontal, space_between_items, use_window_logical_resolution,
+    Button, ButtonStyle, DialogScreen, Fit, FlowColumn, FlowRow, MeasureSize, ScrollArea,
+    ScrollAreaSizing, Text, UIExt, STREET,
 };
+use glam::Vec2;
 use itertools::Itertools;
 use std::sync::Arc;
 
+/// An entity dragged from the [EntityBrowser] into the scene.
+#[derive(Debug, Clone, Copy)]
+pub struct BrowsedEntity(pub EntityId);
+
 #[derive(Debug, Clone)]
 pub struct EntityBrowser {
     on_select: Cb<dyn Fn(EntityId) + Sync + Send>,
+    on_drag_start: Cb<dyn Fn() + Sync + Send>,
 }
 impl ElementComponent for EntityBrowser {
     fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
-        let Self { on_select } = *self;
+        let Self {
+            on_select,
+            on_drag_start,
+        } = *self;
         let (entities, set_entities) = use_state(hooks, Vec::new());
         let (all_tags, set_all_tags) = use_state(hooks, Vec::new());
         let (selected_tag, set_selected_tag) = use_state(hooks, None);
@@ -91,10 +101,16 @@ impl ElementComponent for EntityBrowser {
                     })
                     .take(100)
                     .map(move |(entity, name, tags)| {
-                        Button::new(
-                            format!("{entity} {name} {tags:?}"),
-                            closure!(clone on_select, |_| on_select.0(entity)),
+                        DragSource::new(
+                            Button::new(
+                                format!("{entity} {name} {tags:?}"),
+                                closure!(clone on_select, |_| on_select.0(entity)),
+                            )
+                            .el(),
+                            BrowsedEntity(entity),
                         )
+                        .preview(Text::el(name).with_padding_even(STREET))
+                        .on_drag_start(closure!(clone on_drag_start, |_| on_drag_start.0()))
                         .el()
                     })
                     .collect_vec(),
@@ -115,37 +131,50 @@ impl ElementComponent for EntityBrowserScreen {
     fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
         let Self { on_select, on_back } = *self;
         let (advanced, set_advanced) = use_state(hooks, false);
+        let window = use_window_logical_resolution(hooks);
+        let (buttons_size, set_buttons_size) = use_state(hooks, Vec2::ZERO);
         DialogScreen(
             ScrollArea::el(
                 ScrollAreaSizing::FitChildrenWidth,
                 FlowColumn::el([
-                    FlowRow::el([
-                        Button::new("Back", {
-                            move |_| {
-                                on_back();
-                            }
-                        })
-                        .style(ButtonStyle::Primary)
-                        .el(),
-                        Button::new("Advanced", move |_| set_advanced(!advanced))
-                            .toggled(advanced)
+                    MeasureSize::el(
+                        FlowRow::el([
+                            Button::new("Back", {
+                                let on_back = on_back.clone();
+                                move |_| {
+                                    on_back();
+                                }
+                            })
+                            .style(ButtonStyle::Primary)
                             .el(),
-                    ])
-                    .with(space_between_items(), STREET),
+                            Button::new("Advanced", move |_| set_advanced(!advanced))
+                                .toggled(advanced)
+                                .el(),
+                        ])
+                        .with(space_between_items(), STREET),
+                        set_buttons_size,
+                    ),
                     if advanced {
-                        ECSEditor::el(Arc::new(InspectableAsyncWorld(cb({
-                            let client_state = hooks.world.resource(client_state()).clone();
-                            move |cb| {
-                                let state = client_state.as_ref().unwrap().game_state.lock();
-                                cb(&state.world);
-                            }
-                        }))))
-                        .memoize_subtree("")
+                        // Fill the rest of the dialog, which is padded by 30 on each side
+                        let height = window.y as f32 - 60. - buttons_size.y - STREET;
+                        ECSEditor::el(
+                            Arc::new(InspectableAsyncWorld(cb({
+                                let client_state = hooks.world.resource(client_state()).clone();
+                                move |cb| {
+                                    let state = client_state.as_ref().unwrap().game_state.lock();
+                                    cb(&state.world);
+                                }
+                            }))),
+                            height.max(0.),
+                        )
+                        .memoize_subtree(height.to_string())
                     } else {
                         EntityBrowser {
                             on_select: cb(move |id| {
                                 on_select(id);
                             }),
+                            // Hide the browser so the entity can be dropped in the scene
+                            on_drag_start: on_back.clone(),
                         }
                         .el()
                     },
@@ -3,13 +3,14 @@ use std::{sync::Arc, time::Duration};
 use ambient_core::{
     asset_cache, async_ecs::async_run, runtime, window::get_mouse_clip_space_position,
 };
-use ambient_ecs::{Component, ComponentValue, EntityId};
+use ambient_ecs::{Component, ComponentValue, EntityId, World};
 use ambient_element::{
     consume_context, provide_context, use_interval_deps, use_ref_with, use_rerender_signal,
     use_state, Element, ElementComponent, ElementComponentExt, Hooks,
 };
 use ambient_intent::{client_push_intent, rpc_undo_head_exact};
 use ambient_network::client::ClientState;
+use ambient_physics::intersection::{rpc_pick, RaycastFilter};
 use ambient_shared_types::MouseButton;
 use ambient_sys::task::RuntimeHandle;
 use derive_more::Display;
@@ -23,6 +24,7 @@ use ambient_native_std::{
 use ambient_shared_types::VirtualKeyCode;
 use ambient_ui_native::{
     command_modifier,
+    drag_drop::use_world_drop_target,
     layout::{docking, width, Docking},
     margin, padding, space_between_items, use_keyboard_input, Borders, Button, ButtonStyle, Dock,
     FlowRow, Hotkey, ScreenContainer, Separator, StylesExt, STREET,
@@ -32,8 +34,8 @@ use tokio::time::sleep;
 use super::{terrain_mode::GenerateTerrainButton, EditorPlayerInputHandler, EditorPrefs};
 use crate::{
     intents::{
-        intent_delete, intent_duplicate, intent_spawn_object, IntentDuplicate, IntentSpawnObject,
-        SelectMode,
+        intent_delete, intent_duplicate, intent_place_ray, intent_spawn_object, IntentDuplicate,
+        IntentPlaceRay, IntentSpawnObject, SelectMode,
     },
     ui::use_player_selection,
     Selection, GRID_SIZE,
@@ -51,7 +53,7 @@ use select_area::*;
 use selection_panel::*;
 use transform::*;
 
-use self::entity_browser::EntityBrowserScreen;
+use self::entity_browser::{BrowsedEntity, EntityBrowserScreen};
 
 /// An editor can only be in one action at a time.
 /// They can be confirmed or aborted.
@@ -206,6 +208,52 @@ impl ElementComponent for EditorBuildMode {
                 }
             }
         });
+        // Entities dragged from the entity browser into the scene are selected and placed where the
+        // cursor hits the scene, or placed interactively if it doesn't hit anything
+        let (prefs, _) = consume_context::<EditorPrefs>(hooks).unwrap();
+        use_world_drop_target(
+            hooks,
+            None,
+            cb({
+                let client_state = client_state.clone();
+                let set_selection = set_selection.clone();
+                let set_srt_mode = set_srt_mode.clone();
+                move |world: &mut World, BrowsedEntity(id): BrowsedEntity, _: EntityId| {
+                    let ray = client_state
+                        .game_state
+                        .lock()
+                        .screen_ray(get_mouse_clip_space_position(world));
+                    let filter = RaycastFilter {
+                        entities: None,
+                        collider_type: None,
+                    };
+                    let client_state = client_state.clone();
+                    let set_selection = set_selection.clone();
+                    let set_srt_mode = set_srt_mode.clone();
+                    world.resource(runtime()).spawn(async move {
+                        set_selection(Selection::new([id]));
+                        match client_state.rpc(rpc_pick, (ray, filter)).await {
+                            Ok(Some(_)) => {
+                                let intent = IntentPlaceRay {
+                                    targets: vec![id],
+                                    ray,
+                                    snap: prefs.snap,
+                                };
+                                client_push_intent(
+                                    client_state,
+                                    intent_place_ray(),
+                                    intent,
+                                    None,
+                                    None,
+                                )
+                                .await;
+                            }
+                            _ => set_srt_mode(Some(TransformMode::Place)),
+                        }
+                    });
+                }
+            }),
+        );
 
         // Make sure to get the value *after* the `use_interval_deps`
         let targets = targets.lock();
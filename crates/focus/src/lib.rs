@@ -1,40 +1,113 @@
 use ambient_ecs::{
     generated::{
         input::components::mouse_over_entity,
-        messages::WindowMouseInput,
+        messages::{WindowKeyboardInput, WindowMouseInput},
+        transform::components::local_to_world,
         ui::{
             components::{focus, focusable},
             messages::FocusChanged,
         },
     },
-    read_messages, world_events, FnSystem, SystemGroup, WorldEventReader, WorldEventsExt,
+    query, read_messages, world_events, EntityId, FnSystem, SystemGroup, World, WorldEventReader,
+    WorldEventsExt,
 };
+use ambient_shared_types::{ModifiersState, VirtualKeyCode};
 
 pub fn systems() -> SystemGroup {
-    let mut reader = WorldEventReader::new();
+    let mut mouse_reader = WorldEventReader::new();
+    let mut keyboard_reader = WorldEventReader::new();
     SystemGroup::new(
         "focus",
         vec![Box::new(FnSystem::new(move |world, _| {
             for event in
-                read_messages::<WindowMouseInput>(&mut reader, world.resource(world_events()))
+                read_messages::<WindowMouseInput>(&mut mouse_reader, world.resource(world_events()))
             {
                 if event.button == 0 {
                     let mouse_over = *world.resource(mouse_over_entity());
                     let focus_id = world
                         .get_cloned(mouse_over, focusable())
                         .unwrap_or_default();
-                    let cur_focus = world.resource(focus()).clone();
-                    if cur_focus != focus_id {
-                        *world.resource_mut(focus()) = focus_id.clone();
-                        world
-                            .resource_mut(world_events())
-                            .add_message(FocusChanged {
-                                from_external: false,
-                                focus: focus_id,
-                            });
-                    }
+                    set_focus(world, focus_id);
+                }
+            }
+            for event in read_messages::<WindowKeyboardInput>(
+                &mut keyboard_reader,
+                world.resource(world_events()),
+            ) {
+                let tab = event
+                    .keycode
+                    .as_ref()
+                    .and_then(|k| k.parse::<VirtualKeyCode>().ok())
+                    == Some(VirtualKeyCode::Tab);
+                if tab && event.pressed {
+                    let backwards = ModifiersState::from_bits_truncate(event.modifiers).shift();
+                    move_focus(world, backwards);
                 }
             }
         }))],
     )
 }
+
+/// Sorts UI entities in keyboard traversal order: top to bottom, and then left to right.
+pub fn sort_in_focus_order(world: &World, entities: &mut [EntityId]) {
+    let position = |id: EntityId| {
+        world
+            .get(id, local_to_world())
+            .map(|transform| transform.w_axis.truncate())
+            .unwrap_or_default()
+    };
+    entities.sort_by(|a, b| {
+        let (a, b) = (position(*a), position(*b));
+        a.y.total_cmp(&b.y)
+            .then(a.x.total_cmp(&b.x))
+            // Elements drawn on top come first
+            .then(b.z.total_cmp(&a.z))
+    });
+}
+
+/// The focusable entities, in keyboard traversal order.
+pub fn focus_order(world: &World) -> Vec<EntityId> {
+    let mut entities = query((focusable(),))
+        .iter(world, None)
+        .filter(|(_, (id,))| !id.is_empty())
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    sort_in_focus_order(world, &mut entities);
+    entities
+}
+
+/// Moves the focus to the next focusable entity, or the previous one if `backwards` is set.
+///
+/// When nothing is focused, the first (or last) focusable entity is focused.
+pub fn move_focus(world: &mut World, backwards: bool) {
+    let order = focus_order(world);
+    if order.is_empty() {
+        return;
+    }
+    let current = world.resource(focus()).clone();
+    let index = order
+        .iter()
+        .position(|&id| world.get_ref(id, focusable()).ok() == Some(&current));
+    let next = match (index, backwards) {
+        (None, false) => 0,
+        (None, true) => order.len() - 1,
+        (Some(index), false) => (index + 1) % order.len(),
+        (Some(index), true) => (index + order.len() - 1) % order.len(),
+    };
+    let focus_id = world.get_cloned(order[next], focusable()).unwrap();
+    set_focus(world, focus_id);
+}
+
+/// Focuses `focus_id`, and sends [FocusChanged] if the focus changed.
+pub fn set_focus(world: &mut World, focus_id: String) {
+    if *world.resource(focus()) == focus_id {
+        return;
+    }
+    *world.resource_mut(focus()) = focus_id.clone();
+    world
+        .resource_mut(world_events())
+        .add_message(FocusChanged {
+            from_external: false,
+            focus: focus_id,
+        });
+}
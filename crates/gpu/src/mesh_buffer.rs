@@ -108,7 +108,8 @@ pub struct BaseMesh {
     normal: Vec4,
     tangent: Vec4,
     texcoord0: Vec2,
-    _padding: Vec2,
+    texcoord1: Vec2,
+    color: Vec4,
 }
 
 #[repr(C)]
@@ -191,32 +192,8 @@ impl MeshBuffer {
             ..Default::default()
         };
 
-        // Pad all vertex attributes to match vertex positions buffer.
         {
-            let pos = mesh.positions();
-            let norm = mesh.normals();
-            let tan = mesh.tangents();
-            let uv = mesh.texcoords(0);
-
-            let len = ([pos.len(), norm.len(), tan.len(), uv.len()])
-                .into_iter()
-                .max()
-                .unwrap_or(0);
-
-            let mut data = vec![BaseMesh::default(); len];
-
-            pos.iter()
-                .zip(&mut data)
-                .for_each(|(src, dst)| dst.position = src.extend(0.0));
-            norm.iter()
-                .zip(&mut data)
-                .for_each(|(src, dst)| dst.normal = src.extend(0.0));
-            tan.iter()
-                .zip(&mut data)
-                .for_each(|(src, dst)| dst.tangent = src.extend(0.0));
-            uv.iter()
-                .zip(&mut data)
-                .for_each(|(src, dst)| dst.texcoord0 = *src);
+            let data = base_data(mesh, 0..usize::MAX);
 
             self.base_buffer
                 .front
@@ -230,21 +207,8 @@ impl MeshBuffer {
         }
 
         if !mesh.joint_indices().is_empty() && !mesh.joint_weights().is_empty() {
-            let joints = mesh.joint_indices();
-            let weights = mesh.joint_weights();
-
-            let len = joints.len().max(weights.len());
-
-            let mut data = vec![SkinnedMesh::default(); len];
-
-            joints
-                .iter()
-                .zip(&mut data)
-                .for_each(|(src, dst)| dst.joint = *src);
-            weights
-                .iter()
-                .zip(&mut data)
-                .for_each(|(src, dst)| dst.weights = *src);
+            let data = skinned_data(mesh, 0..usize::MAX);
+            let len = data.len();
 
             self.skinned_buffer
                 .front
@@ -440,6 +404,59 @@ impl MeshBuffer {
         MESHES_TOTAL_SIZE.store(self.size() as usize, Ordering::SeqCst);
     }
 
+    /// Rewrites the vertices in `range` of an already uploaded mesh with the contents of `mesh`, and all of
+    /// its indices if `indices_changed` is set.
+    ///
+    /// Returns `false` if the mesh no longer has the same number of vertices or indices as when it was
+    /// uploaded, in which case it must be inserted again instead.
+    pub fn write_vertices(
+        &mut self,
+        gpu: &Gpu,
+        gpu_mesh: &GpuMesh,
+        mesh: &Mesh,
+        range: Range<usize>,
+        indices_changed: bool,
+    ) -> bool {
+        let Some(Some(internal)) = self.meshes.get(gpu_mesh.index as usize) else {
+            return false;
+        };
+        let has_skin = !mesh.joint_indices().is_empty() && !mesh.joint_weights().is_empty();
+        let skinned_count = if has_skin {
+            mesh.joint_indices().len().max(mesh.joint_weights().len())
+        } else {
+            0
+        };
+        if internal.base_count != base_len(mesh) as u64
+            || internal.skinned_count != skinned_count as u64
+            || internal.index_count != mesh.index_count() as u64
+        {
+            return false;
+        }
+
+        let metadata = internal.metadata;
+        let range = range.start..range.end.min(internal.base_count as usize);
+        if !range.is_empty() {
+            self.base_buffer.front.write(
+                gpu,
+                metadata.base_offset as usize + range.start,
+                &base_data(mesh, range.clone()),
+            );
+            if has_skin {
+                self.skinned_buffer.front.write(
+                    gpu,
+                    metadata.skinned_offset as usize + range.start,
+                    &skinned_data(mesh, range),
+                );
+            }
+        }
+        if indices_changed {
+            self.index_buffer
+                .front
+                .write(gpu, metadata.index_offset as usize, mesh.indices());
+        }
+        true
+    }
+
     pub fn get_mesh_metadata(&self, mesh: &GpuMesh) -> &MeshMetadata {
         &self.meshes[mesh.index as usize].as_ref().unwrap().metadata
     }
@@ -472,6 +489,73 @@ impl MeshBuffer {
     }
 }
 
+/// All vertex attributes are padded to match the longest one.
+fn base_len(mesh: &Mesh) -> usize {
+    [
+        mesh.positions().len(),
+        mesh.normals().len(),
+        mesh.tangents().len(),
+        mesh.texcoords(0).len(),
+        mesh.texcoords(1).len(),
+        mesh.colors().len(),
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or(0)
+}
+
+/// Interleaves the base attributes of the vertices in `range`, clamped to the mesh's vertex count.
+fn base_data(mesh: &Mesh, range: Range<usize>) -> Vec<BaseMesh> {
+    let range = range.start..range.end.min(base_len(mesh));
+    let mut data = vec![
+        BaseMesh {
+            color: Vec4::ONE,
+            ..Default::default()
+        };
+        range.len()
+    ];
+
+    macro_rules! fill {
+        ($src:expr, |$v:ident, $dst:ident| $assign:expr) => {
+            $src.get(range.start..range.end.min($src.len()))
+                .unwrap_or_default()
+                .iter()
+                .zip(&mut data)
+                .for_each(|($v, $dst)| $assign);
+        };
+    }
+    fill!(mesh.positions(), |v, dst| dst.position = v.extend(0.0));
+    fill!(mesh.normals(), |v, dst| dst.normal = v.extend(0.0));
+    fill!(mesh.tangents(), |v, dst| dst.tangent = v.extend(0.0));
+    fill!(mesh.texcoords(0), |v, dst| dst.texcoord0 = *v);
+    fill!(mesh.texcoords(1), |v, dst| dst.texcoord1 = *v);
+    fill!(mesh.colors(), |v, dst| dst.color = *v);
+
+    data
+}
+
+fn skinned_data(mesh: &Mesh, range: Range<usize>) -> Vec<SkinnedMesh> {
+    let (joints, weights) = (mesh.joint_indices(), mesh.joint_weights());
+    let len = joints.len().max(weights.len());
+    let range = range.start..range.end.min(len);
+    let mut data = vec![SkinnedMesh::default(); range.len()];
+
+    joints
+        .get(range.start..range.end.min(joints.len()))
+        .unwrap_or_default()
+        .iter()
+        .zip(&mut data)
+        .for_each(|(src, dst)| dst.joint = *src);
+    weights
+        .get(range.start..range.end.min(weights.len()))
+        .unwrap_or_default()
+        .iter()
+        .zip(&mut data)
+        .for_each(|(src, dst)| dst.weights = *src);
+
+    data
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct MeshMetadata {
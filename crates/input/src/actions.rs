@@ -0,0 +1,514 @@
+use std::collections::{HashMap, VecDeque};
+
+use ambient_core::asset_cache;
+use ambient_ecs::{
+    generated::{
+        input::{
+            components::{
+                input_action, input_axis, input_bindings, input_negative_bindings,
+                input_previous_value, input_value,
+            },
+            messages::InputRebind,
+        },
+        package::components::id as package_id,
+        wasm::components::package_ref,
+    },
+    query, world_events, Entity, EntityId, FrameEvent, Message, QueryState, System, World,
+    WorldEventReader, WorldEventSource,
+};
+use ambient_native_std::asset_cache::SyncAssetKey;
+use ambient_settings::{InputBindingOverride, InputSettings, SettingsKey};
+use ambient_shared_types::InputBinding;
+
+use crate::player_raw_input;
+
+/// Analog inputs at or above this value count as pressed.
+pub const PRESSED_THRESHOLD: f32 = 0.5;
+
+/// The current value of every input reported by the connected [InputDevice]s.
+///
+/// Digital inputs are `1.0` while held; analog inputs report their current value. Inputs that
+/// are not held are absent.
+#[derive(Clone, Default, Debug)]
+pub struct InputState {
+    values: HashMap<InputBinding, f32>,
+}
+impl InputState {
+    /// Sets the value of `binding`. If several devices report the same binding, the value with
+    /// the largest magnitude wins.
+    pub fn set(&mut self, binding: InputBinding, value: f32) {
+        if value == 0.0 {
+            return;
+        }
+        let current = self.values.entry(binding).or_default();
+        if value.abs() > current.abs() {
+            *current = value;
+        }
+    }
+    pub fn press(&mut self, binding: InputBinding) {
+        self.set(binding, 1.0);
+    }
+    pub fn value(&self, binding: &InputBinding) -> f32 {
+        self.values.get(binding).copied().unwrap_or_default()
+    }
+    pub fn is_pressed(&self, binding: &InputBinding) -> bool {
+        self.value(binding) >= PRESSED_THRESHOLD
+    }
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Evaluates an axis: the strongest of the `positive` bindings minus the strongest of the
+    /// `negative` bindings, in the range `-1.0..=1.0`.
+    pub fn axis(&self, positive: &[InputBinding], negative: &[InputBinding]) -> f32 {
+        let strongest = |bindings: &[InputBinding]| {
+            bindings.iter().map(|b| self.value(b)).fold(0.0f32, |a, b| {
+                if b.abs() > a.abs() {
+                    b
+                } else {
+                    a
+                }
+            })
+        };
+        (strongest(positive) - strongest(negative)).clamp(-1.0, 1.0)
+    }
+
+    /// Evaluates an action: `1.0` if any of the `bindings` are pressed, `0.0` otherwise.
+    pub fn action(&self, bindings: &[InputBinding]) -> f32 {
+        if bindings.iter().any(|b| self.is_pressed(b)) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A source of input, like a keyboard or a gamepad.
+pub trait InputDevice: Send + std::fmt::Debug {
+    /// Writes the current state of this device into `state`.
+    fn poll(&mut self, world: &World, state: &mut InputState);
+}
+
+/// Reads the keyboard and mouse from the [player_raw_input] resource.
+#[derive(Debug, Default)]
+pub struct KeyboardMouseDevice;
+impl InputDevice for KeyboardMouseDevice {
+    fn poll(&mut self, world: &World, state: &mut InputState) {
+        let Some(input) = world.resource_opt(player_raw_input()) else {
+            return;
+        };
+        for &key in &input.keys {
+            state.press(InputBinding::Key(key));
+        }
+        for &button in &input.mouse_buttons {
+            state.press(InputBinding::Mouse(button));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VirtualInputStep {
+    Set(InputBinding, f32),
+    Wait(usize),
+}
+
+/// A device that replays a script of inputs, one step per frame. Useful for tests and automation.
+///
+/// ```ignore
+/// let device = VirtualDevice::new()
+///     .press(InputBinding::Key(VirtualKeyCode::Space))
+///     .wait(2)
+///     .release(InputBinding::Key(VirtualKeyCode::Space));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VirtualDevice {
+    script: VecDeque<VirtualInputStep>,
+    held: HashMap<InputBinding, f32>,
+}
+impl VirtualDevice {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Holds `binding` down until it is released.
+    pub fn press(self, binding: InputBinding) -> Self {
+        self.set(binding, 1.0)
+    }
+    pub fn release(self, binding: InputBinding) -> Self {
+        self.set(binding, 0.0)
+    }
+    /// Sets the value of an analog input, like a gamepad stick.
+    pub fn set(mut self, binding: InputBinding, value: f32) -> Self {
+        self.script.push_back(VirtualInputStep::Set(binding, value));
+        self
+    }
+    /// Ends the current frame, then keeps the current state for `frames` more frames.
+    pub fn wait(mut self, frames: usize) -> Self {
+        self.script.push_back(VirtualInputStep::Wait(frames));
+        self
+    }
+    /// Whether the whole script has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.script.is_empty()
+    }
+    fn advance(&mut self) {
+        while let Some(step) = self.script.pop_front() {
+            match step {
+                VirtualInputStep::Set(binding, value) if value == 0.0 => {
+                    self.held.remove(&binding);
+                }
+                VirtualInputStep::Set(binding, value) => {
+                    self.held.insert(binding, value);
+                }
+                VirtualInputStep::Wait(0) => return,
+                VirtualInputStep::Wait(frames) => {
+                    self.script.push_front(VirtualInputStep::Wait(frames - 1));
+                    return;
+                }
+            }
+        }
+    }
+}
+impl InputDevice for VirtualDevice {
+    fn poll(&mut self, _world: &World, state: &mut InputState) {
+        self.advance();
+        for (&binding, &value) in &self.held {
+            state.set(binding, value);
+        }
+    }
+}
+
+/// The devices that are available on this platform.
+pub fn default_devices() -> Vec<Box<dyn InputDevice>> {
+    #[allow(unused_mut)]
+    let mut devices: Vec<Box<dyn InputDevice>> = vec![Box::new(KeyboardMouseDevice)];
+    #[cfg(feature = "gamepad")]
+    devices
+        .extend(crate::gamepad::GamepadDevice::new().map(|d| Box::new(d) as Box<dyn InputDevice>));
+    devices
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Bindings {
+    positive: Vec<InputBinding>,
+    negative: Vec<InputBinding>,
+}
+impl Bindings {
+    fn parse(positive: &[String], negative: &[String]) -> anyhow::Result<Self> {
+        let parse = |bindings: &[String]| {
+            bindings
+                .iter()
+                .map(|b| Ok(b.parse()?))
+                .collect::<anyhow::Result<Vec<_>>>()
+        };
+        Ok(Self {
+            positive: parse(positive)?,
+            negative: parse(negative)?,
+        })
+    }
+}
+
+/// Updates the [input_value] of every input action and axis from the given devices, and applies
+/// rebinds requested through [InputRebind].
+///
+/// Rebinds are stored in the player's [InputSettings], and are keyed by the package that owns the action.
+/// The [input_bindings] of each entity are only parsed when they change.
+#[derive(Debug)]
+pub struct InputActionSystem {
+    devices: Vec<Box<dyn InputDevice>>,
+    state: InputState,
+    overrides: Option<HashMap<String, Bindings>>,
+    parsed: HashMap<EntityId, Bindings>,
+    changed_qs: QueryState,
+    despawn_qs: QueryState,
+    rebind_reader: WorldEventReader,
+}
+impl InputActionSystem {
+    pub fn new(devices: Vec<Box<dyn InputDevice>>) -> Self {
+        Self {
+            devices,
+            state: InputState::default(),
+            overrides: None,
+            parsed: HashMap::new(),
+            changed_qs: QueryState::new(),
+            despawn_qs: QueryState::new(),
+            rebind_reader: WorldEventReader::new(),
+        }
+    }
+
+    fn overrides(&mut self, world: &World) -> &mut HashMap<String, Bindings> {
+        self.overrides.get_or_insert_with(|| {
+            let Some(assets) = world.resource_opt(asset_cache()) else {
+                return HashMap::new();
+            };
+            SettingsKey
+                .get(assets)
+                .input
+                .bindings
+                .iter()
+                .filter_map(
+                    |(key, o)| match Bindings::parse(&o.bindings, &o.negative_bindings) {
+                        Ok(bindings) => Some((key.clone(), bindings)),
+                        Err(err) => {
+                            tracing::warn!("Ignoring invalid input bindings for {key:?}: {err}");
+                            None
+                        }
+                    },
+                )
+                .collect()
+        })
+    }
+
+    fn handle_rebinds(&mut self, world: &World) {
+        let rebinds = self
+            .rebind_reader
+            .iter(world.resource(world_events()))
+            .filter(|(_, (_, name, _))| *name == InputRebind::id())
+            .filter_map(|(_, (source, _, data))| {
+                Some((source.clone(), InputRebind::deserialize_message(data).ok()?))
+            })
+            .collect::<Vec<_>>();
+
+        for (source, rebind) in rebinds {
+            let package_id = match source {
+                WorldEventSource::Local(module) => world
+                    .get(module, package_ref())
+                    .ok()
+                    .and_then(|package| world.get_cloned(package, package_id()).ok()),
+                _ => None,
+            };
+            let Some(package_id) = package_id else {
+                tracing::warn!(
+                    "Ignoring rebind of {:?}: it was not sent by a module",
+                    rebind.name
+                );
+                continue;
+            };
+
+            let key = InputSettings::key(&package_id, &rebind.name);
+            if rebind.bindings.is_empty() && rebind.negative_bindings.is_empty() {
+                self.overrides(world).remove(&key);
+                persist_override(world, key, None);
+                continue;
+            }
+
+            match Bindings::parse(&rebind.bindings, &rebind.negative_bindings) {
+                Ok(bindings) => {
+                    self.overrides(world).insert(key.clone(), bindings);
+                    persist_override(
+                        world,
+                        key,
+                        Some(InputBindingOverride {
+                            bindings: rebind.bindings,
+                            negative_bindings: rebind.negative_bindings,
+                        }),
+                    );
+                }
+                Err(err) => tracing::warn!("Ignoring rebind of {key:?}: {err}"),
+            }
+        }
+    }
+
+    /// Parses the bindings of the entities whose bindings changed since the last frame, and forgets
+    /// the entities that no longer have bindings.
+    fn update_parsed(&mut self, world: &World) {
+        for (id, (positive,)) in query((input_bindings().changed(),))
+            .optional_changed(input_negative_bindings())
+            .iter(world, Some(&mut self.changed_qs))
+        {
+            let negative = world
+                .get_ref(id, input_negative_bindings())
+                .map(|b| b.as_slice())
+                .unwrap_or_default();
+            match Bindings::parse(positive, negative) {
+                Ok(bindings) => {
+                    self.parsed.insert(id, bindings);
+                }
+                Err(err) => {
+                    tracing::warn!("Ignoring invalid input bindings of {id}: {err}");
+                    self.parsed.remove(&id);
+                }
+            }
+        }
+        for (id, _) in query((input_bindings(),))
+            .despawned()
+            .iter(world, Some(&mut self.despawn_qs))
+        {
+            self.parsed.remove(&id);
+        }
+    }
+
+    /// The bindings of `id`, from the overrides if it has any. [Self::overrides] must have been
+    /// loaded.
+    fn bindings(&self, world: &World, id: EntityId) -> Option<&Bindings> {
+        let name = world
+            .get_ref(id, input_action())
+            .or_else(|_| world.get_ref(id, input_axis()))
+            .ok()?;
+        let key = world
+            .get(id, package_ref())
+            .ok()
+            .and_then(|package| world.get_ref(package, package_id()).ok())
+            .map(|package_id| InputSettings::key(package_id, name));
+
+        key.and_then(|key| self.overrides.as_ref()?.get(&key))
+            .or_else(|| self.parsed.get(&id))
+    }
+}
+impl System for InputActionSystem {
+    fn run(&mut self, world: &mut World, _event: &FrameEvent) {
+        self.handle_rebinds(world);
+        self.update_parsed(world);
+        self.overrides(world);
+
+        self.state.clear();
+        for device in &mut self.devices {
+            device.poll(world, &mut self.state);
+        }
+
+        let entities = query(input_bindings())
+            .iter(world, None)
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        for id in entities {
+            let Some(bindings) = self.bindings(world, id) else {
+                continue;
+            };
+            let value = if world.has_component(id, input_axis()) {
+                self.state.axis(&bindings.positive, &bindings.negative)
+            } else {
+                self.state.action(&bindings.positive)
+            };
+            let previous = world.get(id, input_value()).unwrap_or_default();
+            world
+                .add_components(
+                    id,
+                    Entity::new()
+                        .with(input_previous_value(), previous)
+                        .with(input_value(), value),
+                )
+                .ok();
+        }
+    }
+}
+
+fn persist_override(world: &World, key: String, value: Option<InputBindingOverride>) {
+    let Some(assets) = world.resource_opt(asset_cache()) else {
+        return;
+    };
+    let mut settings = SettingsKey.get(assets);
+    match value {
+        Some(value) => settings.input.bindings.insert(key, value),
+        None => settings.input.bindings.remove(&key),
+    };
+
+    #[cfg(not(target_os = "unknown"))]
+    if let Err(err) = settings.write_to_file(None) {
+        tracing::warn!("Failed to persist input bindings: {err:?}");
+    }
+    SettingsKey.insert(assets, settings);
+}
+
+#[cfg(test)]
+mod tests {
+    use ambient_shared_types::{GamepadAxis, GamepadButton, VirtualKeyCode};
+
+    use super::*;
+
+    const SPACE: InputBinding = InputBinding::Key(VirtualKeyCode::Space);
+    const W: InputBinding = InputBinding::Key(VirtualKeyCode::W);
+    const S: InputBinding = InputBinding::Key(VirtualKeyCode::S);
+    const SOUTH: InputBinding = InputBinding::GamepadButton(GamepadButton::South);
+    const STICK_Y: InputBinding = InputBinding::GamepadAxis(GamepadAxis::LeftStickY);
+
+    fn poll(device: &mut VirtualDevice, world: &World) -> InputState {
+        let mut state = InputState::default();
+        device.poll(world, &mut state);
+        state
+    }
+
+    #[test]
+    fn virtual_device_replays_script() {
+        let world = World::new("test", ambient_ecs::WorldContext::App);
+        let mut device = VirtualDevice::new()
+            .press(SPACE)
+            .wait(1)
+            .release(SPACE)
+            .press(SOUTH);
+
+        assert_eq!(poll(&mut device, &world).action(&[SPACE, SOUTH]), 1.0);
+        assert!(poll(&mut device, &world).is_pressed(&SPACE));
+
+        let state = poll(&mut device, &world);
+        assert!(!state.is_pressed(&SPACE));
+        assert_eq!(state.action(&[SPACE, SOUTH]), 1.0);
+        assert_eq!(state.action(&[SPACE]), 0.0);
+        assert!(device.is_finished());
+    }
+
+    #[test]
+    fn axes_combine_digital_and_analog_bindings() {
+        let world = World::new("test", ambient_ecs::WorldContext::App);
+        let mut device = VirtualDevice::new()
+            .press(W)
+            .wait(0)
+            .press(S)
+            .wait(0)
+            .release(W)
+            .release(S)
+            .set(STICK_Y, -0.4)
+            .wait(0);
+
+        let positive = [W, STICK_Y];
+        let negative = [S];
+        assert_eq!(poll(&mut device, &world).axis(&positive, &negative), 1.0);
+        assert_eq!(poll(&mut device, &world).axis(&positive, &negative), 0.0);
+        assert_eq!(poll(&mut device, &world).axis(&positive, &negative), -0.4);
+    }
+
+    #[test]
+    fn bindings_are_parsed_when_they_change() {
+        ambient_ecs::init_components();
+        let mut world = World::new("test", ambient_ecs::WorldContext::App);
+        world.add_resource(world_events(), Default::default());
+        let mut system = InputActionSystem::new(vec![Box::new(VirtualDevice::new().press(W))]);
+        let id = world.spawn(
+            Entity::new()
+                .with(input_action(), "jump".to_string())
+                .with(input_bindings(), vec!["Space".to_string()]),
+        );
+        system.run(&mut world, &FrameEvent);
+        assert_eq!(world.get(id, input_value()).unwrap(), 0.0);
+
+        world
+            .set(id, input_bindings(), vec!["W".to_string()])
+            .unwrap();
+        system.run(&mut world, &FrameEvent);
+        assert_eq!(world.get(id, input_value()).unwrap(), 1.0);
+        assert_eq!(system.parsed[&id].positive, [W]);
+
+        // Invalid bindings disable the action
+        world
+            .set(id, input_bindings(), vec!["Nope".to_string()])
+            .unwrap();
+        system.run(&mut world, &FrameEvent);
+        assert!(system.parsed.is_empty());
+
+        world
+            .set(id, input_bindings(), vec!["W".to_string()])
+            .unwrap();
+        system.run(&mut world, &FrameEvent);
+        world.despawn(id);
+        system.run(&mut world, &FrameEvent);
+        assert!(system.parsed.is_empty());
+    }
+
+    #[test]
+    fn actions_use_threshold_for_analog_bindings() {
+        let mut state = InputState::default();
+        let trigger = InputBinding::GamepadAxis(GamepadAxis::RightTrigger);
+        state.set(trigger, 0.3);
+        assert_eq!(state.action(&[trigger]), 0.0);
+        state.set(trigger, 0.8);
+        assert_eq!(state.action(&[trigger]), 1.0);
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, time::Duration};
+
+use ambient_ecs::World;
+use ambient_shared_types::{GamepadAxis, GamepadButton, InputBinding};
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+use crate::actions::{InputDevice, InputState};
+
+/// Reads all connected gamepads through `gilrs`. Input from every gamepad is merged, as there is only one local player.
+///
+/// `gilrs` is not `Send` on all platforms, so it is polled on a dedicated thread that forwards changes to this device.
+#[derive(Debug)]
+pub struct GamepadDevice {
+    rx: flume::Receiver<(InputBinding, f32)>,
+    values: HashMap<InputBinding, f32>,
+}
+
+impl GamepadDevice {
+    /// Starts listening for gamepads. Returns `None` if the platform's gamepad backend could not be initialized.
+    pub fn new() -> Option<Self> {
+        let (tx, rx) = flume::unbounded();
+        let (init_tx, init_rx) = flume::bounded(1);
+
+        std::thread::Builder::new()
+            .name("gamepad".to_string())
+            .spawn(move || {
+                let mut gilrs = match Gilrs::new() {
+                    Ok(gilrs) => {
+                        let _ = init_tx.send(true);
+                        gilrs
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed to initialize gamepad support: {err}");
+                        let _ = init_tx.send(false);
+                        return;
+                    }
+                };
+
+                loop {
+                    let Some(event) = gilrs.next_event_blocking(Some(Duration::from_millis(100)))
+                    else {
+                        if tx.is_disconnected() {
+                            return;
+                        }
+                        continue;
+                    };
+
+                    let change = match event.event {
+                        EventType::ButtonChanged(button, value, _) => {
+                            convert_button(button).map(|b| (InputBinding::GamepadButton(b), value))
+                        }
+                        EventType::AxisChanged(axis, value, _) => {
+                            convert_axis(axis).map(|a| (InputBinding::GamepadAxis(a), value))
+                        }
+                        _ => None,
+                    };
+                    let changes = change.into_iter().chain(trigger_axis(&event.event));
+
+                    for change in changes {
+                        if tx.send(change).is_err() {
+                            return;
+                        }
+                    }
+                }
+            })
+            .ok()?;
+
+        init_rx.recv().unwrap_or(false).then_some(Self {
+            rx,
+            values: HashMap::new(),
+        })
+    }
+}
+
+impl InputDevice for GamepadDevice {
+    fn poll(&mut self, _world: &World, state: &mut InputState) {
+        for (binding, value) in self.rx.try_iter() {
+            self.values.insert(binding, value);
+        }
+        for (&binding, &value) in &self.values {
+            state.set(binding, value);
+        }
+    }
+}
+
+fn convert_button(button: Button) -> Option<GamepadButton> {
+    Some(match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::LeftTrigger => GamepadButton::LeftBumper,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        Button::RightTrigger => GamepadButton::RightBumper,
+        Button::RightTrigger2 => GamepadButton::RightTrigger,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftThumb,
+        Button::RightThumb => GamepadButton::RightThumb,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        Button::C | Button::Z | Button::Unknown => return None,
+    })
+}
+
+fn convert_axis(axis: Axis) -> Option<GamepadAxis> {
+    Some(match axis {
+        Axis::LeftStickX => GamepadAxis::LeftStickX,
+        Axis::LeftStickY => GamepadAxis::LeftStickY,
+        Axis::RightStickX => GamepadAxis::RightStickX,
+        Axis::RightStickY => GamepadAxis::RightStickY,
+        _ => return None,
+    })
+}
+
+/// Most controllers report their triggers as analog buttons; expose them as axes as well.
+fn trigger_axis(event: &EventType) -> Option<(InputBinding, f32)> {
+    match *event {
+        EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+            Some((InputBinding::GamepadAxis(GamepadAxis::LeftTrigger), value))
+        }
+        EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+            Some((InputBinding::GamepadAxis(GamepadAxis::RightTrigger), value))
+        }
+        _ => None,
+    }
+}
@@ -8,10 +8,13 @@ use glam::{vec2, Vec2};
 use serde::{Deserialize, Serialize};
 use winit::event::ModifiersState;
 pub use winit::event::{
-    DeviceEvent, ElementState, Event, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode,
-    WindowEvent,
+    DeviceEvent, ElementState, Event, Ime, KeyboardInput, MouseButton, MouseScrollDelta,
+    VirtualKeyCode, WindowEvent,
 };
 
+pub mod actions;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
 pub mod picking;
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -110,6 +113,24 @@ impl System<Event<'static, ()>> for InputSystem {
                         .resource_mut(world_events())
                         .add_message(messages::WindowKeyboardCharacter::new(c.to_string()));
                 }
+                WindowEvent::Ime(ime) => match ime {
+                    Ime::Preedit(text, _) => {
+                        world
+                            .resource_mut(world_events())
+                            .add_message(messages::WindowImeComposition::new(text.clone()));
+                    }
+                    Ime::Commit(text) => {
+                        let events = world.resource_mut(world_events());
+                        events.add_message(messages::WindowImeComposition::new(String::new()));
+                        events.add_message(messages::WindowKeyboardCharacter::new(text.clone()));
+                    }
+                    Ime::Disabled => {
+                        world
+                            .resource_mut(world_events())
+                            .add_message(messages::WindowImeComposition::new(String::new()));
+                    }
+                    Ime::Enabled => {}
+                },
 
                 WindowEvent::ModifiersChanged(mods) => {
                     self.modifiers = *mods;
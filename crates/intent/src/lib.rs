@@ -47,6 +47,15 @@ components!("intent", {
     @[Debuggable, Networked, Store]
     intent_no_state: (),
 
+    /// A group of intents which are applied, reverted and redone as a single unit.
+    ///
+    /// If any of the intents fails, the already applied intents are rolled back.
+    @[Debuggable, Networked, Store]
+    intent_transaction: Vec<Entity>,
+    /// The revert states of the applied members of a transaction, in application order
+    @[Debuggable, Networked, Store]
+    intent_transaction_revert: Vec<Entity>,
+
     @[Debuggable, Resource]
     intent_registry: Arc<IntentRegistry>,
 
@@ -97,6 +106,79 @@ pub async fn server_push_intent<T: ComponentValue>(
     push_intent(state, user_id, create_intent(intent_arg, arg, collapse_id));
 }
 
+/// A group of intents which will be pushed as a single undo step.
+///
+/// Created through [`IntentTransaction::begin`], and either pushed with
+/// [`IntentTransaction::client_commit`]/[`IntentTransaction::server_commit`] or discarded with
+/// [`IntentTransaction::abort`].
+#[derive(Debug, Clone, Default)]
+pub struct IntentTransaction {
+    intents: Vec<Entity>,
+}
+
+impl IntentTransaction {
+    pub fn begin() -> Self {
+        Self::default()
+    }
+
+    /// Adds an intent to the transaction. Intents are applied in the order they were added.
+    pub fn push<T: ComponentValue>(&mut self, intent_arg: Component<T>, arg: T) -> &mut Self {
+        self.intents.push(create_intent(intent_arg, arg, None));
+        self
+    }
+
+    pub fn with<T: ComponentValue>(mut self, intent_arg: Component<T>, arg: T) -> Self {
+        self.push(intent_arg, arg);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.intents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intents.is_empty()
+    }
+
+    /// Returns the intents of the transaction, to be used as the argument of an
+    /// [`intent_transaction`] intent
+    pub fn into_intents(self) -> Vec<Entity> {
+        self.intents
+    }
+
+    /// Discards the transaction without applying any of its intents
+    pub fn abort(self) {
+        tracing::debug!("Aborted transaction of {} intents", self.intents.len());
+    }
+
+    pub async fn client_commit(
+        self,
+        client_state: ClientState,
+        on_applied: Option<Box<dyn Fn() + Sync + Send + 'static>>,
+    ) {
+        if self.is_empty() {
+            return;
+        }
+
+        client_push_intent(
+            client_state,
+            intent_transaction(),
+            self.intents,
+            None,
+            on_applied,
+        )
+        .await
+    }
+
+    pub async fn server_commit(self, state: SharedServerState, user_id: String) {
+        if self.is_empty() {
+            return;
+        }
+
+        server_push_intent(state, intent_transaction(), self.intents, user_id, None).await
+    }
+}
+
 pub async fn rpc_push_intent(args: ServerRpcArgs, intent: Entity) -> Option<EntityId> {
     Some(push_intent(args.state, args.user_id, intent))
 }
@@ -7,13 +7,14 @@ use ambient_network::{
     assert_networked,
     server::{ServerState, SharedServerState},
 };
+use anyhow::{bail, Context};
 use futures::Future;
 use parking_lot::MutexGuard;
 
 use crate::{
     common_intent_systems, intent, intent_applied, intent_failed, intent_id, intent_id_index,
     intent_index, intent_index_applied, intent_index_reverted, intent_reverted, intent_success,
-    logic::get_head_applied_intent,
+    intent_transaction, intent_transaction_revert, logic::get_head_applied_intent,
 };
 
 pub struct IntentContext<'a> {
@@ -80,6 +81,12 @@ trait Handler<'a>: 'static + Send + Sync {
     fn apply(&'a self, ctx: IntentContext<'a>, id: EntityId);
     fn revert(&'a self, ctx: IntentContext<'a>, id: EntityId);
     fn merge(&self, ctx: &mut IntentContext<'_>, a: EntityId, b: EntityId);
+    /// Applies an intent which is part of a transaction, and thus not spawned in the world.
+    ///
+    /// Returns an entity containing the revert state.
+    fn apply_member(&self, ctx: IntentContext<'_>, data: &Entity) -> anyhow::Result<Entity>;
+    /// Reverts a transaction member using the state returned by [`Handler::apply_member`]
+    fn revert_member(&self, ctx: IntentContext<'_>, state: &Entity) -> anyhow::Result<()>;
 }
 
 pub struct IntentHandler<Arg: ComponentValue, RevertState: ComponentValue, Apply, Revert, Merge> {
@@ -176,55 +183,13 @@ where
                 let world = &mut ctx.world;
                 world.despawn(head).unwrap();
 
-                world.sync_index(
-                    intent_id_index(),
-                    head,
-                    ArchetypeFilter::new().excl(intent_reverted()),
-                );
-                world.sync_index(
-                    intent_index(),
-                    head,
-                    ArchetypeFilter::new().excl(intent_reverted()),
-                );
-                world.sync_index(
-                    intent_index_reverted(),
-                    head,
-                    ArchetypeFilter::new().incl(intent_reverted()),
-                );
-                world.sync_index(
-                    intent_index_applied(),
-                    head,
-                    ArchetypeFilter::new()
-                        .incl(intent_applied())
-                        .excl(intent_reverted()),
-                );
+                sync_intent_indices(world, head);
             }
         }
 
         let world = &mut ctx.world;
         // Update the indices
-        world.sync_index(
-            intent_id_index(),
-            id,
-            ArchetypeFilter::new().excl(intent_reverted()),
-        );
-        world.sync_index(
-            intent_index(),
-            id,
-            ArchetypeFilter::new().excl(intent_reverted()),
-        );
-        world.sync_index(
-            intent_index_reverted(),
-            id,
-            ArchetypeFilter::new().incl(intent_reverted()),
-        );
-        world.sync_index(
-            intent_index_applied(),
-            id,
-            ArchetypeFilter::new()
-                .incl(intent_applied())
-                .excl(intent_reverted()),
-        );
+        sync_intent_indices(world, id);
     }
 
     fn revert(&'a self, mut ctx: IntentContext<'a>, id: EntityId) {
@@ -265,28 +230,7 @@ where
         }
 
         // Update the indices
-        world.sync_index(
-            intent_id_index(),
-            id,
-            ArchetypeFilter::new().excl(intent_reverted()),
-        );
-        world.sync_index(
-            intent_index(),
-            id,
-            ArchetypeFilter::new().excl(intent_reverted()),
-        );
-        world.sync_index(
-            intent_index_reverted(),
-            id,
-            ArchetypeFilter::new().incl(intent_reverted()),
-        );
-        world.sync_index(
-            intent_index_applied(),
-            id,
-            ArchetypeFilter::new()
-                .incl(intent_applied())
-                .excl(intent_reverted()),
-        );
+        sync_intent_indices(world, id);
     }
 
     fn merge(&self, ctx: &mut IntentContext<'_>, a: EntityId, b: EntityId) {
@@ -308,6 +252,25 @@ where
         *world.get_mut(b, self.intent).unwrap() = arg;
         *world.get_mut(b, self.intent_revert).unwrap() = state;
     }
+
+    fn apply_member(&self, ctx: IntentContext<'_>, data: &Entity) -> anyhow::Result<Entity> {
+        let arg = data.get_cloned(self.intent).with_context(|| {
+            format!("Transaction member is missing intent arg for {}", self.name)
+        })?;
+
+        let state = (self.apply)(ctx, arg)?;
+        Ok(Entity::new()
+            .with(intent(), self.intent.index())
+            .with(self.intent_revert, state))
+    }
+
+    fn revert_member(&self, ctx: IntentContext<'_>, state: &Entity) -> anyhow::Result<()> {
+        let state = state
+            .get_cloned(self.intent_revert)
+            .with_context(|| format!("Transaction member {} was not applied", self.name))?;
+
+        (self.revert)(ctx, state)
+    }
 }
 
 pub struct IntentRegistry {
@@ -396,6 +359,10 @@ impl IntentRegistry {
     }
 
     pub fn get_intent_name(&self, intent: u32) -> Option<String> {
+        if intent == intent_transaction().index() {
+            return Some(intent_transaction().path());
+        }
+
         Some(self.handlers.get(&intent)?.name().to_string())
     }
 
@@ -417,6 +384,11 @@ impl IntentRegistry {
             assert!(ctx.world.exists(head), "Head intent does not exist");
         }
 
+        if intent_arg == intent_transaction().index() {
+            self.apply_transaction(ctx, id);
+            return;
+        }
+
         // Check if it is possible to collapse the intents
         let handler = self
             .handlers
@@ -443,6 +415,11 @@ impl IntentRegistry {
         let mut guard = state.lock();
         let ctx = IntentContext::from_guard(&mut guard, user_id);
 
+        if intent_arg == intent_transaction().index() {
+            self.revert_transaction(ctx, id);
+            return;
+        }
+
         let handler = self
             .handlers
             .get(&intent_arg)
@@ -456,6 +433,165 @@ impl IntentRegistry {
     }
 }
 
+impl IntentRegistry {
+    /// Applies each member of the transaction in order.
+    ///
+    /// If any member fails, the already applied members are reverted in reverse order and the
+    /// whole transaction is marked as failed.
+    fn apply_transaction(&self, ctx: IntentContext<'_>, id: EntityId) {
+        let IntentContext { world, user_id } = ctx;
+        if world.has_component(id, intent_applied()) {
+            panic!("Intent applied twice");
+        }
+
+        let members = world
+            .get_cloned(id, intent_transaction())
+            .expect("Transaction is missing its members");
+
+        let mut applied = Vec::with_capacity(members.len());
+        let mut result = Ok(());
+        for member in &members {
+            let handler = match self.member_handler(member) {
+                Ok(handler) => handler,
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            };
+
+            match handler.apply_member(IntentContext { world, user_id }, member) {
+                Ok(state) => applied.push(state),
+                Err(err) => {
+                    result = Err(err.context(format!("Failed to apply {}", handler.name())));
+                    break;
+                }
+            }
+        }
+
+        match result {
+            Ok(()) => {
+                world
+                    .add_components(
+                        id,
+                        Entity::new()
+                            .with(
+                                intent_applied(),
+                                format!("transaction of {}", applied.len()),
+                            )
+                            .with(intent_success(), ())
+                            .with(intent_transaction_revert(), applied),
+                    )
+                    .unwrap();
+            }
+            Err(err) => {
+                tracing::error!("Failed to apply transaction: {id}\n\n{err:?}");
+
+                // Roll back what has been applied so far, leaving the world as it was
+                if let Err(rollback_err) =
+                    self.revert_members(IntentContext { world, user_id }, &applied)
+                {
+                    tracing::error!("Failed to roll back transaction: {id}\n\n{rollback_err:?}");
+                }
+
+                world
+                    .add_components(
+                        id,
+                        Entity::new()
+                            .with(intent_applied(), format!("failed: {err:#}"))
+                            .with(intent_failed(), format!("{err:#}")),
+                    )
+                    .unwrap();
+            }
+        }
+
+        sync_intent_indices(world, id);
+    }
+
+    fn revert_transaction(&self, ctx: IntentContext<'_>, id: EntityId) {
+        let IntentContext { world, user_id } = ctx;
+
+        // Undoing a failed transaction is always a success, as it was rolled back when applied
+        let result = match world.get_cloned(id, intent_transaction_revert()) {
+            Ok(applied) => self.revert_members(IntentContext { world, user_id }, &applied),
+            Err(_) => Ok(()),
+        };
+
+        let mut data = Entity::new().with(intent_reverted(), ());
+        if let Err(err) = result {
+            tracing::error!("Failed to revert transaction: {id} {err:?}");
+            data.set(intent_failed(), format!("{err:#}"));
+        }
+
+        world.add_components(id, data).unwrap();
+
+        sync_intent_indices(world, id);
+    }
+
+    /// Reverts the applied members of a transaction in reverse order.
+    ///
+    /// All members are reverted even if one of them fails, and the first error is returned.
+    fn revert_members(&self, ctx: IntentContext<'_>, applied: &[Entity]) -> anyhow::Result<()> {
+        let IntentContext { world, user_id } = ctx;
+        let mut result = Ok(());
+        for state in applied.iter().rev() {
+            let res = self.member_handler(state).and_then(|handler| {
+                handler
+                    .revert_member(IntentContext { world, user_id }, state)
+                    .with_context(|| format!("Failed to revert {}", handler.name()))
+            });
+
+            if let Err(err) = res {
+                tracing::error!("{err:?}");
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn member_handler(&self, member: &Entity) -> anyhow::Result<&dyn for<'x> Handler<'x>> {
+        let intent_arg = member
+            .get(intent())
+            .context("Transaction member is missing intent kind")?;
+
+        if intent_arg == intent_transaction().index() {
+            bail!("Transactions can not be nested");
+        }
+
+        self.handlers
+            .get(&intent_arg)
+            .map(|v| &**v)
+            .with_context(|| format!("No handler for intent {intent_arg}"))
+    }
+}
+
+fn sync_intent_indices(world: &mut World, id: EntityId) {
+    world.sync_index(
+        intent_id_index(),
+        id,
+        ArchetypeFilter::new().excl(intent_reverted()),
+    );
+    world.sync_index(
+        intent_index(),
+        id,
+        ArchetypeFilter::new().excl(intent_reverted()),
+    );
+    world.sync_index(
+        intent_index_reverted(),
+        id,
+        ArchetypeFilter::new().incl(intent_reverted()),
+    );
+    world.sync_index(
+        intent_index_applied(),
+        id,
+        ArchetypeFilter::new()
+            .incl(intent_applied())
+            .excl(intent_reverted()),
+    );
+}
+
 pub fn registry_systems() -> SystemGroup {
     SystemGroup::new("intents/registry", vec![Box::new(common_intent_systems())])
 }
@@ -4,9 +4,9 @@ use ambient_ecs::{
     components, query, query_mut, Entity, EntityId, FrameEvent, Networked, Store, System, World,
 };
 use ambient_intent::{
-    common_intent_systems, intent_registry,
+    common_intent_systems, intent_failed, intent_registry, intent_success, intent_transaction,
     logic::{create_intent, push_intent, redo_intent, undo_head},
-    use_old_state, IntentRegistry,
+    use_old_state, IntentRegistry, IntentTransaction,
 };
 use ambient_native_std::{asset_cache::AssetCache, friendly_id};
 use ambient_network::{
@@ -727,3 +727,113 @@ async fn collapse() {
         assert!(world.exists(y));
     }
 }
+
+#[tokio::test]
+async fn transaction() {
+    init_components();
+    ambient_intent::init_components();
+
+    let user_id = "user1".to_string();
+    let state = setup_state();
+    let mut reg = IntentRegistry::new();
+    {
+        let mut guard = state.lock();
+        let world = guard.get_player_world_mut(&user_id).unwrap();
+        register_intents(&mut reg);
+        world.add_resource(intent_registry(), Arc::new(reg));
+    }
+
+    // Create test entities
+    let mut values = create_test_entities(&state, &user_id);
+
+    let a = push_intent(
+        state.clone(),
+        user_id.clone(),
+        create_intent(intent_add(), 1.0, None),
+    );
+
+    IntentTransaction::begin()
+        .with(intent_add(), 2.0)
+        .with(intent_mul(), 3.0)
+        .server_commit(state.clone(), user_id.clone())
+        .await;
+
+    {
+        let guard = state.lock();
+        let world = guard.get_player_world(&user_id).unwrap();
+        values
+            .values_mut()
+            .for_each(|v| *v = (*v + 1.0 + 2.0) * 3.0);
+        assert_eq!(values, as_map(world));
+    }
+
+    // The whole transaction is reverted as a single step
+    let b = undo_head(state.clone(), &user_id).unwrap();
+    assert_ne!(a, b);
+
+    {
+        let guard = state.lock();
+        let world = guard.get_player_world(&user_id).unwrap();
+        values.values_mut().for_each(|v| *v = *v / 3.0 - 2.0);
+        assert_eq!(values, as_map(world));
+    }
+
+    assert_eq!(redo_intent(state.clone(), &user_id).await, Some(b));
+
+    {
+        let guard = state.lock();
+        let world = guard.get_player_world(&user_id).unwrap();
+        values.values_mut().for_each(|v| *v = (*v + 2.0) * 3.0);
+        assert_eq!(values, as_map(world));
+    }
+}
+
+#[tokio::test]
+async fn transaction_rollback() {
+    init_components();
+    ambient_intent::init_components();
+
+    let user_id = "user1".to_string();
+    let state = setup_state();
+    let mut reg = IntentRegistry::new();
+    {
+        let mut guard = state.lock();
+        let world = guard.get_player_world_mut(&user_id).unwrap();
+        register_intents(&mut reg);
+        world.add_resource(intent_registry(), Arc::new(reg));
+    }
+
+    // Create test entities
+    let values = create_test_entities(&state, &user_id);
+
+    let a = push_intent(
+        state.clone(),
+        user_id.clone(),
+        create_intent(
+            intent_transaction(),
+            IntentTransaction::begin()
+                .with(intent_add(), 2.0)
+                .with(intent_mul(), 3.0)
+                .with(intent_fail(), ())
+                .into_intents(),
+            None,
+        ),
+    );
+
+    {
+        let guard = state.lock();
+        let world = guard.get_player_world(&user_id).unwrap();
+        assert_eq!(values, as_map(world));
+        assert!(world.has_component(a, intent_failed()));
+        assert!(!world.has_component(a, intent_success()));
+    }
+
+    // Undoing a rolled back transaction does not touch the world
+    assert_eq!(undo_head(state.clone(), &user_id), Some(a));
+
+    {
+        let guard = state.lock();
+        let world = guard.get_player_world(&user_id).unwrap();
+        assert_eq!(values, as_map(world));
+    }
+}
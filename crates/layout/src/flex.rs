@@ -0,0 +1,583 @@
+use ambient_core::transform::{local_to_parent, translation};
+use ambient_ecs::{components, Component, EntityId, World};
+use glam::{vec2, Vec2};
+use itertools::Itertools;
+
+use crate::{
+    align_content, align_horizontal, align_vertical, fit_horizontal, fit_vertical, flex_basis,
+    flex_grow, flex_shrink, flex_wrap, gap, height, invalidate_parent_layout, justify_content,
+    margin, max_height, max_width, min_height, min_width, orientation, padding,
+    space_between_items, width, Align, Borders, Fit, Justify, Orientation, Z_DELTA,
+};
+
+components!("layout", {
+    /// The size of a flex item along the main axis before the layout grew or shrank it.
+    flex_natural_size: f32,
+    /// The size along the main axis the layout last gave a flex item. When the item's size no
+    /// longer matches it, its author or the text system resized it and it is the new natural size.
+    flex_assigned_size: f32,
+});
+
+/// Splits free space according to `justify`, returning the space before the first item, the
+/// space between items and the extra size given to each item (only for [Justify::Stretch]).
+pub(crate) fn distribute(justify: Justify, free: f32, count: usize) -> (f32, f32, f32) {
+    if count == 0 {
+        return (0., 0., 0.);
+    }
+    let count = count as f32;
+    match justify {
+        Justify::Begin => (0., 0., 0.),
+        Justify::Center => (free / 2., 0., 0.),
+        Justify::End => (free, 0., 0.),
+        // Like CSS, the space-* modes fall back to packing at the beginning when overflowing
+        Justify::SpaceBetween if count > 1. => (0., free.max(0.) / (count - 1.), 0.),
+        Justify::SpaceBetween => (0., 0., 0.),
+        Justify::SpaceAround => {
+            let space = free.max(0.) / count;
+            (space / 2., space, 0.)
+        }
+        Justify::SpaceEvenly => {
+            let space = free.max(0.) / (count + 1.);
+            (space, space, 0.)
+        }
+        Justify::Stretch => (0., 0., free.max(0.) / count),
+    }
+}
+
+/// An item of a flex line, measured along the main axis of the layout.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FlexItem {
+    pub basis: f32,
+    pub grow: f32,
+    pub shrink: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Size of the margins along the main axis.
+    pub margin: f32,
+    /// Items that fit their children keep their size.
+    pub rigid: bool,
+}
+
+/// Resolves the main sizes of the items of a line by growing or shrinking them to fill
+/// `available`. Items that hit their min or max size are frozen, and the remaining space is
+/// distributed again between the others.
+pub(crate) fn resolve_flexible(items: &[FlexItem], gap: f32, available: f32) -> Vec<f32> {
+    let mut sizes = items
+        .iter()
+        .map(|item| {
+            if item.rigid {
+                item.basis
+            } else {
+                item.basis.clamp(item.min, item.max)
+            }
+        })
+        .collect_vec();
+    let available = available
+        - items.iter().map(|item| item.margin).sum::<f32>()
+        - gap * items.len().saturating_sub(1) as f32;
+    let growing = sizes.iter().sum::<f32>() < available;
+    let mut frozen = items
+        .iter()
+        .map(|item| {
+            let factor = if growing { item.grow } else { item.shrink };
+            item.rigid || factor <= 0.
+        })
+        .collect_vec();
+    loop {
+        let free = available
+            - items
+                .iter()
+                .zip(&sizes)
+                .zip(&frozen)
+                .map(|((item, size), frozen)| if *frozen { *size } else { item.basis })
+                .sum::<f32>();
+        let factor = |item: &FlexItem| {
+            if growing {
+                item.grow
+            } else {
+                item.shrink * item.basis
+            }
+        };
+        let total_factor = items
+            .iter()
+            .zip(&frozen)
+            .filter(|(_, frozen)| !**frozen)
+            .map(|(item, _)| factor(item))
+            .sum::<f32>();
+        if total_factor <= 0. {
+            break;
+        }
+        let mut violated = false;
+        for ((item, size), frozen) in items.iter().zip(&mut sizes).zip(&mut frozen) {
+            if *frozen {
+                continue;
+            }
+            let target = item.basis + free * factor(item) / total_factor;
+            *size = target.clamp(item.min, item.max);
+            if *size != target {
+                *frozen = true;
+                violated = true;
+            }
+        }
+        if !violated {
+            break;
+        }
+    }
+    sizes
+}
+
+/// Returns the size of `id` before growing or shrinking, so that the sizes the layout assigns
+/// don't feed back into it.
+fn natural_size(world: &mut World, id: EntityId, size: f32) -> f32 {
+    if let (Ok(natural), Ok(assigned)) = (
+        world.get(id, flex_natural_size()),
+        world.get(id, flex_assigned_size()),
+    ) {
+        if assigned == size {
+            return natural;
+        }
+    }
+    if world.set_if_changed(id, flex_natural_size(), size).is_err() {
+        world.add_component(id, flex_natural_size(), size).ok();
+    }
+    size
+}
+
+fn main_axis(value: Vec2, orientation: Orientation) -> f32 {
+    match orientation {
+        Orientation::Horizontal => value.x,
+        Orientation::Vertical => value.y,
+    }
+}
+fn cross_axis(value: Vec2, orientation: Orientation) -> f32 {
+    match orientation {
+        Orientation::Horizontal => value.y,
+        Orientation::Vertical => value.x,
+    }
+}
+fn from_axes(main: f32, cross: f32, orientation: Orientation) -> Vec2 {
+    match orientation {
+        Orientation::Horizontal => vec2(main, cross),
+        Orientation::Vertical => vec2(cross, main),
+    }
+}
+
+struct Child {
+    id: EntityId,
+    item: FlexItem,
+    margin_begin: Vec2,
+    margin_size: Vec2,
+    fit_cross: Fit,
+    cross: f32,
+}
+
+#[profiling::function]
+pub(crate) fn flex_layout(world: &mut World, id: EntityId, children: Vec<EntityId>) {
+    let orientation = world
+        .get(id, orientation())
+        .unwrap_or(Orientation::Horizontal);
+    let (main_size, cross_size, main_fit, cross_fit, main_min, main_max, cross_align) =
+        match orientation {
+            Orientation::Horizontal => (
+                width(),
+                height(),
+                fit_horizontal(),
+                fit_vertical(),
+                min_width(),
+                max_width(),
+                align_vertical(),
+            ),
+            Orientation::Vertical => (
+                height(),
+                width(),
+                fit_vertical(),
+                fit_horizontal(),
+                min_height(),
+                max_height(),
+                align_horizontal(),
+            ),
+        };
+    let self_padding: Borders = world
+        .get(id, padding())
+        .unwrap_or(Borders::ZERO.into())
+        .into();
+    let self_size = vec2(
+        world.get(id, width()).unwrap_or(0.),
+        world.get(id, height()).unwrap_or(0.),
+    );
+    let self_fit_main = world.get(id, main_fit).unwrap_or(Fit::None);
+    let self_fit_cross = world.get(id, cross_fit).unwrap_or(Fit::None);
+    let wrap = world.get(id, flex_wrap()).unwrap_or(false);
+    let justify = world.get(id, justify_content()).unwrap_or(Justify::Begin);
+    let align_lines = world.get(id, align_content()).unwrap_or(Justify::Begin);
+    let align_items = world.get(id, cross_align).unwrap_or(Align::Begin);
+    let space_between_items = world.get(id, space_between_items()).unwrap_or(0.);
+    let gap = world
+        .get(id, gap())
+        .unwrap_or(Vec2::splat(space_between_items));
+    let (main_gap, cross_gap) = (main_axis(gap, orientation), cross_axis(gap, orientation));
+    let padding_begin = self_padding.offset();
+    let padding_size = self_padding.border_size();
+
+    // Without a size along the main axis there is nothing to wrap, grow or shrink into
+    let available_main = if self_fit_main == Fit::Children {
+        None
+    } else {
+        Some(main_axis(self_size - padding_size, orientation))
+    };
+    let available_cross = if self_fit_cross == Fit::Children {
+        None
+    } else {
+        Some(cross_axis(self_size - padding_size, orientation))
+    };
+
+    let children = children
+        .iter()
+        .filter(|id| world.has_component(**id, local_to_parent()))
+        .copied()
+        .collect_vec();
+    let children = children
+        .into_iter()
+        .map(|c| {
+            let child_margin: Borders = world
+                .get(c, margin())
+                .unwrap_or(Borders::ZERO.into())
+                .into();
+            let rigid = world.get(c, main_fit).unwrap_or(Fit::None) == Fit::Children;
+            let size = world.get(c, main_size).unwrap_or(0.);
+            let basis = if rigid {
+                size
+            } else if let Ok(basis) = world.get(c, flex_basis()) {
+                basis
+            } else {
+                natural_size(world, c, size)
+            };
+            let fit_cross = world.get(c, cross_fit).unwrap_or(Fit::None);
+            let margin_size = child_margin.border_size();
+            Child {
+                id: c,
+                item: FlexItem {
+                    basis,
+                    grow: world.get(c, flex_grow()).unwrap_or(0.),
+                    shrink: world.get(c, flex_shrink()).unwrap_or(1.),
+                    min: world.get(c, main_min).unwrap_or(0.),
+                    max: world.get(c, main_max).unwrap_or(f32::INFINITY),
+                    margin: main_axis(margin_size, orientation),
+                    rigid,
+                },
+                margin_begin: child_margin.offset(),
+                margin_size,
+                fit_cross,
+                // Items stretched across the line don't contribute to its size
+                cross: if fit_cross == Fit::Parent {
+                    0.
+                } else {
+                    world.get(c, cross_size).unwrap_or(0.) + cross_axis(margin_size, orientation)
+                },
+            }
+        })
+        .collect_vec();
+
+    let mut lines: Vec<Vec<Child>> = Vec::new();
+    let mut line_main = 0.;
+    for child in children {
+        let outer = child.item.basis + child.item.margin;
+        let fits = match available_main {
+            Some(available) => line_main + main_gap + outer <= available,
+            None => true,
+        };
+        match lines.last_mut() {
+            Some(line) if !wrap || fits => {
+                line_main += main_gap + outer;
+                line.push(child);
+            }
+            _ => {
+                line_main = outer;
+                lines.push(vec![child]);
+            }
+        }
+    }
+
+    let resolved = lines
+        .iter()
+        .map(|line| {
+            let items = line.iter().map(|child| child.item).collect_vec();
+            let sizes = match available_main {
+                Some(available) => resolve_flexible(&items, main_gap, available),
+                None => items.iter().map(|item| item.basis).collect_vec(),
+            };
+            let used = sizes.iter().sum::<f32>()
+                + items.iter().map(|item| item.margin).sum::<f32>()
+                + main_gap * items.len().saturating_sub(1) as f32;
+            // A single line always fills the container
+            let cross = match available_cross {
+                Some(available) if !wrap => available,
+                _ => line.iter().map(|child| child.cross).fold(0., f32::max),
+            };
+            (sizes, used, cross)
+        })
+        .collect_vec();
+
+    let content_main = resolved.iter().map(|(_, used, _)| *used).fold(0., f32::max);
+    let content_cross = resolved.iter().map(|(_, _, cross)| *cross).sum::<f32>()
+        + cross_gap * lines.len().saturating_sub(1) as f32;
+    let (mut cross_offset, cross_between, cross_extra) = distribute(
+        align_lines,
+        available_cross.unwrap_or(content_cross) - content_cross,
+        lines.len(),
+    );
+    for (line, (sizes, used, line_cross)) in lines.iter().zip(resolved) {
+        let line_cross = line_cross + cross_extra;
+        let (mut main_offset, main_between, _) = distribute(
+            if justify == Justify::Stretch {
+                Justify::Begin
+            } else {
+                justify
+            },
+            available_main.unwrap_or(content_main) - used,
+            line.len(),
+        );
+        for (child, size) in line.iter().zip(sizes) {
+            if !child.item.rigid {
+                world.set_if_changed(child.id, main_size, size).ok();
+                if world
+                    .set_if_changed(child.id, flex_assigned_size(), size)
+                    .is_err()
+                {
+                    world
+                        .add_component(child.id, flex_assigned_size(), size)
+                        .ok();
+                }
+            }
+            let margin_cross = cross_axis(child.margin_size, orientation);
+            let child_cross = if child.fit_cross == Fit::Parent {
+                let child_new_cross = line_cross - margin_cross;
+                world
+                    .set_if_changed(child.id, cross_size, child_new_cross)
+                    .ok();
+                child_new_cross
+            } else {
+                child.cross - margin_cross
+            };
+            let align_offset = match align_items {
+                Align::Begin => 0.,
+                Align::Center => (line_cross - child_cross - margin_cross) / 2.,
+                Align::End => line_cross - child_cross - margin_cross,
+            };
+            let position = padding_begin
+                + child.margin_begin
+                + from_axes(main_offset, cross_offset + align_offset, orientation).floor();
+            world
+                .set_if_changed(child.id, translation(), position.extend(Z_DELTA))
+                .ok();
+            main_offset += size + child.item.margin + main_gap + main_between;
+        }
+        cross_offset += line_cross + cross_gap + cross_between;
+    }
+
+    let new_self_size = from_axes(content_main, content_cross, orientation) + padding_size;
+    let (main_min_self, cross_min_self) = match orientation {
+        Orientation::Horizontal => (min_width(), min_height()),
+        Orientation::Vertical => (min_height(), min_width()),
+    };
+    set_size_to_children(
+        world,
+        id,
+        self_fit_main,
+        main_size,
+        main_min_self,
+        main_axis(new_self_size, orientation),
+        orientation,
+    );
+    set_size_to_children(
+        world,
+        id,
+        self_fit_cross,
+        cross_size,
+        cross_min_self,
+        cross_axis(new_self_size, orientation),
+        match orientation {
+            Orientation::Horizontal => Orientation::Vertical,
+            Orientation::Vertical => Orientation::Horizontal,
+        },
+    );
+}
+
+fn set_size_to_children(
+    world: &mut World,
+    id: EntityId,
+    fit: Fit,
+    size: Component<f32>,
+    min_size: Component<f32>,
+    new_size: f32,
+    orientation: Orientation,
+) {
+    if fit != Fit::Children {
+        return;
+    }
+    let new_size = new_size.max(world.get(id, min_size).unwrap_or(0.));
+    if world.get(id, size).unwrap_or(0.) != new_size {
+        world.set(id, size, new_size).ok();
+        invalidate_parent_layout(world, id, orientation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ambient_ecs::{Entity, WorldContext};
+    use glam::{Mat4, Vec3};
+
+    use super::*;
+
+    fn item(basis: f32) -> FlexItem {
+        FlexItem {
+            basis,
+            grow: 0.,
+            shrink: 1.,
+            min: 0.,
+            max: f32::INFINITY,
+            margin: 0.,
+            rigid: false,
+        }
+    }
+
+    #[test]
+    fn distribute_free_space() {
+        assert_eq!(distribute(Justify::Begin, 90., 3), (0., 0., 0.));
+        assert_eq!(distribute(Justify::Center, 90., 3), (45., 0., 0.));
+        assert_eq!(distribute(Justify::End, 90., 3), (90., 0., 0.));
+        assert_eq!(distribute(Justify::SpaceBetween, 90., 3), (0., 45., 0.));
+        assert_eq!(distribute(Justify::SpaceBetween, 90., 1), (0., 0., 0.));
+        assert_eq!(distribute(Justify::SpaceAround, 90., 3), (15., 30., 0.));
+        assert_eq!(distribute(Justify::SpaceEvenly, 80., 3), (20., 20., 0.));
+        assert_eq!(distribute(Justify::Stretch, 90., 3), (0., 0., 30.));
+        assert_eq!(distribute(Justify::Center, 90., 0), (0., 0., 0.));
+    }
+
+    #[test]
+    fn distribute_overflow() {
+        assert_eq!(distribute(Justify::Center, -20., 2), (-10., 0., 0.));
+        assert_eq!(distribute(Justify::End, -20., 2), (-20., 0., 0.));
+        assert_eq!(distribute(Justify::SpaceBetween, -20., 2), (0., 0., 0.));
+        assert_eq!(distribute(Justify::SpaceEvenly, -20., 2), (0., 0., 0.));
+        assert_eq!(distribute(Justify::Stretch, -20., 2), (0., 0., 0.));
+    }
+
+    #[test]
+    fn grow_by_factor() {
+        let items = [
+            FlexItem {
+                grow: 1.,
+                ..item(100.)
+            },
+            FlexItem {
+                grow: 3.,
+                ..item(100.)
+            },
+        ];
+        assert_eq!(resolve_flexible(&items, 10., 410.), vec![150., 250.]);
+    }
+
+    #[test]
+    fn grow_excludes_margins() {
+        let items = [FlexItem {
+            grow: 1.,
+            margin: 20.,
+            ..item(50.)
+        }];
+        assert_eq!(resolve_flexible(&items, 0., 100.), vec![80.]);
+    }
+
+    #[test]
+    fn shrink_by_factor_and_basis() {
+        let items = [item(100.), item(300.)];
+        assert_eq!(resolve_flexible(&items, 0., 200.), vec![50., 150.]);
+    }
+
+    #[test]
+    fn max_freezes_while_growing() {
+        let items = [
+            FlexItem {
+                grow: 1.,
+                max: 50.,
+                ..item(0.)
+            },
+            FlexItem {
+                grow: 1.,
+                ..item(0.)
+            },
+        ];
+        assert_eq!(resolve_flexible(&items, 0., 300.), vec![50., 250.]);
+    }
+
+    #[test]
+    fn min_freezes_while_shrinking() {
+        let items = [
+            FlexItem {
+                min: 80.,
+                ..item(100.)
+            },
+            item(100.),
+        ];
+        assert_eq!(resolve_flexible(&items, 0., 100.), vec![80., 20.]);
+    }
+
+    #[test]
+    fn inflexible_items_overflow() {
+        let items = [
+            FlexItem {
+                shrink: 0.,
+                ..item(100.)
+            },
+            FlexItem {
+                grow: 1.,
+                rigid: true,
+                ..item(100.)
+            },
+        ];
+        assert_eq!(resolve_flexible(&items, 0., 150.), vec![100., 100.]);
+        assert_eq!(resolve_flexible(&items, 0., 300.), vec![100., 100.]);
+    }
+
+    #[test]
+    fn natural_size_follows_the_author() {
+        ambient_ecs::init_components();
+        ambient_core::init_all_components();
+        crate::init_components();
+
+        let mut world = World::new("flex", WorldContext::Client);
+        let parent = Entity::new()
+            .with(width(), 300.)
+            .with(height(), 100.)
+            .spawn(&mut world);
+        let child = |world: &mut World, size: f32| {
+            Entity::new()
+                .with(width(), size)
+                .with(height(), 10.)
+                .with(flex_grow(), 1.)
+                .with(local_to_parent(), Mat4::IDENTITY)
+                .with(translation(), Vec3::ZERO)
+                .spawn(world)
+        };
+        let a = child(&mut world, 100.);
+        let b = child(&mut world, 50.);
+        let sizes = |world: &World| {
+            (
+                world.get(a, width()).unwrap(),
+                world.get(b, width()).unwrap(),
+            )
+        };
+
+        flex_layout(&mut world, parent, vec![a, b]);
+        assert_eq!(sizes(&world), (175., 125.));
+
+        // The grown sizes don't become the natural sizes
+        flex_layout(&mut world, parent, vec![a, b]);
+        assert_eq!(sizes(&world), (175., 125.));
+
+        // A size set outside of the layout does
+        world.set(a, width(), 200.).unwrap();
+        flex_layout(&mut world, parent, vec![a, b]);
+        assert_eq!(sizes(&world), (225., 75.));
+    }
+}
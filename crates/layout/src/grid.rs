@@ -0,0 +1,650 @@
+use ambient_core::transform::{local_to_parent, translation};
+use ambient_ecs::{EntityId, World};
+use glam::{vec2, vec3, Vec2};
+use itertools::Itertools;
+
+use crate::{
+    align_horizontal, align_vertical, fit_horizontal, fit_vertical, gap, grid_column,
+    grid_column_span, grid_columns, grid_row, grid_row_span, grid_rows, height,
+    invalidate_parent_layout, margin, min_height, min_width, padding, space_between_items, width,
+    Align, Borders, Fit, Orientation, Z_DELTA,
+};
+
+/// The size of a single column or row of a grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Track {
+    /// A fixed size in pixels.
+    Fixed(f32),
+    /// A share of the space left over by the other tracks.
+    Fraction(f32),
+    /// The size of the largest item in the track.
+    Auto,
+}
+
+/// Parses a track template such as `100 auto repeat(2, 1fr)`.
+pub(crate) fn parse_tracks(template: &str) -> Vec<Track> {
+    let mut tracks = Vec::new();
+    let mut rest = template.trim();
+    while !rest.is_empty() {
+        if let Some(repeat) = rest.strip_prefix("repeat(") {
+            let Some(end) = repeat.find(')') else {
+                tracing::warn!("Unterminated repeat in grid template: {template}");
+                break;
+            };
+            match repeat[..end].split_once(',') {
+                Some((count, inner)) => match count.trim().parse::<usize>() {
+                    Ok(count) => {
+                        let inner = parse_tracks(inner);
+                        for _ in 0..count {
+                            tracks.extend_from_slice(&inner);
+                        }
+                    }
+                    Err(_) => tracing::warn!("Invalid repeat count in grid template: {template}"),
+                },
+                None => tracing::warn!("Invalid repeat in grid template: {template}"),
+            }
+            rest = repeat[end + 1..].trim_start();
+            continue;
+        }
+        let (token, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let track = if token == "auto" {
+            Some(Track::Auto)
+        } else if let Some(fraction) = token.strip_suffix("fr") {
+            fraction.parse().ok().map(Track::Fraction)
+        } else {
+            token
+                .strip_suffix("px")
+                .unwrap_or(token)
+                .parse()
+                .ok()
+                .map(Track::Fixed)
+        };
+        match track {
+            Some(track) => tracks.push(track),
+            None => tracing::warn!("Invalid grid track {token:?} in template: {template}"),
+        }
+        rest = remaining.trim_start();
+    }
+    tracks
+}
+
+/// Where an item wants to be placed; `None` means it is placed automatically.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PlacementRequest {
+    pub column: Option<usize>,
+    pub row: Option<usize>,
+    pub column_span: usize,
+    pub row_span: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Placement {
+    pub column: usize,
+    pub row: usize,
+    pub column_span: usize,
+    pub row_span: usize,
+}
+
+struct Occupancy {
+    columns: usize,
+    cells: Vec<Vec<bool>>,
+}
+impl Occupancy {
+    fn is_free(&self, p: &Placement) -> bool {
+        (p.row..p.row + p.row_span).all(|row| {
+            (p.column..p.column + p.column_span)
+                .all(|column| !self.cells.get(row).is_some_and(|cells| cells[column]))
+        })
+    }
+    fn occupy(&mut self, p: &Placement) {
+        while self.cells.len() < p.row + p.row_span {
+            self.cells.push(vec![false; self.columns]);
+        }
+        for row in p.row..p.row + p.row_span {
+            for column in p.column..p.column + p.column_span {
+                self.cells[row][column] = true;
+            }
+        }
+    }
+}
+
+/// Places the items on the grid: items with both a column and a row first, then the rest in
+/// order, scanning row by row from the last auto-placed item. Returns the placements together
+/// with the number of columns and rows used, which can exceed the template.
+pub(crate) fn place_items(
+    template_columns: usize,
+    requests: &[PlacementRequest],
+) -> (Vec<Placement>, usize, usize) {
+    let columns = requests
+        .iter()
+        .map(|r| r.column.unwrap_or(0) + r.column_span)
+        .fold(template_columns.max(1), usize::max);
+    let mut occupancy = Occupancy {
+        columns,
+        cells: Vec::new(),
+    };
+    let mut placements = vec![None; requests.len()];
+    for (i, r) in requests.iter().enumerate() {
+        if let (Some(column), Some(row)) = (r.column, r.row) {
+            let p = Placement {
+                column,
+                row,
+                column_span: r.column_span,
+                row_span: r.row_span,
+            };
+            occupancy.occupy(&p);
+            placements[i] = Some(p);
+        }
+    }
+    let mut cursor = (0, 0);
+    for (i, r) in requests.iter().enumerate() {
+        if placements[i].is_some() {
+            continue;
+        }
+        let mut p = Placement {
+            column: 0,
+            row: 0,
+            column_span: r.column_span.min(columns),
+            row_span: r.row_span,
+        };
+        match (r.column, r.row) {
+            (None, Some(row)) => {
+                p.row = row;
+                while !occupancy.is_free(&p) && p.column + p.column_span < columns {
+                    p.column += 1;
+                }
+                // If the row is full, the item overlaps its last cells
+            }
+            (Some(column), None) => {
+                p.column = column;
+                p.row = if column < cursor.1 {
+                    cursor.0 + 1
+                } else {
+                    cursor.0
+                };
+                while !occupancy.is_free(&p) {
+                    p.row += 1;
+                }
+                cursor = (p.row, p.column);
+            }
+            _ => {
+                p.row = cursor.0;
+                p.column = cursor.1;
+                while p.column + p.column_span > columns || !occupancy.is_free(&p) {
+                    p.column += 1;
+                    if p.column + p.column_span > columns {
+                        p.column = 0;
+                        p.row += 1;
+                    }
+                }
+                cursor = (p.row, p.column + p.column_span);
+            }
+        }
+        occupancy.occupy(&p);
+        placements[i] = Some(p);
+    }
+    let rows = placements
+        .iter()
+        .flatten()
+        .map(|p| p.row + p.row_span)
+        .max()
+        .unwrap_or(0);
+    (placements.into_iter().flatten().collect(), columns, rows)
+}
+
+/// Resolves the sizes of the tracks along one axis. `items` are `(start, span, size)` of the
+/// items in the tracks, including their margins. Fractional tracks share the space left over in
+/// `available`; when the container fits its children there is no such space, and they are sized
+/// like `auto` tracks instead.
+pub(crate) fn size_tracks(
+    tracks: &[Track],
+    items: &[(usize, usize, f32)],
+    gap: f32,
+    available: Option<f32>,
+) -> Vec<f32> {
+    let is_content_sized = |track: &Track| match track {
+        Track::Fixed(_) => false,
+        Track::Fraction(_) => available.is_none(),
+        Track::Auto => true,
+    };
+    let mut sizes = tracks
+        .iter()
+        .map(|track| match track {
+            Track::Fixed(size) => *size,
+            _ => 0.,
+        })
+        .collect_vec();
+    for &(start, _, size) in items.iter().filter(|(_, span, _)| *span == 1) {
+        if is_content_sized(&tracks[start]) {
+            sizes[start] = sizes[start].max(size);
+        }
+    }
+    for &(start, span, size) in items
+        .iter()
+        .filter(|(_, span, _)| *span > 1)
+        .sorted_by_key(|(_, span, _)| *span)
+    {
+        let covered = sizes[start..start + span].iter().sum::<f32>() + gap * (span - 1) as f32;
+        if let Some(last) = (start..start + span)
+            .rev()
+            .find(|&i| is_content_sized(&tracks[i]))
+        {
+            sizes[last] += (size - covered).max(0.);
+        }
+    }
+    if let Some(available) = available {
+        let used = tracks
+            .iter()
+            .zip(&sizes)
+            .filter(|(track, _)| !matches!(track, Track::Fraction(_)))
+            .map(|(_, size)| size)
+            .sum::<f32>()
+            + gap * tracks.len().saturating_sub(1) as f32;
+        let free = (available - used).max(0.);
+        let total_fraction = tracks
+            .iter()
+            .map(|track| match track {
+                Track::Fraction(fraction) => *fraction,
+                _ => 0.,
+            })
+            .sum::<f32>()
+            // Like CSS, fractions adding up to less than 1 leave part of the space empty
+            .max(1.);
+        for (track, size) in tracks.iter().zip(&mut sizes) {
+            if let Track::Fraction(fraction) = track {
+                *size = free * fraction / total_fraction;
+            }
+        }
+    }
+    sizes
+}
+
+fn track_offsets(sizes: &[f32], gap: f32) -> Vec<f32> {
+    let mut offset = 0.;
+    sizes
+        .iter()
+        .map(|size| {
+            let start = offset;
+            offset += size + gap;
+            start
+        })
+        .collect()
+}
+
+fn span_size(sizes: &[f32], start: usize, span: usize, gap: f32) -> f32 {
+    sizes[start..start + span].iter().sum::<f32>() + gap * (span - 1) as f32
+}
+
+#[profiling::function]
+pub(crate) fn grid_layout(world: &mut World, id: EntityId, children: Vec<EntityId>) {
+    let self_padding: Borders = world
+        .get(id, padding())
+        .unwrap_or(Borders::ZERO.into())
+        .into();
+    let self_size = vec2(
+        world.get(id, width()).unwrap_or(0.),
+        world.get(id, height()).unwrap_or(0.),
+    );
+    let self_fit_horizontal = world.get(id, fit_horizontal()).unwrap_or(Fit::None);
+    let self_fit_vertical = world.get(id, fit_vertical()).unwrap_or(Fit::None);
+    let space_between_items = world.get(id, space_between_items()).unwrap_or(0.);
+    let gap = world
+        .get(id, gap())
+        .unwrap_or(Vec2::splat(space_between_items));
+    let mut column_tracks = world
+        .get_ref(id, grid_columns())
+        .map(|template| parse_tracks(template))
+        .unwrap_or_default();
+    if column_tracks.is_empty() {
+        column_tracks.push(Track::Fraction(1.));
+    }
+    let mut row_tracks = world
+        .get_ref(id, grid_rows())
+        .map(|template| parse_tracks(template))
+        .unwrap_or_default();
+
+    let children = children
+        .iter()
+        .filter(|id| world.has_component(**id, local_to_parent()))
+        .copied()
+        .collect_vec();
+    let requests = children
+        .iter()
+        .map(|&c| PlacementRequest {
+            column: world.get(c, grid_column()).ok().map(|x| x as usize),
+            row: world.get(c, grid_row()).ok().map(|x| x as usize),
+            column_span: world.get(c, grid_column_span()).unwrap_or(1).max(1) as usize,
+            row_span: world.get(c, grid_row_span()).unwrap_or(1).max(1) as usize,
+        })
+        .collect_vec();
+    let (placements, columns, rows) = place_items(column_tracks.len(), &requests);
+    column_tracks.resize(columns, Track::Auto);
+    row_tracks.resize(rows.max(row_tracks.len()), Track::Auto);
+
+    let items = children
+        .iter()
+        .map(|&c| {
+            let child_margin: Borders = world
+                .get(c, margin())
+                .unwrap_or(Borders::ZERO.into())
+                .into();
+            let child_fit_horizontal = world.get(c, fit_horizontal()).unwrap_or(Fit::None);
+            let child_fit_vertical = world.get(c, fit_vertical()).unwrap_or(Fit::None);
+            // Items that fit their cell don't contribute to the size of their tracks
+            let size = vec2(
+                if child_fit_horizontal == Fit::Parent {
+                    0.
+                } else {
+                    world.get(c, width()).unwrap_or(0.) + child_margin.get_horizontal()
+                },
+                if child_fit_vertical == Fit::Parent {
+                    0.
+                } else {
+                    world.get(c, height()).unwrap_or(0.) + child_margin.get_vertical()
+                },
+            );
+            (child_margin, child_fit_horizontal, child_fit_vertical, size)
+        })
+        .collect_vec();
+
+    let available_width = if self_fit_horizontal == Fit::Children {
+        None
+    } else {
+        Some(self_size.x - self_padding.get_horizontal())
+    };
+    let available_height = if self_fit_vertical == Fit::Children {
+        None
+    } else {
+        Some(self_size.y - self_padding.get_vertical())
+    };
+    let column_sizes = size_tracks(
+        &column_tracks,
+        &placements
+            .iter()
+            .zip(&items)
+            .map(|(p, item)| (p.column, p.column_span, item.3.x))
+            .collect_vec(),
+        gap.x,
+        available_width,
+    );
+    let row_sizes = size_tracks(
+        &row_tracks,
+        &placements
+            .iter()
+            .zip(&items)
+            .map(|(p, item)| (p.row, p.row_span, item.3.y))
+            .collect_vec(),
+        gap.y,
+        available_height,
+    );
+    let column_offsets = track_offsets(&column_sizes, gap.x);
+    let row_offsets = track_offsets(&row_sizes, gap.y);
+
+    let align_horizontal = world.get(id, align_horizontal()).unwrap_or(Align::Begin);
+    let align_vertical = world.get(id, align_vertical()).unwrap_or(Align::Begin);
+    for ((&c, p), (child_margin, child_fit_horizontal, child_fit_vertical, _)) in
+        children.iter().zip(&placements).zip(items)
+    {
+        let cell_position =
+            self_padding.offset() + vec2(column_offsets[p.column], row_offsets[p.row]).floor();
+        let cell_size = vec2(
+            span_size(&column_sizes, p.column, p.column_span, gap.x),
+            span_size(&row_sizes, p.row, p.row_span, gap.y),
+        );
+        let child_width = if child_fit_horizontal == Fit::Parent {
+            let child_new_width = cell_size.x - child_margin.get_horizontal();
+            world.set_if_changed(c, width(), child_new_width).ok();
+            child_new_width
+        } else {
+            world.get(c, width()).unwrap_or(0.)
+        };
+        let child_height = if child_fit_vertical == Fit::Parent {
+            let child_new_height = cell_size.y - child_margin.get_vertical();
+            world.set_if_changed(c, height(), child_new_height).ok();
+            child_new_height
+        } else {
+            world.get(c, height()).unwrap_or(0.)
+        };
+        let x = match align_horizontal {
+            Align::Begin => child_margin.left,
+            Align::Center => {
+                (cell_size.x - child_width - child_margin.get_horizontal()) / 2. + child_margin.left
+            }
+            Align::End => cell_size.x - child_width - child_margin.right,
+        };
+        let y = match align_vertical {
+            Align::Begin => child_margin.top,
+            Align::Center => {
+                (cell_size.y - child_height - child_margin.get_vertical()) / 2. + child_margin.top
+            }
+            Align::End => cell_size.y - child_height - child_margin.bottom,
+        };
+        world
+            .set_if_changed(
+                c,
+                translation(),
+                vec3(cell_position.x + x, cell_position.y + y, Z_DELTA),
+            )
+            .ok();
+    }
+
+    if self_fit_horizontal == Fit::Children {
+        let new_self_width = (span_size(&column_sizes, 0, column_sizes.len(), gap.x)
+            + self_padding.get_horizontal())
+        .max(world.get(id, min_width()).unwrap_or(0.));
+        if self_size.x != new_self_width {
+            world.set(id, width(), new_self_width).ok();
+            invalidate_parent_layout(world, id, Orientation::Horizontal);
+        }
+    }
+    if self_fit_vertical == Fit::Children {
+        let new_self_height = (if row_sizes.is_empty() {
+            0.
+        } else {
+            span_size(&row_sizes, 0, row_sizes.len(), gap.y)
+        } + self_padding.get_vertical())
+        .max(world.get(id, min_height()).unwrap_or(0.));
+        if self_size.y != new_self_height {
+            world.set(id, height(), new_self_height).ok();
+            invalidate_parent_layout(world, id, Orientation::Vertical);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto(column_span: usize, row_span: usize) -> PlacementRequest {
+        PlacementRequest {
+            column: None,
+            row: None,
+            column_span,
+            row_span,
+        }
+    }
+    fn at(column: usize, row: usize, column_span: usize, row_span: usize) -> Placement {
+        Placement {
+            column,
+            row,
+            column_span,
+            row_span,
+        }
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(
+            parse_tracks("100 auto 1fr 20px 0.5fr"),
+            vec![
+                Track::Fixed(100.),
+                Track::Auto,
+                Track::Fraction(1.),
+                Track::Fixed(20.),
+                Track::Fraction(0.5),
+            ]
+        );
+        assert_eq!(parse_tracks("  "), vec![]);
+    }
+
+    #[test]
+    fn parse_repeat() {
+        assert_eq!(
+            parse_tracks("repeat(3, 1fr) auto"),
+            vec![
+                Track::Fraction(1.),
+                Track::Fraction(1.),
+                Track::Fraction(1.),
+                Track::Auto,
+            ]
+        );
+        assert_eq!(
+            parse_tracks("10 repeat(2, 50 auto)"),
+            vec![
+                Track::Fixed(10.),
+                Track::Fixed(50.),
+                Track::Auto,
+                Track::Fixed(50.),
+                Track::Auto,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_skips_invalid_tracks() {
+        assert_eq!(
+            parse_tracks("100 bogus 2fr"),
+            vec![Track::Fixed(100.), Track::Fraction(2.)]
+        );
+        assert_eq!(parse_tracks("repeat(x, 1fr) 10"), vec![Track::Fixed(10.)]);
+        assert_eq!(parse_tracks("repeat(2 1fr) 10"), vec![Track::Fixed(10.)]);
+        assert_eq!(parse_tracks("10 repeat(2, 1fr"), vec![Track::Fixed(10.)]);
+    }
+
+    #[test]
+    fn auto_placement_wraps_rows() {
+        let (placements, columns, rows) = place_items(3, &[auto(1, 1); 4]);
+        assert_eq!(
+            placements,
+            vec![
+                at(0, 0, 1, 1),
+                at(1, 0, 1, 1),
+                at(2, 0, 1, 1),
+                at(0, 1, 1, 1)
+            ]
+        );
+        assert_eq!((columns, rows), (3, 2));
+    }
+
+    #[test]
+    fn column_spans_move_to_the_next_row() {
+        let (placements, _, rows) = place_items(3, &[auto(2, 1), auto(2, 1), auto(1, 1)]);
+        assert_eq!(
+            placements,
+            vec![at(0, 0, 2, 1), at(0, 1, 2, 1), at(2, 1, 1, 1)]
+        );
+        assert_eq!(rows, 2);
+    }
+
+    #[test]
+    fn auto_placement_skips_explicit_items() {
+        let explicit = PlacementRequest {
+            column: Some(1),
+            row: Some(0),
+            column_span: 1,
+            row_span: 2,
+        };
+        let (placements, _, rows) = place_items(
+            3,
+            &[auto(1, 1), explicit, auto(1, 1), auto(1, 1), auto(1, 1)],
+        );
+        assert_eq!(
+            placements,
+            vec![
+                at(0, 0, 1, 1),
+                at(1, 0, 1, 2),
+                at(2, 0, 1, 1),
+                at(0, 1, 1, 1),
+                at(2, 1, 1, 1),
+            ]
+        );
+        assert_eq!(rows, 2);
+    }
+
+    #[test]
+    fn items_outside_the_template_add_columns() {
+        let explicit = PlacementRequest {
+            column: Some(3),
+            row: None,
+            column_span: 2,
+            row_span: 1,
+        };
+        let (placements, columns, rows) = place_items(2, &[auto(1, 1), explicit]);
+        assert_eq!(placements, vec![at(0, 0, 1, 1), at(3, 0, 2, 1)]);
+        assert_eq!((columns, rows), (5, 1));
+    }
+
+    #[test]
+    fn fractions_share_the_free_space() {
+        let tracks = [
+            Track::Fixed(100.),
+            Track::Auto,
+            Track::Fraction(1.),
+            Track::Fraction(3.),
+        ];
+        assert_eq!(
+            size_tracks(&tracks, &[(1, 1, 50.)], 10., Some(530.)),
+            vec![100., 50., 87.5, 262.5]
+        );
+        // Fractions adding up to less than 1 leave part of the space empty
+        assert_eq!(
+            size_tracks(&[Track::Fraction(0.5)], &[], 0., Some(100.)),
+            vec![50.]
+        );
+        // Nothing is left when the other tracks overflow
+        assert_eq!(
+            size_tracks(
+                &[Track::Fixed(200.), Track::Fraction(1.)],
+                &[],
+                0.,
+                Some(100.)
+            ),
+            vec![200., 0.]
+        );
+    }
+
+    #[test]
+    fn fractions_fit_their_items_without_available_space() {
+        assert_eq!(
+            size_tracks(
+                &[Track::Fraction(1.), Track::Auto],
+                &[(0, 1, 30.), (1, 1, 40.)],
+                0.,
+                None
+            ),
+            vec![30., 40.]
+        );
+    }
+
+    #[test]
+    fn spanning_items_grow_the_last_auto_track() {
+        assert_eq!(
+            size_tracks(
+                &[Track::Auto, Track::Auto],
+                &[(0, 1, 30.), (0, 2, 100.)],
+                10.,
+                None
+            ),
+            vec![30., 60.]
+        );
+        assert_eq!(
+            size_tracks(&[Track::Auto, Track::Fixed(50.)], &[(0, 2, 100.)], 0., None),
+            vec![50., 50.]
+        );
+        // Spans that already fit don't grow anything
+        assert_eq!(
+            size_tracks(&[Track::Fixed(50.), Track::Auto], &[(0, 2, 40.)], 0., None),
+            vec![50., 0.]
+        );
+    }
+}
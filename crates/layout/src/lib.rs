@@ -12,20 +12,28 @@ use itertools::Itertools;
 
 pub use ambient_ecs::generated::layout::{
     components::{
-        align_horizontal, align_vertical, docking, fit_horizontal, fit_vertical, gpu_ui_size,
-        height, is_book_file, is_screen, layout, margin, max_height, max_width,
-        mesh_to_local_from_size, min_height, min_width, orientation, padding, space_between_items,
-        width,
+        align_content, align_horizontal, align_vertical, docking, fit_horizontal, fit_vertical,
+        flex_basis, flex_grow, flex_shrink, flex_wrap, gap, gpu_ui_size, grid_column,
+        grid_column_span, grid_columns, grid_row, grid_row_span, grid_rows, height, is_book_file,
+        is_screen, justify_content, layout, margin, max_height, max_width, mesh_to_local_from_size,
+        min_height, min_width, orientation, padding, space_between_items, width,
     },
-    types::{Align, Docking, Fit, Layout, Orientation},
+    types::{Align, Docking, Fit, Justify, Layout, Orientation},
 };
 use ambient_gpu::gpu::Gpu;
 use std::sync::Arc;
 
+mod flex;
+mod grid;
+
 gpu_components! {
     gpu_ui_size() => ui_size: GpuComponentFormat::Vec4,
 }
 
+pub fn init_components() {
+    flex::init_components();
+}
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Borders {
     pub top: f32,
@@ -195,6 +203,12 @@ pub fn layout_systems() -> SystemGroup {
                             Layout::WidthToChildren => {
                                 width_to_children(world, id, children);
                             }
+                            Layout::Grid => {
+                                grid::grid_layout(world, id, children);
+                            }
+                            Layout::Flex => {
+                                flex::flex_layout(world, id, children);
+                            }
                         }
                     }
                     if !changed {
@@ -0,0 +1,1130 @@
+use std::{collections::HashMap, io::Cursor, path::PathBuf};
+
+use ambient_animation::{AnimationClip, AnimationOutputs, AnimationTarget};
+use ambient_core::{
+    hierarchy::{children, parent},
+    name,
+    transform::{rotation, scale, translation},
+};
+use ambient_ecs::{generated::animation::components::bind_id, query, query_mut, EntityId, World};
+use ambient_model::{
+    model_from_url, model_skin_ix, model_skins, pbr_renderer_primitives_from_url, Model, ModelSkin,
+    PbrRenderPrimitiveFromUrl,
+};
+use ambient_native_std::{
+    asset_cache::AssetCache,
+    asset_url::{AbsAssetUrl, AssetUrl},
+    mesh::{flip_winding, Mesh},
+};
+use ambient_renderer::materials::pbr_material::PbrMaterialDesc;
+use anyhow::Context;
+use glam::{Mat4, Quat, Vec3, Vec4};
+use image::{ImageOutputFormat, RgbaImage};
+use itertools::Itertools;
+use relative_path::{Component, RelativePathBuf};
+use serde_json::{json, Map, Value};
+
+use crate::model_crate::ModelCrate;
+
+/// Where the exporter gets the models, meshes, materials and images that the exported worlds refer to by url.
+pub trait GltfExportAssets {
+    fn model(&self, url: &str) -> Option<Model>;
+    fn mesh(&self, url: &str) -> Option<Mesh>;
+    fn material(&self, url: &str) -> Option<PbrMaterialDesc>;
+    fn image(&self, url: &str) -> Option<RgbaImage>;
+}
+
+impl GltfExportAssets for ModelCrate {
+    fn model(&self, url: &str) -> Option<Model> {
+        self.models.get_by_path(url).cloned()
+    }
+    fn mesh(&self, url: &str) -> Option<Mesh> {
+        self.meshes.get_by_path(url).cloned()
+    }
+    fn material(&self, url: &str) -> Option<PbrMaterialDesc> {
+        self.materials.get_by_path(url).cloned()
+    }
+    fn image(&self, url: &str) -> Option<RgbaImage> {
+        self.images.get_by_path(url).cloned()
+    }
+}
+
+/// Reads the assets from a directory laid out like a built model crate, with `models/`, `prefabs/`, `meshes/`,
+/// `materials/` and `images/` directories, which the relative urls of its models and prefabs refer to.
+pub struct GltfExportDirAssets(pub PathBuf);
+impl GltfExportDirAssets {
+    fn path(&self, url: &str) -> Option<PathBuf> {
+        if let Some(path) = url.strip_prefix("file://") {
+            return Some(PathBuf::from(path));
+        }
+        if url.contains("://") {
+            return None;
+        }
+        let path = RelativePathBuf::from(url);
+        let path = path
+            .components()
+            .skip_while(|c| *c == Component::ParentDir || *c == Component::CurDir)
+            .collect::<RelativePathBuf>();
+        Some(path.to_path(&self.0))
+    }
+    /// The animation clips in the `animations/` directory, sorted by file name.
+    pub fn animations(&self) -> Vec<AnimationClip> {
+        let Ok(entries) = std::fs::read_dir(self.0.join("animations")) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map(|ext| ext == "anim").unwrap_or(false))
+            .sorted()
+            .filter_map(|path| bincode::deserialize(&std::fs::read(path).ok()?).ok())
+            .collect()
+    }
+    fn read(&self, url: &str) -> Option<Vec<u8>> {
+        let path = self.path(url)?;
+        std::fs::read(&path)
+            .map_err(|err| tracing::warn!("Failed to read {path:?}: {err}"))
+            .ok()
+    }
+}
+impl GltfExportAssets for GltfExportDirAssets {
+    fn model(&self, url: &str) -> Option<Model> {
+        Model::from_slice(&self.read(url)?).ok()
+    }
+    fn mesh(&self, url: &str) -> Option<Mesh> {
+        bincode::deserialize(&self.read(url)?).ok()
+    }
+    fn material(&self, url: &str) -> Option<PbrMaterialDesc> {
+        serde_json::from_slice(&self.read(url)?).ok()
+    }
+    fn image(&self, url: &str) -> Option<RgbaImage> {
+        Some(image::load_from_memory(&self.read(url)?).ok()?.into_rgba8())
+    }
+}
+
+/// The assets of a live world, such as a world dumped from a running server, whose urls are absolute. The exporter
+/// reads assets synchronously, so [GltfExportUrlAssets::fetch] downloads everything it needs up front. The relative
+/// urls in the fetched models and materials are made absolute, so the assets of different packages don't collide.
+#[derive(Default)]
+pub struct GltfExportUrlAssets {
+    models: HashMap<String, Model>,
+    meshes: HashMap<String, Mesh>,
+    materials: HashMap<String, PbrMaterialDesc>,
+    images: HashMap<String, RgbaImage>,
+}
+impl GltfExportUrlAssets {
+    /// Downloads the models, meshes, materials and images used by `roots` and their children.
+    pub async fn fetch(assets: &AssetCache, world: &World, roots: &[EntityId]) -> Self {
+        let mut res = Self::default();
+        let mut primitives = Vec::new();
+        let mut stack = roots.to_vec();
+        while let Some(id) = stack.pop() {
+            if let Ok(prims) = world.get_ref(id, pbr_renderer_primitives_from_url()) {
+                primitives.extend(prims.iter().cloned());
+            }
+            if let Ok(url) = world.get_ref(id, model_from_url()) {
+                if !res.models.contains_key(url) {
+                    if let Some(model) = Self::fetch_model(assets, url).await {
+                        for (_, prims) in
+                            query(pbr_renderer_primitives_from_url()).iter(&model.0, None)
+                        {
+                            primitives.extend(prims.iter().cloned());
+                        }
+                        res.models.insert(url.clone(), model);
+                    }
+                }
+            }
+            if let Ok(children) = world.get_ref(id, children()) {
+                stack.extend(children.iter().copied());
+            }
+        }
+
+        let mut images = Vec::new();
+        for primitive in primitives.iter().filter(|p| p.lod == 0) {
+            let mesh_url = primitive.mesh.to_string();
+            if !res.meshes.contains_key(&mesh_url) {
+                let mesh = download(assets, &primitive.mesh)
+                    .await
+                    .and_then(|data| bincode::deserialize(&data).ok());
+                if let Some(mesh) = mesh {
+                    res.meshes.insert(mesh_url, mesh);
+                }
+            }
+            let Some(material_url) = &primitive.material else {
+                continue;
+            };
+            if res.materials.contains_key(&material_url.to_string()) {
+                continue;
+            }
+            let Some(base) = absolute(material_url) else {
+                continue;
+            };
+            let Some(mut desc) = download(assets, material_url)
+                .await
+                .and_then(|data| serde_json::from_slice::<PbrMaterialDesc>(&data).ok())
+            else {
+                continue;
+            };
+            for url in [
+                &mut desc.base_color,
+                &mut desc.opacity,
+                &mut desc.normalmap,
+                &mut desc.metallic_roughness,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if let Ok(resolved) = url.resolve(&base) {
+                    *url = AssetUrl::Absolute(resolved);
+                }
+                images.push(url.clone());
+            }
+            res.materials.insert(material_url.to_string(), desc);
+        }
+
+        for url in images {
+            if res.images.contains_key(&url.to_string()) {
+                continue;
+            }
+            let image = download(assets, &url)
+                .await
+                .and_then(|data| image::load_from_memory(&data).ok());
+            if let Some(image) = image {
+                res.images.insert(url.to_string(), image.into_rgba8());
+            }
+        }
+        res
+    }
+
+    async fn fetch_model(assets: &AssetCache, url: &str) -> Option<Model> {
+        let url: AssetUrl = url.parse().ok()?;
+        let base = absolute(&url)?;
+        let mut model = Model::from_slice(&download(assets, &url).await?)
+            .map_err(|err| tracing::warn!("Failed to parse model {url}: {err:?}"))
+            .ok()?;
+        for (_, (prims,), _) in
+            query_mut((pbr_renderer_primitives_from_url(),), ()).iter(&mut model.0, None)
+        {
+            for prim in prims {
+                if let Ok(mesh) = prim.mesh.resolve(&base) {
+                    prim.mesh = AssetUrl::Absolute(mesh);
+                }
+                if let Some(material) = &mut prim.material {
+                    if let Ok(resolved) = material.resolve(&base) {
+                        *material = AssetUrl::Absolute(resolved);
+                    }
+                }
+            }
+        }
+        Some(model)
+    }
+}
+impl GltfExportAssets for GltfExportUrlAssets {
+    fn model(&self, url: &str) -> Option<Model> {
+        self.models.get(url).cloned()
+    }
+    fn mesh(&self, url: &str) -> Option<Mesh> {
+        self.meshes.get(url).cloned()
+    }
+    fn material(&self, url: &str) -> Option<PbrMaterialDesc> {
+        self.materials.get(url).cloned()
+    }
+    fn image(&self, url: &str) -> Option<RgbaImage> {
+        self.images.get(url).cloned()
+    }
+}
+
+fn absolute(url: &AssetUrl) -> Option<AbsAssetUrl> {
+    match url {
+        AssetUrl::Absolute(url) => Some(url.clone()),
+        AssetUrl::Relative(path) => {
+            tracing::warn!("{path} isn't an absolute url, skipping it");
+            None
+        }
+    }
+}
+
+async fn download(assets: &AssetCache, url: &AssetUrl) -> Option<Vec<u8>> {
+    let url = absolute(url)?;
+    url.download_bytes(assets)
+        .await
+        .map_err(|err| tracing::warn!("Failed to download {url}: {err:?}"))
+        .ok()
+}
+
+#[derive(Debug, Clone)]
+pub struct GltfExportOptions {
+    /// Ambient is Z-up, while glTF is Y-up. When set, the exported nodes are put under a root node which converts
+    /// between the two, undoing the conversion made when importing.
+    pub zup_to_yup: bool,
+}
+impl Default for GltfExportOptions {
+    fn default() -> Self {
+        Self { zup_to_yup: true }
+    }
+}
+
+/// Exports the entities `roots` of `world` and their children to a binary glTF 2.0 (.glb) file, with their
+/// meshes, PBR materials, skins and the `animations`. Entities with a `model_from_url` get the nodes of the model
+/// as children, so this works for prefabs as well as models.
+///
+/// `world` doesn't have to be a prefab or model, but the urls of its assets must be resolvable by `assets`:
+/// [GltfExportDirAssets] resolves the relative and `file://` urls of built model crates, and [GltfExportUrlAssets]
+/// the absolute urls of live worlds.
+pub fn export_glb(
+    world: &World,
+    roots: &[EntityId],
+    animations: &[AnimationClip],
+    assets: &dyn GltfExportAssets,
+    options: &GltfExportOptions,
+) -> anyhow::Result<Vec<u8>> {
+    let mut exporter = GltfExporter::new(assets);
+    let (mut scene_nodes, nodes) = exporter.world(world, roots)?;
+    for clip in animations {
+        exporter.animation(clip, &nodes);
+    }
+    if options.zup_to_yup {
+        // The inverse of `Model::rotate_yup_to_zup`, which swaps the Y and Z axes
+        scene_nodes = vec![exporter.push_node(json!({
+            "name": "zup_to_yup",
+            "rotation": Quat::from_rotation_x(std::f32::consts::FRAC_PI_2).to_array(),
+            "scale": [1., 1., -1.],
+            "children": scene_nodes,
+        }))];
+    }
+    Ok(exporter.finish(scene_nodes))
+}
+
+/// The entities of `world` that aren't children of another entity: the `children` resource of models, or the
+/// entities without a `parent` in other worlds.
+pub fn world_roots(world: &World) -> Vec<EntityId> {
+    match world.resource_opt(children()) {
+        Some(roots) => roots.clone(),
+        None => query(())
+            .excl(parent())
+            .iter(world, None)
+            .map(|(id, _)| id)
+            .collect(),
+    }
+}
+
+impl ModelCrate {
+    /// Exports the main prefab, or the main model if there's no prefab, with all the animations of the crate.
+    pub fn export_glb(&self, options: &GltfExportOptions) -> anyhow::Result<Vec<u8>> {
+        let world = match self.prefabs.content.get(Self::MAIN) {
+            Some(prefab) => prefab,
+            None => self
+                .models
+                .content
+                .get(Self::MAIN)
+                .map(|model| &model.0)
+                .context("The model crate has no main prefab or model")?,
+        };
+        let animations = self
+            .animations
+            .content
+            .iter()
+            .sorted_by_key(|(id, _)| *id)
+            .map(|(_, clip)| clip.clone())
+            .collect_vec();
+        export_glb(world, &world_roots(world), &animations, self, options)
+    }
+}
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum TextureKind {
+    Color,
+    MetallicRoughness,
+}
+
+struct GltfExporter<'a> {
+    assets: &'a dyn GltfExportAssets,
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    materials: Vec<Value>,
+    textures: Vec<Value>,
+    images: Vec<Value>,
+    skins: Vec<Value>,
+    animations: Vec<Value>,
+    /// The node of each `bind_id`, in the exported world and the models it refers to, as animations target the
+    /// bones of models by their `bind_id`. If several nodes have the same `bind_id`, the first one is used.
+    bind_id_nodes: HashMap<String, usize>,
+    mesh_cache: HashMap<String, Option<usize>>,
+    primitive_cache: HashMap<String, Option<Value>>,
+    material_cache: HashMap<String, Option<usize>>,
+    texture_cache: HashMap<(String, TextureKind), Option<usize>>,
+}
+impl<'a> GltfExporter<'a> {
+    fn new(assets: &'a dyn GltfExportAssets) -> Self {
+        Self {
+            assets,
+            buffer: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            nodes: Vec::new(),
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            textures: Vec::new(),
+            images: Vec::new(),
+            skins: Vec::new(),
+            animations: Vec::new(),
+            bind_id_nodes: HashMap::new(),
+            mesh_cache: HashMap::new(),
+            primitive_cache: HashMap::new(),
+            material_cache: HashMap::new(),
+            texture_cache: HashMap::new(),
+        }
+    }
+
+    /// Exports the hierarchies under `roots`, and the skins of the world. Returns the root nodes, and the node of
+    /// each exported entity.
+    fn world(
+        &mut self,
+        world: &World,
+        roots: &[EntityId],
+    ) -> anyhow::Result<(Vec<usize>, HashMap<EntityId, usize>)> {
+        let mut nodes = HashMap::new();
+        let roots = roots
+            .iter()
+            .map(|&id| self.node(world, id, &mut nodes))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // Skins refer to their joints, so they're exported once all the nodes have been
+        let skins = world
+            .resource_opt(model_skins())
+            .map(|skins| {
+                skins
+                    .iter()
+                    .map(|skin| self.skin(skin, &nodes))
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+        for (&id, &node) in &nodes {
+            if let Ok(skin_ix) = world.get(id, model_skin_ix()) {
+                if let Some(Some(skin)) = skins.get(skin_ix) {
+                    self.nodes[node]["skin"] = json!(skin);
+                }
+            }
+        }
+        Ok((roots, nodes))
+    }
+
+    fn node(
+        &mut self,
+        world: &World,
+        id: EntityId,
+        nodes: &mut HashMap<EntityId, usize>,
+    ) -> anyhow::Result<usize> {
+        let index = self.push_node(Value::Null);
+        nodes.insert(id, index);
+        if let Ok(bind_id) = world.get_ref(id, bind_id()) {
+            self.bind_id_nodes.entry(bind_id.clone()).or_insert(index);
+        }
+
+        let mut node = Map::new();
+        if let Ok(name) = world.get_ref(id, name()) {
+            node.insert("name".into(), json!(name));
+        }
+        let translation = world.get(id, translation()).unwrap_or(Vec3::ZERO);
+        if translation != Vec3::ZERO {
+            node.insert("translation".into(), json!(translation.to_array()));
+        }
+        let rotation = world.get(id, rotation()).unwrap_or(Quat::IDENTITY);
+        if rotation != Quat::IDENTITY {
+            node.insert("rotation".into(), json!(rotation.to_array()));
+        }
+        let scale = world.get(id, scale()).unwrap_or(Vec3::ONE);
+        if scale != Vec3::ONE {
+            node.insert("scale".into(), json!(scale.to_array()));
+        }
+        if let Ok(primitives) = world.get_ref(id, pbr_renderer_primitives_from_url()) {
+            if let Some(mesh) = self.mesh(primitives) {
+                node.insert("mesh".into(), json!(mesh));
+            }
+        }
+
+        let mut child_nodes = world
+            .get_ref(id, children())
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| self.node(world, child, nodes))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if let Ok(url) = world.get_ref(id, model_from_url()) {
+            let model = self
+                .assets
+                .model(url)
+                .with_context(|| format!("Failed to find model {url}"))?;
+            let (model_roots, _) = self.world(&model.0, &model.roots())?;
+            child_nodes.extend(model_roots);
+        }
+        if !child_nodes.is_empty() {
+            node.insert("children".into(), json!(child_nodes));
+        }
+
+        self.nodes[index] = Value::Object(node);
+        Ok(index)
+    }
+
+    fn push_node(&mut self, node: Value) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// Exports the primitives of the highest LOD as a glTF mesh. Meshes that can't be found are skipped.
+    fn mesh(&mut self, primitives: &[PbrRenderPrimitiveFromUrl]) -> Option<usize> {
+        let primitives = primitives.iter().filter(|p| p.lod == 0).collect_vec();
+        let key = primitives
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}:{:?}",
+                    p.mesh,
+                    p.material.as_ref().map(|m| m.to_string())
+                )
+            })
+            .join(";");
+        if let Some(mesh) = self.mesh_cache.get(&key) {
+            return *mesh;
+        }
+        let primitives = primitives
+            .into_iter()
+            .filter_map(|primitive| {
+                let mut value = self.primitive(&primitive.mesh.to_string())?;
+                if let Some(material) = primitive
+                    .material
+                    .as_ref()
+                    .and_then(|url| self.material(&url.to_string()))
+                {
+                    value["material"] = json!(material);
+                }
+                Some(value)
+            })
+            .collect_vec();
+        let mesh = if primitives.is_empty() {
+            None
+        } else {
+            self.meshes.push(json!({ "primitives": primitives }));
+            Some(self.meshes.len() - 1)
+        };
+        self.mesh_cache.insert(key, mesh);
+        mesh
+    }
+
+    fn primitive(&mut self, url: &str) -> Option<Value> {
+        if let Some(primitive) = self.primitive_cache.get(url) {
+            return primitive.clone();
+        }
+        let primitive = match self.assets.mesh(url) {
+            Some(mesh) if mesh.positions().is_empty() || mesh.indices().is_empty() => {
+                tracing::warn!("Mesh {url} is empty, skipping it");
+                None
+            }
+            Some(mesh) => Some(self.mesh_primitive(&mesh)),
+            None => {
+                tracing::warn!("Failed to find mesh {url}, skipping it");
+                None
+            }
+        };
+        self.primitive_cache
+            .insert(url.to_string(), primitive.clone());
+        primitive
+    }
+
+    fn mesh_primitive(&mut self, mesh: &Mesh) -> Value {
+        let mut attributes = Map::new();
+        let positions = mesh.positions();
+        let (min, max) = positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), p| (min.min(*p), max.max(*p)),
+        );
+        let position = self.accessor(
+            bytemuck::cast_slice(positions),
+            Some(ARRAY_BUFFER),
+            FLOAT,
+            positions.len(),
+            "VEC3",
+            Some((min.to_array().to_vec(), max.to_array().to_vec())),
+        );
+        attributes.insert("POSITION".into(), json!(position));
+        if !mesh.normals().is_empty() {
+            let normals = self.vertex_accessor(bytemuck::cast_slice(mesh.normals()), "VEC3");
+            attributes.insert("NORMAL".into(), json!(normals));
+        }
+        if !mesh.tangents().is_empty() {
+            let tangents = tangents_with_handedness(mesh);
+            let tangents = self.vertex_accessor(bytemuck::cast_slice(&tangents), "VEC4");
+            attributes.insert("TANGENT".into(), json!(tangents));
+        }
+        let mut set = 0;
+        while !mesh.texcoords(set).is_empty() {
+            let texcoords = self.vertex_accessor(bytemuck::cast_slice(mesh.texcoords(set)), "VEC2");
+            attributes.insert(format!("TEXCOORD_{set}"), json!(texcoords));
+            set += 1;
+        }
+        if !mesh.colors().is_empty() {
+            let colors = self.vertex_accessor(bytemuck::cast_slice(mesh.colors()), "VEC4");
+            attributes.insert("COLOR_0".into(), json!(colors));
+        }
+        let joints_fit = mesh
+            .joint_indices()
+            .iter()
+            .all(|j| j.max_element() <= u16::MAX as u32);
+        if !joints_fit {
+            tracing::warn!(
+                "Mesh has joint indices above {}, skipping its skin",
+                u16::MAX
+            );
+        }
+        if joints_fit && !mesh.joint_indices().is_empty() {
+            let joints = mesh
+                .joint_indices()
+                .iter()
+                .flat_map(|j| j.to_array().map(|x| x as u16))
+                .collect_vec();
+            let joints = self.accessor(
+                bytemuck::cast_slice(&joints),
+                Some(ARRAY_BUFFER),
+                UNSIGNED_SHORT,
+                mesh.joint_indices().len(),
+                "VEC4",
+                None,
+            );
+            attributes.insert("JOINTS_0".into(), json!(joints));
+        }
+        if joints_fit && !mesh.joint_weights().is_empty() {
+            let weights = self.vertex_accessor(bytemuck::cast_slice(mesh.joint_weights()), "VEC4");
+            attributes.insert("WEIGHTS_0".into(), json!(weights));
+        }
+
+        // The importer flips the winding of glTF meshes, so it's flipped back here
+        let mut indices = mesh.indices().to_vec();
+        flip_winding(&mut indices);
+        let indices = self.accessor(
+            bytemuck::cast_slice(&indices),
+            Some(ELEMENT_ARRAY_BUFFER),
+            UNSIGNED_INT,
+            indices.len(),
+            "SCALAR",
+            None,
+        );
+        json!({ "attributes": attributes, "indices": indices })
+    }
+
+    fn material(&mut self, url: &str) -> Option<usize> {
+        if let Some(material) = self.material_cache.get(url) {
+            return *material;
+        }
+        let material = match self.assets.material(url) {
+            Some(desc) => Some(self.material_desc(&desc)),
+            None => {
+                tracing::warn!("Failed to find material {url}, skipping it");
+                None
+            }
+        };
+        self.material_cache.insert(url.to_string(), material);
+        material
+    }
+
+    fn material_desc(&mut self, desc: &PbrMaterialDesc) -> usize {
+        let mut pbr = Map::new();
+        pbr.insert(
+            "baseColorFactor".into(),
+            json!(desc.base_color_factor.unwrap_or(Vec4::ONE).to_array()),
+        );
+        pbr.insert("metallicFactor".into(), json!(desc.metallic_factor));
+        pbr.insert("roughnessFactor".into(), json!(desc.roughness_factor));
+        let base_color = desc.base_color.as_ref().and_then(|url| {
+            let opacity = desc.opacity.as_ref().map(|x| x.to_string());
+            self.texture(&url.to_string(), opacity.as_deref(), TextureKind::Color)
+        });
+        if let Some(texture) = base_color {
+            pbr.insert("baseColorTexture".into(), json!({ "index": texture }));
+        }
+        let metallic_roughness = desc
+            .metallic_roughness
+            .as_ref()
+            .and_then(|url| self.texture(&url.to_string(), None, TextureKind::MetallicRoughness));
+        if let Some(texture) = metallic_roughness {
+            pbr.insert(
+                "metallicRoughnessTexture".into(),
+                json!({ "index": texture }),
+            );
+        }
+
+        let mut material = Map::new();
+        if let Some(name) = &desc.name {
+            material.insert("name".into(), json!(name));
+        }
+        material.insert("pbrMetallicRoughness".into(), Value::Object(pbr));
+        let normal = desc
+            .normalmap
+            .as_ref()
+            .and_then(|url| self.texture(&url.to_string(), None, TextureKind::Color));
+        if let Some(texture) = normal {
+            material.insert("normalTexture".into(), json!({ "index": texture }));
+        }
+        if let Some(emissive) = desc.emissive_factor {
+            material.insert(
+                "emissiveFactor".into(),
+                json!(emissive.truncate().to_array()),
+            );
+        }
+        if desc.transparent == Some(true) {
+            material.insert("alphaMode".into(), json!("BLEND"));
+        } else if let Some(cutoff) = desc.alpha_cutoff {
+            material.insert("alphaMode".into(), json!("MASK"));
+            material.insert("alphaCutoff".into(), json!(cutoff));
+        }
+        if let Some(double_sided) = desc.double_sided {
+            material.insert("doubleSided".into(), json!(double_sided));
+        }
+        self.materials.push(Value::Object(material));
+        self.materials.len() - 1
+    }
+
+    /// Embeds an image as a PNG texture. Opacity maps are merged into the alpha of the base color, and the
+    /// metallic and roughness are moved from the red and green channels of Ambient to the blue and green channels
+    /// of glTF.
+    fn texture(&mut self, url: &str, opacity: Option<&str>, kind: TextureKind) -> Option<usize> {
+        let key = (format!("{url}|{}", opacity.unwrap_or_default()), kind);
+        if let Some(texture) = self.texture_cache.get(&key) {
+            return *texture;
+        }
+        let texture = match self.assets.image(url) {
+            Some(mut image) => {
+                if let Some(opacity) = opacity.and_then(|url| self.assets.image(url)) {
+                    if opacity.dimensions() == image.dimensions() {
+                        for (p, o) in image.pixels_mut().zip(opacity.pixels()) {
+                            p[3] = o[0];
+                        }
+                    }
+                }
+                if kind == TextureKind::MetallicRoughness {
+                    for p in image.pixels_mut() {
+                        p[2] = p[0];
+                        p[0] = 0;
+                    }
+                }
+                let mut png = Cursor::new(Vec::new());
+                image.write_to(&mut png, ImageOutputFormat::Png).ok()?;
+                let view = self.buffer_view(&png.into_inner(), None);
+                self.images
+                    .push(json!({ "bufferView": view, "mimeType": "image/png" }));
+                self.textures
+                    .push(json!({ "source": self.images.len() - 1, "sampler": 0 }));
+                Some(self.textures.len() - 1)
+            }
+            None => {
+                tracing::warn!("Failed to find image {url}, skipping it");
+                None
+            }
+        };
+        self.texture_cache.insert(key, texture);
+        texture
+    }
+
+    fn skin(&mut self, skin: &ModelSkin, nodes: &HashMap<EntityId, usize>) -> Option<usize> {
+        let joints = skin
+            .joints
+            .iter()
+            .map(|joint| nodes.get(joint).copied())
+            .collect::<Option<Vec<_>>>()?;
+        let mut value = json!({ "joints": joints });
+        if !skin.inverse_bind_matrices.is_empty() {
+            let matrices = skin
+                .inverse_bind_matrices
+                .iter()
+                .map(Mat4::to_cols_array)
+                .collect_vec();
+            value["inverseBindMatrices"] = json!(self.accessor(
+                bytemuck::cast_slice(&matrices),
+                None,
+                FLOAT,
+                matrices.len(),
+                "MAT4",
+                None,
+            ));
+        }
+        self.skins.push(value);
+        Some(self.skins.len() - 1)
+    }
+
+    /// Exports `clip`, whose [AnimationTarget::Entity] targets are entities of the exported world.
+    fn animation(&mut self, clip: &AnimationClip, nodes: &HashMap<EntityId, usize>) {
+        let mut channels = Vec::new();
+        let mut samplers = Vec::new();
+        for track in &clip.tracks {
+            let node = match &track.target {
+                AnimationTarget::BinderId(id) => self.bind_id_nodes.get(id),
+                AnimationTarget::Entity(id) => nodes.get(id),
+            };
+            let Some(&node) = node else {
+                continue;
+            };
+            let (path, output) = match &track.outputs {
+                AnimationOutputs::Vec3 { component, data } => {
+                    let path = if *component == translation() {
+                        "translation"
+                    } else if *component == scale() {
+                        "scale"
+                    } else {
+                        continue;
+                    };
+                    let output = self.accessor(
+                        bytemuck::cast_slice(data),
+                        None,
+                        FLOAT,
+                        data.len(),
+                        "VEC3",
+                        None,
+                    );
+                    (path, output)
+                }
+                AnimationOutputs::Quat { component, data } if *component == rotation() => {
+                    let data = data.iter().map(|q| q.to_array()).collect_vec();
+                    let output = self.accessor(
+                        bytemuck::cast_slice(&data),
+                        None,
+                        FLOAT,
+                        data.len(),
+                        "VEC4",
+                        None,
+                    );
+                    ("rotation", output)
+                }
+                // Single fields of vectors can't be animated in glTF
+                _ => continue,
+            };
+            let min = track.inputs.iter().copied().fold(f32::MAX, f32::min);
+            let max = track.inputs.iter().copied().fold(f32::MIN, f32::max);
+            let input = self.accessor(
+                bytemuck::cast_slice(&track.inputs),
+                None,
+                FLOAT,
+                track.inputs.len(),
+                "SCALAR",
+                Some((vec![min], vec![max])),
+            );
+            samplers.push(json!({ "input": input, "output": output, "interpolation": "LINEAR" }));
+            channels.push(json!({
+                "sampler": samplers.len() - 1,
+                "target": { "node": node, "path": path },
+            }));
+        }
+        if !channels.is_empty() {
+            self.animations.push(json!({
+                "name": clip.id,
+                "channels": channels,
+                "samplers": samplers,
+            }));
+        }
+    }
+
+    fn vertex_accessor(&mut self, data: &[f32], kind: &str) -> usize {
+        let components = match kind {
+            "VEC2" => 2,
+            "VEC3" => 3,
+            _ => 4,
+        };
+        self.accessor(
+            bytemuck::cast_slice(data),
+            Some(ARRAY_BUFFER),
+            FLOAT,
+            data.len() / components,
+            kind,
+            None,
+        )
+    }
+
+    fn accessor(
+        &mut self,
+        data: &[u8],
+        target: Option<u32>,
+        component_type: u32,
+        count: usize,
+        kind: &str,
+        min_max: Option<(Vec<f32>, Vec<f32>)>,
+    ) -> usize {
+        let view = self.buffer_view(data, target);
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": component_type,
+            "count": count,
+            "type": kind,
+        });
+        if let Some((min, max)) = min_max {
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn buffer_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+        while self.buffer.len() % 4 != 0 {
+            self.buffer.push(0);
+        }
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": data.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.buffer.extend_from_slice(data);
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+
+    fn finish(mut self, scene_nodes: Vec<usize>) -> Vec<u8> {
+        while self.buffer.len() % 4 != 0 {
+            self.buffer.push(0);
+        }
+        let mut root = json!({
+            "asset": { "version": "2.0", "generator": "Ambient" },
+            "scene": 0,
+            "scenes": [{ "nodes": scene_nodes }],
+            "nodes": self.nodes,
+        });
+        let arrays = [
+            ("meshes", self.meshes),
+            ("materials", self.materials),
+            ("textures", self.textures),
+            ("images", self.images),
+            ("skins", self.skins),
+            ("animations", self.animations),
+            ("accessors", self.accessors),
+            ("bufferViews", self.buffer_views),
+        ];
+        for (key, values) in arrays {
+            if !values.is_empty() {
+                root[key] = Value::Array(values);
+            }
+        }
+        if !root["textures"].is_null() {
+            root["samplers"] = json!([{}]);
+        }
+        if !self.buffer.is_empty() {
+            root["buffers"] = json!([{ "byteLength": self.buffer.len() }]);
+        }
+
+        let mut json = serde_json::to_vec(&root).unwrap();
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        let mut length = 12 + 8 + json.len();
+        if !self.buffer.is_empty() {
+            length += 8 + self.buffer.len();
+        }
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        if !self.buffer.is_empty() {
+            glb.extend_from_slice(&(self.buffer.len() as u32).to_le_bytes());
+            glb.extend_from_slice(b"BIN\0");
+            glb.extend_from_slice(&self.buffer);
+        }
+        glb
+    }
+}
+
+/// glTF tangents carry the handedness of the bitangent in w, which the
+/// mesh doesn't store, so it's recovered from the UV layout of each triangle
+fn tangents_with_handedness(mesh: &Mesh) -> Vec<Vec4> {
+    let positions = mesh.positions();
+    let normals = mesh.normals();
+    let texcoords = mesh.texcoords(0);
+    let mut bitangents = vec![Vec3::ZERO; mesh.tangents().len()];
+    if normals.len() == bitangents.len() && texcoords.len() == bitangents.len() {
+        for tri in mesh.indices().chunks_exact(3) {
+            let [a, b, c] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            if [a, b, c].iter().any(|&i| i >= bitangents.len()) {
+                continue;
+            }
+            let (e1, e2) = (positions[b] - positions[a], positions[c] - positions[a]);
+            let (d1, d2) = (texcoords[b] - texcoords[a], texcoords[c] - texcoords[a]);
+            let det = d1.x * d2.y - d2.x * d1.y;
+            if det.abs() < f32::EPSILON {
+                continue;
+            }
+            let bitangent = (e2 * d1.x - e1 * d2.x) / det;
+            for i in [a, b, c] {
+                bitangents[i] += bitangent;
+            }
+        }
+    }
+    mesh.tangents()
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let handedness = match normals.get(i) {
+                Some(n) if n.cross(*t).dot(bitangents[i]) < 0. => -1.,
+                _ => 1.,
+            };
+            t.extend(handedness)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ambient_animation::{AnimationClip, AnimationOutputs, AnimationTarget, AnimationTrack};
+    use ambient_core::{
+        hierarchy::{children, parent},
+        name,
+        transform::translation,
+    };
+    use ambient_ecs::{
+        generated::animation::components::bind_id, query, Entity, World, WorldContext,
+    };
+    use ambient_model::{pbr_renderer_primitives_from_url, Model, PbrRenderPrimitiveFromUrl};
+    use ambient_native_std::mesh::MeshBuilder;
+    use futures::FutureExt;
+    use glam::{vec2, vec3, Vec3};
+
+    use super::{tangents_with_handedness, GltfExportOptions};
+    use crate::{
+        dotdot_path,
+        gltf::{gltf_import::GltfImport, import},
+        model_crate::ModelCrate,
+    };
+
+    /// A prefab of a model with a mesh and a bone, which is animated by its `bind_id`.
+    fn model_crate() -> ModelCrate {
+        let mut model_crate = ModelCrate::new();
+        let mesh = MeshBuilder {
+            positions: vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+            indices: vec![0, 1, 2],
+            ..MeshBuilder::default()
+        }
+        .build()
+        .unwrap();
+        let mesh = model_crate.meshes.insert("triangle", mesh).path;
+
+        let mut model = World::new("model", WorldContext::Prefab);
+        let bone = Entity::new()
+            .with(name(), "Bone".to_string())
+            .with(bind_id(), "Bone".to_string())
+            .with(translation(), vec3(0., 0., 1.))
+            .spawn(&mut model);
+        let root = Entity::new()
+            .with(name(), "Root".to_string())
+            .with(
+                pbr_renderer_primitives_from_url(),
+                vec![PbrRenderPrimitiveFromUrl {
+                    mesh: dotdot_path(mesh).into(),
+                    material: None,
+                    lod: 0,
+                }],
+            )
+            .with(children(), vec![bone])
+            .spawn(&mut model);
+        model.add_component(bone, parent(), root).unwrap();
+        model.add_resource(children(), vec![root]);
+        model_crate.models.insert(ModelCrate::MAIN, Model(model));
+        model_crate.create_prefab_from_model();
+
+        let mut clip = AnimationClip::from_tracks(vec![AnimationTrack {
+            target: AnimationTarget::BinderId("Bone".to_string()),
+            inputs: vec![0., 1.],
+            outputs: AnimationOutputs::Vec3 {
+                component: translation(),
+                data: vec![vec3(0., 0., 1.), vec3(0., 0., 2.)],
+            },
+        }]);
+        clip.id = "raise".to_string();
+        model_crate.animations.insert("raise", clip);
+        model_crate
+    }
+
+    #[test]
+    fn prefab_round_trips_through_the_importer() {
+        ambient_ecs::init_components();
+        ambient_core::init_all_components();
+        ambient_model::init_components();
+
+        let glb = model_crate()
+            .export_glb(&GltfExportOptions::default())
+            .unwrap();
+        let gltf = GltfImport::from_slice("exported".to_string(), true, &glb).unwrap();
+        let mut imported = ModelCrate::new();
+        import(&gltf, &mut imported)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let mesh = imported.meshes.content.values().next().unwrap();
+        assert_eq!(imported.meshes.content.len(), 1);
+        assert_eq!(mesh.positions(), &[Vec3::ZERO, Vec3::X, Vec3::Y]);
+        assert_eq!(mesh.indices(), &[0, 1, 2]);
+
+        let world = &imported.model().0;
+        let node = |node_name: &str| {
+            query(name())
+                .iter(world, None)
+                .find(|(_, name)| name.as_str() == node_name)
+                .map(|(id, _)| id)
+                .unwrap()
+        };
+        let (root, bone) = (node("Root"), node("Bone"));
+        assert_eq!(world.get(bone, parent()).unwrap(), root);
+        assert_eq!(world.get(bone, translation()).unwrap(), vec3(0., 0., 1.));
+
+        // The animation of the model's bone is exported even though the bone isn't in the prefab's world
+        let clip = imported.animations.content.values().next().unwrap();
+        assert_eq!(clip.id, "raise");
+        assert_eq!(clip.tracks.len(), 1);
+        let track = &clip.tracks[0];
+        assert_eq!(
+            track.target,
+            AnimationTarget::BinderId(world.get_cloned(bone, bind_id()).unwrap())
+        );
+        assert_eq!(track.inputs, vec![0., 1.]);
+        match &track.outputs {
+            AnimationOutputs::Vec3 { component, data } => {
+                assert_eq!(*component, translation());
+                assert_eq!(data, &vec![vec3(0., 0., 1.), vec3(0., 0., 2.)]);
+            }
+            _ => panic!("Expected a translation track"),
+        }
+    }
+
+    #[test]
+    fn tangent_handedness_follows_the_uvs() {
+        let triangle = |v: f32| {
+            MeshBuilder {
+                positions: vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+                normals: vec![Vec3::Z; 3],
+                tangents: vec![Vec3::X; 3],
+                texcoords: vec![vec![vec2(0., 0.), vec2(1., 0.), vec2(0., v)]],
+                indices: vec![0, 1, 2],
+                ..MeshBuilder::default()
+            }
+            .build()
+            .unwrap()
+        };
+        let handedness = |v| {
+            tangents_with_handedness(&triangle(v))
+                .iter()
+                .map(|t| t.w)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(handedness(1.), vec![1.; 3]);
+        // Mirrored UVs flip the bitangent
+        assert_eq!(handedness(-1.), vec![-1.; 3]);
+    }
+}
@@ -33,8 +33,11 @@ use crate::{
     model_crate::ModelCrate,
 };
 
+mod gltf_export;
 mod gltf_import;
 
+pub use gltf_export::*;
+
 pub async fn import_url(
     assets: &AssetCache,
     url: &AbsAssetUrl,
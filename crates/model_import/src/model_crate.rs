@@ -27,7 +27,7 @@ use ambient_physics::{
         character_controller_height, character_controller_radius, collider, ColliderDef,
         ColliderFromUrls,
     },
-    mesh::PhysxGeometryFromUrl,
+    mesh::{convex_decomposition, PhysxGeometryFromUrl},
     physx::PhysicsKey,
 };
 use ambient_renderer::{
@@ -48,6 +48,9 @@ use relative_path::RelativePathBuf;
 
 use crate::{dotdot_path, material_filter_matches, MaterialFilter, TextureResolver};
 
+/// A generated LOD is only kept if it has at most this fraction of the triangles of the LOD before it.
+const MIN_LOD_REDUCTION: f32 = 0.8;
+
 #[derive(Debug, Clone)]
 pub struct AssetLoc {
     pub id: String,
@@ -417,6 +420,88 @@ impl ModelCrate {
                 .collect(),
         )
     }
+    /// Generates simplified LODs for every node which doesn't have any, where `ratios` is the
+    /// fraction of the original triangles to keep for each LOD after the first.
+    ///
+    /// LODs which would barely have fewer triangles than the one before them, like those of meshes
+    /// which are already simple, are skipped. The cutoffs keep the on-screen triangle density
+    /// roughly constant, and the last LOD is never culled.
+    pub fn generate_lods(&mut self, ratios: &[f32]) {
+        if ratios.is_empty() {
+            return;
+        }
+        let ratios = ratios
+            .iter()
+            .copied()
+            .sorted_by(|a, b| b.total_cmp(a))
+            .collect_vec();
+
+        let entities =
+            query(pbr_renderer_primitives_from_url()).collect_cloned(self.model_world(), None);
+        for (id, primitives) in entities {
+            if primitives.iter().any(|p| p.lod != 0) {
+                continue;
+            }
+            let original_count = primitives
+                .iter()
+                .filter_map(|primitive| self.meshes.get_by_path(primitive.mesh.path()))
+                .map(|mesh| mesh.index_count())
+                .sum::<u32>();
+            let mut previous_count = original_count;
+            let mut lod_primitives = primitives.clone();
+            let mut cutoffs = Vec::new();
+            for &ratio in &ratios {
+                let mut simplified = Vec::new();
+                for primitive in &primitives {
+                    let Some(mesh_id) = self.meshes.loc.id_from_path(primitive.mesh.path()) else {
+                        continue;
+                    };
+                    let Some(mesh) = self.meshes.content.get(&mesh_id) else {
+                        continue;
+                    };
+                    match mesh.simplified(ratio) {
+                        Ok(mesh) => simplified.push((primitive, mesh_id, mesh)),
+                        Err(err) => {
+                            tracing::warn!("Failed to simplify mesh {mesh_id}: {err:?}");
+                        }
+                    }
+                }
+                let count = simplified
+                    .iter()
+                    .map(|(_, _, mesh)| mesh.index_count())
+                    .sum::<u32>();
+                if simplified.is_empty() || count as f32 > previous_count as f32 * MIN_LOD_REDUCTION
+                {
+                    continue;
+                }
+                previous_count = count;
+                cutoffs.push((count as f32 / original_count as f32).sqrt());
+
+                let lod = cutoffs.len();
+                for (primitive, mesh_id, mesh) in simplified {
+                    let mesh_path = self.meshes.insert(format!("{mesh_id}_lod{lod}"), mesh).path;
+                    lod_primitives.push(PbrRenderPrimitiveFromUrl {
+                        mesh: dotdot_path(mesh_path).into(),
+                        material: primitive.material.clone(),
+                        lod,
+                    });
+                }
+            }
+            if cutoffs.is_empty() {
+                continue;
+            }
+            cutoffs.push(0.);
+
+            let world = self.model_world_mut();
+            world
+                .set(id, pbr_renderer_primitives_from_url(), lod_primitives)
+                .unwrap();
+            world
+                .add_component(id, lod_cutoffs(), LodCutoffs::new(&cutoffs))
+                .unwrap();
+            world.add_component(id, gpu_lod(), ()).unwrap();
+        }
+    }
     pub fn set_all_material(&mut self, material: PbrMaterialDesc) {
         self.materials.content.clear();
         let mat_path = dotdot_path(self.materials.insert("main".to_string(), material).path);
@@ -611,30 +696,6 @@ impl ModelCrate {
         reverse_indices: bool,
     ) -> anyhow::Result<()> {
         self.update_transforms();
-        let physics = PhysicsKey.get(assets);
-        let create_triangle_mesh = |asset_crate: &mut ModelCrate, id: &str| -> bool {
-            if asset_crate.px_triangle_meshes.content.contains_key(id) {
-                return true;
-            }
-            let mesh = asset_crate.meshes.content.get(id).unwrap();
-            if let Some(desc) =
-                physx_triangle_mesh_desc_from_mesh(mesh, flip_normals, reverse_indices)
-            {
-                let stream = PxDefaultMemoryOutputStream::new();
-                let mut res = physxx::PxTriangleMeshCookingResult::Success;
-                if !physics.cooking.cook_triangle_mesh(&desc, &stream, &mut res) {
-                    tracing::error!("Failed to cook triangle mesh: {:?}", res);
-                    return false;
-                }
-                asset_crate
-                    .px_triangle_meshes
-                    .content
-                    .insert(id.to_string(), stream.get_data());
-                true
-            } else {
-                false
-            }
-        };
         let create_convex_mesh = |asset_crate: &mut ModelCrate,
                                   id: &str,
                                   scale_signum: Vec3|
@@ -652,30 +713,14 @@ impl ModelCrate {
                 return Some(asset_crate.px_convex_meshes.loc.path(&full_id));
             }
             let mesh = asset_crate.meshes.content.get(id).unwrap();
-
-            let desc = PxConvexMeshDesc {
-                // Apply the correct mirroring according to the base scale
-                points: mesh
-                    .positions()
-                    .iter()
-                    .map(|&p| p * scale_signum)
-                    .collect_vec(),
-                indices: Some(mesh.indices().to_vec()),
-                vertex_limit: None,
-                flags: Some(PxConvexFlag::COMPUTE_CONVEX),
-            };
-            let stream = PxDefaultMemoryOutputStream::new();
-            let mut res = physxx::PxConvexMeshCookingResult::Success;
-            if !physics.cooking.cook_convex_mesh(&desc, &stream, &mut res) {
-                tracing::error!("Failed to cook convex mesh: {:?}", res);
-                return None;
-            }
-            Some(
-                asset_crate
-                    .px_convex_meshes
-                    .insert(full_id, stream.get_data())
-                    .path,
-            )
+            // Apply the correct mirroring according to the base scale
+            let points = mesh
+                .positions()
+                .iter()
+                .map(|&p| p * scale_signum)
+                .collect_vec();
+            let indices = mesh.indices().to_vec();
+            asset_crate.cook_convex_mesh(assets, full_id, points, Some(indices))
         };
         let mut convex = Vec::new();
         let mut triangle = Vec::new();
@@ -699,7 +744,7 @@ impl ModelCrate {
                     let transform = world_transform * ltw * mtl;
                     let (scale, rot, pos) = transform.to_scale_rotation_translation();
                     let mesh_id = self.meshes.loc.id_from_path(primitive.mesh.path()).unwrap();
-                    if create_triangle_mesh(self, &mesh_id) {
+                    if self.cook_triangle_mesh(assets, &mesh_id, flip_normals, reverse_indices) {
                         if let Some(convex_path) =
                             create_convex_mesh(self, &mesh_id, scale.signum())
                         {
@@ -716,24 +761,132 @@ impl ModelCrate {
                 }
             }
         }
-        let obj_collider = self.colliders.insert(
-            ModelCrate::MAIN.to_string(),
-            ColliderFromUrls {
-                convex,
-                concave: triangle,
+        self.set_collider(ColliderFromUrls {
+            convex,
+            concave: triangle,
+        });
+        Ok(())
+    }
+    /// Creates a collider from convex pieces of the model, which unlike triangle meshes can be used by dynamic actors
+    pub fn create_convex_decomposition_collider(
+        &mut self,
+        assets: &AssetCache,
+        max_hulls: usize,
+        resolution: u32,
+    ) {
+        self.update_transforms();
+        let world_transform = self.model().get_transform().unwrap_or_default();
+        let entities =
+            query(pbr_renderer_primitives_from_url()).collect_cloned(self.model_world(), None);
+        let mut positions = Vec::new();
+        let mut indices = Vec::new();
+        for (id, prims) in entities {
+            let Some(max_lod) = prims.iter().map(|x| x.lod).max() else {
+                continue;
+            };
+            let world = self.model_world();
+            let transform = world_transform
+                * world.get(id, local_to_world()).unwrap_or_default()
+                * world.get(id, mesh_to_local()).unwrap_or_default();
+            // Only use the "max" lod for colliders
+            for primitive in prims.into_iter().filter(|x| x.lod == max_lod) {
+                let Some(mesh) = self.meshes.get_by_path(primitive.mesh.path()) else {
+                    continue;
+                };
+                let offset = positions.len() as u32;
+                positions.extend(
+                    mesh.positions()
+                        .iter()
+                        .map(|&p| transform.transform_point3(p)),
+                );
+                indices.extend(mesh.indices().iter().map(|&i| i + offset));
+            }
+        }
+
+        let hulls = convex_decomposition(&positions, &indices, max_hulls, resolution)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, points)| {
+                let path = self.cook_convex_mesh(assets, format!("hull_{i}"), points, None)?;
+                Some((
+                    Mat4::IDENTITY,
+                    PhysxGeometryFromUrl(dotdot_path(path).into()),
+                ))
+            })
+            .collect_vec();
+        self.set_collider(ColliderFromUrls {
+            convex: hulls.clone(),
+            concave: hulls,
+        });
+    }
+    /// Cooks a PhysX triangle mesh from one of the meshes in this crate, and stores it under the same id.
+    /// Returns false if the mesh couldn't be cooked
+    pub fn cook_triangle_mesh(
+        &mut self,
+        assets: &AssetCache,
+        mesh_id: &str,
+        flip_normals: bool,
+        reverse_indices: bool,
+    ) -> bool {
+        if self.px_triangle_meshes.content.contains_key(mesh_id) {
+            return true;
+        }
+        let physics = PhysicsKey.get(assets);
+        let mesh = self.meshes.content.get(mesh_id).unwrap();
+        if let Some(desc) = physx_triangle_mesh_desc_from_mesh(mesh, flip_normals, reverse_indices)
+        {
+            let stream = PxDefaultMemoryOutputStream::new();
+            let mut res = physxx::PxTriangleMeshCookingResult::Success;
+            if !physics.cooking.cook_triangle_mesh(&desc, &stream, &mut res) {
+                tracing::error!("Failed to cook triangle mesh: {:?}", res);
+                return false;
+            }
+            self.px_triangle_meshes
+                .content
+                .insert(mesh_id.to_string(), stream.get_data());
+            true
+        } else {
+            false
+        }
+    }
+    /// Cooks a PhysX convex mesh from the convex hull of `points`, and stores it under `id`
+    pub fn cook_convex_mesh(
+        &mut self,
+        assets: &AssetCache,
+        id: impl Into<String>,
+        points: Vec<Vec3>,
+        indices: Option<Vec<u32>>,
+    ) -> Option<RelativePathBuf> {
+        let id = id.into();
+        if self.px_convex_meshes.content.contains_key(&id) {
+            return Some(self.px_convex_meshes.loc.path(&id));
+        }
+        let physics = PhysicsKey.get(assets);
+        let desc = PxConvexMeshDesc {
+            points,
+            indices,
+            vertex_limit: None,
+            flags: Some(PxConvexFlag::COMPUTE_CONVEX),
+        };
+        let stream = PxDefaultMemoryOutputStream::new();
+        let mut res = physxx::PxConvexMeshCookingResult::Success;
+        if !physics.cooking.cook_convex_mesh(&desc, &stream, &mut res) {
+            tracing::error!("Failed to cook convex mesh: {:?}", res);
+            return None;
+        }
+        Some(self.px_convex_meshes.insert(id, stream.get_data()).path)
+    }
+    /// Stores the collider in this crate and attaches it to the prefab
+    pub fn set_collider(&mut self, collider: ColliderFromUrls) {
+        let obj_collider = self
+            .colliders
+            .insert(ModelCrate::MAIN.to_string(), collider);
+        self.add_component_to_prefab(
+            collider(),
+            ColliderDef::Asset {
+                collider: dotdot_path(obj_collider.path).into(),
             },
         );
-        let prefab = self.prefab_world_mut();
-        prefab
-            .add_component(
-                prefab.resource(children())[0],
-                collider(),
-                ColliderDef::Asset {
-                    collider: dotdot_path(obj_collider.path).into(),
-                },
-            )
-            .unwrap();
-        Ok(())
     }
 }
 pub struct AssetItem {
@@ -42,6 +42,16 @@ impl SyncAssetKey<AbsAssetUrl> for ContentBaseUrlKey {
     }
 }
 
+/// The URL that deployed packages are retrieved from. Defaults to
+/// [deployments_base_url](ambient_shared_types::urls::deployments_base_url).
+#[derive(Debug, Clone)]
+pub struct DeploymentsBaseUrlKey;
+impl SyncAssetKey<String> for DeploymentsBaseUrlKey {
+    fn load(&self, _assets: AssetCache) -> String {
+        ambient_shared_types::urls::deployments_base_url()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UsingLocalDebugAssetsKey;
 impl SyncAssetKey<bool> for UsingLocalDebugAssetsKey {
@@ -1,17 +1,440 @@
-use std::{fmt::Debug, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
+use ambient_asset_cache::AssetsTimeline;
+use ambient_sys::time::SystemTime;
 use anyhow::Context;
 use async_trait::async_trait;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
-    asset_cache::{Asset, AssetCache, AsyncAssetKey, AsyncAssetKeyExt, SyncAssetKeyExt},
-    download_asset::{AssetError, AssetsCacheDir},
-    sha256_digest,
+    asset_cache::{
+        Asset, AssetCache, AsyncAssetKey, AsyncAssetKeyExt, SyncAssetKey, SyncAssetKeyExt,
+    },
+    download_asset::{AssetError, AssetsCacheDir, AssetsCacheOnDisk},
+    friendly_id, sha256_digest,
 };
 
+/// The maximum total size of the on-disk asset cache, in bytes. Defaults to 10 GiB.
+#[derive(Clone, Debug)]
+pub struct DiskCacheBudget;
+impl SyncAssetKey<u64> for DiskCacheBudget {
+    fn load(&self, _assets: AssetCache) -> u64 {
+        10 * 1024 * 1024 * 1024
+    }
+}
+
+/// The on-disk asset cache, stored in the `content` directory of [AssetsCacheDir].
+#[derive(Clone, Debug)]
+pub struct DiskCacheKey;
+impl SyncAssetKey<Arc<DiskCache>> for DiskCacheKey {
+    fn load(&self, assets: AssetCache) -> Arc<DiskCache> {
+        let dir = AssetsCacheDir.get(&assets).join("content");
+        Arc::new(
+            DiskCache::open(dir, DiskCacheBudget.get(&assets))
+                .with_timeline(assets.timeline.clone()),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct DiskCacheEntry {
+    /// The SHA-256 of the content, which is also the name of the file it is stored in
+    hash: String,
+    size: u64,
+    /// Milliseconds since the Unix epoch
+    last_access: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DiskCacheIndex {
+    entries: HashMap<String, DiskCacheEntry>,
+}
+
+#[derive(Debug, Default)]
+struct DiskCacheState {
+    index: DiskCacheIndex,
+    /// Hashes whose files have been verified during this session
+    verified: HashSet<String>,
+    /// Hashes whose paths have been handed out during this session. Their files may still be read, so they're
+    /// never deleted before the next session
+    pinned: HashSet<String>,
+    dirty: bool,
+}
+
+/// A content-addressed cache for downloaded and processed assets that persists between sessions.
+///
+/// Entries are looked up by key (e.g. the URL they were downloaded from) and stored by the hash
+/// of their content, so identical content is only stored once. The content is verified against
+/// its hash the first time it's used in a session, and the least recently used entries are evicted
+/// when the cache grows beyond its budget. Entries whose paths were handed out by [Self::get_path]
+/// or [Self::insert_file] are only evicted in a later session.
+///
+/// Hashing large files takes a while, so the methods that may do it should be called from a
+/// blocking task.
+pub struct DiskCache {
+    dir: PathBuf,
+    budget: u64,
+    state: Mutex<DiskCacheState>,
+    timeline: Option<Arc<parking_lot::Mutex<AssetsTimeline>>>,
+}
+impl Debug for DiskCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiskCache")
+            .field("dir", &self.dir)
+            .field("budget", &self.budget)
+            .finish()
+    }
+}
+impl DiskCache {
+    const INDEX_FILENAME: &'static str = "index.json";
+
+    /// Opens the cache in `dir`, creating it if it does not exist.
+    pub fn open(dir: impl Into<PathBuf>, budget: u64) -> Self {
+        let dir = dir.into();
+        let mut index = std::fs::read(dir.join(Self::INDEX_FILENAME))
+            .ok()
+            .and_then(
+                |data| match serde_json::from_slice::<DiskCacheIndex>(&data) {
+                    Ok(index) => Some(index),
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed to parse disk cache index, starting over: {:?}",
+                            err
+                        );
+                        None
+                    }
+                },
+            )
+            .unwrap_or_default();
+        index
+            .entries
+            .retain(|_, entry| dir.join(&entry.hash).is_file());
+
+        let cache = Self {
+            dir,
+            budget,
+            state: Mutex::new(DiskCacheState {
+                index,
+                ..Default::default()
+            }),
+            timeline: None,
+        };
+        {
+            let mut state = cache.state.lock().unwrap();
+            cache.evict_locked(&mut state, "");
+            cache.remove_orphans_locked(&state);
+        }
+        cache
+    }
+
+    /// Records the cache's activity in `timeline`.
+    pub fn with_timeline(mut self, timeline: Arc<parking_lot::Mutex<AssetsTimeline>>) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
+    /// Returns the total size of the content in the cache.
+    pub fn total_size(&self) -> u64 {
+        Self::total_size_of(&self.state.lock().unwrap().index)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.state.lock().unwrap().index.entries.contains_key(key)
+    }
+
+    /// Returns the path of the file holding the content for `key`, if it is cached and intact.
+    ///
+    /// The file is kept until the end of the session.
+    pub fn get_path(&self, key: &str) -> Option<PathBuf> {
+        self.lookup(key, true)
+    }
+
+    fn lookup(&self, key: &str, pin: bool) -> Option<PathBuf> {
+        let (hash, verified) = {
+            let state = self.state.lock().unwrap();
+            let hash = state.index.entries.get(key)?.hash.clone();
+            let verified = state.verified.contains(&hash);
+            (hash, verified)
+        };
+        let path = self.dir.join(&hash);
+
+        // The file is hashed without holding the lock, as it may take a while
+        let result = (!verified).then(|| hash_file(&path));
+
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        match result {
+            None => {}
+            Some(Ok(actual)) if actual == hash => {
+                state.verified.insert(actual);
+            }
+            Some(result) => {
+                tracing::warn!(
+                    "Disk cache entry {key:?} failed its integrity check ({:?}); discarding",
+                    result.err()
+                );
+                if state.index.entries.get(key).map(|entry| &entry.hash) == Some(&hash) {
+                    self.remove_locked(state, key);
+                }
+                return None;
+            }
+        }
+
+        // The entry may have been replaced or removed while the file was being hashed
+        let entry = state
+            .index
+            .entries
+            .get_mut(key)
+            .filter(|entry| entry.hash == hash)?;
+        entry.last_access = now_ms();
+        state.dirty = true;
+        if let Some(timeline) = &self.timeline {
+            timeline.lock().disk_cache_hit(key, entry.size);
+        }
+        if pin {
+            state.pinned.insert(hash);
+        }
+
+        Some(path)
+    }
+
+    /// Returns the content for `key`, if it is cached and intact.
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.lookup(key, false)?;
+        match std::fs::read(&path) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                tracing::warn!("Failed to read disk cache file {path:?}: {:?}", err);
+                self.remove(key);
+                None
+            }
+        }
+    }
+
+    /// Stores `data` under `key`.
+    pub fn insert(&self, key: &str, data: &[u8]) -> anyhow::Result<()> {
+        let tmp_path = self.tmp_path();
+        std::fs::write(&tmp_path, data)
+            .with_context(|| format!("Failed to write disk cache file {tmp_path:?}"))?;
+        self.insert_file_with(key, &tmp_path, false)?;
+        Ok(())
+    }
+
+    /// Moves the file at `path` into the cache under `key`, and returns the path of the file in the cache.
+    ///
+    /// The file is kept until the end of the session.
+    pub fn insert_file(&self, key: &str, path: &Path) -> anyhow::Result<PathBuf> {
+        self.insert_file_with(key, path, true)
+    }
+
+    fn insert_file_with(&self, key: &str, path: &Path, pin: bool) -> anyhow::Result<PathBuf> {
+        let hash = hash_file(path).with_context(|| format!("Failed to hash {path:?}"))?;
+        let size = std::fs::metadata(path)?.len();
+        let content_path = self.dir.join(&hash);
+        if content_path.is_file() {
+            std::fs::remove_file(path)?;
+        } else {
+            std::fs::rename(path, &content_path).with_context(|| {
+                format!("Failed to move {path:?} into the disk cache at {content_path:?}")
+            })?;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(previous) = state.index.entries.get(key).cloned() {
+            if previous.hash != hash {
+                self.remove_locked(&mut state, key);
+            }
+        }
+        state.index.entries.insert(
+            key.to_string(),
+            DiskCacheEntry {
+                hash: hash.clone(),
+                size,
+                last_access: now_ms(),
+            },
+        );
+        state.verified.insert(hash.clone());
+        if pin {
+            state.pinned.insert(hash);
+        }
+        state.dirty = true;
+        if let Some(timeline) = &self.timeline {
+            timeline.lock().disk_cache_insert(key, size);
+        }
+
+        self.evict_locked(&mut state, key);
+        self.flush_locked(&mut state)?;
+
+        Ok(content_path)
+    }
+
+    pub fn remove(&self, key: &str) {
+        let mut state = self.state.lock().unwrap();
+        self.remove_locked(&mut state, key);
+    }
+
+    /// Returns a path in the cache directory that can be used to write content before inserting it with [Self::insert_file].
+    pub fn tmp_path(&self) -> PathBuf {
+        std::fs::create_dir_all(&self.dir).ok();
+        self.dir.join(format!("{}.tmp", friendly_id()))
+    }
+
+    /// Writes the index to disk if it has changed.
+    pub fn flush(&self) -> anyhow::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        self.flush_locked(&mut state)
+    }
+
+    fn flush_locked(&self, state: &mut DiskCacheState) -> anyhow::Result<()> {
+        if !state.dirty {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.dir).context("Failed to create disk cache dir")?;
+        let tmp_path = self.tmp_path();
+        std::fs::write(&tmp_path, serde_json::to_vec(&state.index)?)
+            .context("Failed to write disk cache index")?;
+        std::fs::rename(&tmp_path, self.dir.join(Self::INDEX_FILENAME))
+            .context("Failed to replace disk cache index")?;
+        state.dirty = false;
+
+        Ok(())
+    }
+
+    /// Evicts the least recently used entries until the cache fits its budget, keeping `keep` and
+    /// the pinned entries.
+    fn evict_locked(&self, state: &mut DiskCacheState, keep: &str) {
+        let mut total_size = Self::total_size_of(&state.index);
+        if total_size <= self.budget {
+            return;
+        }
+
+        let mut entries = state
+            .index
+            .entries
+            .iter()
+            .filter(|(key, entry)| key.as_str() != keep && !state.pinned.contains(&entry.hash))
+            .map(|(key, entry)| (entry.last_access, key.clone()))
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        for (_, key) in entries {
+            if total_size <= self.budget {
+                break;
+            }
+            total_size -= self.remove_locked(state, &key);
+        }
+    }
+
+    /// Removes the entry for `key`, deleting its content if no other entry refers to it and it isn't
+    /// pinned. Returns the number of bytes freed.
+    fn remove_locked(&self, state: &mut DiskCacheState, key: &str) -> u64 {
+        let Some(entry) = state.index.entries.remove(key) else {
+            return 0;
+        };
+        state.dirty = true;
+        if let Some(timeline) = &self.timeline {
+            timeline.lock().disk_cache_evict(key, entry.size);
+        }
+
+        if state
+            .index
+            .entries
+            .values()
+            .any(|other| other.hash == entry.hash)
+        {
+            return 0;
+        }
+        if state.pinned.contains(&entry.hash) {
+            // Deleted by [Self::remove_orphans_locked] in a later session
+            return 0;
+        }
+
+        state.verified.remove(&entry.hash);
+        if let Err(err) = std::fs::remove_file(self.dir.join(&entry.hash)) {
+            tracing::warn!("Failed to remove disk cache file {}: {:?}", entry.hash, err);
+        }
+        entry.size
+    }
+
+    /// Deletes the content files that no entry refers to, which are left behind when pinned entries
+    /// are removed.
+    fn remove_orphans_locked(&self, state: &DiskCacheState) {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let hashes = state
+            .index
+            .entries
+            .values()
+            .map(|entry| entry.hash.as_str())
+            .collect::<HashSet<_>>();
+        for file in dir.flatten() {
+            let name = file.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            // Temporary files may belong to another process that's using the cache
+            let is_content = name.len() == 64 && name.bytes().all(|c| c.is_ascii_hexdigit());
+            if is_content && !hashes.contains(name) {
+                if let Err(err) = std::fs::remove_file(file.path()) {
+                    tracing::warn!("Failed to remove disk cache file {name}: {:?}", err);
+                }
+            }
+        }
+    }
+
+    fn total_size_of(index: &DiskCacheIndex) -> u64 {
+        let mut seen = HashSet::new();
+        index
+            .entries
+            .values()
+            .filter(|entry| seen.insert(&entry.hash))
+            .map(|entry| entry.size)
+            .sum()
+    }
+}
+impl Drop for DiskCache {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            tracing::warn!("Failed to save disk cache index: {:?}", err);
+        }
+    }
+}
+
+fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let len = file.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        context.update(&buffer[..len]);
+    }
+    Ok(data_encoding::HEXLOWER.encode(context.finish().as_ref()))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
 /// This can wrap any resource which is Serializable, and will cache that resource to disk
 /// for faster loads in the future
+///
+/// The resource is stored in the [DiskCacheKey] cache under the hash of the wrapped key's [Debug] output, so
+/// it's only useful for keys whose result doesn't change between sessions. Without [AssetsCacheOnDisk], the
+/// wrapped key is loaded as is.
 #[derive(Debug, Clone)]
 pub struct DiskCachedJson<T>(pub T);
 
@@ -22,17 +445,21 @@ impl<
     > AsyncAssetKey<Result<V, AssetError>> for DiskCachedJson<T>
 {
     async fn load(self, assets: AssetCache) -> Result<V, AssetError> {
-        let cache_dir = AssetsCacheDir
-            .try_get(&assets)
-            .unwrap_or_else(|| PathBuf::from("tmp"));
-        let cache_dir = cache_dir.join("json_cache");
-        std::fs::create_dir_all(&cache_dir).context("Failed to created cache dir")?;
-        let cache_key = sha256_digest(&format!("{:?}", self.0));
-        let file = cache_dir.join(cache_key);
-        if file.exists() {
-            let data = ambient_sys::fs::read(&file)
-                .await
-                .context("Failed to read cache file")?;
+        if !AssetsCacheOnDisk.get(&assets) {
+            return self.0.get(&assets).await;
+        }
+
+        let cache = DiskCacheKey.get(&assets);
+        let cache_key = format!("json:{}", sha256_digest(&format!("{:?}", self.0)));
+        // The cache hashes and writes files, so it's used from a blocking task
+        let cached = ambient_sys::task::spawn_blocking({
+            let cache = cache.clone();
+            let cache_key = cache_key.clone();
+            move || cache.get(&cache_key)
+        })
+        .await
+        .context("Failed to look up the disk cache")?;
+        if let Some(data) = cached {
             match serde_json::from_slice(&data) {
                 Ok(value) => return Ok(value),
                 Err(err) => {
@@ -40,10 +467,103 @@ impl<
                 }
             }
         }
+
         let value = self.0.get(&assets).await?;
-        ambient_sys::fs::write(file, serde_json::to_string(&value).unwrap())
+        let data = serde_json::to_vec(&value).context("Failed to serialize asset")?;
+        ambient_sys::task::spawn_blocking(move || cache.insert(&cache_key, &data))
             .await
+            .context("Failed to insert into the disk cache")?
             .context("Failed to write cache file")?;
         Ok(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DiskCache;
+
+    fn open(test: &str, budget: u64) -> (std::path::PathBuf, DiskCache) {
+        let dir = std::env::temp_dir()
+            .join("ambient_native_std_disk_cache")
+            .join(test);
+        let _ = std::fs::remove_dir_all(&dir);
+        (dir.clone(), DiskCache::open(dir, budget))
+    }
+
+    #[test]
+    fn persists_between_sessions() {
+        let (dir, cache) = open("persists", 1024);
+        cache.insert("a", b"hello").unwrap();
+        drop(cache);
+
+        let cache = DiskCache::open(&dir, 1024);
+        assert_eq!(cache.get("a").as_deref(), Some(&b"hello"[..]));
+        assert_eq!(cache.get("b"), None);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let (_, cache) = open("lru", 10);
+        cache.insert("a", b"aaaa").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        cache.insert("b", b"bbbb").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(cache.get("a").is_some());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        cache.insert("c", b"cccc").unwrap();
+
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+        assert!(cache.contains("c"));
+        assert_eq!(cache.total_size(), 8);
+    }
+
+    #[test]
+    fn keeps_files_in_use_until_the_next_session() {
+        let (dir, cache) = open("pinned", 10);
+        cache.insert("a", b"aaaa").unwrap();
+        let a = cache.get_path("a").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        cache.insert("b", b"bbbb").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        cache.insert("c", b"cccc").unwrap();
+
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+        assert_eq!(std::fs::read(&a).unwrap(), b"aaaa");
+
+        cache.remove("a");
+        assert!(a.is_file());
+        drop(cache);
+
+        let cache = DiskCache::open(&dir, 4);
+        assert!(!a.is_file());
+        assert!(cache.contains("c"));
+        assert_eq!(cache.total_size(), 4);
+    }
+
+    #[test]
+    fn deduplicates_content() {
+        let (_, cache) = open("dedup", 1024);
+        cache.insert("a", b"same").unwrap();
+        cache.insert("b", b"same").unwrap();
+        assert_eq!(cache.get_path("a"), cache.get_path("b"));
+        assert_eq!(cache.total_size(), 4);
+
+        cache.remove("a");
+        assert_eq!(cache.get("b").as_deref(), Some(&b"same"[..]));
+    }
+
+    #[test]
+    fn discards_corrupted_entries() {
+        let (dir, cache) = open("integrity", 1024);
+        cache.insert("a", b"hello").unwrap();
+        let path = cache.get_path("a").unwrap();
+        drop(cache);
+
+        std::fs::write(path, b"corrupted").unwrap();
+        let cache = DiskCache::open(&dir, 1024);
+        assert_eq!(cache.get("a"), None);
+        assert!(!cache.contains("a"));
+    }
+}
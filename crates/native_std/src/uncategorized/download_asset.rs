@@ -1,5 +1,7 @@
 use std::{marker::PhantomData, path::PathBuf, sync::Arc, time::Duration};
 
+#[cfg(not(target_os = "unknown"))]
+use crate::disk_cache::DiskCacheKey;
 use crate::{
     asset_cache::{AssetCache, AsyncAssetKey, AsyncAssetKeyExt, SyncAssetKey, SyncAssetKeyExt},
     asset_url::AbsAssetUrl,
@@ -209,6 +211,8 @@ impl AsyncAssetKey<AssetResult<Arc<Vec<u8>>>> for BytesFromUrl {
 }
 
 /// Get the local cache file location of a resource, and ensure the resource is downloaded to that cache file
+///
+/// The file is stored in the [DiskCacheKey] cache, so it may be removed by eviction in a later session.
 #[derive(Clone, Debug)]
 #[cfg(not(target_os = "unknown"))]
 pub struct BytesFromUrlCachedPath {
@@ -235,50 +239,57 @@ impl AsyncAssetKey<AssetResult<Arc<PathBuf>>> for BytesFromUrlCachedPath {
             return Ok(Arc::new(path));
         }
 
-        let path = self.url.absolute_cache_path(&assets);
-        if !path.exists() {
-            use tokio::io::AsyncWriteExt;
-            let mut dir = path.clone();
-            dir.pop();
-            std::fs::create_dir_all(&dir)
-                .context(format!("Failed to create asset dir: {dir:?}"))?;
-            let tmp_path = path.with_extension(".downloading");
-            download(
-                &assets,
-                self.url
-                    .to_download_url(&assets)
-                    .map_err(anyhow::Error::new)?
-                    .0,
-                {
+        let cache = DiskCacheKey.get(&assets);
+        let key = self.url.0.to_string();
+        // The cache hashes the file, which can take a while for large assets
+        let cached = ambient_sys::task::spawn_blocking({
+            let cache = cache.clone();
+            let key = key.clone();
+            move || cache.get_path(&key)
+        })
+        .await
+        .context("Failed to look up the disk cache")?;
+        if let Some(path) = cached {
+            return Ok(Arc::new(path));
+        }
+
+        use tokio::io::AsyncWriteExt;
+        let tmp_path = cache.tmp_path();
+        download(
+            &assets,
+            self.url
+                .to_download_url(&assets)
+                .map_err(anyhow::Error::new)?
+                .0,
+            {
+                let tmp_path = tmp_path.clone();
+                move |mut resp| {
                     let tmp_path = tmp_path.clone();
-                    move |mut resp| {
-                        let tmp_path = tmp_path.clone();
-                        async move {
-                            let mut file = tokio::fs::File::create(&tmp_path)
+                    async move {
+                        let mut file = tokio::fs::File::create(&tmp_path)
+                            .await
+                            .context(format!("Failed to create file: {tmp_path:?}"))?;
+                        use std::borrow::BorrowMut;
+                        while let Some(mut item) =
+                            resp.chunk().await.context("Failed to download chunk")?
+                        {
+                            file.write_all_buf(item.borrow_mut())
                                 .await
-                                .context(format!("Failed to create file: {tmp_path:?}"))?;
-                            use std::borrow::BorrowMut;
-                            while let Some(mut item) =
-                                resp.chunk().await.context("Failed to download chunk")?
-                            {
-                                file.write_all_buf(item.borrow_mut())
-                                    .await
-                                    .context("Failed to write to tmp file")?;
-                            }
-                            file.flush().await.context("Failed to flush tmp file")?;
-                            Ok(())
+                                .context("Failed to write to tmp file")?;
                         }
+                        file.flush().await.context("Failed to flush tmp file")?;
+                        Ok(())
                     }
-                },
-            )
-            .await?;
-            std::fs::rename(&tmp_path, &path).context(format!(
-                "Failed to rename tmp file, from: {tmp_path:?}, to: {path:?}"
-            ))?;
-            tracing::debug!("Cached asset at {:?}", path);
-        }
+                }
+            },
+        )
+        .await?;
+        let path = ambient_sys::task::spawn_blocking(move || cache.insert_file(&key, &tmp_path))
+            .await
+            .context("Failed to insert into the disk cache")??;
+        tracing::debug!("Cached asset at {:?}", path);
 
-        return Ok(Arc::new(path));
+        Ok(Arc::new(path))
     }
 }
 
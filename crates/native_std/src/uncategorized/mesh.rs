@@ -1,5 +1,7 @@
+use std::ops::Range;
+
 use crate::shapes::AABB;
-use anyhow::ensure;
+use anyhow::{bail, ensure};
 use glam::*;
 use serde::{Deserialize, Serialize};
 
@@ -21,17 +23,39 @@ impl MeshBuilder {
     }
 
     pub fn build(self) -> anyhow::Result<Mesh> {
-        ensure!(!self.positions.is_empty());
-        ensure!(!self.indices.is_empty());
-        ensure!(self.colors.is_empty() || self.positions.len() == self.colors.len());
-        ensure!(self.normals.is_empty() || self.positions.len() == self.normals.len());
-        ensure!(self.tangents.is_empty() || self.positions.len() == self.tangents.len());
+        ensure!(!self.positions.is_empty(), "a mesh needs positions");
+        ensure!(!self.indices.is_empty(), "a mesh needs indices");
+        ensure!(
+            self.colors.is_empty() || self.positions.len() == self.colors.len(),
+            "colors must have one entry per vertex"
+        );
+        ensure!(
+            self.normals.is_empty() || self.positions.len() == self.normals.len(),
+            "normals must have one entry per vertex"
+        );
+        ensure!(
+            self.tangents.is_empty() || self.positions.len() == self.tangents.len(),
+            "tangents must have one entry per vertex"
+        );
         ensure!(
             self.texcoords.is_empty()
                 || self
                     .texcoords
                     .iter()
-                    .all(|tc| tc.len() == self.positions.len())
+                    .all(|tc| tc.len() == self.positions.len()),
+            "every set of texcoords must have one entry per vertex"
+        );
+        ensure!(
+            self.joint_indices.is_empty() || self.positions.len() == self.joint_indices.len(),
+            "joint indices must have one entry per vertex"
+        );
+        ensure!(
+            self.joint_weights.is_empty() || self.positions.len() == self.joint_weights.len(),
+            "joint weights must have one entry per vertex"
+        );
+        ensure!(
+            self.joint_indices.is_empty() == self.joint_weights.is_empty(),
+            "joint indices and joint weights must be set together"
         );
 
         let mut aabb: crate::shapes::Cuboid = AABB::new_invalid();
@@ -227,4 +251,270 @@ impl Mesh {
         flip_winding(&mut self.indices);
         self
     }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Replaces a range of vertices in place, growing the mesh if the update extends past its end.
+    ///
+    /// Returns the range of vertices that were written. The mesh is left untouched if the update is invalid.
+    pub fn apply_update(&mut self, update: MeshUpdate) -> anyhow::Result<Range<usize>> {
+        let MeshUpdate {
+            vertex_offset: offset,
+            positions,
+            colors,
+            normals,
+            tangents,
+            texcoords,
+            joint_indices,
+            joint_weights,
+            indices,
+        } = update;
+
+        let vertex_count = spliced_len(self.positions.len(), offset, positions.len())?;
+        let mut written = 0;
+        {
+            let mut check = |name: &str, old: usize, new: usize| -> anyhow::Result<()> {
+                let len = spliced_len(old, offset, new)?;
+                ensure!(
+                    len == 0 || len == vertex_count,
+                    "{name} would have {len} entries, but the mesh has {vertex_count} vertices"
+                );
+                written = written.max(new);
+                Ok(())
+            };
+            check("positions", self.positions.len(), positions.len())?;
+            check("colors", self.colors.len(), colors.len())?;
+            check("normals", self.normals.len(), normals.len())?;
+            check("tangents", self.tangents.len(), tangents.len())?;
+            for set in 0..self.texcoords.len().max(texcoords.len()) {
+                let new = texcoords.get(set).map_or(0, |tc| tc.len());
+                check("texcoords", self.texcoords(set).len(), new)?;
+            }
+            check(
+                "joint indices",
+                self.joint_indices.len(),
+                joint_indices.len(),
+            )?;
+            check(
+                "joint weights",
+                self.joint_weights.len(),
+                joint_weights.len(),
+            )?;
+        }
+        ensure!(
+            spliced_len(self.joint_indices.len(), offset, joint_indices.len())?
+                == spliced_len(self.joint_weights.len(), offset, joint_weights.len())?,
+            "joint indices and joint weights must be set together"
+        );
+        if let Some(indices) = &indices {
+            ensure!(
+                !indices.is_empty() && indices.iter().all(|&i| (i as usize) < vertex_count),
+                "indices must be non-empty and refer to existing vertices"
+            );
+        }
+
+        splice(&mut self.positions, offset, positions);
+        splice(&mut self.colors, offset, colors);
+        splice(&mut self.normals, offset, normals);
+        splice(&mut self.tangents, offset, tangents);
+        if self.texcoords.len() < texcoords.len() {
+            self.texcoords.resize_with(texcoords.len(), Vec::new);
+        }
+        for (set, texcoords) in texcoords.into_iter().enumerate() {
+            splice(&mut self.texcoords[set], offset, texcoords);
+        }
+        splice(&mut self.joint_indices, offset, joint_indices);
+        splice(&mut self.joint_weights, offset, joint_weights);
+        if let Some(indices) = indices {
+            self.indices = indices;
+        }
+
+        self.aabb = AABB::new_invalid();
+        for &position in &self.positions {
+            self.aabb.take_point(position);
+        }
+
+        Ok(offset..offset + written)
+    }
+}
+
+/// A partial update of a [Mesh]'s vertices; see [Mesh::apply_update].
+///
+/// Each attribute replaces the mesh's values starting at `vertex_offset`; empty attributes are left unchanged.
+#[derive(Clone, Default, Debug)]
+pub struct MeshUpdate {
+    pub vertex_offset: usize,
+    pub positions: Vec<Vec3>,
+    pub colors: Vec<Vec4>,
+    pub normals: Vec<Vec3>,
+    pub tangents: Vec<Vec3>,
+    pub texcoords: Vec<Vec<Vec2>>,
+    pub joint_indices: Vec<UVec4>,
+    pub joint_weights: Vec<Vec4>,
+    /// Replaces all of the mesh's indices, if set.
+    pub indices: Option<Vec<u32>>,
+}
+
+/// The length of an attribute of length `old` after writing `new` values at `offset`.
+fn spliced_len(old: usize, offset: usize, new: usize) -> anyhow::Result<usize> {
+    if new == 0 {
+        return Ok(old);
+    }
+    if offset > old {
+        bail!("cannot write at vertex {offset}, as the attribute only has {old} entries");
+    }
+    Ok(old.max(offset + new))
+}
+
+fn splice<T>(values: &mut Vec<T>, offset: usize, new: Vec<T>) {
+    if new.is_empty() {
+        return;
+    }
+    let end = (offset + new.len()).min(values.len());
+    values.splice(offset..end, new);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> Mesh {
+        MeshBuilder {
+            positions: vec![Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::ONE],
+            colors: vec![Vec4::ONE; 4],
+            texcoords: vec![vec![Vec2::ZERO; 4]],
+            indices: vec![0, 1, 2, 1, 3, 2],
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn update_replaces_range_in_place() {
+        let mut mesh = quad();
+        let range = mesh
+            .apply_update(MeshUpdate {
+                vertex_offset: 2,
+                positions: vec![Vec3::Z * 2.0, Vec3::ONE * 3.0],
+                colors: vec![Vec4::ZERO],
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(range, 2..4);
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.positions()[2], Vec3::Z * 2.0);
+        assert_eq!(mesh.colors()[1..3], [Vec4::ONE, Vec4::ZERO]);
+        assert_eq!(mesh.aabb().max, Vec3::ONE * 3.0);
+    }
+
+    #[test]
+    fn update_can_grow_the_mesh() {
+        let mut mesh = quad();
+        mesh.apply_update(MeshUpdate {
+            vertex_offset: 4,
+            positions: vec![Vec3::NEG_X],
+            colors: vec![Vec4::ONE],
+            texcoords: vec![vec![Vec2::ONE]],
+            indices: Some(vec![0, 1, 2, 2, 4, 0]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(mesh.vertex_count(), 5);
+        assert_eq!(mesh.texcoords(0).len(), 5);
+        assert_eq!(mesh.index_count(), 6);
+    }
+
+    #[test]
+    fn build_validates_attribute_lengths() {
+        let builder = MeshBuilder {
+            positions: vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+            indices: vec![0, 1, 2],
+            ..Default::default()
+        };
+        let invalid = [
+            MeshBuilder {
+                texcoords: vec![vec![Vec2::ZERO; 3], vec![]],
+                ..builder.clone()
+            },
+            MeshBuilder {
+                joint_indices: vec![UVec4::ZERO; 2],
+                joint_weights: vec![Vec4::X; 3],
+                ..builder.clone()
+            },
+            MeshBuilder {
+                joint_indices: vec![UVec4::ZERO; 3],
+                joint_weights: vec![Vec4::X; 2],
+                ..builder.clone()
+            },
+            // Skinning needs both indices and weights
+            MeshBuilder {
+                joint_indices: vec![UVec4::ZERO; 3],
+                ..builder.clone()
+            },
+        ];
+        for builder in invalid {
+            assert!(builder.build().is_err());
+        }
+
+        let mesh = MeshBuilder {
+            joint_indices: vec![UVec4::ZERO; 3],
+            joint_weights: vec![Vec4::X; 3],
+            ..builder
+        }
+        .build()
+        .unwrap();
+        assert_eq!(mesh.joint_weights().len(), 3);
+    }
+
+    #[test]
+    fn invalid_updates_leave_the_mesh_untouched() {
+        let mut mesh = quad();
+        let invalid = [
+            // Growing without the other attributes
+            MeshUpdate {
+                vertex_offset: 4,
+                positions: vec![Vec3::NEG_X],
+                ..Default::default()
+            },
+            // Writing past the end
+            MeshUpdate {
+                vertex_offset: 5,
+                positions: vec![Vec3::NEG_X],
+                ..Default::default()
+            },
+            // Introducing an attribute for only some vertices
+            MeshUpdate {
+                vertex_offset: 1,
+                normals: vec![Vec3::Z],
+                ..Default::default()
+            },
+            // Indices out of bounds
+            MeshUpdate {
+                indices: Some(vec![0, 1, 4]),
+                ..Default::default()
+            },
+            // Joint indices without weights
+            MeshUpdate {
+                joint_indices: vec![UVec4::ZERO; 4],
+                ..Default::default()
+            },
+        ];
+        for update in invalid {
+            assert!(mesh.apply_update(update).is_err());
+            assert_eq!(mesh.positions(), quad().positions());
+            assert_eq!(mesh.indices(), quad().indices());
+        }
+
+        // Introducing an attribute for every vertex is fine
+        mesh.apply_update(MeshUpdate {
+            normals: vec![Vec3::Z; 4],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(mesh.normals().len(), 4);
+    }
 }
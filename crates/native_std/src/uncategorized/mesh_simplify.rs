@@ -0,0 +1,626 @@
+//! Quadric error mesh simplification, used to generate LODs for meshes which don't come with any.
+//!
+//! Simplification is done with half-edge collapses: a vertex is merged into one of its neighbours,
+//! which keeps all of the neighbour's attributes as they are. This means texture coordinates, colors
+//! and skin weights never have to be interpolated. Vertices with exactly the same attributes are
+//! welded together first. Vertices which still share their position with other vertices (i.e. lie
+//! on a UV or normal seam) are never removed, so seams stay intact.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+use glam::{DVec3, Vec3};
+
+use super::mesh::{Mesh, MeshBuilder};
+
+/// How much more it costs to move a vertex away from an open border than away from a surface.
+const BORDER_WEIGHT: f64 = 10.;
+
+impl Mesh {
+    /// Returns a simplified copy of this mesh with roughly `target_ratio` of its triangles.
+    ///
+    /// The result may have more triangles than requested if the mesh can't be simplified further
+    /// without breaking seams, borders or flipping triangles.
+    pub fn simplified(&self, target_ratio: f32) -> anyhow::Result<Mesh> {
+        let welded = weld(self);
+        let indices = self
+            .indices()
+            .iter()
+            .map(|&i| welded[i as usize])
+            .collect::<Vec<_>>();
+        let indices = simplify_indices(self.positions(), &indices, target_ratio);
+        compact(self, &indices)
+    }
+}
+
+/// Maps each vertex to the first vertex with exactly the same attributes.
+///
+/// Meshes are often exported with duplicate vertices, like one set per triangle or per face, which
+/// would otherwise be treated as seams and never be removed.
+fn weld(mesh: &Mesh) -> Vec<u32> {
+    fn bits(values: impl IntoIterator<Item = f32>) -> impl Iterator<Item = u32> {
+        values.into_iter().map(f32::to_bits)
+    }
+
+    let texcoords = (0..)
+        .map(|set| mesh.texcoords(set))
+        .take_while(|tc| !tc.is_empty())
+        .collect::<Vec<_>>();
+    let mut first = HashMap::new();
+    (0..mesh.vertex_count())
+        .map(|i| {
+            let mut key = bits(mesh.positions()[i].to_array()).collect::<Vec<_>>();
+            if let Some(color) = mesh.colors().get(i) {
+                key.extend(bits(color.to_array()));
+            }
+            if let Some(normal) = mesh.normals().get(i) {
+                key.extend(bits(normal.to_array()));
+            }
+            if let Some(tangent) = mesh.tangents().get(i) {
+                key.extend(bits(tangent.to_array()));
+            }
+            for tc in &texcoords {
+                key.extend(bits(tc[i].to_array()));
+            }
+            if let Some(joints) = mesh.joint_indices().get(i) {
+                key.extend(joints.to_array());
+            }
+            if let Some(weights) = mesh.joint_weights().get(i) {
+                key.extend(bits(weights.to_array()));
+            }
+            *first.entry(key).or_insert(i as u32)
+        })
+        .collect()
+}
+
+/// A symmetric 4x4 matrix measuring the squared distance to a set of planes.
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn plane(normal: DVec3, point: DVec3, weight: f64) -> Self {
+        let [a, b, c] = normal.to_array();
+        let d = -normal.dot(point);
+        Self(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|v| v * weight),
+        )
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a += b;
+        }
+    }
+
+    fn error(&self, p: DVec3) -> f64 {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let (x, y, z) = (p.x, p.y, p.z);
+        let error = aa * x * x
+            + 2. * ab * x * y
+            + 2. * ac * x * z
+            + 2. * ad * x
+            + bb * y * y
+            + 2. * bc * y * z
+            + 2. * bd * y
+            + cc * z * z
+            + 2. * cd * z
+            + dd;
+        error.max(0.)
+    }
+}
+
+#[derive(PartialEq)]
+struct Candidate {
+    cost: f64,
+    vertex: u32,
+    target: u32,
+    version: u32,
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Candidate {
+    // Reversed, so that the cheapest collapse is at the top of the heap
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+struct Simplifier {
+    positions: Vec<DVec3>,
+    triangles: Vec<[u32; 3]>,
+    alive: Vec<bool>,
+    alive_count: usize,
+    /// Triangles referencing each vertex; may contain triangles which have since been removed.
+    vertex_triangles: Vec<Vec<u32>>,
+    /// Vertices are grouped by position, so that seams are treated as a single surface.
+    group: Vec<u32>,
+    group_vertices: Vec<Vec<u32>>,
+    quadrics: Vec<Quadric>,
+    removed: Vec<bool>,
+    version: Vec<u32>,
+}
+
+impl Simplifier {
+    fn new(positions: &[Vec3], indices: &[u32]) -> Self {
+        let mut group = Vec::with_capacity(positions.len());
+        let mut group_vertices: Vec<Vec<u32>> = Vec::new();
+        let mut by_position = HashMap::new();
+        let mut used = vec![false; positions.len()];
+        for &i in indices {
+            used[i as usize] = true;
+        }
+        for (i, p) in positions.iter().enumerate() {
+            let id = *by_position
+                .entry(p.to_array().map(f32::to_bits))
+                .or_insert_with(|| {
+                    group_vertices.push(Vec::new());
+                    group_vertices.len() as u32 - 1
+                });
+            group.push(id);
+            // Vertices which were welded into others don't hold their position in place
+            if used[i] {
+                group_vertices[id as usize].push(i as u32);
+            }
+        }
+
+        let triangles = indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .filter(|t| {
+                group[t[0] as usize] != group[t[1] as usize]
+                    && group[t[1] as usize] != group[t[2] as usize]
+                    && group[t[2] as usize] != group[t[0] as usize]
+            })
+            .collect::<Vec<_>>();
+        let mut vertex_triangles = vec![Vec::new(); positions.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            for &v in t {
+                vertex_triangles[v as usize].push(i as u32);
+            }
+        }
+
+        let mut simplifier = Self {
+            positions: positions.iter().map(|p| p.as_dvec3()).collect(),
+            alive: vec![true; triangles.len()],
+            alive_count: triangles.len(),
+            triangles,
+            vertex_triangles,
+            quadrics: vec![Quadric::default(); group_vertices.len()],
+            group,
+            group_vertices,
+            removed: vec![false; positions.len()],
+            version: vec![0; positions.len()],
+        };
+        simplifier.init_quadrics();
+        simplifier
+    }
+
+    fn init_quadrics(&mut self) {
+        let mut edge_count = HashMap::<(u32, u32), u32>::new();
+        for t in &self.triangles {
+            for i in 0..3 {
+                let (a, b) = (
+                    self.group[t[i] as usize],
+                    self.group[t[(i + 1) % 3] as usize],
+                );
+                *edge_count.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+
+        for t in &self.triangles {
+            let [p0, p1, p2] = t.map(|v| self.positions[v as usize]);
+            let cross = (p1 - p0).cross(p2 - p0);
+            let area = cross.length() / 2.;
+            if area <= 0. {
+                continue;
+            }
+            let normal = cross.normalize();
+            let plane = Quadric::plane(normal, p0, area);
+            for &v in t {
+                self.quadrics[self.group[v as usize] as usize].add(&plane);
+            }
+
+            // Open borders get a plane perpendicular to the surface, so that they keep their shape
+            for i in 0..3 {
+                let (a, b) = (t[i], t[(i + 1) % 3]);
+                let (ga, gb) = (self.group[a as usize], self.group[b as usize]);
+                if edge_count[&(ga.min(gb), ga.max(gb))] != 1 {
+                    continue;
+                }
+                let (pa, pb) = (self.positions[a as usize], self.positions[b as usize]);
+                let edge = pb - pa;
+                let border_normal = edge.cross(normal).normalize_or_zero();
+                let border =
+                    Quadric::plane(border_normal, pa, edge.length_squared() * BORDER_WEIGHT);
+                self.quadrics[ga as usize].add(&border);
+                self.quadrics[gb as usize].add(&border);
+            }
+        }
+    }
+
+    fn live_triangles(&self, vertex: u32) -> impl Iterator<Item = u32> + '_ {
+        self.vertex_triangles[vertex as usize]
+            .iter()
+            .copied()
+            .filter(|&t| self.alive[t as usize])
+    }
+
+    fn contains_group(&self, triangle: u32, group: u32) -> bool {
+        self.triangles[triangle as usize]
+            .iter()
+            .any(|&v| self.group[v as usize] == group)
+    }
+
+    /// The positions around `vertex`, as `(vertex, group, number of triangles shared with it)`.
+    fn neighbours(&self, vertex: u32) -> Vec<(u32, u32, u32)> {
+        let mut neighbours = Vec::<(u32, u32, u32)>::new();
+        for t in self.live_triangles(vertex) {
+            for &n in &self.triangles[t as usize] {
+                if n == vertex {
+                    continue;
+                }
+                let group = self.group[n as usize];
+                match neighbours.iter_mut().find(|(_, g, _)| *g == group) {
+                    Some((_, _, count)) => *count += 1,
+                    None => neighbours.push((n, group, 1)),
+                }
+            }
+        }
+        neighbours
+    }
+
+    /// The collapse of `vertex` into one of its neighbours with the lowest quadric error.
+    fn best_candidate(&self, vertex: u32) -> Option<Candidate> {
+        if self.removed[vertex as usize]
+            || self.group_vertices[self.group[vertex as usize] as usize].len() != 1
+        {
+            return None;
+        }
+
+        let neighbours = self.neighbours(vertex);
+        let on_border = neighbours.iter().any(|(_, _, count)| *count != 2);
+        let quadric = self.quadrics[self.group[vertex as usize] as usize];
+        let mut candidates = neighbours
+            .iter()
+            // Vertices on a border may only slide along it
+            .filter(|(_, _, shared)| !on_border || *shared == 1)
+            .map(|&(target, target_group, _)| {
+                let mut quadric = quadric;
+                quadric.add(&self.quadrics[target_group as usize]);
+                Candidate {
+                    cost: quadric.error(self.positions[target as usize]),
+                    vertex,
+                    target,
+                    version: self.version[vertex as usize],
+                }
+            })
+            .collect::<Vec<_>>();
+        // `Candidate` sorts in reverse, so the cheapest comes last
+        candidates.sort();
+        candidates
+            .into_iter()
+            .rev()
+            .find(|c| self.can_collapse(vertex, c.target))
+    }
+
+    /// Whether collapsing `vertex` into `target` keeps the mesh manifold and its triangles facing the same way.
+    fn can_collapse(&self, vertex: u32, target: u32) -> bool {
+        let target_group = self.group[target as usize];
+        let neighbours = self.neighbours(vertex);
+        let Some(&(_, _, shared)) = neighbours.iter().find(|(_, g, _)| *g == target_group) else {
+            return false;
+        };
+
+        // Link condition: the only positions adjacent to both ends must be those of the collapsing triangles
+        let mut target_neighbours = Vec::new();
+        for &tv in &self.group_vertices[target_group as usize] {
+            for t in self.live_triangles(tv) {
+                for &n in &self.triangles[t as usize] {
+                    let group = self.group[n as usize];
+                    if !target_neighbours.contains(&group) {
+                        target_neighbours.push(group);
+                    }
+                }
+            }
+        }
+        let common = neighbours
+            .iter()
+            .filter(|(_, group, _)| *group != target_group && target_neighbours.contains(group))
+            .count() as u32;
+        if common > shared {
+            return false;
+        }
+
+        let target_pos = self.positions[target as usize];
+        let mut remaining = 0;
+        for t in self.live_triangles(vertex) {
+            if self.contains_group(t, target_group) {
+                continue;
+            }
+            remaining += 1;
+            let tri = self.triangles[t as usize].map(|v| self.positions[v as usize]);
+            let moved = self.triangles[t as usize].map(|v| {
+                if v == vertex {
+                    target_pos
+                } else {
+                    self.positions[v as usize]
+                }
+            });
+            let before = (tri[1] - tri[0]).cross(tri[2] - tri[0]).normalize_or_zero();
+            let after = (moved[1] - moved[0])
+                .cross(moved[2] - moved[0])
+                .normalize_or_zero();
+            if after == DVec3::ZERO || before.dot(after) <= 0. {
+                return false;
+            }
+        }
+        remaining > 0 || self.alive_count > shared as usize
+    }
+
+    fn collapse(&mut self, vertex: u32, target: u32) {
+        let target_group = self.group[target as usize];
+        let triangles = std::mem::take(&mut self.vertex_triangles[vertex as usize]);
+        for t in triangles {
+            if !self.alive[t as usize] {
+                continue;
+            }
+            if self.contains_group(t, target_group) {
+                self.alive[t as usize] = false;
+                self.alive_count -= 1;
+                continue;
+            }
+            for v in &mut self.triangles[t as usize] {
+                if *v == vertex {
+                    *v = target;
+                }
+            }
+            self.vertex_triangles[target as usize].push(t);
+        }
+        let alive = &self.alive;
+        for &tv in &self.group_vertices[target_group as usize] {
+            self.vertex_triangles[tv as usize].retain(|&t| alive[t as usize]);
+        }
+        self.removed[vertex as usize] = true;
+        let quadric = self.quadrics[self.group[vertex as usize] as usize];
+        self.quadrics[target_group as usize].add(&quadric);
+    }
+
+    /// Vertices whose collapses may have changed after collapsing into `target`.
+    fn affected(&self, target: u32) -> Vec<u32> {
+        let mut affected = Vec::new();
+        for &tv in &self.group_vertices[self.group[target as usize] as usize] {
+            for t in self.live_triangles(tv) {
+                for &v in &self.triangles[t as usize] {
+                    if !affected.contains(&v) {
+                        affected.push(v);
+                    }
+                }
+            }
+        }
+        affected
+    }
+
+    fn run(&mut self, target_count: usize) {
+        let mut heap = (0..self.positions.len() as u32)
+            .filter_map(|v| self.best_candidate(v))
+            .collect::<BinaryHeap<_>>();
+
+        while self.alive_count > target_count {
+            let Some(candidate) = heap.pop() else { break };
+            if self.removed[candidate.vertex as usize]
+                || candidate.version != self.version[candidate.vertex as usize]
+            {
+                continue;
+            }
+            // Collapses elsewhere may have changed the neighbourhood of the target
+            if !self.can_collapse(candidate.vertex, candidate.target) {
+                self.version[candidate.vertex as usize] += 1;
+                heap.extend(self.best_candidate(candidate.vertex));
+                continue;
+            }
+            self.collapse(candidate.vertex, candidate.target);
+            for v in self.affected(candidate.target) {
+                self.version[v as usize] += 1;
+                heap.extend(self.best_candidate(v));
+            }
+        }
+    }
+}
+
+/// Returns the indices of `indices` simplified to roughly `target_ratio` of its triangles.
+fn simplify_indices(positions: &[Vec3], indices: &[u32], target_ratio: f32) -> Vec<u32> {
+    let mut simplifier = Simplifier::new(positions, indices);
+    let target_count = ((indices.len() / 3) as f32 * target_ratio.clamp(0., 1.))
+        .ceil()
+        .max(1.) as usize;
+    simplifier.run(target_count);
+    simplifier
+        .triangles
+        .iter()
+        .zip(&simplifier.alive)
+        .filter(|(_, alive)| **alive)
+        .flat_map(|(t, _)| *t)
+        .collect()
+}
+
+/// Builds a mesh from the vertices of `mesh` which are referenced by `indices`.
+fn compact(mesh: &Mesh, indices: &[u32]) -> anyhow::Result<Mesh> {
+    let mut remap = vec![u32::MAX; mesh.vertex_count()];
+    let mut used = Vec::new();
+    let indices = indices
+        .iter()
+        .map(|&i| {
+            if remap[i as usize] == u32::MAX {
+                remap[i as usize] = used.len() as u32;
+                used.push(i as usize);
+            }
+            remap[i as usize]
+        })
+        .collect();
+
+    fn pick<T: Copy>(values: &[T], used: &[usize]) -> Vec<T> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+        used.iter().map(|&i| values[i]).collect()
+    }
+
+    MeshBuilder {
+        positions: pick(mesh.positions(), &used),
+        colors: pick(mesh.colors(), &used),
+        normals: pick(mesh.normals(), &used),
+        tangents: pick(mesh.tangents(), &used),
+        texcoords: (0..)
+            .map(|set| mesh.texcoords(set))
+            .take_while(|tc| !tc.is_empty())
+            .map(|tc| pick(tc, &used))
+            .collect(),
+        joint_indices: pick(mesh.joint_indices(), &used),
+        joint_weights: pick(mesh.joint_weights(), &used),
+        indices,
+    }
+    .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{UVec4, Vec2, Vec4};
+
+    use super::*;
+
+    /// A flat `n` by `n` grid of quads in the XY plane.
+    fn grid(n: u32) -> MeshBuilder {
+        let mut builder = MeshBuilder::new();
+        for y in 0..=n {
+            for x in 0..=n {
+                builder.positions.push(Vec3::new(x as f32, y as f32, 0.));
+            }
+        }
+        for y in 0..n {
+            for x in 0..n {
+                let i = y * (n + 1) + x;
+                builder
+                    .indices
+                    .extend([i, i + 1, i + n + 1, i + 1, i + n + 2, i + n + 1]);
+            }
+        }
+        let count = builder.positions.len();
+        builder.texcoords = vec![builder
+            .positions
+            .iter()
+            .map(|p| p.truncate() / n as f32)
+            .collect()];
+        builder.joint_indices = vec![UVec4::new(0, 1, 0, 0); count];
+        builder.joint_weights = builder
+            .positions
+            .iter()
+            .map(|p| Vec4::new(1. - p.x / n as f32, p.x / n as f32, 0., 0.))
+            .collect();
+        builder
+    }
+
+    #[test]
+    fn flat_grid_keeps_its_outline() {
+        let mesh = grid(8).build().unwrap();
+        let simplified = mesh.simplified(0.25).unwrap();
+        assert!(simplified.index_count() <= mesh.index_count() / 4);
+        assert_eq!(simplified.aabb(), mesh.aabb());
+
+        let area: f32 = simplified
+            .indices()
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [0, 1, 2].map(|i| simplified.positions()[t[i] as usize]);
+                (b - a).cross(c - a).z / 2.
+            })
+            .sum();
+        assert!((area - 64.).abs() < 1e-3, "area {area}");
+    }
+
+    #[test]
+    fn attributes_are_kept_per_vertex() {
+        let mesh = grid(6).build().unwrap();
+        let simplified = mesh.simplified(0.5).unwrap();
+        assert!(simplified.vertex_count() < mesh.vertex_count());
+        for (i, p) in simplified.positions().iter().enumerate() {
+            let source = mesh.positions().iter().position(|s| s == p).unwrap();
+            assert_eq!(simplified.texcoords(0)[i], mesh.texcoords(0)[source]);
+            assert_eq!(simplified.joint_weights()[i], mesh.joint_weights()[source]);
+        }
+    }
+
+    #[test]
+    fn seam_vertices_are_not_removed() {
+        // Split the grid in two along x = 2, with separate texture coordinates on each side
+        let mut builder = grid(4);
+        let seam = builder
+            .positions
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.x == 2.)
+            .map(|(i, _)| i as u32)
+            .collect::<Vec<_>>();
+        for &i in &seam {
+            let duplicate = builder.positions.len() as u32;
+            builder.positions.push(builder.positions[i as usize]);
+            builder.texcoords[0].push(Vec2::splat(-1.));
+            builder.joint_indices.push(UVec4::ZERO);
+            builder.joint_weights.push(Vec4::X);
+            for t in builder.indices.chunks_exact_mut(3) {
+                let on_right = t.iter().any(|&v| builder.positions[v as usize].x > 2.);
+                if on_right {
+                    for v in t.iter_mut().filter(|v| **v == i) {
+                        *v = duplicate;
+                    }
+                }
+            }
+        }
+        let mesh = builder.build().unwrap();
+        let simplified = mesh.simplified(0.1).unwrap();
+        let seam_count = simplified.positions().iter().filter(|p| p.x == 2.).count();
+        assert_eq!(seam_count, seam.len() * 2);
+    }
+
+    #[test]
+    fn duplicate_vertices_are_welded() {
+        // Give every triangle its own vertices, with the same attributes as the shared ones
+        let grid = grid(6);
+        let mut builder = MeshBuilder::new();
+        for &i in &grid.indices {
+            let i = i as usize;
+            builder.indices.push(builder.positions.len() as u32);
+            builder.positions.push(grid.positions[i]);
+            builder.joint_indices.push(grid.joint_indices[i]);
+            builder.joint_weights.push(grid.joint_weights[i]);
+        }
+        builder.texcoords = vec![builder
+            .positions
+            .iter()
+            .map(|p| p.truncate() / 6.)
+            .collect()];
+        let mesh = builder.build().unwrap();
+
+        let simplified = mesh.simplified(0.25).unwrap();
+        assert!(simplified.index_count() <= mesh.index_count() / 4);
+        assert_eq!(simplified.aabb(), mesh.aabb());
+        assert!(simplified.vertex_count() < 49);
+    }
+}
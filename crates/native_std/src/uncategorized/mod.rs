@@ -9,6 +9,7 @@ pub mod encode;
 pub mod fps_counter;
 
 pub mod mesh;
+pub mod mesh_simplify;
 pub mod ordered_glam;
 pub mod shapes;
 pub mod size_histogram;
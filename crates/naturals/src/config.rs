@@ -99,6 +99,13 @@ pub struct NaturalElement {
     pub steepness: NaturalCurve,
     #[serde(default)]
     pub cluster_noise: NaturalCurve,
+    /// Elements with a collider are scattered on the server instead of the client, as networked entities that can
+    /// be collided with and queried. See [crate::scatter].
+    #[serde(default)]
+    pub collider: Option<NaturalCollider>,
+    /// Added as the `tags` of the entities scattered on the server, for queries like [crate::nearest_natural].
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 fn cluster_noise_scale_default() -> f32 {
     1.
@@ -127,10 +134,19 @@ impl Default for NaturalElement {
             water_depth: Default::default(),
             steepness: Default::default(),
             cluster_noise: Default::default(),
+            collider: None,
+            tags: Vec::new(),
         }
     }
 }
 
+/// The collider of a [NaturalElement], before the scale of the natural is applied.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NaturalCollider {
+    Sphere { radius: f32 },
+    Cube { size: Vec3 },
+}
+
 pub type BoxModelKey = Box<dyn AsyncAssetKeyExt<Result<Arc<Model>, AssetError>>>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, ElementEditor)]
@@ -172,6 +188,31 @@ pub enum NaturalCurve {
         y1: f32,
     },
 }
+impl NaturalCurve {
+    /// Evaluates the curve at `x`, like `sample_curve` in `naturals.wgsl`.
+    pub fn sample(&self, x: f32) -> f32 {
+        match *self {
+            NaturalCurve::Constant { value } => value,
+            NaturalCurve::Interpolate { x0, x1, y0, y1 } => y0 + (x - x0) / (x1 - x0) * (y1 - y0),
+            NaturalCurve::InterpolateClamped { x0, x1, y0, y1 } => {
+                y0 + ((x - x0) / (x1 - x0)).clamp(0., 1.) * (y1 - y0)
+            }
+            NaturalCurve::SmoothStep { x0, x1, y0, y1 } => {
+                let t = ((x - x0) / (x1 - x0)).clamp(0., 1.);
+                y0 + t * t * (3. - 2. * t) * (y1 - y0)
+            }
+            NaturalCurve::BellCurve {
+                center,
+                width,
+                y0,
+                y1,
+            } => {
+                let k = x - center;
+                y0 + (-k * k / (2. * width * width)).exp() * (y1 - y0)
+            }
+        }
+    }
+}
 impl Default for NaturalCurve {
     fn default() -> Self {
         Self::Constant { value: 1. }
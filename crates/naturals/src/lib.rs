@@ -9,7 +9,7 @@ use ambient_core::{
     runtime,
     transform::{local_to_world, translation},
 };
-use ambient_ecs::{components, query, Entity, EntityId, FnSystem, SystemGroup};
+use ambient_ecs::{components, query, Entity, EntityId, FnSystem, Networked, Store, SystemGroup};
 use ambient_gpu::gpu::GpuKey;
 use ambient_model::{Model, ModelFromUrl, ModelSpawnOpts, ModelSpawnRoot};
 use ambient_native_std::{
@@ -24,21 +24,29 @@ use rand::prelude::SliceRandom;
 
 mod compute;
 mod config;
+pub mod scatter;
 use ambient_network::ServerWorldExt;
 pub use compute::*;
 pub use config::*;
+pub use scatter::{nearest_natural, remove_natural, server_systems};
 use tokio::sync::Semaphore;
 
 components!("game_objects", {
     natural_entities: HashMap<String, Vec<EntityId>>,
     // Keep a ref to the model on each natural, so that the model doesn't get unloaded while there are any naturals of that type
     natural_model: Arc<Model>,
+    @[Networked, Store]
     natural_layers: Vec<NaturalLayer>,
     natural_layers_in_progress: usize,
     terrain_cell_nature_version: i32,
     terrain_cell_nature_conf_hash: u64,
 });
 
+pub fn init_all_components() {
+    init_components();
+    scatter::init_components();
+}
+
 pub fn init_world_resources() -> Entity {
     Entity::new()
 }
@@ -325,10 +333,11 @@ pub fn client_systems() -> SystemGroup {
                     .into_iter()
                     .filter_map(|layer| {
                         let res = NaturalLayer {
+                            // Elements with colliders are scattered by the server
                             elements: layer
                                 .elements
                                 .into_iter()
-                                .filter(|e| e.enabled)
+                                .filter(|e| e.enabled && e.collider.is_none())
                                 .collect_vec(),
                             ..layer
                         };
@@ -0,0 +1,519 @@
+//! Deterministic scattering of naturals on the CPU, for the [NaturalElement](crate::NaturalElement)s that have a
+//! [collider](crate::NaturalElement::collider).
+//!
+//! The client scatters the small elements (grass, pebbles) on the GPU for rendering only. The large elements are
+//! instead scattered by the server from the same [NaturalLayer]s and the `map_seed`, and spawned as networked
+//! entities with a model and a collider, so that they can be collided with, queried with [nearest_natural] and
+//! removed with [remove_natural]. Removals are kept in the [removed_naturals] persisted resource, so that removed
+//! naturals stay removed when their terrain cell is scattered again.
+
+use std::collections::HashSet;
+
+use ambient_core::{
+    map_seed, tags,
+    transform::{local_to_world, rotation, scale, translation},
+};
+use ambient_ecs::{
+    components,
+    generated::physics::components::{cube_collider, sphere_collider},
+    query, Entity, EntityId, FnSystem, Networked, Store, SystemGroup, World,
+};
+use ambient_model::model_from_url;
+use ambient_network::ServerWorldExt;
+use ambient_renderer::color;
+use ambient_terrain::{
+    get_terrain_cell, terrain_cell_version, terrain_state_cpu, terrain_world_cell, TerrainLayers,
+    TerrainSize, TerrainStateCpu, TERRAIN_BASE,
+};
+use glam::{ivec2, vec2, vec3, EulerRot, IVec2, Quat, Vec2, Vec3, Vec3Swizzles};
+use itertools::Itertools;
+
+use crate::{
+    calculate_hash, get_default_natural_layers, natural_layers, terrain_cell_nature_conf_hash,
+    terrain_cell_nature_version, NaturalCollider, NaturalLayer, NaturalsPreset,
+};
+
+components!("game_objects", {
+    /// The stable id of a natural scattered by the server. The same natural gets the same id every time its terrain
+    /// cell is scattered.
+    @[Networked]
+    natural_id: u64,
+    /// The naturals the server has scattered on this terrain cell.
+    terrain_cell_naturals: Vec<EntityId>,
+    /// The ids of the naturals that have been removed with [remove_natural].
+    @[Networked, Store]
+    removed_naturals: HashSet<u64>,
+});
+
+/// A natural placed by [scatter_naturals_cpu].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScatteredNatural {
+    pub id: u64,
+    /// The index of the layer in the scattered layers.
+    pub layer: usize,
+    /// The index of the element in the [NaturalLayer::elements] of the layer.
+    pub element: usize,
+    /// The model url, picked among the models of the element.
+    pub model: Option<String>,
+    /// The position relative to the terrain cell.
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: f32,
+}
+
+/// Scatters the enabled elements with a collider of `layers` on a terrain cell. The result only depends on the
+/// arguments, so the server and any other caller get the same naturals for the same cell.
+///
+/// Unlike the GPU scatter, which uses blue noise and cluster noise textures, the random numbers are hashed from
+/// `seed`, `cell` and the grid position, and the normals aren't mipmapped.
+pub fn scatter_naturals_cpu(
+    seed: u64,
+    cell: IVec2,
+    state: &TerrainStateCpu,
+    layers: &[NaturalLayer],
+) -> Vec<ScatteredNatural> {
+    let size_in_meters = state.size.size_in_meters();
+    let mut naturals = Vec::new();
+    for (layer_index, layer) in layers.iter().enumerate() {
+        if layer.grid_size <= 0.05 || layer.grid_size >= 1000. {
+            continue;
+        }
+        let elements = layer
+            .elements
+            .iter()
+            .enumerate()
+            .filter(|(_, el)| el.enabled && el.collider.is_some())
+            .collect_vec();
+        if elements.is_empty() {
+            continue;
+        }
+        let grid_cells = (size_in_meters / layer.grid_size).ceil() as i32;
+        let mut probabilities = vec![0.; elements.len()];
+        for gy in 0..grid_cells {
+            for gx in 0..grid_cells {
+                let id = hash(&[
+                    seed,
+                    cell.x as u64,
+                    cell.y as u64,
+                    layer_index as u64,
+                    gx as u64,
+                    gy as u64,
+                ]);
+                let random = |channel: u64| unit(hash(&[id, channel]));
+
+                let position =
+                    (vec2(gx as f32, gy as f32) + vec2(random(0), random(1))) * layer.grid_size;
+                // Only scatter inside the cell, so that neighbouring cells don't overlap
+                if position.x >= size_in_meters || position.y >= size_in_meters {
+                    continue;
+                }
+                let Some(sample) = sample_terrain(state, position) else {
+                    continue;
+                };
+                let steepness = sample.normal.z.clamp(-1., 1.).acos().to_degrees();
+                let height_over_ocean = sample.elevation + TERRAIN_BASE;
+                let world_position = cell.as_vec2() * size_in_meters + position;
+
+                for (i, (element_index, element)) in elements.iter().enumerate() {
+                    let cluster_noise = value_noise(
+                        hash(&[seed, layer_index as u64, *element_index as u64]),
+                        world_position / (size_in_meters * element.cluster_noise_scale) * 8.,
+                    );
+                    probabilities[i] = element.soil_depth.sample(sample.soil).clamp(0., 1.)
+                        * element.elevation.sample(height_over_ocean).clamp(0., 1.)
+                        * element
+                            .water_depth
+                            .sample((-height_over_ocean).max(0.))
+                            .clamp(0., 1.)
+                        * element.steepness.sample(steepness).clamp(0., 1.)
+                        * element.cluster_noise.sample(cluster_noise).clamp(0., 1.);
+                }
+                let i = cdf_sample(&probabilities, random(2));
+                if probabilities[i] <= random(3) {
+                    continue;
+                }
+                let (element_index, element) = elements[i];
+                let normal = sample.normal;
+
+                let rotation_z = normal.y.atan2(normal.x)
+                    + (2. * random(4) - 1.) * std::f32::consts::PI * element.rotation_z_jitter
+                    + element.rotation_z;
+                let rotation_x = -normal.y.clamp(-1., 1.).asin()
+                    * (1. - element.rotation_straightness)
+                    + (2. * random(5) - 1.) * std::f32::consts::PI * element.rotation_xy_jitter
+                    + element.rotation_x;
+                let rotation_y = normal.x.clamp(-1., 1.).asin()
+                    * (1. - element.rotation_straightness)
+                    + (2. * random(6) - 1.) * std::f32::consts::PI * element.rotation_xy_jitter
+                    + element.rotation_y;
+                let scale = element.scale_min
+                    + random(7).powf(element.scale_power) * (element.scale_max - element.scale_min);
+
+                let models = element
+                    .models
+                    .iter()
+                    .flat_map(|models| models.0.iter())
+                    .filter_map(|url| Some(url.join("../models/main.json").ok()?.to_string()))
+                    .collect_vec();
+                let model = if models.is_empty() {
+                    None
+                } else {
+                    let index = (random(8) * models.len() as f32) as usize;
+                    Some(models[index.min(models.len() - 1)].clone())
+                };
+
+                naturals.push(ScatteredNatural {
+                    id,
+                    layer: layer_index,
+                    element: element_index,
+                    model,
+                    position: position.extend(sample.elevation)
+                        + normal * element.position_normal_offset
+                        + vec3(0., 0., element.position_z_offset),
+                    rotation: Quat::from_euler(EulerRot::XYZ, rotation_x, rotation_y, rotation_z),
+                    scale,
+                });
+            }
+        }
+    }
+    naturals
+}
+
+/// The naturals scattered by the server within `radius` of `position`, from the nearest to the furthest.
+pub fn naturals_within(world: &World, position: Vec2, radius: f32) -> Vec<(EntityId, f32)> {
+    let size_in_meters = TerrainSize::new().size_in_meters();
+    let min = ((position - radius) / size_in_meters).floor().as_ivec2();
+    let max = ((position + radius) / size_in_meters).floor().as_ivec2();
+    (min.y..=max.y)
+        .flat_map(|y| (min.x..=max.x).map(move |x| ivec2(x, y)))
+        .filter_map(|cell| get_terrain_cell(world, cell))
+        .filter_map(|cell| world.get_ref(cell, terrain_cell_naturals()).ok())
+        .flatten()
+        .filter_map(|&id| {
+            let distance = world.get(id, translation()).ok()?.xy().distance(position);
+            (distance <= radius).then_some((id, distance))
+        })
+        .sorted_by(|a, b| a.1.total_cmp(&b.1))
+        .collect_vec()
+}
+
+/// The nearest natural scattered by the server within `radius` of `position`, optionally only among those with the
+/// `tag` in their [NaturalElement::tags](crate::NaturalElement::tags). For instance, the nearest tree that can be
+/// cut down.
+pub fn nearest_natural(
+    world: &World,
+    position: Vec2,
+    radius: f32,
+    tag: Option<&str>,
+) -> Option<EntityId> {
+    naturals_within(world, position, radius)
+        .into_iter()
+        .map(|(id, _)| id)
+        .find(|&id| match tag {
+            Some(tag) => world
+                .get_ref(id, tags())
+                .map(|tags| tags.iter().any(|t| t == tag))
+                .unwrap_or(false),
+            None => true,
+        })
+}
+
+/// Removes a natural scattered by the server, for instance a tree that has been cut down. The natural is despawned,
+/// which removes it from the clients, and it's added to [removed_naturals] so that it isn't scattered again.
+pub fn remove_natural(world: &mut World, id: EntityId) {
+    let Ok(natural) = world.get(id, natural_id()) else {
+        return;
+    };
+    if let Some(removed) = world.persisted_resource_mut(removed_naturals()) {
+        removed.insert(natural);
+    } else if let Some(resources) = world.persisted_resource_entity() {
+        world
+            .add_component(resources, removed_naturals(), HashSet::from([natural]))
+            .unwrap();
+    }
+    world.despawn(id);
+}
+
+pub fn server_systems() -> SystemGroup {
+    SystemGroup::new(
+        "dims/naturals/server_systems",
+        vec![
+            query(())
+                .incl(terrain_state_cpu())
+                .excl(terrain_cell_naturals())
+                .to_system(|q, world, qs, _| {
+                    for (id, _) in q.collect_cloned(world, qs) {
+                        world
+                            .add_components(
+                                id,
+                                Entity::new()
+                                    .with(terrain_cell_nature_conf_hash(), 0u64)
+                                    .with(terrain_cell_nature_version(), -1)
+                                    .with(terrain_cell_naturals(), Default::default()),
+                            )
+                            .unwrap();
+                    }
+                }),
+            query((terrain_cell_naturals(),))
+                .despawned()
+                .to_system(|q, world, qs, _| {
+                    for (_, (naturals,)) in q.collect_cloned(world, qs) {
+                        for id in naturals {
+                            world.despawn(id);
+                        }
+                    }
+                }),
+            Box::new(FnSystem::new(|world, _| {
+                let layers = world
+                    .persisted_resource(natural_layers())
+                    .cloned()
+                    .unwrap_or_else(|| get_default_natural_layers(NaturalsPreset::Mountains));
+                let seed = world
+                    .persisted_resource(map_seed())
+                    .copied()
+                    .unwrap_or_default();
+                let removed = world
+                    .persisted_resource(removed_naturals())
+                    .cloned()
+                    .unwrap_or_default();
+                // Removals despawn the natural right away, so they don't require the cells to be scattered again
+                let conf_hash = calculate_hash(&format!("{seed} {layers:?}"));
+
+                let updatable = query((
+                    terrain_cell_nature_conf_hash(),
+                    terrain_cell_version(),
+                    terrain_cell_nature_version(),
+                ))
+                .incl(terrain_cell_naturals())
+                .iter(world, None)
+                .filter(|(_, (hash, version, nature_version))| {
+                    **version != **nature_version || **hash != conf_hash
+                })
+                .map(|(id, _)| id)
+                .collect_vec();
+
+                for cell_id in updatable {
+                    let version = world.get(cell_id, terrain_cell_version()).unwrap();
+                    world
+                        .set(cell_id, terrain_cell_nature_conf_hash(), conf_hash)
+                        .unwrap();
+                    world
+                        .set(cell_id, terrain_cell_nature_version(), version)
+                        .unwrap();
+
+                    let old =
+                        std::mem::take(world.get_mut(cell_id, terrain_cell_naturals()).unwrap());
+                    for id in old {
+                        world.despawn(id);
+                    }
+
+                    let (Ok(state), Ok(cell), Ok(cell_position)) = (
+                        world.get_ref(cell_id, terrain_state_cpu()).cloned(),
+                        world.get(cell_id, terrain_world_cell()),
+                        world.get(cell_id, translation()),
+                    ) else {
+                        continue;
+                    };
+                    let naturals = scatter_naturals_cpu(seed, cell, &state, &layers)
+                        .into_iter()
+                        .filter(|natural| !removed.contains(&natural.id))
+                        .map(|natural| {
+                            let element = &layers[natural.layer].elements[natural.element];
+                            let mut entity = Entity::new()
+                                .with(natural_id(), natural.id)
+                                .with(translation(), cell_position + natural.position)
+                                .with(rotation(), natural.rotation)
+                                .with(scale(), Vec3::ONE * natural.scale)
+                                .with(local_to_world(), Default::default())
+                                .with(color(), element.color.clone().into())
+                                .with(tags(), element.tags.clone());
+                            if let Some(model) = natural.model {
+                                entity.set(model_from_url(), model);
+                            }
+                            match &element.collider {
+                                Some(NaturalCollider::Sphere { radius }) => {
+                                    entity.set(sphere_collider(), *radius)
+                                }
+                                Some(NaturalCollider::Cube { size }) => {
+                                    entity.set(cube_collider(), *size)
+                                }
+                                None => {}
+                            }
+                            entity.spawn(world)
+                        })
+                        .collect_vec();
+                    world
+                        .set(cell_id, terrain_cell_naturals(), naturals)
+                        .unwrap();
+                }
+            })),
+        ],
+    )
+}
+
+struct TerrainSample {
+    soil: f32,
+    /// Rock and soil, above the terrain base.
+    elevation: f32,
+    normal: Vec3,
+}
+
+/// Bilinearly samples the heightmap, and takes the nearest normal, at `position` meters from the cell origin.
+fn sample_terrain(state: &TerrainStateCpu, position: Vec2) -> Option<TerrainSample> {
+    let (_, height, width) = state.heightmap.dim();
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let max = vec2(width as f32 - 1., height as f32 - 1.);
+    let p = (position / state.size.texel_size_in_meters()).clamp(Vec2::ZERO, max);
+    let (x0, y0) = (p.x.floor() as usize, p.y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let t = p - p.floor();
+    let layer = |layer: TerrainLayers| {
+        let layer = layer as usize;
+        let top =
+            state.heightmap[(layer, y0, x0)] * (1. - t.x) + state.heightmap[(layer, y0, x1)] * t.x;
+        let bottom =
+            state.heightmap[(layer, y1, x0)] * (1. - t.x) + state.heightmap[(layer, y1, x1)] * t.x;
+        top * (1. - t.y) + bottom * t.y
+    };
+    let rock = layer(TerrainLayers::Rock);
+    let soil = layer(TerrainLayers::Soil);
+    let (nx, ny) = (p.x.round() as usize, p.y.round() as usize);
+    let normal = vec3(
+        state.normalmap[(ny, nx, 0)],
+        state.normalmap[(ny, nx, 1)],
+        state.normalmap[(ny, nx, 2)],
+    );
+    Some(TerrainSample {
+        soil,
+        elevation: rock + soil,
+        normal: normal.try_normalize().unwrap_or(Vec3::Z),
+    })
+}
+
+/// Picks an index with a probability proportional to its weight, like `cdf_sample` in `naturals.wgsl`.
+fn cdf_sample(probabilities: &[f32], r: f32) -> usize {
+    let total = probabilities.iter().sum::<f32>();
+    let mut culm = 0.;
+    for (i, probability) in probabilities.iter().enumerate() {
+        culm += probability / total;
+        if r <= culm {
+            return i;
+        }
+    }
+    0
+}
+
+/// Smooth noise in the range 0..1 with one random value per integer coordinate.
+fn value_noise(seed: u64, p: Vec2) -> f32 {
+    let cell = p.floor();
+    let t = p - cell;
+    let t = t * t * (3. - 2. * t);
+    let corner = |x: f32, y: f32| {
+        unit(hash(&[
+            seed,
+            (cell.x + x) as i64 as u64,
+            (cell.y + y) as i64 as u64,
+        ]))
+    };
+    let top = corner(0., 0.) * (1. - t.x) + corner(1., 0.) * t.x;
+    let bottom = corner(0., 1.) * (1. - t.x) + corner(1., 1.) * t.x;
+    top * (1. - t.y) + bottom * t.y
+}
+
+/// Hashes the values with SplitMix64, which unlike the std hasher is stable across versions and platforms.
+fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0x9e37_79b9_7f4a_7c15, |acc, value| {
+        let mut z = (acc ^ value).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    })
+}
+
+/// Maps a hash to the range 0..1.
+fn unit(hash: u64) -> f32 {
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NaturalCurve, NaturalElement};
+
+    fn layers() -> Vec<NaturalLayer> {
+        vec![NaturalLayer {
+            grid_size: 4.,
+            elements: vec![
+                NaturalElement {
+                    scale_min: 1.,
+                    scale_max: 2.,
+                    collider: Some(NaturalCollider::Sphere { radius: 0.5 }),
+                    tags: vec!["tree".to_string()],
+                    ..Default::default()
+                },
+                NaturalElement {
+                    scale_min: 0.5,
+                    scale_max: 0.5,
+                    ..Default::default()
+                },
+            ],
+        }]
+    }
+
+    fn state() -> TerrainStateCpu {
+        let mut state = TerrainStateCpu::empty();
+        state
+            .heightmap
+            .outer_iter_mut()
+            .nth(TerrainLayers::Rock as usize)
+            .unwrap()
+            .fill(40.);
+        for mut normal in state.normalmap.rows_mut() {
+            normal[2] = 1.;
+        }
+        state
+    }
+
+    #[test]
+    fn scatter_is_deterministic() {
+        let a = scatter_naturals_cpu(42, ivec2(3, -2), &state(), &layers());
+        let b = scatter_naturals_cpu(42, ivec2(3, -2), &state(), &layers());
+        assert!(!a.is_empty());
+        assert_eq!(a, b);
+
+        let other_seed = scatter_naturals_cpu(43, ivec2(3, -2), &state(), &layers());
+        assert_ne!(a, other_seed);
+        let other_cell = scatter_naturals_cpu(42, ivec2(4, -2), &state(), &layers());
+        assert_ne!(a, other_cell);
+    }
+
+    #[test]
+    fn scatter_only_places_elements_with_colliders_inside_the_cell() {
+        let state = state();
+        let size = state.size.size_in_meters();
+        let naturals = scatter_naturals_cpu(7, ivec2(0, 0), &state, &layers());
+        let ids = naturals.iter().map(|n| n.id).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), naturals.len());
+        for natural in naturals {
+            assert_eq!(natural.element, 0);
+            assert!((1. ..=2.).contains(&natural.scale));
+            assert!(natural.position.x >= 0. && natural.position.x < size);
+            assert!(natural.position.y >= 0. && natural.position.y < size);
+            assert!((natural.position.z - 40.).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn curves_respect_probabilities() {
+        let mut layers = layers();
+        layers[0].elements[0].elevation = NaturalCurve::InterpolateClamped {
+            x0: 0.,
+            x1: 1.,
+            y0: 0.,
+            y1: 0.,
+        };
+        assert!(scatter_naturals_cpu(42, ivec2(0, 0), &state(), &layers).is_empty());
+    }
+}
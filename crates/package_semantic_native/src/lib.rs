@@ -6,13 +6,21 @@ use std::{
 };
 
 use ambient_cb::Cb;
+use ambient_core::asset_cache;
 use ambient_ecs::{
-    components, generated::app::components::name as app_name,
-    generated::wasm::components::module_enabled, query, ComponentRegistry, Entity, EntityId,
-    ExternalComponentAttributes, ExternalComponentDesc, Networked, PrimitiveComponentType,
-    Resource, SystemGroup, World,
+    components,
+    generated::app::components::name as app_name,
+    generated::input::components::{
+        input_action, input_axis, input_bindings, input_negative_bindings,
+    },
+    generated::wasm::components::{module_enabled, package_ref},
+    query, ComponentRegistry, Entity, EntityId, ExternalComponentAttributes, ExternalComponentDesc,
+    Networked, PrimitiveComponentType, Resource, SystemGroup, World,
+};
+use ambient_native_std::{
+    asset_cache::SyncAssetKeyExt,
+    asset_url::{AbsAssetUrl, DeploymentsBaseUrlKey},
 };
-use ambient_native_std::asset_url::AbsAssetUrl;
 use ambient_network::ServerWorldExt;
 use ambient_package::PackageContent;
 use ambient_package_semantic::{
@@ -60,12 +68,9 @@ pub async fn initialize(
     wasm_spawn: WasmSpawn,
 ) -> anyhow::Result<()> {
     world.add_resource(self::wasm_spawn(), wasm_spawn);
-    world.add_resource(
-        self::semantic(),
-        Arc::new(Mutex::new(
-            ambient_package_semantic::Semantic::new(false).await?,
-        )),
-    );
+    let mut semantic = ambient_package_semantic::Semantic::new(false).await?;
+    semantic.deployments_base_url = DeploymentsBaseUrlKey.get(world.resource(asset_cache()));
+    world.add_resource(self::semantic(), Arc::new(Mutex::new(semantic)));
 
     add(
         world,
@@ -256,6 +261,33 @@ fn sync_semantic_to_world(
             .unwrap()
             .insert(package_id.clone(), entity);
 
+        for (name, action) in &manifest.actions {
+            Entity::new()
+                .with(app_name(), format!("Input action {name}"))
+                .with(input_action(), name.to_string())
+                .with(
+                    input_bindings(),
+                    action.bindings.iter().map(|b| b.to_string()).collect(),
+                )
+                .with(package_ref(), entity)
+                .spawn(world);
+        }
+        for (name, axis) in &manifest.axes {
+            Entity::new()
+                .with(app_name(), format!("Input axis {name}"))
+                .with(input_axis(), name.to_string())
+                .with(
+                    input_bindings(),
+                    axis.positive.iter().map(|b| b.to_string()).collect(),
+                )
+                .with(
+                    input_negative_bindings(),
+                    axis.negative.iter().map(|b| b.to_string()).collect(),
+                )
+                .with(package_ref(), entity)
+                .spawn(world);
+        }
+
         if let Some(metadata) = &package.build_metadata {
             let asset_url = AbsAssetUrl(base_asset_url.clone());
 
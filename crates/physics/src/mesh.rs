@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use ambient_native_std::{
     asset_cache::{AssetCache, AsyncAssetKey, AsyncAssetKeyExt, SyncAssetKeyExt},
@@ -6,6 +9,8 @@ use ambient_native_std::{
     download_asset::{AssetError, AssetsCacheOnDisk, BytesFromUrl, BytesFromUrlCachedPath},
 };
 use async_trait::async_trait;
+use glam::Vec3;
+use itertools::Itertools;
 use physxx::{
     PxConvexMesh, PxDefaultFileInputData, PxDefaultMemoryInputData, PxPhysicsRef, PxTriangleMesh,
 };
@@ -133,3 +138,488 @@ impl AsyncAssetKey<Result<PxRcAsset<PxConvexMesh>, AssetError>> for PhysxConvexM
         }
     }
 }
+
+/// Splits a triangle mesh into at most `max_hulls` convex parts, V-HACD style.
+///
+/// The mesh is voxelized with `resolution` voxels along its longest axis, and the voxels are then
+/// recursively split along the axis-aligned plane which reduces concavity the most. Returns the
+/// points of each convex hull, in the same space as `positions`.
+pub fn convex_decomposition(
+    positions: &[Vec3],
+    indices: &[u32],
+    max_hulls: usize,
+    resolution: u32,
+) -> Vec<Vec<Vec3>> {
+    let Some(grid) = VoxelGrid::from_mesh(positions, indices, resolution.max(1)) else {
+        return Vec::new();
+    };
+    let whole = DecompositionPart::new(grid.solid_voxels());
+    let total_volume = whole.volume.max(1.);
+
+    let mut done = Vec::new();
+    let mut parts = vec![whole];
+    while !parts.is_empty() && parts.len() + done.len() < max_hulls.max(1) {
+        // Always split the most concave part first
+        let (index, _) = parts
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.concavity.total_cmp(&b.concavity))
+            .unwrap();
+        let part = parts.swap_remove(index);
+        if part.concavity / total_volume < MAX_CONCAVITY {
+            done.push(part);
+            continue;
+        }
+        match part.best_split() {
+            Some((left, right)) => parts.extend([left, right]),
+            None => done.push(part),
+        }
+    }
+
+    done.into_iter()
+        .chain(parts)
+        .filter_map(|part| part.hull_points())
+        .map(|points| points.into_iter().map(|p| grid.to_mesh_space(p)).collect())
+        .collect()
+}
+
+/// Parts whose convex hull exceeds their volume by less than this fraction of the total volume are not split further.
+const MAX_CONCAVITY: f64 = 0.05;
+/// The number of planes along each axis to try when splitting a part.
+const SPLIT_PLANES_PER_AXIS: i32 = 12;
+
+struct VoxelGrid {
+    origin: Vec3,
+    voxel_size: f32,
+    dims: [i32; 3],
+    solid: Vec<bool>,
+}
+
+impl VoxelGrid {
+    fn from_mesh(positions: &[Vec3], indices: &[u32], resolution: u32) -> Option<Self> {
+        let min = positions.iter().copied().reduce(Vec3::min)?;
+        let max = positions.iter().copied().reduce(Vec3::max)?;
+        let voxel_size = (max - min).max_element() / resolution as f32;
+        if voxel_size <= 0. {
+            return None;
+        }
+        let dims = ((max - min) / voxel_size)
+            .ceil()
+            .max(Vec3::ONE)
+            .as_ivec3()
+            .to_array();
+        let mut grid = Self {
+            origin: min,
+            voxel_size,
+            dims,
+            solid: vec![false; (dims[0] * dims[1] * dims[2]) as usize],
+        };
+
+        // Mark every voxel touched by the surface, by sampling the triangles at half the voxel size
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| positions[triangle[i] as usize]);
+            let longest = (b - a).length().max((c - a).length()).max((c - b).length());
+            let steps = (longest / (voxel_size * 0.5)).ceil().max(1.) as u32;
+            for i in 0..=steps {
+                for j in 0..=(steps - i) {
+                    let (u, v) = (i as f32 / steps as f32, j as f32 / steps as f32);
+                    let voxel = grid.voxel_at(a + (b - a) * u + (c - a) * v);
+                    let index = grid.index(voxel);
+                    grid.solid[index] = true;
+                }
+            }
+        }
+
+        // Everything which can't be reached from outside the grid is inside the mesh
+        let mut outside = vec![false; grid.solid.len()];
+        let mut stack = Vec::new();
+        for z in 0..dims[2] {
+            for y in 0..dims[1] {
+                for x in 0..dims[0] {
+                    let voxel = [x, y, z];
+                    let on_edge =
+                        (0..3).any(|axis| voxel[axis] == 0 || voxel[axis] == dims[axis] - 1);
+                    if on_edge && !grid.solid[grid.index(voxel)] {
+                        outside[grid.index(voxel)] = true;
+                        stack.push(voxel);
+                    }
+                }
+            }
+        }
+        while let Some(voxel) = stack.pop() {
+            for neighbour in neighbours(voxel) {
+                if grid.contains(neighbour) {
+                    let index = grid.index(neighbour);
+                    if !outside[index] && !grid.solid[index] {
+                        outside[index] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+        for (solid, outside) in grid.solid.iter_mut().zip(outside) {
+            *solid |= !outside;
+        }
+        Some(grid)
+    }
+
+    fn voxel_at(&self, p: Vec3) -> [i32; 3] {
+        let voxel = ((p - self.origin) / self.voxel_size)
+            .floor()
+            .as_ivec3()
+            .to_array();
+        [0, 1, 2].map(|axis| voxel[axis].clamp(0, self.dims[axis] - 1))
+    }
+
+    fn contains(&self, voxel: [i32; 3]) -> bool {
+        (0..3).all(|axis| voxel[axis] >= 0 && voxel[axis] < self.dims[axis])
+    }
+
+    fn index(&self, [x, y, z]: [i32; 3]) -> usize {
+        (x + self.dims[0] * (y + self.dims[1] * z)) as usize
+    }
+
+    fn solid_voxels(&self) -> Vec<[i32; 3]> {
+        let mut voxels = Vec::new();
+        for z in 0..self.dims[2] {
+            for y in 0..self.dims[1] {
+                for x in 0..self.dims[0] {
+                    if self.solid[self.index([x, y, z])] {
+                        voxels.push([x, y, z]);
+                    }
+                }
+            }
+        }
+        voxels
+    }
+
+    /// Converts a point in half-voxel units back to the space of the mesh.
+    fn to_mesh_space(&self, p: [i64; 3]) -> Vec3 {
+        self.origin + Vec3::new(p[0] as f32, p[1] as f32, p[2] as f32) * (self.voxel_size * 0.5)
+    }
+}
+
+fn neighbours([x, y, z]: [i32; 3]) -> [[i32; 3]; 6] {
+    [
+        [x - 1, y, z],
+        [x + 1, y, z],
+        [x, y - 1, z],
+        [x, y + 1, z],
+        [x, y, z - 1],
+        [x, y, z + 1],
+    ]
+}
+
+struct DecompositionPart {
+    voxels: Vec<[i32; 3]>,
+    /// Voxels on the surface count for half, as the surface passes through them.
+    volume: f64,
+    concavity: f64,
+}
+
+impl DecompositionPart {
+    fn new(voxels: Vec<[i32; 3]>) -> Self {
+        let set = voxels.iter().copied().collect::<HashSet<_>>();
+        let surface = voxels
+            .iter()
+            .filter(|&&v| neighbours(v).iter().any(|n| !set.contains(n)))
+            .copied()
+            .collect_vec();
+        let mut volume = voxels.len() as f64 - surface.len() as f64 * 0.5;
+        let hull_volume = match ConvexHull::new(&hull_candidates(&surface, false)) {
+            Some(hull) => hull.volume(),
+            None => {
+                // The voxel centers are coplanar, so compare whole voxels instead
+                volume = voxels.len() as f64;
+                ConvexHull::new(&hull_candidates(&surface, true)).map_or(0., |hull| hull.volume())
+            }
+        };
+        Self {
+            concavity: (hull_volume - volume).abs(),
+            volume,
+            voxels,
+        }
+    }
+
+    /// Splits this part in two along the plane which leaves the least concavity.
+    ///
+    /// Planes are first sampled coarsely along each axis, and then refined around the best one.
+    fn best_split(&self) -> Option<(Self, Self)> {
+        let mut best: Option<(f64, Self, Self)> = None;
+        let mut best_plane = None;
+        for axis in 0..3 {
+            let min = self.voxels.iter().map(|v| v[axis]).min()?;
+            let max = self.voxels.iter().map(|v| v[axis]).max()?;
+            let step = ((max - min) / SPLIT_PLANES_PER_AXIS).max(1);
+            for plane in ((min + 1)..=max).step_by(step as usize) {
+                let split = self.split(axis, plane);
+                if best
+                    .as_ref()
+                    .filter(|(cost, ..)| *cost <= split.0)
+                    .is_none()
+                {
+                    best = Some(split);
+                    best_plane = Some((axis, plane, step, min, max));
+                }
+            }
+        }
+
+        let (axis, plane, step, min, max) = best_plane?;
+        for plane in
+            ((plane - step + 1).max(min + 1)..(plane + step).min(max + 1)).filter(|p| *p != plane)
+        {
+            let split = self.split(axis, plane);
+            if best
+                .as_ref()
+                .filter(|(cost, ..)| *cost <= split.0)
+                .is_none()
+            {
+                best = Some(split);
+            }
+        }
+        best.map(|(_, left, right)| (left, right))
+    }
+
+    /// Splits this part into the voxels before and after `plane` along `axis`, and the combined concavity of both.
+    fn split(&self, axis: usize, plane: i32) -> (f64, Self, Self) {
+        let (left, right): (Vec<_>, Vec<_>) = self.voxels.iter().partition(|v| v[axis] < plane);
+        let (left, right) = (Self::new(left), Self::new(right));
+        (left.concavity + right.concavity, left, right)
+    }
+
+    /// The points of this part's convex hull, in half-voxel units.
+    fn hull_points(&self) -> Option<Vec<[i64; 3]>> {
+        let set = self.voxels.iter().copied().collect::<HashSet<_>>();
+        let surface = self
+            .voxels
+            .iter()
+            .filter(|&&v| neighbours(v).iter().any(|n| !set.contains(n)))
+            .copied()
+            .collect_vec();
+        // Parts which are a single voxel thick have no volume between the voxel centers
+        let hull = ConvexHull::new(&hull_candidates(&surface, false))
+            .or_else(|| ConvexHull::new(&hull_candidates(&surface, true)))?;
+        Some(hull.vertices())
+    }
+}
+
+/// The voxel centers (or corners) which may lie on the convex hull of `voxels`, in half-voxel units.
+///
+/// Only points at either end of their row along every axis can be hull vertices.
+fn hull_candidates(voxels: &[[i32; 3]], corners: bool) -> Vec<[i64; 3]> {
+    let points = voxels
+        .iter()
+        .flat_map(|v| {
+            let center = v.map(|c| 2 * c as i64 + 1);
+            let offsets: &[[i64; 3]] = if corners {
+                &[
+                    [-1, -1, -1],
+                    [1, -1, -1],
+                    [-1, 1, -1],
+                    [1, 1, -1],
+                    [-1, -1, 1],
+                    [1, -1, 1],
+                    [-1, 1, 1],
+                    [1, 1, 1],
+                ]
+            } else {
+                &[[0, 0, 0]]
+            };
+            offsets
+                .iter()
+                .map(move |o| [center[0] + o[0], center[1] + o[1], center[2] + o[2]])
+        })
+        .unique()
+        .collect_vec();
+
+    let mut extents = [HashMap::new(), HashMap::new(), HashMap::new()];
+    for p in &points {
+        for (axis, extents) in extents.iter_mut().enumerate() {
+            let row = row_key(*p, axis);
+            let (min, max) = extents.entry(row).or_insert((p[axis], p[axis]));
+            *min = p[axis].min(*min);
+            *max = p[axis].max(*max);
+        }
+    }
+    points
+        .into_iter()
+        .filter(|p| {
+            extents.iter().enumerate().all(|(axis, extents)| {
+                let (min, max) = extents[&row_key(*p, axis)];
+                p[axis] == min || p[axis] == max
+            })
+        })
+        .collect()
+}
+
+fn row_key(p: [i64; 3], axis: usize) -> (i64, i64) {
+    match axis {
+        0 => (p[1], p[2]),
+        1 => (p[0], p[2]),
+        _ => (p[0], p[1]),
+    }
+}
+
+/// An incrementally built convex hull of integer points, which keeps all predicates exact.
+struct ConvexHull {
+    points: Vec<[i64; 3]>,
+    /// Triangles wound counter-clockwise when seen from outside the hull.
+    faces: Vec<[usize; 3]>,
+}
+
+impl ConvexHull {
+    fn new(points: &[[i64; 3]]) -> Option<Self> {
+        let a = *points.first()?;
+        let b = *points.iter().find(|&&p| p != a)?;
+        let c = *points
+            .iter()
+            .find(|&&p| cross(sub(b, a), sub(p, a)) != [0, 0, 0])?;
+        let d = *points.iter().find(|&&p| orient(a, b, c, p) != 0)?;
+        let mut hull = Self {
+            points: vec![a, b, c, d],
+            faces: if orient(a, b, c, d) < 0 {
+                vec![[0, 1, 2], [0, 3, 1], [1, 3, 2], [2, 3, 0]]
+            } else {
+                vec![[0, 2, 1], [0, 1, 3], [1, 2, 3], [2, 0, 3]]
+            },
+        };
+        for &p in points {
+            hull.add(p);
+        }
+        Some(hull)
+    }
+
+    fn add(&mut self, p: [i64; 3]) {
+        let visible = self
+            .faces
+            .iter()
+            .map(|&[a, b, c]| orient(self.points[a], self.points[b], self.points[c], p) > 0)
+            .collect_vec();
+        if !visible.contains(&true) {
+            return;
+        }
+        let visible_edges = self
+            .faces
+            .iter()
+            .zip(&visible)
+            .filter(|(_, visible)| **visible)
+            .flat_map(|(&[a, b, c], _)| [(a, b), (b, c), (c, a)])
+            .collect::<HashSet<_>>();
+
+        let index = self.points.len();
+        self.points.push(p);
+        let mut faces = self
+            .faces
+            .iter()
+            .zip(&visible)
+            .filter(|(_, visible)| !**visible)
+            .map(|(face, _)| *face)
+            .collect_vec();
+        // Connect the new point to the horizon, i.e. the edges between visible and hidden faces
+        for &(a, b) in &visible_edges {
+            if !visible_edges.contains(&(b, a)) {
+                faces.push([a, b, index]);
+            }
+        }
+        self.faces = faces;
+    }
+
+    /// The volume of the hull, in voxels.
+    fn volume(&self) -> f64 {
+        let inside = self.points[0];
+        let volume: i64 = self
+            .faces
+            .iter()
+            .map(|&[a, b, c]| -orient(self.points[a], self.points[b], self.points[c], inside))
+            .sum();
+        // The points are in half-voxel units
+        volume as f64 / 6. / 8.
+    }
+
+    fn vertices(&self) -> Vec<[i64; 3]> {
+        self.faces
+            .iter()
+            .flatten()
+            .unique()
+            .map(|&i| self.points[i])
+            .collect()
+    }
+}
+
+fn sub(a: [i64; 3], b: [i64; 3]) -> [i64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [i64; 3], b: [i64; 3]) -> [i64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Positive if `d` is in front of the triangle `a`, `b`, `c` (wound counter-clockwise).
+fn orient(a: [i64; 3], b: [i64; 3], c: [i64; 3], d: [i64; 3]) -> i64 {
+    let n = cross(sub(b, a), sub(c, a));
+    let e = sub(d, a);
+    n[0] * e[0] + n[1] * e[1] + n[2] * e[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A closed triangle mesh made of one axis-aligned box per `(min, max)` pair.
+    fn boxes(boxes: &[(Vec3, Vec3)]) -> (Vec<Vec3>, Vec<u32>) {
+        const QUADS: [[u32; 4]; 6] = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+        let mut positions = Vec::new();
+        let mut indices = Vec::new();
+        for &(min, max) in boxes {
+            let offset = positions.len() as u32;
+            positions.extend((0..8).map(|corner| {
+                Vec3::select(
+                    glam::BVec3::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0),
+                    max,
+                    min,
+                )
+            }));
+            for [a, b, c, d] in QUADS {
+                indices.extend([a, b, c, a, c, d].map(|i| offset + i));
+            }
+        }
+        (positions, indices)
+    }
+
+    #[test]
+    fn convex_mesh_is_one_hull() {
+        let (positions, indices) = boxes(&[(Vec3::ZERO, Vec3::ONE)]);
+        let hulls = convex_decomposition(&positions, &indices, 16, 32);
+        assert_eq!(hulls.len(), 1);
+    }
+
+    #[test]
+    fn l_shape_is_split() {
+        let (positions, indices) = boxes(&[
+            (Vec3::ZERO, Vec3::new(4., 1., 1.)),
+            (Vec3::ZERO, Vec3::new(1., 4., 1.)),
+        ]);
+        let hulls = convex_decomposition(&positions, &indices, 16, 32);
+        assert!(hulls.len() >= 2, "got {} hulls", hulls.len());
+        for hull in &hulls {
+            let min = hull.iter().copied().reduce(Vec3::min).unwrap();
+            let max = hull.iter().copied().reduce(Vec3::max).unwrap();
+            // Every hull lies within one of the arms, rather than spanning the notch
+            assert!(
+                max.x <= 1.5 || max.y <= 1.5,
+                "hull {min} {max} spans the notch"
+            );
+        }
+    }
+}
@@ -61,6 +61,13 @@ pub struct ModelsPipeline {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub transforms: Vec<ModelTransform>,
+    /// If specified, generates simplified LODs for meshes which don't have any.
+    ///
+    /// Each entry is the fraction of the original triangles to keep for that LOD, i.e. `[0.5, 0.2, 0.05]`.
+    /// The distances at which the LODs are switched are picked automatically.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub generate_lods: Vec<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +90,8 @@ pub enum ModelImporter {
         /// Whether or not the Unity prefabs should be converted to Ambient prefabs.
         use_prefabs: bool,
     },
+    /// Import Unity scenes (.unity), including prefab instances and their overrides, directional lights and colliders.
+    UnityScenes,
     /// Import Quixel models.
     Quixel,
 }
@@ -122,6 +131,24 @@ pub enum Collider {
         /// The height of the collider.
         height: Option<f32>,
     },
+    /// Split the model into convex pieces, which can be used by dynamic objects.
+    ConvexDecomposition {
+        /// The maximum number of convex pieces to produce.
+        #[serde(default = "default_max_hulls")]
+        max_hulls: u32,
+        /// The number of voxels along the longest axis of the model used to find the pieces.
+        /// Higher values are more accurate, but slower to process.
+        #[serde(default = "default_decomposition_resolution")]
+        resolution: u32,
+    },
+}
+
+fn default_max_hulls() -> u32 {
+    16
+}
+
+fn default_decomposition_resolution() -> u32 {
+    32
 }
 
 impl Collider {
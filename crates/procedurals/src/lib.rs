@@ -1,27 +1,34 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, ops::Range, sync::Arc};
 
 use ambient_core::{
     asset_cache,
     bounding::{local_bounding_aabb, world_bounding_aabb, world_bounding_sphere},
     gpu, main_scene,
-    transform::{local_to_world, mesh_to_world},
+    transform::{inv_local_to_world, local_to_world, mesh_to_world},
 };
 use ambient_ecs::{
     components,
     generated::procedurals::components::{procedural_material, procedural_mesh},
-    query, Entity, Resource, SystemGroup,
+    query, Entity, FnSystem, Resource, SystemGroup,
 };
-use ambient_gpu::{mesh_buffer::GpuMesh, texture::TextureView};
-use ambient_native_std::{cb, mesh::Mesh};
+use ambient_gpu::{
+    mesh_buffer::{GpuMesh, MeshBufferKey},
+    texture::TextureView,
+};
+use ambient_native_std::{asset_cache::SyncAssetKeyExt, cb, mesh::Mesh};
 use ambient_renderer::{
     gpu_primitives_lod, gpu_primitives_mesh,
     pbr_material::{get_pbr_shader, PbrMaterial, PbrMaterialConfig},
-    primitives, renderer_shader, SharedMaterial,
+    primitives, renderer_shader,
+    skinning::{inverse_bind_matrices, joint_matrices, joints, skin, SkinsBufferKey},
+    SharedMaterial,
 };
 use ambient_shared_types::{
     procedural_storage_handle_definitions, ProceduralMaterialHandle, ProceduralMeshHandle,
     ProceduralSamplerHandle, ProceduralTextureHandle,
 };
+use glam::Mat4;
+use itertools::Itertools;
 use paste::paste;
 
 components!("procedurals", {
@@ -65,6 +72,86 @@ pub fn client_systems() -> SystemGroup {
                         world.add_component_if_required(id, mesh_to_world(), Default::default());
                 }
             }),
+            Box::new(FnSystem::new(|world, _| {
+                let changes =
+                    std::mem::take(&mut world.resource_mut(procedural_storage()).mesh_changes);
+                if changes.is_empty() {
+                    return;
+                }
+                let assets = world.resource(asset_cache()).clone();
+                let gpu = world.resource(gpu()).clone();
+                let entities = query((procedural_mesh(), ambient_core::mesh()))
+                    .iter(world, None)
+                    .map(|(id, (&handle, gpu_mesh))| (id, handle, gpu_mesh.clone()))
+                    .collect_vec();
+                for change in changes {
+                    for (id, _, gpu_mesh) in entities
+                        .iter()
+                        .filter(|(_, handle, _)| *handle == change.handle)
+                    {
+                        let components = {
+                            let storage = world.resource(procedural_storage());
+                            let Some(mesh) = storage.meshes.try_get(change.handle) else {
+                                continue;
+                            };
+                            let mesh_aabb = mesh.aabb();
+                            let written = MeshBufferKey.get(&assets).lock().write_vertices(
+                                &gpu,
+                                gpu_mesh,
+                                mesh,
+                                change.vertices.clone(),
+                                change.indices_changed,
+                            );
+                            let mut components = Entity::new()
+                                .with(local_bounding_aabb(), mesh_aabb)
+                                .with(world_bounding_aabb(), mesh_aabb)
+                                .with(world_bounding_sphere(), mesh_aabb.to_sphere());
+                            if !written {
+                                components.set(
+                                    ambient_core::mesh(),
+                                    GpuMesh::from_mesh(&gpu, &assets, mesh),
+                                );
+                            }
+                            components
+                        };
+                        world.add_components(*id, components).unwrap();
+                    }
+                }
+            })),
+            query((procedural_mesh(), joints())).excl(skin()).to_system(
+                |query, world, query_state, _| {
+                    let assets = world.resource(asset_cache()).clone();
+                    let gpu = world.resource(gpu()).clone();
+                    for (id, (_, joints)) in query.collect_cloned(world, query_state) {
+                        let inv_ltw = world
+                            .get(id, local_to_world())
+                            .unwrap_or_default()
+                            .inverse();
+                        // The skeleton's pose at the time the mesh is attached is used as its bind pose
+                        let bind_matrices = joints
+                            .iter()
+                            .map(|&joint| {
+                                (inv_ltw * world.get(joint, local_to_world()).unwrap_or_default())
+                                    .inverse()
+                            })
+                            .collect_vec();
+                        let mesh_skin = SkinsBufferKey
+                            .get(&assets)
+                            .lock()
+                            .create(&gpu, joints.len() as u32);
+                        world
+                            .add_components(
+                                id,
+                                Entity::new()
+                                    .with(inverse_bind_matrices(), Arc::new(bind_matrices))
+                                    .with(joint_matrices(), vec![Mat4::IDENTITY; joints.len()])
+                                    .with(skin(), mesh_skin),
+                            )
+                            .unwrap();
+                        let _ = world.add_component_if_required(id, inv_local_to_world(), inv_ltw);
+                    }
+                },
+            ),
             query(procedural_material().changed()).to_system(|query, world, query_state, _| {
                 let assets = world.resource(asset_cache()).clone();
                 let gpu = world.resource(gpu()).clone();
@@ -120,6 +207,20 @@ where
             .unwrap_or_else(|| panic!("Procedural resource {handle} must exist"))
     }
 
+    pub fn try_get(&self, handle: Handle) -> Option<&Resource> {
+        self.0.get(&handle)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> &mut Resource {
+        self.0
+            .get_mut(&handle)
+            .unwrap_or_else(|| panic!("Procedural resource {handle} must exist"))
+    }
+
+    pub fn try_get_mut(&mut self, handle: Handle) -> Option<&mut Resource> {
+        self.0.get_mut(&handle)
+    }
+
     pub fn remove(&mut self, handle: Handle) -> Resource {
         self.0
             .remove(&handle)
@@ -133,12 +234,22 @@ impl<Handle, Resource> Default for ProceduralMap<Handle, Resource> {
     }
 }
 
+/// A change made to the vertices of a procedural mesh after it was created, which has to be
+/// uploaded to the GPU meshes of the entities that use it.
+#[derive(Clone, Debug)]
+pub struct ProceduralMeshChange {
+    pub handle: ProceduralMeshHandle,
+    pub vertices: Range<usize>,
+    pub indices_changed: bool,
+}
+
 #[derive(Clone)]
 pub struct ProceduralStorage {
     pub meshes: ProceduralMap<ProceduralMeshHandle, ProceduralMesh>,
     pub textures: ProceduralMap<ProceduralTextureHandle, ProceduralTexture>,
     pub samplers: ProceduralMap<ProceduralSamplerHandle, ProceduralSampler>,
     pub materials: ProceduralMap<ProceduralMaterialHandle, ProceduralMaterial>,
+    pub mesh_changes: Vec<ProceduralMeshChange>,
 }
 
 impl ProceduralStorage {
@@ -149,6 +260,7 @@ impl ProceduralStorage {
             textures: Default::default(),
             samplers: Default::default(),
             materials: Default::default(),
+            mesh_changes: Default::default(),
         }
     }
 }
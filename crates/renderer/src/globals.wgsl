@@ -142,6 +142,7 @@ fn get_solids_screen_normal_quat(screen_ndc: vec3<f32>) -> vec4<f32> {
 struct MaterialInput {
             position: vec4<f32>,
             texcoord: vec2<f32>,
+            color: vec4<f32>,
             world_position: vec3<f32>,
             normal: vec3<f32>,
             normal_matrix: mat3x3<f32>,
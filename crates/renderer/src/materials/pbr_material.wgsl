@@ -30,7 +30,7 @@ fn get_material(in: MaterialInput) -> MaterialOutput {
     var out: MaterialOutput;
     let base_color_texture_sample = textureSample(base_color_texture, base_color_sampler, in.texcoord);
     let mr = textureSample(metallic_roughness, base_color_sampler, in.texcoord);
-    let color = base_color_texture_sample * pbr_params.base_color_factor * get_entity_color_or(in.entity_loc, vec4<f32>(1., 1., 1., 1.));
+    let color = base_color_texture_sample * pbr_params.base_color_factor * in.color * get_entity_color_or(in.entity_loc, vec4<f32>(1., 1., 1., 1.));
     out.opacity = color.a;
 
     out.metallic = mr.r * pbr_params.metallic;
@@ -3,6 +3,8 @@ struct MeshBase {
     normal: vec3<f32>,
     tangent: vec3<f32>,
     texcoord0: vec2<f32>,
+    texcoord1: vec2<f32>,
+    color: vec4<f32>,
 }
 
 struct MeshSkinned {
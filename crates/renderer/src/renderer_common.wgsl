@@ -10,6 +10,7 @@ struct ModelToWorld {
     normal: vec3<f32>,
     tangent: vec3<f32>,
     texcoord: vec2<f32>,
+    color: vec4<f32>,
 }
 
 
@@ -24,6 +25,7 @@ fn model_to_world(loc: vec2<u32>, mesh_index: u32, vertex_index: u32) -> ModelTo
     let normal = vec4<f32>(mesh.normal.xyz, 0.0);
     let tangent = vec4<f32>(mesh.tangent.xyz, 0.0);
     let texcoord: vec2<f32> = mesh.texcoord0;
+    let color: vec4<f32> = mesh.color;
 
     var result: ModelToWorld;
 
@@ -70,12 +72,14 @@ fn model_to_world(loc: vec2<u32>, mesh_index: u32, vertex_index: u32) -> ModelTo
         result.normal = normalize((model * total_norm).xyz);
         result.tangent = normalize((model * total_tangent).xyz);
         result.texcoord = texcoord;
+        result.color = color;
     } else {
         result.local = pos;
         result.pos = model * pos;
         result.normal = normalize((model * normal).xyz);
         result.tangent = normalize((model * tangent).xyz);
         result.texcoord = texcoord;
+        result.color = color;
     }
 
     return result;
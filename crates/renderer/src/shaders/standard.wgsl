@@ -8,6 +8,7 @@ struct VertexOutput {
     @location(4) world_bitangent: vec3<f32>,
     @location(5) world_normal: vec3<f32>,
     @location(6) local_position: vec3<f32>,
+    @location(7) color: vec4<f32>,
 };
 
 @vertex
@@ -21,6 +22,7 @@ fn vs_main(@builtin(instance_index) instance_index: u32, @builtin(vertex_index)
     let world = model_to_world(entity_loc, mesh_index, vertex_index);
     out.instance_index = instance_index;
     out.texcoord = world.texcoord;
+    out.color = world.color;
 
     out.world_normal = world.normal;
     out.world_tangent = world.tangent;
@@ -38,6 +40,7 @@ fn get_material_in(in: VertexOutput, is_front: bool) -> MaterialInput {
     var material_in: MaterialInput;
     material_in.position = in.position;
     material_in.texcoord = in.texcoord;
+    material_in.color = in.color;
     material_in.world_position = in.world_position.xyz / in.world_position.w;
     material_in.normal = in.world_normal;
     material_in.normal_matrix = mat3x3<f32>(
@@ -5,6 +5,11 @@ pub struct GeneralSettings {
     pub user_id: Option<String>,
     pub api_token: Option<String>,
     pub sentry: Sentry,
+    #[serde(default)]
+    pub disk_cache: DiskCache,
+    /// The locale of the UI, like `en` or `pt-BR`; `None` uses the default locale
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -23,3 +28,20 @@ impl Default for Sentry {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiskCache {
+    /// Whether downloaded and processed assets are kept on disk between sessions
+    pub enabled: bool,
+    /// The maximum size of the cache in megabytes; the least recently used assets are evicted beyond this
+    pub budget_mb: u64,
+}
+
+impl Default for DiskCache {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            budget_mb: 10 * 1024,
+        }
+    }
+}
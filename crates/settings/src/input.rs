@@ -0,0 +1,22 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct InputSettings {
+    /// Bindings that replace the defaults from a package's manifest, keyed by `<package id>/<action or axis name>`
+    #[serde(default)]
+    pub bindings: BTreeMap<String, InputBindingOverride>,
+}
+impl InputSettings {
+    pub fn key(package_id: &str, name: &str) -> String {
+        format!("{package_id}/{name}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct InputBindingOverride {
+    pub bindings: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub negative_bindings: Vec<String>,
+}
@@ -0,0 +1,695 @@
+//! CPU implementations of the terrain brushes.
+//!
+//! These mirror the WGSL compute shaders next to them, so that terrain can be edited where there is no GPU
+//! (such as on dedicated servers), and so that brush results can be tested. The heightmap is laid out like
+//! [TerrainStateCpu::heightmap](crate::TerrainStateCpu), i.e. `(layer, y, x)`.
+//!
+//! The shaders read and write the same texture from many invocations at once; here, every texel of a pass
+//! reads the heightmap as it was before the pass started, which makes the results deterministic. Like the
+//! shaders, reads outside of the heightmap return `0.` and writes outside of it are discarded. The water
+//! outflow and velocity layers are not part of the [TERRAIN_LAYERS](crate::TERRAIN_LAYERS) heightmap, so they
+//! always read as `0.`.
+
+use std::f32::consts::PI;
+
+use glam::{ivec2, vec2, vec3, IVec2, Vec2, Vec3};
+use ndarray::{s, Array2, Array3};
+
+use super::{
+    noise_offsets, BrushShape, BrushWGSL, FlattenBrushParams, HydraulicErosionConfig,
+    RaiseBrushConfig, ThermalErosionParams, WaterSimParams,
+};
+use crate::{TerrainLayers, TERRAIN_BASE};
+
+const ROCK: usize = TerrainLayers::Rock as usize;
+const SOIL: usize = TerrainLayers::Soil as usize;
+const SEDIMENT: usize = TerrainLayers::Sediment as usize;
+const HARDNESS: usize = TerrainLayers::Hardness as usize;
+const HARDNESS_STRATA_AMOUNT: usize = TerrainLayers::HardnessStrataAmount as usize;
+const HARDNESS_STRATA_WAVELENGTH: usize = TerrainLayers::HardnessStrataWavelength as usize;
+const WATER: usize = TerrainLayers::Water as usize;
+const WATER_OUTFLOW_L: usize = TerrainLayers::WaterOutflowL as usize;
+const WATER_OUTFLOW_R: usize = TerrainLayers::WaterOutflowR as usize;
+const WATER_OUTFLOW_T: usize = TerrainLayers::WaterOutflowT as usize;
+const WATER_OUTFLOW_B: usize = TerrainLayers::WaterOutflowB as usize;
+const WATER_VELOCITY_X: usize = TerrainLayers::WaterVelocityX as usize;
+const WATER_VELOCITY_Y: usize = TerrainLayers::WaterVelocityY as usize;
+
+/// The time step of [water_sim].
+const WATER_SIM_DT: f32 = 0.02;
+
+/// The noise texture used by [raise], sampled like a linearly filtered texture with clamp-to-edge addressing.
+#[derive(Debug, Clone)]
+pub struct BrushNoise {
+    /// The red channel of the texture, indexed by `(y, x)`.
+    texels: Array2<f32>,
+}
+impl BrushNoise {
+    pub fn from_image(image: &image::RgbaImage) -> Self {
+        let texels = Array2::from_shape_fn(
+            (image.height() as usize, image.width() as usize),
+            |(y, x)| image.get_pixel(x as u32, y as u32)[0] as f32 / 255.,
+        );
+        Self { texels }
+    }
+    /// A noise texture with the same value everywhere.
+    pub fn constant(value: f32) -> Self {
+        Self {
+            texels: Array2::from_elem((1, 1), value),
+        }
+    }
+    pub fn sample(&self, uv: Vec2) -> f32 {
+        let (height, width) = self.texels.dim();
+        let texel = |x: i32, y: i32| {
+            self.texels[(
+                y.clamp(0, height as i32 - 1) as usize,
+                x.clamp(0, width as i32 - 1) as usize,
+            )]
+        };
+        let p = uv * vec2(width as f32, height as f32) - 0.5;
+        let (x, y) = (p.x.floor() as i32, p.y.floor() as i32);
+        let t = p - p.floor();
+        let top = mix(texel(x, y), texel(x + 1, y), t.x);
+        let bottom = mix(texel(x, y + 1), texel(x + 1, y + 1), t.x);
+        mix(top, bottom, t.y)
+    }
+}
+
+impl BrushWGSL {
+    fn distance_from_edge(&self, world_position: Vec2) -> f32 {
+        match self.shape {
+            BrushShape::Circle => (self.center - world_position).length().min(self.radius),
+            BrushShape::Square => {
+                let d = (world_position - self.center).abs();
+                d.x.max(d.y).min(self.radius)
+            }
+        }
+    }
+    /// The amount the brush affects `world_position`; see `get_brush_strength` in `brush.wgsl`.
+    pub fn strength_at(&self, world_position: Vec2) -> f32 {
+        let d = self.distance_from_edge(world_position);
+        smoothstep_power(self.radius, 0., d, self.smoothness) * self.amplitude
+    }
+}
+
+/// Raises (or with a negative amplitude, lowers) `params.layer`, and regenerates the hardness layers.
+pub fn raise(heightmap: &mut Array3<f32>, config: &RaiseBrushConfig, noise: &BrushNoise) {
+    let params = &config.params;
+    let offsets = noise_offsets(config.seed);
+    let layer = params.layer as usize;
+    for cell in texels(heightmap) {
+        let p =
+            cell.as_vec2() * params.heightmap_world_texel_size + params.heightmap_world_position;
+        let brush_strength = params.brush.strength_at(p);
+        let p = p * 0.01;
+
+        let height = load(heightmap, cell, layer)
+            + brush_strength * mix(0.5, 1., noise.sample(offsets[0] + p * 0.1));
+        store(heightmap, cell, layer, height.max(0.));
+
+        let hardness = noise.sample(offsets[1] + p * 0.02);
+        store(heightmap, cell, HARDNESS, hardness);
+
+        let strata_amplitude = smoothstep(0.4, 0.7, noise.sample(offsets[2] + p * 0.01));
+        store(heightmap, cell, HARDNESS_STRATA_AMOUNT, strata_amplitude);
+
+        let strata_wavelength = mix(60., 200., noise.sample(offsets[3] + p * 0.05));
+        store(
+            heightmap,
+            cell,
+            HARDNESS_STRATA_WAVELENGTH,
+            strata_wavelength,
+        );
+    }
+}
+
+/// Moves every layer towards `start_heights`, the values of each layer at the texel the stroke started at.
+pub fn flatten(heightmap: &mut Array3<f32>, params: &FlattenBrushParams, start_heights: &[f32]) {
+    for layer in 0..heightmap.dim().0 {
+        let sample_height = start_heights.get(layer).copied().unwrap_or_default();
+        for cell in texels(heightmap) {
+            let p = cell.as_vec2() * params.heightmap_world_texel_size
+                + params.heightmap_world_position;
+            let our_height = load(heightmap, cell, layer);
+            let delta = sample_height - our_height;
+
+            let brush_strength = params.brush.strength_at(p) * 0.04;
+            let target_height = our_height + (delta * brush_strength).clamp(-10.0, 10.0);
+            store(heightmap, cell, layer, target_height);
+        }
+    }
+}
+
+/// Simulates the droplets of [HydraulicErosionConfig] one after the other.
+pub fn hydraulic_erosion(heightmap: &mut Array3<f32>, config: &HydraulicErosionConfig) {
+    let params = &config.params;
+    let (_, height, width) = heightmap.dim();
+    let map_size = params.heightmap_size.x;
+    let (brush_positions, brush_weights) = config.erosion_brush();
+    let random_positions = config.droplet_positions(glam::uvec2(width as u32, height as u32));
+
+    for (id, start) in random_positions.iter().enumerate() {
+        let mut pos = start.as_vec2();
+        let mut dir = Vec2::ZERO;
+        let mut vel = params.start_velocity;
+        let mut water = params.start_water;
+        let mut sediment = 0.;
+
+        for lifetime in 0..params.max_lifetime {
+            let node = pos.as_ivec2();
+            let cell_offset = pos - pos.floor();
+            let (gradient, current_height) = gradient_and_height(heightmap, pos);
+
+            dir = dir * (1. - params.inertia) - gradient * params.inertia;
+            if dir.length() < 0.00001 {
+                let random = random_positions[(id + lifetime as usize) % random_positions.len()];
+                dir = (random - params.heightmap_size / 2).as_vec2().normalize();
+            }
+
+            if node.x < params.border_size
+                || node.x > map_size - params.border_size
+                || node.y < params.border_size
+                || node.y > map_size - params.border_size
+            {
+                break;
+            }
+
+            dir = dir.normalize();
+            pos += dir;
+
+            let (_, new_height) = gradient_and_height(heightmap, pos);
+            let delta_height = new_height - current_height;
+
+            let capacity = (-delta_height).max(params.min_slope) * vel * water * params.capacity;
+
+            let hardness = hardness(heightmap, node, new_height + TERRAIN_BASE);
+
+            if sediment > capacity || delta_height > 0. {
+                let amount_to_deposit = if delta_height > 0. {
+                    delta_height.min(sediment)
+                } else {
+                    (sediment - capacity) * params.deposition
+                };
+                sediment -= amount_to_deposit;
+
+                let deposit = |heightmap: &mut Array3<f32>, cell: IVec2, weight: f32| {
+                    let soil = load(heightmap, cell, SOIL);
+                    store(
+                        heightmap,
+                        cell,
+                        SOIL,
+                        (soil + amount_to_deposit * weight).max(0.),
+                    );
+                };
+                let o = cell_offset;
+                deposit(heightmap, node, (1. - o.x) * (1. - o.y));
+                deposit(heightmap, node + ivec2(1, 0), o.x * (1. - o.y));
+                deposit(heightmap, node + ivec2(0, 1), (1. - o.x) * o.y);
+                deposit(heightmap, node + ivec2(1, 1), o.x * o.y);
+            } else {
+                let amount_to_erode =
+                    ((capacity - sediment) * params.erosion * mix(1., 0.2, hardness))
+                        .min(-delta_height);
+
+                for (offset, weight) in brush_positions.iter().zip(&brush_weights) {
+                    let erode_pos = node + *offset;
+                    let weighted_erode_amount = amount_to_erode * weight;
+                    let current_soil = load(heightmap, erode_pos, SOIL);
+                    if current_soil > 0. {
+                        let soil = (current_soil - weighted_erode_amount).max(0.);
+                        store(heightmap, erode_pos, SOIL, soil);
+                    } else {
+                        let rock =
+                            (load(heightmap, erode_pos, ROCK) - weighted_erode_amount).max(0.);
+                        store(heightmap, erode_pos, ROCK, rock);
+                    }
+                    sediment += weighted_erode_amount;
+                }
+            }
+
+            let gravity = mix(0.1, 4., hardness);
+            vel = (vel * vel + delta_height * gravity).max(0.).sqrt();
+            water *= 1. - params.evaporation;
+        }
+    }
+}
+
+/// Moves rock and soil towards one neighbour, chosen by `params.frame`, where the slope is too steep.
+pub fn thermal_erosion(heightmap: &mut Array3<f32>, params: &ThermalErosionParams) {
+    struct Sample {
+        rock: f32,
+        soil: f32,
+        depth: f32,
+    }
+    fn get_sample(heightmap: &Array3<f32>, cell: IVec2) -> Sample {
+        let rock = load(heightmap, cell, ROCK);
+        let soil = load(heightmap, cell, SOIL);
+        Sample {
+            rock,
+            soil,
+            depth: rock + soil,
+        }
+    }
+    fn thermal_erode(
+        local_depth: f32,
+        neighbor_depth: f32,
+        local: f32,
+        neighbor: f32,
+        viscosity: f32,
+        brush_strength: f32,
+    ) -> f32 {
+        let d = local_depth - neighbor_depth;
+        if d.abs().atan() > viscosity {
+            if d > 0. {
+                local - (brush_strength * d / 4.).min(local)
+            } else {
+                local + (brush_strength * -d / 4.).min(neighbor)
+            }
+        } else {
+            local
+        }
+    }
+
+    let source = heightmap.clone();
+    let size = params.heightmap_texture_size.as_vec2();
+    let dir = frame_direction(params.frame);
+    for cell in texels(&source) {
+        let t = cell.as_vec2();
+        let border_dist = t.x.min(t.y).min((size.x - 1. - t.x).min(size.y - 1. - t.y));
+        let brush_strength = smoothstep(0., 50., (border_dist - 1.).max(0.));
+
+        let local = get_sample(&source, cell);
+        let neighbor = get_sample(&source, cell + dir);
+
+        if local.soil > 0.1 {
+            let soil = thermal_erode(
+                local.depth,
+                neighbor.depth,
+                local.soil,
+                neighbor.soil,
+                10f32.to_radians(),
+                brush_strength,
+            );
+            store(heightmap, cell, SOIL, soil);
+        } else {
+            let hardness = hardness(&source, cell, local.depth + TERRAIN_BASE);
+            let viscosity = mix(89f32.to_radians(), 45f32.to_radians(), hardness);
+            let rock = thermal_erode(
+                local.depth,
+                neighbor.depth,
+                local.rock,
+                neighbor.rock,
+                viscosity,
+                brush_strength,
+            );
+            store(heightmap, cell, ROCK, rock);
+        }
+    }
+}
+
+/// Runs one step of the water simulation: rain, water flow, erosion, sediment transport and thermal erosion.
+pub fn water_sim(heightmap: &mut Array3<f32>, params: &WaterSimParams) {
+    let height = |heightmap: &Array3<f32>, cell: IVec2| {
+        load(heightmap, cell, ROCK) + load(heightmap, cell, SOIL) + load(heightmap, cell, WATER)
+    };
+    let velocity = |heightmap: &Array3<f32>, cell: IVec2| {
+        vec2(
+            load(heightmap, cell, WATER_VELOCITY_X),
+            load(heightmap, cell, WATER_VELOCITY_Y),
+        )
+    };
+
+    // rain
+    for cell in texels(heightmap) {
+        let water = load(heightmap, cell, WATER) + WATER_SIM_DT * 0.012;
+        store(heightmap, cell, WATER, water);
+    }
+
+    // flux
+    let source = heightmap.clone();
+    for cell in texels(&source) {
+        let cell_height = height(&source, cell);
+        let water = load(&source, cell, WATER);
+        let next_flux = |delta: IVec2, layer: usize| {
+            let f = load(&source, cell, layer);
+            let h = (cell_height - height(&source, cell + delta)).clamp(-2., 2.);
+            (f + WATER_SIM_DT * params.gravity * h).max(0.)
+        };
+        let outflows = [
+            (WATER_OUTFLOW_L, next_flux(ivec2(-1, 0), WATER_OUTFLOW_L)),
+            (WATER_OUTFLOW_R, next_flux(ivec2(1, 0), WATER_OUTFLOW_R)),
+            (WATER_OUTFLOW_T, next_flux(ivec2(0, -1), WATER_OUTFLOW_T)),
+            (WATER_OUTFLOW_B, next_flux(ivec2(0, 1), WATER_OUTFLOW_B)),
+        ];
+        let f_total: f32 = outflows.iter().map(|(_, f)| f).sum();
+        let k = if f_total == 0. {
+            1.
+        } else {
+            (water / (f_total * WATER_SIM_DT)).max(1.)
+        };
+        for (layer, f) in outflows {
+            store(heightmap, cell, layer, k * f);
+        }
+    }
+
+    // update_water
+    let source = heightmap.clone();
+    for cell in texels(&source) {
+        let f_l_to_r = load(&source, cell + ivec2(-1, 0), WATER_OUTFLOW_R);
+        let f_r_to_l = load(&source, cell + ivec2(1, 0), WATER_OUTFLOW_L);
+        let f_t_to_b = load(&source, cell + ivec2(0, -1), WATER_OUTFLOW_B);
+        let f_b_to_t = load(&source, cell + ivec2(0, 1), WATER_OUTFLOW_T);
+
+        let f_l = load(&source, cell, WATER_OUTFLOW_L);
+        let f_r = load(&source, cell, WATER_OUTFLOW_R);
+        let f_b = load(&source, cell, WATER_OUTFLOW_B);
+        let f_t = load(&source, cell, WATER_OUTFLOW_T);
+
+        let water_change =
+            WATER_SIM_DT * (f_l_to_r + f_r_to_l + f_t_to_b + f_b_to_t - (f_l + f_r + f_t + f_b));
+        let evaporation = 0.015;
+        let water =
+            (load(&source, cell, WATER) + water_change).max(0.) * (1. - evaporation * WATER_SIM_DT);
+        store(heightmap, cell, WATER, water);
+
+        let w_x = 0.5 * (f_l_to_r - f_l + f_r - f_r_to_l);
+        let w_y = 0.5 * (f_t_to_b - f_t + f_b - f_b_to_t);
+        store(heightmap, cell, WATER_VELOCITY_X, w_x);
+        store(heightmap, cell, WATER_VELOCITY_Y, w_y);
+    }
+
+    // water_erosion
+    let source = heightmap.clone();
+    for cell in texels(&source) {
+        let mut rock = load(&source, cell, ROCK);
+        let mut sediment = load(&source, cell, SEDIMENT);
+
+        let k_c = 1.;
+        let carrying_capacity =
+            k_c * (1. - terrain_normal(&source, cell).z) * velocity(&source, cell).length();
+
+        if sediment < carrying_capacity {
+            let rock_hardness = 0.01;
+            let rock_to_sediment = WATER_SIM_DT * rock_hardness * (carrying_capacity - sediment);
+            rock -= rock_to_sediment;
+            sediment += rock_to_sediment;
+        } else {
+            let deposit_speed = 1.;
+            let deposit = WATER_SIM_DT * deposit_speed * (sediment - carrying_capacity);
+            rock += deposit;
+            sediment -= deposit;
+        }
+
+        store(heightmap, cell, ROCK, rock);
+        store(heightmap, cell, SEDIMENT, sediment);
+    }
+
+    // sediment_movement
+    let source = heightmap.clone();
+    for cell in texels(&source) {
+        let pos = cell.as_vec2() - WATER_SIM_DT * velocity(&source, cell);
+        let sediment = bilinear_sample(&source, pos, SEDIMENT);
+        store(heightmap, cell, SEDIMENT, sediment);
+    }
+
+    // thermal_erosion
+    let source = heightmap.clone();
+    let dir = frame_direction(params.frame);
+    for cell in texels(&source) {
+        let mut rock = load(&source, cell, ROCK);
+        let d = rock - load(&source, cell + dir, ROCK);
+        if d.abs() > 0.1 {
+            rock -= d / 4.;
+        }
+        store(heightmap, cell, ROCK, rock);
+    }
+}
+
+/// Computes the normals of the rock and soil layers, laid out like
+/// [TerrainStateCpu::normalmap](crate::TerrainStateCpu), i.e. `(y, x, xyzw)`.
+pub fn normalmap(heightmap: &Array3<f32>) -> Array3<f32> {
+    let (_, height, width) = heightmap.dim();
+    let mut normalmap = Array3::zeros((height, width, 4));
+    for cell in texels(heightmap) {
+        let normal = terrain_normal(heightmap, cell);
+        normalmap
+            .slice_mut(s![cell.y as usize, cell.x as usize, ..3])
+            .assign(&ndarray::arr1(&normal.to_array()));
+    }
+    normalmap
+}
+
+fn texels(heightmap: &Array3<f32>) -> impl Iterator<Item = IVec2> {
+    let (_, height, width) = heightmap.dim();
+    (0..height).flat_map(move |y| (0..width).map(move |x| ivec2(x as i32, y as i32)))
+}
+
+fn load(heightmap: &Array3<f32>, cell: IVec2, layer: usize) -> f32 {
+    if cell.x < 0 || cell.y < 0 {
+        return 0.;
+    }
+    heightmap
+        .get((layer, cell.y as usize, cell.x as usize))
+        .copied()
+        .unwrap_or_default()
+}
+
+fn store(heightmap: &mut Array3<f32>, cell: IVec2, layer: usize, value: f32) {
+    if cell.x < 0 || cell.y < 0 {
+        return;
+    }
+    if let Some(texel) = heightmap.get_mut((layer, cell.y as usize, cell.x as usize)) {
+        *texel = value;
+    }
+}
+
+fn bilinear_sample(heightmap: &Array3<f32>, pos: Vec2, layer: usize) -> f32 {
+    let coord = pos.as_ivec2();
+    let p = pos - pos.floor();
+
+    let nw = load(heightmap, coord, layer);
+    let ne = load(heightmap, coord + ivec2(1, 0), layer);
+    let sw = load(heightmap, coord + ivec2(0, 1), layer);
+    let se = load(heightmap, coord + ivec2(1, 1), layer);
+
+    nw * (1. - p.x) * (1. - p.y) + ne * p.x * (1. - p.y) + sw * (1. - p.x) * p.y + se * p.x * p.y
+}
+
+fn terrain_height(heightmap: &Array3<f32>, cell: IVec2) -> f32 {
+    load(heightmap, cell, ROCK) + load(heightmap, cell, SOIL)
+}
+
+/// The gradient and the height of the rock and soil layers at `pos`, interpolated bilinearly.
+fn gradient_and_height(heightmap: &Array3<f32>, pos: Vec2) -> (Vec2, f32) {
+    let coord = pos.as_ivec2();
+    let p = pos - pos.floor();
+
+    let nw = terrain_height(heightmap, coord);
+    let ne = terrain_height(heightmap, coord + ivec2(1, 0));
+    let sw = terrain_height(heightmap, coord + ivec2(0, 1));
+    let se = terrain_height(heightmap, coord + ivec2(1, 1));
+
+    let gradient = vec2(
+        (ne - nw) * (1. - p.y) + (se - sw) * p.y,
+        (sw - nw) * (1. - p.x) + (se - ne) * p.x,
+    );
+    let height = nw * (1. - p.x) * (1. - p.y)
+        + ne * p.x * (1. - p.y)
+        + sw * (1. - p.x) * p.y
+        + se * p.x * p.y;
+    (gradient, height)
+}
+
+fn terrain_normal(heightmap: &Array3<f32>, cell: IVec2) -> Vec3 {
+    let height = terrain_height(heightmap, cell);
+
+    let height_up = terrain_height(heightmap, cell + ivec2(0, -1));
+    let height_down = terrain_height(heightmap, cell + ivec2(0, 1));
+    let height_left = terrain_height(heightmap, cell + ivec2(-1, 0));
+    let height_right = terrain_height(heightmap, cell + ivec2(1, 0));
+    let normal_up = vec3(0., -1., height_up - height);
+    let normal_down = vec3(0.0, 1.0, height_down - height);
+    let normal_left = vec3(-1.0, 0.0, height_left - height);
+    let normal_right = vec3(1.0, 0.0, height_right - height);
+    let a = normal_up.cross(normal_right);
+    let b = normal_down.cross(normal_left);
+    (a + b).normalize()
+}
+
+fn hardness(heightmap: &Array3<f32>, cell: IVec2, height: f32) -> f32 {
+    let hardness = load(heightmap, cell, HARDNESS);
+    let amount = load(heightmap, cell, HARDNESS_STRATA_AMOUNT);
+    let wavelength = load(heightmap, cell, HARDNESS_STRATA_WAVELENGTH);
+    // Texels that were never raised have no strata; don't divide by their zero wavelength
+    let strata = if wavelength > 0. {
+        (1. + (height * 2. * PI / wavelength).sin()) * 0.5
+    } else {
+        1.
+    };
+    smoothstep(0.4, 0.6, hardness * mix(1., strata, amount))
+}
+
+fn frame_direction(frame: i32) -> IVec2 {
+    match frame % 4 {
+        0 => ivec2(-1, 0),
+        1 => ivec2(1, 0),
+        2 => ivec2(0, -1),
+        3 => ivec2(0, 1),
+        _ => IVec2::ZERO,
+    }
+}
+
+fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a * (1. - t) + b * t
+}
+
+fn smoothstep(e0: f32, e1: f32, x: f32) -> f32 {
+    let t = ((x - e0) / (e1 - e0)).clamp(0., 1.);
+    t * t * (3. - 2. * t)
+}
+
+/// See `smoothstep_power` in `brush.wgsl`.
+fn smoothstep_power(e0: f32, e1: f32, x: f32, smoothness: f32) -> f32 {
+    let r: f32 = 6.643_856;
+    let z = 1. + (1. - smoothness).powf(r) * 1000.;
+    1. - (1. - smoothstep(e0, e1, x)).powf(z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{brushes::RaiseBrushParams, TERRAIN_LAYERS};
+
+    const SIZE: usize = 17;
+
+    fn test_heightmap() -> Array3<f32> {
+        Array3::from_shape_fn((TERRAIN_LAYERS as usize, SIZE, SIZE), |(layer, y, x)| {
+            let (fx, fy) = (x as f32, y as f32);
+            match layer {
+                ROCK => 20. + fx * 0.5 + (fy * 0.7).sin() * 3.,
+                SOIL if (x + y) % 3 == 0 => 0.,
+                SOIL => 0.5,
+                HARDNESS => 0.5,
+                HARDNESS_STRATA_AMOUNT => 0.3,
+                HARDNESS_STRATA_WAVELENGTH => 10.,
+                _ => 0.,
+            }
+        })
+    }
+    fn test_brush() -> BrushWGSL {
+        BrushWGSL {
+            center: vec2(8., 8.),
+            radius: 6.,
+            amplitude: 2.,
+            ..Default::default()
+        }
+    }
+    fn assert_golden(heightmap: &Array3<f32>, golden: &str) {
+        let golden: Array3<f32> = serde_json::from_str(golden).unwrap();
+        assert_eq!(heightmap.dim(), golden.dim());
+        for ((index, value), expected) in heightmap.indexed_iter().zip(golden.iter()) {
+            assert!(
+                (value - expected).abs() <= 1e-4 * expected.abs().max(1.),
+                "{index:?}: {value} != {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn raise_matches_golden() {
+        let noise = BrushNoise::from_image(&image::RgbaImage::from_fn(8, 8, |x, y| {
+            image::Rgba([((x * 37 + y * 91) % 256) as u8, 0, 0, 255])
+        }));
+        let mut heightmap = test_heightmap();
+        let config = RaiseBrushConfig {
+            params: RaiseBrushParams {
+                brush: test_brush(),
+                heightmap_world_texel_size: 1.,
+                ..Default::default()
+            },
+            seed: 3,
+        };
+        raise(&mut heightmap, &config, &noise);
+        assert_golden(&heightmap, include_str!("golden/raise.json"));
+    }
+
+    #[test]
+    fn flatten_matches_golden() {
+        let mut heightmap = test_heightmap();
+        let start_heights = heightmap.slice(s![.., 8, 8]).to_vec();
+        let params = FlattenBrushParams {
+            brush: test_brush(),
+            start_texel: glam::uvec2(8, 8),
+            heightmap_world_texel_size: 1.,
+            ..Default::default()
+        };
+        for _ in 0..10 {
+            flatten(&mut heightmap, &params, &start_heights);
+        }
+        assert_golden(&heightmap, include_str!("golden/flatten.json"));
+    }
+
+    #[test]
+    fn hydraulic_erosion_matches_golden() {
+        let mut heightmap = test_heightmap();
+        let mut config = HydraulicErosionConfig {
+            drop_radius: 2,
+            drops_per_m2: 0.1,
+            seed: 1,
+            brush_position: vec2(8., 8.),
+            brush_radius: 6.,
+            ..Default::default()
+        };
+        config.params.heightmap_size = ivec2(SIZE as i32, SIZE as i32);
+        config.params.border_size = 2;
+        hydraulic_erosion(&mut heightmap, &config);
+        assert_golden(&heightmap, include_str!("golden/hydraulic_erosion.json"));
+    }
+
+    #[test]
+    fn thermal_erosion_matches_golden() {
+        let mut heightmap = test_heightmap();
+        for frame in 0..8 {
+            let params = ThermalErosionParams {
+                heightmap_world_size: vec2(SIZE as f32, SIZE as f32),
+                heightmap_texture_size: ivec2(SIZE as i32, SIZE as i32),
+                brush_position: vec2(8., 8.),
+                brush_radius: 6.,
+                frame,
+                ..Default::default()
+            };
+            thermal_erosion(&mut heightmap, &params);
+        }
+        assert_golden(&heightmap, include_str!("golden/thermal_erosion.json"));
+    }
+
+    #[test]
+    fn water_sim_matches_golden() {
+        let mut heightmap = test_heightmap();
+        for frame in 0..8 {
+            water_sim(
+                &mut heightmap,
+                &WaterSimParams {
+                    frame,
+                    ..Default::default()
+                },
+            );
+        }
+        assert_golden(&heightmap, include_str!("golden/water_sim.json"));
+    }
+
+    #[test]
+    fn brushes_are_deterministic() {
+        let config = HydraulicErosionConfig {
+            seed: 7,
+            brush_position: vec2(8., 8.),
+            ..Default::default()
+        };
+        let run = || {
+            let mut heightmap = test_heightmap();
+            hydraulic_erosion(&mut heightmap, &config);
+            heightmap
+        };
+        assert_eq!(run(), run());
+    }
+}
@@ -25,6 +25,44 @@ pub struct HydraulicErosionConfig {
     pub brush_position: Vec2,
     pub brush_radius: f32,
 }
+impl HydraulicErosionConfig {
+    /// The offsets and normalized weights of the texels a droplet erodes around itself.
+    pub(crate) fn erosion_brush(&self) -> (Vec<IVec2>, Vec<f32>) {
+        let mut brush_positions = Vec::new();
+        let mut brush_weights = Vec::new();
+
+        let mut weight_sum = 0.;
+        for brush_y in (-self.drop_radius)..(self.drop_radius + 1) {
+            for brush_x in (-self.drop_radius)..(self.drop_radius + 1) {
+                let sqr_dst = brush_x * brush_x + brush_y * brush_y;
+                if sqr_dst < self.drop_radius * self.drop_radius {
+                    brush_positions.push(ivec2(brush_x, brush_y));
+                    let brush_weight = 1. - (sqr_dst as f32).sqrt() / self.drop_radius as f32;
+                    weight_sum += brush_weight;
+                    brush_weights.push(brush_weight);
+                }
+            }
+        }
+        for brush_weight in &mut brush_weights {
+            *brush_weight /= weight_sum;
+        }
+        (brush_positions, brush_weights)
+    }
+
+    /// The starting texels of the droplets for a heightmap of `size` texels.
+    pub(crate) fn droplet_positions(&self, size: UVec2) -> Vec<IVec2> {
+        let drops = (((size.x * size.y) as f32 * self.drops_per_m2) as usize).max(1);
+        let mut rng = Pcg64::seed_from_u64(self.seed);
+        (0..drops)
+            .map(|_| {
+                let r = self.brush_radius * rng.gen::<f32>().sqrt();
+                let theta = rng.gen::<f32>() * 2. * PI;
+                (vec2(theta.cos(), theta.sin()) * r + self.brush_position).as_ivec2()
+                // ivec2(rng.gen_range(0..(size.x as i32 - 1)), rng.gen_range(0..(size.y as i32 - 1)))
+            })
+            .collect_vec()
+    }
+}
 impl Default for HydraulicErosionConfig {
     fn default() -> Self {
         Self {
@@ -192,8 +230,6 @@ impl HydraulicErosionCompute {
         size: UVec2,
         config: &HydraulicErosionConfig,
     ) {
-        let drops = (((size.x * size.y) as f32 * config.drops_per_m2) as usize).max(1);
-
         let param_buffer = gpu
             .device
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -202,34 +238,9 @@ impl HydraulicErosionCompute {
                 usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             });
 
-        // Create brush
-        let mut brush_positions = Vec::new();
-        let mut brush_weights = Vec::new();
-
-        let mut weight_sum = 0.;
-        for brush_y in (-config.drop_radius)..(config.drop_radius + 1) {
-            for brush_x in (-config.drop_radius)..(config.drop_radius + 1) {
-                let sqr_dst = brush_x * brush_x + brush_y * brush_y;
-                if sqr_dst < config.drop_radius * config.drop_radius {
-                    brush_positions.push(ivec2(brush_x, brush_y));
-                    let brush_weight = 1. - (sqr_dst as f32).sqrt() / config.drop_radius as f32;
-                    weight_sum += brush_weight;
-                    brush_weights.push(brush_weight);
-                }
-            }
-        }
-        for brush_weight in &mut brush_weights {
-            *brush_weight /= weight_sum;
-        }
-        let mut rng = Pcg64::seed_from_u64(config.seed);
-        let random_positions = (0..drops)
-            .map(|_| {
-                let r = config.brush_radius * rng.gen::<f32>().sqrt();
-                let theta = rng.gen::<f32>() * 2. * PI;
-                (vec2(theta.cos(), theta.sin()) * r + config.brush_position).as_ivec2()
-                // ivec2(rng.gen_range(0..(size.x as i32 - 1)), rng.gen_range(0..(size.y as i32 - 1)))
-            })
-            .collect_vec();
+        let (brush_positions, brush_weights) = config.erosion_brush();
+        let random_positions = config.droplet_positions(size);
+        let drops = random_positions.len();
 
         let brush_positions = gpu
             .device
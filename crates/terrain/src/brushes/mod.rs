@@ -3,20 +3,29 @@ use std::sync::{atomic::AtomicI32, Arc};
 use ambient_app::gpu;
 use ambient_core::{asset_cache, frame_index, map_seed};
 use ambient_ecs::{EntityId, World};
-use ambient_gpu::{gpu::GpuKey, std_assets::PixelTextureViewKey, texture::Texture};
-use ambient_native_std::asset_cache::{AssetCache, AsyncAssetKey, SyncAssetKeyExt};
+use ambient_gpu::{
+    gpu::GpuKey, std_assets::PixelTextureViewKey, texture::Texture,
+    texture_loaders::Rgba8ImageFromUrl,
+};
+use ambient_native_std::asset_cache::{
+    AssetCache, AsyncAssetKey, AsyncAssetKeyExt, SyncAssetKeyExt,
+};
 use ambient_network::ServerWorldExt;
 use glam::{ivec2, IVec2, UVec2, Vec2, Vec3, Vec3Swizzles};
+use ndarray::s;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    gather_terrain_cells, spread_terrain_cells, TerrainSize, TerrainStateCpu, TERRAIN_LAYERS,
+    gather_terrain_cells, gather_terrain_cells_cpu, spread_terrain_cells, spread_terrain_cells_cpu,
+    TerrainSize, TerrainStateCpu, TERRAIN_LAYERS,
 };
 use crate::{
     get_terrain_cell, spawn_terrain, terrain_cell_needs_cpu_download, terrain_cell_version,
-    terrain_state,
+    terrain_state, terrain_state_cpu,
 };
+use cpu::BrushNoise;
 
+pub mod cpu;
 mod flatten;
 mod hydraulic_erosion;
 mod init;
@@ -156,6 +165,81 @@ impl TerrainBrushStroke {
         }
         true
     }
+    /// The heightmap spanning all of the cells this stroke touches.
+    fn region(&self) -> BrushRegion {
+        let terrain = TerrainSize::new();
+        let (top_left_cell, bottom_right_cell) = self.get_brush_cells();
+        let mut cells_size = bottom_right_cell - top_left_cell;
+        cells_size.x = cells_size.x.max(1);
+        cells_size.y = cells_size.y.max(1);
+        let cells_size = cells_size.as_uvec2();
+        let texture_size = cells_size * (terrain.texture_size() - 1) as u32 + 1;
+        let world_size = cells_size.as_vec2() * terrain.size_in_meters();
+        BrushRegion {
+            top_left_cell,
+            cells_size,
+            texture_size,
+            world_position: top_left_cell.as_vec2() * terrain.size_in_meters(),
+            world_size,
+            world_texel_size: world_size.x / (texture_size.x as f32 - 1.),
+        }
+    }
+    fn brush_wgsl(&self, amplitude: f32) -> BrushWGSL {
+        BrushWGSL {
+            center: self.center,
+            radius: self.brush_size.radius(),
+            shape: self.brush_shape,
+            amplitude,
+            smoothness: self.brush_smoothness.0,
+            _padding: Default::default(),
+        }
+    }
+    fn raise_params(&self, region: &BrushRegion) -> RaiseBrushParams {
+        let amount = self.brush_strength.strength();
+        RaiseBrushParams {
+            heightmap_world_position: region.world_position,
+            heightmap_world_texel_size: region.world_texel_size,
+            brush: self.brush_wgsl(if self.brush == Brush::Raise {
+                amount
+            } else {
+                -amount
+            }),
+            layer: self.layer as i32,
+            ..Default::default()
+        }
+    }
+    fn flatten_params(&self, region: &BrushRegion, start_texel: UVec2) -> FlattenBrushParams {
+        FlattenBrushParams {
+            heightmap_world_position: region.world_position,
+            heightmap_world_texel_size: region.world_texel_size,
+            brush: self.brush_wgsl(self.brush_strength.strength()),
+            start_texel,
+            _padding: Default::default(),
+        }
+    }
+    fn erosion_config(&self, region: &BrushRegion) -> HydraulicErosionConfig {
+        let mut config = self.erosion.clone();
+        // config.drops_per_m2 = match brush_strength {
+        //     BrushStrength::Small => 0.01,
+        //     BrushStrength::Medium => 0.1,
+        //     BrushStrength::Large => 1.4,
+        // };
+        config.params.heightmap_size = region.texture_size.as_ivec2();
+        config.brush_radius = self.brush_size.radius();
+        config.brush_position = self.center - region.world_position;
+        config
+    }
+    fn thermal_params(&self, region: &BrushRegion, frame: i32) -> ThermalErosionParams {
+        ThermalErosionParams {
+            heightmap_world_position: region.world_position,
+            heightmap_world_size: region.world_size,
+            heightmap_texture_size: region.texture_size.as_ivec2(),
+            brush_position: self.center,
+            brush_radius: self.brush_size.radius(),
+            frame,
+            _padding: Default::default(),
+        }
+    }
     pub fn initial_island() -> Self {
         Self {
             center: Vec2::ZERO,
@@ -171,6 +255,17 @@ impl TerrainBrushStroke {
     }
 }
 
+/// The heightmap a stroke is applied to, made up of all of the terrain cells it touches.
+#[derive(Clone, Copy, Debug)]
+struct BrushRegion {
+    top_left_cell: IVec2,
+    cells_size: UVec2,
+    texture_size: UVec2,
+    world_position: Vec2,
+    world_size: Vec2,
+    world_texel_size: f32,
+}
+
 #[derive(Clone, Debug)]
 pub struct TerrainBrushKey;
 #[async_trait]
@@ -244,30 +339,17 @@ impl TerrainBrush {
         let map_globals = world.persisted_resource_entity().unwrap();
         let seed = world.get(map_globals, map_seed()).unwrap();
 
-        let (top_left_cell, bottom_right_cell) = stroke.get_brush_cells();
-        let TerrainBrushStroke {
-            center,
-            layer,
-            brush,
-            brush_size,
-            brush_strength,
-            brush_smoothness,
-            brush_shape,
-            start_position,
-            erosion,
-        } = stroke;
+        let region = stroke.region();
+        let BrushRegion {
+            top_left_cell,
+            cells_size,
+            texture_size,
+            ..
+        } = region;
         let gpu = world.resource(gpu()).clone();
         let mut encoder = gpu
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
-        let terrain = TerrainSize::new();
-        let mut cells_size = bottom_right_cell - top_left_cell;
-        cells_size.x = cells_size.x.max(1);
-        cells_size.y = cells_size.y.max(1);
-        let cells_size = cells_size.as_uvec2();
-        let texture_size = cells_size * (terrain.texture_size() - 1) as u32 + 1;
-        let heightmap_world_size = cells_size.as_vec2() * terrain.size_in_meters();
-        let heightmap_world_texel_size = heightmap_world_size.x / (texture_size.x as f32 - 1.);
         gather_terrain_cells(
             world,
             &mut encoder,
@@ -275,33 +357,17 @@ impl TerrainBrush {
             top_left_cell,
             cells_size,
         );
-        match brush {
+        match stroke.brush {
             Brush::Raise | Brush::Lower => {
-                let amount = brush_strength.strength();
-                let params = RaiseBrushParams {
-                    heightmap_world_position: top_left_cell.as_vec2() * terrain.size_in_meters(),
-                    heightmap_world_texel_size,
-                    brush: BrushWGSL {
-                        center,
-                        radius: brush_size.radius(),
-                        shape: brush_shape,
-                        amplitude: if brush == Brush::Raise {
-                            amount
-                        } else {
-                            -amount
-                        },
-                        smoothness: brush_smoothness.0,
-                        _padding: Default::default(),
-                    },
-                    layer: layer as i32,
-                    ..Default::default()
-                };
                 self.brush_raise_lower.run(
                     &gpu,
                     &mut encoder,
                     &self.intermediate_heightmap.create_view(&Default::default()),
                     texture_size,
-                    &RaiseBrushConfig { params, seed },
+                    &RaiseBrushConfig {
+                        params: stroke.raise_params(&region),
+                        seed,
+                    },
                 );
             }
             Brush::Flatten => {
@@ -309,53 +375,30 @@ impl TerrainBrush {
                 let mut start_heightmap =
                     PixelTextureViewKey::white().get(world.resource(asset_cache()));
                 let (start_cell, start_texel) =
-                    TerrainSize::new().cell_and_texel_from_position(start_position.xy());
+                    TerrainSize::new().cell_and_texel_from_position(stroke.start_position.xy());
                 if let Some(id) = get_terrain_cell(world, start_cell) {
                     if let Ok(state) = world.get_ref(id, terrain_state()) {
                         start_heightmap =
                             Arc::new(state.heightmap.create_view(&Default::default()));
                     }
                 }
-                let params = FlattenBrushParams {
-                    heightmap_world_position: top_left_cell.as_vec2() * terrain.size_in_meters(),
-                    heightmap_world_texel_size,
-                    brush: BrushWGSL {
-                        center,
-                        radius: brush_size.radius(),
-                        shape: brush_shape,
-                        amplitude: brush_strength.strength(),
-                        smoothness: brush_smoothness.0,
-                        _padding: Default::default(),
-                    },
-                    start_texel,
-                    _padding: Default::default(),
-                };
                 brush.run(
                     &gpu,
                     &mut encoder,
                     &self.intermediate_heightmap.create_view(&Default::default()),
                     &start_heightmap,
                     texture_size,
-                    &params,
+                    &stroke.flatten_params(&region, start_texel),
                 );
             }
             Brush::Erode => {
-                let mut config = erosion;
-                // config.drops_per_m2 = match brush_strength {
-                //     BrushStrength::Small => 0.01,
-                //     BrushStrength::Medium => 0.1,
-                //     BrushStrength::Large => 1.4,
-                // };
-                config.params.heightmap_size = texture_size.as_ivec2();
-                config.brush_radius = brush_size.radius();
-                config.brush_position = center - top_left_cell.as_vec2() * terrain.size_in_meters();
                 let brush = HydraulicErosionCompute::new(&gpu);
                 brush.run(
                     &gpu,
                     &mut encoder,
                     &self.intermediate_heightmap.create_view(&Default::default()),
                     texture_size,
-                    &config,
+                    &stroke.erosion_config(&region),
                 );
 
                 // for i in 0..4 {
@@ -388,16 +431,10 @@ impl TerrainBrush {
             Brush::Thermal => {
                 let brush = ThermalErosionCompute::new(&gpu);
                 let config = ThermalErosionConfig {
-                    params: ThermalErosionParams {
-                        heightmap_world_position: top_left_cell.as_vec2()
-                            * terrain.size_in_meters(),
-                        heightmap_world_size: cells_size.as_vec2() * terrain.size_in_meters(),
-                        heightmap_texture_size: texture_size.as_ivec2(),
-                        brush_position: center,
-                        brush_radius: brush_size.radius(),
-                        frame: self.frame.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
-                        _padding: Default::default(),
-                    },
+                    params: stroke.thermal_params(
+                        &region,
+                        self.frame.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
+                    ),
                 };
                 brush.run(
                     &gpu,
@@ -435,3 +472,108 @@ impl TerrainBrush {
         changed_cells
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct TerrainBrushCpuKey;
+#[async_trait]
+impl AsyncAssetKey<Arc<TerrainBrushCpu>> for TerrainBrushCpuKey {
+    async fn load(self, assets: AssetCache) -> Arc<TerrainBrushCpu> {
+        let noise = match (Rgba8ImageFromUrl {
+            url: noise_texture_url(),
+        })
+        .get(&assets)
+        .await
+        {
+            Ok(image) => BrushNoise::from_image(&image),
+            Err(err) => {
+                tracing::warn!("Failed to load the terrain brush noise, using a flat one: {err:?}");
+                BrushNoise::constant(0.5)
+            }
+        };
+        Arc::new(TerrainBrushCpu::new(noise))
+    }
+}
+
+/// Applies strokes like [TerrainBrush], but on the CPU state of the terrain cells, so that it can
+/// be used without a GPU. See [cpu] for how the results relate to the GPU brushes.
+#[derive(Debug)]
+pub struct TerrainBrushCpu {
+    noise: BrushNoise,
+    frame: AtomicI32,
+}
+
+impl TerrainBrushCpu {
+    pub fn new(noise: BrushNoise) -> Self {
+        Self {
+            noise,
+            frame: AtomicI32::new(0),
+        }
+    }
+    #[profiling::function]
+    pub fn apply(&self, world: &mut World, stroke: TerrainBrushStroke) -> Vec<EntityId> {
+        let map_globals = world.persisted_resource_entity().unwrap();
+        let seed = world.get(map_globals, map_seed()).unwrap();
+
+        let region = stroke.region();
+        let mut heightmap =
+            gather_terrain_cells_cpu(world, region.top_left_cell, region.cells_size);
+        match stroke.brush {
+            Brush::Raise | Brush::Lower => cpu::raise(
+                &mut heightmap,
+                &RaiseBrushConfig {
+                    params: stroke.raise_params(&region),
+                    seed,
+                },
+                &self.noise,
+            ),
+            Brush::Flatten => {
+                let (start_cell, start_texel) =
+                    TerrainSize::new().cell_and_texel_from_position(stroke.start_position.xy());
+                // Like the GPU brush, fall back to a white texel if the stroke started outside of the terrain
+                let start_heights = get_terrain_cell(world, start_cell)
+                    .and_then(|id| world.get_ref(id, terrain_state_cpu()).ok())
+                    .and_then(|state| {
+                        let (_, height, width) = state.heightmap.dim();
+                        let (x, y) = (start_texel.x as usize, start_texel.y as usize);
+                        (x < width && y < height)
+                            .then(|| state.heightmap.slice(s![.., y, x]).to_vec())
+                    })
+                    .unwrap_or_else(|| vec![1.; TERRAIN_LAYERS as usize]);
+                cpu::flatten(
+                    &mut heightmap,
+                    &stroke.flatten_params(&region, start_texel),
+                    &start_heights,
+                );
+            }
+            Brush::Erode => cpu::hydraulic_erosion(&mut heightmap, &stroke.erosion_config(&region)),
+            Brush::Erode2 => {
+                let mut config = WaterSimConfig::default();
+                // The server has no frame index, so count strokes like the thermal brush does
+                config.params.frame = self.frame.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                cpu::water_sim(&mut heightmap, &config.params);
+            }
+            Brush::Thermal => cpu::thermal_erosion(
+                &mut heightmap,
+                &stroke.thermal_params(
+                    &region,
+                    self.frame.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
+                ),
+            ),
+        }
+        let normalmap = cpu::normalmap(&heightmap);
+        let changed_cells = spread_terrain_cells_cpu(
+            world,
+            &heightmap,
+            &normalmap,
+            region.top_left_cell,
+            region.cells_size,
+        );
+        for id in &changed_cells {
+            if let Ok(version) = world.get_mut(*id, terrain_cell_version()) {
+                *version += 1;
+            }
+        }
+
+        changed_cells
+    }
+}
@@ -53,6 +53,22 @@ impl Default for RaiseBrushParams {
     }
 }
 
+pub(crate) fn noise_texture_url() -> AbsAssetUrl {
+    AbsAssetUrl::from_str(&format!(
+        "{OLD_CONTENT_SERVER_URL}assets/models/{}",
+        "ArtStationSurfaces/VFX-HQ-Seamless-Noise-Pack-Vol1/Noise_002.png"
+    ))
+    .unwrap()
+}
+
+/// Random offsets into the noise texture, derived from the map seed.
+pub(crate) fn noise_offsets(seed: u64) -> Vec<Vec2> {
+    let mut rng = Pcg64::seed_from_u64(seed);
+    (0..12)
+        .map(|_| vec2(rng.gen::<f32>() * 1000., rng.gen::<f32>() * 1000.))
+        .collect_vec()
+}
+
 #[derive(Debug)]
 pub struct RaiseBrush {
     pipeline: wgpu::ComputePipeline,
@@ -149,11 +165,7 @@ impl RaiseBrush {
         Self {
             pipeline,
             noise_texture: TextureFromUrl {
-                url: AbsAssetUrl::from_str(&format!(
-                    "{OLD_CONTENT_SERVER_URL}assets/models/{}",
-                    "ArtStationSurfaces/VFX-HQ-Seamless-Noise-Pack-Vol1/Noise_002.png"
-                ))
-                .unwrap(),
+                url: noise_texture_url(),
                 format: wgpu::TextureFormat::Rgba8Unorm,
             }
             .get(&assets)
@@ -178,11 +190,7 @@ impl RaiseBrush {
                 usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             });
 
-        let mut rng = Pcg64::seed_from_u64(config.seed);
-        let offsets = (0..12)
-            .map(|_| vec2(rng.gen::<f32>() * 1000., rng.gen::<f32>() * 1000.))
-            .collect_vec();
-
+        let offsets = noise_offsets(config.seed);
         let offsets = gpu
             .device
             .create_buffer_init(&wgpu::util::BufferInitDescriptor {
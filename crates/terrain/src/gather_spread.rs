@@ -1,10 +1,16 @@
+use std::sync::Arc;
+
 use ambient_app::gpu;
 use ambient_core::asset_cache;
 use ambient_ecs::{EntityId, World};
 use ambient_gpu::texture::Texture;
 use glam::{ivec2, IVec2, UVec2};
+use ndarray::{s, Array3};
 
-use crate::{get_terrain_cell, terrain_state};
+use crate::{
+    get_terrain_cell, terrain_state, terrain_state_cpu, TerrainSize, TerrainStateCpu,
+    TERRAIN_LAYERS,
+};
 
 pub fn gather_terrain_cells(
     world: &World,
@@ -17,6 +23,9 @@ pub fn gather_terrain_cells(
         for x in 0..cells.x as i32 {
             if let Some(id) = get_terrain_cell(world, top_left_cell + ivec2(x, y)) {
                 if let Ok(state) = world.get_ref(id, terrain_state()) {
+                    if state.size.downsample != 0 {
+                        continue;
+                    }
                     encoder.copy_texture_to_texture(
                         wgpu::ImageCopyTexture {
                             texture: &state.heightmap.handle,
@@ -57,6 +66,9 @@ pub fn spread_terrain_cells(
         for x in 0..cells.x as i32 {
             if let Some(id) = get_terrain_cell(world, top_left_cell + ivec2(x, y)) {
                 if let Ok(state) = world.get_ref(id, terrain_state()) {
+                    if state.size.downsample != 0 {
+                        continue;
+                    }
                     encoder.copy_texture_to_texture(
                         wgpu::ImageCopyTexture {
                             texture: &heightmap.handle,
@@ -115,3 +127,91 @@ pub fn spread_terrain_cells(
     }
     changed_cells
 }
+
+/// Copies the [terrain_state_cpu] of `cells` cells starting at `top_left_cell` into one heightmap,
+/// like [gather_terrain_cells]. Missing cells are left at zero.
+pub fn gather_terrain_cells_cpu(world: &World, top_left_cell: IVec2, cells: UVec2) -> Array3<f32> {
+    gather_terrain_states_cpu(top_left_cell, cells, |cell| {
+        let id = get_terrain_cell(world, cell)?;
+        world.get_ref(id, terrain_state_cpu()).ok().cloned()
+    })
+}
+
+/// Like [gather_terrain_cells_cpu], but with the state of each cell returned by `state`, e.g. from a
+/// [crate::streaming::TerrainCellStorage].
+pub(crate) fn gather_terrain_states_cpu(
+    top_left_cell: IVec2,
+    cells: UVec2,
+    mut state: impl FnMut(IVec2) -> Option<Arc<TerrainStateCpu>>,
+) -> Array3<f32> {
+    let texture_size = TerrainSize::new().texture_size();
+    let size = cells * (texture_size - 1) as u32 + 1;
+    let mut heightmap = Array3::zeros((TERRAIN_LAYERS as usize, size.y as usize, size.x as usize));
+    for y in 0..cells.y as usize {
+        for x in 0..cells.x as usize {
+            let cell = top_left_cell + ivec2(x as i32, y as i32);
+            if let Some(state) = state(cell) {
+                if state.size.downsample != 0 {
+                    continue;
+                }
+                let (ox, oy) = (x * (texture_size - 1), y * (texture_size - 1));
+                heightmap
+                    .slice_mut(s![.., oy..oy + texture_size, ox..ox + texture_size])
+                    .assign(&state.heightmap);
+            }
+        }
+    }
+    heightmap
+}
+
+/// Writes `heightmap` and `normalmap` back into the [terrain_state_cpu] of the cells they were
+/// gathered from, like [spread_terrain_cells]. Returns the cells that were changed.
+pub fn spread_terrain_cells_cpu(
+    world: &mut World,
+    heightmap: &Array3<f32>,
+    normalmap: &Array3<f32>,
+    top_left_cell: IVec2,
+    cells: UVec2,
+) -> Vec<EntityId> {
+    let mut changed_cells = Vec::new();
+    for y in 0..cells.y as usize {
+        for x in 0..cells.x as usize {
+            let cell = top_left_cell + ivec2(x as i32, y as i32);
+            let Some(id) = get_terrain_cell(world, cell) else {
+                continue;
+            };
+            let Ok(state) = world.get_ref(id, terrain_state_cpu()) else {
+                continue;
+            };
+            if state.size.downsample != 0 {
+                continue;
+            }
+            let texture_size = state.size.texture_size();
+            let (ox, oy) = (x * (texture_size - 1), y * (texture_size - 1));
+            let mut state = TerrainStateCpu::clone(state);
+            state.heightmap.assign(&heightmap.slice(s![
+                ..,
+                oy..oy + texture_size,
+                ox..ox + texture_size
+            ]));
+
+            // Note: we're not copying the border cells, because those normals will be wrong
+            let lx = if x == 0 { 1 } else { 0 };
+            let ly = if y == 0 { 1 } else { 0 };
+            let rx = if x >= cells.x as usize - 1 { 1 } else { 0 };
+            let ry = if y >= cells.y as usize - 1 { 1 } else { 0 };
+            state
+                .normalmap
+                .slice_mut(s![ly..texture_size - ry, lx..texture_size - rx, ..])
+                .assign(&normalmap.slice(s![
+                    oy + ly..oy + texture_size - ry,
+                    ox + lx..ox + texture_size - rx,
+                    ..
+                ]));
+
+            world.set(id, terrain_state_cpu(), Arc::new(state)).unwrap();
+            changed_cells.push(id);
+        }
+    }
+    changed_cells
+}
@@ -0,0 +1,357 @@
+use std::io::Cursor;
+
+use anyhow::{bail, Context};
+use image::{DynamicImage, ImageBuffer, ImageOutputFormat, Luma, Rgba};
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use tiff::{
+    decoder::{Decoder, DecodingResult},
+    encoder::{colortype, TiffEncoder},
+    tags::Tag,
+    ColorType,
+};
+
+/// The file formats heightmaps can be imported from and exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeightmapFormat {
+    /// A grayscale PNG. Exported with 16 bits per texel.
+    Png16,
+    /// Headerless little endian 16 bit unsigned integers, row by row.
+    RawR16 { width: u32, height: u32 },
+    /// Headerless little endian 32 bit floats, row by row, in meters.
+    RawR32 { width: u32, height: u32 },
+    /// A single channel GeoTIFF. Unsigned integer samples are normalized, signed integer and float samples are
+    /// in meters. Exported with 32 bit float samples.
+    GeoTiff,
+}
+impl HeightmapFormat {
+    /// Whether this format is exported as normalized integers, which are mapped to [HeightmapScale::min_height]
+    /// and [HeightmapScale::max_height], rather than meters.
+    pub fn is_normalized(&self) -> bool {
+        matches!(self, Self::Png16 | Self::RawR16 { .. })
+    }
+}
+
+/// How the texels of a heightmap file map to meters.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeightmapScale {
+    /// The distance between two texels, in meters.
+    pub texel_size: f32,
+    /// The height of the lowest value of normalized formats.
+    pub min_height: f32,
+    /// The height of the highest value of normalized formats.
+    pub max_height: f32,
+}
+impl Default for HeightmapScale {
+    fn default() -> Self {
+        Self {
+            texel_size: 1.,
+            min_height: 0.,
+            max_height: 256.,
+        }
+    }
+}
+impl HeightmapScale {
+    fn denormalize(&self, value: f32) -> f32 {
+        self.min_height + value * (self.max_height - self.min_height)
+    }
+    fn normalize(&self, height: f32) -> f32 {
+        let range = self.max_height - self.min_height;
+        if range == 0. {
+            0.
+        } else {
+            ((height - self.min_height) / range).clamp(0., 1.)
+        }
+    }
+}
+
+/// Decodes a heightmap into heights in meters, indexed by `(y, x)`. Texels without data, such as the
+/// `GDAL_NODATA` texels of a GeoTIFF, are `NaN`.
+pub fn decode_heightmap(
+    data: &[u8],
+    format: HeightmapFormat,
+    scale: &HeightmapScale,
+) -> anyhow::Result<Array2<f32>> {
+    match format {
+        HeightmapFormat::Png16 => {
+            let image = image::load_from_memory_with_format(data, image::ImageFormat::Png)
+                .context("Failed to decode PNG heightmap")?
+                .into_luma16();
+            let (width, height) = image.dimensions();
+            Ok(Array2::from_shape_fn(
+                (height as usize, width as usize),
+                |(y, x)| {
+                    scale.denormalize(
+                        image.get_pixel(x as u32, y as u32)[0] as f32 / u16::MAX as f32,
+                    )
+                },
+            ))
+        }
+        HeightmapFormat::RawR16 { width, height } => {
+            let texels = raw_texels::<2>(data, width, height)?
+                .map(|bytes| scale.denormalize(u16::from_le_bytes(bytes) as f32 / u16::MAX as f32))
+                .collect();
+            Ok(Array2::from_shape_vec(
+                (height as usize, width as usize),
+                texels,
+            )?)
+        }
+        HeightmapFormat::RawR32 { width, height } => {
+            let texels = raw_texels::<4>(data, width, height)?
+                .map(f32::from_le_bytes)
+                .collect();
+            Ok(Array2::from_shape_vec(
+                (height as usize, width as usize),
+                texels,
+            )?)
+        }
+        HeightmapFormat::GeoTiff => decode_geotiff(data, scale),
+    }
+}
+
+/// Encodes heights in meters, indexed by `(y, x)`, into a heightmap. Normalized formats clamp heights to the
+/// range of `scale`.
+pub fn encode_heightmap(
+    heights: &Array2<f32>,
+    format: HeightmapFormat,
+    scale: &HeightmapScale,
+) -> anyhow::Result<Vec<u8>> {
+    let (height, width) = heights.dim();
+    let quantize = |h: &f32| (scale.normalize(*h) * u16::MAX as f32).round() as u16;
+    match format {
+        HeightmapFormat::Png16 => {
+            let image: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_raw(
+                width as u32,
+                height as u32,
+                heights.iter().map(quantize).collect(),
+            )
+            .context("Heightmap has an invalid shape")?;
+            let mut data = Vec::new();
+            DynamicImage::ImageLuma16(image)
+                .write_to(&mut Cursor::new(&mut data), ImageOutputFormat::Png)?;
+            Ok(data)
+        }
+        HeightmapFormat::RawR16 { .. } => Ok(heights
+            .iter()
+            .flat_map(|h| quantize(h).to_le_bytes())
+            .collect()),
+        HeightmapFormat::RawR32 { .. } => {
+            Ok(heights.iter().flat_map(|h| h.to_le_bytes()).collect())
+        }
+        HeightmapFormat::GeoTiff => {
+            let mut data = Vec::new();
+            let mut encoder = TiffEncoder::new(Cursor::new(&mut data))?;
+            let mut image =
+                encoder.new_image::<colortype::Gray32Float>(width as u32, height as u32)?;
+            let texel_size = scale.texel_size as f64;
+            image
+                .encoder()
+                .write_tag(Tag::ModelPixelScaleTag, &[texel_size, texel_size, 0.][..])?;
+            image
+                .encoder()
+                .write_tag(Tag::ModelTiepointTag, &[0f64; 6][..])?;
+            // GTModelTypeGeoKey: projected, GTRasterTypeGeoKey: pixel is point
+            image.encoder().write_tag(
+                Tag::GeoKeyDirectoryTag,
+                &[1u16, 1, 0, 2, 1024, 0, 1, 1, 1025, 0, 1, 2][..],
+            )?;
+            image.write_data(&heights.iter().copied().collect::<Vec<_>>())?;
+            Ok(data)
+        }
+    }
+}
+
+/// Decodes every channel of a splat map (any format the `image` crate can read, such as an RGBA PNG) into
+/// values between `0` and `1`, indexed by `(y, x)`.
+pub fn decode_splat_map(data: &[u8]) -> anyhow::Result<Vec<Array2<f32>>> {
+    let image = image::load_from_memory(data).context("Failed to decode splat map")?;
+    let channels = image.color().channel_count() as usize;
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let (samples, stride) = if channels <= 2 {
+        (image.into_luma_alpha16().into_raw(), 2)
+    } else {
+        (image.into_rgba16().into_raw(), 4)
+    };
+    Ok((0..channels)
+        .map(|channel| {
+            Array2::from_shape_fn((height, width), |(y, x)| {
+                samples[(y * width + x) * stride + channel] as f32 / u16::MAX as f32
+            })
+        })
+        .collect())
+}
+
+/// Encodes up to four channels of values between `0` and `1`, indexed by `(y, x)`, into a 16 bit RGBA PNG
+/// splat map. Missing channels are `0`, except for alpha which is `1`.
+pub fn encode_splat_map(channels: &[Array2<f32>]) -> anyhow::Result<Vec<u8>> {
+    let Some(first) = channels.first() else {
+        bail!("A splat map needs at least one channel");
+    };
+    if channels.len() > 4 {
+        bail!(
+            "A splat map can have at most 4 channels, got {}",
+            channels.len()
+        );
+    }
+    let (height, width) = first.dim();
+    if channels
+        .iter()
+        .any(|channel| channel.dim() != (height, width))
+    {
+        bail!("All channels of a splat map must have the same size");
+    }
+    let image = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
+        let mut texel = [0, 0, 0, u16::MAX];
+        for (value, channel) in texel.iter_mut().zip(channels) {
+            *value =
+                (channel[(y as usize, x as usize)].clamp(0., 1.) * u16::MAX as f32).round() as u16;
+        }
+        Rgba(texel)
+    });
+    let mut data = Vec::new();
+    DynamicImage::ImageRgba16(image)
+        .write_to(&mut Cursor::new(&mut data), ImageOutputFormat::Png)?;
+    Ok(data)
+}
+
+fn raw_texels<const N: usize>(
+    data: &[u8],
+    width: u32,
+    height: u32,
+) -> anyhow::Result<impl Iterator<Item = [u8; N]> + '_> {
+    let expected = width as usize * height as usize * N;
+    if data.len() != expected {
+        bail!(
+            "Expected {expected} bytes for a {width}x{height} heightmap with {N} bytes per texel, got {}",
+            data.len()
+        );
+    }
+    Ok(data.chunks_exact(N).map(|bytes| bytes.try_into().unwrap()))
+}
+
+fn decode_geotiff(data: &[u8], scale: &HeightmapScale) -> anyhow::Result<Array2<f32>> {
+    let mut decoder = Decoder::new(Cursor::new(data)).context("Failed to decode GeoTIFF")?;
+    if !matches!(decoder.colortype()?, ColorType::Gray(_)) {
+        bail!("Only single channel GeoTIFF heightmaps are supported");
+    }
+    let (width, height) = decoder.dimensions()?;
+    let no_data = decoder
+        .find_tag(Tag::GdalNodata)?
+        .and_then(|value| value.into_string().ok())
+        .and_then(|value| value.trim_end_matches('\0').trim().parse::<f64>().ok());
+
+    let texels: Vec<(f64, f32)> = match decoder.read_image()? {
+        DecodingResult::U8(t) => t
+            .into_iter()
+            .map(|t| (t as f64, scale.denormalize(t as f32 / u8::MAX as f32)))
+            .collect(),
+        DecodingResult::U16(t) => t
+            .into_iter()
+            .map(|t| (t as f64, scale.denormalize(t as f32 / u16::MAX as f32)))
+            .collect(),
+        DecodingResult::I16(t) => t.into_iter().map(|t| (t as f64, t as f32)).collect(),
+        DecodingResult::I32(t) => t.into_iter().map(|t| (t as f64, t as f32)).collect(),
+        DecodingResult::F32(t) => t.into_iter().map(|t| (t as f64, t)).collect(),
+        DecodingResult::F64(t) => t.into_iter().map(|t| (t, t as f32)).collect(),
+        _ => bail!("Unsupported GeoTIFF sample format"),
+    };
+    let texels = texels
+        .into_iter()
+        .map(|(raw, height)| {
+            if Some(raw) == no_data {
+                f32::NAN
+            } else {
+                height
+            }
+        })
+        .collect();
+    Ok(Array2::from_shape_vec(
+        (height as usize, width as usize),
+        texels,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_heights() -> Array2<f32> {
+        Array2::from_shape_fn((5, 7), |(y, x)| x as f32 * 10. + y as f32 * 3.5)
+    }
+
+    #[test]
+    fn heightmap_round_trips() {
+        let heights = test_heights();
+        let scale = HeightmapScale {
+            texel_size: 2.,
+            min_height: 0.,
+            max_height: 100.,
+        };
+        for format in [
+            HeightmapFormat::Png16,
+            HeightmapFormat::RawR16 {
+                width: 7,
+                height: 5,
+            },
+            HeightmapFormat::RawR32 {
+                width: 7,
+                height: 5,
+            },
+            HeightmapFormat::GeoTiff,
+        ] {
+            let data = encode_heightmap(&heights, format, &scale).unwrap();
+            let decoded = decode_heightmap(&data, format, &scale).unwrap();
+            assert_eq!(decoded.dim(), heights.dim(), "{format:?}");
+            let tolerance = if format.is_normalized() { 0.01 } else { 0. };
+            for (a, b) in decoded.iter().zip(heights.iter()) {
+                assert!((a - b).abs() <= tolerance, "{format:?}: {a} != {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn normalized_formats_clamp_to_scale() {
+        let heights = Array2::from_shape_vec((1, 3), vec![-10., 50., 300.]).unwrap();
+        let scale = HeightmapScale {
+            min_height: 0.,
+            max_height: 100.,
+            ..Default::default()
+        };
+        let format = HeightmapFormat::RawR16 {
+            width: 3,
+            height: 1,
+        };
+        let data = encode_heightmap(&heights, format, &scale).unwrap();
+        let decoded = decode_heightmap(&data, format, &scale).unwrap();
+        assert_eq!(decoded[(0, 0)], 0.);
+        assert!((decoded[(0, 1)] - 50.).abs() < 0.01);
+        assert_eq!(decoded[(0, 2)], 100.);
+    }
+
+    #[test]
+    fn raw_heightmap_with_wrong_size_fails() {
+        let format = HeightmapFormat::RawR32 {
+            width: 4,
+            height: 4,
+        };
+        assert!(decode_heightmap(&[0; 12], format, &Default::default()).is_err());
+    }
+
+    #[test]
+    fn splat_map_round_trips() {
+        let channels = vec![
+            Array2::from_shape_fn((4, 3), |(y, x)| (x + y) as f32 / 6.),
+            Array2::from_elem((4, 3), 0.25),
+            Array2::from_elem((4, 3), 1.),
+        ];
+        let data = encode_splat_map(&channels).unwrap();
+        let decoded = decode_splat_map(&data).unwrap();
+        assert_eq!(decoded.len(), 4);
+        for (a, b) in decoded.iter().zip(&channels) {
+            for (a, b) in a.iter().zip(b.iter()) {
+                assert!((a - b).abs() < 0.001);
+            }
+        }
+        assert!(decoded[3].iter().all(|a| *a == 1.));
+    }
+}
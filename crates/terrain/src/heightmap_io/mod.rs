@@ -0,0 +1,321 @@
+//! Importing terrain from heightmaps and splat maps made in external tools, and exporting it back.
+//!
+//! Files are decoded to and encoded from arrays of meters (or layer values) with the functions in [format],
+//! and these arrays are then written to or read from the [TerrainStateCpu] of the terrain cells, either in a
+//! world or in a [TerrainCellStorage] directory (like the `--terrain-dir` of a server).
+//!
+//! The height of the terrain is the sum of its rock and soil layers, on top of [TERRAIN_BASE].
+
+use std::{collections::HashMap, sync::Arc};
+
+use ambient_ecs::{EntityId, World};
+use glam::{ivec2, vec2, IVec2, UVec2, Vec2};
+use ndarray::{s, Array2, Array3, ArrayViewMut1, Axis};
+
+use crate::{
+    brushes::cpu, gather_terrain_cells_cpu, gather_terrain_states_cpu, get_terrain_cell,
+    spawn_terrain, streaming::TerrainCellStorage, terrain_cell_version, terrain_state_cpu,
+    TerrainLayers, TerrainSize, TerrainStateCpu, TERRAIN_BASE,
+};
+
+mod format;
+pub use format::*;
+
+/// Imports heights in meters, such as those from [decode_heightmap], into the terrain cells they cover, spawning
+/// any cells that are missing. `position` is the world position of the first texel of `heights`, and `texel_size`
+/// the distance between its texels. The heights replace the rock layer, and the soil layer is cleared, so that
+/// [export_heightmap] returns them again. Returns the changed cells.
+pub fn import_heightmap(
+    world: &mut World,
+    heights: &Array2<f32>,
+    position: Vec2,
+    texel_size: f32,
+) -> Vec<EntityId> {
+    import_map(world, heights, position, texel_size, write_height)
+}
+
+/// Imports the values of one of the [TerrainLayers], such as a channel from [decode_splat_map] mapped to the range
+/// of the layer, like [import_heightmap].
+pub fn import_layer_map(
+    world: &mut World,
+    layer: TerrainLayers,
+    values: &Array2<f32>,
+    position: Vec2,
+    texel_size: f32,
+) -> Vec<EntityId> {
+    import_map(world, values, position, texel_size, |layers, value| {
+        write_layer(layers, layer, value)
+    })
+}
+
+/// Imports heights into the cells of `storage`, like [import_heightmap]. Returns the changed cells.
+pub fn import_heightmap_to_storage(
+    storage: &TerrainCellStorage,
+    heights: &Array2<f32>,
+    position: Vec2,
+    texel_size: f32,
+) -> anyhow::Result<Vec<IVec2>> {
+    import_map_to_storage(storage, heights, position, texel_size, write_height)
+}
+
+/// Imports the values of one of the [TerrainLayers] into the cells of `storage`, like [import_layer_map].
+pub fn import_layer_map_to_storage(
+    storage: &TerrainCellStorage,
+    layer: TerrainLayers,
+    values: &Array2<f32>,
+    position: Vec2,
+    texel_size: f32,
+) -> anyhow::Result<Vec<IVec2>> {
+    import_map_to_storage(storage, values, position, texel_size, |layers, value| {
+        write_layer(layers, layer, value)
+    })
+}
+
+/// Exports the heights in meters of `cells` terrain cells starting at `top_left_cell`, with one texel per terrain
+/// texel, i.e. with a texel size of [TerrainSize::texel_size_in_meters]. Missing cells are at the terrain base.
+pub fn export_heightmap(world: &World, top_left_cell: IVec2, cells: UVec2) -> Array2<f32> {
+    heights(&gather_terrain_cells_cpu(world, top_left_cell, cells))
+}
+
+/// Exports the values of one of the [TerrainLayers], like [export_heightmap].
+pub fn export_layer_map(
+    world: &World,
+    layer: TerrainLayers,
+    top_left_cell: IVec2,
+    cells: UVec2,
+) -> Array2<f32> {
+    layer_values(
+        &gather_terrain_cells_cpu(world, top_left_cell, cells),
+        layer,
+    )
+}
+
+/// Exports the heights of cells in `storage`, like [export_heightmap].
+pub fn export_heightmap_from_storage(
+    storage: &TerrainCellStorage,
+    top_left_cell: IVec2,
+    cells: UVec2,
+) -> anyhow::Result<Array2<f32>> {
+    Ok(heights(&gather_stored_cells(
+        storage,
+        top_left_cell,
+        cells,
+    )?))
+}
+
+/// Exports the values of one of the [TerrainLayers] of cells in `storage`, like [export_layer_map].
+pub fn export_layer_map_from_storage(
+    storage: &TerrainCellStorage,
+    layer: TerrainLayers,
+    top_left_cell: IVec2,
+    cells: UVec2,
+) -> anyhow::Result<Array2<f32>> {
+    Ok(layer_values(
+        &gather_stored_cells(storage, top_left_cell, cells)?,
+        layer,
+    ))
+}
+
+fn write_height(mut layers: ArrayViewMut1<f32>, height: f32) {
+    layers[TerrainLayers::Rock as usize] = (height - TERRAIN_BASE).max(0.);
+    layers[TerrainLayers::Soil as usize] = 0.;
+}
+
+fn write_layer(mut layers: ArrayViewMut1<f32>, layer: TerrainLayers, value: f32) {
+    if let Some(layer) = layers.get_mut(layer as usize) {
+        *layer = value;
+    }
+}
+
+fn heights(heightmap: &Array3<f32>) -> Array2<f32> {
+    &heightmap.index_axis(Axis(0), TerrainLayers::Rock as usize)
+        + &heightmap.index_axis(Axis(0), TerrainLayers::Soil as usize)
+        + TERRAIN_BASE
+}
+
+fn layer_values(heightmap: &Array3<f32>, layer: TerrainLayers) -> Array2<f32> {
+    if (layer as usize) < heightmap.dim().0 {
+        heightmap.index_axis(Axis(0), layer as usize).to_owned()
+    } else {
+        Array2::zeros((heightmap.dim().1, heightmap.dim().2))
+    }
+}
+
+fn gather_stored_cells(
+    storage: &TerrainCellStorage,
+    top_left_cell: IVec2,
+    cells: UVec2,
+) -> anyhow::Result<Array3<f32>> {
+    let mut states = HashMap::new();
+    for y in 0..cells.y as i32 {
+        for x in 0..cells.x as i32 {
+            let cell = top_left_cell + ivec2(x, y);
+            if let Some(state) = storage.load(cell)? {
+                states.insert(cell, Arc::new(state));
+            }
+        }
+    }
+    Ok(gather_terrain_states_cpu(top_left_cell, cells, |cell| {
+        states.get(&cell).cloned()
+    }))
+}
+
+fn import_map(
+    world: &mut World,
+    map: &Array2<f32>,
+    position: Vec2,
+    texel_size: f32,
+    mut write: impl FnMut(ArrayViewMut1<f32>, f32),
+) -> Vec<EntityId> {
+    let mut changed_cells = Vec::new();
+    for cell in covered_cells(map, position, texel_size) {
+        let id = get_terrain_cell(world, cell)
+            .unwrap_or_else(|| spawn_terrain(world, Arc::new(TerrainStateCpu::empty()), cell));
+        let mut state = match world.get_ref(id, terrain_state_cpu()) {
+            Ok(state) => TerrainStateCpu::clone(state),
+            Err(_) => TerrainStateCpu::empty(),
+        };
+        if !write_cell(&mut state, cell, map, position, texel_size, &mut write) {
+            continue;
+        }
+        world.set(id, terrain_state_cpu(), Arc::new(state)).unwrap();
+        if let Ok(version) = world.get_mut(id, terrain_cell_version()) {
+            *version += 1;
+        }
+        changed_cells.push(id);
+    }
+    changed_cells
+}
+
+fn import_map_to_storage(
+    storage: &TerrainCellStorage,
+    map: &Array2<f32>,
+    position: Vec2,
+    texel_size: f32,
+    mut write: impl FnMut(ArrayViewMut1<f32>, f32),
+) -> anyhow::Result<Vec<IVec2>> {
+    let mut changed_cells = Vec::new();
+    for cell in covered_cells(map, position, texel_size) {
+        let mut state = storage.load(cell)?.unwrap_or_else(TerrainStateCpu::empty);
+        if write_cell(&mut state, cell, map, position, texel_size, &mut write) {
+            storage.save(cell, &state)?;
+            changed_cells.push(cell);
+        }
+    }
+    Ok(changed_cells)
+}
+
+/// The cells covered by `map`, with its first texel at `position`.
+fn covered_cells(map: &Array2<f32>, position: Vec2, texel_size: f32) -> Vec<IVec2> {
+    let (height, width) = map.dim();
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let terrain = TerrainSize::new();
+    let end = position + vec2(width as f32 - 1., height as f32 - 1.) * texel_size;
+    let top_left_cell = (position / terrain.size_in_meters()).floor().as_ivec2();
+    let bottom_right_cell = (end / terrain.size_in_meters()).floor().as_ivec2();
+    (top_left_cell.y..=bottom_right_cell.y)
+        .flat_map(|y| (top_left_cell.x..=bottom_right_cell.x).map(move |x| ivec2(x, y)))
+        .collect()
+}
+
+/// Writes the texels of `map` that cover `cell` into its `state`. Returns false if none do.
+fn write_cell(
+    state: &mut TerrainStateCpu,
+    cell: IVec2,
+    map: &Array2<f32>,
+    position: Vec2,
+    texel_size: f32,
+    write: &mut impl FnMut(ArrayViewMut1<f32>, f32),
+) -> bool {
+    let terrain = TerrainSize::new();
+    let cell_position = cell.as_vec2() * terrain.size_in_meters();
+    let (_, texels_y, texels_x) = state.heightmap.dim();
+    let mut changed = false;
+    for ty in 0..texels_y {
+        for tx in 0..texels_x {
+            let texel_position =
+                cell_position + vec2(tx as f32, ty as f32) * terrain.texel_size_in_meters();
+            if let Some(value) = sample(map, (texel_position - position) / texel_size) {
+                write(state.heightmap.slice_mut(s![.., ty, tx]), value);
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        state.normalmap = cpu::normalmap(&state.heightmap);
+    }
+    changed
+}
+
+/// Bilinearly samples `map` at the texel coordinates `p`. Returns `None` outside of the map and next to texels
+/// without data.
+fn sample(map: &Array2<f32>, p: Vec2) -> Option<f32> {
+    let (height, width) = map.dim();
+    let max = vec2(width as f32 - 1., height as f32 - 1.);
+    // Allow for rounding errors at the edges of the map
+    let epsilon = 0.001;
+    if p.x < -epsilon || p.y < -epsilon || p.x > max.x + epsilon || p.y > max.y + epsilon {
+        return None;
+    }
+    let p = p.clamp(Vec2::ZERO, max);
+    let (x0, y0) = (p.x.floor() as usize, p.y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let t = p - p.floor();
+    let top = map[(y0, x0)] * (1. - t.x) + map[(y0, x1)] * t.x;
+    let bottom = map[(y1, x0)] * (1. - t.x) + map[(y1, x1)] * t.x;
+    let value = top * (1. - t.y) + bottom * t.y;
+    (!value.is_nan()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use super::*;
+
+    #[test]
+    fn heightmap_round_trips_through_stored_cells() {
+        let dir =
+            std::env::temp_dir().join(format!("terrain_heightmap_test_{}", std::process::id()));
+        let storage = TerrainCellStorage::new(&dir);
+        let terrain = TerrainSize::new();
+        let texels = terrain.texture_size() - 1;
+
+        // Sediment isn't part of the height, so importing heights keeps it
+        let mut state = TerrainStateCpu::empty();
+        state.heightmap[(TerrainLayers::Sediment as usize, 2, 3)] = 4.;
+        state.heightmap[(TerrainLayers::Soil as usize, 2, 3)] = 5.;
+        storage.save(ivec2(-1, 0), &state).unwrap();
+
+        let heights = Array2::from_shape_fn((texels + 1, 2 * texels + 1), |(y, x)| {
+            x as f32 * 0.5 + y as f32 * 0.25
+        });
+        let position = vec2(-terrain.size_in_meters(), 0.);
+        let cells = import_heightmap_to_storage(
+            &storage,
+            &heights,
+            position,
+            terrain.texel_size_in_meters(),
+        )
+        .unwrap();
+        assert!(cells.contains(&ivec2(-1, 0)) && cells.contains(&ivec2(0, 0)));
+
+        let exported = export_heightmap_from_storage(&storage, ivec2(-1, 0), uvec2(2, 1)).unwrap();
+        assert_eq!(exported.dim(), heights.dim());
+        for (exported, height) in exported.iter().zip(heights.iter()) {
+            assert!((exported - height).abs() < 0.001, "{exported} != {height}");
+        }
+        let sediment = export_layer_map_from_storage(
+            &storage,
+            TerrainLayers::Sediment,
+            ivec2(-1, 0),
+            uvec2(1, 1),
+        )
+        .unwrap();
+        assert_eq!(sediment[(2, 3)], 4.);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,17 +1,23 @@
+use std::sync::Arc;
+
 use ambient_core::{asset_cache, async_ecs::async_run, runtime, session_start};
-use ambient_ecs::{components, query, SystemGroup};
+use ambient_ecs::{components, query, SystemGroup, World};
 use ambient_intent::{
     intent_applied, intent_reverted, intent_timestamp, use_old_state, IntentRegistry,
 };
 use ambient_native_std::asset_cache::AsyncAssetKeyExt;
 use itertools::Itertools;
 
-use crate::brushes::{TerrainBrushKey, TerrainBrushStroke};
+use crate::brushes::{TerrainBrushCpu, TerrainBrushCpuKey, TerrainBrushKey, TerrainBrushStroke};
 
 components!("terrain", {
     intent_terrain_stroke: TerrainBrushStroke,
     intent_terrain_stroke_state: (),
     stroke_client_applied: (),
+
+    terrain_brush_cpu: Arc<TerrainBrushCpu>,
+    /// Strokes which were applied before [terrain_brush_cpu] loaded, in order.
+    pending_cpu_strokes: Vec<TerrainBrushStroke>,
 });
 
 pub fn register_intents(reg: &mut IntentRegistry) {
@@ -20,12 +26,41 @@ pub fn register_intents(reg: &mut IntentRegistry) {
         intent_terrain_stroke_state(),
         |ctx, stroke| {
             stroke.ensure_cells_exist(ctx.world);
+            apply_cpu_stroke(ctx.world, stroke);
             Ok(())
         },
         |_, _| Ok(()),
         use_old_state,
     )
 }
+
+/// Applies `stroke` with the cpu brushes, as the server has no gpu.
+///
+/// The brush is loaded on the first stroke; until it has loaded, strokes are queued, and then
+/// applied in the order they came in.
+fn apply_cpu_stroke(world: &mut World, stroke: TerrainBrushStroke) {
+    if let Some(brush) = world.resource_opt(terrain_brush_cpu()).cloned() {
+        brush.apply(world, stroke);
+        return;
+    }
+    if let Some(pending) = world.resource_mut_opt(pending_cpu_strokes()) {
+        pending.push(stroke);
+        return;
+    }
+
+    world.add_resource(pending_cpu_strokes(), vec![stroke]);
+    let async_run = world.resource(async_run()).clone();
+    let assets = world.resource(asset_cache()).clone();
+    world.resource(runtime()).spawn(async move {
+        let brush = TerrainBrushCpuKey.get(&assets).await;
+        async_run.run(move |world| {
+            for stroke in std::mem::take(world.resource_mut(pending_cpu_strokes())) {
+                brush.apply(world, stroke);
+            }
+            world.add_resource(terrain_brush_cpu(), brush);
+        });
+    });
+}
 pub fn terrain_intent_client_system() -> SystemGroup {
     SystemGroup::new(
         "dims/terrain/intent/client",
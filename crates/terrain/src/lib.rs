@@ -47,7 +47,7 @@ use ambient_renderer::{
 };
 use glam::{vec2, vec3, vec4, IVec2, Mat4, Quat, UVec2, Vec2, Vec3, Vec3Swizzles, Vec4};
 use itertools::Itertools;
-use ndarray::{s, Array3, ArrayView3, Axis};
+use ndarray::{s, Array3, ArrayView2, ArrayView3, Axis};
 use physxx::{
     AsPxActor, AsPxRigidActor, PxActor, PxActorFlag, PxHeightFieldDesc, PxHeightFieldGeometry,
     PxMaterial, PxPhysicsRef, PxQuantizedHeightFieldSamples, PxRigidActor, PxRigidStaticRef,
@@ -59,7 +59,9 @@ use tracing::info_span;
 
 pub mod brushes;
 mod gather_spread;
+pub mod heightmap_io;
 pub mod intents;
+pub mod streaming;
 mod terrain_shader;
 use ambient_network::ServerWorldExt;
 use ambient_sys::time::Instant;
@@ -88,6 +90,7 @@ components!("terrain", {
 pub fn init_all_components() {
     init_components();
     intents::init_components();
+    streaming::init_components();
 }
 
 pub const TERRAIN_BASE: f32 = -30.;
@@ -118,9 +121,25 @@ pub fn spawn_terrain(
         .with(name(), "Terrain".to_string())
         .with(terrain_world_cell(), cell)
         .with(terrain_cell_needs_cpu_download(), false)
+        .with(terrain_cell_version(), 0)
         .spawn(world)
 }
 
+pub(crate) fn remove_terrain_physics(world: &mut World, id: EntityId) {
+    if let Ok(actor) = world.get_ref(id, rigid_static()) {
+        if let Some(scene) = actor.get_scene() {
+            scene.remove_actor(actor, false);
+            for shape in actor.get_shapes() {
+                shape.remove_user_data::<PxShapeUserData>();
+            }
+            actor.as_actor().remove_user_data::<PxActorUserData>();
+            actor.release();
+        }
+    }
+    world.remove_component(id, rigid_static()).ok();
+    world.remove_component(id, physics_shape()).ok();
+}
+
 fn create_terrain_physics(
     world: &World,
     terrain_state: Arc<TerrainStateCpu>,
@@ -130,7 +149,12 @@ fn create_terrain_physics(
     let scene = world.resource(main_physics_scene());
     let physics = world.resource(physics());
     let physics_material = PxTerrainMaterialKey.get(world.resource(asset_cache()));
-    let actor = px_rigid_static_from_heightmap(physics, physics_material, &terrain_state.heightmap);
+    let actor = px_rigid_static_from_heightmap(
+        physics,
+        physics_material,
+        &terrain_state.heightmap,
+        terrain_state.size.texel_size_in_meters(),
+    );
     actor
         .as_actor()
         .set_user_data(PxActorUserData { serialize: false });
@@ -197,6 +221,7 @@ pub fn server_systems() -> SystemGroup {
     SystemGroup::new(
         "dims/terrain/server_systems",
         vec![
+            Box::new(streaming::systems()),
             query((
                 terrain_state_cpu().changed(),
                 translation(),
@@ -213,15 +238,12 @@ pub fn server_systems() -> SystemGroup {
                     .sorted_by_key(|x| x.0)
                     .dedup_by(|x, y| x.0 == y.0)
                     .collect_vec();
+                let physics_cells = streaming::physics_cells(world);
                 for (id, (state, position, cell)) in all {
-                    if let Ok(actor) = world.get_ref(id, rigid_static()) {
-                        if let Some(scene) = actor.get_scene() {
-                            scene.remove_actor(actor, false);
-                            for shape in actor.get_shapes() {
-                                shape.remove_user_data::<PxShapeUserData>();
-                            }
-                            actor.as_actor().remove_user_data::<PxActorUserData>();
-                            actor.release();
+                    remove_terrain_physics(world, id);
+                    if let Some(physics_cells) = &physics_cells {
+                        if !physics_cells.contains(&cell) {
+                            continue;
                         }
                     }
                     let body = create_terrain_physics(world, state.clone(), position, cell);
@@ -458,12 +480,16 @@ impl SyncAssetKey<PxMaterial> for PxTerrainMaterialKey {
 pub struct TerrainSize {
     pub lods: usize,
     pub base_polygons: usize,
+    /// How many times the resolution of the heightmap has been halved; see [streaming].
+    #[serde(default)]
+    pub downsample: usize,
 }
 impl Default for TerrainSize {
     fn default() -> Self {
         Self {
             lods: 4,
             base_polygons: 8,
+            downsample: 0,
         }
     }
 }
@@ -478,7 +504,7 @@ impl TerrainSize {
         self.base_polygons * 2usize.pow((lod_max - lod) as u32)
     }
     pub fn texture_size(&self) -> usize {
-        self.polygons_at_lod(0) + 1
+        (self.polygons_at_lod(0) >> self.downsample) + 1
     }
     pub fn heightmap_extent(&self) -> wgpu::Extent3d {
         wgpu::Extent3d {
@@ -500,6 +526,10 @@ impl TerrainSize {
     pub fn size_in_meters(&self) -> f32 {
         self.polygon_size() as f32
     }
+    /// The distance between two texels of the heightmap, in meters.
+    pub fn texel_size_in_meters(&self) -> f32 {
+        self.size_in_meters() / (self.texture_size() - 1) as f32
+    }
     pub fn cell_and_texel_from_position(&self, position: Vec2) -> (IVec2, UVec2) {
         let cell = (position / self.size_in_meters()).floor().as_ivec2();
         let offset = position - cell.as_vec2() * self.size_in_meters();
@@ -510,6 +540,7 @@ impl TerrainSize {
 
 pub const TERRAIN_LAYERS: u32 = 7;
 #[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TerrainLayers {
     Rock,
     Soil,
@@ -714,6 +745,35 @@ impl TerrainStateCpu {
         self.heightmap.shape()
     }
 
+    /// Halves the resolution of the heightmap and normalmap `levels` times.
+    pub fn downsampled(&self, levels: usize) -> Self {
+        let step = 1 << levels;
+        Self {
+            size: TerrainSize {
+                downsample: self.size.downsample + levels,
+                ..self.size.clone()
+            },
+            heightmap: self.heightmap.slice(s![.., ..;step, ..;step]).to_owned(),
+            normalmap: self.normalmap.slice(s![..;step, ..;step, ..]).to_owned(),
+        }
+    }
+
+    /// Adds the edits made to `edited`, a downsampled copy of this state, to this state. The edits are interpolated
+    /// between the texels of `edited`, so the parts of this state that weren't edited keep their full resolution.
+    pub fn apply_downsampled_edits(&mut self, edited: &TerrainStateCpu) {
+        let levels = edited.size.downsample - self.size.downsample;
+        let step = 1 << levels;
+        let base = self.downsampled(levels);
+        let heights = &edited.heightmap - &base.heightmap;
+        let normals = &edited.normalmap - &base.normalmap;
+        for ((layer, y, x), height) in self.heightmap.indexed_iter_mut() {
+            *height += upsample_at(heights.index_axis(Axis(0), layer), y, x, step);
+        }
+        for ((y, x, channel), normal) in self.normalmap.indexed_iter_mut() {
+            *normal += upsample_at(normals.index_axis(Axis(2), channel), y, x, step);
+        }
+    }
+
     pub fn to_gpu(&self, gpu: &Gpu, assets: &AssetCache) -> TerrainState {
         let state = TerrainState::new_empty(gpu, assets, self.size.clone());
         state.heightmap.write_array(gpu, &self.heightmap);
@@ -790,6 +850,18 @@ impl TerrainStateCpu {
     }
 }
 
+/// Bilinearly samples `coarse`, whose texels are `step` texels apart, at the texel `(y, x)` of the full resolution.
+fn upsample_at(coarse: ArrayView2<f32>, y: usize, x: usize, step: usize) -> f32 {
+    let (height, width) = coarse.dim();
+    let (y0, x0) = ((y / step).min(height - 1), (x / step).min(width - 1));
+    let (y1, x1) = ((y0 + 1).min(height - 1), (x0 + 1).min(width - 1));
+    let ty = ((y as f32 / step as f32) - y0 as f32).clamp(0., 1.);
+    let tx = ((x as f32 / step as f32) - x0 as f32).clamp(0., 1.);
+    let top = coarse[(y0, x0)] * (1. - tx) + coarse[(y0, x1)] * tx;
+    let bottom = coarse[(y1, x0)] * (1. - tx) + coarse[(y1, x1)] * tx;
+    top * (1. - ty) + bottom * ty
+}
+
 // #[derive(Clone, Serialize, Deserialize, PartialEq)]
 // pub struct TerrainStateCompressed {
 //     pub size: TerrainSize,
@@ -937,6 +1009,7 @@ impl ElementComponent for Terrain {
                         heightmap_position,
                         lod_factor,
                         cell_diagonal,
+                        texel_size: self.state.size.texel_size_in_meters(),
                         _padding: Default::default(),
                     },
                     Arc::new(
@@ -963,7 +1036,8 @@ impl ElementComponent for Terrain {
             },
         );
 
-        let lod_meshes = (0..self.state.size.lods)
+        let lod_meshes = (self.state.size.downsample.min(self.state.size.lods - 1)
+            ..self.state.size.lods)
             .map(|lod| {
                 GridMeshKey(GridMesh {
                     n_vertices_height: self.state.size.polygons_at_lod(lod) + 1,
@@ -1022,6 +1096,7 @@ pub fn px_rigid_static_from_heightmap(
     physics: &Physics,
     physics_material: PxMaterial,
     heightmap: &Array3<f32>,
+    texel_size: f32,
 ) -> PxRigidStaticRef {
     let heightmap = heightmap.slice(s![0..2, .., ..]).sum_axis(Axis(0));
     let texture_size = heightmap.shape()[0];
@@ -1037,7 +1112,7 @@ pub fn px_rigid_static_from_heightmap(
     }
     let hfd = PxHeightFieldDesc::new(texture_size as u32, texture_size as u32, &quantized.samples);
 
-    let xy_scale = 1.;
+    let xy_scale = texel_size;
     PxRigidStaticRef::new_with_geometry(
         physics.physics,
         &PxTransform::identity(),
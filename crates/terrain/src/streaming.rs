@@ -0,0 +1,431 @@
+//! Streaming of terrain cells around the players, the camera and [terrain_stream_focus] entities, for worlds that
+//! are too large to keep in memory.
+//!
+//! Streaming is enabled by adding the [terrain_streaming] resource, which the server does when it's started with
+//! `--terrain-dir`. Cells within [TerrainStreaming::load_distance] of a focus are loaded from
+//! [TerrainStreaming::storage] (or spawned empty), and cells further away than [TerrainStreaming::unload_distance]
+//! are saved and despawned. Cells beyond each of the [TerrainStreaming::lod_distances] are kept at half the
+//! resolution, and physics heightfields are only created for cells within [TerrainStreaming::physics_distance] of a
+//! dynamic actor.
+
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
+
+use ambient_core::{
+    async_ecs::async_run,
+    camera::get_active_camera,
+    hierarchy::despawn_recursive,
+    main_scene,
+    player::{is_player, local_user_id},
+    runtime,
+    transform::translation,
+    FixedTimestepSystem,
+};
+use ambient_ecs::{components, query, EntityId, FnSystem, Resource, SystemGroup, World};
+use ambient_network::server::ShutdownEvent;
+use ambient_physics::physx::{character_controller, rigid_dynamic, rigid_static};
+use glam::{ivec2, vec2, IVec2, Vec2, Vec3Swizzles};
+use itertools::Itertools;
+
+use crate::{
+    get_terrain_cell, remove_terrain_physics, spawn_terrain, terrain_cell_version, terrain_state,
+    terrain_state_cpu, terrain_world_cell, TerrainSize, TerrainStateCpu,
+};
+
+components!("terrain", {
+    @[Resource]
+    terrain_streaming: TerrainStreaming,
+    /// Terrain cells are streamed in around entities with this component, in addition to players and the camera.
+    terrain_stream_focus: (),
+    /// The cell is being loaded from the storage.
+    terrain_cell_loading: (),
+    /// The [terrain_cell_version] the cell had when it was last loaded or saved. Cells without it have never been
+    /// saved, so they're saved even if they haven't been edited.
+    terrain_cell_saved_version: i32,
+});
+
+#[derive(Clone, Debug)]
+pub struct TerrainStreaming {
+    /// Cells closer than this to a focus are loaded, in meters.
+    pub load_distance: f32,
+    /// Cells further away than this from every focus are unloaded, in meters.
+    pub unload_distance: f32,
+    /// Cells further away than each of these distances are downsampled once more. Only used with a [Self::storage],
+    /// so that the full resolution can be loaded back.
+    pub lod_distances: Vec<f32>,
+    /// Cells closer than this to a dynamic actor or character get a physics heightfield, in meters.
+    pub physics_distance: f32,
+    /// Where cells are kept while they're unloaded. Without it, unloaded cells are lost.
+    pub storage: Option<TerrainCellStorage>,
+}
+impl Default for TerrainStreaming {
+    fn default() -> Self {
+        Self {
+            load_distance: 512.,
+            unload_distance: 640.,
+            lod_distances: vec![192., 384.],
+            physics_distance: 128.,
+            storage: None,
+        }
+    }
+}
+impl TerrainStreaming {
+    /// How many times a cell `distance` meters away from the closest focus is downsampled.
+    pub fn lod_at(&self, distance: f32) -> usize {
+        if self.storage.is_none() {
+            return 0;
+        }
+        let max_lod = TerrainSize::new().lods - 1;
+        self.lod_distances
+            .iter()
+            .filter(|lod_distance| distance > **lod_distance)
+            .count()
+            .min(max_lod)
+    }
+}
+
+/// Reads and writes the full resolution state of terrain cells from and to a directory, with one file per cell.
+#[derive(Clone, Debug)]
+pub struct TerrainCellStorage {
+    dir: PathBuf,
+}
+impl TerrainCellStorage {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+    fn path(&self, cell: IVec2) -> PathBuf {
+        self.dir.join(format!("{}_{}.terrain", cell.x, cell.y))
+    }
+    /// Returns `None` if the cell has never been saved.
+    pub fn load(&self, cell: IVec2) -> anyhow::Result<Option<TerrainStateCpu>> {
+        match std::fs::read(self.path(cell)) {
+            Ok(data) => Ok(Some(bincode::deserialize(&data)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+    pub fn save(&self, cell: IVec2, state: &TerrainStateCpu) -> anyhow::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        // Write to a temporary file first, so that a crash never leaves a partially written cell behind
+        let path = self.path(cell);
+        let tmp_path = path.with_extension("terrain.tmp");
+        std::fs::write(&tmp_path, bincode::serialize(state)?)?;
+        std::fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+pub fn systems() -> SystemGroup {
+    SystemGroup::new(
+        "dims/terrain/streaming",
+        vec![Box::new(FixedTimestepSystem::new(
+            0.5,
+            Box::new(FnSystem::new(|world, _| {
+                profiling::scope!("terrain.streaming");
+                update_streaming(world);
+            })),
+        ))],
+    )
+}
+
+/// Saves the loaded cells when the server shuts down.
+pub fn on_shutdown_systems() -> SystemGroup<ShutdownEvent> {
+    SystemGroup::new(
+        "dims/terrain/streaming/on_shutdown_systems",
+        vec![Box::new(FnSystem::new(|world, _| {
+            save_terrain_cells(world)
+        }))],
+    )
+}
+
+/// Saves all loaded full resolution cells that have changed since they were last saved, e.g. before shutting down.
+pub fn save_terrain_cells(world: &mut World) {
+    let Some(config) = world.resource_opt(terrain_streaming()).cloned() else {
+        return;
+    };
+    for (id, _) in query((terrain_world_cell(),))
+        .excl(terrain_cell_loading())
+        .collect_cloned(world, None)
+    {
+        save_cell(world, &config, id);
+    }
+}
+
+/// The cells that should have a physics heightfield, or `None` if all of them should.
+pub(crate) fn physics_cells(world: &World) -> Option<HashSet<IVec2>> {
+    let config = world.resource_opt(terrain_streaming())?;
+    let actors = query(translation())
+        .incl(rigid_dynamic())
+        .iter(world, None)
+        .chain(
+            query(translation())
+                .incl(character_controller())
+                .iter(world, None),
+        )
+        .map(|(_, position)| position.xy())
+        .collect_vec();
+    Some(cells_within(&actors, config.physics_distance))
+}
+
+fn update_streaming(world: &mut World) {
+    let Some(config) = world.resource_opt(terrain_streaming()).cloned() else {
+        return;
+    };
+    let focus = focus_positions(world);
+    let distance_to_focus = |cell: IVec2| {
+        focus
+            .iter()
+            .map(|position| cell_distance(cell, *position))
+            .fold(f32::INFINITY, f32::min)
+    };
+
+    for cell in cells_within(&focus, config.load_distance) {
+        if get_terrain_cell(world, cell).is_none() {
+            let lod = config.lod_at(distance_to_focus(cell));
+            let id = spawn_terrain(
+                world,
+                Arc::new(TerrainStateCpu::empty().downsampled(lod)),
+                cell,
+            );
+            load_cell(world, &config, id, cell, lod);
+        }
+    }
+
+    let cells = query((terrain_world_cell(), terrain_state_cpu()))
+        .excl(terrain_cell_loading())
+        .iter(world, None)
+        .map(|(id, (cell, state))| (id, *cell, state.size.downsample))
+        .collect_vec();
+    for (id, cell, downsample) in cells {
+        let distance = distance_to_focus(cell);
+        if distance > config.unload_distance {
+            // Cells that couldn't be saved are kept, so that their data isn't lost
+            if !save_cell(world, &config, id) {
+                continue;
+            }
+            remove_terrain_physics(world, id);
+            despawn_recursive(world, id);
+            continue;
+        }
+        let lod = config.lod_at(distance);
+        if lod > downsample {
+            if !save_cell(world, &config, id) {
+                continue;
+            }
+            let state = world.get_ref(id, terrain_state_cpu()).unwrap();
+            let state = state.downsampled(lod - downsample);
+            set_cell_state(world, id, state);
+        } else if config.lod_at(distance * 1.1) < downsample {
+            // Cells have to come a bit closer than where they were downsampled before they're loaded back, so
+            // that cells right at the edge don't keep reloading
+            load_cell(world, &config, id, cell, lod);
+        }
+    }
+
+    if let Some(physics_cells) = physics_cells(world) {
+        for (id, cell) in query(terrain_world_cell())
+            .incl(rigid_static())
+            .collect_cloned(world, None)
+        {
+            if !physics_cells.contains(&cell) {
+                remove_terrain_physics(world, id);
+            }
+        }
+    }
+}
+
+fn focus_positions(world: &World) -> Vec<Vec2> {
+    let mut positions = query(translation())
+        .incl(terrain_stream_focus())
+        .iter(world, None)
+        .chain(query(translation()).incl(is_player()).iter(world, None))
+        .map(|(_, position)| position.xy())
+        .collect_vec();
+    if let Some(camera) =
+        get_active_camera(world, main_scene(), world.resource_opt(local_user_id()))
+    {
+        if let Ok(position) = world.get(camera, translation()) {
+            positions.push(position.xy());
+        }
+    }
+    positions
+}
+
+/// The distance from `position` to the closest point of `cell`.
+fn cell_distance(cell: IVec2, position: Vec2) -> f32 {
+    let size = TerrainSize::new().size_in_meters();
+    let min = cell.as_vec2() * size;
+    position.distance(position.clamp(min, min + size))
+}
+
+/// All cells that are at most `distance` meters from one of `positions`.
+fn cells_within(positions: &[Vec2], distance: f32) -> HashSet<IVec2> {
+    let size = TerrainSize::new().size_in_meters();
+    let mut cells = HashSet::new();
+    for position in positions {
+        let min = ((*position - distance) / size).floor().as_ivec2();
+        let max = ((*position + distance) / size).floor().as_ivec2();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = ivec2(x, y);
+                if cell_distance(cell, *position) <= distance {
+                    cells.insert(cell);
+                }
+            }
+        }
+    }
+    cells
+}
+
+/// Loads the saved state of `cell` into `id`, downsampled `lod` times.
+fn load_cell(world: &mut World, config: &TerrainStreaming, id: EntityId, cell: IVec2, lod: usize) {
+    let Some(storage) = config.storage.clone() else {
+        return;
+    };
+    world.add_component(id, terrain_cell_loading(), ()).unwrap();
+    let async_run = world.resource(async_run()).clone();
+    world.resource(runtime()).spawn_blocking(move || {
+        let state = match storage.load(cell) {
+            Ok(state) => state,
+            Err(err) => {
+                tracing::error!("Failed to load terrain cell {cell}: {err:?}");
+                None
+            }
+        };
+        async_run.run(move |world| {
+            if world.remove_component(id, terrain_cell_loading()).is_err() {
+                // The cell was unloaded while it was loading
+                return;
+            }
+            let state = state.unwrap_or_else(TerrainStateCpu::empty);
+            let version = world.get(id, terrain_cell_version()).unwrap_or_default();
+            world
+                .add_component(id, terrain_cell_saved_version(), version)
+                .unwrap();
+            set_cell_state(world, id, state.downsampled(lod));
+        });
+    });
+}
+
+/// Saves the state of the cell, if it has changed since it was last saved. The edits of downsampled cells are
+/// upsampled into the saved full resolution state of the cell.
+///
+/// Returns `false` if the cell couldn't be saved, in which case it must be kept as it is.
+fn save_cell(world: &mut World, config: &TerrainStreaming, id: EntityId) -> bool {
+    let Some(storage) = &config.storage else {
+        return true;
+    };
+    let (Ok(state), Ok(cell)) = (
+        world.get_cloned(id, terrain_state_cpu()),
+        world.get(id, terrain_world_cell()),
+    ) else {
+        return true;
+    };
+    let version = world.get(id, terrain_cell_version()).unwrap_or_default();
+    let saved_version = world.get(id, terrain_cell_saved_version()).ok();
+    if saved_version == Some(version) {
+        return true;
+    }
+    let state = if state.size.downsample == 0 {
+        state
+    } else {
+        let mut full = match storage.load(cell) {
+            Ok(full) => full.unwrap_or_else(TerrainStateCpu::empty),
+            Err(err) => {
+                tracing::error!("Failed to load terrain cell {cell} to save its edits: {err:?}");
+                return false;
+            }
+        };
+        full.apply_downsampled_edits(&state);
+        Arc::new(full)
+    };
+    match storage.save(cell, &state) {
+        Ok(()) => {
+            world
+                .add_component(id, terrain_cell_saved_version(), version)
+                .unwrap();
+            true
+        }
+        Err(err) => {
+            tracing::error!("Failed to save terrain cell {cell}: {err:?}");
+            false
+        }
+    }
+}
+
+fn set_cell_state(world: &mut World, id: EntityId, state: TerrainStateCpu) {
+    world.set(id, terrain_state_cpu(), Arc::new(state)).unwrap();
+    // Makes the client upload the new state to the gpu
+    world.remove_component(id, terrain_state()).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lods_need_storage() {
+        let mut config = TerrainStreaming::default();
+        assert_eq!(config.lod_at(1000.), 0);
+        config.storage = Some(TerrainCellStorage::new(std::env::temp_dir()));
+        assert_eq!(config.lod_at(0.), 0);
+        assert_eq!(config.lod_at(200.), 1);
+        assert_eq!(config.lod_at(1000.), 2);
+    }
+
+    #[test]
+    fn cells_within_distance() {
+        let size = TerrainSize::new().size_in_meters();
+        let cells = cells_within(&[vec2(size / 2., size / 2.)], size / 2.);
+        assert_eq!(
+            cells,
+            HashSet::from([
+                ivec2(0, 0),
+                ivec2(-1, 0),
+                ivec2(1, 0),
+                ivec2(0, -1),
+                ivec2(0, 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn storage_round_trips() {
+        let dir = std::env::temp_dir().join(format!("terrain_storage_test_{}", std::process::id()));
+        let storage = TerrainCellStorage::new(&dir);
+        assert!(storage.load(ivec2(3, -2)).unwrap().is_none());
+
+        let mut state = TerrainStateCpu::empty();
+        state.heightmap[(0, 4, 5)] = 12.;
+        storage.save(ivec2(3, -2), &state).unwrap();
+        assert_eq!(storage.load(ivec2(3, -2)).unwrap(), Some(state));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn downsampled_edits_are_upsampled() {
+        let mut full = TerrainStateCpu::empty();
+        full.heightmap[(0, 9, 9)] = 1.;
+        let mut edited = full.downsampled(1);
+        edited.heightmap[(0, 2, 2)] = 4.;
+        full.apply_downsampled_edits(&edited);
+
+        assert_eq!(full.heightmap[(0, 4, 4)], 4.);
+        assert_eq!(full.heightmap[(0, 4, 5)], 2.);
+        assert_eq!(full.heightmap[(0, 5, 5)], 1.);
+        assert_eq!(full.heightmap[(0, 4, 6)], 0.);
+        // Texels between the downsampled ones keep their own values
+        assert_eq!(full.heightmap[(0, 9, 9)], 1.);
+        assert_eq!(full.downsampled(1), edited);
+    }
+
+    #[test]
+    fn downsampled_cells_keep_their_size_in_meters() {
+        let state = TerrainStateCpu::empty().downsampled(2);
+        let full = TerrainSize::new();
+        assert_eq!(state.size.size_in_meters(), full.size_in_meters());
+        assert_eq!(state.size.texture_size(), (full.texture_size() - 1) / 4 + 1);
+        assert_eq!(state.heightmap.dim().1, state.size.texture_size());
+        assert_eq!(state.normalmap.dim().0, state.size.texture_size());
+    }
+}
@@ -4,6 +4,7 @@ struct TerrainMaterialParams {
     heightmap_position: vec3<f32>,
     lod_factor: f32,
     cell_diagonal: f32,
+    texel_size: f32,
 };
 @group(MATERIAL_BIND_GROUP)
 @binding(0)
@@ -154,7 +155,7 @@ fn vs_main(@builtin(instance_index) instance_index: u32, @builtin(vertex_index)
     let blended_xy = interpolate_clamped_1_2(dist_from_camera, lod_start, lod_start + terrain_params.cell_diagonal, world_position_norm.xy, aligned_xy);
 
     // This is only "exact" (texel -> vertex) on lod0, on higher lods it samples between texels
-    out.texcoord = (blended_xy - terrain_params.heightmap_position.xy + 0.5) / heightmap_size;
+    out.texcoord = ((blended_xy - terrain_params.heightmap_position.xy) / terrain_params.texel_size + 0.5) / heightmap_size;
     var height = 0.;
     for (var i = 0; i < 2; i = i + 1) {
         height = height + textureSampleLevel(heightmap, heightmap_sampler, out.texcoord, i, 0.).r;
@@ -24,7 +24,7 @@ use ambient_renderer::{
     MATERIAL_BIND_GROUP, PRIMITIVES_BIND_GROUP,
 };
 use futures::future::join_all;
-use glam::{UVec2, Vec3};
+use glam::{UVec2, Vec2, Vec3};
 use serde::{Deserialize, Serialize};
 use wgpu::{util::DeviceExt, BindGroup};
 
@@ -231,7 +231,9 @@ pub struct TerrainMaterialParams {
     pub heightmap_position: Vec3,
     pub lod_factor: f32,
     pub cell_diagonal: f32,
-    pub _padding: Vec3,
+    /// The distance between two texels of the heightmap, in meters.
+    pub texel_size: f32,
+    pub _padding: Vec2,
 }
 #[derive(Debug)]
 pub struct TerrainMaterial {
@@ -7,10 +7,12 @@ use ambient_core::{
 };
 use ambient_ecs::{
     components, ensure_has_component, generated::text::types::FontStyle, query, Debuggable, Entity,
-    SystemGroup,
+    EntityId, SystemGroup, World,
 };
 use ambient_gpu::{mesh_buffer::GpuMesh, texture::Texture};
-use ambient_layout::{height, max_height, max_width, min_height, min_width, width};
+use ambient_layout::{
+    align_horizontal, height, max_height, max_width, min_height, min_width, width, Align,
+};
 use ambient_native_std::{
     asset_cache::{AssetCache, AsyncAssetKey, AsyncAssetKeyExt},
     asset_url::AbsAssetUrl,
@@ -26,21 +28,30 @@ use anyhow::Context;
 use async_trait::async_trait;
 use glam::*;
 use glyph_brush::{
-    ab_glyph::{Font, FontArc, PxScale, Rect},
-    BrushAction, BrushError, GlyphBrush, GlyphBrushBuilder, GlyphCruncher, Section,
+    ab_glyph::{point, Font, FontArc, Point, PxScale, Rect, ScaleFont},
+    BrushAction, BrushError, FontId, GlyphBrush, GlyphBrushBuilder, GlyphCruncher, HorizontalAlign,
+    Layout, Section,
 };
 use parking_lot::Mutex;
 
-use crate::text_material::{get_text_shader, TextMaterial};
+use crate::{
+    markup::{parse_markup, SpanStyle, TextSpan},
+    text_material::{get_text_shader, TextMaterial},
+};
 
+pub mod markup;
 mod text_material;
 
-pub use ambient_ecs::generated::text::components::{font_family, font_size, font_style, text};
+pub use ambient_ecs::generated::text::components::{
+    font_family, font_size, font_style, rich_text, text,
+};
 
 components!("text", {
     @[Debuggable]
     text_case: TextCase,
     font_arc: Arc<FontArc>,
+    rich_text_fonts: Arc<RichTextFonts>,
+    fallback_fonts: Arc<Vec<Arc<FontArc>>>,
 
     glyph_brush: Arc<Mutex<GlyphBrush<GlyphVertex>>>,
     text_texture: Arc<Texture>,
@@ -192,6 +203,172 @@ impl AsyncAssetKey<Arc<FontArc>> for FontDef {
     }
 }
 
+/// The fonts used by the spans of a [rich_text] entity, in the order of their [FontId]s.
+#[derive(Debug, Clone)]
+pub struct RichTextFonts(Vec<Arc<FontArc>>);
+impl RichTextFonts {
+    async fn load(family: FontFamily, regular: Arc<FontArc>, assets: &AssetCache) -> Self {
+        let mut fonts = vec![regular];
+        for style in [FontStyle::Bold, FontStyle::Italic, FontStyle::BoldItalic] {
+            fonts.push(FontDef(family.clone(), style).get(assets).await);
+        }
+        fonts.push(
+            FontDef(FontFamily::FontAwesome { solid: true }, FontStyle::Regular)
+                .get(assets)
+                .await,
+        );
+        Self(fonts)
+    }
+    fn font_id(style: &SpanStyle) -> FontId {
+        FontId(match style {
+            SpanStyle { icon: true, .. } => 4,
+            SpanStyle {
+                bold: true,
+                italic: true,
+                ..
+            } => 3,
+            SpanStyle { italic: true, .. } => 2,
+            SpanStyle { bold: true, .. } => 1,
+            _ => 0,
+        })
+    }
+}
+
+/// Fonts installed on the system that provide the glyphs missing from the other fonts, such as CJK
+/// glyphs, in order of preference.
+#[cfg(target_os = "windows")]
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\malgun.ttf",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+];
+#[cfg(target_os = "macos")]
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+    "/Library/Fonts/Arial Unicode.ttf",
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "unknown")))]
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+];
+#[cfg(target_os = "unknown")]
+const FALLBACK_FONT_PATHS: &[&str] = &[];
+
+/// The fonts used for the glyphs that are missing from the font of a text, such as CJK glyphs.
+///
+/// They are loaded from [FALLBACK_FONT_PATHS] until Chinese, Japanese and Korean are covered. As
+/// these fonts are large, they are only loaded once a text needs them.
+#[derive(Debug, Clone)]
+struct FallbackFonts;
+
+#[async_trait]
+impl AsyncAssetKey<Arc<Vec<Arc<FontArc>>>> for FallbackFonts {
+    async fn load(self, _assets: AssetCache) -> Arc<Vec<Arc<FontArc>>> {
+        let fonts = ambient_sys::task::spawn_blocking(|| {
+            const SAMPLES: [char; 3] = ['中', 'あ', '한'];
+            let mut fonts: Vec<Arc<FontArc>> = Vec::new();
+            for path in FALLBACK_FONT_PATHS {
+                let missing = SAMPLES
+                    .into_iter()
+                    .filter(|&c| !fonts.iter().any(|font| has_glyph(font, c)))
+                    .collect::<Vec<_>>();
+                if missing.is_empty() {
+                    break;
+                }
+                let Ok(data) = std::fs::read(path) else {
+                    continue;
+                };
+                match FontArc::try_from_vec(data) {
+                    Ok(font) if missing.iter().any(|&c| has_glyph(&font, c)) => {
+                        fonts.push(Arc::new(font))
+                    }
+                    Ok(_) => {}
+                    Err(err) => tracing::warn!("Failed to parse fallback font {path}: {err}"),
+                }
+            }
+            fonts
+        })
+        .await;
+        match fonts {
+            Ok(fonts) => Arc::new(fonts),
+            Err(err) => {
+                tracing::warn!("Failed to load fallback fonts: {err}");
+                Default::default()
+            }
+        }
+    }
+}
+
+fn has_glyph(font: &FontArc, c: char) -> bool {
+    font.glyph_id(c).0 != 0
+}
+
+/// Whether some of the text of `spans` is missing from the fonts of the spans.
+fn has_missing_glyphs(spans: &[TextSpan], fonts: &[FontArc]) -> bool {
+    spans.iter().any(|span| {
+        let font = fonts.get(RichTextFonts::font_id(&span.style).0);
+        span.text
+            .chars()
+            .any(|c| !c.is_whitespace() && !font.is_some_and(|font| has_glyph(font, c)))
+    })
+}
+
+/// Loads the [FallbackFonts] for the text `id`, which rebuilds its brush once they are loaded.
+fn load_fallback_fonts(world: &World, id: EntityId) {
+    let async_run = world.resource(async_run()).clone();
+    let assets = world.resource(asset_cache()).clone();
+    world.resource(runtime()).spawn(async move {
+        let fallbacks = FallbackFonts.get(&assets).await;
+        async_run.run(move |world| {
+            world.add_component(id, fallback_fonts(), fallbacks).ok();
+        });
+    });
+}
+
+/// Splits `spans` into runs of text that use the same font. Characters that are missing from the
+/// font of their span use the first of the fonts from `fallback_start` that has them.
+fn font_runs(
+    spans: &[TextSpan],
+    fonts: &[FontArc],
+    fallback_start: usize,
+) -> Vec<(TextSpan, FontId)> {
+    let mut runs: Vec<(TextSpan, FontId)> = Vec::new();
+    let mut last = None;
+    for (index, span) in spans.iter().enumerate() {
+        let primary = RichTextFonts::font_id(&span.style);
+        for c in span.text.chars() {
+            let font_id = match fonts.get(primary.0) {
+                Some(font) if c.is_whitespace() || has_glyph(font, c) => primary,
+                _ => (fallback_start..fonts.len())
+                    .find(|&i| has_glyph(&fonts[i], c))
+                    .map(FontId)
+                    .unwrap_or(primary),
+            };
+            match runs.last_mut() {
+                Some((run, run_font)) if last == Some(index) && *run_font == font_id => {
+                    run.text.push(c)
+                }
+                _ => runs.push((
+                    TextSpan {
+                        text: c.to_string(),
+                        style: span.style,
+                    },
+                    font_id,
+                )),
+            }
+            last = Some(index);
+        }
+    }
+    runs
+}
+
 pub fn systems(use_gpu: bool) -> SystemGroup {
     SystemGroup::new(
         "ui/text",
@@ -258,28 +435,43 @@ pub fn systems(use_gpu: bool) -> SystemGroup {
                     for (id, (font_family, font_style)) in q.collect_cloned(world, qs) {
                         let async_run = world.resource(async_run()).clone();
                         let assets = world.resource(asset_cache()).clone();
+                        let is_rich_text = world.has_component(id, rich_text());
                         world.resource(runtime()).spawn(async move {
-                            let font = FontDef(
-                                unwrap_log_warn!(FontFamily::from_str(&font_family)),
-                                font_style,
-                            )
-                            .get(&assets)
-                            .await;
+                            let font_family = unwrap_log_warn!(FontFamily::from_str(&font_family));
+                            let font = FontDef(font_family.clone(), font_style).get(&assets).await;
+                            let rich_fonts = if is_rich_text {
+                                Some(RichTextFonts::load(font_family, font.clone(), &assets).await)
+                            } else {
+                                None
+                            };
                             async_run.run(move |world| {
+                                if let Some(fonts) = rich_fonts {
+                                    world
+                                        .add_component(id, rich_text_fonts(), Arc::new(fonts))
+                                        .ok();
+                                }
                                 world.add_component(id, font_arc(), font).ok();
                             });
                         });
                     }
                 },
             ),
-            query(font_arc().changed()).to_system(|q, world, qs, _| {
-                for (id, font) in q.collect_cloned(world, qs) {
-                    let brush = Arc::new(Mutex::new(
-                        GlyphBrushBuilder::using_font(font.deref().clone()).build(),
-                    ));
-                    world.add_component(id, glyph_brush(), brush).unwrap();
-                }
-            }),
+            query(font_arc().changed())
+                .optional_changed(fallback_fonts())
+                .to_system(|q, world, qs, _| {
+                    for (id, font) in q.collect_cloned(world, qs) {
+                        let mut fonts = match world.get_ref(id, rich_text_fonts()) {
+                            Ok(fonts) => fonts.0.iter().map(|font| font.deref().clone()).collect(),
+                            Err(_) => vec![font.deref().clone()],
+                        };
+                        if let Ok(fallbacks) = world.get_ref(id, fallback_fonts()) {
+                            fonts.extend(fallbacks.iter().map(|font| font.deref().clone()));
+                        }
+                        let brush =
+                            Arc::new(Mutex::new(GlyphBrushBuilder::using_fonts(fonts).build()));
+                        world.add_component(id, glyph_brush(), brush).unwrap();
+                    }
+                }),
             query(())
                 .incl(mesh_to_local())
                 .incl(text())
@@ -313,6 +505,7 @@ pub fn systems(use_gpu: bool) -> SystemGroup {
             }
             .optional_changed(text_case())
             .optional_changed(min_width())
+            .optional_changed(align_horizontal())
             .to_system(move |q, world, qs, _| {
                 let scale_factor = world
                     .resource_opt(window_scale_factor())
@@ -320,21 +513,80 @@ pub fn systems(use_gpu: bool) -> SystemGroup {
                     .unwrap_or(1.) as f32;
                 for (id, (glyph_brush, text, font_size, font)) in q.collect_cloned(world, qs) {
                     let assets = world.resource(asset_cache()).clone();
-                    let text = world.get(id, text_case()).unwrap_or_default().format(text);
+                    let text_case = world.get(id, text_case()).unwrap_or_default();
+                    // Spans need all the rich text fonts; until they're loaded, show the markup as is
+                    let spans = if world.has_component(id, rich_text_fonts()) {
+                        parse_markup(&text)
+                            .into_iter()
+                            .map(|span| TextSpan {
+                                text: if span.style.icon {
+                                    span.text
+                                } else {
+                                    text_case.format(span.text)
+                                },
+                                style: span.style,
+                            })
+                            .collect::<Vec<_>>()
+                    } else {
+                        vec![TextSpan {
+                            text: text_case.format(text),
+                            style: SpanStyle::default(),
+                        }]
+                    };
                     let min_width = world.get(id, min_width()).unwrap_or(0.);
                     let min_height = world.get(id, min_height()).unwrap_or(0.);
                     let max_width = world.get(id, max_width()).unwrap_or(f32::MAX);
                     let max_height = world.get(id, max_height()).unwrap_or(f32::MAX);
+                    let align = world.get(id, align_horizontal()).unwrap_or(Align::Begin);
+                    // The fonts of the spans come first in the brush, followed by the fallbacks
+                    let fallback_start = world
+                        .get_ref(id, rich_text_fonts())
+                        .map_or(1, |fonts| fonts.0.len());
+                    if !world.has_component(id, fallback_fonts()) {
+                        let brush = glyph_brush.lock();
+                        if has_missing_glyphs(&spans, brush.fonts()) {
+                            load_fallback_fonts(world, id);
+                        }
+                    }
 
                     loop {
-                        let process_result = {
+                        let (process_result, underlines) = {
                             let mut brush = glyph_brush.lock();
-                            let section = Section::default()
+                            let runs = font_runs(&spans, brush.fonts(), fallback_start);
+                            let mut section = Section::default()
                                 .with_bounds((max_width, max_height))
-                                .add_text(glyph_brush::Text::new(&text).with_scale(
-                                    pt_size_to_px_scale(&*font, font_size, scale_factor),
-                                ));
+                                .with_text(
+                                    runs.iter()
+                                        .map(|(span, font_id)| {
+                                            let font_id = *font_id;
+                                            let scale = pt_size_to_px_scale(
+                                                brush.fonts().get(font_id.0).unwrap_or(&*font),
+                                                span.style.size.unwrap_or(font_size),
+                                                scale_factor,
+                                            );
+                                            glyph_brush::Text::new(&span.text)
+                                                .with_font_id(font_id)
+                                                .with_scale(scale)
+                                                .with_color(
+                                                    span.style
+                                                        .color
+                                                        .unwrap_or(Vec4::ONE)
+                                                        .to_array(),
+                                                )
+                                        })
+                                        .collect::<Vec<_>>(),
+                                );
                             if let Some(bounds) = brush.glyph_bounds(&section) {
+                                // Lines are aligned within the width of the widest line
+                                let align_width = bounds.max.x.max(min_width * scale_factor);
+                                let (h_align, x) = match align {
+                                    Align::Begin => (HorizontalAlign::Left, 0.),
+                                    Align::Center => (HorizontalAlign::Center, align_width / 2.),
+                                    Align::End => (HorizontalAlign::Right, align_width),
+                                };
+                                section = section
+                                    .with_layout(Layout::default_wrap().h_align(h_align))
+                                    .with_screen_position((x, 0.));
                                 if world.has_component(id, width()) {
                                     world
                                         .set_if_changed(
@@ -354,8 +606,9 @@ pub fn systems(use_gpu: bool) -> SystemGroup {
                                         .unwrap();
                                 }
                             }
+                            let underlines = underline_vertices(&mut brush, &section, &runs);
                             brush.queue(section);
-                            brush.process_queued(
+                            let process_result = brush.process_queued(
                                 |rect, tex_data| {
                                     if !use_gpu {
                                         return;
@@ -392,11 +645,15 @@ pub fn systems(use_gpu: bool) -> SystemGroup {
                                 |vertex_data| GlyphVertex {
                                     tex_coords: vertex_data.tex_coords,
                                     pixel_coords: vertex_data.pixel_coords,
+                                    color: vertex_data.extra.color,
                                 },
-                            )
+                            );
+                            (process_result, underlines)
                         };
                         match process_result {
                             Ok(BrushAction::Draw(vertices)) => {
+                                let vertices =
+                                    vertices.into_iter().chain(underlines).collect::<Vec<_>>();
                                 if vertices.is_empty() {
                                     // Mesh has no vertices. We have to clear any left over GPU state.
                                     world
@@ -483,12 +740,63 @@ fn pt_size_to_px_scale<F: Font>(font: &F, pt_size: f32, screen_scale_factor: f32
 pub struct GlyphVertex {
     pub tex_coords: Rect,
     pub pixel_coords: Rect,
+    pub color: [f32; 4],
+}
+
+/// Texture coordinates outside of the atlas, which the text shader draws as solid quads.
+const SOLID_TEX_COORDS: Rect = Rect {
+    min: Point { x: -1., y: -1. },
+    max: Point { x: -1., y: -1. },
+};
+
+/// Builds quads underlining the glyphs of the underlined spans, one per run of glyphs on a line.
+fn underline_vertices(
+    brush: &mut GlyphBrush<GlyphVertex>,
+    section: &Section,
+    runs: &[(TextSpan, FontId)],
+) -> Vec<GlyphVertex> {
+    let fonts = brush.fonts().to_vec();
+    let mut underlines: Vec<GlyphVertex> = Vec::new();
+    for glyph in brush.glyphs(section) {
+        let Some((span, _)) = runs.get(glyph.section_index) else {
+            continue;
+        };
+        if !span.style.underline {
+            continue;
+        }
+        let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+        let position = glyph.glyph.position;
+        let thickness = (glyph.glyph.scale.y / 16.).max(1.).round();
+        let top = (position.y + font.descent().abs() * 0.4).round();
+        let right = position.x + font.h_advance(glyph.glyph.id);
+        let color = span.style.color.unwrap_or(Vec4::ONE).to_array();
+        match underlines.last_mut() {
+            // Extend the underline of the previous glyph if it's on the same line
+            Some(last)
+                if last.color == color
+                    && (last.pixel_coords.min.y - top).abs() < thickness
+                    && (position.x - last.pixel_coords.max.x).abs() < glyph.glyph.scale.x / 4. =>
+            {
+                last.pixel_coords.max.x = right;
+            }
+            _ => underlines.push(GlyphVertex {
+                tex_coords: SOLID_TEX_COORDS,
+                pixel_coords: Rect {
+                    min: point(position.x, top),
+                    max: point(right, top + thickness),
+                },
+                color,
+            }),
+        }
+    }
+    underlines
 }
 
 fn mesh_from_glyph_vertices(vertices: Vec<GlyphVertex>) -> Mesh {
     assert!(!vertices.is_empty());
     let mut positions = Vec::new();
     let mut texcoords = Vec::new();
+    let mut colors = Vec::new();
     let mut normals = Vec::new();
     let mut indices = Vec::new();
     for vert in vertices.into_iter() {
@@ -503,6 +811,8 @@ fn mesh_from_glyph_vertices(vertices: Vec<GlyphVertex>) -> Mesh {
         texcoords.push(vec2(vert.tex_coords.min.x, vert.tex_coords.max.y));
         texcoords.push(vec2(vert.tex_coords.max.x, vert.tex_coords.max.y));
 
+        colors.extend([Vec4::from_array(vert.color); 4]);
+
         normals.push(vec3(0., 0., 1.));
         normals.push(vec3(0., 0., 1.));
         normals.push(vec3(0., 0., 1.));
@@ -519,6 +829,7 @@ fn mesh_from_glyph_vertices(vertices: Vec<GlyphVertex>) -> Mesh {
     MeshBuilder {
         positions,
         texcoords: vec![texcoords],
+        colors,
         normals,
         indices,
         ..MeshBuilder::default()
@@ -542,3 +853,61 @@ impl AsyncAssetKey<AssetResult<Arc<FontArc>>> for FontFromUrl {
         Ok(Arc::new(brush))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fonts() -> Vec<FontArc> {
+        vec![
+            FontArc::try_from_slice(include_bytes!(
+                "../fonts/Source_Sans_Pro/SourceSansPro-Regular.ttf"
+            ))
+            .unwrap(),
+            FontArc::try_from_slice(include_bytes!(
+                "../fonts/FontAwesome/Font Awesome 6 Free-Solid-900.otf"
+            ))
+            .unwrap(),
+        ]
+    }
+    fn span(text: &str) -> TextSpan {
+        TextSpan {
+            text: text.to_string(),
+            style: SpanStyle::default(),
+        }
+    }
+    /// The runs of `spans`, with the icon font standing in for a fallback font.
+    fn runs(spans: &[TextSpan]) -> Vec<(String, usize)> {
+        font_runs(spans, &fonts(), 1)
+            .into_iter()
+            .map(|(span, font)| (span.text, font.0))
+            .collect()
+    }
+    fn run(text: &str, font: usize) -> (String, usize) {
+        (text.to_string(), font)
+    }
+
+    #[test]
+    fn missing_glyphs_use_the_fallback_fonts() {
+        let spans = [span("a\u{f007}\u{f007} b")];
+        assert_eq!(
+            runs(&spans),
+            vec![run("a", 0), run("\u{f007}\u{f007}", 1), run(" b", 0)]
+        );
+        assert!(has_missing_glyphs(&spans, &fonts()[..1]));
+        assert!(!has_missing_glyphs(&[span("a b")], &fonts()[..1]));
+    }
+
+    #[test]
+    fn glyphs_missing_from_every_font_keep_their_font() {
+        assert_eq!(runs(&[span("a中b")]), vec![run("a中b", 0)]);
+    }
+
+    #[test]
+    fn spans_are_not_merged() {
+        assert_eq!(
+            runs(&[span("a"), span("b")]),
+            vec![run("a", 0), run("b", 0)]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Parses the markup of [rich_text](crate::rich_text) entities into styled spans.
+//!
+//! Supported tags are `<b>`, `<i>`, `<u>`, `<color=#rrggbb>` (also `#rgb`, `#rgba` and
+//! `#rrggbbaa`) and `<size=20>`, which are closed with `</b>` etc., and `<icon=f118>`, which
+//! inserts a solid FontAwesome icon by its codepoint. Anything that isn't a valid tag is kept as
+//! text, so a stray `<` doesn't need escaping; text that could be read as a tag is escaped by
+//! writing its `<` as `<lt>`.
+
+use ambient_native_std::color::Color;
+use glam::Vec4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SpanStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Multiplied with the color of the entity.
+    pub color: Option<Vec4>,
+    /// Font size in points, overriding the `font_size` of the entity.
+    pub size: Option<f32>,
+    /// The span is a FontAwesome icon.
+    pub icon: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    pub style: SpanStyle,
+}
+
+enum Tag {
+    Open(&'static str, SpanStyle),
+    Close(&'static str),
+    Icon(char),
+    Lt,
+}
+
+fn parse_tag(tag: &str, style: SpanStyle) -> Option<Tag> {
+    const NAMES: [&str; 5] = ["b", "i", "u", "color", "size"];
+    if let Some(name) = tag.strip_prefix('/') {
+        return NAMES
+            .into_iter()
+            .find(|n| *n == name.trim())
+            .map(Tag::Close);
+    }
+    let (name, value) = match tag.split_once('=') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (tag.trim(), None),
+    };
+    Some(match (name, value) {
+        ("b", None) => Tag::Open(
+            "b",
+            SpanStyle {
+                bold: true,
+                ..style
+            },
+        ),
+        ("i", None) => Tag::Open(
+            "i",
+            SpanStyle {
+                italic: true,
+                ..style
+            },
+        ),
+        ("u", None) => Tag::Open(
+            "u",
+            SpanStyle {
+                underline: true,
+                ..style
+            },
+        ),
+        ("color", Some(value)) => Tag::Open(
+            "color",
+            SpanStyle {
+                color: Some(parse_color(value)?),
+                ..style
+            },
+        ),
+        ("size", Some(value)) => Tag::Open(
+            "size",
+            SpanStyle {
+                size: Some(value.parse().ok().filter(|size: &f32| *size > 0.)?),
+                ..style
+            },
+        ),
+        ("icon", Some(value)) => Tag::Icon(char::from_u32(u32::from_str_radix(value, 16).ok()?)?),
+        ("lt", None) => Tag::Lt,
+        _ => return None,
+    })
+}
+
+/// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` into an sRGB color.
+pub fn parse_color(value: &str) -> Option<Vec4> {
+    // Unlike `Color::hex`, the `#` is required
+    let hex = value.strip_prefix('#')?;
+    if !hex.is_ascii() {
+        return None;
+    }
+    Color::hex(hex).ok().map(Vec4::from)
+}
+
+fn push_span(spans: &mut Vec<TextSpan>, text: &str, style: SpanStyle) {
+    if text.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(last) if last.style == style && !style.icon => last.text.push_str(text),
+        _ => spans.push(TextSpan {
+            text: text.to_string(),
+            style,
+        }),
+    }
+}
+
+/// Parses `markup` into spans of text with the same style. Adjacent spans with the same style
+/// are merged.
+pub fn parse_markup(markup: &str) -> Vec<TextSpan> {
+    let mut spans: Vec<TextSpan> = Vec::new();
+    let mut stack: Vec<(&'static str, SpanStyle)> = Vec::new();
+    let mut rest = markup;
+    while let Some(start) = rest.find('<') {
+        let style = stack.last().map(|(_, style)| *style).unwrap_or_default();
+        push_span(&mut spans, &rest[..start], style);
+        let after = &rest[start + 1..];
+        // A tag can't contain another `<`, which lets `a <b <b>c</b>` work as expected
+        let tag = after
+            .find(['<', '>'])
+            .filter(|end| after[*end..].starts_with('>'))
+            .and_then(|end| Some((end, parse_tag(&after[..end], style)?)));
+        match tag {
+            Some((end, Tag::Open(name, style))) => {
+                stack.push((name, style));
+                rest = &after[end + 1..];
+            }
+            Some((end, Tag::Close(name))) if stack.iter().any(|(n, _)| *n == name) => {
+                while let Some((n, _)) = stack.pop() {
+                    if n == name {
+                        break;
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            Some((end, Tag::Icon(icon))) => {
+                push_span(
+                    &mut spans,
+                    icon.encode_utf8(&mut [0; 4]),
+                    SpanStyle {
+                        icon: true,
+                        ..style
+                    },
+                );
+                rest = &after[end + 1..];
+            }
+            Some((end, Tag::Lt)) => {
+                push_span(&mut spans, "<", style);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                push_span(&mut spans, "<", style);
+                rest = after;
+            }
+        }
+    }
+    let style = stack.last().map(|(_, style)| *style).unwrap_or_default();
+    push_span(&mut spans, rest, style);
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::vec4;
+
+    use super::*;
+
+    fn span(text: &str, style: SpanStyle) -> TextSpan {
+        TextSpan {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    const BOLD: SpanStyle = SpanStyle {
+        bold: true,
+        italic: false,
+        underline: false,
+        color: None,
+        size: None,
+        icon: false,
+    };
+
+    #[test]
+    fn nested_tags() {
+        let italic = SpanStyle {
+            italic: true,
+            ..Default::default()
+        };
+        let bold_italic = SpanStyle {
+            italic: true,
+            ..BOLD
+        };
+        assert_eq!(
+            parse_markup("a<b>b<i>c</i>d</b>e"),
+            vec![
+                span("a", SpanStyle::default()),
+                span("b", BOLD),
+                span("c", bold_italic),
+                span("d", BOLD),
+                span("e", SpanStyle::default()),
+            ]
+        );
+        // Closing an outer tag also closes the tags inside it
+        assert_eq!(
+            parse_markup("<i>a<b>b</i>c"),
+            vec![
+                span("a", italic),
+                span("b", bold_italic),
+                span("c", SpanStyle::default())
+            ]
+        );
+        assert_eq!(
+            parse_markup("<size=20><color=#f00>a</color>b</size>"),
+            vec![
+                span(
+                    "a",
+                    SpanStyle {
+                        size: Some(20.),
+                        color: Some(vec4(1., 0., 0., 1.)),
+                        ..Default::default()
+                    }
+                ),
+                span(
+                    "b",
+                    SpanStyle {
+                        size: Some(20.),
+                        ..Default::default()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_closing_tags_are_text() {
+        assert_eq!(
+            parse_markup("a</b>c"),
+            vec![span("a</b>c", SpanStyle::default())]
+        );
+        assert_eq!(parse_markup("<b>a</i>b"), vec![span("a</i>b", BOLD)]);
+        // Unclosed tags last until the end
+        assert_eq!(parse_markup("<b>a"), vec![span("a", BOLD)]);
+    }
+
+    #[test]
+    fn stray_angle_brackets_are_text() {
+        assert_eq!(
+            parse_markup("1 < 2 > 0"),
+            vec![span("1 < 2 > 0", SpanStyle::default())]
+        );
+        assert_eq!(
+            parse_markup("a <b <b>c</b>"),
+            vec![span("a <b ", SpanStyle::default()), span("c", BOLD)]
+        );
+        assert_eq!(parse_markup("a<"), vec![span("a<", SpanStyle::default())]);
+        assert_eq!(
+            parse_markup("<unknown>"),
+            vec![span("<unknown>", SpanStyle::default())]
+        );
+    }
+
+    #[test]
+    fn escaped_tags_are_text() {
+        assert_eq!(
+            parse_markup("<u><lt>b>a<lt>/u></u>"),
+            vec![span(
+                "<b>a</u>",
+                SpanStyle {
+                    underline: true,
+                    ..Default::default()
+                }
+            )]
+        );
+        assert_eq!(
+            parse_markup("<lt>lt>"),
+            vec![span("<lt>", SpanStyle::default())]
+        );
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(parse_color("#fff"), Some(vec4(1., 1., 1., 1.)));
+        assert_eq!(
+            parse_color("#ff000080"),
+            Some(vec4(1., 0., 0., 128. / 255.))
+        );
+        assert_eq!(parse_color("#0000ff"), Some(vec4(0., 0., 1., 1.)));
+        assert_eq!(parse_color("#00f0"), Some(vec4(0., 0., 1., 0.)));
+        for invalid in ["fff", "#ff", "#fffff", "#ggg", "#ééé", "", "#"] {
+            assert_eq!(parse_color(invalid), None, "{invalid:?}");
+        }
+        assert_eq!(
+            parse_markup("<color=red>a</color>"),
+            vec![span("<color=red>a</color>", SpanStyle::default())]
+        );
+    }
+
+    #[test]
+    fn icons() {
+        let icon = SpanStyle { icon: true, ..BOLD };
+        assert_eq!(
+            parse_markup("<b>a<icon=f118><icon=f119>b</b>"),
+            vec![
+                span("a", BOLD),
+                span("\u{f118}", icon),
+                span("\u{f119}", icon),
+                span("b", BOLD),
+            ]
+        );
+        // Icons don't need to be closed, and invalid codepoints are text
+        assert_eq!(
+            parse_markup("<icon=zz><icon=d800>"),
+            vec![span("<icon=zz><icon=d800>", SpanStyle::default())]
+        );
+    }
+}
@@ -12,8 +12,11 @@ fn get_material(in: MaterialInput) -> MaterialOutput {
     var out: MaterialOutput;
     out.roughness = 0.4;
     out.metallic = 0.5;
-    let color = get_entity_color_or(in.entity_loc, vec4<f32>(1., 1., 1., 1.));
-    out.opacity = textureSample(font_atlas, font_sampler, in.texcoord).x * color.a;
+    // Vertex colors come from rich text spans
+    let color = get_entity_color_or(in.entity_loc, vec4<f32>(1., 1., 1., 1.)) * in.color;
+    // Quads outside of the atlas, such as underlines, are solid
+    let coverage = select(textureSample(font_atlas, font_sampler, in.texcoord).x, 1., in.texcoord.x < 0.);
+    out.opacity = coverage * color.a;
     out.alpha_cutoff = 0.01;
     out.base_color = from_srgb_to_linear(color.rgb);
     out.emissive_factor = vec3<f32>(0., 0., 0.);
@@ -54,7 +54,9 @@ use element::{use_ref_with, use_runtime_message, use_spawn};
 
 pub fn init_all_components() {
     layout::init_gpu_components();
+    layout::init_components();
     ambient_text::init_components();
+    ambient_ui::init_components();
 }
 
 pub fn systems() -> SystemGroup {
@@ -0,0 +1,345 @@
+//! A headless harness for testing UI [Element]s.
+//!
+//! [UiTestHarness] mounts an element in a world without a window or GPU, runs the layout and
+//! transform systems every [step](UiTestHarness::step), and simulates mouse and keyboard input
+//! the way the host's input and picking systems would, so that `ClickArea`, `use_keyboard_input`
+//! and focus behave as they do in a running app.
+//!
+//! Fonts aren't loaded, so text isn't measured and text entities have no size; assert on their
+//! content with [UiTestHarness::texts] or [UiTestHarness::snapshot] instead.
+
+use std::{path::PathBuf, sync::Once, time::Duration};
+
+use ambient_core::{
+    frame_index,
+    hierarchy::{children, parent},
+    time_resources_start,
+    transform::{local_to_world, translation, TransformSystem},
+    window::{
+        cursor_position, window_ctl, window_logical_size, window_physical_size,
+        window_scale_factor, WindowCtl,
+    },
+};
+use ambient_ecs::{
+    generated::{
+        input::components::{is_mouse_over, mouse_over_entity},
+        layout::components::{height, layout, width},
+        messages,
+        text::components::text,
+        ui::components::focus,
+    },
+    query, world_events, Entity, EntityId, FrameEvent, System, SystemGroup, World, WorldContext,
+    WorldEventsExt, WorldEventsSystem,
+};
+use ambient_element::{Element, ElementTree};
+use ambient_input::picking::mouse_pickable;
+use ambient_shared_types::{ModifiersState, MouseButton, VirtualKeyCode};
+use glam::{uvec2, vec3, UVec2, Vec2, Vec3Swizzles};
+use itertools::Itertools;
+
+/// The laid out bounds of an entity, in window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+impl Bounds {
+    pub fn center(&self) -> Vec2 {
+        self.position + self.size / 2.
+    }
+}
+
+/// Mounts an [Element] in a headless world and drives it frame by frame.
+pub struct UiTestHarness {
+    pub world: World,
+    tree: ElementTree,
+    systems: SystemGroup,
+    window_ctl_rx: flume::Receiver<WindowCtl>,
+    modifiers: ModifiersState,
+}
+
+impl UiTestHarness {
+    /// Mounts `element` in an 800x600 window and runs the first frame.
+    pub fn new(element: Element) -> Self {
+        Self::with_window_size(element, uvec2(800, 600))
+    }
+    /// Mounts `element` in a window of `size` logical pixels and runs the first frame.
+    pub fn with_window_size(element: Element, size: UVec2) -> Self {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            ambient_ecs::init_components();
+            ambient_core::init_all_components();
+            ambient_element::init_components();
+            ambient_input::init_all_components();
+            ambient_layout::init_components();
+            ambient_ui::init_components();
+        });
+
+        let mut world = World::new("ui_test", WorldContext::Client);
+        let (window_ctl_tx, window_ctl_rx) = flume::unbounded();
+        world
+            .add_components(
+                world.resource_entity(),
+                Entity::new()
+                    .with(world_events(), Default::default())
+                    .with(frame_index(), 0_usize)
+                    .with(cursor_position(), Vec2::ZERO)
+                    .with(window_physical_size(), size)
+                    .with(window_logical_size(), size)
+                    .with(window_scale_factor(), 1.)
+                    .with(window_ctl(), window_ctl_tx)
+                    .with(focus(), Default::default())
+                    .with_merge(time_resources_start(Duration::ZERO))
+                    .with_merge(ambient_input::picking::resources()),
+            )
+            .unwrap();
+        let tree = ElementTree::new(&mut world, element);
+        let systems = SystemGroup::new(
+            "ui_test",
+            vec![
+                Box::new(ambient_core::hierarchy::systems()),
+                Box::new(ambient_input::picking::frame_systems()),
+                Box::new(ambient_layout::layout_systems()),
+                Box::new(TransformSystem::new()),
+                Box::new(ambient_focus::systems()),
+                Box::new(WorldEventsSystem),
+            ],
+        );
+        let mut harness = Self {
+            world,
+            tree,
+            systems,
+            window_ctl_rx,
+            modifiers: ModifiersState::empty(),
+        };
+        harness.step();
+        harness
+    }
+
+    /// Runs one frame: updates the element tree, then lays out and picks the entity under the
+    /// mouse.
+    pub fn step(&mut self) {
+        self.world.next_frame();
+        self.tree.update(&mut self.world);
+        self.systems.run(&mut self.world, &FrameEvent);
+        self.update_mouse_over();
+        *self.world.resource_mut(frame_index()) += 1;
+    }
+    /// Runs `frames` frames.
+    pub fn steps(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// The entity at the root of the element tree.
+    pub fn root(&self) -> EntityId {
+        self.tree.root_entity().expect("the tree has no root")
+    }
+    /// All entities of the tree, depth first.
+    pub fn entities(&self) -> Vec<EntityId> {
+        let mut entities = Vec::new();
+        self.visit(self.root(), 0, &mut |id, _| entities.push(id));
+        entities
+    }
+    /// The first entity, depth first, for which `predicate` is true.
+    pub fn find(&self, predicate: impl Fn(&World, EntityId) -> bool) -> Option<EntityId> {
+        self.entities()
+            .into_iter()
+            .find(|id| predicate(&self.world, *id))
+    }
+    /// The first entity whose text is `content`.
+    pub fn find_text(&self, content: &str) -> Option<EntityId> {
+        self.find(|world, id| world.get_ref(id, text()).is_ok_and(|text| text == content))
+    }
+    /// The text of every text entity, depth first.
+    pub fn texts(&self) -> Vec<String> {
+        self.entities()
+            .into_iter()
+            .filter_map(|id| self.world.get_cloned(id, text()).ok())
+            .collect()
+    }
+    /// The laid out `width`, `height` and `translation` of an entity, with the position in window
+    /// coordinates.
+    pub fn bounds(&self, id: EntityId) -> Bounds {
+        let position = match self.world.get(id, local_to_world()) {
+            Ok(transform) => transform.to_scale_rotation_translation().2.xy(),
+            Err(_) => self.world.get(id, translation()).unwrap_or_default().xy(),
+        };
+        Bounds {
+            position,
+            size: Vec2::new(
+                self.world.get(id, width()).unwrap_or_default(),
+                self.world.get(id, height()).unwrap_or_default(),
+            ),
+        }
+    }
+
+    /// Moves the mouse to `position`, in window coordinates, and runs a frame.
+    pub fn move_mouse(&mut self, position: Vec2) {
+        let previous = *self.world.resource(cursor_position());
+        *self.world.resource_mut(cursor_position()) = position;
+        self.send(messages::WindowMouseMotion::new(position - previous));
+        self.step();
+    }
+    /// Presses or releases a mouse button and runs a frame.
+    pub fn mouse_input(&mut self, button: MouseButton, pressed: bool) {
+        self.send(messages::WindowMouseInput::new(pressed, u32::from(button)));
+        self.step();
+    }
+    /// Moves the mouse to the center of `id`, and clicks the left mouse button.
+    pub fn click(&mut self, id: EntityId) {
+        self.move_mouse(self.bounds(id).center());
+        self.mouse_input(MouseButton::Left, true);
+        self.mouse_input(MouseButton::Left, false);
+    }
+    /// Scrolls the mouse wheel by `delta` lines and runs a frame.
+    pub fn scroll(&mut self, delta: Vec2) {
+        self.send(messages::WindowMouseWheel::new(delta, false));
+        self.step();
+    }
+
+    /// Sets the held keyboard modifiers, which are sent with the following key presses.
+    pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+        self.send(messages::WindowKeyboardModifiersChange::new(
+            modifiers.bits(),
+        ));
+        self.step();
+    }
+    /// Presses or releases a key and runs a frame.
+    pub fn key_input(&mut self, key: VirtualKeyCode, pressed: bool) {
+        self.send(messages::WindowKeyboardInput::new(
+            pressed,
+            self.modifiers.bits(),
+            Some(key.to_string()),
+        ));
+        self.step();
+    }
+    /// Presses and releases a key.
+    pub fn press_key(&mut self, key: VirtualKeyCode) {
+        self.key_input(key, true);
+        self.key_input(key, false);
+    }
+    /// Types `text` one character at a time, running a frame after each.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.send(messages::WindowKeyboardCharacter::new(c.to_string()));
+            self.step();
+        }
+    }
+    /// Sends a message to the world, as the host would for window events.
+    pub fn send(&mut self, message: impl ambient_ecs::Message) {
+        self.world.resource_mut(world_events()).add_message(message);
+    }
+    /// The window commands (such as cursor changes) sent since the last call.
+    pub fn window_ctls(&mut self) -> Vec<WindowCtl> {
+        self.window_ctl_rx.drain().collect()
+    }
+
+    /// Describes the tree as one line per entity, with its text or layout, position and size.
+    pub fn snapshot(&self) -> String {
+        let mut lines = Vec::new();
+        self.visit(self.root(), 0, &mut |id, depth| {
+            let label = if let Ok(text) = self.world.get_ref(id, text()) {
+                format!("{text:?}")
+            } else if let Ok(layout) = self.world.get(id, layout()) {
+                format!("{layout:?}")
+            } else {
+                "Entity".to_string()
+            };
+            let Bounds { position, size } = self.bounds(id);
+            lines.push(format!(
+                "{}{label} ({}, {}) {}x{}",
+                "  ".repeat(depth),
+                position.x,
+                position.y,
+                size.x,
+                size.y
+            ));
+        });
+        lines.join("\n")
+    }
+    /// Compares [snapshot](Self::snapshot) with `tests/snapshots/<name>.snap` of the crate under
+    /// test. Fails if the file doesn't exist; set `UPDATE_SNAPSHOTS` to write it instead.
+    pub fn assert_snapshot(&self, name: &str) {
+        let snapshot = self.snapshot();
+        let path = PathBuf::from(
+            std::env::var("CARGO_MANIFEST_DIR").expect("snapshots must be run through cargo"),
+        )
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{name}.snap"));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, snapshot + "\n").unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+            panic!(
+                "snapshot {name} can't be read ({err}); rerun with UPDATE_SNAPSHOTS=1 to write it"
+            )
+        });
+        assert_eq!(
+            expected.trim_end(),
+            snapshot,
+            "snapshot {name} changed; rerun with UPDATE_SNAPSHOTS=1 to accept it",
+        );
+    }
+
+    fn visit(&self, id: EntityId, depth: usize, f: &mut impl FnMut(EntityId, usize)) {
+        f(id, depth);
+        for child in self
+            .world
+            .get_ref(id, children())
+            .cloned()
+            .unwrap_or_default()
+        {
+            self.visit(child, depth + 1, f);
+        }
+    }
+    fn depth(&self, mut id: EntityId) -> usize {
+        let mut depth = 0;
+        while let Ok(parent) = self.world.get(id, parent()) {
+            id = parent;
+            depth += 1;
+        }
+        depth
+    }
+    /// Picks the frontmost pickable entity under the mouse, like the host's picking does with a
+    /// UI camera.
+    fn update_mouse_over(&mut self) {
+        let mouse = *self.world.resource(cursor_position());
+        let hit = query((mouse_pickable(), local_to_world()))
+            .iter(&self.world, None)
+            .filter(|(_, (pickable, transform))| {
+                let local = transform
+                    .inverse()
+                    .transform_point3(vec3(mouse.x, mouse.y, 0.));
+                (pickable.min.x..=pickable.max.x).contains(&local.x)
+                    && (pickable.min.y..=pickable.max.y).contains(&local.y)
+            })
+            .map(|(id, (_, transform))| (id, transform.w_axis.z))
+            .collect_vec()
+            .into_iter()
+            // UI in front has a lower z, and children are drawn over their parents
+            .min_by(|(a, a_z), (b, b_z)| {
+                a_z.total_cmp(b_z)
+                    .then_with(|| self.depth(*b).cmp(&self.depth(*a)))
+            })
+            .map(|(id, _)| id)
+            .unwrap_or_else(EntityId::null);
+
+        let previous = *self.world.resource(mouse_over_entity());
+        if previous == hit {
+            return;
+        }
+        if self.world.exists(previous) {
+            self.world.remove_component(previous, is_mouse_over()).ok();
+        }
+        if !hit.is_null() {
+            self.world.add_component(hit, is_mouse_over(), 1).unwrap();
+        }
+        *self.world.resource_mut(mouse_over_entity()) = hit;
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::{Arc, Mutex};
+
+use ambient_ecs::{
+    generated::layout::components::{height, space_between_items, width},
+    EntityId, World,
+};
+use ambient_element::{element_component, to_owned, Element, ElementComponentExt, Hooks};
+use ambient_shared_types::MouseButton;
+use ambient_ui::{
+    drag_drop::{use_world_drop_target, DragSource, DropTarget},
+    layout::{FlowColumn, FlowRow},
+    prelude::cb,
+    text::Text,
+    UIExt,
+};
+use ambient_ui_testing::UiTestHarness;
+use glam::{vec2, Vec2};
+
+/// The drops received by each target, with the dropped payload.
+type Drops = Arc<Mutex<Vec<(&'static str, u32)>>>;
+
+fn label(content: &str) -> Element {
+    // Fonts aren't loaded, so the texts are given a size
+    Text::el(content).with(width(), 20.).with(height(), 20.)
+}
+
+#[element_component]
+fn Scene(hooks: &mut Hooks, drops: Drops) -> Element {
+    use_world_drop_target(
+        hooks,
+        None,
+        cb({
+            to_owned![drops];
+            move |_: &mut World, payload: u32, picked: EntityId| {
+                assert!(picked.is_null());
+                drops.lock().unwrap().push(("world", payload));
+            }
+        }),
+    );
+    let target = |name: &'static str, inner: Element| {
+        DropTarget::new(inner, {
+            to_owned![drops];
+            move |_, payload: u32| drops.lock().unwrap().push((name, payload))
+        })
+    };
+    FlowRow::el([
+        DragSource::new(label("even"), 2_u32).el(),
+        DragSource::new(label("odd"), 1_u32).el(),
+        // The outer target only accepts even payloads, and has a pickable child that isn't a target
+        target(
+            "outer",
+            FlowColumn::el([
+                label("child").with_clickarea().el(),
+                target("inner", label("inner")).el(),
+            ]),
+        )
+        .accept(|payload| payload % 2 == 0)
+        .el(),
+    ])
+    .with(space_between_items(), 10.)
+}
+
+fn center(harness: &UiTestHarness, content: &str) -> Vec2 {
+    harness.bounds(harness.find_text(content).unwrap()).center()
+}
+
+fn drag(harness: &mut UiTestHarness, source: &str, to: Vec2) {
+    harness.move_mouse(center(harness, source));
+    harness.mouse_input(MouseButton::Left, true);
+    harness.move_mouse(to);
+    harness.mouse_input(MouseButton::Left, false);
+}
+
+#[test]
+fn drops_go_to_the_closest_target() {
+    let drops = Drops::default();
+    let mut harness = UiTestHarness::new(Scene::el(drops.clone()));
+    let take = || std::mem::take(&mut *drops.lock().unwrap());
+
+    // A child that isn't a target resolves to its closest ancestor target
+    let child = center(&harness, "child");
+    drag(&mut harness, "even", child);
+    assert_eq!(take(), [("outer", 2)]);
+
+    // A nested target takes the drop instead of the target it's in
+    let inner = center(&harness, "inner");
+    drag(&mut harness, "even", inner);
+    assert_eq!(take(), [("inner", 2)]);
+}
+
+#[test]
+fn accept_predicates() {
+    let drops = Drops::default();
+    let mut harness = UiTestHarness::new(Scene::el(drops.clone()));
+    let take = || std::mem::take(&mut *drops.lock().unwrap());
+
+    // The closest target decides, so a rejected payload doesn't fall through to the world
+    let child = center(&harness, "child");
+    drag(&mut harness, "odd", child);
+    assert!(take().is_empty());
+
+    // The inner target accepts what the outer one rejects
+    let inner = center(&harness, "inner");
+    drag(&mut harness, "odd", inner);
+    assert_eq!(take(), [("inner", 1)]);
+}
+
+#[test]
+fn drops_outside_of_targets_go_to_the_world() {
+    let drops = Drops::default();
+    let mut harness = UiTestHarness::new(Scene::el(drops.clone()));
+
+    drag(&mut harness, "odd", vec2(700., 500.));
+    assert_eq!(*drops.lock().unwrap(), [("world", 1)]);
+
+    // Moving less than the drag threshold is a click, which drops nothing
+    let source = center(&harness, "even");
+    drag(&mut harness, "even", source + 1.);
+    assert_eq!(*drops.lock().unwrap(), [("world", 1)]);
+}
@@ -0,0 +1,179 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use ambient_ecs::generated::{
+    layout::components::{height, space_between_items, width},
+    ui::{
+        components::{focus, focusable},
+        messages::Activate,
+    },
+};
+use ambient_element::{element_component, use_state, Element, ElementComponentExt, Hooks};
+use ambient_shared_types::{ModifiersState, VirtualKeyCode};
+use ambient_ui::{
+    button::Button,
+    editor::TextEditor,
+    layout::{FlowColumn, FlowRow},
+    text::Text,
+    Rectangle, UIExt,
+};
+use ambient_ui_testing::UiTestHarness;
+use glam::vec2;
+
+#[test]
+fn flow_row_layout() {
+    let harness = UiTestHarness::new(
+        FlowRow::el([
+            Rectangle.el().with(width(), 10.).with(height(), 20.),
+            Rectangle.el().with(width(), 30.).with(height(), 5.),
+        ])
+        .with(space_between_items(), 5.),
+    );
+
+    let entities = harness.entities();
+    assert_eq!(entities.len(), 3);
+    assert_eq!(harness.bounds(entities[0]).size, vec2(45., 20.));
+    assert_eq!(harness.bounds(entities[1]).position, vec2(0., 0.));
+    assert_eq!(harness.bounds(entities[2]).position, vec2(15., 0.));
+}
+
+#[test]
+fn flow_column_snapshot() {
+    // Fonts aren't loaded, so the texts are given a size
+    let harness = UiTestHarness::new(
+        FlowColumn::el([
+            Text::el("Title").with(width(), 40.).with(height(), 10.),
+            Rectangle.el().with(width(), 10.).with(height(), 20.),
+            Text::el("Body").with(width(), 30.).with(height(), 8.),
+        ])
+        .with(space_between_items(), 4.),
+    );
+
+    assert_eq!(
+        harness.texts(),
+        vec!["Title".to_string(), "Body".to_string()]
+    );
+    harness.assert_snapshot("flow_column");
+}
+
+#[test]
+fn click() {
+    let clicks = Arc::new(AtomicU32::new(0));
+    let mut harness = UiTestHarness::new(
+        Rectangle
+            .el()
+            .with_clickarea()
+            .on_mouse_up({
+                let clicks = clicks.clone();
+                move |_, _, _| {
+                    clicks.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .el(),
+    );
+
+    harness.click(harness.root());
+    assert_eq!(clicks.load(Ordering::SeqCst), 1);
+
+    // Releasing outside of the area doesn't count
+    harness.mouse_input(ambient_shared_types::MouseButton::Left, true);
+    harness.move_mouse(vec2(500., 500.));
+    harness.mouse_input(ambient_shared_types::MouseButton::Left, false);
+    assert_eq!(clicks.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn text_editor_input() {
+    #[element_component]
+    fn Editor(hooks: &mut Hooks) -> Element {
+        let (value, set_value) = use_state(hooks, String::new());
+        TextEditor::new(value, set_value).auto_focus().el()
+    }
+
+    let mut harness = UiTestHarness::new(Editor.el());
+    harness.steps(2);
+
+    harness.type_text("hi!");
+    harness.step();
+    assert_eq!(harness.texts(), vec!["hi!".to_string()]);
+
+    harness.press_key(VirtualKeyCode::Back);
+    harness.step();
+    assert_eq!(harness.texts(), vec!["hi".to_string()]);
+}
+
+#[test]
+fn focus_order() {
+    let item = |id: &str| {
+        Rectangle
+            .el()
+            .with(width(), 10.)
+            .with(height(), 10.)
+            .with(focusable(), id.to_string())
+    };
+    // Depth first, the items are b, d, c
+    let mut harness = UiTestHarness::new(
+        FlowRow::el([FlowColumn::el([item("b"), item("d")]), item("c")])
+            .with(space_between_items(), 5.),
+    );
+
+    // Top to bottom, and then left to right
+    let order = ambient_focus::focus_order(&harness.world)
+        .into_iter()
+        .map(|id| harness.world.get_cloned(id, focusable()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(order, ["b", "c", "d"]);
+
+    // Tab moves through them in that order, and wraps around
+    let mut visited = Vec::new();
+    for _ in 0..4 {
+        harness.press_key(VirtualKeyCode::Tab);
+        visited.push(harness.world.resource(focus()).clone());
+    }
+    assert_eq!(visited, ["b", "c", "d", "b"]);
+
+    harness.set_modifiers(ModifiersState::SHIFT);
+    harness.press_key(VirtualKeyCode::Tab);
+    assert_eq!(harness.world.resource(focus()), "d");
+}
+
+#[test]
+fn button_keyboard_and_activate() {
+    let presses = Arc::new(AtomicU32::new(0));
+    let mut harness = UiTestHarness::new(
+        Button::new(Rectangle.el().with(width(), 40.).with(height(), 20.), {
+            let presses = presses.clone();
+            move |_| {
+                presses.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .el(),
+    );
+    let button = harness
+        .find(|world, id| world.has_component(id, focusable()))
+        .unwrap();
+    let focus_id = harness.world.get_cloned(button, focusable()).unwrap();
+
+    // Clicking focuses the button, but doesn't let space press it again
+    harness.click(button);
+    assert_eq!(harness.world.resource(focus()), &focus_id);
+    assert_eq!(presses.load(Ordering::SeqCst), 1);
+    harness.press_key(VirtualKeyCode::Space);
+    assert_eq!(presses.load(Ordering::SeqCst), 1);
+
+    // Focused with tab, it is pressed with space and enter
+    harness.press_key(VirtualKeyCode::Tab);
+    assert_eq!(harness.world.resource(focus()), &focus_id);
+    harness.press_key(VirtualKeyCode::Space);
+    harness.press_key(VirtualKeyCode::Return);
+    assert_eq!(presses.load(Ordering::SeqCst), 3);
+
+    // Assistive technologies press it by its focus id
+    harness.send(Activate::new(focus_id));
+    harness.step();
+    harness.send(Activate::new("another button"));
+    harness.step();
+    assert_eq!(presses.load(Ordering::SeqCst), 4);
+}
@@ -0,0 +1,119 @@
+use std::sync::{Arc, Mutex};
+
+use ambient_core::hierarchy::{children, parent};
+use ambient_ecs::{generated::text::components::text, EntityId};
+use ambient_element::ElementComponentExt;
+use ambient_shared_types::MouseButton;
+use ambient_ui::{
+    default_theme::{CHEVRON_DOWN, CHEVRON_UP},
+    text::Text,
+    virtual_list::{SortOrder, TableColumn, VirtualTable},
+};
+use ambient_ui_testing::UiTestHarness;
+use glam::vec2;
+
+fn table(sorts: Arc<Mutex<Vec<(usize, SortOrder)>>>) -> UiTestHarness {
+    UiTestHarness::new(
+        VirtualTable::new(
+            vec![
+                TableColumn::new("Name", 100.).sortable(),
+                TableColumn::new("Size", 60.).sortable(),
+                TableColumn::new("Notes", 80.),
+            ],
+            3,
+            |row, column| Text::el(format!("{row}:{column}")),
+        )
+        .on_sort(move |column, order| sorts.lock().unwrap().push((column, order)))
+        .el(),
+    )
+}
+
+/// The clickable title of a column's header, which holds its text.
+fn header_title(harness: &UiTestHarness, title: &str) -> EntityId {
+    let text = harness.find_text(title).unwrap();
+    harness.world.get(text, parent()).unwrap()
+}
+
+/// The handle right of a column's title that resizes it.
+fn resize_handle(harness: &UiTestHarness, title: &str) -> EntityId {
+    let column = harness
+        .world
+        .get(header_title(harness, title), parent())
+        .unwrap();
+    harness.world.get_ref(column, children()).unwrap()[1]
+}
+
+#[test]
+fn header_clicks_cycle_the_sort_order() {
+    let sorts = Arc::new(Mutex::new(Vec::new()));
+    let mut harness = table(sorts.clone());
+    let chevrons = |harness: &UiTestHarness| {
+        harness
+            .texts()
+            .into_iter()
+            .filter(|text| text == CHEVRON_UP || text == CHEVRON_DOWN)
+            .collect::<Vec<_>>()
+    };
+    assert!(chevrons(&harness).is_empty());
+
+    for _ in 0..3 {
+        harness.click(header_title(&harness, "Name"));
+        harness.step();
+    }
+    assert_eq!(
+        *sorts.lock().unwrap(),
+        [
+            (0, SortOrder::Ascending),
+            (0, SortOrder::Descending),
+            (0, SortOrder::Ascending)
+        ]
+    );
+    assert_eq!(chevrons(&harness), [CHEVRON_UP]);
+
+    // Sorting by another column starts ascending, and moves the chevron there
+    harness.click(header_title(&harness, "Size"));
+    harness.step();
+    assert_eq!(
+        sorts.lock().unwrap().last(),
+        Some(&(1, SortOrder::Ascending))
+    );
+    let size_title = header_title(&harness, "Size");
+    let size_chevron = harness.world.get_ref(size_title, children()).unwrap()[1];
+    assert_eq!(
+        harness.world.get_cloned(size_chevron, text()).unwrap(),
+        CHEVRON_UP
+    );
+    assert_eq!(chevrons(&harness), [CHEVRON_UP]);
+
+    // Columns that aren't sortable ignore clicks
+    harness.click(header_title(&harness, "Notes"));
+    harness.step();
+    assert_eq!(sorts.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn columns_resize_down_to_a_minimum_width() {
+    let mut harness = table(Default::default());
+    let title_width =
+        |harness: &UiTestHarness| harness.bounds(header_title(harness, "Name")).size.x;
+    // The title is the width of the column minus the resize handle
+    assert_eq!(title_width(&harness), 96.);
+
+    let handle = harness.bounds(resize_handle(&harness, "Name")).center();
+    harness.move_mouse(handle);
+    harness.mouse_input(MouseButton::Left, true);
+    harness.move_mouse(handle + vec2(50., 0.));
+    harness.steps(2);
+    assert_eq!(title_width(&harness), 146.);
+
+    // Columns can't be narrower than the minimum width
+    harness.move_mouse(handle - vec2(500., 0.));
+    harness.steps(2);
+    assert_eq!(title_width(&harness), 16.);
+
+    // Moving the mouse after releasing it doesn't resize the column anymore
+    harness.mouse_input(MouseButton::Left, false);
+    harness.move_mouse(handle);
+    harness.steps(2);
+    assert_eq!(title_width(&harness), 16.);
+}
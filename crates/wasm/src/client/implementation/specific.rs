@@ -14,11 +14,11 @@ use ambient_core::{
 use ambient_ecs::generated::input::messages::ClipboardGet;
 use ambient_gpu::texture::Texture;
 use ambient_input::{player_prev_raw_input, player_raw_input};
-use ambient_native_std::mesh::MeshBuilder;
+use ambient_native_std::mesh::{MeshBuilder, MeshUpdate};
 use ambient_network::client::client_state;
 use ambient_procedurals::{
     new_material_handle, new_mesh_handle, new_sampler_handle, new_texture_handle,
-    procedural_storage,
+    procedural_storage, ProceduralMeshChange,
 };
 use ambient_renderer::pbr_material::{PbrMaterialConfig, PbrMaterialParams};
 
@@ -274,6 +274,71 @@ impl wit::client_mesh::Host for Bindings {
         storage.meshes.insert(mesh_handle, mesh);
         Ok(mesh_handle.into_bindgen())
     }
+    fn create_extended(
+        &mut self,
+        desc: wit::client_mesh::ExtendedDescriptor,
+    ) -> anyhow::Result<Result<wit::client_mesh::Handle, String>> {
+        let wit::client_mesh::ExtendedDescriptor {
+            attributes,
+            indices,
+        } = desc;
+        let attributes = mesh_attributes(attributes);
+        let mesh = MeshBuilder {
+            positions: attributes.positions,
+            colors: attributes.colors,
+            normals: attributes.normals,
+            tangents: attributes.tangents,
+            texcoords: attributes.texcoords,
+            joint_indices: attributes.joint_indices,
+            joint_weights: attributes.joint_weights,
+            indices,
+        }
+        .build();
+        let mesh = match mesh {
+            Ok(mesh) => mesh,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+
+        let world = self.world_mut();
+        let storage = world.resource_mut(procedural_storage());
+        let mesh_handle = new_mesh_handle();
+        storage.meshes.insert(mesh_handle, mesh);
+        Ok(Ok(mesh_handle.into_bindgen()))
+    }
+    fn update(
+        &mut self,
+        handle: wit::client_mesh::Handle,
+        desc: wit::client_mesh::UpdateDescriptor,
+    ) -> anyhow::Result<Result<(), String>> {
+        let wit::client_mesh::UpdateDescriptor {
+            vertex_offset,
+            attributes,
+            indices,
+        } = desc;
+        let indices_changed = indices.is_some();
+        let update = MeshUpdate {
+            vertex_offset: vertex_offset as usize,
+            indices,
+            ..mesh_attributes(attributes)
+        };
+
+        let handle = handle.from_bindgen();
+        let world = self.world_mut();
+        let storage = world.resource_mut(procedural_storage());
+        let Some(mesh) = storage.meshes.try_get_mut(handle) else {
+            return Ok(Err("unknown mesh handle".into()));
+        };
+        let vertices = match mesh.apply_update(update) {
+            Ok(vertices) => vertices,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+        storage.mesh_changes.push(ProceduralMeshChange {
+            handle,
+            vertices,
+            indices_changed,
+        });
+        Ok(Ok(()))
+    }
     fn destroy(&mut self, handle: wit::client_mesh::Handle) -> anyhow::Result<()> {
         let world = self.world_mut();
         let storage = world.resource_mut(procedural_storage());
@@ -281,6 +346,35 @@ impl wit::client_mesh::Host for Bindings {
         Ok(())
     }
 }
+/// Converts the guest's mesh attributes into the vertex streams of a [MeshUpdate].
+fn mesh_attributes(attributes: wit::client_mesh::Attributes) -> MeshUpdate {
+    let wit::client_mesh::Attributes {
+        positions,
+        normals,
+        tangents,
+        texcoord0,
+        texcoord1,
+        colors,
+        joint_indices,
+        joint_weights,
+    } = attributes;
+    // An empty set is left out, unless it keeps the second set at its index
+    let texcoords = match (texcoord0.is_empty(), texcoord1.is_empty()) {
+        (true, true) => vec![],
+        (false, true) => vec![texcoord0.from_bindgen()],
+        _ => vec![texcoord0.from_bindgen(), texcoord1.from_bindgen()],
+    };
+    MeshUpdate {
+        positions: positions.from_bindgen(),
+        colors: colors.from_bindgen(),
+        normals: normals.from_bindgen(),
+        tangents: tangents.from_bindgen(),
+        texcoords,
+        joint_indices: joint_indices.from_bindgen(),
+        joint_weights: joint_weights.from_bindgen(),
+        ..Default::default()
+    }
+}
 impl wit::client_texture::Host for Bindings {
     fn create2d(
         &mut self,
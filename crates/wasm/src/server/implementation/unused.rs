@@ -94,6 +94,19 @@ impl wit::client_mesh::Host for Bindings {
     ) -> anyhow::Result<wit::client_mesh::Handle> {
         unsupported()
     }
+    fn create_extended(
+        &mut self,
+        _desc: wit::client_mesh::ExtendedDescriptor,
+    ) -> anyhow::Result<Result<wit::client_mesh::Handle, String>> {
+        unsupported()
+    }
+    fn update(
+        &mut self,
+        _handle: wit::client_mesh::Handle,
+        _desc: wit::client_mesh::UpdateDescriptor,
+    ) -> anyhow::Result<Result<(), String>> {
+        unsupported()
+    }
     fn destroy(&mut self, _handle: wit::client_mesh::Handle) -> anyhow::Result<()> {
         unsupported()
     }
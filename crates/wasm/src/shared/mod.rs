@@ -20,8 +20,13 @@ use std::{path::Path, str::FromStr, sync::Arc};
 
 use ambient_core::{asset_cache, async_ecs::async_run, hierarchy::despawn_recursive, runtime};
 use ambient_ecs::{
-    dont_despawn_on_unload, generated::messages, query, world_events, EntityId, FnSystem, Message,
-    SystemGroup, World, WorldContext, WorldEventReader,
+    dont_despawn_on_unload,
+    generated::{
+        messages,
+        ui::messages::{LoadLocale, LocaleLoaded},
+    },
+    query, world_events, EntityId, FnSystem, Message, SystemGroup, World, WorldContext,
+    WorldEventReader, WorldEventSource,
 };
 
 pub use ambient_ecs::generated::wasm::components::*;
@@ -96,6 +101,7 @@ pub enum MessageType {
 
 pub fn systems() -> SystemGroup {
     let mut app_events_reader = WorldEventReader::new();
+    let mut locale_reader = WorldEventReader::new();
 
     SystemGroup::new(
         "core/wasm",
@@ -196,6 +202,22 @@ pub fn systems() -> SystemGroup {
                     }
                 },
             ),
+            Box::new(FnSystem::new(move |world, _| {
+                profiling::scope!("WASM module locales");
+                let requests = locale_reader
+                    .iter(world.resource(world_events()))
+                    .filter(|(_, (_, name, _))| *name == LoadLocale::id())
+                    .filter_map(|(_, (source, _, data))| {
+                        Some((source.clone(), LoadLocale::deserialize_message(data).ok()?))
+                    })
+                    .collect_vec();
+
+                for (source, request) in requests {
+                    if let WorldEventSource::Local(module_id) = source {
+                        load_locale(world, module_id, request);
+                    }
+                }
+            })),
             Box::new(FnSystem::new(move |world, _| {
                 profiling::scope!("WASM module app events");
                 let events = app_events_reader
@@ -234,6 +256,43 @@ pub fn systems() -> SystemGroup {
     )
 }
 
+/// Reads the string table requested with [LoadLocale] from the assets of its package, and sends it
+/// back to the module that asked for it with [LocaleLoaded].
+fn load_locale(world: &World, module_id: EntityId, request: LoadLocale) {
+    let LoadLocale { package, locale } = request;
+    // The locale ends up in a path, so it can only be a language tag like `en` or `pt-BR`
+    let url = if !locale.is_empty()
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        ambient_package_semantic_native::file_path(
+            world,
+            package,
+            Path::new(&format!("assets/locales/{locale}.ftl")),
+        )
+        .map_err(anyhow::Error::from)
+    } else {
+        Err(anyhow::anyhow!("Invalid locale {locale:?}"))
+    };
+
+    let assets = world.resource(asset_cache()).clone();
+    let async_run = world.resource(async_run()).clone();
+    world.resource(runtime()).spawn(async move {
+        let source = match url {
+            Ok(url) => url.download_string(&assets).await,
+            Err(err) => Err(err),
+        };
+        let response = match source {
+            Ok(source) => LocaleLoaded::new(package, locale, source, None),
+            Err(err) => LocaleLoaded::new(package, locale, "", Some(format!("{err:#}"))),
+        };
+        async_run.run(move |world| {
+            message::MessageExt::send(response, world, Some(module_id)).ok();
+        });
+    });
+}
+
 pub type ModuleStateMaker = Arc<
     dyn Fn(ModuleStateArgs<'_>) -> PlatformBoxFuture<anyhow::Result<ModuleState>> + Send + Sync,
 >;
@@ -22,6 +22,9 @@ export type Pipeline = {
       type: "UnityModels",
       /// Whether or not the Unity prefabs should be converted to Ambient prefabs.
       use_prefabs: boolean,
+    } | {
+      /// Import Unity scenes (.unity), including prefab instances and their overrides, directional lights and colliders.
+      type: "UnityScenes",
     } | {
       /// Import Quixel models.
       type: "Quixel",
@@ -47,6 +50,14 @@ export type Pipeline = {
       radius?: f32,
       /// The height of the collider.
       height?: f32,
+    } | {
+      /// Split the model into convex pieces, which can be used by dynamic objects.
+      type: "ConvexDecomposition",
+      /// The maximum number of convex pieces to produce.
+      max_hulls?: u32,
+      /// The number of voxels along the longest axis of the model used to find the pieces.
+      /// Higher values are more accurate, but slower to process.
+      resolution?: u32,
     },
     /// If a collider is present, this controls how it will interact with other colliders.
     collider_type?: 
@@ -187,6 +198,11 @@ export type Pipeline = {
       /// Re-center this mesh such that the root is located at the origin.
       type: "Center",
     })[],
+    /// If specified, generates simplified LODs for meshes which don't have any.
+    /// 
+    /// Each entry is the fraction of the original triangles to keep for that LOD, i.e. `[0.5, 0.2, 0.05]`.
+    /// The distances at which the LODs are switched are picked automatically.
+    generate_lods?: f32[],
   } | {
     /// The materials asset pipeline.
     /// Will import specific materials without needing to be part of a model.
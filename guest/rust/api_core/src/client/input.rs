@@ -1,14 +1,20 @@
 use std::collections::HashSet;
 
 use crate::{
-    core::ui::{components::focus, messages::FocusChanged},
+    core::{
+        input::{
+            components::{input_action, input_axis, input_previous_value, input_value},
+            messages::InputRebind,
+        },
+        ui::{components::focus, messages::FocusChanged},
+    },
     entity,
     global::{CursorIcon, Vec2},
     internal::{
         conversion::{FromBindgen, IntoBindgen},
         wit,
     },
-    prelude::ModuleMessage,
+    prelude::{Component, ModuleMessage},
 };
 
 pub use ambient_shared_types::MouseButton;
@@ -70,6 +76,66 @@ pub fn set_focus(focus_id: impl Into<String>) {
     .send_local_broadcast(true);
 }
 
+/// Returns true if the input action `name` is currently triggered.
+///
+/// Actions are declared in the `[actions]` section of a package's `ambient.toml`. If more than one
+/// package declares an action with this name, any of them being triggered counts.
+pub fn action(name: &str) -> bool {
+    values(input_action(), name).any(|(value, _)| value > 0.0)
+}
+
+/// Returns true if the input action `name` was triggered in the most recent update.
+pub fn action_pressed(name: &str) -> bool {
+    values(input_action(), name).any(|(value, previous)| value > 0.0 && previous <= 0.0)
+}
+
+/// Returns true if the input action `name` stopped being triggered in the most recent update.
+pub fn action_released(name: &str) -> bool {
+    values(input_action(), name).any(|(value, previous)| value <= 0.0 && previous > 0.0)
+}
+
+/// Returns the value of the input axis `name`, in the range `-1.0..=1.0`.
+///
+/// Axes are declared in the `[axes]` section of a package's `ambient.toml`.
+pub fn axis(name: &str) -> f32 {
+    values(input_axis(), name)
+        .map(|(value, _)| value)
+        .fold(0.0, |a, b| if b.abs() > a.abs() { b } else { a })
+}
+
+/// Replaces the bindings of this package's input action `name`, and saves them to the player's settings.
+///
+/// Bindings use the same format as the manifest: `"Space"`, `"Mouse.Left"`, `"Gamepad.South"`, etc.
+pub fn rebind_action(name: &str, bindings: &[&str]) {
+    rebind_axis(name, bindings, &[]);
+}
+
+/// Replaces the bindings of this package's input axis `name`, and saves them to the player's settings.
+pub fn rebind_axis(name: &str, positive: &[&str], negative: &[&str]) {
+    let to_vec = |bindings: &[&str]| bindings.iter().map(|b| b.to_string()).collect::<Vec<_>>();
+    InputRebind::new(name, to_vec(positive), to_vec(negative)).send_local_broadcast(false);
+}
+
+/// Restores the bindings of this package's input action or axis `name` to the defaults from its manifest.
+pub fn reset_bindings(name: &str) {
+    rebind_axis(name, &[], &[]);
+}
+
+fn values<'a>(
+    component: Component<String>,
+    name: &'a str,
+) -> impl Iterator<Item = (f32, f32)> + 'a {
+    entity::get_all(component)
+        .into_iter()
+        .filter(move |&id| entity::get_component(id, component).as_deref() == Some(name))
+        .map(|id| {
+            (
+                entity::get_component(id, input_value()).unwrap_or_default(),
+                entity::get_component(id, input_previous_value()).unwrap_or_default(),
+            )
+        })
+}
+
 #[allow(missing_docs)]
 /// The code associated with a key on the keyboard.
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
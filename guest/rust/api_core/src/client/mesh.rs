@@ -1,4 +1,4 @@
-use crate::global::{ProceduralMeshHandle, Vec2, Vec3};
+use crate::global::{ProceduralMeshHandle, UVec4, Vec2, Vec3, Vec4};
 use crate::internal::conversion::*;
 use crate::internal::wit;
 
@@ -39,10 +39,99 @@ impl<'a> IntoBindgen for &'a Descriptor<'a> {
     }
 }
 
+/// Per-vertex attributes of a mesh.
+///
+/// Every non-empty attribute must have one entry per vertex, and `texcoord1` needs `texcoord0`. `joint_indices` and `joint_weights` index
+/// into the `joints` component of the entity the mesh is attached to, which skins it against those joints.
+#[derive(Clone, Default, Debug)]
+pub struct Attributes {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub tangents: Vec<Vec3>,
+    pub texcoord0: Vec<Vec2>,
+    pub texcoord1: Vec<Vec2>,
+    pub colors: Vec<Vec4>,
+    pub joint_indices: Vec<UVec4>,
+    pub joint_weights: Vec<Vec4>,
+}
+impl IntoBindgen for &Attributes {
+    type Item = wit::client_mesh::Attributes;
+
+    fn into_bindgen(self) -> Self::Item {
+        Self::Item {
+            positions: self.positions.clone().into_bindgen(),
+            normals: self.normals.clone().into_bindgen(),
+            tangents: self.tangents.clone().into_bindgen(),
+            texcoord0: self.texcoord0.clone().into_bindgen(),
+            texcoord1: self.texcoord1.clone().into_bindgen(),
+            colors: self.colors.clone().into_bindgen(),
+            joint_indices: self.joint_indices.clone().into_bindgen(),
+            joint_weights: self.joint_weights.clone().into_bindgen(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct ExtendedDescriptor {
+    pub attributes: Attributes,
+    pub indices: Vec<u32>,
+}
+impl IntoBindgen for &ExtendedDescriptor {
+    type Item = wit::client_mesh::ExtendedDescriptor;
+
+    fn into_bindgen(self) -> Self::Item {
+        Self::Item {
+            attributes: self.attributes.into_bindgen(),
+            indices: self.indices.clone(),
+        }
+    }
+}
+
+/// A partial update of a mesh's vertices. See [update].
+#[derive(Clone, Default, Debug)]
+pub struct Update {
+    /// The first vertex to replace.
+    pub vertex_offset: u32,
+    /// The vertices to write, starting at `vertex_offset`. Empty attributes are left unchanged;
+    /// writing past the end of the mesh appends vertices.
+    pub attributes: Attributes,
+    /// Replaces all of the mesh's indices, if set.
+    pub indices: Option<Vec<u32>>,
+}
+impl IntoBindgen for &Update {
+    type Item = wit::client_mesh::UpdateDescriptor;
+
+    fn into_bindgen(self) -> Self::Item {
+        Self::Item {
+            vertex_offset: self.vertex_offset,
+            attributes: self.attributes.into_bindgen(),
+            indices: self.indices.clone(),
+        }
+    }
+}
+
 pub fn create(desc: &Descriptor) -> ProceduralMeshHandle {
     wit::client_mesh::create(&desc.into_bindgen()).from_bindgen()
 }
 
+/// Creates a mesh with any combination of vertex attributes, including vertex colors, a second set of
+/// texture coordinates, and joint indices and weights for skinning.
+///
+/// Fails if there are no positions or indices, if an attribute does not have one entry per vertex, or if only
+/// one of `joint_indices` and `joint_weights` is set.
+pub fn create_extended(desc: &ExtendedDescriptor) -> Result<ProceduralMeshHandle, String> {
+    wit::client_mesh::create_extended(&desc.into_bindgen()).map(|handle| handle.from_bindgen())
+}
+
+/// Replaces a range of vertices of an existing mesh in place. Entities using the mesh keep their handle
+/// and are updated on the next frame.
+///
+/// Fails if the update does not leave every attribute with the same number of vertices, or if an index is
+/// out of bounds; the mesh is left unchanged in that case.
+pub fn update(handle: ProceduralMeshHandle, update: &Update) -> Result<(), String> {
+    wit::client_mesh::update(handle.into_bindgen(), &update.into_bindgen())
+}
+
 pub fn destroy(handle: ProceduralMeshHandle) {
     wit::client_mesh::destroy(handle.into_bindgen());
 }
@@ -0,0 +1,80 @@
+#[cfg(feature = "client")]
+use crate::core::ui::messages::SetLocale;
+use crate::{
+    core::ui::{
+        components::locale as locale_component,
+        messages::{LoadLocale, LocaleLoaded},
+    },
+    entity,
+    global::wait_for_runtime_message,
+    prelude::{EntityId, ModuleMessage},
+};
+
+pub use ambient_i18n::{
+    add_locale, has_locale, locales, ParseError, PluralCategory, Translations, Value,
+    DEFAULT_LOCALE,
+};
+
+/// Gets the locale of the app, like `en` or `pt-BR`.
+///
+/// On the server, this is [DEFAULT_LOCALE].
+pub fn locale() -> String {
+    entity::get_component(entity::resources(), locale_component())
+        .unwrap_or_else(ambient_i18n::locale)
+}
+
+/// Changes the locale of the app, and saves it to the player's settings.
+///
+/// The change is applied on the next frame; UI that uses `use_locale` is re-rendered with the new
+/// locale.
+#[cfg(feature = "client")]
+pub fn set_locale(locale: &str) {
+    SetLocale::new(locale).send_local_broadcast(false);
+}
+
+/// Loads the string table of `locale` from the `assets/locales/<locale>.ftl` asset of `package`, usually
+/// `packages::this::entity()`, and adds it with [add_locale].
+///
+/// ```ignore
+/// i18n::load_locale(packages::this::entity(), "fr").await?;
+/// ```
+pub async fn load_locale(package: EntityId, locale: &str) -> anyhow::Result<()> {
+    LoadLocale::new(package, locale).send_local_broadcast(false);
+    let response = wait_for_runtime_message({
+        let locale = locale.to_string();
+        move |message: &LocaleLoaded| message.package == package && message.locale == locale
+    })
+    .await;
+
+    if let Some(error) = response.error {
+        anyhow::bail!("Failed to load the string table for {locale}: {error}");
+    }
+    add_locale(locale, &response.source)?;
+    Ok(())
+}
+
+/// Translates `key` in the locale of the app. See [t!](crate::t) for a shorter form.
+///
+/// The string tables of this package are added with [load_locale] from its assets, or with [add_locale]
+/// from files compiled in with `include_str!`. Keys that are missing from the locale are looked up in its
+/// parent locales and then [DEFAULT_LOCALE]; if none of them have the key, the key itself is returned.
+pub fn translate(key: &str, args: &[(&str, Value)]) -> String {
+    ambient_i18n::set_locale(&locale());
+    ambient_i18n::translate(key, args)
+}
+
+/// Translates a key in the locale of the app, with named arguments:
+///
+/// ```ignore
+/// i18n::add_locale("en", include_str!("../assets/locales/en.ftl")).unwrap();
+/// let text = t!("inbox", count = 3);
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::translate(
+            $key,
+            &[$((::std::stringify!($name), $crate::i18n::Value::from($value))),*],
+        )
+    };
+}
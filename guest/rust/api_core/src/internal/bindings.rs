@@ -378,6 +378,183 @@ pub mod ambient {
           }
         }
         #[allow(clippy::all)]
+        pub fn create_extended(desc: &ExtendedDescriptor,) -> Result<Handle,wit_bindgen::rt::string::String>{
+          
+          #[allow(unused_imports)]
+          use wit_bindgen::rt::{alloc, vec::Vec, string::String};
+          unsafe {
+            
+            #[repr(align(8))]
+            struct RetArea([u8; 72]);
+            let mut ret_area = ::core::mem::MaybeUninit::<RetArea>::uninit();
+            let ptr0 = ret_area.as_mut_ptr() as i32;let ExtendedDescriptor{ attributes:attributes1, indices:indices1, } = desc;
+            let Attributes{ positions:positions2, normals:normals2, tangents:tangents2, texcoord0:texcoord02, texcoord1:texcoord12, colors:colors2, joint_indices:joint_indices2, joint_weights:joint_weights2, } = attributes1;
+            let vec3 = positions2;
+            let ptr3 = vec3.as_ptr() as i32;
+            let len3 = vec3.len() as i32;
+            *((ptr0 + 4) as *mut i32) = len3;
+            *((ptr0 + 0) as *mut i32) = ptr3;
+            let vec4 = normals2;
+            let ptr4 = vec4.as_ptr() as i32;
+            let len4 = vec4.len() as i32;
+            *((ptr0 + 12) as *mut i32) = len4;
+            *((ptr0 + 8) as *mut i32) = ptr4;
+            let vec5 = tangents2;
+            let ptr5 = vec5.as_ptr() as i32;
+            let len5 = vec5.len() as i32;
+            *((ptr0 + 20) as *mut i32) = len5;
+            *((ptr0 + 16) as *mut i32) = ptr5;
+            let vec6 = texcoord02;
+            let ptr6 = vec6.as_ptr() as i32;
+            let len6 = vec6.len() as i32;
+            *((ptr0 + 28) as *mut i32) = len6;
+            *((ptr0 + 24) as *mut i32) = ptr6;
+            let vec7 = texcoord12;
+            let ptr7 = vec7.as_ptr() as i32;
+            let len7 = vec7.len() as i32;
+            *((ptr0 + 36) as *mut i32) = len7;
+            *((ptr0 + 32) as *mut i32) = ptr7;
+            let vec8 = colors2;
+            let ptr8 = vec8.as_ptr() as i32;
+            let len8 = vec8.len() as i32;
+            *((ptr0 + 44) as *mut i32) = len8;
+            *((ptr0 + 40) as *mut i32) = ptr8;
+            let vec9 = joint_indices2;
+            let ptr9 = vec9.as_ptr() as i32;
+            let len9 = vec9.len() as i32;
+            *((ptr0 + 52) as *mut i32) = len9;
+            *((ptr0 + 48) as *mut i32) = ptr9;
+            let vec10 = joint_weights2;
+            let ptr10 = vec10.as_ptr() as i32;
+            let len10 = vec10.len() as i32;
+            *((ptr0 + 60) as *mut i32) = len10;
+            *((ptr0 + 56) as *mut i32) = ptr10;
+            let vec11 = indices1;
+            let ptr11 = vec11.as_ptr() as i32;
+            let len11 = vec11.len() as i32;
+            *((ptr0 + 68) as *mut i32) = len11;
+            *((ptr0 + 64) as *mut i32) = ptr11;
+            let ptr12 = ret_area.as_mut_ptr() as i32;
+            #[link(wasm_import_module = "ambient:bindings/client-mesh")]
+            extern "C" {
+              #[cfg_attr(target_arch = "wasm32", link_name = "create-extended")]
+              #[cfg_attr(not(target_arch = "wasm32"), link_name = "ambient:bindings/client-mesh_create-extended")]
+              fn wit_import(
+              _: i32, _: i32, );
+            }
+            wit_import(ptr0, ptr12);
+            match i32::from(*((ptr12 + 0) as *const u8)) {
+              0 => Ok(Handle{ulid:(*((ptr12 + 8) as *const i64) as u64, *((ptr12 + 16) as *const i64) as u64), }),
+              1 => Err({
+                let len13 = *((ptr12 + 12) as *const i32) as usize;
+                
+                {#[cfg(not(debug_assertions))]{String::from_utf8_unchecked(Vec::from_raw_parts(*((ptr12 + 8) as *const i32) as *mut _, len13, len13))}#[cfg(debug_assertions)]{String::from_utf8(Vec::from_raw_parts(*((ptr12 + 8) as *const i32) as *mut _, len13, len13)).unwrap()}}
+              }),
+              #[cfg(not(debug_assertions))]
+              _ => ::core::hint::unreachable_unchecked(),
+              #[cfg(debug_assertions)]
+              _ => panic!("invalid enum discriminant"),
+            }
+          }
+        }
+        #[allow(clippy::all)]
+        pub fn update(handle: Handle,desc: &UpdateDescriptor,) -> Result<(),wit_bindgen::rt::string::String>{
+          
+          #[allow(unused_imports)]
+          use wit_bindgen::rt::{alloc, vec::Vec, string::String};
+          unsafe {
+            
+            #[repr(align(8))]
+            struct RetArea([u8; 96]);
+            let mut ret_area = ::core::mem::MaybeUninit::<RetArea>::uninit();
+            let ptr0 = ret_area.as_mut_ptr() as i32;let Handle{ ulid:ulid1, } = handle;
+            let (t2_0, t2_1, ) = ulid1;
+            *((ptr0 + 0) as *mut i64) = wit_bindgen::rt::as_i64(t2_0);
+            *((ptr0 + 8) as *mut i64) = wit_bindgen::rt::as_i64(t2_1);
+            let UpdateDescriptor{ vertex_offset:vertex_offset3, attributes:attributes3, indices:indices3, } = desc;
+            *((ptr0 + 16) as *mut i32) = wit_bindgen::rt::as_i32(vertex_offset3);
+            let Attributes{ positions:positions4, normals:normals4, tangents:tangents4, texcoord0:texcoord04, texcoord1:texcoord14, colors:colors4, joint_indices:joint_indices4, joint_weights:joint_weights4, } = attributes3;
+            let vec5 = positions4;
+            let ptr5 = vec5.as_ptr() as i32;
+            let len5 = vec5.len() as i32;
+            *((ptr0 + 24) as *mut i32) = len5;
+            *((ptr0 + 20) as *mut i32) = ptr5;
+            let vec6 = normals4;
+            let ptr6 = vec6.as_ptr() as i32;
+            let len6 = vec6.len() as i32;
+            *((ptr0 + 32) as *mut i32) = len6;
+            *((ptr0 + 28) as *mut i32) = ptr6;
+            let vec7 = tangents4;
+            let ptr7 = vec7.as_ptr() as i32;
+            let len7 = vec7.len() as i32;
+            *((ptr0 + 40) as *mut i32) = len7;
+            *((ptr0 + 36) as *mut i32) = ptr7;
+            let vec8 = texcoord04;
+            let ptr8 = vec8.as_ptr() as i32;
+            let len8 = vec8.len() as i32;
+            *((ptr0 + 48) as *mut i32) = len8;
+            *((ptr0 + 44) as *mut i32) = ptr8;
+            let vec9 = texcoord14;
+            let ptr9 = vec9.as_ptr() as i32;
+            let len9 = vec9.len() as i32;
+            *((ptr0 + 56) as *mut i32) = len9;
+            *((ptr0 + 52) as *mut i32) = ptr9;
+            let vec10 = colors4;
+            let ptr10 = vec10.as_ptr() as i32;
+            let len10 = vec10.len() as i32;
+            *((ptr0 + 64) as *mut i32) = len10;
+            *((ptr0 + 60) as *mut i32) = ptr10;
+            let vec11 = joint_indices4;
+            let ptr11 = vec11.as_ptr() as i32;
+            let len11 = vec11.len() as i32;
+            *((ptr0 + 72) as *mut i32) = len11;
+            *((ptr0 + 68) as *mut i32) = ptr11;
+            let vec12 = joint_weights4;
+            let ptr12 = vec12.as_ptr() as i32;
+            let len12 = vec12.len() as i32;
+            *((ptr0 + 80) as *mut i32) = len12;
+            *((ptr0 + 76) as *mut i32) = ptr12;
+            match indices3 {
+              Some(e) => {
+                *((ptr0 + 84) as *mut u8) = (1i32) as u8;
+                let vec13 = e;
+                let ptr13 = vec13.as_ptr() as i32;
+                let len13 = vec13.len() as i32;
+                *((ptr0 + 92) as *mut i32) = len13;
+                *((ptr0 + 88) as *mut i32) = ptr13;
+                
+              },
+              None => {
+                {
+                  *((ptr0 + 84) as *mut u8) = (0i32) as u8;
+                }
+                
+              },
+            };
+            let ptr14 = ret_area.as_mut_ptr() as i32;
+            #[link(wasm_import_module = "ambient:bindings/client-mesh")]
+            extern "C" {
+              #[cfg_attr(target_arch = "wasm32", link_name = "update")]
+              #[cfg_attr(not(target_arch = "wasm32"), link_name = "ambient:bindings/client-mesh_update")]
+              fn wit_import(
+              _: i32, _: i32, );
+            }
+            wit_import(ptr0, ptr14);
+            match i32::from(*((ptr14 + 0) as *const u8)) {
+              0 => Ok(()),
+              1 => Err({
+                let len15 = *((ptr14 + 8) as *const i32) as usize;
+                
+                {#[cfg(not(debug_assertions))]{String::from_utf8_unchecked(Vec::from_raw_parts(*((ptr14 + 4) as *const i32) as *mut _, len15, len15))}#[cfg(debug_assertions)]{String::from_utf8(Vec::from_raw_parts(*((ptr14 + 4) as *const i32) as *mut _, len15, len15)).unwrap()}}
+              }),
+              #[cfg(not(debug_assertions))]
+              _ => ::core::hint::unreachable_unchecked(),
+              #[cfg(debug_assertions)]
+              _ => panic!("invalid enum discriminant"),
+            }
+          }
+        }
+        #[allow(clippy::all)]
         pub fn destroy(handle: Handle,){
           
           #[allow(unused_imports)]
@@ -1951,6 +1951,44 @@ mod raw {
                 pub fn mouse_pickable_min() -> Component<Vec3> {
                     *MOUSE_PICKABLE_MIN
                 }
+                static INPUT_ACTION: Lazy<Component<String>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::input::input_action"));
+                #[doc = "**Input action**: The name of the input action this entity represents. Actions are declared in the `[actions]` section of a package manifest.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn input_action() -> Component<String> {
+                    *INPUT_ACTION
+                }
+                static INPUT_AXIS: Lazy<Component<String>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::input::input_axis"));
+                #[doc = "**Input axis**: The name of the input axis this entity represents. Axes are declared in the `[axes]` section of a package manifest.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn input_axis() -> Component<String> {
+                    *INPUT_AXIS
+                }
+                static INPUT_BINDINGS: Lazy<Component<Vec<String>>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::input::input_bindings"));
+                #[doc = "**Input bindings**: The inputs that trigger this action, or that move this axis in the positive direction (e.g. `Space`, `Mouse.Left`, `Gamepad.South`).\n\n*Attributes*: Debuggable, Networked"]
+                pub fn input_bindings() -> Component<Vec<String>> {
+                    *INPUT_BINDINGS
+                }
+                static INPUT_NEGATIVE_BINDINGS: Lazy<Component<Vec<String>>> = Lazy::new(|| {
+                    __internal_get_component("ambient_core::input::input_negative_bindings")
+                });
+                #[doc = "**Input negative bindings**: The inputs that move this axis in the negative direction.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn input_negative_bindings() -> Component<Vec<String>> {
+                    *INPUT_NEGATIVE_BINDINGS
+                }
+                static INPUT_VALUE: Lazy<Component<f32>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::input::input_value"));
+                #[doc = "**Input value**: The current value of this action or axis. Actions are 1.0 when triggered and 0.0 otherwise, and axes are in the range -1.0 to 1.0. Only updated on the client.\n\n*Attributes*: Debuggable"]
+                pub fn input_value() -> Component<f32> {
+                    *INPUT_VALUE
+                }
+                static INPUT_PREVIOUS_VALUE: Lazy<Component<f32>> = Lazy::new(|| {
+                    __internal_get_component("ambient_core::input::input_previous_value")
+                });
+                #[doc = "**Input previous value**: The value of this action or axis prior to the most recent update. Only updated on the client.\n\n*Attributes*: Debuggable"]
+                pub fn input_previous_value() -> Component<f32> {
+                    *INPUT_PREVIOUS_VALUE
+                }
             }
             #[doc = r" Auto-generated message definitions. Messages are used to communicate with the runtime, the other side of the network,"]
             #[doc = r" and with other modules."]
@@ -2031,6 +2069,47 @@ mod raw {
                     }
                 }
                 impl RuntimeMessage for ClipboardGet {}
+                #[derive(Clone, Debug)]
+                #[doc = "**InputRebind**: Changes the bindings of an input action or axis belonging to the sending package. The new bindings are persisted to the player's settings. Empty bindings restore the defaults from the manifest."]
+                pub struct InputRebind {
+                    pub name: String,
+                    pub bindings: Vec<String>,
+                    pub negative_bindings: Vec<String>,
+                }
+                impl InputRebind {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(
+                        name: impl Into<String>,
+                        bindings: impl Into<Vec<String>>,
+                        negative_bindings: impl Into<Vec<String>>,
+                    ) -> Self {
+                        Self {
+                            name: name.into(),
+                            bindings: bindings.into(),
+                            negative_bindings: negative_bindings.into(),
+                        }
+                    }
+                }
+                impl Message for InputRebind {
+                    fn id() -> &'static str {
+                        "ambient_core::input::InputRebind"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.name.serialize_message_part(&mut output)?;
+                        self.bindings.serialize_message_part(&mut output)?;
+                        self.negative_bindings.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            name: String::deserialize_message_part(&mut input)?,
+                            bindings: Vec::<String>::deserialize_message_part(&mut input)?,
+                            negative_bindings: Vec::<String>::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for InputRebind {}
             }
         }
         pub mod layout {
@@ -2167,6 +2246,86 @@ mod raw {
                 pub fn gpu_ui_size() -> Component<Vec4> {
                     *GPU_UI_SIZE
                 }
+                static GAP: Lazy<Component<Vec2>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::gap"));
+                #[doc = "**Gap**: Space between columns (x) and rows (y) in grid and flex layouts. Defaults to `space_between_items` on both axes.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn gap() -> Component<Vec2> {
+                    *GAP
+                }
+                static GRID_COLUMNS: Lazy<Component<String>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::grid_columns"));
+                #[doc = "**Grid columns**: The column tracks of a grid layout, separated by spaces. A track is a size in pixels (`100`), a fraction of the remaining space (`1fr`) or `auto`; `repeat(3, 1fr)` repeats tracks.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn grid_columns() -> Component<String> {
+                    *GRID_COLUMNS
+                }
+                static GRID_ROWS: Lazy<Component<String>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::grid_rows"));
+                #[doc = "**Grid rows**: The row tracks of a grid layout, in the same format as `grid_columns`. Rows beyond the template are sized `auto`.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn grid_rows() -> Component<String> {
+                    *GRID_ROWS
+                }
+                static GRID_COLUMN: Lazy<Component<u32>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::grid_column"));
+                #[doc = "**Grid column**: The column a grid item starts at. Items without a column or row are placed in the next free cell.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn grid_column() -> Component<u32> {
+                    *GRID_COLUMN
+                }
+                static GRID_ROW: Lazy<Component<u32>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::grid_row"));
+                #[doc = "**Grid row**: The row a grid item starts at. Items without a column or row are placed in the next free cell.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn grid_row() -> Component<u32> {
+                    *GRID_ROW
+                }
+                static GRID_COLUMN_SPAN: Lazy<Component<u32>> = Lazy::new(|| {
+                    __internal_get_component("ambient_core::layout::grid_column_span")
+                });
+                #[doc = "**Grid column span**: The number of columns a grid item covers. Defaults to 1.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn grid_column_span() -> Component<u32> {
+                    *GRID_COLUMN_SPAN
+                }
+                static GRID_ROW_SPAN: Lazy<Component<u32>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::grid_row_span"));
+                #[doc = "**Grid row span**: The number of rows a grid item covers. Defaults to 1.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn grid_row_span() -> Component<u32> {
+                    *GRID_ROW_SPAN
+                }
+                static FLEX_WRAP: Lazy<Component<bool>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::flex_wrap"));
+                #[doc = "**Flex wrap**: Whether the items of a flex layout wrap onto new lines when they do not fit.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn flex_wrap() -> Component<bool> {
+                    *FLEX_WRAP
+                }
+                static FLEX_GROW: Lazy<Component<f32>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::flex_grow"));
+                #[doc = "**Flex grow**: How much of the free space on its line a flex item takes, relative to its siblings. Defaults to 0.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn flex_grow() -> Component<f32> {
+                    *FLEX_GROW
+                }
+                static FLEX_SHRINK: Lazy<Component<f32>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::flex_shrink"));
+                #[doc = "**Flex shrink**: How much a flex item shrinks when its line overflows, relative to its siblings. Defaults to 1.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn flex_shrink() -> Component<f32> {
+                    *FLEX_SHRINK
+                }
+                static FLEX_BASIS: Lazy<Component<f32>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::flex_basis"));
+                #[doc = "**Flex basis**: The size of a flex item along the main axis before growing or shrinking. Defaults to the size it is given outside of the layout, e.g. by its author or the text system.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn flex_basis() -> Component<f32> {
+                    *FLEX_BASIS
+                }
+                static JUSTIFY_CONTENT: Lazy<
+                    Component<crate::ambient_core::layout::types::Justify>,
+                > = Lazy::new(|| __internal_get_component("ambient_core::layout::justify_content"));
+                #[doc = "**Justify content**: How the free space along the main axis of a flex layout is distributed between the items of a line.\n\n*Attributes*: Debuggable, Networked, Store, Enum"]
+                pub fn justify_content() -> Component<crate::ambient_core::layout::types::Justify> {
+                    *JUSTIFY_CONTENT
+                }
+                static ALIGN_CONTENT: Lazy<Component<crate::ambient_core::layout::types::Justify>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::layout::align_content"));
+                #[doc = "**Align content**: How the free space along the cross axis of a flex layout is distributed between its lines.\n\n*Attributes*: Debuggable, Networked, Store, Enum"]
+                pub fn align_content() -> Component<crate::ambient_core::layout::types::Justify> {
+                    *ALIGN_CONTENT
+                }
             }
             #[doc = r" Auto-generated type definitions."]
             pub mod types {
@@ -2484,6 +2643,104 @@ mod raw {
                     Default,
                 )]
                 #[serde(crate = "self::serde")]
+                #[doc = "**Justify**: How free space is distributed along an axis."]
+                pub enum Justify {
+                    #[default]
+                    #[doc = "Pack at the beginning"]
+                    Begin,
+                    #[doc = "Pack in the center"]
+                    Center,
+                    #[doc = "Pack at the end"]
+                    End,
+                    #[doc = "Evenly between items, with no space at the ends"]
+                    SpaceBetween,
+                    #[doc = "Evenly around items, with half the space at the ends"]
+                    SpaceAround,
+                    #[doc = "Evenly between items and at the ends"]
+                    SpaceEvenly,
+                    #[doc = "Stretch lines to fill the space; only used by `align_content`"]
+                    Stretch,
+                }
+                impl crate::ecs::EnumComponent for Justify {
+                    fn to_u32(&self) -> u32 {
+                        match self {
+                            Self::Begin => Justify::Begin as u32,
+                            Self::Center => Justify::Center as u32,
+                            Self::End => Justify::End as u32,
+                            Self::SpaceBetween => Justify::SpaceBetween as u32,
+                            Self::SpaceAround => Justify::SpaceAround as u32,
+                            Self::SpaceEvenly => Justify::SpaceEvenly as u32,
+                            Self::Stretch => Justify::Stretch as u32,
+                        }
+                    }
+                    fn from_u32(value: u32) -> Option<Self> {
+                        if value == Justify::Begin as u32 {
+                            return Some(Self::Begin);
+                        }
+                        if value == Justify::Center as u32 {
+                            return Some(Self::Center);
+                        }
+                        if value == Justify::End as u32 {
+                            return Some(Self::End);
+                        }
+                        if value == Justify::SpaceBetween as u32 {
+                            return Some(Self::SpaceBetween);
+                        }
+                        if value == Justify::SpaceAround as u32 {
+                            return Some(Self::SpaceAround);
+                        }
+                        if value == Justify::SpaceEvenly as u32 {
+                            return Some(Self::SpaceEvenly);
+                        }
+                        if value == Justify::Stretch as u32 {
+                            return Some(Self::Stretch);
+                        }
+                        None
+                    }
+                }
+                impl crate::ecs::SupportedValue for Justify {
+                    fn from_result(result: crate::ecs::WitComponentValue) -> Option<Self> {
+                        use crate::ecs::EnumComponent;
+                        u32::from_result(result).and_then(Self::from_u32)
+                    }
+                    fn into_result(self) -> crate::ecs::WitComponentValue {
+                        use crate::ecs::EnumComponent;
+                        self.to_u32().into_result()
+                    }
+                    fn from_value(value: crate::ecs::ComponentValue) -> Option<Self> {
+                        use crate::ecs::EnumComponent;
+                        u32::from_value(value).and_then(Self::from_u32)
+                    }
+                    fn into_value(self) -> crate::ecs::ComponentValue {
+                        use crate::ecs::EnumComponent;
+                        self.to_u32().into_value()
+                    }
+                }
+                impl MessageSerde for Justify {
+                    fn serialize_message_part(
+                        &self,
+                        output: &mut Vec<u8>,
+                    ) -> Result<(), MessageSerdeError> {
+                        crate::ecs::EnumComponent::to_u32(self).serialize_message_part(output)
+                    }
+                    fn deserialize_message_part(
+                        input: &mut dyn std::io::Read,
+                    ) -> Result<Self, MessageSerdeError> {
+                        crate::ecs::EnumComponent::from_u32(u32::deserialize_message_part(input)?)
+                            .ok_or(MessageSerdeError::InvalidValue)
+                    }
+                }
+                #[derive(
+                    Copy,
+                    Clone,
+                    Debug,
+                    PartialEq,
+                    Eq,
+                    serde :: Serialize,
+                    serde :: Deserialize,
+                    Default,
+                )]
+                #[serde(crate = "self::serde")]
                 #[doc = "**Layout**: The type of the layout to use."]
                 pub enum Layout {
                     #[default]
@@ -2495,6 +2752,10 @@ mod raw {
                     Bookcase,
                     #[doc = "Width to children."]
                     WidthToChildren,
+                    #[doc = "Grid layout with column and row tracks."]
+                    Grid,
+                    #[doc = "Flex layout with wrapping, growing and shrinking items."]
+                    Flex,
                 }
                 impl crate::ecs::EnumComponent for Layout {
                     fn to_u32(&self) -> u32 {
@@ -2503,6 +2764,8 @@ mod raw {
                             Self::Dock => Layout::Dock as u32,
                             Self::Bookcase => Layout::Bookcase as u32,
                             Self::WidthToChildren => Layout::WidthToChildren as u32,
+                            Self::Grid => Layout::Grid as u32,
+                            Self::Flex => Layout::Flex as u32,
                         }
                     }
                     fn from_u32(value: u32) -> Option<Self> {
@@ -2518,6 +2781,12 @@ mod raw {
                         if value == Layout::WidthToChildren as u32 {
                             return Some(Self::WidthToChildren);
                         }
+                        if value == Layout::Grid as u32 {
+                            return Some(Self::Grid);
+                        }
+                        if value == Layout::Flex as u32 {
+                            return Some(Self::Flex);
+                        }
                         None
                     }
                 }
@@ -4313,6 +4582,12 @@ mod raw {
                 pub fn text() -> Component<String> {
                     *TEXT
                 }
+                static RICH_TEXT: Lazy<Component<()>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::text::rich_text"));
+                #[doc = "**Rich text**: Parse markup in the `text` of this entity: `<b>`, `<i>`, `<u>`, `<color=#f00>` and `<size=20>` style the enclosed text, `<icon=f118>` inserts a FontAwesome icon, and `<lt>` inserts a literal `<`.\n\n*Attributes*: Debuggable, Networked, Store"]
+                pub fn rich_text() -> Component<()> {
+                    *RICH_TEXT
+                }
             }
             #[doc = r" Auto-generated type definitions."]
             pub mod types {
@@ -4680,6 +4955,53 @@ mod raw {
                 pub fn focusable() -> Component<String> {
                     *FOCUSABLE
                 }
+                static LOCALE: Lazy<Component<String>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::ui::locale"));
+                #[doc = "**Locale**: The locale of the UI, like `en` or `pt-BR`, which selects the string tables used by `t!`. Defaults to the locale stored in the settings, or `en`.\n\n*Attributes*: Debuggable, Resource"]
+                pub fn locale() -> Component<String> {
+                    *LOCALE
+                }
+                static ACCESSIBILITY_ROLE: Lazy<
+                    Component<crate::ambient_core::ui::types::AccessibilityRole>,
+                > = Lazy::new(|| __internal_get_component("ambient_core::ui::accessibility_role"));
+                #[doc = "**Accessibility role**: Exposes this UI element to assistive technologies, like screen readers, with this role. Elements without a role are left out of the accessibility tree, and their accessible descendants are attached to the nearest accessible ancestor.\n\n*Attributes*: Debuggable, Networked, Enum"]
+                pub fn accessibility_role(
+                ) -> Component<crate::ambient_core::ui::types::AccessibilityRole> {
+                    *ACCESSIBILITY_ROLE
+                }
+                static ACCESSIBILITY_LABEL: Lazy<Component<String>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::ui::accessibility_label"));
+                #[doc = "**Accessibility label**: The name of this UI element for assistive technologies. Defaults to the text of its descendants.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn accessibility_label() -> Component<String> {
+                    *ACCESSIBILITY_LABEL
+                }
+                static ACCESSIBILITY_VALUE: Lazy<Component<String>> =
+                    Lazy::new(|| __internal_get_component("ambient_core::ui::accessibility_value"));
+                #[doc = "**Accessibility value**: The current value of this UI element for assistive technologies, like the text of a text field.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn accessibility_value() -> Component<String> {
+                    *ACCESSIBILITY_VALUE
+                }
+                static ACCESSIBILITY_DISABLED: Lazy<Component<bool>> = Lazy::new(|| {
+                    __internal_get_component("ambient_core::ui::accessibility_disabled")
+                });
+                #[doc = "**Accessibility disabled**: Whether this UI element is disabled, for assistive technologies.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn accessibility_disabled() -> Component<bool> {
+                    *ACCESSIBILITY_DISABLED
+                }
+                static ACCESSIBILITY_EXPANDED: Lazy<Component<bool>> = Lazy::new(|| {
+                    __internal_get_component("ambient_core::ui::accessibility_expanded")
+                });
+                #[doc = "**Accessibility expanded**: Whether this UI element is expanded or collapsed, for assistive technologies. Leave it out for elements that can't be expanded.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn accessibility_expanded() -> Component<bool> {
+                    *ACCESSIBILITY_EXPANDED
+                }
+                static ACCESSIBILITY_SELECTED: Lazy<Component<bool>> = Lazy::new(|| {
+                    __internal_get_component("ambient_core::ui::accessibility_selected")
+                });
+                #[doc = "**Accessibility selected**: Whether this UI element is selected, for assistive technologies. Leave it out for elements that can't be selected.\n\n*Attributes*: Debuggable, Networked"]
+                pub fn accessibility_selected() -> Component<bool> {
+                    *ACCESSIBILITY_SELECTED
+                }
             }
             #[doc = r" Auto-generated message definitions. Messages are used to communicate with the runtime, the other side of the network,"]
             #[doc = r" and with other modules."]
@@ -4723,6 +5045,275 @@ mod raw {
                     }
                 }
                 impl ModuleMessage for FocusChanged {}
+                #[derive(Clone, Debug)]
+                #[doc = "**SetLocale**: Changes the locale of the UI and stores it in the player's settings."]
+                pub struct SetLocale {
+                    pub locale: String,
+                }
+                impl SetLocale {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(locale: impl Into<String>) -> Self {
+                        Self {
+                            locale: locale.into(),
+                        }
+                    }
+                }
+                impl Message for SetLocale {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::SetLocale"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.locale.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            locale: String::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for SetLocale {}
+                #[derive(Clone, Debug)]
+                #[doc = "**LoadLocale**: Asks the runtime for the string table of `locale` in the `assets/locales/<locale>.ftl` asset of `package`. The runtime replies to the sending module with `LocaleLoaded`."]
+                pub struct LoadLocale {
+                    pub package: EntityId,
+                    pub locale: String,
+                }
+                impl LoadLocale {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(package: impl Into<EntityId>, locale: impl Into<String>) -> Self {
+                        Self {
+                            package: package.into(),
+                            locale: locale.into(),
+                        }
+                    }
+                }
+                impl Message for LoadLocale {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::LoadLocale"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.package.serialize_message_part(&mut output)?;
+                        self.locale.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            package: EntityId::deserialize_message_part(&mut input)?,
+                            locale: String::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for LoadLocale {}
+                #[derive(Clone, Debug)]
+                #[doc = "**LocaleLoaded**: Sent in reply to `LoadLocale` with the `source` of the string table, or the `error` that prevented it from being loaded."]
+                pub struct LocaleLoaded {
+                    pub package: EntityId,
+                    pub locale: String,
+                    pub source: String,
+                    pub error: Option<String>,
+                }
+                impl LocaleLoaded {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(
+                        package: impl Into<EntityId>,
+                        locale: impl Into<String>,
+                        source: impl Into<String>,
+                        error: impl Into<Option<String>>,
+                    ) -> Self {
+                        Self {
+                            package: package.into(),
+                            locale: locale.into(),
+                            source: source.into(),
+                            error: error.into(),
+                        }
+                    }
+                }
+                impl Message for LocaleLoaded {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::LocaleLoaded"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.package.serialize_message_part(&mut output)?;
+                        self.locale.serialize_message_part(&mut output)?;
+                        self.source.serialize_message_part(&mut output)?;
+                        self.error.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            package: EntityId::deserialize_message_part(&mut input)?,
+                            locale: String::deserialize_message_part(&mut input)?,
+                            source: String::deserialize_message_part(&mut input)?,
+                            error: Option::<String>::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl RuntimeMessage for LocaleLoaded {}
+                #[derive(Clone, Debug)]
+                #[doc = "**Activate**: Activates the focusable UI element `focus` on behalf of an assistive technology, like a screen reader pressing a button."]
+                pub struct Activate {
+                    pub focus: String,
+                }
+                impl Activate {
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(focus: impl Into<String>) -> Self {
+                        Self {
+                            focus: focus.into(),
+                        }
+                    }
+                }
+                impl Message for Activate {
+                    fn id() -> &'static str {
+                        "ambient_core::ui::Activate"
+                    }
+                    fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                        let mut output = vec![];
+                        self.focus.serialize_message_part(&mut output)?;
+                        Ok(output)
+                    }
+                    fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                        Ok(Self {
+                            focus: String::deserialize_message_part(&mut input)?,
+                        })
+                    }
+                }
+                impl ModuleMessage for Activate {}
+            }
+            #[doc = r" Auto-generated type definitions."]
+            pub mod types {
+                use crate::{global::serde, message::*};
+                #[derive(
+                    Copy,
+                    Clone,
+                    Debug,
+                    PartialEq,
+                    Eq,
+                    serde :: Serialize,
+                    serde :: Deserialize,
+                    Default,
+                )]
+                #[serde(crate = "self::serde")]
+                #[doc = "**AccessibilityRole**: The role of a UI element for assistive technologies."]
+                pub enum AccessibilityRole {
+                    #[default]
+                    #[doc = "A group of related elements"]
+                    Group,
+                    #[doc = "Static text"]
+                    Text,
+                    #[doc = "A button"]
+                    Button,
+                    #[doc = "A field that text can be typed into"]
+                    TextInput,
+                    #[doc = "A button that opens a list of options"]
+                    ComboBox,
+                    #[doc = "A list of items"]
+                    List,
+                    #[doc = "An item of a list"]
+                    ListItem,
+                    #[doc = "A list of tabs"]
+                    TabList,
+                    #[doc = "A tab of a tab list"]
+                    Tab,
+                    #[doc = "The content of the selected tab"]
+                    TabPanel,
+                    #[doc = "A tooltip"]
+                    Tooltip,
+                    #[doc = "A dialog or window"]
+                    Dialog,
+                }
+                impl crate::ecs::EnumComponent for AccessibilityRole {
+                    fn to_u32(&self) -> u32 {
+                        match self {
+                            Self::Group => AccessibilityRole::Group as u32,
+                            Self::Text => AccessibilityRole::Text as u32,
+                            Self::Button => AccessibilityRole::Button as u32,
+                            Self::TextInput => AccessibilityRole::TextInput as u32,
+                            Self::ComboBox => AccessibilityRole::ComboBox as u32,
+                            Self::List => AccessibilityRole::List as u32,
+                            Self::ListItem => AccessibilityRole::ListItem as u32,
+                            Self::TabList => AccessibilityRole::TabList as u32,
+                            Self::Tab => AccessibilityRole::Tab as u32,
+                            Self::TabPanel => AccessibilityRole::TabPanel as u32,
+                            Self::Tooltip => AccessibilityRole::Tooltip as u32,
+                            Self::Dialog => AccessibilityRole::Dialog as u32,
+                        }
+                    }
+                    fn from_u32(value: u32) -> Option<Self> {
+                        if value == AccessibilityRole::Group as u32 {
+                            return Some(Self::Group);
+                        }
+                        if value == AccessibilityRole::Text as u32 {
+                            return Some(Self::Text);
+                        }
+                        if value == AccessibilityRole::Button as u32 {
+                            return Some(Self::Button);
+                        }
+                        if value == AccessibilityRole::TextInput as u32 {
+                            return Some(Self::TextInput);
+                        }
+                        if value == AccessibilityRole::ComboBox as u32 {
+                            return Some(Self::ComboBox);
+                        }
+                        if value == AccessibilityRole::List as u32 {
+                            return Some(Self::List);
+                        }
+                        if value == AccessibilityRole::ListItem as u32 {
+                            return Some(Self::ListItem);
+                        }
+                        if value == AccessibilityRole::TabList as u32 {
+                            return Some(Self::TabList);
+                        }
+                        if value == AccessibilityRole::Tab as u32 {
+                            return Some(Self::Tab);
+                        }
+                        if value == AccessibilityRole::TabPanel as u32 {
+                            return Some(Self::TabPanel);
+                        }
+                        if value == AccessibilityRole::Tooltip as u32 {
+                            return Some(Self::Tooltip);
+                        }
+                        if value == AccessibilityRole::Dialog as u32 {
+                            return Some(Self::Dialog);
+                        }
+                        None
+                    }
+                }
+                impl crate::ecs::SupportedValue for AccessibilityRole {
+                    fn from_result(result: crate::ecs::WitComponentValue) -> Option<Self> {
+                        use crate::ecs::EnumComponent;
+                        u32::from_result(result).and_then(Self::from_u32)
+                    }
+                    fn into_result(self) -> crate::ecs::WitComponentValue {
+                        use crate::ecs::EnumComponent;
+                        self.to_u32().into_result()
+                    }
+                    fn from_value(value: crate::ecs::ComponentValue) -> Option<Self> {
+                        use crate::ecs::EnumComponent;
+                        u32::from_value(value).and_then(Self::from_u32)
+                    }
+                    fn into_value(self) -> crate::ecs::ComponentValue {
+                        use crate::ecs::EnumComponent;
+                        self.to_u32().into_value()
+                    }
+                }
+                impl MessageSerde for AccessibilityRole {
+                    fn serialize_message_part(
+                        &self,
+                        output: &mut Vec<u8>,
+                    ) -> Result<(), MessageSerdeError> {
+                        crate::ecs::EnumComponent::to_u32(self).serialize_message_part(output)
+                    }
+                    fn deserialize_message_part(
+                        input: &mut dyn std::io::Read,
+                    ) -> Result<Self, MessageSerdeError> {
+                        crate::ecs::EnumComponent::from_u32(u32::deserialize_message_part(input)?)
+                            .ok_or(MessageSerdeError::InvalidValue)
+                    }
+                }
             }
         }
         pub mod wasm {
@@ -5181,6 +5772,33 @@ mod raw {
             }
             impl RuntimeMessage for WindowKeyboardCharacter {}
             #[derive(Clone, Debug)]
+            #[doc = "**WindowImeComposition**: Sent when the text being composed by the input method changes. `text` is empty when the composition ends; the final text is sent as a `WindowKeyboardCharacter`."]
+            pub struct WindowImeComposition {
+                pub text: String,
+            }
+            impl WindowImeComposition {
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(text: impl Into<String>) -> Self {
+                    Self { text: text.into() }
+                }
+            }
+            impl Message for WindowImeComposition {
+                fn id() -> &'static str {
+                    "ambient_core::WindowImeComposition"
+                }
+                fn serialize_message(&self) -> Result<Vec<u8>, MessageSerdeError> {
+                    let mut output = vec![];
+                    self.text.serialize_message_part(&mut output)?;
+                    Ok(output)
+                }
+                fn deserialize_message(mut input: &[u8]) -> Result<Self, MessageSerdeError> {
+                    Ok(Self {
+                        text: String::deserialize_message_part(&mut input)?,
+                    })
+                }
+            }
+            impl RuntimeMessage for WindowImeComposition {}
+            #[derive(Clone, Debug)]
             #[doc = "**WindowKeyboardModifiersChange**: Sent when the window's keyboard modifiers change."]
             pub struct WindowKeyboardModifiersChange {
                 pub modifiers: u32,
@@ -25,6 +25,8 @@ pub mod ecs;
 pub mod entity;
 /// Global functions and types for your convenience.
 pub mod global;
+/// Localization with string tables, plural rules and the locale of the app.
+pub mod i18n;
 /// Messaging to other packages and to the other side of the network boundary.
 pub mod message;
 /// Player-specific functionality.
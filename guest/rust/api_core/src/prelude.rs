@@ -6,9 +6,9 @@ pub use crate::{
     },
     entity,
     global::*,
-    main, message,
+    i18n, main, message,
     message::{Message, ModuleMessage, RuntimeMessage},
-    player,
+    player, t,
 };
 pub use anyhow::{anyhow, Context as AnyhowContext};
 pub use rand::prelude::*;
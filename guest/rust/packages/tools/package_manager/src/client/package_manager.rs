@@ -284,14 +284,18 @@ fn PackageList(_hooks: &mut Hooks, packages: Vec<DisplayPackage>) -> Element {
         .with(height(), 1.)
         .with(fit_horizontal(), Fit::Parent);
 
-    ScrollArea::el(
-        ScrollAreaSizing::FitChildrenWidth,
-        FlowColumn::el(itertools::intersperse(
-            packages.into_iter().map(Package::el),
-            sep,
-        ))
-        .with(space_between_items(), 0.0),
-    )
+    let count = packages.len();
+    VirtualList::new(count, move |index| {
+        FlowColumn::el([
+            Package::el(packages[index].clone()),
+            if index + 1 < count {
+                sep.clone()
+            } else {
+                Element::new()
+            },
+        ])
+    })
+    .el()
     .with(height(), 400.)
 }
 
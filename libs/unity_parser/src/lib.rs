@@ -6,6 +6,7 @@ pub mod asset;
 pub mod mat;
 pub mod model_importer;
 pub mod prefab;
+pub mod scene;
 
 #[derive(Debug, Clone)]
 pub struct UnityRef {
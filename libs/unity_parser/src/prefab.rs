@@ -4,7 +4,11 @@ use anyhow::Context;
 use glam::{Mat4, Quat, Vec3};
 use yaml_rust::Yaml;
 
-use crate::{parse_unity_yaml, quat_from_yaml, vec3_from_yaml, UnityRef, YamlExt};
+use crate::{
+    parse_unity_yaml, quat_from_yaml,
+    scene::{Collider, Light, PrefabInstance, StrippedObject},
+    vec3_from_yaml, UnityRef, YamlExt,
+};
 
 /// A unity .prefab file
 #[derive(Debug)]
@@ -19,12 +23,23 @@ impl PrefabFile {
         let objects = docs
             .iter()
             .map(|doc| {
-                let (_, id) = doc["unity_object"]
+                let header = doc["unity_object"]
                     .as_str()
-                    .unwrap()
-                    .split_once('&')
-                    .unwrap();
-                Ok((id.parse().unwrap(), PrefabObject::from_yaml(doc)?))
+                    .context("Missing object header")?;
+                let (_, id) = header.split_once('&').context("Missing object id")?;
+                // Objects that belong to a prefab instance are written as "<type> &<id> stripped"
+                let mut id_and_flags = id.split_whitespace();
+                let id = id_and_flags
+                    .next()
+                    .context("Missing object id")?
+                    .parse()
+                    .context("Bad object id")?;
+                let object = if id_and_flags.any(|flag| flag == "stripped") {
+                    PrefabObject::Stripped(StrippedObject::from_yaml(doc)?)
+                } else {
+                    PrefabObject::from_yaml(doc)?
+                };
+                Ok((id, object))
             })
             .collect::<anyhow::Result<HashMap<_, _>>>()?;
         Ok(Self { objects })
@@ -95,11 +110,19 @@ pub enum PrefabObject {
     MeshRenderer(MeshRenderer),
     MeshFilter(MeshFilter),
     Transform(Transform),
+    PrefabInstance(PrefabInstance),
+    Stripped(StrippedObject),
+    Light(Light),
+    Collider(Collider),
     Unimplemented,
 }
 impl PrefabObject {
     fn from_yaml(yaml: &Yaml) -> anyhow::Result<Self> {
-        if yaml["Prefab"].as_hash().is_some() {
+        if yaml["PrefabInstance"].as_hash().is_some() {
+            Ok(Self::PrefabInstance(PrefabInstance::from_yaml(
+                &yaml["PrefabInstance"],
+            )?))
+        } else if yaml["Prefab"].as_hash().is_some() {
             Ok(Self::Prefab(Prefab::from_yaml(&yaml["Prefab"])?))
         } else if yaml["GameObject"].as_hash().is_some() {
             Ok(Self::GameObject(GameObject::from_yaml(
@@ -117,6 +140,10 @@ impl PrefabObject {
             )?))
         } else if yaml["Transform"].as_hash().is_some() {
             Ok(Self::Transform(Transform::from_yaml(&yaml["Transform"])?))
+        } else if yaml["Light"].as_hash().is_some() {
+            Ok(Self::Light(Light::from_yaml(&yaml["Light"])?))
+        } else if let Some(collider) = Collider::from_object_yaml(yaml)? {
+            Ok(Self::Collider(collider))
         } else {
             Ok(Self::Unimplemented)
             // bail!("Unimplemented: {:?}", yaml);
@@ -133,6 +160,10 @@ impl PrefabObject {
             PrefabObject::MeshRenderer(o) => o.dump(prefab),
             PrefabObject::MeshFilter(o) => o.dump(prefab),
             PrefabObject::Transform(o) => o.dump(prefab, dump_game_obj),
+            PrefabObject::PrefabInstance(o) => o.dump(prefab),
+            PrefabObject::Stripped(o) => o.dump(prefab),
+            PrefabObject::Light(o) => o.dump(prefab),
+            PrefabObject::Collider(o) => o.dump(prefab),
             PrefabObject::Unimplemented => {
                 let mut out = yaml_rust::yaml::Hash::new();
                 out.insert(
@@ -259,6 +290,7 @@ impl GetObject for Prefab {
 pub struct GameObject {
     components: Vec<UnityRef>,
     pub name: String,
+    pub is_active: bool,
 }
 impl GameObject {
     fn from_yaml(yaml: &Yaml) -> anyhow::Result<Self> {
@@ -267,12 +299,23 @@ impl GameObject {
                 .as_vec()
                 .context("m_Component not a vec")?
                 .iter()
-                .map(|comp| UnityRef::from_yaml(&comp["component"]))
+                .map(|comp| {
+                    // Older files key the component by its class id instead, i.e. "- 4: {fileID: 123}"
+                    match comp["component"] {
+                        Yaml::BadValue => UnityRef::from_yaml(
+                            comp.as_hash()
+                                .and_then(|comp| comp.values().next())
+                                .context("Bad m_Component entry")?,
+                        ),
+                        ref comp => UnityRef::from_yaml(comp),
+                    }
+                })
                 .collect::<anyhow::Result<Vec<UnityRef>>>()?,
             name: yaml["m_Name"]
                 .as_str()
                 .context("GameObject missing m_Name")?
                 .to_string(),
+            is_active: yaml["m_IsActive"].as_i64().map_or(true, |x| x != 0),
         })
     }
     pub fn get_component<'a, T: GetObject>(&self, prefab: &'a PrefabFile) -> Option<&'a T> {
@@ -285,6 +328,17 @@ impl GameObject {
         }
         None
     }
+    pub fn get_components<'a, T: GetObject>(
+        &'a self,
+        prefab: &'a PrefabFile,
+    ) -> impl Iterator<Item = &'a T> + 'a {
+        self.components.iter().filter_map(|c| {
+            prefab
+                .objects
+                .get(&c.file_id)
+                .and_then(|obj| obj.as_object::<T>())
+        })
+    }
     pub fn dump(&self, prefab: &PrefabFile) -> yaml_rust::yaml::Hash {
         let mut out = yaml_rust::yaml::Hash::new();
         out.insert(
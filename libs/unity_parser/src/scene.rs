@@ -0,0 +1,604 @@
+use anyhow::Context;
+use glam::{Quat, Vec3};
+use itertools::Itertools;
+use yaml_rust::Yaml;
+
+use crate::{
+    parse_unity_yaml,
+    prefab::{GameObject, GetObject, PrefabFile, PrefabObject, Transform},
+    vec3_from_yaml, UnityRef, YamlExt,
+};
+
+/// A unity .unity scene file
+///
+/// Scenes use the same object model as prefabs, but their hierarchy is usually a mix of plain
+/// game objects and instances of other prefabs (with property overrides)
+#[derive(Debug)]
+pub struct UnityScene(pub PrefabFile);
+impl UnityScene {
+    pub fn from_string(data: &str) -> anyhow::Result<Self> {
+        Self::from_yaml(parse_unity_yaml(data)?)
+    }
+    pub fn from_yaml(docs: Vec<Yaml>) -> anyhow::Result<Self> {
+        Ok(Self(PrefabFile::from_yaml(docs)?))
+    }
+    pub fn get_root_nodes(&self) -> Vec<SceneNode> {
+        self.0.get_root_nodes()
+    }
+}
+
+/// A node in a scene or prefab hierarchy
+#[derive(Debug, Clone, Copy)]
+pub enum SceneNode<'a> {
+    GameObject(&'a GameObject),
+    PrefabInstance {
+        id: i64,
+        instance: &'a PrefabInstance,
+    },
+}
+
+impl PrefabFile {
+    /// Root game objects and prefab instances, ordered by file id so that the output is stable
+    pub fn get_root_nodes(&self) -> Vec<SceneNode> {
+        self.objects
+            .iter()
+            .filter_map(|(id, obj)| match obj {
+                PrefabObject::Transform(trans) if trans.father.file_id == 0 => Some((
+                    *id,
+                    SceneNode::GameObject(
+                        self.objects
+                            .get(&trans.game_object.file_id)?
+                            .as_object::<GameObject>()?,
+                    ),
+                )),
+                PrefabObject::PrefabInstance(instance)
+                    if instance.transform_parent.file_id == 0 =>
+                {
+                    Some((*id, SceneNode::PrefabInstance { id: *id, instance }))
+                }
+                _ => None,
+            })
+            .sorted_by_key(|(id, _)| *id)
+            .map(|(_, node)| node)
+            .collect()
+    }
+    /// The children of a transform. Children that are the root of a prefab instance are written
+    /// as stripped transforms, which are resolved to their instance
+    pub fn get_child_nodes(&self, transform: &Transform) -> Vec<SceneNode> {
+        transform
+            .children
+            .iter()
+            .filter_map(|c| match self.objects.get(&c.file_id)? {
+                PrefabObject::Transform(trans) => Some(SceneNode::GameObject(
+                    self.objects
+                        .get(&trans.game_object.file_id)?
+                        .as_object::<GameObject>()?,
+                )),
+                PrefabObject::Stripped(stripped) => {
+                    let id = stripped.prefab_instance.file_id;
+                    Some(SceneNode::PrefabInstance {
+                        id,
+                        instance: self.objects.get(&id)?.as_object::<PrefabInstance>()?,
+                    })
+                }
+                _ => None,
+            })
+            .dedup_by(|a, b| match (a, b) {
+                (
+                    SceneNode::PrefabInstance { id: a, .. },
+                    SceneNode::PrefabInstance { id: b, .. },
+                ) => a == b,
+                _ => false,
+            })
+            .collect()
+    }
+    /// Game objects that were added to a prefab instance in this file, as children of the object
+    /// with file id `source_object` in the instance's source prefab
+    pub fn get_added_game_objects(&self, instance_id: i64, source_object: i64) -> Vec<&GameObject> {
+        self.objects
+            .iter()
+            .filter_map(|(id, obj)| {
+                let trans = obj.as_object::<Transform>()?;
+                let father = self
+                    .objects
+                    .get(&trans.father.file_id)?
+                    .as_object::<StrippedObject>()?;
+                if father.prefab_instance.file_id == instance_id
+                    && father.corresponding_source_object.file_id == source_object
+                {
+                    Some((
+                        *id,
+                        self.objects
+                            .get(&trans.game_object.file_id)?
+                            .as_object::<GameObject>()?,
+                    ))
+                } else {
+                    None
+                }
+            })
+            .sorted_by_key(|(id, _)| *id)
+            .map(|(_, obj)| obj)
+            .collect()
+    }
+    /// Applies the overrides of a prefab instance to this (source) prefab
+    pub fn apply_instance(&mut self, instance: &PrefabInstance) {
+        for removed in &instance.removed_components {
+            self.objects.remove(&removed.file_id);
+        }
+        for modification in &instance.modifications {
+            if let Some(obj) = self.objects.get_mut(&modification.target.file_id) {
+                if !obj.apply_modification(modification) {
+                    tracing::debug!(
+                        "Ignoring unsupported prefab override: {}",
+                        modification.property_path
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl PrefabObject {
+    /// Returns false if the property isn't supported
+    fn apply_modification(&mut self, modification: &PropertyModification) -> bool {
+        let path = modification.property_path.as_str();
+        let (field, sub) = path.split_once('.').unwrap_or((path, ""));
+        let value = modification.value_f32();
+        match self {
+            PrefabObject::Transform(trans) => match (field, value) {
+                ("m_LocalPosition", Some(value)) => {
+                    set_vec3_field(&mut trans.local_position, sub, value)
+                }
+                ("m_LocalScale", Some(value)) => set_vec3_field(&mut trans.local_scale, sub, value),
+                ("m_LocalRotation", Some(value)) => {
+                    set_quat_field(&mut trans.local_rotation, sub, value)
+                }
+                _ => false,
+            },
+            PrefabObject::GameObject(obj) => match field {
+                "m_Name" => {
+                    obj.name = modification.value_string();
+                    true
+                }
+                "m_IsActive" => {
+                    obj.is_active = value.map_or(true, |x| x != 0.);
+                    true
+                }
+                _ => false,
+            },
+            PrefabObject::Light(light) => match (field, value) {
+                ("m_Enabled", Some(value)) => {
+                    light.enabled = value != 0.;
+                    true
+                }
+                ("m_Type", Some(value)) => {
+                    light.type_ = LightType::from_id(value as i64);
+                    true
+                }
+                ("m_Color", Some(value)) => {
+                    let channel = match sub {
+                        "r" => "x",
+                        "g" => "y",
+                        "b" => "z",
+                        _ => return sub == "a",
+                    };
+                    set_vec3_field(&mut light.color, channel, value)
+                }
+                ("m_Intensity", Some(value)) => {
+                    light.intensity = value;
+                    true
+                }
+                ("m_Range", Some(value)) => {
+                    light.range = value;
+                    true
+                }
+                ("m_SpotAngle", Some(value)) => {
+                    light.spot_angle = value;
+                    true
+                }
+                _ => false,
+            },
+            PrefabObject::Collider(collider) => match (field, value, &mut collider.shape) {
+                ("m_Enabled", Some(value), _) => {
+                    collider.enabled = value != 0.;
+                    true
+                }
+                ("m_IsTrigger", Some(value), _) => {
+                    collider.is_trigger = value != 0.;
+                    true
+                }
+                (
+                    "m_Center",
+                    Some(value),
+                    ColliderShape::Box { center, .. }
+                    | ColliderShape::Sphere { center, .. }
+                    | ColliderShape::Capsule { center, .. },
+                ) => set_vec3_field(center, sub, value),
+                ("m_Size", Some(value), ColliderShape::Box { size, .. }) => {
+                    set_vec3_field(size, sub, value)
+                }
+                (
+                    "m_Radius",
+                    Some(value),
+                    ColliderShape::Sphere { radius, .. } | ColliderShape::Capsule { radius, .. },
+                ) => {
+                    *radius = value;
+                    true
+                }
+                ("m_Height", Some(value), ColliderShape::Capsule { height, .. }) => {
+                    *height = value;
+                    true
+                }
+                ("m_Direction", Some(value), ColliderShape::Capsule { direction, .. }) => {
+                    *direction = value as usize;
+                    true
+                }
+                ("m_Convex", Some(value), ColliderShape::Mesh { convex, .. }) => {
+                    *convex = value != 0.;
+                    true
+                }
+                ("m_Mesh", _, ColliderShape::Mesh { mesh, .. }) => {
+                    if let Some(reference) = &modification.object_reference {
+                        *mesh = reference.clone();
+                    }
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+fn set_vec3_field(v: &mut Vec3, field: &str, value: f32) -> bool {
+    match field {
+        "x" => v.x = value,
+        "y" => v.y = value,
+        "z" => v.z = value,
+        _ => return false,
+    }
+    true
+}
+fn set_quat_field(q: &mut Quat, field: &str, value: f32) -> bool {
+    match field {
+        "x" => q.x = value,
+        "y" => q.y = value,
+        "z" => q.z = value,
+        "w" => q.w = value,
+        _ => return false,
+    }
+    true
+}
+
+/// An instance of another prefab, placed in a scene or nested in a prefab
+#[derive(Debug)]
+pub struct PrefabInstance {
+    pub source_prefab: UnityRef,
+    pub transform_parent: UnityRef,
+    pub modifications: Vec<PropertyModification>,
+    pub removed_components: Vec<UnityRef>,
+}
+impl PrefabInstance {
+    pub(crate) fn from_yaml(yaml: &Yaml) -> anyhow::Result<Self> {
+        let modification = &yaml["m_Modification"];
+        Ok(Self {
+            source_prefab: UnityRef::from_yaml(&yaml["m_SourcePrefab"])?,
+            transform_parent: UnityRef::from_yaml(&modification["m_TransformParent"])?,
+            modifications: modification["m_Modifications"]
+                .as_vec()
+                .map(|mods| mods.iter().map(PropertyModification::from_yaml).collect())
+                .transpose()?
+                .unwrap_or_default(),
+            removed_components: modification["m_RemovedComponents"]
+                .as_vec()
+                .map(|refs| refs.iter().map(UnityRef::from_yaml).collect())
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+    pub fn dump(&self, _prefab: &PrefabFile) -> yaml_rust::yaml::Hash {
+        let mut out = yaml_rust::yaml::Hash::new();
+        out.insert(
+            Yaml::String("type".to_string()),
+            Yaml::String("PrefabInstance".to_string()),
+        );
+        out.insert(
+            Yaml::String("source_prefab".to_string()),
+            Yaml::String(self.source_prefab.to_string()),
+        );
+        out
+    }
+}
+impl GetObject for PrefabInstance {
+    fn get_object(obj: &PrefabObject) -> Option<&Self> {
+        if let PrefabObject::PrefabInstance(obj) = obj {
+            Some(obj)
+        } else {
+            None
+        }
+    }
+}
+
+/// A single property override of a prefab instance, i.e. `m_LocalPosition.x` of the object `target`
+/// in the source prefab
+#[derive(Debug, Clone)]
+pub struct PropertyModification {
+    pub target: UnityRef,
+    pub property_path: String,
+    pub value: Yaml,
+    pub object_reference: Option<UnityRef>,
+}
+impl PropertyModification {
+    fn from_yaml(yaml: &Yaml) -> anyhow::Result<Self> {
+        Ok(Self {
+            target: UnityRef::from_yaml(&yaml["target"])?,
+            property_path: yaml["propertyPath"]
+                .as_str()
+                .context("propertyPath not a string")?
+                .to_string(),
+            value: yaml["value"].clone(),
+            object_reference: UnityRef::from_yaml(&yaml["objectReference"])
+                .ok()
+                .filter(|x| x.file_id != 0),
+        })
+    }
+    pub fn value_f32(&self) -> Option<f32> {
+        self.value
+            .as_float()
+            .or_else(|| self.value.as_str().and_then(|x| x.parse().ok()))
+            .map(|x| x as f32)
+    }
+    pub fn value_string(&self) -> String {
+        match &self.value {
+            Yaml::String(x) | Yaml::Real(x) => x.clone(),
+            Yaml::Integer(x) => x.to_string(),
+            Yaml::Boolean(x) => x.to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// A placeholder for an object that lives in the source prefab of a prefab instance
+#[derive(Debug)]
+pub struct StrippedObject {
+    pub corresponding_source_object: UnityRef,
+    pub prefab_instance: UnityRef,
+}
+impl StrippedObject {
+    /// Takes the whole document, as the type of a stripped object doesn't matter
+    pub(crate) fn from_yaml(doc: &Yaml) -> anyhow::Result<Self> {
+        let yaml = doc
+            .as_hash()
+            .and_then(|doc| {
+                doc.iter()
+                    .find(|(key, _)| key.as_str() != Some("unity_object"))
+                    .map(|(_, value)| value)
+            })
+            .context("Stripped object is empty")?;
+        // Files written before Unity 2018.3 use the older names
+        let field = |name: &str, legacy_name: &str| match &yaml[name] {
+            Yaml::BadValue => UnityRef::from_yaml(&yaml[legacy_name]),
+            value => UnityRef::from_yaml(value),
+        };
+        Ok(Self {
+            corresponding_source_object: field(
+                "m_CorrespondingSourceObject",
+                "m_PrefabParentObject",
+            )?,
+            prefab_instance: field("m_PrefabInstance", "m_PrefabInternal")?,
+        })
+    }
+    pub fn dump(&self, _prefab: &PrefabFile) -> yaml_rust::yaml::Hash {
+        let mut out = yaml_rust::yaml::Hash::new();
+        out.insert(
+            Yaml::String("type".to_string()),
+            Yaml::String("Stripped".to_string()),
+        );
+        out.insert(
+            Yaml::String("prefab_instance".to_string()),
+            Yaml::Integer(self.prefab_instance.file_id),
+        );
+        out
+    }
+}
+impl GetObject for StrippedObject {
+    fn get_object(obj: &PrefabObject) -> Option<&Self> {
+        if let PrefabObject::Stripped(obj) = obj {
+            Some(obj)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightType {
+    Spot,
+    Directional,
+    Point,
+    Area,
+}
+impl LightType {
+    fn from_id(id: i64) -> Self {
+        match id {
+            0 => Self::Spot,
+            1 => Self::Directional,
+            2 => Self::Point,
+            _ => Self::Area,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Light {
+    game_object: UnityRef,
+    pub enabled: bool,
+    pub type_: LightType,
+    pub color: Vec3,
+    pub intensity: f32,
+    pub range: f32,
+    /// In degrees
+    pub spot_angle: f32,
+}
+impl Light {
+    pub(crate) fn from_yaml(yaml: &Yaml) -> anyhow::Result<Self> {
+        let color = &yaml["m_Color"];
+        Ok(Self {
+            game_object: UnityRef::from_yaml(&yaml["m_GameObject"])?,
+            enabled: yaml["m_Enabled"].as_i64().map_or(true, |x| x != 0),
+            type_: LightType::from_id(yaml["m_Type"].as_i64().context("m_Type not an int")?),
+            color: Vec3::new(
+                color["r"].as_float().context("Failed to parse m_Color.r")? as f32,
+                color["g"].as_float().context("Failed to parse m_Color.g")? as f32,
+                color["b"].as_float().context("Failed to parse m_Color.b")? as f32,
+            ),
+            intensity: yaml["m_Intensity"].as_float().unwrap_or(1.) as f32,
+            range: yaml["m_Range"].as_float().unwrap_or(10.) as f32,
+            spot_angle: yaml["m_SpotAngle"].as_float().unwrap_or(30.) as f32,
+        })
+    }
+    pub fn get_game_object<'a>(&self, prefab: &'a PrefabFile) -> Option<&'a GameObject> {
+        prefab
+            .objects
+            .get(&self.game_object.file_id)
+            .and_then(|x| x.as_object::<GameObject>())
+    }
+    pub fn dump(&self, _prefab: &PrefabFile) -> yaml_rust::yaml::Hash {
+        let mut out = yaml_rust::yaml::Hash::new();
+        out.insert(
+            Yaml::String("type".to_string()),
+            Yaml::String("Light".to_string()),
+        );
+        out.insert(
+            Yaml::String("light_type".to_string()),
+            Yaml::String(format!("{:?}", self.type_)),
+        );
+        out
+    }
+}
+impl GetObject for Light {
+    fn get_object(obj: &PrefabObject) -> Option<&Self> {
+        if let PrefabObject::Light(obj) = obj {
+            Some(obj)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ColliderShape {
+    Box {
+        size: Vec3,
+        center: Vec3,
+    },
+    Sphere {
+        radius: f32,
+        center: Vec3,
+    },
+    Capsule {
+        radius: f32,
+        height: f32,
+        /// The axis of the capsule; 0 = X, 1 = Y, 2 = Z
+        direction: usize,
+        center: Vec3,
+    },
+    Mesh {
+        mesh: UnityRef,
+        convex: bool,
+    },
+}
+
+/// A BoxCollider, SphereCollider, CapsuleCollider or MeshCollider
+#[derive(Debug, Clone)]
+pub struct Collider {
+    game_object: UnityRef,
+    pub enabled: bool,
+    pub is_trigger: bool,
+    pub shape: ColliderShape,
+}
+impl Collider {
+    /// Returns None if the object isn't a collider
+    pub(crate) fn from_object_yaml(doc: &Yaml) -> anyhow::Result<Option<Self>> {
+        let (yaml, shape) = if doc["BoxCollider"].as_hash().is_some() {
+            let yaml = &doc["BoxCollider"];
+            (
+                yaml,
+                ColliderShape::Box {
+                    size: vec3_from_yaml(&yaml["m_Size"])?,
+                    center: vec3_from_yaml(&yaml["m_Center"])?,
+                },
+            )
+        } else if doc["SphereCollider"].as_hash().is_some() {
+            let yaml = &doc["SphereCollider"];
+            (
+                yaml,
+                ColliderShape::Sphere {
+                    radius: yaml["m_Radius"]
+                        .as_float()
+                        .context("m_Radius not a float")? as f32,
+                    center: vec3_from_yaml(&yaml["m_Center"])?,
+                },
+            )
+        } else if doc["CapsuleCollider"].as_hash().is_some() {
+            let yaml = &doc["CapsuleCollider"];
+            (
+                yaml,
+                ColliderShape::Capsule {
+                    radius: yaml["m_Radius"]
+                        .as_float()
+                        .context("m_Radius not a float")? as f32,
+                    height: yaml["m_Height"]
+                        .as_float()
+                        .context("m_Height not a float")? as f32,
+                    direction: yaml["m_Direction"].as_i64().unwrap_or(1) as usize,
+                    center: vec3_from_yaml(&yaml["m_Center"])?,
+                },
+            )
+        } else if doc["MeshCollider"].as_hash().is_some() {
+            let yaml = &doc["MeshCollider"];
+            (
+                yaml,
+                ColliderShape::Mesh {
+                    mesh: UnityRef::from_yaml(&yaml["m_Mesh"])?,
+                    convex: yaml["m_Convex"].as_i64().unwrap_or(0) != 0,
+                },
+            )
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            game_object: UnityRef::from_yaml(&yaml["m_GameObject"])?,
+            enabled: yaml["m_Enabled"].as_i64().map_or(true, |x| x != 0),
+            is_trigger: yaml["m_IsTrigger"].as_i64().unwrap_or(0) != 0,
+            shape,
+        }))
+    }
+    pub fn get_game_object<'a>(&self, prefab: &'a PrefabFile) -> Option<&'a GameObject> {
+        prefab
+            .objects
+            .get(&self.game_object.file_id)
+            .and_then(|x| x.as_object::<GameObject>())
+    }
+    pub fn dump(&self, _prefab: &PrefabFile) -> yaml_rust::yaml::Hash {
+        let mut out = yaml_rust::yaml::Hash::new();
+        out.insert(
+            Yaml::String("type".to_string()),
+            Yaml::String("Collider".to_string()),
+        );
+        out.insert(
+            Yaml::String("shape".to_string()),
+            Yaml::String(format!("{:?}", self.shape)),
+        );
+        out
+    }
+}
+impl GetObject for Collider {
+    fn get_object(obj: &PrefabObject) -> Option<&Self> {
+        if let PrefabObject::Collider(obj) = obj {
+            Some(obj)
+        } else {
+            None
+        }
+    }
+}
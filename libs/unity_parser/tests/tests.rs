@@ -1,4 +1,9 @@
-use ambient_unity_parser::{asset::Asset, mat::Material, prefab::PrefabFile};
+use ambient_unity_parser::{
+    asset::Asset,
+    mat::Material,
+    prefab::{PrefabFile, Transform},
+    scene::{Collider, ColliderShape, Light, LightType, SceneNode, UnityScene},
+};
 
 #[test]
 pub fn test_prefab() {
@@ -14,3 +19,42 @@ pub fn test_asset() {
 pub fn test_mat() {
     Material::from_string(include_str!("M_Fir_Bark_01.mat")).unwrap();
 }
+
+#[test]
+pub fn test_scene() {
+    let scene = UnityScene::from_string(include_str!("Level_01.unity")).unwrap();
+    let roots = scene.get_root_nodes();
+    assert_eq!(roots.len(), 3);
+
+    let SceneNode::GameObject(light_obj) = roots[0] else {
+        panic!("Expected a game object");
+    };
+    let light = light_obj.get_component::<Light>(&scene.0).unwrap();
+    assert_eq!(light.type_, LightType::Directional);
+    assert_eq!(light.intensity, 1.2);
+
+    let SceneNode::GameObject(wall) = roots[1] else {
+        panic!("Expected a game object");
+    };
+    let colliders = wall
+        .get_components::<Collider>(&scene.0)
+        .collect::<Vec<_>>();
+    assert_eq!(colliders.len(), 2);
+    assert!(matches!(colliders[0].shape, ColliderShape::Box { .. }));
+    assert!(colliders[1].is_trigger);
+
+    let wall_transform = wall.get_component::<Transform>(&scene.0).unwrap();
+    let children = scene.0.get_child_nodes(wall_transform);
+    let SceneNode::PrefabInstance { instance, .. } = children[0] else {
+        panic!("Expected a prefab instance");
+    };
+    let mut source = PrefabFile::from_string(include_str!("Fir_01_Plant.prefab")).unwrap();
+    source.apply_instance(instance);
+    let root = source.get_root_game_objects()[0];
+    assert_eq!(root.name, "Fir (Wall)");
+    let transform = root.get_component::<Transform>(&source).unwrap();
+    assert_eq!(transform.local_position.x, 1.5);
+    assert_eq!(transform.local_scale.y, 2.);
+
+    assert!(matches!(roots[2], SceneNode::PrefabInstance { .. }));
+}
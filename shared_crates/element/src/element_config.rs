@@ -22,6 +22,8 @@ pub(crate) struct ElementConfig {
     pub on_spawned: Option<Arc<dyn Fn(&mut World, EntityId, &str) + Sync + Send>>,
     #[derivative(Debug = "ignore")]
     pub on_despawn: Option<Arc<dyn Fn(&mut World, EntityId, &str) + Sync + Send>>,
+    pub on_enter: Option<Transition>,
+    pub on_exit: Option<Transition>,
     pub key: String,
     pub memo_key: Option<String>,
 }
@@ -37,6 +39,8 @@ impl ElementConfig {
             }),
             on_spawned: None,
             on_despawn: None,
+            on_enter: None,
+            on_exit: None,
             key: "".to_string(),
             memo_key: None,
         }
@@ -62,6 +66,17 @@ impl ElementConfig {
     }
 }
 
+/// An animation of an element's entity, run when it's spawned or removed.
+#[derive(Derivative, Clone)]
+#[derivative(Debug)]
+pub(crate) struct Transition {
+    /// The length of the animation, in seconds.
+    pub duration: f32,
+    /// Called every frame with the entity and the progress, from 0 to 1.
+    #[derivative(Debug = "ignore")]
+    pub animate: Arc<dyn Fn(&mut World, EntityId, f32) + Sync + Send>,
+}
+
 #[derive(Clone)]
 #[allow(clippy::type_complexity)]
 pub(crate) struct ElementComponents(
@@ -236,6 +236,34 @@ impl Element {
         self.config.on_despawn = Some(Arc::new(handler));
         self
     }
+    /// Animate the element's entity for `duration` seconds after it's spawned. `animate` is called
+    /// every frame with the entity and the progress, from 0 to 1.
+    pub fn on_enter<F: Fn(&mut World, EntityId, f32) + Sync + Send + 'static>(
+        mut self,
+        duration: f32,
+        animate: F,
+    ) -> Self {
+        self.config.on_enter = Some(Transition {
+            duration,
+            animate: Arc::new(animate),
+        });
+        self
+    }
+    /// Keep the element's entities for `duration` seconds after it's removed from the tree, calling
+    /// `animate` every frame with the entity and the progress, from 0 to 1, and then despawn them.
+    ///
+    /// The element's hooks are cleaned up when it's removed, so it doesn't re-render while exiting.
+    pub fn on_exit<F: Fn(&mut World, EntityId, f32) + Sync + Send + 'static>(
+        mut self,
+        duration: f32,
+        animate: F,
+    ) -> Self {
+        self.config.on_exit = Some(Transition {
+            duration,
+            animate: Arc::new(animate),
+        });
+        self
+    }
     /// Set the unique key used to identify this element.
     ///
     /// This is used to disambiguate elements with the same type. This should be used when rendering lists of elements.
@@ -1,3 +1,5 @@
+#[cfg(feature = "guest")]
+use std::time::Instant;
 use std::{
     any::TypeId,
     collections::{HashMap, HashSet},
@@ -10,7 +12,7 @@ use crate::element_tree;
 use crate::element_unmanaged_children;
 use crate::{
     AnyCloneable, ContextUpdate, DespawnFn, Element, ElementConfig, Hooks, HooksEnvironment,
-    InstanceId, StateUpdate,
+    InstanceId, StateUpdate, Transition,
 };
 #[cfg(feature = "native")]
 use ambient_core::hierarchy::{children, parent};
@@ -23,6 +25,8 @@ use ambient_guest_bridge::{
     core::{app::components::name, hierarchy::components::unmanaged_children},
     ecs::{Entity, EntityId, World},
 };
+#[cfg(feature = "native")]
+use ambient_sys::time::Instant;
 use itertools::Itertools;
 use parking_lot::Mutex;
 use tracing::debug_span;
@@ -104,6 +108,42 @@ impl ElementInstance {
     }
 }
 
+type Despawner = Arc<dyn Fn(&mut World, EntityId) + Sync + Send>;
+
+/// An enter or exit [Transition] that is running.
+#[derive(Debug)]
+struct RunningTransition {
+    entity: EntityId,
+    transition: Transition,
+    started: Instant,
+    /// Set for exit transitions.
+    exit: Option<Exit>,
+}
+
+/// The entities of a removed element, kept while its exit transition runs.
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Exit {
+    parent_entity: Option<EntityId>,
+    /// The position of the entity among its parent's children when it was removed.
+    index: usize,
+    #[derivative(Debug = "ignore")]
+    despawn: Vec<(Despawner, EntityId)>,
+}
+impl Exit {
+    fn finish(self, world: &mut World, entity: EntityId) {
+        if let Some(parent) = self.parent_entity {
+            if let Ok(mut siblings) = world.get_cloned(parent, children()) {
+                siblings.retain(|sibling| *sibling != entity);
+                world.set(parent, children(), siblings).ok();
+            }
+        }
+        for (despawner, entity) in self.despawn {
+            despawner(world, entity);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) enum ElementParent {
     Super(InstanceId),
@@ -117,6 +157,7 @@ pub struct ElementTree {
     pub(crate) instances: HashMap<InstanceId, ElementInstance>,
     pub(crate) hooks_env: Arc<Mutex<HooksEnvironment>>,
     pub(crate) root: Option<InstanceId>,
+    transitions: Vec<RunningTransition>,
 }
 impl ElementTree {
     /// Creates a new [ElementTree] from the given [Element].
@@ -125,6 +166,7 @@ impl ElementTree {
             instances: HashMap::new(),
             hooks_env: Arc::new(Mutex::new(HooksEnvironment::new())),
             root: None,
+            transitions: Vec::new(),
         };
         let (_, instance) = s.create(world, element, None, ElementParent::None);
         s.update_instance_children(world, &instance);
@@ -212,11 +254,18 @@ impl ElementTree {
     }
 
     /// Remove the root of this tree.
+    ///
+    /// Exit transitions are cut short, as the tree won't be updated anymore.
     pub fn remove_root(&mut self, world: &mut World) {
         if let Some(root) = self.root.clone() {
             self.remove(world, &root);
         }
         self.root = None;
+        for transition in std::mem::take(&mut self.transitions) {
+            if let Some(exit) = transition.exit {
+                exit.finish(world, transition.entity);
+            }
+        }
     }
 
     fn create(
@@ -361,8 +410,16 @@ impl ElementTree {
             if let Some(on_spawned) = &instance.config.on_spawned {
                 on_spawned(world, entity, instance_id);
             }
+            if let Some(transition) = instance.config.on_enter.clone() {
+                self.transitions.push(RunningTransition {
+                    entity,
+                    transition,
+                    started: Instant::now(),
+                    exit: None,
+                });
+            }
         }
-        instance.entity
+        entity
     }
     fn rerender_instance(&mut self, world: &mut World, instance_id: &str) {
         let old_entity = if let Some(instance) = self.instances.get(instance_id) {
@@ -473,6 +530,46 @@ impl ElementTree {
         entity
     }
     fn remove(&mut self, world: &mut World, instance_id: &str) {
+        let (on_exit, entity, parent_entity) = {
+            let instance = self.instances.get(instance_id).unwrap();
+            (
+                instance.config.on_exit.clone(),
+                instance.entity,
+                instance.parent_entity,
+            )
+        };
+        match on_exit {
+            Some(transition) if world.exists(entity) => {
+                let mut despawn = Vec::new();
+                self.remove_instance(world, instance_id, Some(&mut despawn));
+                let index = parent_entity
+                    .and_then(|parent| world.get_cloned(parent, children()).ok())
+                    .and_then(|siblings| siblings.iter().position(|sibling| *sibling == entity))
+                    .unwrap_or_default();
+                self.transitions
+                    .retain(|transition| transition.entity != entity);
+                self.transitions.push(RunningTransition {
+                    entity,
+                    transition,
+                    started: Instant::now(),
+                    exit: Some(Exit {
+                        parent_entity,
+                        index,
+                        despawn,
+                    }),
+                });
+            }
+            _ => self.remove_instance(world, instance_id, None),
+        }
+    }
+    /// Removes an instance and its descendants. If `despawn` is set, their entities are added to it
+    /// instead of being despawned.
+    fn remove_instance(
+        &mut self,
+        world: &mut World,
+        instance_id: &str,
+        mut despawn: Option<&mut Vec<(Despawner, EntityId)>>,
+    ) {
         let mut instance = self.instances.remove(instance_id).unwrap();
 
         if let Some(on_despawn) = &instance.config.on_despawn {
@@ -482,7 +579,10 @@ impl ElementTree {
             on_despawn(world);
         }
         if instance.config.part.is_none() {
-            (instance.config.despawner)(world, instance.entity);
+            match despawn.as_deref_mut() {
+                Some(despawn) => despawn.push((instance.config.despawner.clone(), instance.entity)),
+                None => (instance.config.despawner)(world, instance.entity),
+            }
         }
         instance.entity = EntityId::null();
         instance.hooks_state = Vec::new();
@@ -496,17 +596,52 @@ impl ElementTree {
             }
         }
 
+        // The element rendered by a component may have an exit transition of its own
         if let Some(super_) = &instance.super_ {
-            self.remove(world, super_);
+            match despawn.as_deref_mut() {
+                Some(despawn) => self.remove_instance(world, super_, Some(despawn)),
+                None => self.remove(world, super_),
+            }
         }
         for child in &instance.children {
-            self.remove(world, child);
+            match despawn.as_deref_mut() {
+                Some(despawn) => self.remove_instance(world, child, Some(despawn)),
+                None => self.remove(world, child),
+            }
+        }
+    }
+    fn update_transitions(&mut self, world: &mut World) {
+        let mut running = Vec::new();
+        for transition in std::mem::take(&mut self.transitions) {
+            let RunningTransition {
+                entity,
+                transition: Transition { duration, animate },
+                started,
+                ..
+            } = &transition;
+            let progress = if *duration > 0. {
+                (started.elapsed().as_secs_f32() / duration).min(1.)
+            } else {
+                1.
+            };
+            if world.exists(*entity) {
+                animate(world, *entity, progress);
+            }
+            if progress < 1. {
+                running.push(transition);
+            } else if let Some(exit) = transition.exit {
+                exit.finish(world, transition.entity);
+            }
         }
+        // Transitions started by the callbacks are kept
+        running.append(&mut self.transitions);
+        self.transitions = running;
     }
 
     #[profiling::function]
     /// Update the tree and re-render instances as required.
     pub fn update(&mut self, world: &mut World) {
+        self.update_transitions(world);
         let frame_listeners = self.hooks_env.lock().frame_listeners.clone();
         for listeners in frame_listeners.values() {
             profiling::scope!("frame_listeners");
@@ -558,17 +693,23 @@ impl ElementTree {
         if !world.has_component(instance.entity, element_unmanaged_children()) {
             let mut all_children = Vec::new();
             self.get_full_instance_children(id, &mut all_children);
+            let mut entities = all_children
+                .iter()
+                .map(|c| self.instances.get(c).unwrap().entity)
+                .collect_vec();
+            // Exiting children stay where they were until they are despawned
+            for transition in &self.transitions {
+                if let Some(exit) = &transition.exit {
+                    if exit.parent_entity == Some(instance.entity) {
+                        entities.insert(exit.index.min(entities.len()), transition.entity);
+                    }
+                }
+            }
             world
                 .add_components(
                     instance.entity,
                     Entity::new()
-                        .with(
-                            children(),
-                            all_children
-                                .iter()
-                                .map(|c| self.instances.get(c).unwrap().entity)
-                                .collect_vec(),
-                        )
+                        .with(children(), entities)
                         .with(unmanaged_children(), ()),
                 )
                 .unwrap();
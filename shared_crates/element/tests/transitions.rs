@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use ambient_element::{use_state, Element, ElementComponent, ElementComponentExt, Hooks};
+use ambient_guest_bridge::ecs::query_mut;
+mod common;
+use ambient_cb::cb;
+use common::*;
+use itertools::Itertools;
+
+#[test]
+fn enter_transition_runs_on_spawn() {
+    let mut world = initialize();
+    let mut tree = Element::new()
+        .init(prop_b(), 0)
+        .on_enter(0., |world, entity, progress| {
+            world
+                .set(entity, prop_b(), (progress * 100.) as u32)
+                .unwrap();
+        })
+        .spawn_tree(&mut world);
+    tree.update(&mut world);
+    let values = query_mut((), (prop_b(),))
+        .iter(&mut world, None)
+        .map(|(_, _, (value,))| *value)
+        .collect_vec();
+    assert_eq!(values, vec![100]);
+}
+
+#[test]
+fn exit_transition_delays_despawn() {
+    #[derive(Debug, Clone)]
+    pub struct Root;
+    impl ElementComponent for Root {
+        fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+            let (visible, set_visible) = use_state(hooks, true);
+            Element::new()
+                .with(trigger(), cb(move |_| set_visible(false)))
+                .children(if visible {
+                    vec![Element::new().init(prop_b(), 0).on_exit(
+                        0.05,
+                        |world, entity, progress| {
+                            world
+                                .set(entity, prop_b(), (progress * 100.) as u32)
+                                .unwrap();
+                        },
+                    )]
+                } else {
+                    vec![]
+                })
+        }
+    }
+
+    let mut world = initialize();
+    let mut tree = Root.el().spawn_tree(&mut world);
+    let child = query_mut((), (prop_b(),))
+        .iter(&mut world, None)
+        .map(|(id, _, _)| id)
+        .next()
+        .unwrap();
+
+    run_triggers(&mut world);
+    tree.update(&mut world);
+    assert!(world.exists(child));
+
+    std::thread::sleep(Duration::from_millis(100));
+    tree.update(&mut world);
+    assert!(!world.exists(child));
+}
+
+#[test]
+fn exit_transition_of_a_component_delays_despawn() {
+    #[derive(Debug, Clone)]
+    pub struct Fading;
+    impl ElementComponent for Fading {
+        fn render(self: Box<Self>, _: &mut Hooks) -> Element {
+            Element::new()
+                .init(prop_b(), 0)
+                .on_exit(0.05, |world, entity, progress| {
+                    world
+                        .set(entity, prop_b(), (progress * 100.) as u32)
+                        .unwrap();
+                })
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Root;
+    impl ElementComponent for Root {
+        fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+            let (visible, set_visible) = use_state(hooks, true);
+            Element::new()
+                .with(trigger(), cb(move |_| set_visible(false)))
+                .children(if visible { vec![Fading.el()] } else { vec![] })
+        }
+    }
+
+    let mut world = initialize();
+    let mut tree = Root.el().spawn_tree(&mut world);
+    let child = query_mut((), (prop_b(),))
+        .iter(&mut world, None)
+        .map(|(id, _, _)| id)
+        .next()
+        .unwrap();
+
+    run_triggers(&mut world);
+    tree.update(&mut world);
+    assert!(world.exists(child));
+
+    std::thread::sleep(Duration::from_millis(100));
+    tree.update(&mut world);
+    assert!(!world.exists(child));
+}
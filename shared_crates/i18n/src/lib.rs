@@ -0,0 +1,263 @@
+//! String tables, plural rules and locale selection.
+//!
+//! Strings are written in a subset of [Fluent](https://projectfluent.org/), one table per locale:
+//!
+//! ```ftl
+//! # Comments start with a hash
+//! greeting = Hello, { $name }!
+//! inbox = { $count ->
+//!     [0] Your inbox is empty.
+//!     [one] You have one message.
+//!    *[other] You have { $count } messages.
+//! }
+//! about =
+//!     Values can continue on
+//!     indented lines.
+//! ```
+//!
+//! Placeables can be variables (`{ $name }`), references to other messages (`{ greeting }`),
+//! string literals (`{ "{" }`) and select expressions. Variants of a select expression match a
+//! string value by name, and a number either exactly or by its [PluralCategory] in the locale.
+//!
+//! Tables are added to the global registry with [add_locale], and the current locale is chosen with
+//! [set_locale]. [t!] translates a key in the current locale:
+//!
+//! ```
+//! ambient_i18n::add_locale("en", "greeting = Hello, { $name }!").unwrap();
+//! assert_eq!(ambient_i18n::t!("greeting", name = "Ada"), "Hello, Ada!");
+//! ```
+use std::{collections::HashMap, fmt};
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+
+mod parser;
+mod plural;
+
+pub use parser::ParseError;
+use parser::{Part, Pattern, Variant, VariantKey};
+pub use plural::PluralCategory;
+
+/// The locale that is used when a key is missing from the current locale.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// References to other messages are followed up to this depth, to stop cycles.
+const MAX_DEPTH: usize = 8;
+
+/// An argument of a translation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Number(f64),
+}
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(value) => f.write_str(value),
+            Value::Number(value) if value.fract() == 0. && value.abs() < 1e15 => {
+                write!(f, "{}", *value as i64)
+            }
+            Value::Number(value) => write!(f, "{value}"),
+        }
+    }
+}
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Value::String(value.clone())
+    }
+}
+macro_rules! number_value {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(value: $ty) -> Self {
+                    Value::Number(value as f64)
+                }
+            }
+        )*
+    };
+}
+number_value!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// The string tables of a set of locales.
+#[derive(Debug, Clone, Default)]
+pub struct Translations {
+    locales: HashMap<String, HashMap<String, Pattern>>,
+}
+impl Translations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `source` and adds its messages to the table of `locale`, replacing existing messages
+    /// with the same keys.
+    pub fn add(&mut self, locale: &str, source: &str) -> Result<(), ParseError> {
+        let messages = parser::parse(source)?;
+        self.locales
+            .entry(normalize(locale))
+            .or_default()
+            .extend(messages);
+        Ok(())
+    }
+
+    /// Returns true if there is a table for `locale`.
+    pub fn has_locale(&self, locale: &str) -> bool {
+        self.locales.contains_key(&normalize(locale))
+    }
+
+    /// The locales that have a table.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.locales.keys().map(|locale| locale.as_str())
+    }
+
+    /// Translates `key` in `locale`.
+    ///
+    /// If `locale` doesn't have the key, its parent locales are tried, from `pt-BR` to `pt`, and
+    /// then [DEFAULT_LOCALE]. If none of them have the key, the key itself is returned.
+    pub fn translate(&self, locale: &str, key: &str, args: &[(&str, Value)]) -> String {
+        let locale = normalize(locale);
+        match self.find(&locale, key) {
+            Some(pattern) => {
+                let mut out = String::new();
+                self.format(&locale, pattern, args, 0, &mut out);
+                out
+            }
+            None => key.to_string(),
+        }
+    }
+
+    fn find(&self, locale: &str, key: &str) -> Option<&Pattern> {
+        fallbacks(locale).find_map(|locale| self.locales.get(locale)?.get(key))
+    }
+
+    fn format(
+        &self,
+        locale: &str,
+        pattern: &Pattern,
+        args: &[(&str, Value)],
+        depth: usize,
+        out: &mut String,
+    ) {
+        let arg = |name: &str| args.iter().find(|(arg, _)| *arg == name).map(|(_, v)| v);
+        for part in pattern {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Variable(name) => match arg(name) {
+                    Some(value) => out.push_str(&value.to_string()),
+                    None => out.push_str(&format!("{{${name}}}")),
+                },
+                Part::Message(key) => match self.find(locale, key) {
+                    Some(pattern) if depth < MAX_DEPTH => {
+                        self.format(locale, pattern, args, depth + 1, out)
+                    }
+                    _ => out.push_str(&format!("{{{key}}}")),
+                },
+                Part::Select {
+                    selector,
+                    variants,
+                    default,
+                } => {
+                    let selected = arg(selector).and_then(|value| select(locale, variants, value));
+                    let variant = &variants[selected.unwrap_or(*default)];
+                    self.format(locale, &variant.value, args, depth, out);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the variant that matches `value` exactly, or else its plural category.
+fn select(locale: &str, variants: &[Variant], value: &Value) -> Option<usize> {
+    let exact = variants
+        .iter()
+        .position(|variant| match (&variant.key, value) {
+            (VariantKey::Number(key), Value::Number(n)) => key == n,
+            (VariantKey::Name(key), Value::String(s)) => key == s,
+            _ => false,
+        });
+    exact.or_else(|| {
+        let Value::Number(n) = value else {
+            return None;
+        };
+        let category = PluralCategory::of(locale, *n).as_str();
+        variants
+            .iter()
+            .position(|variant| matches!(&variant.key, VariantKey::Name(key) if key == category))
+    })
+}
+
+/// Locale tags are matched case-insensitively, with `-` or `_` as the separator.
+fn normalize(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+/// `locale`, its parents, and then [DEFAULT_LOCALE].
+fn fallbacks(locale: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(locale), |locale| {
+        locale.rsplit_once('-').map(|(parent, _)| parent)
+    })
+    .chain(std::iter::once(DEFAULT_LOCALE))
+}
+
+static TRANSLATIONS: Lazy<RwLock<Translations>> = Lazy::new(Default::default);
+static LOCALE: Lazy<RwLock<String>> = Lazy::new(|| RwLock::new(DEFAULT_LOCALE.to_string()));
+
+/// Parses `source` and adds its messages to the global table of `locale`.
+pub fn add_locale(locale: &str, source: &str) -> Result<(), ParseError> {
+    TRANSLATIONS.write().add(locale, source)
+}
+
+/// Returns true if the global registry has a table for `locale`.
+pub fn has_locale(locale: &str) -> bool {
+    TRANSLATIONS.read().has_locale(locale)
+}
+
+/// The locales that have a table in the global registry.
+pub fn locales() -> Vec<String> {
+    TRANSLATIONS
+        .read()
+        .locales()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+/// Sets the current locale, like `en` or `pt-BR`.
+pub fn set_locale(locale: &str) {
+    *LOCALE.write() = locale.to_string();
+}
+
+/// The current locale. Defaults to [DEFAULT_LOCALE].
+pub fn locale() -> String {
+    LOCALE.read().clone()
+}
+
+/// Translates `key` in the current locale. See [Translations::translate].
+pub fn translate(key: &str, args: &[(&str, Value)]) -> String {
+    TRANSLATIONS.read().translate(&locale(), key, args)
+}
+
+/// Translates a key in the current locale, with named arguments:
+///
+/// ```
+/// # use ambient_i18n::t;
+/// let text = t!("inbox", count = 3, folder = "Archive");
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::translate(
+            $key,
+            &[$((::std::stringify!($name), $crate::Value::from($value))),*],
+        )
+    };
+}
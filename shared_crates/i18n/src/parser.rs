@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+/// An error in the source of a string table.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("line {line}: {message}")]
+pub struct ParseError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Part {
+    Text(String),
+    Variable(String),
+    Message(String),
+    Select {
+        selector: String,
+        variants: Vec<Variant>,
+        default: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Variant {
+    pub key: VariantKey,
+    pub value: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum VariantKey {
+    Number(f64),
+    Name(String),
+}
+
+pub(crate) type Pattern = Vec<Part>;
+
+/// Parses the messages of a string table.
+pub(crate) fn parse(source: &str) -> Result<HashMap<String, Pattern>, ParseError> {
+    Parser {
+        chars: source.chars().filter(|&c| c != '\r').collect(),
+        pos: 0,
+    }
+    .resource()
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+impl Parser {
+    fn resource(&mut self) -> Result<HashMap<String, Pattern>, ParseError> {
+        let mut messages = HashMap::new();
+        loop {
+            self.skip_blank();
+            match self.peek() {
+                None => return Ok(messages),
+                Some('#') => self.skip_line(),
+                Some(_) => {
+                    let key = self.identifier()?;
+                    self.skip_inline_blank();
+                    self.expect('=')?;
+                    self.skip_inline_blank();
+                    let pattern = self.pattern()?;
+                    if pattern.is_empty() {
+                        return Err(self.error(format!("message {key:?} has no value")));
+                    }
+                    match self.peek() {
+                        None | Some('\n') => {}
+                        Some(c) => return Err(self.error(format!("unexpected {c:?}"))),
+                    }
+                    messages.insert(key, pattern);
+                }
+            }
+        }
+    }
+
+    /// Parses text and placeables up to the end of the pattern, which is the end of the last line
+    /// that continues it. Continuation lines are indented, and don't start with a variant or the
+    /// end of a select expression.
+    fn pattern(&mut self) -> Result<Pattern, ParseError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None => break,
+                Some('{') => {
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(self.placeable()?);
+                }
+                Some('}') => return Err(self.error("unexpected '}'".to_string())),
+                Some('\n') => {
+                    let end = self.pos;
+                    let mut lines = 0;
+                    while self.peek() == Some('\n') {
+                        self.pos += 1;
+                        lines += 1;
+                        self.skip_inline_blank();
+                    }
+                    let indented = self.chars[self.pos - 1] != '\n';
+                    let continues =
+                        indented && !matches!(self.peek(), None | Some('[' | '*' | '}' | '.'));
+                    if !continues {
+                        self.pos = end;
+                        break;
+                    }
+                    truncate_end(&mut text);
+                    if !text.is_empty() || !parts.is_empty() {
+                        text.extend(std::iter::repeat('\n').take(lines));
+                    }
+                }
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        truncate_end(&mut text);
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(parts)
+    }
+
+    fn placeable(&mut self) -> Result<Part, ParseError> {
+        self.expect('{')?;
+        self.skip_blank();
+        let part = match self.peek() {
+            Some('$') => {
+                self.pos += 1;
+                let variable = self.identifier()?;
+                self.skip_blank();
+                if self.peek() == Some('-') {
+                    self.expect('-')?;
+                    self.expect('>')?;
+                    self.select(variable)?
+                } else {
+                    Part::Variable(variable)
+                }
+            }
+            Some('"') => Part::Text(self.string_literal()?),
+            Some(c) if c.is_ascii_alphabetic() => Part::Message(self.identifier()?),
+            Some(c) => return Err(self.error(format!("unexpected {c:?} in placeable"))),
+            None => return Err(self.error("unterminated placeable".to_string())),
+        };
+        self.skip_blank();
+        self.expect('}')?;
+        Ok(part)
+    }
+
+    fn select(&mut self, selector: String) -> Result<Part, ParseError> {
+        let mut variants = Vec::new();
+        let mut default = None;
+        loop {
+            self.skip_blank();
+            match self.peek() {
+                Some('}') => break,
+                Some('*') => {
+                    if default.is_some() {
+                        return Err(self.error("more than one default variant".to_string()));
+                    }
+                    default = Some(variants.len());
+                    self.pos += 1;
+                }
+                _ => {}
+            }
+            self.expect('[')?;
+            self.skip_inline_blank();
+            let key = self.variant_key()?;
+            self.skip_inline_blank();
+            self.expect(']')?;
+            self.skip_inline_blank();
+            variants.push(Variant {
+                key,
+                value: self.pattern()?,
+            });
+        }
+        let default =
+            default.ok_or_else(|| self.error("select expression has no default variant".into()))?;
+        Ok(Part::Select {
+            selector,
+            variants,
+            default,
+        })
+    }
+
+    fn variant_key(&mut self) -> Result<VariantKey, ParseError> {
+        let key = self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if key.is_empty() {
+            return Err(self.error("expected a variant key".to_string()));
+        }
+        Ok(match key.parse() {
+            Ok(number) => VariantKey::Number(number),
+            Err(_) => VariantKey::Name(key),
+        })
+    }
+
+    fn string_literal(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => break,
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c @ ('"' | '\\')) => value.push(c),
+                        _ => return Err(self.error("invalid escape sequence".to_string())),
+                    }
+                }
+                Some('\n') | None => {
+                    return Err(self.error("unterminated string literal".to_string()))
+                }
+                Some(c) => value.push(c),
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        if !matches!(self.peek(), Some(c) if c.is_ascii_alphabetic()) {
+            return Err(self.error("expected an identifier".to_string()));
+        }
+        Ok(self.take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected {expected:?}, found {c:?}"))),
+            None => Err(self.error(format!("expected {expected:?}, found the end"))),
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if f(c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+    fn skip_inline_blank(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+    fn skip_blank(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n')) {
+            self.pos += 1;
+        }
+    }
+    fn skip_line(&mut self) {
+        while !matches!(self.peek(), None | Some('\n')) {
+            self.pos += 1;
+        }
+    }
+    fn error(&self, message: String) -> ParseError {
+        let line = 1 + self.chars[..self.pos.min(self.chars.len())]
+            .iter()
+            .filter(|&&c| c == '\n')
+            .count();
+        ParseError { line, message }
+    }
+}
+
+fn truncate_end(text: &mut String) {
+    text.truncate(text.trim_end_matches([' ', '\t']).len());
+}
@@ -0,0 +1,86 @@
+/// The plural category of a number, as defined by the
+/// [CLDR](https://cldr.unicode.org/index/cldr-spec/plural-rules).
+///
+/// Select expressions match these by name, like `[one]` or `[few]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+impl PluralCategory {
+    /// Returns the category for the number `n` in `locale`.
+    ///
+    /// The cardinal rules of the most common languages are supported; the other languages use the
+    /// English rules.
+    pub fn of(locale: &str, n: f64) -> Self {
+        use PluralCategory::*;
+
+        let n = n.abs();
+        let integer = n.fract() == 0.;
+        let i = n.trunc() as u64;
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "my" | "lo" | "km" => Other,
+            "fr" | "pt" | "hi" | "bn" | "fa" | "zu" => {
+                if i <= 1 {
+                    One
+                } else {
+                    Other
+                }
+            }
+            "ru" | "uk" | "be" | "pl" if !integer => Other,
+            "ru" | "uk" | "be" => match (i % 10, i % 100) {
+                (1, r) if r != 11 => One,
+                (2..=4, r) if !(12..=14).contains(&r) => Few,
+                _ => Many,
+            },
+            "pl" => match (i, i % 10, i % 100) {
+                (1, _, _) => One,
+                (_, 2..=4, r) if !(12..=14).contains(&r) => Few,
+                _ => Many,
+            },
+            "cs" | "sk" => match i {
+                _ if !integer => Many,
+                1 => One,
+                2..=4 => Few,
+                _ => Other,
+            },
+            "ar" => match (integer, i, i % 100) {
+                (false, _, _) => Other,
+                (_, 0, _) => Zero,
+                (_, 1, _) => One,
+                (_, 2, _) => Two,
+                (_, _, 3..=10) => Few,
+                (_, _, 11..=99) => Many,
+                _ => Other,
+            },
+            _ => {
+                if integer && i == 1 {
+                    One
+                } else {
+                    Other
+                }
+            }
+        }
+    }
+
+    /// The name of the category, as used in select expressions.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
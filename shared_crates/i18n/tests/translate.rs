@@ -0,0 +1,116 @@
+use ambient_i18n::{PluralCategory, Translations, Value};
+
+const EN: &str = r#"
+# Greetings
+greeting = Hello, { $name }!
+inbox = { $count ->
+    [0] Your inbox is empty.
+    [one] You have one message.
+   *[other] You have { $count } messages.
+}
+about =
+    First line
+    second line
+title = { app-name } settings
+app-name = Ambient
+braces = Use { "{" } and { "}" }
+theme = { $mode ->
+    [dark] Dark
+   *[light] Light
+}
+"#;
+
+const RU: &str = r#"
+inbox = { $count ->
+    [one] { $count } сообщение
+    [few] { $count } сообщения
+   *[many] { $count } сообщений
+}
+"#;
+
+fn translations() -> Translations {
+    let mut translations = Translations::new();
+    translations.add("en", EN).unwrap();
+    translations.add("ru", RU).unwrap();
+    translations
+}
+
+#[test]
+fn formats_variables_and_references() {
+    let t = translations();
+    assert_eq!(
+        t.translate("en", "greeting", &[("name", "Ada".into())]),
+        "Hello, Ada!"
+    );
+    assert_eq!(t.translate("en", "greeting", &[]), "Hello, {$name}!");
+    assert_eq!(t.translate("en", "title", &[]), "Ambient settings");
+    assert_eq!(t.translate("en", "about", &[]), "First line\nsecond line");
+    assert_eq!(t.translate("en", "braces", &[]), "Use { and }");
+}
+
+#[test]
+fn selects_variants() {
+    let t = translations();
+    let inbox = |locale, count: i32| t.translate(locale, "inbox", &[("count", count.into())]);
+    assert_eq!(inbox("en", 0), "Your inbox is empty.");
+    assert_eq!(inbox("en", 1), "You have one message.");
+    assert_eq!(inbox("en", 5), "You have 5 messages.");
+    assert_eq!(inbox("ru", 1), "1 сообщение");
+    assert_eq!(inbox("ru", 3), "3 сообщения");
+    assert_eq!(inbox("ru", 11), "11 сообщений");
+    assert_eq!(inbox("ru", 21), "21 сообщение");
+    assert_eq!(
+        t.translate("en", "theme", &[("mode", Value::from("dark"))]),
+        "Dark"
+    );
+    assert_eq!(t.translate("en", "theme", &[]), "Light");
+}
+
+#[test]
+fn falls_back_to_parent_and_default_locales() {
+    let mut t = translations();
+    t.add("pt", "greeting = Olá, { $name }!").unwrap();
+    let args = [("name", Value::from("Ada"))];
+    assert_eq!(t.translate("pt-BR", "greeting", &args), "Olá, Ada!");
+    assert_eq!(t.translate("pt_br", "title", &[]), "Ambient settings");
+    assert_eq!(t.translate("ja", "greeting", &args), "Hello, Ada!");
+    assert_eq!(t.translate("en", "missing", &[]), "missing");
+}
+
+#[test]
+fn reports_errors_with_lines() {
+    let mut t = Translations::new();
+    let err = t
+        .add("en", "ok = Fine\nbroken = { $count ->\n   [one] One\n}\n")
+        .unwrap_err();
+    assert_eq!(err.line, 4);
+    assert!(t.add("en", "stray = a } b").is_err());
+    assert!(t.add("en", "empty =\n").is_err());
+}
+
+#[test]
+fn plural_categories() {
+    use PluralCategory::*;
+    assert_eq!(PluralCategory::of("en", 1.), One);
+    assert_eq!(PluralCategory::of("en", 1.5), Other);
+    assert_eq!(PluralCategory::of("fr", 0.), One);
+    assert_eq!(PluralCategory::of("pl", 22.), Few);
+    assert_eq!(PluralCategory::of("pl", 25.), Many);
+    assert_eq!(PluralCategory::of("ar", 0.), Zero);
+    assert_eq!(PluralCategory::of("ar", 2.), Two);
+    assert_eq!(PluralCategory::of("ar", 105.), Few);
+    assert_eq!(PluralCategory::of("zh-Hans", 1.), Other);
+}
+
+#[test]
+fn global_registry() {
+    ambient_i18n::add_locale("en", "farewell = Goodbye, { $name }").unwrap();
+    ambient_i18n::add_locale("de", "farewell = Auf Wiedersehen, { $name }").unwrap();
+    ambient_i18n::set_locale("de-AT");
+    assert_eq!(
+        ambient_i18n::t!("farewell", name = "Ada"),
+        "Auf Wiedersehen, Ada"
+    );
+    ambient_i18n::set_locale("en");
+    assert_eq!(ambient_i18n::t!("farewell", name = "Ada"), "Goodbye, Ada");
+}
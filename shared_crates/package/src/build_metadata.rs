@@ -21,6 +21,11 @@ pub struct BuildMetadata {
     pub settings: BuildSettings,
     #[serde(default)]
     pub asset: Vec<BuildAsset>,
+    /// Every file in the build output, relative to the build directory.
+    ///
+    /// Used to retrieve a deployment in full; older builds will not have this.
+    #[serde(default)]
+    pub files: Vec<String>,
 }
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct BuildSettings {
@@ -0,0 +1,81 @@
+use ambient_shared_types::InputBinding;
+use serde::{Deserialize, Serialize};
+
+/// A named input action, like `jump`, that is triggered by any of its bindings.
+#[derive(Deserialize, Clone, Debug, PartialEq, Serialize)]
+pub struct Action {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub bindings: Vec<InputBinding>,
+}
+
+/// A named input axis, like `move_forward`, with a value in the range `-1.0..=1.0`.
+///
+/// Digital bindings contribute `1.0` when pressed; analog bindings (gamepad sticks and triggers)
+/// contribute their current value.
+#[derive(Deserialize, Clone, Debug, PartialEq, Serialize)]
+pub struct Axis {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub positive: Vec<InputBinding>,
+    #[serde(default)]
+    pub negative: Vec<InputBinding>,
+}
+
+#[cfg(test)]
+mod tests {
+    use ambient_shared_types::{GamepadAxis, GamepadButton, MouseButton, VirtualKeyCode};
+
+    use super::*;
+
+    #[test]
+    fn can_parse_bindings() {
+        let action: Action = toml::from_str(
+            r#"
+            description = "Jump"
+            bindings = ["Space", "Mouse.Right", "Mouse.4", "Gamepad.South"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            action.bindings,
+            vec![
+                InputBinding::Key(VirtualKeyCode::Space),
+                InputBinding::Mouse(MouseButton::Right),
+                InputBinding::Mouse(MouseButton::Other(4)),
+                InputBinding::GamepadButton(GamepadButton::South),
+            ]
+        );
+
+        let axis: Axis = toml::from_str(
+            r#"
+            positive = ["W", "Gamepad.LeftStickY"]
+            negative = ["S"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            axis.positive,
+            vec![
+                InputBinding::Key(VirtualKeyCode::W),
+                InputBinding::GamepadAxis(GamepadAxis::LeftStickY),
+            ]
+        );
+
+        for binding in action.bindings.iter().chain(&axis.positive) {
+            assert_eq!(
+                binding.to_string().parse::<InputBinding>().as_ref(),
+                Ok(binding)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_bindings() {
+        for binding in ["Spacebar", "Gamepad.Z", "Keyboard.Space", "Mouse.Top"] {
+            assert!(toml::from_str::<Action>(&format!("bindings = [\"{binding}\"]")).is_err());
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::PackageId;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum LockfileError {
+    #[error("Failed to parse lockfile")]
+    ParseError(#[from] toml::de::Error),
+    #[error("Unsupported lockfile version {0}; expected {expected}", expected = Lockfile::VERSION)]
+    UnsupportedVersion(u32),
+}
+
+/// The result of resolving the versioned dependencies of a package, stored next to its manifest.
+///
+/// Each package ID appears at most once, as the dependency tree can only contain one version
+/// of each package.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default)]
+    #[serde(rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            packages: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub id: PackageId,
+    pub name: String,
+    pub version: Version,
+    /// The deployment that `version` resolved to.
+    pub deployment: String,
+    /// The IDs of the locked packages this package depends on.
+    #[serde(default)]
+    pub dependencies: Vec<PackageId>,
+}
+
+impl Lockfile {
+    pub const FILENAME: &'static str = "ambient.lock";
+    pub const VERSION: u32 = 1;
+
+    pub fn parse(contents: &str) -> Result<Self, LockfileError> {
+        let lockfile: Self = toml::from_str(contents)?;
+        if lockfile.version != Self::VERSION {
+            return Err(LockfileError::UnsupportedVersion(lockfile.version));
+        }
+
+        Ok(lockfile)
+    }
+
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string_pretty(self).unwrap()
+    }
+
+    pub fn get(&self, id: &PackageId) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| &p.id == id)
+    }
+
+    /// Returns the deployment the package was locked to, if it was locked.
+    pub fn deployment(&self, id: &PackageId) -> Option<&str> {
+        self.get(id).map(|p| p.deployment.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use crate::{LockedPackage, Lockfile, LockfileError, PackageId};
+
+    #[test]
+    fn can_roundtrip_lockfile() {
+        let lockfile = Lockfile {
+            version: Lockfile::VERSION,
+            packages: vec![LockedPackage {
+                id: PackageId("lktsfudbjw2qikhyumt573ozxhadkiwm".to_string()),
+                name: "Test".to_string(),
+                version: Version::parse("1.2.3").unwrap(),
+                deployment: "jhsdfu574S".to_string(),
+                dependencies: vec![],
+            }],
+        };
+
+        assert_eq!(Lockfile::parse(&lockfile.to_toml_string()), Ok(lockfile));
+    }
+
+    #[test]
+    fn will_fail_on_unknown_version() {
+        const TOML: &str = r#"
+        version = 1000
+        "#;
+
+        assert_eq!(
+            Lockfile::parse(TOML),
+            Err(LockfileError::UnsupportedVersion(1000))
+        );
+    }
+}
@@ -8,7 +8,8 @@ use sha2::Digest;
 use thiserror::Error;
 
 use crate::{
-    Component, Concept, Enum, ItemPathBuf, Message, PascalCaseIdentifier, SnakeCaseIdentifier,
+    Action, Axis, Component, Concept, Enum, ItemPathBuf, Message, PascalCaseIdentifier,
+    SnakeCaseIdentifier,
 };
 
 #[derive(Error, Debug, PartialEq)]
@@ -37,6 +38,12 @@ pub struct Manifest {
     #[serde(alias = "enum")]
     pub enums: IndexMap<PascalCaseIdentifier, Enum>,
     #[serde(default)]
+    #[serde(alias = "action")]
+    pub actions: IndexMap<SnakeCaseIdentifier, Action>,
+    #[serde(default)]
+    #[serde(alias = "axis")]
+    pub axes: IndexMap<SnakeCaseIdentifier, Axis>,
+    #[serde(default)]
     pub includes: HashMap<SnakeCaseIdentifier, PathBuf>,
     #[serde(default)]
     pub dependencies: IndexMap<SnakeCaseIdentifier, Dependency>,
@@ -249,12 +256,19 @@ pub struct Dependency {
     pub path: Option<PathBuf>,
     #[serde(default)]
     pub deployment: Option<String>,
+    /// The ID of the package; required when `version` is specified.
+    #[serde(default)]
+    pub id: Option<PackageId>,
+    /// A version requirement that is resolved against a registry index to a deployment,
+    /// which is then recorded in the lockfile.
+    #[serde(default)]
+    pub version: Option<VersionReq>,
     #[serde(default)]
     pub enabled: Option<bool>,
 }
 impl Dependency {
     pub fn has_remote_dependency(&self) -> bool {
-        self.deployment.is_some()
+        self.deployment.is_some() || self.version.is_some()
     }
 }
 
@@ -383,6 +397,8 @@ mod tests {
                 )]),
                 messages: Default::default(),
                 enums: Default::default(),
+                actions: Default::default(),
+                axes: Default::default(),
                 includes: Default::default(),
                 dependencies: Default::default(),
             })
@@ -570,6 +586,8 @@ mod tests {
                 )]),
                 messages: Default::default(),
                 enums: Default::default(),
+                actions: Default::default(),
+                axes: Default::default(),
                 includes: Default::default(),
                 dependencies: Default::default(),
             }
@@ -632,6 +650,8 @@ mod tests {
                         ])
                     }
                 )]),
+                actions: Default::default(),
+                axes: Default::default(),
                 includes: Default::default(),
                 dependencies: Default::default(),
             })
@@ -709,6 +729,8 @@ mod tests {
                 concepts: Default::default(),
                 messages: Default::default(),
                 enums: Default::default(),
+                actions: Default::default(),
+                axes: Default::default(),
                 includes: Default::default(),
                 dependencies: Default::default(),
             })
@@ -746,6 +768,8 @@ mod tests {
                 concepts: Default::default(),
                 messages: Default::default(),
                 enums: Default::default(),
+                actions: Default::default(),
+                axes: Default::default(),
                 includes: Default::default(),
                 dependencies: IndexMap::from_iter([
                     (
@@ -753,6 +777,8 @@ mod tests {
                         Dependency {
                             path: Some(PathBuf::from("deps/assets")),
                             deployment: None,
+                            id: None,
+                            version: None,
                             enabled: None,
                         }
                     ),
@@ -761,6 +787,8 @@ mod tests {
                         Dependency {
                             path: Some(PathBuf::from("deps/code")),
                             deployment: None,
+                            id: None,
+                            version: None,
                             enabled: None,
                         }
                     ),
@@ -769,6 +797,8 @@ mod tests {
                         Dependency {
                             path: Some(PathBuf::from("deps/ignore_me")),
                             deployment: None,
+                            id: None,
+                            version: None,
                             enabled: Some(false),
                         }
                     ),
@@ -777,6 +807,8 @@ mod tests {
                         Dependency {
                             path: None,
                             deployment: Some("jhsdfu574S".to_owned()),
+                            id: None,
+                            version: None,
                             enabled: None,
                         }
                     )
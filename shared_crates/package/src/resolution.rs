@@ -0,0 +1,442 @@
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+
+use indexmap::IndexMap;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{LockedPackage, Lockfile, Manifest, PackageId, SnakeCaseIdentifier};
+
+/// The published versions of a single package in a registry index.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct IndexEntry {
+    pub name: String,
+    #[serde(default)]
+    pub versions: Vec<IndexVersion>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct IndexVersion {
+    pub version: Version,
+    pub deployment: String,
+    #[serde(default)]
+    pub dependencies: IndexMap<SnakeCaseIdentifier, IndexDependency>,
+    /// Yanked versions are only used if they are already locked.
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct IndexDependency {
+    pub id: PackageId,
+    pub version: VersionReq,
+}
+
+/// A source of published package versions that dependencies can be resolved against.
+pub trait RegistryIndex {
+    /// Returns the entry for the package, or `None` if the index does not know about it.
+    fn get(&self, id: &PackageId) -> Result<Option<IndexEntry>, ResolveError>;
+}
+
+/// A registry index stored in a local directory, with one `<package id>.toml` per package.
+#[derive(Clone, Debug)]
+pub struct LocalRegistryIndex {
+    pub path: PathBuf,
+}
+impl LocalRegistryIndex {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn entry_path(&self, id: &PackageId) -> PathBuf {
+        self.path.join(format!("{id}.toml"))
+    }
+}
+impl RegistryIndex for LocalRegistryIndex {
+    fn get(&self, id: &PackageId) -> Result<Option<IndexEntry>, ResolveError> {
+        let path = self.entry_path(id);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(ResolveError::IndexError {
+                    path,
+                    message: err.to_string(),
+                })
+            }
+        };
+
+        toml::from_str(&contents)
+            .map(Some)
+            .map_err(|err| ResolveError::IndexError {
+                path,
+                message: err.to_string(),
+            })
+    }
+}
+
+/// Who asked for a package to be in the dependency tree, and with what requirement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Requirement {
+    pub required_by: String,
+    pub version: VersionReq,
+}
+impl Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (required by {})", self.version, self.required_by)
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    #[error("Dependency `{dependency_name}` has a version requirement but no package `id`")]
+    MissingId {
+        dependency_name: SnakeCaseIdentifier,
+    },
+    #[error("Package {id} (required by {required_by}) was not found in the registry index")]
+    PackageNotFound { id: PackageId, required_by: String },
+    #[error(
+        "No version of {name} ({id}) satisfies all requirements:\n{}\navailable versions: {}",
+        format_requirements(.requirements),
+        format_versions(.available)
+    )]
+    Conflict {
+        id: PackageId,
+        name: String,
+        requirements: Vec<Requirement>,
+        available: Vec<Version>,
+    },
+    #[error("Failed to read registry index entry {path:?}: {message}")]
+    IndexError { path: PathBuf, message: String },
+}
+
+fn format_requirements(requirements: &[Requirement]) -> String {
+    requirements
+        .iter()
+        .map(|r| format!("  - {r}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_versions(versions: &[Version]) -> String {
+    if versions.is_empty() {
+        return "none".to_string();
+    }
+
+    versions
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Clone, Default)]
+struct ResolveState {
+    requirements: BTreeMap<PackageId, Vec<Requirement>>,
+    selected: BTreeMap<PackageId, (String, IndexVersion)>,
+}
+
+/// Resolves the versioned dependencies of `manifest` against `index`.
+///
+/// Versions already present in `previous` are preferred if they still satisfy the requirements,
+/// so that resolving again does not move packages unless the manifest changed. Otherwise, the
+/// highest matching, non-yanked version is picked.
+///
+/// Dependencies without a `version` are not resolved, as they are pinned by `path` or `deployment`.
+pub fn resolve_dependencies(
+    manifest: &Manifest,
+    index: &impl RegistryIndex,
+    previous: Option<&Lockfile>,
+) -> Result<Lockfile, ResolveError> {
+    let mut state = ResolveState::default();
+    let root_name = manifest.package.name.clone();
+    for (dependency_name, dependency) in &manifest.dependencies {
+        let Some(version) = &dependency.version else {
+            continue;
+        };
+        let id = dependency
+            .id
+            .clone()
+            .ok_or_else(|| ResolveError::MissingId {
+                dependency_name: dependency_name.clone(),
+            })?;
+
+        state.requirements.entry(id).or_default().push(Requirement {
+            required_by: root_name.clone(),
+            version: version.clone(),
+        });
+    }
+
+    let mut entries = BTreeMap::new();
+    let state = resolve_next(state, index, previous, &mut entries)?;
+
+    Ok(Lockfile {
+        version: Lockfile::VERSION,
+        packages: state
+            .selected
+            .into_iter()
+            .map(|(id, (name, version))| LockedPackage {
+                id,
+                name,
+                version: version.version,
+                deployment: version.deployment,
+                dependencies: version
+                    .dependencies
+                    .into_values()
+                    .map(|dependency| dependency.id)
+                    .collect(),
+            })
+            .collect(),
+    })
+}
+
+fn resolve_next(
+    state: ResolveState,
+    index: &impl RegistryIndex,
+    previous: Option<&Lockfile>,
+    entries: &mut BTreeMap<PackageId, IndexEntry>,
+) -> Result<ResolveState, ResolveError> {
+    let Some((id, requirements)) = state
+        .requirements
+        .iter()
+        .find(|(id, _)| !state.selected.contains_key(*id))
+        .map(|(id, requirements)| (id.clone(), requirements.clone()))
+    else {
+        return Ok(state);
+    };
+
+    if !entries.contains_key(&id) {
+        let entry = index
+            .get(&id)?
+            .ok_or_else(|| ResolveError::PackageNotFound {
+                id: id.clone(),
+                required_by: requirements[0].required_by.clone(),
+            })?;
+        entries.insert(id.clone(), entry);
+    }
+    let entry = entries[&id].clone();
+
+    let locked = previous.and_then(|p| p.get(&id)).map(|p| p.version.clone());
+
+    let mut candidates = entry
+        .versions
+        .iter()
+        .filter(|v| requirements.iter().all(|r| r.version.matches(&v.version)))
+        .filter(|v| !v.yanked || Some(&v.version) == locked.as_ref())
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| {
+        let a_locked = Some(&a.version) == locked.as_ref();
+        let b_locked = Some(&b.version) == locked.as_ref();
+        b_locked
+            .cmp(&a_locked)
+            .then_with(|| b.version.cmp(&a.version))
+    });
+
+    let mut conflict = ResolveError::Conflict {
+        id: id.clone(),
+        name: entry.name.clone(),
+        requirements: requirements.clone(),
+        available: entry.versions.iter().map(|v| v.version.clone()).collect(),
+    };
+
+    'candidates: for candidate in candidates {
+        let mut state = state.clone();
+        let required_by = format!("{} {}", entry.name, candidate.version);
+        for dependency in candidate.dependencies.values() {
+            // A package that was already selected must also satisfy the new requirement
+            if let Some((_, selected)) = state.selected.get(&dependency.id) {
+                if !dependency.version.matches(&selected.version) {
+                    continue 'candidates;
+                }
+            }
+
+            state
+                .requirements
+                .entry(dependency.id.clone())
+                .or_default()
+                .push(Requirement {
+                    required_by: required_by.clone(),
+                    version: dependency.version.clone(),
+                });
+        }
+        state
+            .selected
+            .insert(id.clone(), (entry.name.clone(), candidate.clone()));
+
+        match resolve_next(state, index, previous, entries) {
+            Ok(state) => return Ok(state),
+            // Keep the deepest conflict, as it is the most descriptive
+            Err(err @ ResolveError::Conflict { .. }) => conflict = err,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(conflict)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use semver::{Version, VersionReq};
+
+    use crate::{
+        resolve_dependencies, IndexDependency, IndexEntry, IndexVersion, Lockfile, Manifest,
+        PackageId, RegistryIndex, ResolveError, SnakeCaseIdentifier,
+    };
+
+    const ROOT: &str = "lktsfudbjw2qikhyumt573ozxhadkiwm";
+    const A: &str = "afl5yv5ya35vbuaj3aido22cwjzat25z";
+    const B: &str = "hvxms7i2px7krvkm23sxfjxsjqlcmtb5";
+
+    struct TestIndex(HashMap<PackageId, IndexEntry>);
+    impl RegistryIndex for TestIndex {
+        fn get(&self, id: &PackageId) -> Result<Option<IndexEntry>, ResolveError> {
+            Ok(self.0.get(id).cloned())
+        }
+    }
+
+    fn id(s: &str) -> PackageId {
+        PackageId(s.to_string())
+    }
+
+    fn version(version: &str, dependencies: &[(&str, &str, &str)]) -> IndexVersion {
+        IndexVersion {
+            version: Version::parse(version).unwrap(),
+            deployment: format!("deployment_{version}"),
+            dependencies: dependencies
+                .iter()
+                .map(|(name, dep_id, req)| {
+                    (
+                        SnakeCaseIdentifier::new(name).unwrap(),
+                        IndexDependency {
+                            id: id(dep_id),
+                            version: VersionReq::parse(req).unwrap(),
+                        },
+                    )
+                })
+                .collect(),
+            yanked: false,
+        }
+    }
+
+    fn index() -> TestIndex {
+        TestIndex(HashMap::from_iter([
+            (
+                id(A),
+                IndexEntry {
+                    name: "a".to_string(),
+                    versions: vec![
+                        version("1.0.0", &[("b", B, "^0.1")]),
+                        version("1.2.0", &[("b", B, "^0.2")]),
+                        version("1.3.0", &[("b", B, "^0.3")]),
+                    ],
+                },
+            ),
+            (
+                id(B),
+                IndexEntry {
+                    name: "b".to_string(),
+                    versions: vec![version("0.1.0", &[]), version("0.2.5", &[])],
+                },
+            ),
+        ]))
+    }
+
+    fn manifest(dependencies: &str) -> Manifest {
+        Manifest::parse(&format!(
+            r#"
+            [package]
+            id = "{ROOT}"
+            name = "Root"
+            version = "0.0.1"
+            content = {{ type = "Playable" }}
+
+            [dependencies]
+            {dependencies}
+            "#
+        ))
+        .unwrap()
+    }
+
+    fn locked_versions(lockfile: &Lockfile) -> Vec<(String, String)> {
+        lockfile
+            .packages
+            .iter()
+            .map(|p| (p.name.clone(), p.version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn resolves_highest_compatible_versions() {
+        // a 1.3.0 requires a version of b which does not exist, so we should fall back to 1.2.0
+        let lockfile = resolve_dependencies(
+            &manifest(&format!(r#"a = {{ id = "{A}", version = "^1.2" }}"#)),
+            &index(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            locked_versions(&lockfile),
+            vec![
+                ("a".to_string(), "1.2.0".to_string()),
+                ("b".to_string(), "0.2.5".to_string())
+            ]
+        );
+        assert_eq!(lockfile.deployment(&id(A)), Some("deployment_1.2.0"));
+    }
+
+    #[test]
+    fn prefers_locked_versions() {
+        let manifest = manifest(&format!(r#"a = {{ id = "{A}", version = "^1.0" }}"#));
+        let mut previous = resolve_dependencies(&manifest, &index(), None).unwrap();
+        previous.packages[0].version = Version::parse("1.0.0").unwrap();
+
+        let lockfile = resolve_dependencies(&manifest, &index(), Some(&previous)).unwrap();
+        assert_eq!(
+            locked_versions(&lockfile),
+            vec![
+                ("a".to_string(), "1.0.0".to_string()),
+                ("b".to_string(), "0.1.0".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_conflicts() {
+        let manifest = manifest(&format!(
+            r#"
+            a = {{ id = "{A}", version = "=1.2.0" }}
+            b = {{ id = "{B}", version = "^0.1" }}
+            "#
+        ));
+
+        let err = resolve_dependencies(&manifest, &index(), None).unwrap_err();
+        let ResolveError::Conflict {
+            name, requirements, ..
+        } = err
+        else {
+            panic!("expected a conflict, got {err:?}");
+        };
+        assert_eq!(name, "b");
+        assert_eq!(
+            requirements
+                .iter()
+                .map(|r| r.required_by.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Root", "a 1.2.0"]
+        );
+    }
+
+    #[test]
+    fn requires_id_for_versioned_dependencies() {
+        let manifest = manifest(r#"a = { version = "^1.0" }"#);
+        assert_eq!(
+            resolve_dependencies(&manifest, &index(), None),
+            Err(ResolveError::MissingId {
+                dependency_name: SnakeCaseIdentifier::new("a").unwrap()
+            })
+        );
+    }
+}
@@ -0,0 +1,615 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+};
+
+use indexmap::IndexMap;
+use semver::Version;
+use thiserror::Error;
+
+use crate::{
+    Component, ConceptValue, Item, ItemId, ItemMap, Package, ResolvableItemId, Scope, Semantic,
+    TypeInner,
+};
+
+/// The kind of version bump a set of schema changes requires.
+///
+/// As with Cargo, the leftmost non-zero version component is treated as the major version,
+/// so a breaking change to a `0.x` package only requires a minor bump.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum VersionBump {
+    Patch,
+    Minor,
+    Major,
+}
+impl VersionBump {
+    /// Returns the smallest version after `version` that reflects this bump.
+    pub fn apply(self, version: &Version) -> Version {
+        let (major, minor, patch) = (version.major, version.minor, version.patch);
+        let (major, minor, patch) = match self {
+            Self::Major if major > 0 => (major + 1, 0, 0),
+            Self::Major if minor > 0 => (0, minor + 1, 0),
+            Self::Major => (0, 0, patch + 1),
+            Self::Minor if major > 0 => (major, minor + 1, 0),
+            Self::Minor | Self::Patch => (major, minor, patch + 1),
+        };
+        Version::new(major, minor, patch)
+    }
+
+    /// Returns whether going from `old` to `new` reflects this bump.
+    ///
+    /// Patch-level changes do not affect consumers, so they only require the version to not go backwards.
+    pub fn is_satisfied_by(self, old: &Version, new: &Version) -> bool {
+        match self {
+            Self::Patch => new >= old,
+            Self::Minor | Self::Major => new >= &self.apply(old),
+        }
+    }
+}
+impl Display for VersionBump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Patch => write!(f, "patch"),
+            Self::Minor => write!(f, "minor"),
+            Self::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// A single difference between two versions of a package's schema.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SchemaChange {
+    /// The path of the changed item, relative to the package.
+    pub path: String,
+    pub description: String,
+    pub bump: VersionBump,
+}
+impl SchemaChange {
+    pub fn is_breaking(&self) -> bool {
+        self.bump == VersionBump::Major
+    }
+}
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.bump, self.path, self.description)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CompatibilityReport {
+    pub changes: Vec<SchemaChange>,
+}
+impl CompatibilityReport {
+    /// Returns the bump required by the changes, or `None` if the schemas are identical.
+    pub fn required_bump(&self) -> Option<VersionBump> {
+        self.changes.iter().map(|c| c.bump).max()
+    }
+
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes.iter().filter(|c| c.is_breaking())
+    }
+
+    /// Checks that going from `old` to `new` reflects the changes in this report.
+    pub fn check_version(&self, old: &Version, new: &Version) -> Result<(), CompatibilityError> {
+        match self.required_bump() {
+            Some(required) if !required.is_satisfied_by(old, new) => {
+                Err(CompatibilityError::InsufficientVersionBump {
+                    old: old.clone(),
+                    new: new.clone(),
+                    required,
+                    suggested: required.apply(old),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CompatibilityError {
+    #[error("The schema changes require a {required} version bump, but the version went from {old} to {new}; consider using {suggested}")]
+    InsufficientVersionBump {
+        old: Version,
+        new: Version,
+        required: VersionBump,
+        suggested: Version,
+    },
+}
+
+/// Compares the schema of the package `old_id` in `old` with the package `new_id` in `new`.
+///
+/// Both semantics must have been resolved. Items are matched by their path within the package,
+/// so a renamed item shows up as a removal and an addition.
+pub fn compare_packages(
+    (old, old_id): (&Semantic, ItemId<Package>),
+    (new, new_id): (&Semantic, ItemId<Package>),
+) -> CompatibilityReport {
+    let old = PackageSchema::new(&old.items, old_id);
+    let new = PackageSchema::new(&new.items, new_id);
+
+    let mut report = CompatibilityReport::default();
+    compare_components(&mut report, &old.components, &new.components);
+    compare_concepts(&mut report, &old.concepts, &new.concepts);
+    compare_messages(&mut report, &old.messages, &new.messages);
+    compare_enums(&mut report, &old.enums, &new.enums);
+    report
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct ComponentSchema {
+    type_: String,
+    attributes: BTreeSet<String>,
+    name: Option<String>,
+    description: Option<String>,
+    default: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct ConceptSchema {
+    extends: BTreeSet<String>,
+    required: BTreeSet<String>,
+    optional: BTreeSet<String>,
+    description: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct MessageSchema {
+    fields: Vec<(String, String)>,
+    description: Option<String>,
+}
+
+#[derive(Default)]
+struct PackageSchema {
+    components: BTreeMap<String, ComponentSchema>,
+    concepts: BTreeMap<String, ConceptSchema>,
+    messages: BTreeMap<String, MessageSchema>,
+    enums: BTreeMap<String, Vec<String>>,
+}
+impl PackageSchema {
+    fn new(items: &ItemMap, package_id: ItemId<Package>) -> Self {
+        let package_scope_id = items.get(package_id).scope_id;
+        let mut schema = Self::default();
+        visit_scopes(items, items.get(package_scope_id), &mut |scope| {
+            for id in scope.components.values().copied() {
+                let component = items.get(id);
+                schema.components.insert(
+                    item_path(items, component, Some(package_scope_id)),
+                    ComponentSchema {
+                        type_: resolvable_path(items, &component.type_),
+                        attributes: component
+                            .attributes
+                            .iter()
+                            .map(|a| resolvable_path(items, a))
+                            .collect(),
+                        name: component.name.clone(),
+                        description: component.description.clone(),
+                        default: component.default.as_ref().map(|d| format!("{d:?}")),
+                    },
+                );
+            }
+
+            for id in scope.concepts.values().copied() {
+                let concept = items.get(id);
+                let components = |map: &IndexMap<ResolvableItemId<Component>, ConceptValue>| {
+                    map.keys().map(|c| resolvable_path(items, c)).collect()
+                };
+                schema.concepts.insert(
+                    item_path(items, concept, Some(package_scope_id)),
+                    ConceptSchema {
+                        extends: concept
+                            .extends
+                            .iter()
+                            .map(|c| resolvable_path(items, c))
+                            .collect(),
+                        required: components(&concept.required_components),
+                        optional: components(&concept.optional_components),
+                        description: concept.description.clone(),
+                    },
+                );
+            }
+
+            for id in scope.messages.values().copied() {
+                let message = items.get(id);
+                schema.messages.insert(
+                    item_path(items, message, Some(package_scope_id)),
+                    MessageSchema {
+                        fields: message
+                            .fields
+                            .iter()
+                            .map(|(name, ty)| (name.to_string(), resolvable_path(items, ty)))
+                            .collect(),
+                        description: message.description.clone(),
+                    },
+                );
+            }
+
+            for id in scope.types.values().copied() {
+                let type_ = items.get(id);
+                if let TypeInner::Enum(e) = &type_.inner {
+                    schema.enums.insert(
+                        item_path(items, type_, Some(package_scope_id)),
+                        e.members.keys().map(|m| m.to_string()).collect(),
+                    );
+                }
+            }
+        });
+        schema
+    }
+}
+
+fn item_path<T: Item>(items: &ItemMap, item: &T, relative_to: Option<ItemId<Scope>>) -> String {
+    items.fully_qualified_display_path(item, relative_to, None)
+}
+
+fn visit_scopes(items: &ItemMap, scope: &Scope, visitor: &mut dyn FnMut(&Scope)) {
+    visitor(scope);
+    for id in scope.scopes.values().copied() {
+        visit_scopes(items, items.get(id), visitor);
+    }
+}
+
+fn resolvable_path<T: Item>(items: &ItemMap, id: &ResolvableItemId<T>) -> String {
+    match id {
+        ResolvableItemId::Unresolved(unresolved) => format!("unresolved({unresolved:?})"),
+        ResolvableItemId::Resolved(id) => item_path(items, items.get(*id), None),
+    }
+}
+
+/// Reports the additions and removals between `old` and `new`, and returns the items present in both.
+fn compare_maps<'a, T>(
+    report: &mut CompatibilityReport,
+    kind: &str,
+    old: &'a BTreeMap<String, T>,
+    new: &'a BTreeMap<String, T>,
+) -> Vec<(&'a String, &'a T, &'a T)> {
+    let mut common = vec![];
+    for (path, old_item) in old {
+        match new.get(path) {
+            Some(new_item) => common.push((path, old_item, new_item)),
+            None => report.push(path, VersionBump::Major, format!("{kind} was removed")),
+        }
+    }
+    for path in new.keys().filter(|p| !old.contains_key(*p)) {
+        report.push(path, VersionBump::Minor, format!("{kind} was added"));
+    }
+    common
+}
+
+fn compare_sets(
+    report: &mut CompatibilityReport,
+    path: &str,
+    what: &str,
+    (old, new): (&BTreeSet<String>, &BTreeSet<String>),
+    (removed_bump, added_bump): (VersionBump, VersionBump),
+) {
+    for item in old.difference(new) {
+        report.push(path, removed_bump, format!("{what} `{item}` was removed"));
+    }
+    for item in new.difference(old) {
+        report.push(path, added_bump, format!("{what} `{item}` was added"));
+    }
+}
+
+fn compare_descriptions(
+    report: &mut CompatibilityReport,
+    path: &str,
+    old: &Option<String>,
+    new: &Option<String>,
+) {
+    if old != new {
+        report.push(path, VersionBump::Patch, "description changed".to_string());
+    }
+}
+
+fn compare_components(
+    report: &mut CompatibilityReport,
+    old: &BTreeMap<String, ComponentSchema>,
+    new: &BTreeMap<String, ComponentSchema>,
+) {
+    for (path, old, new) in compare_maps(report, "component", old, new) {
+        if old.type_ != new.type_ {
+            report.push(
+                path,
+                VersionBump::Major,
+                format!("type changed from `{}` to `{}`", old.type_, new.type_),
+            );
+        }
+        compare_sets(
+            report,
+            path,
+            "attribute",
+            (&old.attributes, &new.attributes),
+            (VersionBump::Major, VersionBump::Minor),
+        );
+        if old.default != new.default {
+            report.push(
+                path,
+                VersionBump::Minor,
+                "default value changed".to_string(),
+            );
+        }
+        if old.name != new.name {
+            report.push(path, VersionBump::Patch, "name changed".to_string());
+        }
+        compare_descriptions(report, path, &old.description, &new.description);
+    }
+}
+
+fn compare_concepts(
+    report: &mut CompatibilityReport,
+    old: &BTreeMap<String, ConceptSchema>,
+    new: &BTreeMap<String, ConceptSchema>,
+) {
+    for (path, old, new) in compare_maps(report, "concept", old, new) {
+        compare_sets(
+            report,
+            path,
+            "extended concept",
+            (&old.extends, &new.extends),
+            (VersionBump::Major, VersionBump::Major),
+        );
+        // Entities that matched the concept before must keep matching it, and code that
+        // reads the required components must keep finding them.
+        compare_sets(
+            report,
+            path,
+            "required component",
+            (&old.required, &new.required),
+            (VersionBump::Major, VersionBump::Major),
+        );
+        compare_sets(
+            report,
+            path,
+            "optional component",
+            (&old.optional, &new.optional),
+            (VersionBump::Major, VersionBump::Minor),
+        );
+        compare_descriptions(report, path, &old.description, &new.description);
+    }
+}
+
+fn compare_messages(
+    report: &mut CompatibilityReport,
+    old: &BTreeMap<String, MessageSchema>,
+    new: &BTreeMap<String, MessageSchema>,
+) {
+    for (path, old, new) in compare_maps(report, "message", old, new) {
+        // Messages are serialized by field order, so any change to the fields breaks
+        // communication between the two versions.
+        if old.fields != new.fields {
+            let format_fields = |fields: &[(String, String)]| {
+                fields
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            report.push(
+                path,
+                VersionBump::Major,
+                format!(
+                    "fields changed from ({}) to ({})",
+                    format_fields(&old.fields),
+                    format_fields(&new.fields)
+                ),
+            );
+        }
+        compare_descriptions(report, path, &old.description, &new.description);
+    }
+}
+
+fn compare_enums(
+    report: &mut CompatibilityReport,
+    old: &BTreeMap<String, Vec<String>>,
+    new: &BTreeMap<String, Vec<String>>,
+) {
+    for (path, old, new) in compare_maps(report, "enum", old, new) {
+        // Enums are serialized by member index, so members can only be appended.
+        if new.starts_with(old) {
+            for member in &new[old.len()..] {
+                report.push(
+                    path,
+                    VersionBump::Minor,
+                    format!("member `{member}` was added"),
+                );
+            }
+            continue;
+        }
+
+        let mut changed = false;
+        for member in old.iter().filter(|m| !new.contains(m)) {
+            report.push(
+                path,
+                VersionBump::Major,
+                format!("member `{member}` was removed"),
+            );
+            changed = true;
+        }
+        if !changed {
+            report.push(
+                path,
+                VersionBump::Major,
+                "members were reordered or inserted before existing members".to_string(),
+            );
+        }
+    }
+}
+
+impl CompatibilityReport {
+    fn push(&mut self, path: &str, bump: VersionBump, description: String) {
+        self.changes.push(SchemaChange {
+            path: path.to_string(),
+            description,
+            bump,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use semver::Version;
+
+    use super::{compare_packages, CompatibilityError, CompatibilityReport, VersionBump};
+    use crate::{ItemId, Package, RetrievableFile, Semantic};
+
+    const HEADER: &str = r#"
+    [package]
+    id = "lktsfudbjw2qikhyumt573ozxhadkiwm"
+    name = "Test"
+    version = "0.1.0"
+    content = { type = "Asset", code = true }
+    ambient_version = "0.3.0"
+    "#;
+
+    async fn load(dir: &Path, body: &str) -> (Semantic, ItemId<Package>) {
+        std::fs::create_dir_all(dir).unwrap();
+        let manifest_path = dir.join("ambient.toml");
+        std::fs::write(&manifest_path, format!("{HEADER}\n{body}")).unwrap();
+
+        let mut semantic = Semantic::new(false).await.unwrap();
+        let id = semantic
+            .add_package(RetrievableFile::Path(manifest_path), None)
+            .await
+            .unwrap();
+        semantic.resolve_all().unwrap();
+        (semantic, id)
+    }
+
+    async fn compare(test: &str, old: &str, new: &str) -> CompatibilityReport {
+        let dir = std::env::temp_dir()
+            .join("ambient_package_semantic_compat")
+            .join(test);
+        let old = load(&dir.join("old"), old).await;
+        let new = load(&dir.join("new"), new).await;
+        let _ = std::fs::remove_dir_all(&dir);
+
+        compare_packages((&old.0, old.1), (&new.0, new.1))
+    }
+
+    fn descriptions(report: &CompatibilityReport) -> Vec<String> {
+        report.changes.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn identical_schemas_have_no_changes() {
+        const SCHEMA: &str = r#"
+        [components.health]
+        type = "F32"
+        attributes = ["Networked"]
+        "#;
+
+        let report = compare("identical", SCHEMA, SCHEMA).await;
+        assert_eq!(report.changes, vec![]);
+        assert_eq!(report.required_bump(), None);
+    }
+
+    #[tokio::test]
+    async fn detects_breaking_component_changes() {
+        let report = compare(
+            "components",
+            r#"
+            [components.health]
+            type = "F32"
+            attributes = ["Networked"]
+
+            [components.mana]
+            type = "F32"
+            "#,
+            r#"
+            [components.health]
+            type = "U32"
+
+            [components.stamina]
+            type = "F32"
+            "#,
+        )
+        .await;
+
+        assert_eq!(
+            descriptions(&report),
+            vec![
+                "[major] mana: component was removed",
+                "[minor] stamina: component was added",
+                "[major] health: type changed from `F32` to `U32`",
+                "[major] health: attribute `Networked` was removed",
+            ]
+        );
+        assert_eq!(report.required_bump(), Some(VersionBump::Major));
+    }
+
+    #[tokio::test]
+    async fn detects_message_and_enum_changes() {
+        let report = compare(
+            "messages",
+            r#"
+            [enums.Mode]
+            members = { A = "", B = "" }
+
+            [messages.Hit]
+            fields = { damage = "F32" }
+            "#,
+            r#"
+            [enums.Mode]
+            members = { A = "", B = "", C = "" }
+
+            [messages.Hit]
+            fields = { damage = "F32", critical = "Bool" }
+            "#,
+        )
+        .await;
+
+        assert_eq!(
+            descriptions(&report),
+            vec![
+                "[major] Hit: fields changed from (damage: F32) to (damage: F32, critical: Bool)",
+                "[minor] Mode: member `C` was added",
+            ]
+        );
+    }
+
+    #[test]
+    fn version_bumps_follow_cargo_semantics() {
+        let v = |s: &str| Version::parse(s).unwrap();
+
+        assert_eq!(VersionBump::Major.apply(&v("1.2.3")), v("2.0.0"));
+        assert_eq!(VersionBump::Major.apply(&v("0.2.3")), v("0.3.0"));
+        assert_eq!(VersionBump::Major.apply(&v("0.0.3")), v("0.0.4"));
+        assert_eq!(VersionBump::Minor.apply(&v("1.2.3")), v("1.3.0"));
+        assert_eq!(VersionBump::Minor.apply(&v("0.2.3")), v("0.2.4"));
+
+        assert!(VersionBump::Major.is_satisfied_by(&v("0.2.3"), &v("0.3.0")));
+        assert!(!VersionBump::Major.is_satisfied_by(&v("0.2.3"), &v("0.2.4")));
+        assert!(!VersionBump::Major.is_satisfied_by(&v("0.3.0-dev"), &v("0.3.0")));
+        assert!(VersionBump::Patch.is_satisfied_by(&v("0.2.3"), &v("0.2.3")));
+    }
+
+    #[tokio::test]
+    async fn check_version_fails_without_bump() {
+        let report = compare(
+            "check_version",
+            r#"
+            [components.health]
+            type = "F32"
+            "#,
+            "",
+        )
+        .await;
+
+        let old = Version::parse("1.4.0").unwrap();
+        assert_eq!(
+            report.check_version(&old, &Version::parse("1.5.0").unwrap()),
+            Err(CompatibilityError::InsufficientVersionBump {
+                old: old.clone(),
+                new: Version::parse("1.5.0").unwrap(),
+                required: VersionBump::Major,
+                suggested: Version::parse("2.0.0").unwrap(),
+            })
+        );
+        assert_eq!(
+            report.check_version(&old, &Version::parse("2.0.0").unwrap()),
+            Ok(())
+        );
+    }
+}
@@ -9,7 +9,7 @@ use anyhow::Context as AnyhowContext;
 use async_recursion::async_recursion;
 
 use ambient_package::{
-    BuildMetadata, ComponentType, Identifier, ItemPath, ItemPathBuf, Manifest,
+    BuildMetadata, ComponentType, Identifier, ItemPath, ItemPathBuf, Lockfile, Manifest,
     PascalCaseIdentifier, SnakeCaseIdentifier,
 };
 use ambient_shared_types::primitive_component_definitions;
@@ -53,6 +53,11 @@ pub use printer::Printer;
 
 mod util;
 
+mod compat;
+pub use compat::{
+    compare_packages, CompatibilityError, CompatibilityReport, SchemaChange, VersionBump,
+};
+
 pub type Schema = HashMap<&'static str, &'static str>;
 pub fn schema() -> &'static Schema {
     static SCHEMA: OnceLock<Schema> = OnceLock::new();
@@ -100,6 +105,16 @@ pub enum PackageAddError {
         locator: PackageLocator,
         dependency_name: SnakeCaseIdentifier,
     },
+    #[error("Dependency `{dependency_name}` for {locator} has a version requirement but is not in the lockfile; run `ambient package lock` to resolve it")]
+    DependencyNotLocked {
+        locator: PackageLocator,
+        dependency_name: SnakeCaseIdentifier,
+    },
+    #[error("Failed to parse lockfile `{lockfile_path}`")]
+    LockfileParseError {
+        lockfile_path: RetrievableFile,
+        source: ambient_package::LockfileError,
+    },
     #[error("{0}")]
     BuildMetadataError(ambient_package::BuildMetadataError),
     #[error("{0}")]
@@ -176,6 +191,11 @@ pub struct Semantic {
     pub id_to_locator: HashMap<Identifier, PackageLocator>,
     pub ambient_package_id: ItemId<Package>,
     pub standard_definitions: StandardDefinitions,
+    /// The lockfile of the first user package added, used to resolve versioned dependencies
+    pub lockfile: Option<Lockfile>,
+    /// The URL that deployed dependencies are retrieved from. Defaults to
+    /// [deployments_base_url](ambient_shared_types::urls::deployments_base_url).
+    pub deployments_base_url: String,
     ignore_local_dependencies: bool,
 }
 impl Semantic {
@@ -190,6 +210,8 @@ impl Semantic {
             id_to_locator: HashMap::new(),
             ambient_package_id: ItemId::empty_you_should_really_initialize_this(),
             standard_definitions,
+            lockfile: None,
+            deployments_base_url: ambient_shared_types::urls::deployments_base_url(),
             ignore_local_dependencies,
         };
 
@@ -257,6 +279,22 @@ impl Semantic {
             .into());
         }
 
+        // The lockfile of the root package applies to the whole dependency tree
+        if self.lockfile.is_none()
+            && dependent_package_id.is_none()
+            && !matches!(retrievable_manifest, RetrievableFile::Ambient(_))
+        {
+            let lockfile_path = retrievable_manifest.parent_join(Path::new(Lockfile::FILENAME))?;
+            if let Ok(lockfile) = lockfile_path.get().await {
+                self.lockfile = Some(Lockfile::parse(&lockfile).map_err(|source| {
+                    Box::new(PackageAddError::LockfileParseError {
+                        lockfile_path,
+                        source,
+                    })
+                })?);
+            }
+        }
+
         let build_metadata = retrievable_manifest
             .parent_join(Path::new(BuildMetadata::FILENAME))?
             .get()
@@ -305,8 +343,17 @@ impl Semantic {
                 &retrievable_manifest,
                 self.ignore_local_dependencies,
                 &dependency,
+                self.lockfile.as_ref(),
+                &self.deployments_base_url,
             )?
             else {
+                if dependency.version.is_some() {
+                    return Err(Box::new(PackageAddError::DependencyNotLocked {
+                        locator,
+                        dependency_name,
+                    }));
+                }
+
                 return Err(Box::new(PackageAddError::NoSupportedSources {
                     locator,
                     dependency_name,
@@ -704,21 +751,33 @@ pub fn package_dependency_to_retrievable_file(
     retrievable_manifest: &RetrievableFile,
     ignore_local_dependencies: bool,
     dependency: &ambient_package::Dependency,
+    lockfile: Option<&Lockfile>,
+    deployments_base_url: &str,
 ) -> Result<Option<RetrievableFile>, ParentJoinError> {
     let path = dependency
         .path
         .as_ref()
         .filter(|_| !ignore_local_dependencies);
 
-    // path takes precedence over url
-    Ok(match (path, &dependency.deployment) {
-        (None, None) => None,
-        (Some(path), _) => Some(retrievable_manifest.parent_join(&path.join("ambient.toml"))?),
-        (_, Some(deployment)) => Some(RetrievableFile::Deployment(RetrievableDeployment {
-            id: deployment.clone(),
-            path: PathBuf::from("ambient.toml"),
-        })),
-    })
+    // A versioned dependency is pinned to the deployment it was locked to
+    let locked_deployment = dependency
+        .id
+        .as_ref()
+        .filter(|_| dependency.version.is_some())
+        .and_then(|id| lockfile?.deployment(id));
+
+    // path takes precedence over url, which takes precedence over version
+    Ok(
+        match (path, dependency.deployment.as_deref().or(locked_deployment)) {
+            (None, None) => None,
+            (Some(path), _) => Some(retrievable_manifest.parent_join(&path.join("ambient.toml"))?),
+            (_, Some(deployment)) => Some(RetrievableFile::Deployment(RetrievableDeployment {
+                id: deployment.to_string(),
+                path: PathBuf::from("ambient.toml"),
+                base_url: deployments_base_url.to_string(),
+            })),
+        },
+    )
 }
 
 /// This item supports being resolved by cloning.
@@ -77,10 +77,12 @@ pub enum ParentJoinError {
 pub struct RetrievableDeployment {
     pub id: String,
     pub path: PathBuf,
+    /// The URL that the deployment is retrieved from, like [Semantic::deployments_base_url].
+    pub base_url: String,
 }
 impl RetrievableDeployment {
     pub fn url(&self) -> Url {
-        let mut url = ambient_shared_types::urls::deployment_url(&self.id);
+        let mut url = ambient_shared_types::urls::deployment_url_from(&self.base_url, &self.id);
         url.push('/');
 
         let path = self.path.to_string_lossy();
@@ -164,6 +166,7 @@ impl RetrievableFile {
                 RetrievableFile::Deployment(RetrievableDeployment {
                     id: old_deployment.id.clone(),
                     path: parent_join(&old_deployment.path, suffix)?,
+                    base_url: old_deployment.base_url.clone(),
                 })
             }
         })
@@ -0,0 +1,122 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum::{Display, EnumString};
+
+use crate::{MouseButton, VirtualKeyCode};
+
+/// A button on a gamepad, named by its position on the controller rather than its label.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumString, Display)]
+pub enum GamepadButton {
+    /// The bottom face button (A on Xbox controllers, Cross on PlayStation controllers).
+    South,
+    /// The right face button (B on Xbox controllers, Circle on PlayStation controllers).
+    East,
+    /// The top face button (Y on Xbox controllers, Triangle on PlayStation controllers).
+    North,
+    /// The left face button (X on Xbox controllers, Square on PlayStation controllers).
+    West,
+    LeftBumper,
+    LeftTrigger,
+    RightBumper,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// An analog axis on a gamepad. Values are in the range `-1.0..=1.0` for sticks, and `0.0..=1.0` for triggers.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, EnumString, Display)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// A single physical input that can be bound to an action or axis.
+///
+/// Bindings are written as strings: keyboard keys use their [`VirtualKeyCode`] name (`"Space"`, `"W"`),
+/// and other devices are prefixed with the device name (`"Mouse.Left"`, `"Gamepad.South"`, `"Gamepad.LeftStickX"`).
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum InputBinding {
+    Key(VirtualKeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    GamepadAxis(GamepadAxis),
+}
+impl InputBinding {
+    /// Whether this binding reports an analog value rather than a pressed state.
+    pub fn is_analog(&self) -> bool {
+        matches!(self, Self::GamepadAxis(_))
+    }
+}
+impl Display for InputBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key(key) => write!(f, "{key}"),
+            Self::Mouse(MouseButton::Left) => write!(f, "Mouse.Left"),
+            Self::Mouse(MouseButton::Right) => write!(f, "Mouse.Right"),
+            Self::Mouse(MouseButton::Middle) => write!(f, "Mouse.Middle"),
+            Self::Mouse(MouseButton::Other(button)) => write!(f, "Mouse.{button}"),
+            Self::GamepadButton(button) => write!(f, "Gamepad.{button}"),
+            Self::GamepadAxis(axis) => write!(f, "Gamepad.{axis}"),
+        }
+    }
+}
+impl FromStr for InputBinding {
+    type Err = InputBindingParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InputBindingParseError(s.to_string());
+        match s.split_once('.') {
+            None => VirtualKeyCode::from_str(s)
+                .map(Self::Key)
+                .map_err(|_| invalid()),
+            Some(("Mouse", button)) => Ok(Self::Mouse(match button {
+                "Left" => MouseButton::Left,
+                "Right" => MouseButton::Right,
+                "Middle" => MouseButton::Middle,
+                other => MouseButton::Other(other.parse().map_err(|_| invalid())?),
+            })),
+            Some(("Gamepad", input)) => GamepadButton::from_str(input)
+                .map(Self::GamepadButton)
+                .or_else(|_| GamepadAxis::from_str(input).map(Self::GamepadAxis))
+                .map_err(|_| invalid()),
+            Some(_) => Err(invalid()),
+        }
+    }
+}
+impl Serialize for InputBinding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for InputBinding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputBindingParseError(pub String);
+impl Display for InputBindingParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid input binding {:?}; expected a key name (e.g. \"Space\"), \"Mouse.<button>\" or \"Gamepad.<button or axis>\"",
+            self.0
+        )
+    }
+}
+impl std::error::Error for InputBindingParseError {}
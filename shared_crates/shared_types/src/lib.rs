@@ -4,6 +4,9 @@ pub use crate::winit::*;
 mod procedurals;
 pub use crate::procedurals::*;
 
+mod input;
+pub use crate::input::*;
+
 pub mod asset;
 pub mod urls;
 
@@ -15,11 +15,52 @@ pub fn web_package_url(package_id: &str, deployment_id: Option<&str>) -> String
     output
 }
 
+/// Overrides where deployments are retrieved from.
+///
+/// Can be a URL, such as a `file://` registry mirror, or a path to a directory created by
+/// `ambient package vendor`.
+pub const DEPLOYMENT_MIRROR_ENV_VAR: &str = "AMBIENT_DEPLOYMENT_MIRROR";
+
 /// The URL for a deployed package on the asset server.
 ///
 /// What the user would use to run this package, or to get its assets.
 pub fn deployment_url(deployment_id: &str) -> String {
-    format!("{ASSETS_URL}/{deployment_id}")
+    deployment_url_from(&deployments_base_url(), deployment_id)
+}
+
+/// The URL for a deployed package, retrieved from `base_url` (see [`deployments_base_url`]).
+pub fn deployment_url_from(base_url: &str, deployment_id: &str) -> String {
+    format!("{base_url}/{deployment_id}")
+}
+
+/// The URL that deployments are retrieved from; the asset server unless a mirror is specified
+/// through [`DEPLOYMENT_MIRROR_ENV_VAR`].
+pub fn deployments_base_url() -> String {
+    match std::env::var(DEPLOYMENT_MIRROR_ENV_VAR) {
+        Ok(mirror) if !mirror.is_empty() => deployment_mirror_url(&mirror),
+        _ => ASSETS_URL.to_string(),
+    }
+}
+
+/// The URL that deployments are retrieved from when using `mirror`, which is either a URL or a
+/// path to a directory.
+pub fn deployment_mirror_url(mirror: &str) -> String {
+    let mirror = mirror.trim_end_matches(['/', '\\']);
+    if mirror.contains("://") {
+        return mirror.to_string();
+    }
+
+    let path = std::path::Path::new(mirror);
+    let path = match std::env::current_dir() {
+        Ok(current_dir) if path.is_relative() => current_dir.join(path),
+        _ => path.to_owned(),
+    };
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else {
+        format!("file:///{path}")
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
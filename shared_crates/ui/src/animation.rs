@@ -0,0 +1,426 @@
+//! Tweens, springs and enter/exit transitions for UI elements.
+//!
+//! [use_tween] and [use_spring] animate a value towards a target, re-rendering the element each
+//! frame until the target is reached. [AnimateExt] builds on them to animate the components of an
+//! element, and to fade or slide elements in and out when they are mounted and unmounted.
+#[cfg(feature = "guest")]
+use std::time::Instant;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    ops::{Add, Mul, Sub},
+    sync::Arc,
+};
+
+use ambient_cb::Cb;
+use ambient_element::{
+    use_frame, use_ref_with, use_rerender_signal, Element, ElementComponent, ElementComponentExt,
+    Hooks,
+};
+use ambient_guest_bridge::{
+    core::{
+        rect::components::background_color, rendering::components::color,
+        transform::components::translation,
+    },
+    ecs::{Component, ComponentValue, EntityId, World},
+};
+#[cfg(feature = "native")]
+use ambient_sys::time::Instant;
+use glam::{Vec2, Vec3, Vec4};
+use parking_lot::Mutex;
+
+/// How an animation progresses over time.
+#[derive(Debug, Clone, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slowly and speeds up.
+    EaseIn,
+    /// Starts quickly and slows down.
+    #[default]
+    EaseOut,
+    /// Starts and ends slowly.
+    EaseInOut,
+    /// A custom function from linear progress in `0..=1` to eased progress. This can be used to
+    /// sample a curve.
+    Custom(Cb<dyn Fn(f32) -> f32 + Sync + Send>),
+}
+impl Easing {
+    /// Returns the eased progress for the linear progress `t`, which is clamped to `0..=1`.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1. - (1. - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4. * t * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+            Easing::Custom(f) => f(t),
+        }
+    }
+}
+
+/// A value that can be animated.
+pub trait Animatable:
+    Copy
+    + PartialEq
+    + Debug
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<f32, Output = Self>
+    + Sync
+    + Send
+    + 'static
+{
+    /// The size of the value, used to decide when a spring has settled.
+    fn magnitude(self) -> f32;
+}
+impl Animatable for f32 {
+    fn magnitude(self) -> f32 {
+        self.abs()
+    }
+}
+impl Animatable for Vec2 {
+    fn magnitude(self) -> f32 {
+        self.length()
+    }
+}
+impl Animatable for Vec3 {
+    fn magnitude(self) -> f32 {
+        self.length()
+    }
+}
+impl Animatable for Vec4 {
+    fn magnitude(self) -> f32 {
+        self.length()
+    }
+}
+
+#[derive(Debug)]
+struct Tween<T> {
+    from: T,
+    to: T,
+    current: T,
+    started: Instant,
+}
+
+/// Returns a value that moves from its previous value to `target` over `duration` seconds whenever
+/// `target` changes. The first value is `target` itself.
+///
+/// The element is re-rendered every frame while the value is moving.
+pub fn use_tween<T: Animatable>(hooks: &mut Hooks, target: T, duration: f32, easing: Easing) -> T {
+    let tween = use_ref_with(hooks, |_| Tween {
+        from: target,
+        to: target,
+        current: target,
+        started: Instant::now(),
+    });
+    let rerender = use_rerender_signal(hooks);
+    let mut tween = tween.lock();
+    if tween.to != target {
+        tween.from = tween.current;
+        tween.to = target;
+        tween.started = Instant::now();
+    }
+    let elapsed = tween.started.elapsed().as_secs_f32();
+    let animating = tween.update(elapsed, duration, &easing);
+    use_frame(hooks, move |_| {
+        if animating {
+            rerender();
+        }
+    });
+    tween.current
+}
+
+impl<T: Animatable> Tween<T> {
+    /// Moves the value to where it is `elapsed` seconds after the tween started, and returns
+    /// whether it's still moving.
+    fn update(&mut self, elapsed: f32, duration: f32, easing: &Easing) -> bool {
+        let t = if duration > 0. {
+            elapsed / duration
+        } else {
+            1.
+        };
+        self.current = if t >= 1. {
+            self.to
+        } else {
+            self.from + (self.to - self.from) * easing.apply(t)
+        };
+        t < 1.
+    }
+}
+
+#[derive(Debug)]
+struct Spring<T> {
+    value: T,
+    velocity: T,
+    updated: Instant,
+}
+impl<T: Animatable> Spring<T> {
+    /// Moves the value `dt` seconds towards `target`, and returns whether it's still moving. The
+    /// value snaps to `target` once it settles.
+    fn update(&mut self, target: T, stiffness: f32, damping: f32, mut dt: f32) -> bool {
+        const STEP: f32 = 1. / 240.;
+        const REST: f32 = 0.001;
+
+        while dt > 0. {
+            let step = dt.min(STEP);
+            let force = (target - self.value) * stiffness - self.velocity * damping;
+            self.velocity = self.velocity + force * step;
+            self.value = self.value + self.velocity * step;
+            dt -= step;
+        }
+        let animating =
+            (target - self.value).magnitude() > REST || self.velocity.magnitude() > REST;
+        if !animating {
+            self.value = target;
+            self.velocity = T::default();
+        }
+        animating
+    }
+}
+
+/// Returns a value that follows `target` as if it was attached to it with a spring.
+///
+/// `stiffness` is how strongly the value is pulled towards `target`, and `damping` how quickly it
+/// stops oscillating; `stiffness: 170.` with `damping: 26.` gives a quick motion without
+/// overshooting. The element is re-rendered every frame until the value settles.
+pub fn use_spring<T: Animatable>(hooks: &mut Hooks, target: T, stiffness: f32, damping: f32) -> T {
+    let spring = use_ref_with(hooks, |_| Spring {
+        value: target,
+        velocity: T::default(),
+        updated: Instant::now(),
+    });
+    let rerender = use_rerender_signal(hooks);
+    let mut spring = spring.lock();
+    // Long pauses, such as the first frame after the target changes, are capped to keep the
+    // integration stable
+    let dt = spring.updated.elapsed().as_secs_f32().min(0.1);
+    spring.updated = Instant::now();
+    let animating = spring.update(target, stiffness, damping, dt);
+    use_frame(hooks, move |_| {
+        if animating {
+            rerender();
+        }
+    });
+    spring.value
+}
+
+/// Sets `component` of `inner` to a value that tweens towards `value`. See [AnimateExt::animated].
+#[derive(Debug, Clone)]
+pub struct Animated<T: Animatable + ComponentValue + Clone> {
+    /// The element to animate.
+    pub inner: Element,
+    /// The animated component.
+    pub component: Component<T>,
+    /// The value to animate towards.
+    pub value: T,
+    /// The duration of the animation, in seconds.
+    pub duration: f32,
+    /// The easing of the animation.
+    pub easing: Easing,
+}
+impl<T: Animatable + ComponentValue + Clone> ElementComponent for Animated<T> {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+        let Self {
+            inner,
+            component,
+            value,
+            duration,
+            easing,
+        } = *self;
+        let value = use_tween(hooks, value, duration, easing);
+        inner.with(component, value)
+    }
+}
+
+/// Animations for [Element]s.
+pub trait AnimateExt {
+    /// Sets `component` to `value`, tweening from the previous value over `duration` seconds when
+    /// `value` changes.
+    fn animated<T: Animatable + ComponentValue + Clone>(
+        self,
+        component: Component<T>,
+        value: T,
+        duration: f32,
+        easing: Easing,
+    ) -> Element;
+    /// Fades the `color` and `background_color` in from transparent when the element is mounted.
+    fn fade_in(self, duration: f32) -> Self;
+    /// Fades the `color` and `background_color` out to transparent when the element is unmounted,
+    /// before it's despawned.
+    fn fade_out(self, duration: f32) -> Self;
+    /// Slides the element in from `offset` relative to its `translation` when it's mounted.
+    fn slide_in(self, offset: Vec3, duration: f32) -> Self;
+    /// Slides the element out to `offset` relative to its `translation` when it's unmounted,
+    /// before it's despawned.
+    fn slide_out(self, offset: Vec3, duration: f32) -> Self;
+}
+impl AnimateExt for Element {
+    fn animated<T: Animatable + ComponentValue + Clone>(
+        self,
+        component: Component<T>,
+        value: T,
+        duration: f32,
+        easing: Easing,
+    ) -> Element {
+        Animated {
+            inner: self,
+            component,
+            value,
+            duration,
+            easing,
+        }
+        .el()
+    }
+    fn fade_in(self, duration: f32) -> Self {
+        self.on_enter(duration, fade(|t| Easing::EaseOut.apply(t)))
+    }
+    fn fade_out(self, duration: f32) -> Self {
+        self.on_exit(duration, fade(|t| 1. - Easing::EaseIn.apply(t)))
+    }
+    fn slide_in(self, offset: Vec3, duration: f32) -> Self {
+        self.on_enter(
+            duration,
+            slide(move |t| offset * (1. - Easing::EaseOut.apply(t))),
+        )
+    }
+    fn slide_out(self, offset: Vec3, duration: f32) -> Self {
+        self.on_exit(duration, slide(move |t| offset * Easing::EaseIn.apply(t)))
+    }
+}
+
+/// Remembers the value a transition started from for each entity, as the closure of a transition is
+/// shared by all the entities of an element.
+struct StartValues<T>(Mutex<HashMap<EntityId, T>>);
+impl<T: Clone> StartValues<T> {
+    fn new() -> Arc<Self> {
+        Arc::new(Self(Mutex::new(HashMap::new())))
+    }
+    fn get(&self, entity: EntityId, progress: f32, init: impl FnOnce() -> T) -> T {
+        let mut values = self.0.lock();
+        if progress >= 1. {
+            values.remove(&entity).unwrap_or_else(init)
+        } else {
+            values.entry(entity).or_insert_with(init).clone()
+        }
+    }
+}
+
+/// Scales the alpha of `color` and `background_color` by `alpha(progress)`.
+fn fade(
+    alpha: impl Fn(f32) -> f32 + Sync + Send + 'static,
+) -> impl Fn(&mut World, EntityId, f32) + Sync + Send + 'static {
+    let start = StartValues::new();
+    move |world, entity, progress| {
+        let components = [color(), background_color()];
+        let values = start.get(entity, progress, || {
+            components.map(|component| world.get_cloned(entity, component).ok())
+        });
+        let alpha = alpha(progress);
+        for (component, value) in components.into_iter().zip(values) {
+            if let Some(value) = value {
+                world
+                    .set(entity, component, value * Vec4::new(1., 1., 1., alpha))
+                    .ok();
+            }
+        }
+    }
+}
+
+/// Offsets the `translation` by `offset(progress)`.
+fn slide(
+    offset: impl Fn(f32) -> Vec3 + Sync + Send + 'static,
+) -> impl Fn(&mut World, EntityId, f32) + Sync + Send + 'static {
+    let start = StartValues::new();
+    move |world, entity, progress| {
+        let value = start.get(entity, progress, || {
+            world.get_cloned(entity, translation()).ok()
+        });
+        if let Some(value) = value {
+            world
+                .set(entity, translation(), value + offset(progress))
+                .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ambient_cb::cb;
+
+    use super::*;
+
+    #[test]
+    fn easing_curves() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.), 0., "{easing:?}");
+            assert_eq!(easing.apply(1.), 1., "{easing:?}");
+            // Progress outside of 0..=1 is clamped
+            assert_eq!(easing.apply(-1.), 0., "{easing:?}");
+            assert_eq!(easing.apply(2.), 1., "{easing:?}");
+
+            let samples = (0..=100)
+                .map(|i| easing.apply(i as f32 / 100.))
+                .collect::<Vec<_>>();
+            assert!(
+                samples.windows(2).all(|pair| pair[0] <= pair[1]),
+                "{easing:?} isn't monotonic"
+            );
+        }
+        assert_eq!(Easing::Custom(cb(|t: f32| t * t)).apply(0.5), 0.25);
+    }
+
+    #[test]
+    fn tweens_reach_the_target_after_the_duration() {
+        let mut tween = Tween {
+            from: 0.,
+            to: 10.,
+            current: 0.,
+            started: Instant::now(),
+        };
+        assert!(tween.update(0.5, 1., &Easing::EaseOut));
+        assert_eq!(tween.current, 10. * Easing::EaseOut.apply(0.5));
+
+        assert!(!tween.update(1.5, 1., &Easing::EaseOut));
+        assert_eq!(tween.current, 10.);
+
+        // Tweens without a duration jump to the target
+        tween.to = 20.;
+        assert!(!tween.update(0., 0., &Easing::Linear));
+        assert_eq!(tween.current, 20.);
+    }
+
+    #[test]
+    fn springs_settle_on_the_target() {
+        // Stiff, critically damped and bouncy springs
+        for (stiffness, damping) in [(400., 40.), (100., 20.), (170., 5.)] {
+            let mut spring = Spring {
+                value: Vec2::ZERO,
+                velocity: Vec2::ZERO,
+                updated: Instant::now(),
+            };
+            let target = Vec2::new(100., -50.);
+            let frames = (0..600)
+                .position(|_| !spring.update(target, stiffness, damping, 1. / 60.))
+                .unwrap_or_else(|| panic!("spring {stiffness}/{damping} didn't settle"));
+            assert!(frames > 0);
+            assert_eq!(spring.value, target);
+            assert_eq!(spring.velocity, Vec2::ZERO);
+
+            // A settled spring stays put
+            assert!(!spring.update(target, stiffness, damping, 1. / 60.));
+        }
+    }
+}
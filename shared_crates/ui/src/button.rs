@@ -11,8 +11,8 @@ use std::{
 use ambient_cb::{cb, Callback, Cb};
 use ambient_color::Color;
 use ambient_element::{
-    element_component, to_owned, use_effect, use_runtime_message, use_state, use_state_with,
-    Element, ElementComponent, ElementComponentExt, Hooks,
+    element_component, to_owned, use_effect, use_module_message, use_runtime_message, use_state,
+    use_state_with, Element, ElementComponent, ElementComponentExt, Hooks,
 };
 use ambient_guest_bridge::{
     core::{
@@ -27,6 +27,11 @@ use ambient_guest_bridge::{
         rect::components::{border_color, border_radius, border_thickness},
         rendering::components::color,
         text::{components::font_style, types::FontStyle},
+        ui::{
+            components::{accessibility_disabled, accessibility_role, focusable},
+            messages::Activate,
+            types::AccessibilityRole,
+        },
     },
     ecs::World,
     run_async,
@@ -37,11 +42,11 @@ use glam::*;
 use parking_lot::Mutex;
 
 use crate::{
-    default_theme::{cutout_color, primary_color, secondary_color},
     dropdown::Tooltip,
     layout::{FlowColumn, FlowRow},
     text::Text,
-    use_keyboard_input, UIBase, UIElement, UIExt,
+    theme::{use_theme, Theme},
+    use_focus, use_keyboard_input, UIBase, UIElement, UIExt,
 };
 
 #[derive(Clone, Debug)]
@@ -91,6 +96,7 @@ impl ButtonStyle {
     #[allow(clippy::too_many_arguments)]
     fn create_container(
         &self,
+        theme: &Theme,
         is_pressed: bool,
         is_working: bool,
         disabled: bool,
@@ -101,32 +107,37 @@ impl ButtonStyle {
         tooltip: Option<Element>,
         content: Element,
     ) -> Element {
+        let style = theme.buttons.get(*self);
         let background = match self {
             ButtonStyle::Regular | ButtonStyle::Card => {
+                let base = style.background.unwrap_or(theme.colors.cutout);
                 if is_pressed {
-                    cutout_color().lighten(0.1)
+                    base.lighten(0.1)
                 } else if disabled || is_working {
-                    cutout_color()
+                    base
                 } else if toggled {
-                    primary_color()
+                    theme.colors.primary
                 } else if hover && *self == ButtonStyle::Card {
-                    cutout_color().lighten(0.05)
+                    base.lighten(0.05)
                 } else {
-                    cutout_color()
+                    base
                 }
             }
             ButtonStyle::Primary => {
+                let base = style.background.unwrap_or(theme.colors.secondary);
                 if is_pressed {
-                    secondary_color().lighten(0.2)
+                    base.lighten(0.2)
                 } else if disabled || is_working {
-                    secondary_color().desaturate(-1.)
+                    base.desaturate(-1.)
                 } else if hover || disabled || is_working {
-                    secondary_color().lighten(0.1)
+                    base.lighten(0.1)
                 } else {
-                    secondary_color()
+                    base
                 }
             }
-            ButtonStyle::Flat | ButtonStyle::Inline => Color::rgba(1., 1., 1., 0.0),
+            ButtonStyle::Flat | ButtonStyle::Inline => {
+                style.background.unwrap_or(Color::rgba(1., 1., 1., 0.0))
+            }
         };
         let content = match self {
             Self::Regular | Self::Flat | ButtonStyle::Inline => content.with(
@@ -137,25 +148,23 @@ impl ButtonStyle {
                     Color::rgba(0.3, 0.3, 0.3, 1.)
                 } else if toggled {
                     if *self == Self::Flat || *self == Self::Inline {
-                        primary_color()
+                        theme.colors.primary
                     } else {
                         Color::rgba(1., 1., 1., 1.)
                     }
                 } else if hover {
                     Color::rgba(0.8, 0.8, 0.8, 1.)
                 } else {
-                    Color::hex("B3B3B3").unwrap()
+                    style.text.unwrap_or(Color::hex("B3B3B3").unwrap())
                 }
                 .into(),
             ),
             Self::Primary => content.with(
                 color(),
-                if is_pressed {
-                    Color::BLACK
-                } else if disabled || is_working {
-                    Color::BLACK.lighten(0.3)
+                if disabled || is_working {
+                    style.text.unwrap_or(Color::BLACK).lighten(0.3)
                 } else {
-                    Color::BLACK
+                    style.text.unwrap_or(Color::BLACK)
                 }
                 .into(),
             ),
@@ -212,11 +221,12 @@ impl ButtonStyle {
                 .with_background(background.into())
                 .with(
                     border_radius(),
-                    match self {
-                        Self::Card => Vec4::ONE * 3.,
-                        Self::Flat => Vec4::ONE * 3.,
-                        _ => Vec4::ONE * 26. / 2.,
-                    },
+                    Vec4::ONE
+                        * style.rounding.unwrap_or(match self {
+                            Self::Card => 3.,
+                            Self::Flat => 3.,
+                            _ => 26. / 2.,
+                        }),
                 )
                 .with(border_thickness(), 0.)
                 .with(border_color(), Color::WHITE.into());
@@ -259,6 +269,10 @@ pub fn Button(
     let (hover, set_hover) = use_state(hooks, false);
     let (is_working, set_is_working) = use_state(hooks, false);
     let (is_pressed_immediate, _) = use_state_with(hooks, |_| Arc::new(AtomicBool::new(false)));
+    // Whether the focus was last moved with the keyboard rather than by clicking
+    let (keyboard_focus, _) = use_state_with(hooks, |_| Arc::new(AtomicBool::new(false)));
+    let (focused, _) = use_focus(hooks);
+    let theme = use_theme(hooks);
 
     use_effect(hooks, is_pressed, move |world, _| {
         if let Some(on_is_pressed_changed) = on_is_pressed_changed {
@@ -267,9 +281,12 @@ pub fn Button(
         |_| {}
     });
     use_runtime_message::<messages::WindowMouseInput>(hooks, {
-        to_owned![set_is_pressed, on_invoked, set_is_working];
+        to_owned![set_is_pressed, on_invoked, set_is_working, keyboard_focus];
         move |world, event| {
             let pressed = event.pressed;
+            if pressed {
+                keyboard_focus.store(false, Ordering::SeqCst);
+            }
             if pressed && hover {
                 set_is_pressed(true);
                 is_pressed_immediate.store(true, Ordering::SeqCst);
@@ -287,8 +304,38 @@ pub fn Button(
         }
     });
 
+    // A button focused with tab is pressed with enter or space. A button focused by clicking it
+    // is not, as that would press it again when typing a space after the click.
+    use_keyboard_input(hooks, {
+        to_owned![on_invoked, set_is_working];
+        move |world, keycode, _, pressed| {
+            if keycode == Some(VirtualKeyCode::Tab) {
+                keyboard_focus.store(true, Ordering::SeqCst);
+            }
+            let key = matches!(
+                keycode,
+                Some(VirtualKeyCode::Return | VirtualKeyCode::Space)
+            );
+            if focused && key && !pressed && !disabled && keyboard_focus.load(Ordering::SeqCst) {
+                on_invoked.invoke(world, set_is_working.clone());
+            }
+        }
+    });
+
+    // Assistive technologies, like screen readers, press the button with [Activate]
+    use_module_message::<Activate>(hooks, {
+        to_owned![on_invoked, set_is_working];
+        let instance_id = hooks.instance_id().to_string();
+        move |world, _, event| {
+            if event.focus == instance_id && !disabled {
+                on_invoked.invoke(world, set_is_working.clone());
+            }
+        }
+    });
+
     let content = style
         .create_container(
+            &theme,
             is_pressed,
             is_working,
             disabled,
@@ -311,7 +358,10 @@ pub fn Button(
             set_hover(false);
             ambient_guest_bridge::window::set_cursor(world, CursorIcon::Default);
         })
-        .el();
+        .el()
+        .with(focusable(), hooks.instance_id().to_string())
+        .with(accessibility_role(), AccessibilityRole::Button)
+        .with(accessibility_disabled(), disabled);
 
     if disabled {
         content
@@ -1,4 +1,5 @@
-//! Defines the default theme for the UI.
+//! Defines the default theme for the UI. Widgets read their style from the [Theme](crate::theme::Theme)
+//! provided with [use_theme](crate::theme::use_theme), which defaults to these values.
 use glam::{vec4, Vec4};
 
 use crate::UIExt;
@@ -102,6 +103,8 @@ pub const COLLECTION_DELETE_ICON: &str = "\u{f6bf}";
 pub const MOVE_UP_ICON: &str = "\u{f062}";
 /// Character code for the "move down" icon.
 pub const MOVE_DOWN_ICON: &str = "\u{f063}";
+/// Character code for the "drag handle" icon.
+pub const DRAG_HANDLE_ICON: &str = "\u{f58e}";
 /// Character code for the "right chevron" icon.
 pub const CHEVRON_RIGHT: &str = "\u{f054}";
 /// Character code for the "left chevron" icon.
@@ -0,0 +1,461 @@
+//! Drag and drop of typed payloads between elements.
+//!
+//! A [DragSource] starts dragging its payload when the left mouse button is pressed on it and the
+//! cursor moves, optionally showing a preview under the cursor. When the button is released, the
+//! payload is dropped on the drop target under the cursor if it accepts it.
+//!
+//! Drop targets are resolved through mouse picking: the target is the `mouse_over_entity`, or its
+//! closest ancestor that is a drop target, so any entity with `mouse_pickable_min` and
+//! `mouse_pickable_max` can receive drops. UI elements become drop targets with [DropTarget], and
+//! other entities with [use_drop_target]. Payloads that aren't dropped on a target go to the world
+//! drop targets registered with [use_world_drop_target], such as a 3D scene.
+//!
+//! Each world has its own drags and drop targets, kept in its [DragDropState].
+use std::{any::Any, collections::HashMap, fmt::Debug, sync::Arc};
+
+use ambient_cb::{cb, Cb};
+use ambient_element::{
+    to_owned, use_effect, use_frame, use_ref_with, use_runtime_message, use_state, Element,
+    ElementComponent, ElementComponentExt, Hooks, Setter,
+};
+use ambient_guest_bridge::{
+    core::{
+        app::components::cursor_position,
+        hierarchy::components::parent,
+        input::components::mouse_over_entity,
+        messages,
+        rect::components::{border_color, border_thickness},
+        transform::components::{local_to_world, translation},
+    },
+    ecs::{EntityId, World},
+    window::set_cursor,
+};
+use ambient_shared_types::{CursorIcon, MouseButton};
+use glam::{vec3, Vec2};
+use parking_lot::Mutex;
+
+use crate::{layout::FlowColumn, theme::use_theme, with_rect, UIBase, UIExt};
+
+/// How far the cursor has to move, in pixels, before a press on a [DragSource] becomes a drag.
+pub const DRAG_THRESHOLD: f32 = 4.;
+
+type Payload = Arc<dyn Any + Sync + Send>;
+
+#[derive(Debug)]
+struct DragSession {
+    source: EntityId,
+    payload: Payload,
+    start: Vec2,
+    dragging: bool,
+    set_dragging: Setter<bool>,
+    on_drag_end: Option<Cb<dyn Fn(&mut World, bool) + Sync + Send>>,
+}
+
+#[derive(Clone)]
+struct RegisteredTarget {
+    accepts: Arc<dyn Fn(&Payload) -> bool + Sync + Send>,
+    on_drop: Arc<dyn Fn(&mut World, &Payload, EntityId) + Sync + Send>,
+}
+impl RegisteredTarget {
+    fn new<T: Clone + Sync + Send + 'static>(
+        accept: Option<Cb<dyn Fn(&T) -> bool + Sync + Send>>,
+        on_drop: Cb<dyn Fn(&mut World, T, EntityId) + Sync + Send>,
+    ) -> Self {
+        Self {
+            accepts: Arc::new(move |payload: &Payload| match payload.downcast_ref::<T>() {
+                Some(payload) => accept.as_ref().map_or(true, |accept| accept(payload)),
+                None => false,
+            }),
+            on_drop: Arc::new(move |world: &mut World, payload: &Payload, picked| {
+                if let Some(payload) = payload.downcast_ref::<T>() {
+                    on_drop(world, payload.clone(), picked);
+                }
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum TargetKey {
+    Entity(EntityId),
+    World(u64),
+}
+
+/// The drags and drop targets of a world.
+#[derive(Default)]
+pub struct DragDropState {
+    // There is a single cursor, so there's at most one drag at a time.
+    session: Option<DragSession>,
+    targets: HashMap<TargetKey, RegisteredTarget>,
+    next_world_target: u64,
+}
+impl Debug for DragDropState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DragDropState")
+            .field("session", &self.session)
+            .field("targets", &self.targets.keys())
+            .finish()
+    }
+}
+
+#[cfg(feature = "native")]
+ambient_guest_bridge::ecs::components!("ui", {
+    /// The drags and drop targets of the world this resource is in.
+    drag_drop_state: Arc<Mutex<DragDropState>>,
+});
+
+/// The [DragDropState] of `world`, which is added if it doesn't have one yet.
+#[cfg(feature = "native")]
+fn state(world: &mut World) -> Arc<Mutex<DragDropState>> {
+    if let Some(state) = world.resource_opt(drag_drop_state()) {
+        return state.clone();
+    }
+    let state = Arc::new(Mutex::new(DragDropState::default()));
+    world.add_resource(drag_drop_state(), state.clone());
+    state
+}
+/// The [DragDropState] of `world`, if it has one.
+#[cfg(feature = "native")]
+fn existing_state(world: &World) -> Option<Arc<Mutex<DragDropState>>> {
+    world.resource_opt(drag_drop_state()).cloned()
+}
+
+// A module only has access to a single world
+#[cfg(not(feature = "native"))]
+static STATE: std::sync::OnceLock<Arc<Mutex<DragDropState>>> = std::sync::OnceLock::new();
+#[cfg(not(feature = "native"))]
+fn state(_world: &mut World) -> Arc<Mutex<DragDropState>> {
+    STATE.get_or_init(Default::default).clone()
+}
+#[cfg(not(feature = "native"))]
+fn existing_state(_world: &World) -> Option<Arc<Mutex<DragDropState>>> {
+    STATE.get().cloned()
+}
+
+fn register_target(world: &mut World, key: TargetKey, target: RegisteredTarget) {
+    state(world).lock().targets.insert(key, target);
+}
+fn unregister_target(world: &mut World, key: TargetKey) {
+    state(world).lock().targets.remove(&key);
+}
+
+/// Returns the drop target under the cursor if it accepts `payload`, and the picked entity.
+fn resolve_target(
+    world: &World,
+    payload: &Payload,
+) -> (Option<(TargetKey, RegisteredTarget)>, EntityId) {
+    let picked = world.resource(mouse_over_entity());
+    let targets = existing_state(world)
+        .map(|state| state.lock().targets.clone())
+        .unwrap_or_default();
+    let mut entity = picked;
+    while !entity.is_null() {
+        let key = TargetKey::Entity(entity);
+        if let Some(target) = targets.get(&key) {
+            // The closest target decides, even if it doesn't accept the payload
+            let accepted = (target.accepts)(payload).then(|| (key, target.clone()));
+            return (accepted, picked);
+        }
+        entity = world.get(entity, parent()).unwrap_or(EntityId::null());
+    }
+    let world_target = targets
+        .into_iter()
+        .find(|(key, target)| matches!(key, TargetKey::World(_)) && (target.accepts)(payload));
+    (world_target, picked)
+}
+
+/// Ends the current drag when the mouse button is released, dropping the payload if it's over a
+/// target. This is run by every source and target, so that the drop still happens if the source
+/// was unmounted during the drag; only the first call does anything.
+fn finish_drag(world: &mut World) {
+    let Some(session) = state(world).lock().session.take() else {
+        return;
+    };
+    if !session.dragging {
+        return;
+    }
+    let (target, picked) = resolve_target(world, &session.payload);
+    if let Some((_, target)) = &target {
+        (target.on_drop)(world, &session.payload, picked);
+    }
+    (session.set_dragging)(false);
+    set_cursor(world, CursorIcon::Default);
+    if let Some(on_drag_end) = &session.on_drag_end {
+        on_drag_end(world, target.is_some());
+    }
+}
+
+fn use_finish_drag(hooks: &mut Hooks) {
+    use_runtime_message::<messages::WindowMouseInput>(hooks, |world, event| {
+        if !event.pressed && MouseButton::from(event.button) == MouseButton::Left {
+            finish_drag(world);
+        }
+    });
+}
+
+/// Returns true if something is being dragged in `world`.
+pub fn is_dragging(world: &World) -> bool {
+    existing_state(world).map_or(false, |state| {
+        state
+            .lock()
+            .session
+            .as_ref()
+            .map_or(false, |session| session.dragging)
+    })
+}
+
+/// Returns the drop target entity under the cursor if it accepts what is being dragged.
+pub fn hovered_drop_target(world: &World) -> Option<EntityId> {
+    let payload = match &existing_state(world)?.lock().session {
+        Some(session) if session.dragging => session.payload.clone(),
+        _ => return None,
+    };
+    match resolve_target(world, &payload) {
+        (Some((TargetKey::Entity(entity), _)), _) => Some(entity),
+        _ => None,
+    }
+}
+
+/// Makes `entity` a drop target for payloads of type `T` while the calling element is mounted.
+///
+/// `accept` decides which payloads can be dropped; `on_drop` is called with the dropped payload.
+/// Nothing is registered while `entity` is null.
+pub fn use_drop_target<T: Clone + Sync + Send + 'static>(
+    hooks: &mut Hooks,
+    entity: EntityId,
+    accept: Option<Cb<dyn Fn(&T) -> bool + Sync + Send>>,
+    on_drop: Cb<dyn Fn(&mut World, T) + Sync + Send>,
+) {
+    use_finish_drag(hooks);
+    if !entity.is_null() {
+        // The callbacks are replaced on each render, as they may capture new state
+        register_target(
+            hooks.world,
+            TargetKey::Entity(entity),
+            RegisteredTarget::new(
+                accept,
+                cb(move |world: &mut World, payload: T, _: EntityId| on_drop(world, payload)),
+            ),
+        );
+    }
+    use_effect(hooks, entity, move |_, &entity| {
+        move |world| unregister_target(world, TargetKey::Entity(entity))
+    });
+}
+
+/// Receives the payloads of type `T` that are dropped outside of any drop target while the calling
+/// element is mounted.
+///
+/// `on_drop` is called with the payload and the entity that was picked under the cursor, which is
+/// null if there was none.
+pub fn use_world_drop_target<T: Clone + Sync + Send + 'static>(
+    hooks: &mut Hooks,
+    accept: Option<Cb<dyn Fn(&T) -> bool + Sync + Send>>,
+    on_drop: Cb<dyn Fn(&mut World, T, EntityId) + Sync + Send>,
+) {
+    use_finish_drag(hooks);
+    let key = *use_ref_with(hooks, |world| {
+        let state = state(world);
+        let mut state = state.lock();
+        state.next_world_target += 1;
+        TargetKey::World(state.next_world_target)
+    })
+    .lock();
+    register_target(hooks.world, key, RegisteredTarget::new(accept, on_drop));
+    use_effect(hooks, key, move |_, &key| {
+        move |world| unregister_target(world, key)
+    });
+}
+
+#[derive(Debug, Clone)]
+/// Makes `inner` draggable, carrying `payload` to a [DropTarget].
+pub struct DragSource<T: Debug + Clone + Sync + Send + 'static> {
+    /// The element to drag.
+    pub inner: Element,
+    /// The value that is dropped on the target.
+    pub payload: T,
+    /// The element shown under the cursor while dragging. It shouldn't be clickable, as it would
+    /// hide the drop targets under it.
+    pub preview: Option<Element>,
+    /// Called when the drag starts.
+    pub on_drag_start: Option<Cb<dyn Fn(&mut World) + Sync + Send>>,
+    /// Called when the drag ends, with whether the payload was dropped on a target.
+    pub on_drag_end: Option<Cb<dyn Fn(&mut World, bool) + Sync + Send>>,
+}
+impl<T: Debug + Clone + Sync + Send + 'static> DragSource<T> {
+    /// Creates a new [DragSource].
+    pub fn new(inner: Element, payload: T) -> Self {
+        Self {
+            inner,
+            payload,
+            preview: None,
+            on_drag_start: None,
+            on_drag_end: None,
+        }
+    }
+    /// Sets the element shown under the cursor while dragging.
+    pub fn preview(mut self, preview: Element) -> Self {
+        self.preview = Some(preview);
+        self
+    }
+    /// Sets the callback for when the drag starts.
+    pub fn on_drag_start(mut self, handle: impl Fn(&mut World) + Sync + Send + 'static) -> Self {
+        self.on_drag_start = Some(cb(handle));
+        self
+    }
+    /// Sets the callback for when the drag ends. It's called even if the source was unmounted
+    /// during the drag, as long as a drop target is mounted.
+    pub fn on_drag_end(
+        mut self,
+        handle: impl Fn(&mut World, bool) + Sync + Send + 'static,
+    ) -> Self {
+        self.on_drag_end = Some(cb(handle));
+        self
+    }
+}
+impl<T: Debug + Clone + Sync + Send + 'static> ElementComponent for DragSource<T> {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+        let Self {
+            inner,
+            payload,
+            preview,
+            on_drag_start,
+            on_drag_end,
+        } = *self;
+        let (dragging, set_dragging) = use_state(hooks, false);
+        let id = use_ref_with(hooks, |_| EntityId::null());
+        let anchor_id = use_ref_with(hooks, |_| EntityId::null());
+        let preview_id = use_ref_with(hooks, |_| EntityId::null());
+        use_finish_drag(hooks);
+
+        use_runtime_message::<messages::WindowMouseMotion>(hooks, {
+            to_owned![id, anchor_id, preview_id];
+            move |world, _| {
+                let position = world.resource(cursor_position());
+                let (started, dragging) = {
+                    let state = state(world);
+                    let mut state = state.lock();
+                    let Some(session) = state.session.as_mut().filter(|s| s.source == *id.lock())
+                    else {
+                        return;
+                    };
+                    let started =
+                        !session.dragging && position.distance(session.start) > DRAG_THRESHOLD;
+                    session.dragging |= started;
+                    if started {
+                        (session.set_dragging)(true);
+                    }
+                    (started, session.dragging)
+                };
+                if started {
+                    set_cursor(world, CursorIcon::Grabbing);
+                    if let Some(on_drag_start) = &on_drag_start {
+                        on_drag_start(world);
+                    }
+                }
+                let (anchor_id, preview_id) = (*anchor_id.lock(), *preview_id.lock());
+                if dragging && !preview_id.is_null() {
+                    if let Ok(anchor) = world.get(anchor_id, local_to_world()) {
+                        let (_, _, anchor) = anchor.to_scale_rotation_translation();
+                        let offset = position - anchor.truncate() + DRAG_THRESHOLD;
+                        world
+                            .set(preview_id, translation(), offset.extend(-0.1))
+                            .ok();
+                    }
+                }
+            }
+        });
+
+        let source = inner
+            .with_clickarea()
+            .on_mouse_down({
+                to_owned![id];
+                move |world, _, button| {
+                    if button == MouseButton::Left {
+                        state(world).lock().session = Some(DragSession {
+                            source: *id.lock(),
+                            payload: Arc::new(payload.clone()),
+                            start: world.resource(cursor_position()),
+                            dragging: false,
+                            set_dragging: set_dragging.clone(),
+                            on_drag_end: on_drag_end.clone(),
+                        });
+                    }
+                }
+            })
+            .el()
+            .on_spawned(move |_, new_id, _| *id.lock() = new_id);
+
+        // The preview is anchored below the source, like a dropdown, and moved to the cursor
+        match preview {
+            Some(preview) => FlowColumn::el([
+                source,
+                if dragging {
+                    UIBase
+                        .el()
+                        .children(vec![preview
+                            .init(translation(), vec3(0., 0., -0.1))
+                            .on_spawned(move |_, id, _| *preview_id.lock() = id)])
+                        .on_spawned(move |_, id, _| *anchor_id.lock() = id)
+                } else {
+                    Element::new()
+                },
+            ]),
+            None => source,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Makes `inner` a target that payloads of type `T` can be dropped on. It's outlined while an
+/// accepted payload is dragged over it.
+pub struct DropTarget<T: Debug + Clone + Sync + Send + 'static> {
+    /// The element to drop on.
+    pub inner: Element,
+    /// Decides which payloads can be dropped. If `None`, all payloads of type `T` are accepted.
+    pub accept: Option<Cb<dyn Fn(&T) -> bool + Sync + Send>>,
+    /// Called with the dropped payload.
+    pub on_drop: Cb<dyn Fn(&mut World, T) + Sync + Send>,
+}
+impl<T: Debug + Clone + Sync + Send + 'static> DropTarget<T> {
+    /// Creates a new [DropTarget].
+    pub fn new(inner: Element, on_drop: impl Fn(&mut World, T) + Sync + Send + 'static) -> Self {
+        Self {
+            inner,
+            accept: None,
+            on_drop: cb(on_drop),
+        }
+    }
+    /// Only accepts the payloads for which `accept` returns true.
+    pub fn accept(mut self, accept: impl Fn(&T) -> bool + Sync + Send + 'static) -> Self {
+        self.accept = Some(cb(accept));
+        self
+    }
+}
+impl<T: Debug + Clone + Sync + Send + 'static> ElementComponent for DropTarget<T> {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+        let Self {
+            inner,
+            accept,
+            on_drop,
+        } = *self;
+        let theme = use_theme(hooks);
+        let (id, set_id) = use_state(hooks, EntityId::null());
+        let (hovered, set_hovered) = use_state(hooks, false);
+        use_drop_target(hooks, id, accept, on_drop);
+        use_frame(hooks, move |world| {
+            let next = !id.is_null() && hovered_drop_target(world) == Some(id);
+            if next != hovered {
+                set_hovered(next);
+            }
+        });
+
+        // The click area makes the target pickable
+        with_rect(inner)
+            .with(border_color(), theme.colors.primary.into())
+            .with(border_thickness(), if hovered { 2. } else { 0. })
+            .with_clickarea()
+            .el()
+            .on_spawned(move |_, id, _| set_id(id))
+    }
+}
@@ -4,15 +4,14 @@ use ambient_element::{
     element_component, to_owned, use_state, Element, ElementComponentExt, Hooks,
 };
 use ambient_guest_bridge::core::{
-    layout::components::margin, rect::components::border_radius, transform::components::translation,
+    layout::components::margin,
+    rect::components::border_radius,
+    transform::components::translation,
+    ui::{components::accessibility_role, types::AccessibilityRole},
 };
 use glam::{vec3, Vec4};
 
-use crate::{
-    default_theme::{tooltip_background_color, SMALL_ROUNDING, STREET},
-    layout::FlowColumn,
-    UIBase, UIExt,
-};
+use crate::{layout::FlowColumn, theme::use_theme, UIBase, UIExt};
 
 #[element_component]
 /// A dropdown element: shows the `dropdown` when `show` is specified.
@@ -47,14 +46,16 @@ pub fn Tooltip(
     tooltip: Element,
 ) -> Element {
     let (hover, set_hover) = use_state(hooks, false);
+    let theme = use_theme(hooks);
     Dropdown {
         content: inner,
         dropdown: FlowColumn(vec![tooltip])
             .el()
-            .with_padding_even(STREET)
-            .with_background(tooltip_background_color().into())
-            .with(border_radius(), Vec4::ONE * SMALL_ROUNDING)
-            .with(margin(), Vec4::X * STREET),
+            .with_padding_even(theme.spacing)
+            .with_background(theme.colors.tooltip_background.into())
+            .with(border_radius(), Vec4::ONE * theme.rounding)
+            .with(margin(), Vec4::X * theme.spacing)
+            .with(accessibility_role(), AccessibilityRole::Tooltip),
         show: hover,
     }
     .el()
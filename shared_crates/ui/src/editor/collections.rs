@@ -16,6 +16,7 @@ use ambient_guest_bridge::core::{
     },
     messages,
 };
+use ambient_guest_bridge::ecs::EntityId;
 use ambient_shared_types::VirtualKeyCode;
 use indexmap::IndexMap;
 use itertools::Itertools;
@@ -24,33 +25,59 @@ use super::{Editor, EditorOpts};
 use crate::{
     button::{Button, ButtonStyle},
     default_theme::{
-        StylesExt, COLLECTION_ADD_ICON, COLLECTION_DELETE_ICON, MOVE_DOWN_ICON, MOVE_UP_ICON,
-        STREET,
+        StylesExt, COLLECTION_ADD_ICON, COLLECTION_DELETE_ICON, DRAG_HANDLE_ICON, MOVE_DOWN_ICON,
+        MOVE_UP_ICON, STREET,
     },
+    drag_drop::{DragSource, DropTarget},
     dropdown::Dropdown,
     layout::{FlowColumn, FlowRow},
+    text::Text,
+    theme::use_theme,
     use_focus, use_keyboard_input, UIBase, UIExt,
 };
 use glam::{vec4, Vec4};
 
+/// The payload of a dragged [ListEditor] item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ListItemDrag {
+    /// The entity of the list the item belongs to; items can only be dropped in their own list.
+    list: EntityId,
+    index: usize,
+}
+
 #[element_component]
 /// An editor for a list of items that implement [Editor]; each item can be edited, moved up or down, or deleted.
+///
+/// Items can also be reordered by dragging their handle onto another item.
 pub fn ListEditor<T: Editor + std::fmt::Debug + Clone + Default + Sync + Send + 'static>(
-    _: &mut Hooks,
+    hooks: &mut Hooks,
     /// The list of items to edit.
     value: Vec<T>,
     /// A callback that is called when the list of items is changed.
     on_change: Option<Cb<dyn Fn(Vec<T>) + Sync + Send>>,
 ) -> Element {
+    let (list, set_list) = use_state(hooks, EntityId::null());
+    let theme = use_theme(hooks);
     if let Some(on_change) = on_change {
         let button_size = 20.;
+        let len = value.len();
         FlowColumn::el([
             FlowColumn(
                 value
                     .iter()
                     .enumerate()
                     .map(|(i, item)| {
-                        FlowRow(vec![
+                        let row = FlowRow(vec![
+                            DragSource::new(
+                                Text::el(DRAG_HANDLE_ICON).with(min_width(), button_size),
+                                ListItemDrag { list, index: i },
+                            )
+                            .preview(
+                                Text::el(DRAG_HANDLE_ICON)
+                                    .with_padding_even(theme.spacing / 2.)
+                                    .with_background(theme.colors.tooltip_background.into()),
+                            )
+                            .el(),
                             Button::new(COLLECTION_DELETE_ICON, {
                                 to_owned![on_change, value];
                                 move |_| {
@@ -105,11 +132,29 @@ pub fn ListEditor<T: Editor + std::fmt::Debug + Clone + Default + Sync + Send +
                                 Default::default(),
                             ),
                         ])
+                        .el();
+                        DropTarget::new(row, {
+                            to_owned![on_change, value];
+                            move |_, drag: ListItemDrag| {
+                                // The list may have shrunk since the drag started
+                                if drag.index >= value.len() {
+                                    return;
+                                }
+                                let mut value = value.clone();
+                                let item = value.remove(drag.index);
+                                value.insert(i, item);
+                                on_change.0(value);
+                            }
+                        })
+                        .accept(move |drag| {
+                            drag.list == list && drag.index != i && drag.index < len
+                        })
                         .el()
                     })
                     .collect(),
             )
-            .el(),
+            .el()
+            .on_spawned(move |_, id, _| set_list(id)),
             Button::new(COLLECTION_ADD_ICON, {
                 to_owned![on_change];
                 move |_| {
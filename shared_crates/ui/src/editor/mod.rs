@@ -4,6 +4,7 @@ mod collections;
 mod primitives;
 mod screens;
 mod slider;
+mod text_edit;
 mod text_editor;
 mod time;
 use std::{ops::Deref, sync::Arc};
@@ -15,6 +16,7 @@ use parking_lot::Mutex;
 pub use primitives::*;
 pub use screens::*;
 pub use slider::*;
+pub use text_edit::*;
 pub use text_editor::*;
 pub use time::*;
 
@@ -22,11 +22,7 @@ use glam::{vec3, Vec4};
 
 use super::Editor;
 use crate::{
-    default_theme::{primary_color, STREET},
-    editor::EditorOpts,
-    layout::FlowRow,
-    text::Text,
-    Rectangle, UIBase, UIExt,
+    editor::EditorOpts, layout::FlowRow, text::Text, theme::use_theme, Rectangle, UIBase, UIExt,
 };
 
 #[derive(Clone, Debug)]
@@ -147,6 +143,7 @@ impl ElementComponent for Slider {
             block_left_offset
         };
 
+        let theme = use_theme(hooks);
         let dragging = use_ref_with(hooks, |_| false);
         use_runtime_message::<messages::WindowMouseInput>(hooks, {
             let dragging = dragging.clone();
@@ -186,7 +183,7 @@ impl ElementComponent for Slider {
             .with(width(), slider_width)
             .with(height(), 2.)
             .with(translation(), vec3(0., (SLIDER_HEIGHT - 2.) / 2., 0.))
-            .with(background_color(), primary_color().into())
+            .with(background_color(), theme.colors.primary.into())
             .on_spawned(move |_, id, _| *block_id.lock() = id);
 
         let thumb = {
@@ -194,7 +191,7 @@ impl ElementComponent for Slider {
                 .el()
                 .with(width(), THUMB_WIDTH)
                 .with(height(), SLIDER_HEIGHT)
-                .with_background(primary_color().into())
+                .with_background(theme.colors.primary.into())
                 .with(border_radius(), Vec4::ONE * THUMB_WIDTH / 2.)
                 .with(translation(), vec3(block_left_offset, 0., -0.01))
                 .with_clickarea()
@@ -227,7 +224,7 @@ impl ElementComponent for Slider {
                 suffix.map(Text::el).unwrap_or_default(),
             ]),
         ])
-        .with(space_between_items(), STREET)
+        .with(space_between_items(), theme.spacing)
     }
 }
 
@@ -0,0 +1,504 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How many edits can be undone.
+const MAX_UNDO: usize = 100;
+
+/// A movement of the cursor of a [TextEditState].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// One grapheme to the left.
+    Left,
+    /// One grapheme to the right.
+    Right,
+    /// To the start of the previous word.
+    WordLeft,
+    /// To the end of the next word.
+    WordRight,
+    /// To the start of the line.
+    LineStart,
+    /// To the end of the line.
+    LineEnd,
+    /// To the same column on the previous line.
+    Up,
+    /// To the same column on the next line.
+    Down,
+    /// To the start of the text.
+    Start,
+    /// To the end of the text.
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Type,
+    DeleteBackward,
+    DeleteForward,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    value: String,
+    cursor: usize,
+    anchor: usize,
+}
+
+/// The editing state of a [TextEditor](super::TextEditor): the text, the cursor and selection,
+/// and the undo history.
+///
+/// Positions are byte offsets into the text, and the cursor always moves by whole graphemes,
+/// so an emoji or a letter with combining accents is never split.
+#[derive(Debug, Clone, Default)]
+pub struct TextEditState {
+    value: String,
+    cursor: usize,
+    anchor: usize,
+    /// The column (in graphemes) that vertical movement tries to keep.
+    column: Option<usize>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: Option<EditKind>,
+    revision: u64,
+}
+
+impl TextEditState {
+    /// Creates a new state with the cursor at the end of `value`.
+    pub fn new(value: String) -> Self {
+        let cursor = value.len();
+        Self {
+            value,
+            cursor,
+            anchor: cursor,
+            ..Default::default()
+        }
+    }
+    /// The text being edited.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+    /// Increases every time the text is edited through this state.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+    /// The position of the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    /// The selected range, which is empty when nothing is selected.
+    pub fn selection(&self) -> Range<usize> {
+        self.cursor.min(self.anchor)..self.cursor.max(self.anchor)
+    }
+    /// Whether any text is selected.
+    pub fn has_selection(&self) -> bool {
+        self.cursor != self.anchor
+    }
+    /// The selected text.
+    pub fn selected_text(&self) -> &str {
+        &self.value[self.selection()]
+    }
+    /// The range of each line, excluding the newline.
+    pub fn lines(&self) -> Vec<Range<usize>> {
+        let mut start = 0;
+        self.value
+            .split('\n')
+            .map(|line| {
+                let range = start..start + line.len();
+                start = range.end + 1;
+                range
+            })
+            .collect()
+    }
+    /// The line the cursor is on.
+    pub fn cursor_line(&self) -> usize {
+        self.value[..self.cursor].matches('\n').count()
+    }
+    /// The position at `column` graphemes into `line`, clamped to the text.
+    pub fn position(&self, line: usize, column: usize) -> usize {
+        match self.lines().get(line) {
+            Some(range) => self.at_column(range.start, column),
+            None => self.value.len(),
+        }
+    }
+
+    /// Replaces the text after it was changed from outside the editor, keeping the cursor and
+    /// selection where possible. This does not add to the undo history.
+    pub fn set_value(&mut self, value: String) {
+        if value == self.value {
+            return;
+        }
+        self.value = value;
+        self.cursor = floor_boundary(&self.value, self.cursor);
+        self.anchor = floor_boundary(&self.value, self.anchor);
+        self.column = None;
+        self.last_edit = None;
+    }
+
+    /// Moves the cursor to `position`, selecting from the current anchor if `extend` is set.
+    pub fn set_cursor(&mut self, position: usize, extend: bool) {
+        self.cursor = floor_boundary(&self.value, position);
+        if !extend {
+            self.anchor = self.cursor;
+        }
+        self.column = None;
+        self.last_edit = None;
+    }
+    /// Moves the cursor, selecting from the current anchor if `extend` is set.
+    pub fn move_cursor(&mut self, motion: Motion, extend: bool) {
+        let value = &self.value;
+        let cursor = self.cursor;
+        let column = matches!(motion, Motion::Up | Motion::Down)
+            .then(|| self.column.unwrap_or_else(|| self.column_at(cursor)));
+        let target = match motion {
+            // Collapsing a selection stops at its edge
+            Motion::Left if !extend && self.has_selection() => self.selection().start,
+            Motion::Right if !extend && self.has_selection() => self.selection().end,
+            Motion::Left => prev_grapheme(value, cursor),
+            Motion::Right => next_grapheme(value, cursor),
+            Motion::WordLeft => word_start(value, cursor),
+            Motion::WordRight => word_end(value, cursor),
+            Motion::LineStart => line_start(value, cursor),
+            Motion::LineEnd => line_end(value, cursor),
+            Motion::Up => match line_start(value, cursor) {
+                0 => 0,
+                start => self.at_column(line_start(value, start - 1), column.unwrap_or_default()),
+            },
+            Motion::Down => match line_end(value, cursor) {
+                end if end == value.len() => end,
+                end => self.at_column(end + 1, column.unwrap_or_default()),
+            },
+            Motion::Start => 0,
+            Motion::End => value.len(),
+        };
+        self.cursor = target;
+        if !extend {
+            self.anchor = target;
+        }
+        self.column = column;
+        self.last_edit = None;
+    }
+    /// Selects all of the text.
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.cursor = self.value.len();
+        self.column = None;
+        self.last_edit = None;
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection.
+    ///
+    /// Characters typed one after another are undone together, up to the next whitespace.
+    pub fn insert(&mut self, text: &str) {
+        if text.is_empty() && !self.has_selection() {
+            return;
+        }
+        let kind = if !self.has_selection() && text.graphemes(true).count() == 1 {
+            EditKind::Type
+        } else {
+            EditKind::Other
+        };
+        self.edit(kind, self.selection(), text);
+    }
+    /// Deletes the selection, or else the grapheme (or word, if `word` is set) before the cursor.
+    pub fn delete_backward(&mut self, word: bool) {
+        if self.has_selection() {
+            return self.edit(EditKind::Other, self.selection(), "");
+        }
+        let start = if word {
+            word_start(&self.value, self.cursor)
+        } else {
+            prev_grapheme(&self.value, self.cursor)
+        };
+        if start < self.cursor {
+            self.edit(EditKind::DeleteBackward, start..self.cursor, "");
+        }
+    }
+    /// Deletes the selection, or else the grapheme (or word, if `word` is set) after the cursor.
+    pub fn delete_forward(&mut self, word: bool) {
+        if self.has_selection() {
+            return self.edit(EditKind::Other, self.selection(), "");
+        }
+        let end = if word {
+            word_end(&self.value, self.cursor)
+        } else {
+            next_grapheme(&self.value, self.cursor)
+        };
+        if end > self.cursor {
+            self.edit(EditKind::DeleteForward, self.cursor..end, "");
+        }
+    }
+    /// Deletes the selection and returns the deleted text.
+    pub fn cut(&mut self) -> Option<String> {
+        if !self.has_selection() {
+            return None;
+        }
+        let text = self.selected_text().to_string();
+        self.edit(EditKind::Other, self.selection(), "");
+        Some(text)
+    }
+
+    /// Reverts the last edit. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+    /// Reapplies the last undone edit. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    fn edit(&mut self, kind: EditKind, range: Range<usize>, text: &str) {
+        let continues = kind != EditKind::Other
+            && self.last_edit == Some(kind)
+            && !text.starts_with(char::is_whitespace);
+        if !continues {
+            self.undo.push(self.snapshot());
+            if self.undo.len() > MAX_UNDO {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.value.replace_range(range.clone(), text);
+        self.cursor = range.start + text.len();
+        self.anchor = self.cursor;
+        self.column = None;
+        self.last_edit = Some(kind);
+        self.revision += 1;
+    }
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            value: self.value.clone(),
+            cursor: self.cursor,
+            anchor: self.anchor,
+        }
+    }
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.value = snapshot.value;
+        self.cursor = snapshot.cursor;
+        self.anchor = snapshot.anchor;
+        self.column = None;
+        self.last_edit = None;
+        self.revision += 1;
+    }
+
+    fn column_at(&self, position: usize) -> usize {
+        self.value[line_start(&self.value, position)..position]
+            .graphemes(true)
+            .count()
+    }
+    fn at_column(&self, start: usize, column: usize) -> usize {
+        let line = &self.value[start..line_end(&self.value, start)];
+        start
+            + line
+                .grapheme_indices(true)
+                .nth(column)
+                .map_or(line.len(), |(i, _)| i)
+    }
+}
+
+fn floor_boundary(value: &str, position: usize) -> usize {
+    let mut position = position.min(value.len());
+    while !value.is_char_boundary(position) {
+        position -= 1;
+    }
+    position
+}
+fn prev_grapheme(value: &str, position: usize) -> usize {
+    value[..position]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+fn next_grapheme(value: &str, position: usize) -> usize {
+    value[position..]
+        .graphemes(true)
+        .next()
+        .map_or(value.len(), |g| position + g.len())
+}
+fn is_blank(word: &str) -> bool {
+    word.chars().all(char::is_whitespace)
+}
+fn word_start(value: &str, position: usize) -> usize {
+    value[..position]
+        .split_word_bound_indices()
+        .rev()
+        .find(|(_, word)| !is_blank(word))
+        .map_or(0, |(i, _)| i)
+}
+fn word_end(value: &str, position: usize) -> usize {
+    value[position..]
+        .split_word_bound_indices()
+        .find(|(_, word)| !is_blank(word))
+        .map_or(value.len(), |(i, word)| position + i + word.len())
+}
+fn line_start(value: &str, position: usize) -> usize {
+    value[..position].rfind('\n').map_or(0, |i| i + 1)
+}
+fn line_end(value: &str, position: usize) -> usize {
+    value[position..]
+        .find('\n')
+        .map_or(value.len(), |i| position + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str, cursor: usize) -> TextEditState {
+        let mut state = TextEditState::new(value.to_string());
+        state.set_cursor(cursor, false);
+        state
+    }
+    fn motions(state: &mut TextEditState, motion: Motion, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                state.move_cursor(motion, false);
+                state.cursor()
+            })
+            .collect()
+    }
+    fn type_text(state: &mut TextEditState, text: &str) {
+        for c in text.chars() {
+            state.insert(&c.to_string());
+        }
+    }
+
+    #[test]
+    fn moves_by_graphemes() {
+        // An accented letter and an emoji with a skin tone are single graphemes
+        let value = "ae\u{301}👍🏽b";
+        let mut state = TextEditState::new(value.to_string());
+        assert_eq!(motions(&mut state, Motion::Left, 5), vec![12, 4, 1, 0, 0]);
+        assert_eq!(
+            motions(&mut state, Motion::Right, 5),
+            vec![1, 4, 12, 13, 13]
+        );
+
+        state.set_cursor(12, false);
+        state.delete_backward(false);
+        assert_eq!(state.value(), "ae\u{301}b");
+        state.delete_backward(false);
+        assert_eq!(state.value(), "ab");
+    }
+
+    #[test]
+    fn set_cursor_snaps_to_char_boundaries() {
+        let mut state = TextEditState::new("aé".to_string());
+        state.set_cursor(2, false);
+        assert_eq!(state.cursor(), 1);
+        state.set_cursor(100, false);
+        assert_eq!(state.cursor(), 3);
+    }
+
+    #[test]
+    fn moves_by_words() {
+        let mut state = TextEditState::new("hello, big world".to_string());
+        assert_eq!(
+            motions(&mut state, Motion::WordLeft, 5),
+            vec![11, 7, 5, 0, 0]
+        );
+        assert_eq!(
+            motions(&mut state, Motion::WordRight, 5),
+            vec![5, 6, 10, 16, 16]
+        );
+
+        state.delete_backward(true);
+        assert_eq!(state.value(), "hello, big ");
+        state.set_cursor(0, false);
+        state.delete_forward(true);
+        assert_eq!(state.value(), ", big ");
+    }
+
+    #[test]
+    fn vertical_motion_keeps_the_column() {
+        let mut state = at("abcd\nx\nabcd", 3);
+        assert_eq!(motions(&mut state, Motion::Down, 3), vec![6, 10, 11]);
+        assert_eq!(motions(&mut state, Motion::Up, 3), vec![6, 3, 0]);
+        assert_eq!(state.cursor_line(), 0);
+    }
+
+    #[test]
+    fn collapsing_a_selection_stops_at_its_edge() {
+        let mut state = at("hello", 2);
+        state.move_cursor(Motion::Right, true);
+        state.move_cursor(Motion::Right, true);
+        assert_eq!(state.selected_text(), "ll");
+        state.move_cursor(Motion::Left, false);
+        assert_eq!((state.cursor(), state.has_selection()), (2, false));
+
+        state.select_all();
+        state.move_cursor(Motion::Right, false);
+        assert_eq!((state.cursor(), state.has_selection()), (5, false));
+    }
+
+    #[test]
+    fn typing_is_undone_a_word_at_a_time() {
+        let mut state = TextEditState::default();
+        type_text(&mut state, "ab cd");
+        assert!(state.undo());
+        assert_eq!(state.value(), "ab");
+        assert!(state.undo());
+        assert_eq!(state.value(), "");
+        assert!(!state.undo());
+
+        assert!(state.redo());
+        assert!(state.redo());
+        assert_eq!(state.value(), "ab cd");
+        assert!(!state.redo());
+    }
+
+    #[test]
+    fn moving_or_switching_edits_stops_coalescing() {
+        let mut state = TextEditState::default();
+        type_text(&mut state, "ab");
+        state.move_cursor(Motion::Left, false);
+        type_text(&mut state, "c");
+        state.delete_backward(false);
+        state.delete_backward(false);
+        assert_eq!(state.value(), "b");
+
+        assert!(state.undo());
+        assert_eq!(state.value(), "acb");
+        assert!(state.undo());
+        assert_eq!(state.value(), "ab");
+        assert!(state.undo());
+        assert_eq!(state.value(), "");
+    }
+
+    #[test]
+    fn editing_clears_redo() {
+        let mut state = TextEditState::default();
+        type_text(&mut state, "a");
+        state.undo();
+        type_text(&mut state, "b");
+        assert!(!state.redo());
+        assert_eq!(state.value(), "b");
+    }
+
+    #[test]
+    fn set_value_clamps_the_cursor_and_selection() {
+        let mut state = TextEditState::new("hello".to_string());
+        state.select_all();
+        state.set_value("hi".to_string());
+        assert_eq!(state.selection(), 0..2);
+
+        // Positions inside a character move back to its start
+        let mut state = at("abc", 2);
+        state.set_value("aé".to_string());
+        assert_eq!(state.cursor(), 1);
+
+        // Changes from outside the editor aren't undone
+        assert!(!state.undo());
+        assert_eq!(state.value(), "aé");
+    }
+}
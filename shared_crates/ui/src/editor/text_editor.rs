@@ -4,32 +4,49 @@ use std::time::Instant;
 use ambient_cb::{cb, Cb};
 use ambient_element::{
     element_component, to_owned, use_frame, use_ref_with, use_rerender_signal, use_runtime_message,
-    use_spawn, use_state, Element, ElementComponentExt, Hooks,
+    use_spawn, Element, ElementComponentExt, Hooks,
 };
 use ambient_guest_bridge::{
     core::{
+        app::components::cursor_position,
         layout::components::{height, min_height, min_width, width},
         messages,
         rect::components::background_color,
         rendering::components::color,
         text::components::text,
-        transform::components::translation,
-        ui::components::focusable,
+        transform::components::{local_to_world, translation},
+        ui::{
+            components::{accessibility_label, accessibility_role, accessibility_value, focusable},
+            types::AccessibilityRole,
+        },
     },
+    ecs::{EntityId, World},
     window::set_cursor,
 };
-use ambient_shared_types::{CursorIcon, VirtualKeyCode};
+use ambient_shared_types::{CursorIcon, ModifiersState, MouseButton, VirtualKeyCode};
 #[cfg(feature = "native")]
 use ambient_sys::time::Instant;
 use glam::*;
-use itertools::Itertools;
+use parking_lot::Mutex;
+use unicode_segmentation::UnicodeSegmentation;
 
-use super::{Editor, EditorOpts};
+use super::{Editor, EditorOpts, Motion, TextEditState};
 use crate::{
-    layout::FlowRow, text::Text, use_focus, use_keyboard_input, with_rect, Rectangle, UIBase, UIExt,
+    layout::{FlowColumn, FlowRow},
+    text::{escape_markup, RichText, Text},
+    use_focus, use_keyboard_input, with_rect, Rectangle, UIBase, UIExt,
 };
 
+const LINE_HEIGHT: f32 = 13.;
+
 /// A text editor.
+///
+/// Supports selecting with the mouse or with shift and the arrow keys, word navigation with
+/// ctrl (alt on macOS), copy, cut and paste, undo (ctrl+z) and redo (ctrl+shift+z or ctrl+y),
+/// and composing text with an input method.
+///
+/// In multiline mode, enter inserts a new line and ctrl+enter submits, and the text scrolls to
+/// keep the cursor visible.
 #[element_component]
 pub fn TextEditor(
     hooks: &mut Hooks,
@@ -45,20 +62,22 @@ pub fn TextEditor(
     placeholder: Option<String>,
     /// Whether the text editor should be focused when it is created.
     auto_focus: bool,
+    /// Whether the text can span multiple lines.
+    multiline: bool,
+    /// The number of lines that are visible at once in multiline mode.
+    rows: u32,
 ) -> Element {
     let (focused, set_focused) = use_focus(hooks);
-    let (command, set_command) = use_state(hooks, false);
-    let intermediate_value = use_ref_with(hooks, |_| value.clone());
-    let cursor_position = use_ref_with(hooks, |_| value.len());
+    let state = use_ref_with(hooks, |_| TextEditState::new(value.clone()));
+    let modifiers = use_ref_with(hooks, |_| ModifiersState::empty());
+    let composition = use_ref_with(hooks, |_| String::new());
+    let dragging = use_ref_with(hooks, |_| false);
+    let scroll = use_ref_with(hooks, |_| 0usize);
+    let last_cursor = use_ref_with(hooks, |_| None);
+    let root_id = use_ref_with(hooks, |_| None);
+    let line_ids = use_ref_with(hooks, |_| Vec::<Option<EntityId>>::new());
     let rerender = use_rerender_signal(hooks);
-    {
-        let mut inter = intermediate_value.lock();
-        if *inter != value {
-            let mut cp = cursor_position.lock();
-            *cp = cp.min(value.len());
-        }
-        *inter = value.clone();
-    }
+    state.lock().set_value(value.clone());
 
     use_spawn(hooks, {
         to_owned![set_focused];
@@ -75,131 +94,237 @@ pub fn TextEditor(
     });
 
     use_runtime_message::<messages::WindowKeyboardCharacter>(hooks, {
-        to_owned![intermediate_value, on_change, cursor_position];
+        to_owned![state, modifiers, on_change, rerender];
         move |_world, event| {
-            let c = event.character.chars().next().unwrap();
-            if command || !focused {
+            if !focused || is_command(*modifiers.lock()) {
                 return;
             }
-
-            let mut value = intermediate_value.lock();
-            value.insert(*cursor_position.lock(), c);
-            *cursor_position.lock() += 1;
-            on_change.0(value.clone());
+            let text = event
+                .character
+                .chars()
+                .filter(|c| !c.is_control())
+                .collect::<String>();
+            if !text.is_empty() {
+                update(&state, &on_change, &rerender, |state| state.insert(&text));
+            }
+        }
+    });
+    use_runtime_message::<messages::WindowImeComposition>(hooks, {
+        to_owned![composition, rerender];
+        move |_world, event| {
+            if focused {
+                *composition.lock() = event.text.clone();
+                rerender();
+            }
         }
     });
     use_keyboard_input(hooks, {
-        to_owned![intermediate_value, on_change, cursor_position];
-        move |world, keycode, _modifiers, pressed| {
-            if !focused {
+        to_owned![state, modifiers, on_change, rerender];
+        move |world, keycode, mods, pressed| {
+            *modifiers.lock() = mods;
+            let Some(keycode) = keycode.filter(|_| focused && pressed) else {
                 return;
+            };
+            let command = is_command(mods);
+            let word = if cfg!(target_os = "macos") {
+                mods.alt()
+            } else {
+                mods.ctrl()
+            };
+            let shift = mods.shift();
+            let motion = match keycode {
+                VirtualKeyCode::Left if word => Some(Motion::WordLeft),
+                VirtualKeyCode::Left => Some(Motion::Left),
+                VirtualKeyCode::Right if word => Some(Motion::WordRight),
+                VirtualKeyCode::Right => Some(Motion::Right),
+                VirtualKeyCode::Up if multiline && !command => Some(Motion::Up),
+                VirtualKeyCode::Down if multiline && !command => Some(Motion::Down),
+                VirtualKeyCode::Up | VirtualKeyCode::Home if command || !multiline => {
+                    Some(Motion::Start)
+                }
+                VirtualKeyCode::Down | VirtualKeyCode::End if command || !multiline => {
+                    Some(Motion::End)
+                }
+                VirtualKeyCode::Home => Some(Motion::LineStart),
+                VirtualKeyCode::End => Some(Motion::LineEnd),
+                _ => None,
+            };
+            if let Some(motion) = motion {
+                return update(&state, &on_change, &rerender, |state| {
+                    state.move_cursor(motion, shift)
+                });
             }
-            if let Some(kc) = keycode {
-                match kc {
-                    VirtualKeyCode::LWin => {
-                        #[cfg(target_os = "macos")]
-                        set_command(pressed);
-                    }
-                    VirtualKeyCode::LControl => {
-                        #[cfg(not(target_os = "macos"))]
-                        set_command(pressed);
-                    }
-                    VirtualKeyCode::C if command && pressed => {
-                        let value = intermediate_value.lock().clone();
+            match keycode {
+                VirtualKeyCode::A if command => {
+                    update(&state, &on_change, &rerender, |state| state.select_all());
+                }
+                VirtualKeyCode::C if command && !password => {
+                    let text = state.lock().selected_text().to_string();
+                    if !text.is_empty() {
                         ambient_guest_bridge::run_async_local(world, move || async move {
-                            let _ = ambient_guest_bridge::window::set_clipboard(&value).await;
+                            let _ = ambient_guest_bridge::window::set_clipboard(&text).await;
                         })
                     }
-                    VirtualKeyCode::V if command && pressed => {
-                        let on_change = on_change.clone();
-                        let cursor_position = cursor_position.clone();
-                        let intermediate_value = intermediate_value.clone();
+                }
+                VirtualKeyCode::X if command && !password => {
+                    let mut text = None;
+                    update(&state, &on_change, &rerender, |state| text = state.cut());
+                    if let Some(text) = text {
                         ambient_guest_bridge::run_async_local(world, move || async move {
-                            if let Some(paste) = ambient_guest_bridge::window::get_clipboard().await
-                            {
-                                let mut value = intermediate_value.lock();
-                                value.insert_str(*cursor_position.lock(), &paste);
-                                *cursor_position.lock() += paste.len();
-                                on_change.0(value.clone());
-                            }
+                            let _ = ambient_guest_bridge::window::set_clipboard(&text).await;
                         })
                     }
-                    VirtualKeyCode::Left => {
-                        if pressed && *cursor_position.lock() > 0 {
-                            *cursor_position.lock() -= 1;
-                            rerender();
-                        }
-                    }
-                    VirtualKeyCode::Right => {
-                        if pressed && *cursor_position.lock() < intermediate_value.lock().len() {
-                            *cursor_position.lock() += 1;
-                            rerender();
-                        }
-                    }
-                    VirtualKeyCode::Back => {
-                        if pressed && *cursor_position.lock() > 0 {
-                            let mut value = intermediate_value.lock();
-                            value.remove(*cursor_position.lock() - 1);
-                            *cursor_position.lock() -= 1;
-                            on_change.0(value.clone());
-                        }
-                    }
-                    VirtualKeyCode::Delete => {
-                        if pressed && *cursor_position.lock() < intermediate_value.lock().len() {
-                            let mut value = intermediate_value.lock();
-                            value.remove(*cursor_position.lock());
-                            on_change.0(value.clone());
+                }
+                VirtualKeyCode::V if command => {
+                    to_owned![state, on_change, rerender];
+                    ambient_guest_bridge::run_async_local(world, move || async move {
+                        if let Some(paste) = ambient_guest_bridge::window::get_clipboard().await {
+                            let paste = if multiline {
+                                paste.replace("\r\n", "\n")
+                            } else {
+                                paste.replace(['\r', '\n'], " ")
+                            };
+                            update(&state, &on_change, &rerender, |state| state.insert(&paste));
                         }
-                    }
-                    VirtualKeyCode::Return => {
-                        if pressed && !command {
-                            if let Some(on_submit) = on_submit.clone() {
-                                on_submit.0(intermediate_value.lock().clone());
-                            }
+                    })
+                }
+                VirtualKeyCode::Z if command && shift => {
+                    update(&state, &on_change, &rerender, |state| {
+                        state.redo();
+                    });
+                }
+                VirtualKeyCode::Z if command => {
+                    update(&state, &on_change, &rerender, |state| {
+                        state.undo();
+                    });
+                }
+                VirtualKeyCode::Y if command => {
+                    update(&state, &on_change, &rerender, |state| {
+                        state.redo();
+                    });
+                }
+                VirtualKeyCode::Back => {
+                    update(&state, &on_change, &rerender, |state| {
+                        state.delete_backward(word)
+                    });
+                }
+                VirtualKeyCode::Delete => {
+                    update(&state, &on_change, &rerender, |state| {
+                        state.delete_forward(word)
+                    });
+                }
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                    if multiline && !command {
+                        update(&state, &on_change, &rerender, |state| state.insert("\n"));
+                    } else if multiline || !command {
+                        if let Some(on_submit) = on_submit.clone() {
+                            on_submit.0(state.lock().value().to_string());
                         }
                     }
-                    _ => {}
                 }
+                _ => {}
             }
         }
     });
-    let (cursor_left, cursor_right) = value.split_at(*cursor_position.lock());
-    let [a, b]: [Element; 2] = [cursor_left, cursor_right]
-        .iter()
-        .map(|value| {
-            Text.el()
-                .with(
-                    text(),
-                    if password {
-                        value.chars().map(|_| '*').collect()
-                    } else {
-                        value.to_string()
-                    },
-                )
-                .with(color(), vec4(0.9, 0.9, 0.9, 1.))
-        })
-        .collect_vec()
-        .try_into()
-        .unwrap();
 
-    with_rect(if focused {
-        if !cursor_left.is_empty() {
-            FlowRow::el([a, Cursor.el(), b])
-        } else {
-            FlowRow::el([Cursor.el(), b])
+    use_runtime_message::<messages::WindowMouseInput>(hooks, {
+        to_owned![dragging];
+        move |_world, event| {
+            if !event.pressed {
+                *dragging.lock() = false;
+            }
+        }
+    });
+    use_runtime_message::<messages::WindowMouseMotion>(hooks, {
+        to_owned![state, dragging, root_id, line_ids, scroll, on_change, rerender];
+        move |world, _event| {
+            if !*dragging.lock() {
+                return;
+            }
+            if let Some(position) = hit_test(world, &state, &root_id, &line_ids, *scroll.lock()) {
+                update(&state, &on_change, &rerender, |state| {
+                    state.set_cursor(position, true)
+                });
+            }
+        }
+    });
+
+    let rows = if multiline { rows.max(1) as usize } else { 1 };
+    let current = state.lock();
+    let lines = current.lines();
+    let first_line = {
+        let mut scroll = scroll.lock();
+        let mut last_cursor = last_cursor.lock();
+        // Only follow the cursor when it moved, so that the mouse wheel can scroll away from it
+        if *last_cursor != Some(current.cursor()) {
+            let cursor_line = current.cursor_line();
+            *scroll = (*scroll).clamp((cursor_line + 1).saturating_sub(rows), cursor_line);
+            *last_cursor = Some(current.cursor());
         }
-    } else if value.is_empty() && !focused && placeholder.is_some() {
+        *scroll = (*scroll).min(lines.len().saturating_sub(rows));
+        *scroll
+    };
+
+    let label = placeholder.clone();
+    let content = if value.is_empty() && !focused && placeholder.is_some() {
         FlowRow::el([Text
             .el()
             .with(text(), placeholder.unwrap())
             .with(color(), vec4(1., 1., 1., 0.2))])
     } else {
-        FlowRow::el([a, b])
+        let composition = composition.lock().clone();
+        line_ids.lock().truncate(rows);
+        let rows = lines
+            .iter()
+            .enumerate()
+            .skip(first_line)
+            .take(rows)
+            .map(|(index, line)| {
+                let cursor = Some(current.cursor())
+                    .filter(|cursor| focused && line.start <= *cursor && *cursor <= line.end);
+                render_line(&current, line.clone(), cursor, &composition, password)
+                    .with(min_height(), LINE_HEIGHT)
+                    .on_spawned({
+                        to_owned![line_ids];
+                        let row = index - first_line;
+                        move |_, id, _| {
+                            let mut line_ids = line_ids.lock();
+                            if line_ids.len() <= row {
+                                line_ids.resize(row + 1, None);
+                            }
+                            line_ids[row] = Some(id);
+                        }
+                    })
+            })
+            .collect::<Vec<_>>();
+        if multiline {
+            FlowColumn::el(rows)
+        } else {
+            FlowRow::el(rows)
+        }
+    };
+
+    let mut root = with_rect(content)
+        .with(min_width(), 3.)
+        .with(min_height(), rows as f32 * LINE_HEIGHT)
+        .with(background_color(), vec4(0., 0., 0., 0.5))
+        .with(focusable(), hooks.instance_id().to_string())
+        .with(accessibility_role(), AccessibilityRole::TextInput)
+        .with(
+            accessibility_value(),
+            if password {
+                "*".repeat(value.chars().count())
+            } else {
+                value
+            },
+        );
+    if let Some(label) = label {
+        root = root.with(accessibility_label(), label);
+    }
+    root.on_spawned({
+        to_owned![root_id];
+        move |_, id, _| *root_id.lock() = Some(id)
     })
-    .with(min_width(), 3.)
-    .with(min_height(), 13.)
-    .with(background_color(), vec4(0., 0., 0., 0.5))
-    .with(focusable(), hooks.instance_id().to_string())
     .with_clickarea()
     .on_mouse_enter(|world, _| {
         set_cursor(world, CursorIcon::Text);
@@ -207,6 +332,37 @@ pub fn TextEditor(
     .on_mouse_leave(|world, _| {
         set_cursor(world, CursorIcon::Default);
     })
+    .on_mouse_down({
+        to_owned![state, dragging, root_id, line_ids, scroll, modifiers, on_change, rerender];
+        move |world, _, button| {
+            if button != MouseButton::Left {
+                return;
+            }
+            if let Some(position) = hit_test(world, &state, &root_id, &line_ids, *scroll.lock()) {
+                let extend = modifiers.lock().shift();
+                update(&state, &on_change, &rerender, |state| {
+                    state.set_cursor(position, extend)
+                });
+                *dragging.lock() = true;
+            }
+        }
+    })
+    .on_mouse_wheel({
+        to_owned![scroll, rerender];
+        move |_, _, delta, pixels| {
+            if !multiline {
+                return;
+            }
+            let lines = if pixels {
+                delta.y / LINE_HEIGHT
+            } else {
+                delta.y
+            };
+            let mut scroll = scroll.lock();
+            *scroll = (*scroll as f32 - lines.round()).max(0.) as usize;
+            rerender();
+        }
+    })
     .el()
 }
 
@@ -220,6 +376,8 @@ impl TextEditor {
             password: false,
             placeholder: None,
             auto_focus: false,
+            multiline: false,
+            rows: 1,
         }
     }
     /// Set the `on_submit` callback.
@@ -242,6 +400,140 @@ impl TextEditor {
         self.auto_focus = true;
         self
     }
+    /// Allow multiple lines of text, showing `rows` of them at once.
+    pub fn multiline(mut self, rows: u32) -> Self {
+        self.multiline = true;
+        self.rows = rows;
+        self
+    }
+}
+
+fn is_command(modifiers: ModifiersState) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.logo()
+    } else {
+        modifiers.ctrl()
+    }
+}
+
+/// Applies `f` to the state, and calls `on_change` if it edited the text.
+fn update(
+    state: &Mutex<TextEditState>,
+    on_change: &Cb<dyn Fn(String) + Sync + Send>,
+    rerender: &Cb<dyn Fn() + Sync + Send>,
+    f: impl FnOnce(&mut TextEditState),
+) {
+    let changed = {
+        let mut state = state.lock();
+        let revision = state.revision();
+        f(&mut state);
+        (state.revision() != revision).then(|| state.value().to_string())
+    };
+    if let Some(value) = changed {
+        on_change.0(value);
+    }
+    rerender();
+}
+
+/// Finds the text position under the mouse cursor.
+///
+/// The row is found from the laid out lines, and the column is estimated from how far along
+/// the line the mouse is, as the glyph positions aren't available to the UI.
+fn hit_test(
+    world: &World,
+    state: &Mutex<TextEditState>,
+    root_id: &Mutex<Option<EntityId>>,
+    line_ids: &Mutex<Vec<Option<EntityId>>>,
+    first_line: usize,
+) -> Option<usize> {
+    let mouse = world.resource(cursor_position());
+    let position = |id: EntityId| {
+        world
+            .get(id, local_to_world())
+            .ok()
+            .map(|transform| transform.to_scale_rotation_translation().2.xy())
+    };
+    let root = position((*root_id.lock())?)?;
+    let line_ids = line_ids.lock();
+    let (row, line_position, line_width) = line_ids
+        .iter()
+        .enumerate()
+        .filter_map(|(row, id)| {
+            let id = (*id)?;
+            Some((
+                row,
+                position(id)?,
+                world.get(id, width()).unwrap_or_default(),
+            ))
+        })
+        .filter(|(row, position, _)| *row == 0 || position.y <= mouse.y)
+        .last()?;
+    let state = state.lock();
+    let line = state.lines().get(first_line + row)?.clone();
+    let graphemes = state.value()[line].graphemes(true).count();
+    let column = if mouse.y < root.y || line_width <= 0. {
+        0
+    } else {
+        ((mouse.x - line_position.x) / line_width * graphemes as f32)
+            .round()
+            .max(0.) as usize
+    };
+    Some(state.position(first_line + row, column))
+}
+
+/// Renders a line as runs of plain and selected text, with the cursor and the text being
+/// composed by the input method inserted at `cursor`.
+fn render_line(
+    state: &TextEditState,
+    line: std::ops::Range<usize>,
+    cursor: Option<usize>,
+    composition: &str,
+    password: bool,
+) -> Element {
+    let selection = state.selection();
+    let mut splits = vec![line.start, line.end];
+    splits.extend(
+        [selection.start, selection.end]
+            .into_iter()
+            .chain(cursor)
+            .filter(|position| line.contains(position)),
+    );
+    splits.sort_unstable();
+    splits.dedup();
+
+    let mut items = Vec::new();
+    let mut push_cursor = |items: &mut Vec<Element>| {
+        if !composition.is_empty() {
+            let composition = escape_markup(composition);
+            items.push(RichText::el(format!("<u>{composition}</u>")));
+        }
+        items.push(Cursor.el());
+    };
+    for range in splits.windows(2) {
+        let (start, end) = (range[0], range[1]);
+        if cursor == Some(start) {
+            push_cursor(&mut items);
+        }
+        let run = &state.value()[start..end];
+        let run = if password {
+            run.graphemes(true).map(|_| '*').collect()
+        } else {
+            run.to_string()
+        };
+        let run = Text
+            .el()
+            .with(text(), run)
+            .with(color(), vec4(0.9, 0.9, 0.9, 1.));
+        items.push(if selection.start <= start && end <= selection.end {
+            FlowRow::el([run]).with_background(vec4(0.2, 0.4, 0.8, 0.6))
+        } else {
+            run
+        });
+    }
+    if cursor == Some(line.end) {
+        push_cursor(&mut items);
+    }
+    FlowRow::el(items)
 }
 
 #[element_component]
@@ -257,7 +549,7 @@ fn CursorInner(hooks: &mut Hooks, render_time: Instant) -> Element {
         UIBase.el().children(vec![Rectangle
             .el()
             .with(width(), 2.)
-            .with(height(), 13.)
+            .with(height(), LINE_HEIGHT)
             .with(translation(), vec3(1., 0., 0.))])
     } else {
         Element::new()
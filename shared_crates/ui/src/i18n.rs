@@ -0,0 +1,73 @@
+//! Localized text.
+//!
+//! String tables are added with `ambient_i18n::add_locale`, and keys are translated in the current
+//! locale with `ambient_i18n::t!`. Elements that call [use_locale], such as [LocalizedText], are
+//! re-rendered when the locale changes.
+use ambient_element::{use_frame, use_rerender_signal, Element, ElementComponent, Hooks};
+use ambient_guest_bridge::{
+    core::ui::components::locale,
+    ecs::{EntityId, World},
+};
+use ambient_i18n::Value;
+
+use crate::text::Text;
+
+/// Returns the current locale, like `en` or `pt-BR`, and re-renders the element when it changes.
+pub fn use_locale(hooks: &mut Hooks) -> String {
+    let current = current_locale(hooks.world);
+    // Each module has its own current locale, which follows the locale of the app
+    #[cfg(feature = "guest")]
+    ambient_i18n::set_locale(&current);
+
+    let rerender = use_rerender_signal(hooks);
+    use_frame(hooks, {
+        let current = current.clone();
+        move |world| {
+            if current_locale(world) != current {
+                rerender();
+            }
+        }
+    });
+    current
+}
+
+fn current_locale(world: &World) -> String {
+    world
+        .get_cloned(EntityId::resources(), locale())
+        .unwrap_or_else(|_| ambient_i18n::locale())
+}
+
+/// A [Text] that shows a key translated in the current locale, and is updated when the locale
+/// changes.
+#[derive(Debug, Clone)]
+pub struct LocalizedText {
+    /// The key of the string.
+    pub key: String,
+    /// The arguments of the string.
+    pub args: Vec<(String, Value)>,
+}
+impl LocalizedText {
+    /// Creates a new localized text for `key`.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+    /// Adds the argument `name`.
+    pub fn arg(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.args.push((name.into(), value.into()));
+        self
+    }
+}
+impl ElementComponent for LocalizedText {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+        use_locale(hooks);
+        let args = self
+            .args
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect::<Vec<_>>();
+        Text::el(ambient_i18n::translate(&self.key, &args))
+    }
+}
@@ -3,6 +3,7 @@
 //! The layout is roughly based on [Windows Forms](https://docs.microsoft.com/en-us/dotnet/desktop/winforms/controls/layout?view=netdesktop-6.0#container-flow-layout).
 //!
 //! There are two major layout components, [Dock] and [Flow] (which includes [FlowColumn] and [FlowRow]).
+//! For more control, [Grid] places its children on rows and columns, and [Flex] grows, shrinks and wraps them.
 use crate::{use_window_logical_resolution, UIBase, UIExt};
 use ambient_cb::Cb;
 use ambient_color::Color;
@@ -19,7 +20,7 @@ use itertools::Itertools;
 
 pub use ambient_guest_bridge::core::layout::{
     components::*,
-    types::{Align, Docking, Fit, Layout, Orientation},
+    types::{Align, Docking, Fit, Justify, Layout, Orientation},
 };
 
 #[derive(Debug, Clone)]
@@ -136,6 +137,40 @@ impl ElementComponent for FlowRow {
     }
 }
 
+/// A grid layout, similar to CSS grid.
+///
+/// The tracks are defined with `grid_columns` and `grid_rows` (e.g. `"100 1fr 2fr"`), and the
+/// children pick their cells with `grid_column`, `grid_row` and the `_span` components; children
+/// without a cell are placed in the next free one. Children that [Fit::Parent] fill their cells.
+#[derive(Debug, Clone)]
+pub struct Grid(pub Vec<Element>);
+define_el_function_for_vec_element_newtype!(Grid);
+impl ElementComponent for Grid {
+    fn render(self: Box<Self>, _: &mut Hooks) -> Element {
+        Element::from(UIBase)
+            .init(layout(), Layout::Grid)
+            .init_default(children())
+            .children(self.0)
+    }
+}
+
+/// A flex layout, similar to CSS flexbox.
+///
+/// Children are laid out along the `orientation`, wrapping onto new lines if `flex_wrap` is set.
+/// They grow and shrink to fill each line according to `flex_grow` and `flex_shrink`, and
+/// `justify_content` and `align_content` distribute the remaining space.
+#[derive(Debug, Clone)]
+pub struct Flex(pub Vec<Element>);
+define_el_function_for_vec_element_newtype!(Flex);
+impl ElementComponent for Flex {
+    fn render(self: Box<Self>, _: &mut Hooks) -> Element {
+        Element::from(UIBase)
+            .init(layout(), Layout::Flex)
+            .init_default(children())
+            .children(self.0)
+    }
+}
+
 /// A [Centered] is a [Flow] that is oriented vertically and is centered.
 ///
 #[derive(Debug, Clone)]
@@ -52,11 +52,14 @@ use ambient_shared_types::{ModifiersState, VirtualKeyCode};
 use clickarea::ClickArea;
 use glam::{vec3, Mat4, UVec2, Vec3, Vec4};
 
+pub mod animation;
 pub mod button;
 pub mod clickarea;
 pub mod default_theme;
+pub mod drag_drop;
 pub mod dropdown;
 pub mod editor;
+pub mod i18n;
 pub mod layout;
 pub mod prelude;
 pub mod prompt;
@@ -65,9 +68,17 @@ pub mod scroll_area;
 pub mod select;
 pub mod tabs;
 pub mod text;
+pub mod theme;
 pub mod throbber;
+pub mod virtual_list;
 pub mod window;
 
+/// Initializes the components used by the UI on the host.
+#[cfg(feature = "native")]
+pub fn init_components() {
+    drag_drop::init_components();
+}
+
 /// A base element for all UI elements. It contains all the components needed for a UI element to work.
 #[element_component]
 pub fn UIBase(_: &mut Hooks) -> Element {
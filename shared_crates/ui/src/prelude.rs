@@ -1,9 +1,10 @@
 //! A prelude for users of the crate. Imports all the most commonly used types and functions.
 
 pub use crate::{
-    button::*, clickarea::*, default_theme::*, dropdown::*, editor::*, layout::*, prompt::*,
-    screens::*, scroll_area::*, select::*, tabs::*, text::*, throbber::*, window::*, with_rect,
-    Line, Rectangle, UIBase, UIElement, UIExt,
+    animation::*, button::*, clickarea::*, default_theme::*, drag_drop::*, dropdown::*, editor::*,
+    i18n::*, layout::*, prompt::*, screens::*, scroll_area::*, select::*, tabs::*, text::*,
+    theme::*, throbber::*, virtual_list::*, window::*, with_rect, Line, Rectangle, UIBase,
+    UIElement, UIExt,
 };
 pub use ambient_cb::{cb, Cb};
 pub use ambient_element::{
@@ -1,8 +1,8 @@
 //! Defines several kinds of window-sized screens.
 
 use crate::{
-    default_theme::app_background_color,
     layout::{Dock, WindowSized},
+    theme::use_theme,
     UIBase, UIExt,
 };
 use ambient_element::{
@@ -19,7 +19,8 @@ use glam::vec3;
 pub struct ScreenContainer(pub Option<Element>);
 impl ElementComponent for ScreenContainer {
     #[allow(clippy::clone_on_copy)]
-    fn render(self: Box<Self>, _: &mut Hooks) -> Element {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+        let mut background = use_theme(hooks).colors.app_background;
         if let Some(content) = self.0 {
             UIBase
                 .el()
@@ -28,7 +29,7 @@ impl ElementComponent for ScreenContainer {
                     .el()
                     .with(translation(), vec3(0., 0., 0.1))])
                 .el()
-                .with_background(app_background_color().set_a(0.99).clone().into())
+                .with_background(background.set_a(0.99).clone().into())
                 .with_clickarea()
                 .el()])
         } else {
@@ -43,10 +44,11 @@ pub struct PageScreen(pub Vec<Element>);
 define_el_function_for_vec_element_newtype!(PageScreen);
 impl ElementComponent for PageScreen {
     #[allow(clippy::clone_on_copy)]
-    fn render(self: Box<Self>, _: &mut Hooks) -> Element {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+        let mut background = use_theme(hooks).colors.app_background;
         WindowSized(vec![Dock(self.0).el().with_padding_even(30.)])
             .el()
-            .with_background(app_background_color().set_a(0.99).clone().into())
+            .with_background(background.set_a(0.99).clone().into())
             .with_clickarea()
             .el()
     }
@@ -58,10 +60,11 @@ impl ElementComponent for PageScreen {
 pub struct DialogScreen(pub Element);
 impl ElementComponent for DialogScreen {
     #[allow(clippy::clone_on_copy)]
-    fn render(self: Box<Self>, _: &mut Hooks) -> Element {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
+        let mut background = use_theme(hooks).colors.app_background;
         WindowSized(vec![Dock(vec![self.0]).el().with_padding_even(30.)])
             .el()
-            .with_background(app_background_color().set_a(0.99).clone().into())
+            .with_background(background.set_a(0.99).clone().into())
             .with_clickarea()
             .el()
     }
@@ -16,7 +16,7 @@ use ambient_guest_bridge::core::{
     rendering::components::scissors_recursive,
     transform::components::{local_to_parent, local_to_world, translation},
 };
-use glam::{uvec4, vec3, vec4, Vec2, Vec3, Vec4};
+use glam::{uvec4, vec3, vec4, UVec4, Vec2, Vec3, Vec4};
 
 use crate::{
     layout::{Flow, MeasureAbsolutePosition, MeasureSize},
@@ -105,26 +105,10 @@ pub fn ScrollArea(
             {
                 let flow = Flow(vec![inner])
                     .el()
-                    .with(scissors_recursive(), {
-                        let (y, h) = if canvas_offset.y > 0.0 {
-                            (
-                                (canvas_offset.y * ratio) as u32,
-                                (outer_size.y * ratio) as u32,
-                            )
-                        } else {
-                            (0, ((outer_size.y + canvas_offset.y) * ratio) as u32)
-                        };
-
-                        let (x, w) = if canvas_offset.x > 0.0 {
-                            (
-                                (canvas_offset.x * ratio) as u32,
-                                (outer_size.x * ratio) as u32,
-                            )
-                        } else {
-                            (0, ((outer_size.x + canvas_offset.x) * ratio) as u32)
-                        };
-                        uvec4(x, y, w, h)
-                    })
+                    .with(
+                        scissors_recursive(),
+                        scissors(canvas_offset, outer_size, ratio),
+                    )
                     .on_spawned({
                         to_owned![inner_flow_id];
                         move |_world, flow_id, _| {
@@ -163,3 +147,18 @@ pub fn ScrollArea(
         ScrollAreaSizing::FitParentWidth => canvas,
     }
 }
+
+/// The scissor rectangle, in physical pixels, of an area at `offset` of `size` logical pixels.
+pub(crate) fn scissors(offset: Vec3, size: Vec2, ratio: f32) -> UVec4 {
+    let (y, h) = if offset.y > 0.0 {
+        ((offset.y * ratio) as u32, (size.y * ratio) as u32)
+    } else {
+        (0, ((size.y + offset.y) * ratio) as u32)
+    };
+    let (x, w) = if offset.x > 0.0 {
+        ((offset.x * ratio) as u32, (size.x * ratio) as u32)
+    } else {
+        (0, ((size.x + offset.x) * ratio) as u32)
+    };
+    uvec4(x, y, w, h)
+}
@@ -4,16 +4,22 @@ use ambient_element::{
     to_owned, use_runtime_message, use_state, Element, ElementComponent, ElementComponentExt, Hooks,
 };
 use ambient_guest_bridge::core::{
-    layout::components::margin, messages, rect::components::border_radius,
+    layout::components::margin,
+    messages,
+    rect::components::border_radius,
+    ui::{
+        components::{accessibility_expanded, accessibility_role},
+        types::AccessibilityRole,
+    },
 };
 use glam::{vec4, Vec4};
 
 use crate::{
     button::{Button, ButtonStyle},
-    default_theme::{tooltip_background_color, SMALL_ROUNDING, STREET},
     dropdown::Dropdown,
     layout::{FlowColumn, FlowRow},
     text::Text,
+    theme::use_theme,
     UIExt,
 };
 
@@ -38,6 +44,7 @@ impl ElementComponent for DropdownSelect {
             inline,
         } = *self;
         let (show, set_show) = use_state(hooks, false);
+        let theme = use_theme(hooks);
         use_runtime_message::<messages::WindowMouseInput>(hooks, {
             to_owned![set_show];
             move |_world, event| {
@@ -63,7 +70,9 @@ impl ElementComponent for DropdownSelect {
             } else {
                 ButtonStyle::Regular
             })
-            .el(),
+            .el()
+            .with(accessibility_role(), AccessibilityRole::ComboBox)
+            .with(accessibility_expanded(), show),
             dropdown: FlowColumn(
                 items
                     .into_iter()
@@ -77,7 +86,10 @@ impl ElementComponent for DropdownSelect {
                         })
                         .style(ButtonStyle::Card)
                         .el()
-                        .with(margin(), vec4(if i != 0 { STREET } else { 0. }, 0., 0., 0.))
+                        .with(
+                            margin(),
+                            vec4(if i != 0 { theme.spacing } else { 0. }, 0., 0., 0.),
+                        )
                     })
                     .collect(), //     vec![Bookcase(
                                 //     items
@@ -96,9 +108,10 @@ impl ElementComponent for DropdownSelect {
                                 // .set(orientation(), Orientation::Vertical)]
             )
             .el()
-            .with_padding_even(STREET)
-            .with(border_radius(), Vec4::ONE * SMALL_ROUNDING)
-            .with_background(tooltip_background_color().into()),
+            .with_padding_even(theme.spacing)
+            .with(border_radius(), Vec4::ONE * theme.rounding)
+            .with_background(theme.colors.tooltip_background.into())
+            .with(accessibility_role(), AccessibilityRole::List),
             show,
         }
         .el()
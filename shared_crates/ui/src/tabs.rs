@@ -3,13 +3,19 @@ use std::fmt::Debug;
 
 use ambient_cb::{cb, Cb};
 use ambient_element::{to_owned, use_state, Element, ElementComponent, ElementComponentExt, Hooks};
-use ambient_guest_bridge::core::layout::components::{padding, space_between_items};
+use ambient_guest_bridge::core::{
+    layout::components::{padding, space_between_items},
+    ui::{
+        components::{accessibility_role, accessibility_selected},
+        types::AccessibilityRole,
+    },
+};
 use glam::vec4;
 
 use crate::{
     button::{Button, ButtonStyle},
-    default_theme::STREET,
     layout::{FlowColumn, FlowRow},
+    theme::use_theme,
 };
 
 #[derive(Clone, Debug)]
@@ -25,12 +31,13 @@ pub struct TabBar<T: ToString + PartialEq + Clone + Debug + Sync + Send + 'stati
 impl<T: ToString + PartialEq + Clone + Debug + Sync + Send + 'static> ElementComponent
     for TabBar<T>
 {
-    fn render(self: Box<Self>, _: &mut Hooks) -> Element {
+    fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
         let Self {
             tabs,
             value,
             on_change,
         } = *self;
+        let spacing = use_theme(hooks).spacing;
         FlowRow(
             tabs.into_iter()
                 .map(|tab| {
@@ -41,11 +48,14 @@ impl<T: ToString + PartialEq + Clone + Debug + Sync + Send + 'static> ElementCom
                     .toggled(tab == value)
                     .style(ButtonStyle::Card)
                     .el()
-                    .with(padding(), vec4(0.0, STREET, 0.0, STREET))
+                    .with(padding(), vec4(0.0, spacing, 0.0, spacing))
+                    .with(accessibility_role(), AccessibilityRole::Tab)
+                    .with(accessibility_selected(), tab == value)
                 })
                 .collect(),
         )
         .el()
+        .with(accessibility_role(), AccessibilityRole::TabList)
     }
 }
 
@@ -78,6 +88,7 @@ impl<T: ToString + PartialEq + Default + Clone + Debug + Sync + Send + 'static>
 {
     fn render(self: Box<Self>, hooks: &mut Hooks) -> Element {
         let (value, set_value) = use_state(hooks, T::default());
+        let spacing = use_theme(hooks).spacing;
         let selected_tab = self
             .tabs
             .iter()
@@ -93,8 +104,10 @@ impl<T: ToString + PartialEq + Default + Clone + Debug + Sync + Send + 'static>
                 on_change: cb(move |value| set_value(value)),
             }
             .el(),
-            selected_tab().key(key),
+            selected_tab()
+                .key(key)
+                .with(accessibility_role(), AccessibilityRole::TabPanel),
         ])
-        .with(space_between_items(), STREET)
+        .with(space_between_items(), spacing)
     }
 }
@@ -1,31 +1,38 @@
 //! Defines a text element.
 
-use crate::{UIBase, UIElement};
+use crate::{theme::use_theme, UIBase, UIElement};
 use ambient_element::{element_component, Element, ElementComponentExt, Hooks};
 use ambient_guest_bridge::core::{
     app::components::{main_scene, name, ui_scene},
     layout::components::{height, width},
     rendering::components::color,
-    text::components::{font_family, font_size, text},
+    text::components::{font_family, font_size, rich_text, text},
     transform::components::{local_to_parent, local_to_world, mesh_to_local, mesh_to_world, scale},
 };
-use glam::{vec4, Mat4, Vec3};
+use glam::{Mat4, Vec3};
 
 /// A text element. Use the [text], [font_size], [font_family] and [color] components to set its state.
+///
+/// Text wraps at its `max_width`, and its lines are aligned with `align_horizontal`. Its color,
+/// size and font default to those of the [Theme](crate::theme::Theme).
 #[element_component(without_el)]
-pub fn Text(_hooks: &mut Hooks) -> Element {
-    UIBase
+pub fn Text(hooks: &mut Hooks) -> Element {
+    let theme = use_theme(hooks);
+    let el = UIBase
         .el()
         .init(width(), 1.)
         .init(height(), 1.)
         .init(mesh_to_local(), Mat4::IDENTITY)
-        .init(color(), vec4(0.6, 0.6, 0.6, 1.))
+        .init(color(), theme.colors.text.into())
         .init(name(), "Text".to_string())
         .init(ui_scene(), ())
-        // .init_default(font_family())
         // .init_default(font_style())
-        .init(font_size(), 12.)
-        .init(text(), "".to_string())
+        .init(font_size(), theme.fonts.size)
+        .init(text(), "".to_string());
+    match theme.fonts.family {
+        Some(family) => el.init(font_family(), family),
+        None => el,
+    }
 }
 impl Text {
     /// Creates a new text element with the given text.
@@ -49,6 +56,26 @@ impl From<&String> for UIElement {
     }
 }
 
+/// A text element that styles its text with markup such as `<b>bold</b>`, `<u>underlined</u>`,
+/// `<color=#f00>red</color>`, `<size=20>large</size>` or icons like `<icon=f118>`.
+///
+/// Text that isn't markup, such as user input, must be escaped with [escape_markup].
+#[element_component(without_el)]
+pub fn RichText(_hooks: &mut Hooks) -> Element {
+    Text.el().init(rich_text(), ())
+}
+impl RichText {
+    /// Creates a new rich text element with the given markup.
+    pub fn el(markup: impl Into<String>) -> Element {
+        RichText.el().with(text(), markup.into())
+    }
+}
+
+/// Escapes `text` so that [RichText] shows it as it is, instead of reading tags from it.
+pub fn escape_markup(text: &str) -> String {
+    text.replace('<', "<lt>")
+}
+
 #[element_component]
 /// A FontAwesome icon.
 pub fn FontAwesomeIcon(
@@ -0,0 +1,342 @@
+//! Defines the [Theme] that styles the UI widgets.
+//!
+//! A theme is provided to a subtree with [ThemeProvider] and read with [use_theme]; elements
+//! outside of a provider use the default theme, which is the look of the editor. Themes can be
+//! written in TOML and loaded with [Theme::from_toml]:
+//!
+//! ```toml
+//! spacing = 8.0
+//!
+//! [colors]
+//! primary = "#3A86FF"
+//! app_background = "#F4F4F4"
+//! text = "#202020"
+//!
+//! [fonts]
+//! family = "Ubuntu"
+//! size = 14.0
+//!
+//! [buttons.primary]
+//! background = "#3A86FF"
+//! text = "#FFFFFF"
+//! rounding = 4.0
+//! ```
+//!
+//! Fields that aren't specified keep their default values. On the host, a theme file can also be
+//! provided with [ThemeFromUrl], which restyles the UI when the file changes.
+use ambient_color::Color;
+use ambient_element::{
+    consume_context, element_component, provide_context, use_effect, Element, Hooks, Setter,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    button::ButtonStyle,
+    default_theme::{
+        app_background_color, cutout_color, error_color, primary_color, secondary_color,
+        tooltip_background_color, SMALL_ROUNDING, STREET,
+    },
+};
+
+/// The style of the UI widgets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// The colors.
+    pub colors: ThemeColors,
+    /// The default margin, padding and space between items.
+    pub spacing: f32,
+    /// The default rounding of corners.
+    pub rounding: f32,
+    /// The fonts.
+    pub fonts: ThemeFonts,
+    /// Overrides for each [ButtonStyle].
+    pub buttons: ButtonThemes,
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            colors: Default::default(),
+            spacing: STREET,
+            rounding: SMALL_ROUNDING,
+            fonts: Default::default(),
+            buttons: Default::default(),
+        }
+    }
+}
+impl Theme {
+    /// Parses a theme from TOML. Colors are written as hex strings, like `"#DE0B5D"`.
+    pub fn from_toml(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+    /// Writes the theme as TOML.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("a theme is always valid TOML")
+    }
+}
+
+/// The colors of a [Theme].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    /// The color used for highlights, such as toggled buttons.
+    #[serde(with = "hex")]
+    pub primary: Color,
+    /// The color used for primary buttons.
+    #[serde(with = "hex")]
+    pub secondary: Color,
+    /// The background of screens.
+    #[serde(with = "hex")]
+    pub app_background: Color,
+    /// The color of errors.
+    #[serde(with = "hex")]
+    pub error: Color,
+    /// The background of regular buttons and other inset surfaces.
+    #[serde(with = "hex")]
+    pub cutout: Color,
+    /// The background of tooltips and dropdowns.
+    #[serde(with = "hex")]
+    pub tooltip_background: Color,
+    /// The color of text.
+    #[serde(with = "hex")]
+    pub text: Color,
+}
+impl Default for ThemeColors {
+    fn default() -> Self {
+        Self {
+            primary: primary_color(),
+            secondary: secondary_color(),
+            app_background: app_background_color(),
+            error: error_color(),
+            cutout: cutout_color(),
+            tooltip_background: tooltip_background_color(),
+            text: Color::rgba(0.6, 0.6, 0.6, 1.),
+        }
+    }
+}
+
+/// The fonts of a [Theme].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeFonts {
+    /// The font family of text. If `None`, the renderer's default font is used.
+    pub family: Option<String>,
+    /// The font size of text.
+    pub size: f32,
+}
+impl Default for ThemeFonts {
+    fn default() -> Self {
+        Self {
+            family: None,
+            size: 12.,
+        }
+    }
+}
+
+/// Overrides of the style of a [ButtonStyle]. Unset values use the [Theme]'s colors.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ButtonTheme {
+    /// The background color when idle; it's lightened when hovered and pressed.
+    #[serde(with = "hex_option", skip_serializing_if = "Option::is_none")]
+    pub background: Option<Color>,
+    /// The color of the content when idle.
+    #[serde(with = "hex_option", skip_serializing_if = "Option::is_none")]
+    pub text: Option<Color>,
+    /// The rounding of the corners.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<f32>,
+}
+
+/// The [ButtonTheme] of each [ButtonStyle].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ButtonThemes {
+    /// Overrides for [ButtonStyle::Regular].
+    pub regular: ButtonTheme,
+    /// Overrides for [ButtonStyle::Primary].
+    pub primary: ButtonTheme,
+    /// Overrides for [ButtonStyle::Flat].
+    pub flat: ButtonTheme,
+    /// Overrides for [ButtonStyle::Card].
+    pub card: ButtonTheme,
+    /// Overrides for [ButtonStyle::Inline].
+    pub inline: ButtonTheme,
+}
+impl ButtonThemes {
+    /// The overrides for `style`.
+    pub fn get(&self, style: ButtonStyle) -> &ButtonTheme {
+        match style {
+            ButtonStyle::Regular => &self.regular,
+            ButtonStyle::Primary => &self.primary,
+            ButtonStyle::Flat => &self.flat,
+            ButtonStyle::Card => &self.card,
+            ButtonStyle::Inline => &self.inline,
+        }
+    }
+}
+
+/// Provides `theme` to `inner` and all of its descendants. Changing `theme` restyles them.
+#[element_component]
+pub fn ThemeProvider(
+    hooks: &mut Hooks,
+    /// The theme to provide.
+    theme: Theme,
+    /// The element to style.
+    inner: Element,
+) -> Element {
+    let set_theme = provide_context(hooks, || theme.clone());
+    use_effect(hooks, theme, move |_, theme| {
+        set_theme(theme.clone());
+        |_| {}
+    });
+    inner
+}
+
+/// Provides the [Theme] in the TOML file at `url` to `inner`, like [ThemeProvider].
+///
+/// The file is checked for changes every second, so that editing it restyles the UI while it's
+/// running. The default theme is used until the file has loaded, and the last valid theme is kept
+/// while the file is invalid.
+#[cfg(feature = "native")]
+#[element_component]
+pub fn ThemeFromUrl(
+    hooks: &mut Hooks,
+    /// The URL of the theme file, such as an asset URL.
+    url: String,
+    /// The element to style.
+    inner: Element,
+) -> Element {
+    let (theme, set_theme) = ambient_element::use_state(hooks, Theme::default());
+    use_effect(hooks, url, move |world, url| {
+        let assets = world.resource(ambient_core::asset_cache()).clone();
+        let url = url.clone();
+        let task = world.resource(ambient_core::runtime()).spawn(async move {
+            let url: ambient_native_std::asset_url::AbsAssetUrl = match url.parse() {
+                Ok(url) => url,
+                Err(err) => {
+                    tracing::warn!("Invalid theme url {url:?}: {err:?}");
+                    return;
+                }
+            };
+            let mut interval = ambient_sys::time::interval(std::time::Duration::from_secs(1));
+            let mut loaded = None;
+            loop {
+                interval.tick().await;
+                let source = match url.download_string(&assets).await {
+                    Ok(source) => source,
+                    Err(err) => {
+                        tracing::warn!("Failed to load theme {url}: {err:?}");
+                        continue;
+                    }
+                };
+                if loaded.as_ref() == Some(&source) {
+                    continue;
+                }
+                match Theme::from_toml(&source) {
+                    Ok(theme) => set_theme(theme),
+                    Err(err) => tracing::warn!("Invalid theme {url}: {err}"),
+                }
+                loaded = Some(source);
+            }
+        });
+        move |_| task.abort()
+    });
+    ThemeProvider::el(theme, inner)
+}
+
+/// Returns the [Theme] provided by the closest [ThemeProvider], or the default theme.
+pub fn use_theme(hooks: &mut Hooks) -> Theme {
+    consume_context::<Theme>(hooks)
+        .map(|(theme, _)| theme)
+        .unwrap_or_default()
+}
+
+/// Returns a setter that replaces the [Theme] of the closest [ThemeProvider], if there is one.
+///
+/// The theme is replaced until the provider's `theme` changes.
+pub fn use_set_theme(hooks: &mut Hooks) -> Option<Setter<Theme>> {
+    consume_context::<Theme>(hooks).map(|(_, set_theme)| set_theme)
+}
+
+mod hex {
+    use ambient_color::Color;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let [r, g, b, a] = color
+            .as_rgba_f32()
+            .map(|c| (c.clamp(0., 1.) * 255.).round() as u8);
+        serializer.serialize_str(&format!("#{r:02X}{g:02X}{b:02X}{a:02X}"))
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Color::hex(&hex).map_err(|err| D::Error::custom(format!("invalid color {hex:?}: {err}")))
+    }
+}
+
+mod hex_option {
+    use ambient_color::Color;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match color {
+            Some(color) => super::hex::serialize(color, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        #[derive(Deserialize)]
+        struct Hex(#[serde(with = "super::hex")] Color);
+        Ok(Option::<Hex>::deserialize(deserializer)?.map(|Hex(color)| color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_round_trips() {
+        let mut theme = Theme::default();
+        theme.spacing = 8.;
+        theme.colors.primary = Color::rgba(1., 0., 0., 1.);
+        theme.fonts.family = Some("Ubuntu".to_string());
+        theme.buttons.primary.rounding = Some(4.);
+        theme.buttons.flat.text = Some(Color::rgba(0., 0., 1., 1.));
+
+        // Colors are written with 8 bits per channel, so only colors which fit survive unchanged
+        let toml = theme.to_toml();
+        let parsed = Theme::from_toml(&toml).unwrap();
+        assert_eq!(parsed.to_toml(), toml);
+        assert_eq!(parsed.spacing, theme.spacing);
+        assert_eq!(parsed.rounding, theme.rounding);
+        assert_eq!(parsed.fonts, theme.fonts);
+        assert_eq!(parsed.colors.primary, theme.colors.primary);
+        assert_eq!(parsed.buttons, theme.buttons);
+        assert_eq!(Theme::from_toml("").unwrap(), Theme::default());
+    }
+
+    #[test]
+    fn unspecified_fields_keep_defaults() {
+        let theme = Theme::from_toml(
+            r##"
+            spacing = 3.0
+
+            [colors]
+            text = "#FFFFFF"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(theme.spacing, 3.);
+        assert_eq!(theme.colors.text, Color::rgba(1., 1., 1., 1.));
+        assert_eq!(theme.colors.primary, Theme::default().colors.primary);
+        assert_eq!(theme.buttons, ButtonThemes::default());
+        assert!(Theme::from_toml("[colors]\ntext = \"red\"").is_err());
+    }
+}
@@ -0,0 +1,636 @@
+//! Defines a virtualized list and table, which only spawn the rows that are in view.
+use std::collections::HashMap;
+
+use ambient_cb::{cb, Cb};
+use ambient_element::{
+    element_component, to_owned, use_frame, use_ref_with, use_rerender_signal, use_runtime_message,
+    use_state, use_state_with, Element, ElementComponentExt, Hooks,
+};
+use ambient_guest_bridge::{
+    core::{
+        app::components::{cursor_position, window_scale_factor},
+        hierarchy::components::children,
+        layout::{
+            components::{
+                fit_horizontal, height, min_height, min_width, space_between_items, width,
+            },
+            types::Fit,
+        },
+        messages,
+        rect::components::{background_color, border_radius},
+        rendering::components::scissors_recursive,
+        transform::components::{local_to_parent, local_to_world, translation},
+        ui::components::focusable,
+    },
+    window::set_cursor,
+};
+use ambient_shared_types::{CursorIcon, MouseButton, VirtualKeyCode};
+use glam::{vec3, vec4, Vec2, Vec3, Vec4};
+
+use crate::{
+    default_theme::{CHEVRON_DOWN, CHEVRON_UP},
+    layout::{FlowColumn, FlowRow, MeasureAbsolutePosition, MeasureSize},
+    scroll_area::scissors,
+    text::Text,
+    use_focus, use_keyboard_input, Rectangle, UIBase, UIExt,
+};
+
+/// The height assumed for rows that haven't been measured, until any row has been.
+const ESTIMATED_ROW_HEIGHT: f32 = 20.;
+const HEADER_HEIGHT: f32 = 20.;
+const RESIZE_HANDLE_WIDTH: f32 = 4.;
+const MIN_COLUMN_WIDTH: f32 = 20.;
+
+/// The vertical offsets of the rows of a [VirtualList].
+enum RowOffsets {
+    Fixed {
+        height: f32,
+        count: usize,
+    },
+    /// The offset of each row, followed by the total height.
+    Measured(Vec<f32>),
+}
+impl RowOffsets {
+    fn new(count: usize, item_height: Option<f32>, measured: &HashMap<usize, Vec2>) -> Self {
+        if let Some(height) = item_height {
+            return Self::Fixed { height, count };
+        }
+        let estimate = if measured.is_empty() {
+            ESTIMATED_ROW_HEIGHT
+        } else {
+            measured.values().map(|size| size.y).sum::<f32>() / measured.len() as f32
+        };
+        let mut offsets = Vec::with_capacity(count + 1);
+        let mut y = 0.;
+        offsets.push(y);
+        for index in 0..count {
+            y += measured.get(&index).map_or(estimate, |size| size.y);
+            offsets.push(y);
+        }
+        Self::Measured(offsets)
+    }
+    fn count(&self) -> usize {
+        match self {
+            Self::Fixed { count, .. } => *count,
+            Self::Measured(offsets) => offsets.len() - 1,
+        }
+    }
+    /// The top of row `index`; the total height when `index` is the row count.
+    fn offset(&self, index: usize) -> f32 {
+        match self {
+            Self::Fixed { height, .. } => index as f32 * height,
+            Self::Measured(offsets) => offsets[index],
+        }
+    }
+    fn total(&self) -> f32 {
+        self.offset(self.count())
+    }
+    /// The row at `y`, clamped to the rows.
+    fn index_at(&self, y: f32) -> usize {
+        let index = match self {
+            Self::Fixed { height, .. } if *height > 0. => (y / height).max(0.) as usize,
+            Self::Fixed { .. } => 0,
+            Self::Measured(offsets) => offsets.partition_point(|offset| *offset <= y).max(1) - 1,
+        };
+        index.min(self.count().saturating_sub(1))
+    }
+    /// The first row in view and the row after the last one, when scrolled down by `scroll` in a
+    /// view that is `view_height` tall.
+    fn visible(&self, scroll: f32, view_height: f32) -> (usize, usize) {
+        if self.count() == 0 {
+            (0, 0)
+        } else {
+            (
+                self.index_at(scroll),
+                self.index_at(scroll + view_height) + 1,
+            )
+        }
+    }
+    /// The scroll position closest to `scroll` which shows all of row `index`.
+    fn scroll_to(&self, index: usize, scroll: f32, view_height: f32) -> f32 {
+        let (top, bottom) = (self.offset(index), self.offset(index + 1));
+        if top < scroll {
+            top
+        } else if bottom > scroll + view_height {
+            bottom - view_height
+        } else {
+            scroll
+        }
+    }
+}
+
+/// The height and offset of a scroll bar for `content_height` of content scrolled by `scroll` in
+/// a view that is `view_height` tall.
+fn scroll_bar(content_height: f32, view_height: f32, scroll: f32) -> (f32, f32) {
+    let max_scroll = (content_height - view_height).max(0.);
+    let height = if content_height > 0. {
+        view_height / content_height * view_height
+    } else {
+        0.
+    };
+    let offset = if max_scroll > 0. {
+        scroll / max_scroll * (view_height - height)
+    } else {
+        0.
+    };
+    (height, offset)
+}
+
+/// A scrollable list that only spawns the items in view, so that it can show thousands of items.
+///
+/// Items either all have the same [height](Self::item_height), or are measured as they come into
+/// view; items that haven't been measured yet are assumed to have the average height. The list
+/// is as wide as the widest item it has shown, unless it's given a `width`, and it should be given
+/// a `height`.
+///
+/// When [selectable](Self::selectable), items are selected by clicking them, or with the arrow
+/// keys, page up/down, home and end while the list is focused. The selection is scrolled into view.
+#[element_component]
+pub fn VirtualList(
+    hooks: &mut Hooks,
+    /// The number of items.
+    item_count: usize,
+    /// Renders the item at an index.
+    render_item: Cb<dyn Fn(usize) -> Element + Sync + Send>,
+    /// The height of every item. If `None`, the items are measured.
+    item_height: Option<f32>,
+    /// The number of items to spawn above and below the ones in view.
+    overscan: usize,
+    /// The selected item.
+    selected: Option<usize>,
+    /// Callback for when an item is selected. If `None`, items can't be selected.
+    on_select: Option<Cb<dyn Fn(usize) + Sync + Send>>,
+) -> Element {
+    let (focused, set_focused) = use_focus(hooks);
+    let scroll = use_ref_with(hooks, |_| 0.);
+    let measured = use_ref_with(hooks, |_| HashMap::<usize, Vec2>::new());
+    let last_selected = use_ref_with(hooks, |_| None);
+    let rerender = use_rerender_signal(hooks);
+    let (ratio, _set_ratio) = use_state_with(hooks, |world| {
+        #[allow(clippy::clone_on_copy)]
+        let r = world.resource(window_scale_factor()).clone();
+        r as f32
+    });
+    let (size, set_size) = use_state(hooks, Vec2::ZERO);
+    let (canvas_offset, set_canvas_offset) = use_state(hooks, Vec3::ZERO);
+
+    let (rows, content_width) = {
+        let mut measured = measured.lock();
+        measured.retain(|index, _| *index < item_count);
+        (
+            RowOffsets::new(item_count, item_height, &measured),
+            measured.values().map(|size| size.x).fold(0., f32::max),
+        )
+    };
+    let max_scroll = (rows.total() - size.y).max(0.);
+    let scroll_y = {
+        let mut scroll = scroll.lock();
+        let mut last_selected = last_selected.lock();
+        if selected != *last_selected {
+            if let Some(index) = selected.filter(|index| *index < item_count) {
+                *scroll = rows.scroll_to(index, *scroll, size.y);
+            }
+            *last_selected = selected;
+        }
+        *scroll = scroll.clamp(0., max_scroll);
+        *scroll
+    };
+
+    let (first, last) = rows.visible(scroll_y, size.y);
+    let page = last.saturating_sub(first + 1).max(1);
+
+    use_keyboard_input(hooks, {
+        to_owned![on_select];
+        move |_world, keycode, _, pressed| {
+            let (Some(on_select), Some(keycode)) = (&on_select, keycode) else {
+                return;
+            };
+            if !focused || !pressed || item_count == 0 {
+                return;
+            }
+            let last = item_count - 1;
+            let next = match (keycode, selected) {
+                (VirtualKeyCode::Home, _) => 0,
+                (VirtualKeyCode::End, _) => last,
+                (VirtualKeyCode::Up | VirtualKeyCode::PageUp, None) => last,
+                (VirtualKeyCode::Down | VirtualKeyCode::PageDown, None) => 0,
+                (VirtualKeyCode::Up, Some(index)) => index.saturating_sub(1),
+                (VirtualKeyCode::Down, Some(index)) => (index + 1).min(last),
+                (VirtualKeyCode::PageUp, Some(index)) => index.saturating_sub(page),
+                (VirtualKeyCode::PageDown, Some(index)) => (index + page).min(last),
+                _ => return,
+            };
+            if selected != Some(next) {
+                on_select(next);
+            }
+        }
+    });
+
+    let items = (first.saturating_sub(overscan)..(last + overscan).min(item_count))
+        .map(|index| {
+            let item = MeasureSize::el(
+                render_item(index),
+                cb({
+                    to_owned![measured, rerender];
+                    move |size| {
+                        if measured.lock().insert(index, size) != Some(size) {
+                            rerender();
+                        }
+                    }
+                }),
+            );
+            FlowColumn::el([item])
+                .with_background(if selected == Some(index) {
+                    vec4(0.2, 0.4, 0.8, 0.6)
+                } else {
+                    Vec4::ZERO
+                })
+                .with(min_width(), size.x)
+                .with(translation(), vec3(0., rows.offset(index) - scroll_y, 0.))
+                .key(index.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    let (bar_height, bar_offset) = scroll_bar(rows.total(), size.y, scroll_y);
+
+    MeasureSize::el(
+        MeasureAbsolutePosition::el(UIBase::el(), set_canvas_offset),
+        set_size,
+    )
+    .init_default(children())
+    .children(vec![
+        UIBase::el()
+            .init_default(children())
+            .with(width(), size.x)
+            .with(height(), size.y)
+            .with(scissors_recursive(), scissors(canvas_offset, size, ratio))
+            .children(items),
+        if max_scroll > 0. {
+            Rectangle::el()
+                .with(width(), 5.)
+                .with(height(), bar_height)
+                .with(border_radius(), Vec4::ONE * 4.0)
+                .with(background_color(), vec4(0.6, 0.6, 0.6, 1.0))
+                .with(local_to_parent(), Default::default())
+                .with(local_to_world(), Default::default())
+                .with(translation(), vec3(size.x - 5.0, bar_offset, -0.1))
+        } else {
+            Element::new()
+        },
+    ])
+    .with(width(), content_width)
+    .with(focusable(), hooks.instance_id().to_string())
+    .with_clickarea()
+    .on_mouse_down({
+        to_owned![set_focused];
+        move |world, id, button| {
+            if button != MouseButton::Left {
+                return;
+            }
+            set_focused(world, true);
+            let Some(on_select) = &on_select else {
+                return;
+            };
+            let top = world
+                .get(id, local_to_world())
+                .map(|transform| transform.w_axis.y)
+                .unwrap_or_default();
+            let y = world.resource(cursor_position()).y - top + scroll_y;
+            if (0.0..rows.total()).contains(&y) {
+                on_select(rows.index_at(y));
+            }
+        }
+    })
+    .on_mouse_wheel({
+        to_owned![scroll, rerender];
+        move |_world, _id, delta, pixels| {
+            let delta = if pixels { delta.y } else { delta.y * 20. };
+            let mut scroll = scroll.lock();
+            let next = (*scroll - delta).clamp(0., max_scroll);
+            if next != *scroll {
+                *scroll = next;
+                rerender();
+            }
+        }
+    })
+    .el()
+}
+
+impl VirtualList {
+    /// Create a list of `item_count` items, rendered by `render_item`.
+    pub fn new(
+        item_count: usize,
+        render_item: impl Fn(usize) -> Element + Sync + Send + 'static,
+    ) -> Self {
+        Self {
+            item_count,
+            render_item: cb(render_item),
+            item_height: None,
+            overscan: 2,
+            selected: None,
+            on_select: None,
+        }
+    }
+    /// Give every item the same height, so that they don't need to be measured.
+    pub fn item_height(mut self, height: f32) -> Self {
+        self.item_height = Some(height);
+        self
+    }
+    /// Set the number of items to spawn above and below the ones in view.
+    pub fn overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+    /// Allow selecting items, with `selected` being the selected item.
+    pub fn selectable(
+        mut self,
+        selected: Option<usize>,
+        on_select: impl Fn(usize) + Sync + Send + 'static,
+    ) -> Self {
+        self.selected = selected;
+        self.on_select = Some(cb(on_select));
+        self
+    }
+}
+
+/// The order of a sorted [VirtualTable] column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest first.
+    Ascending,
+    /// Largest first.
+    Descending,
+}
+
+/// A column of a [VirtualTable].
+#[derive(Debug, Clone)]
+pub struct TableColumn {
+    /// The title shown in the header.
+    pub title: String,
+    /// The initial width of the column.
+    pub width: f32,
+    /// Whether clicking the header sorts the table by this column.
+    pub sortable: bool,
+}
+impl TableColumn {
+    /// Create a column that isn't sortable.
+    pub fn new(title: impl Into<String>, width: f32) -> Self {
+        Self {
+            title: title.into(),
+            width,
+            sortable: false,
+        }
+    }
+    /// Make the table sortable by this column.
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+}
+
+/// A table with a header, whose rows are a [VirtualList].
+///
+/// Columns are resized by dragging the right edge of their header. Clicking the header of a
+/// sortable column sorts by it, and clicking it again reverses the order; the table doesn't own
+/// the rows, so `on_sort` should reorder them.
+#[element_component]
+pub fn VirtualTable(
+    hooks: &mut Hooks,
+    /// The columns.
+    columns: Vec<TableColumn>,
+    /// The number of rows.
+    row_count: usize,
+    /// Renders the cell at a row and column.
+    render_cell: Cb<dyn Fn(usize, usize) -> Element + Sync + Send>,
+    /// The height of every row.
+    row_height: f32,
+    /// The number of rows that are visible at once.
+    visible_rows: usize,
+    /// Callback for when the sorting changes.
+    on_sort: Option<Cb<dyn Fn(usize, SortOrder) + Sync + Send>>,
+    /// The selected row.
+    selected: Option<usize>,
+    /// Callback for when a row is selected. If `None`, rows can't be selected.
+    on_select: Option<Cb<dyn Fn(usize) + Sync + Send>>,
+) -> Element {
+    let (sort, set_sort) = use_state(hooks, None);
+    let widths = use_ref_with(hooks, |_| Vec::new());
+    // The column being resized, with the cursor position and width it started at
+    let resizing = use_ref_with(hooks, |_| None::<(usize, f32, f32)>);
+    let rerender = use_rerender_signal(hooks);
+    let widths_now = {
+        let mut widths = widths.lock();
+        if widths.len() != columns.len() {
+            *widths = columns.iter().map(|column| column.width).collect();
+        }
+        widths.clone()
+    };
+
+    use_frame(hooks, {
+        to_owned![widths, resizing, rerender];
+        move |world| {
+            let Some((column, start_x, start_width)) = *resizing.lock() else {
+                return;
+            };
+            let x = world.resource(cursor_position()).x;
+            let width = (start_width + x - start_x).max(MIN_COLUMN_WIDTH);
+            let mut widths = widths.lock();
+            if widths.get(column).is_some_and(|current| *current != width) {
+                widths[column] = width;
+                rerender();
+            }
+        }
+    });
+    use_runtime_message::<messages::WindowMouseInput>(hooks, {
+        to_owned![resizing];
+        move |world, event| {
+            if !event.pressed && resizing.lock().take().is_some() {
+                set_cursor(world, CursorIcon::Default);
+            }
+        }
+    });
+
+    let header = FlowRow::el(columns.iter().enumerate().map(|(index, column)| {
+        let order = sort
+            .filter(|(sorted, _)| *sorted == index)
+            .map(|(_, order)| order);
+        let title = FlowRow::el([
+            Text::el(column.title.clone()),
+            match order {
+                Some(SortOrder::Ascending) => Text::el(CHEVRON_UP),
+                Some(SortOrder::Descending) => Text::el(CHEVRON_DOWN),
+                None => Element::new(),
+            },
+        ])
+        .with(space_between_items(), 4.)
+        .with(fit_horizontal(), Fit::None)
+        .with(width(), widths_now[index] - RESIZE_HANDLE_WIDTH)
+        .with(min_height(), HEADER_HEIGHT);
+        let title = match &on_sort {
+            Some(on_sort) if column.sortable => title
+                .with_clickarea()
+                .on_mouse_up({
+                    to_owned![on_sort, set_sort];
+                    move |_world, _id, button| {
+                        if button != MouseButton::Left {
+                            return;
+                        }
+                        let order = match order {
+                            Some(SortOrder::Ascending) => SortOrder::Descending,
+                            _ => SortOrder::Ascending,
+                        };
+                        set_sort(Some((index, order)));
+                        on_sort(index, order);
+                    }
+                })
+                .el(),
+            _ => title,
+        };
+        let handle = Rectangle::el()
+            .with(width(), RESIZE_HANDLE_WIDTH)
+            .with(height(), HEADER_HEIGHT)
+            .with(background_color(), vec4(1., 1., 1., 0.1))
+            .with_clickarea()
+            .on_mouse_enter(|world, _| set_cursor(world, CursorIcon::ColResize))
+            .on_mouse_leave({
+                to_owned![resizing];
+                move |world, _| {
+                    if resizing.lock().is_none() {
+                        set_cursor(world, CursorIcon::Default);
+                    }
+                }
+            })
+            .on_mouse_down({
+                to_owned![resizing, widths];
+                move |world, _, button| {
+                    if button == MouseButton::Left {
+                        let x = world.resource(cursor_position()).x;
+                        *resizing.lock() = Some((index, x, widths.lock()[index]));
+                    }
+                }
+            })
+            .el();
+        FlowRow::el([title, handle])
+    }));
+
+    let list = VirtualList {
+        item_count: row_count,
+        render_item: cb(move |row| {
+            FlowRow::el(widths_now.iter().enumerate().map(|(column, width_)| {
+                FlowRow::el([render_cell(row, column)])
+                    .with(fit_horizontal(), Fit::None)
+                    .with(width(), *width_)
+            }))
+        }),
+        item_height: Some(row_height),
+        overscan: 2,
+        selected,
+        on_select,
+    }
+    .el()
+    .with(height(), visible_rows as f32 * row_height);
+
+    FlowColumn::el([header, list])
+}
+
+impl VirtualTable {
+    /// Create a table with `row_count` rows, whose cells are rendered by `render_cell`.
+    pub fn new(
+        columns: Vec<TableColumn>,
+        row_count: usize,
+        render_cell: impl Fn(usize, usize) -> Element + Sync + Send + 'static,
+    ) -> Self {
+        Self {
+            columns,
+            row_count,
+            render_cell: cb(render_cell),
+            row_height: ESTIMATED_ROW_HEIGHT,
+            visible_rows: 10,
+            on_sort: None,
+            selected: None,
+            on_select: None,
+        }
+    }
+    /// Set the height of every row.
+    pub fn row_height(mut self, height: f32) -> Self {
+        self.row_height = height;
+        self
+    }
+    /// Set the number of rows that are visible at once.
+    pub fn visible_rows(mut self, rows: usize) -> Self {
+        self.visible_rows = rows;
+        self
+    }
+    /// Set the callback for when a sortable column's header is clicked.
+    pub fn on_sort(mut self, on_sort: impl Fn(usize, SortOrder) + Sync + Send + 'static) -> Self {
+        self.on_sort = Some(cb(on_sort));
+        self
+    }
+    /// Allow selecting rows, with `selected` being the selected row.
+    pub fn selectable(
+        mut self,
+        selected: Option<usize>,
+        on_select: impl Fn(usize) + Sync + Send + 'static,
+    ) -> Self {
+        self.selected = selected;
+        self.on_select = Some(cb(on_select));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_rows() {
+        let rows = RowOffsets::new(100, Some(10.), &HashMap::new());
+        assert_eq!(rows.total(), 1000.);
+        assert_eq!(rows.index_at(-5.), 0);
+        assert_eq!(rows.index_at(25.), 2);
+        assert_eq!(rows.index_at(5000.), 99);
+        assert_eq!(rows.visible(0., 35.), (0, 4));
+        assert_eq!(rows.visible(15., 30.), (1, 5));
+        assert_eq!(rows.visible(990., 30.), (99, 100));
+        assert_eq!(
+            RowOffsets::new(0, Some(10.), &HashMap::new()).visible(0., 30.),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn measured_rows() {
+        // Rows that haven't been measured are assumed to have the average height
+        let measured = HashMap::from([(0, Vec2::new(50., 10.)), (2, Vec2::new(80., 30.))]);
+        let rows = RowOffsets::new(4, None, &measured);
+        assert_eq!(
+            (0..=4).map(|index| rows.offset(index)).collect::<Vec<_>>(),
+            vec![0., 10., 30., 60., 80.]
+        );
+        assert_eq!(rows.index_at(10.), 1);
+        assert_eq!(rows.index_at(59.), 2);
+        assert_eq!(rows.visible(25., 10.), (1, 3));
+
+        let rows = RowOffsets::new(2, None, &HashMap::new());
+        assert_eq!(rows.total(), 2. * ESTIMATED_ROW_HEIGHT);
+    }
+
+    #[test]
+    fn scrolling_to_rows() {
+        let rows = RowOffsets::new(100, Some(10.), &HashMap::new());
+        // Rows in view don't scroll
+        assert_eq!(rows.scroll_to(3, 20., 30.), 20.);
+        // Rows above are scrolled to the top, and rows below to the bottom
+        assert_eq!(rows.scroll_to(1, 20., 30.), 10.);
+        assert_eq!(rows.scroll_to(9, 20., 30.), 70.);
+    }
+
+    #[test]
+    fn scroll_bars() {
+        assert_eq!(scroll_bar(400., 100., 0.), (25., 0.));
+        assert_eq!(scroll_bar(400., 100., 300.), (25., 75.));
+        assert_eq!(scroll_bar(400., 100., 150.), (25., 37.5));
+        assert_eq!(scroll_bar(50., 100., 0.), (200., 0.));
+        assert_eq!(scroll_bar(0., 100., 0.), (0., 0.));
+    }
+}